@@ -79,28 +79,32 @@ pub use ockam_macros::{node, test};
 pub use ockam_node::database::*;
 pub use ockam_node::{
     debugger, Context, DelayedEvent, Executor, MessageReceiveOptions, MessageSendReceiveOptions,
-    NodeBuilder, WorkerBuilder,
+    NodeBuilder, ShutdownDrain, WorkerBuilder,
 };
 #[cfg(feature = "ockam_transport_tcp")]
 /// TCP transport
 pub mod tcp {
     pub use ockam_transport_tcp::{
-        TcpConnection, TcpConnectionMode, TcpConnectionOptions, TcpInletOptions, TcpListener,
-        TcpListenerInfo, TcpListenerOptions, TcpOutletOptions, TcpSenderInfo, TcpTransport,
-        TcpTransportExtension, MAX_MESSAGE_SIZE, TCP,
+        InletLoadBalancing, PortalLimits, RateLimit, TcpConnection, TcpConnectionMode,
+        TcpConnectionOptions, TcpInletOptions, TcpListener, TcpListenerInfo, TcpListenerOptions,
+        TcpOutletOptions, TcpSenderInfo, TcpTransport, TcpTransportExtension, MAX_MESSAGE_SIZE,
+        TCP,
     };
 }
 #[cfg(feature = "ockam_transport_udp")]
 /// UDP transport
 pub mod udp {
     pub use ockam_transport_udp::{
-        RendezvousClient, RendezvousService, UdpBindArguments, UdpBindOptions, UdpPuncture,
-        UdpPunctureNegotiation, UdpPunctureNegotiationListener,
-        UdpPunctureNegotiationListenerOptions, UdpTransport, UdpTransportExtension,
-        MAX_MESSAGE_SIZE, UDP,
+        RendezvousClient, RendezvousService, UdpBind, UdpBindArguments, UdpBindOptions, UdpInlet,
+        UdpInletOptions, UdpOutletOptions, UdpPuncture, UdpPunctureNegotiation,
+        UdpPunctureNegotiationListener, UdpPunctureNegotiationListenerOptions, UdpTransport,
+        UdpTransportExtension, DEFAULT_UDP_PORTAL_IDLE_TIMEOUT, MAX_MESSAGE_SIZE, UDP,
     };
 }
-pub use relay_service::{RelayService, RelayServiceOptions};
+pub use relay_service::{
+    RelayBackendStatus, RelayLoadBalancing, RelayRegistry, RelayService, RelayServiceOptions,
+    RelayStatus,
+};
 
 /// Transport
 pub mod transport {
@@ -6,6 +6,8 @@ use ockam_core::flow_control::{FlowControlId, FlowControls};
 use ockam_core::{Address, AllowAll, IncomingAccessControl};
 use ockam_identity::{Identifier, IdentitiesAttributes};
 
+use crate::relay_service::{RelayLoadBalancing, RelayRegistry};
+
 /// Trust Options for a Forwarding Service
 pub struct RelayServiceOptions {
     pub(super) service_incoming_access_control: Arc<dyn IncomingAccessControl>,
@@ -15,6 +17,8 @@ pub struct RelayServiceOptions {
     pub(super) prefix: String,
     pub(super) authority_validation: Option<AuthorityValidation>,
     pub(super) aliases: Vec<Address>,
+    pub(super) load_balancing: RelayLoadBalancing,
+    pub(super) registry: RelayRegistry,
 }
 
 pub(super) struct AuthorityValidation {
@@ -33,6 +37,8 @@ impl RelayServiceOptions {
             prefix: "".to_string(),
             authority_validation: None,
             aliases: vec![],
+            load_balancing: RelayLoadBalancing::default(),
+            registry: RelayRegistry::new(),
         }
     }
 
@@ -111,6 +117,20 @@ impl RelayServiceOptions {
         self
     }
 
+    /// Set the strategy used to choose the node receiving a new connection,
+    /// when several nodes registered a relay with the same name
+    pub fn load_balancing(mut self, load_balancing: RelayLoadBalancing) -> Self {
+        self.load_balancing = load_balancing;
+        self
+    }
+
+    /// Set the registry where the relays created by the Relay Service are stored,
+    /// in order to get their status
+    pub fn registry(mut self, registry: RelayRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
     pub(super) fn setup_flow_control_for_relay_service(
         &self,
         flow_controls: &FlowControls,
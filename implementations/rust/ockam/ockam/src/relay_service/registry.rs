@@ -0,0 +1,313 @@
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{async_trait, Address, OutgoingAccessControl, RelayMessage, Result, Route};
+use ockam_identity::Identifier;
+
+/// Strategy used to choose the node receiving a new connection, when several nodes
+/// registered a relay with the same name.
+///
+/// Each message sent to a relay starts a new exchange with the node, for example a secure
+/// channel handshake or a portal connection, since the replies and the following messages
+/// use the return routes which don't go through the relay. Hence every message routed by
+/// the relay is counted as a new connection, which stays open as long as the worker which
+/// sent the message to the relay, for example the TCP connection of a client, exists.
+///
+/// That worker is the last local hop of the message, usually the secure channel or the TCP
+/// connection of the remote node, and not the portal of the connection, which runs on the
+/// remote node. So all the connections received through a long-lived secure channel stay
+/// open, for the relay, until that secure channel is closed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RelayLoadBalancing {
+    /// Send the new connections to each node in turn
+    #[default]
+    RoundRobin,
+    /// Send the new connections to the node with the fewest open connections
+    LeastConnections,
+}
+
+impl RelayLoadBalancing {
+    /// Name of the strategy
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelayLoadBalancing::RoundRobin => "round-robin",
+            RelayLoadBalancing::LeastConnections => "least-connections",
+        }
+    }
+}
+
+impl Display for RelayLoadBalancing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RelayLoadBalancing {
+    type Err = ockam_core::Error;
+
+    fn from_str(s: &str) -> core::result::Result<Self, Self::Err> {
+        match s {
+            "round-robin" => Ok(RelayLoadBalancing::RoundRobin),
+            "least-connections" => Ok(RelayLoadBalancing::LeastConnections),
+            _ => Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::Invalid,
+                format!(
+                    "invalid load balancing strategy '{s}', expected one of: round-robin, least-connections"
+                ),
+            )),
+        }
+    }
+}
+
+/// Relays created by a relay service, with the nodes registered for each of them
+#[derive(Clone, Debug, Default)]
+pub struct RelayRegistry {
+    relays: Arc<RwLock<BTreeMap<Address, Arc<RelayBackends>>>>,
+}
+
+impl RelayRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the status of all the relays
+    pub fn relays(&self) -> Vec<RelayStatus> {
+        self.relays
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(address, backends)| RelayStatus {
+                address: address.clone(),
+                backends: backends.status(),
+            })
+            .collect()
+    }
+
+    pub(super) fn get(&self, address: &Address) -> Option<Arc<RelayBackends>> {
+        self.relays.read().unwrap().get(address).cloned()
+    }
+
+    pub(super) fn insert(&self, address: Address, backends: Arc<RelayBackends>) {
+        self.relays.write().unwrap().insert(address, backends);
+    }
+}
+
+/// Status of a relay created by a relay service
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelayStatus {
+    address: Address,
+    backends: Vec<RelayBackendStatus>,
+}
+
+impl RelayStatus {
+    /// Address of the relay
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+
+    /// Nodes registered for this relay
+    pub fn backends(&self) -> &[RelayBackendStatus] {
+        &self.backends
+    }
+}
+
+/// Status of a node registered for a relay
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelayBackendStatus {
+    route: Route,
+    connections: u64,
+    open_connections: Option<u64>,
+}
+
+impl RelayBackendStatus {
+    /// Route to the node, empty if the node is the one running the relay service
+    pub fn route(&self) -> &Route {
+        &self.route
+    }
+
+    /// Number of connections routed to the node
+    pub fn connections(&self) -> u64 {
+        self.connections
+    }
+
+    /// Number of connections routed to the node which are still open. They are only tracked
+    /// with [`RelayLoadBalancing::LeastConnections`]
+    pub fn open_connections(&self) -> Option<u64> {
+        self.open_connections
+    }
+}
+
+/// Nodes registered under the address of a relay
+#[derive(Debug)]
+pub(super) struct RelayBackends {
+    load_balancing: RelayLoadBalancing,
+    state: RwLock<BackendsState>,
+}
+
+#[derive(Debug, Default)]
+struct BackendsState {
+    /// Identity of the nodes allowed to register, set by the first node registering
+    /// when no other node is registered. `None` if the nodes are not authenticated
+    owner: Option<Identifier>,
+    backends: Vec<RelayBackend>,
+    /// Position of the backend tried first for the next connection
+    next: usize,
+}
+
+#[derive(Clone, Debug)]
+struct RelayBackend {
+    route: Route,
+    connections: u64,
+    /// Addresses of the workers which sent the connections that are still open
+    clients: Vec<Address>,
+}
+
+impl RelayBackends {
+    pub(super) fn new(load_balancing: RelayLoadBalancing) -> Self {
+        Self {
+            load_balancing,
+            state: Default::default(),
+        }
+    }
+
+    /// Add a node, reachable with `route`. A node registering again with the same route
+    /// is only added once.
+    ///
+    /// Once a node is registered, the other nodes must have the same identity, so that
+    /// a relay can't be taken over by registering another node under its name.
+    /// Return false if the node was not added for that reason
+    pub(super) fn add(&self, route: Route, identifier: Option<Identifier>) -> bool {
+        let mut state = self.state.write().unwrap();
+        if state.backends.is_empty() {
+            state.owner = identifier;
+        } else if state.owner != identifier {
+            return false;
+        }
+        if !state.backends.iter().any(|b| b.route == route) {
+            state.backends.push(RelayBackend {
+                route,
+                connections: 0,
+                clients: vec![],
+            });
+        }
+        true
+    }
+
+    pub(super) fn remove(&self, route: &Route) {
+        self.state
+            .write()
+            .unwrap()
+            .backends
+            .retain(|b| &b.route != route);
+    }
+
+    /// Return the routes of all the nodes
+    pub(super) fn routes(&self) -> Vec<Route> {
+        let state = self.state.read().unwrap();
+        state.backends.iter().map(|b| b.route.clone()).collect()
+    }
+
+    /// Return the routes of the nodes, in the order in which they must be tried for a new
+    /// connection: if a node can't be reached, the connection fails over to the next one
+    pub(super) fn candidates(&self) -> Vec<Route> {
+        let mut state = self.state.write().unwrap();
+        if state.backends.is_empty() {
+            return vec![];
+        }
+        let start = state.next % state.backends.len();
+        state.next = start + 1;
+
+        let mut backends = state.backends.clone();
+        backends.rotate_left(start);
+        if self.load_balancing == RelayLoadBalancing::LeastConnections {
+            // the sort is stable, so the rotation breaks the ties fairly
+            backends.sort_by_key(|b| b.clients.len());
+        }
+        backends.into_iter().map(|b| b.route).collect()
+    }
+
+    /// Count a connection sent to a node by the worker at `client`
+    pub(super) fn record_connection(&self, route: &Route, client: Address) {
+        let mut state = self.state.write().unwrap();
+        if let Some(backend) = state.backends.iter_mut().find(|b| &b.route == route) {
+            backend.connections += 1;
+            if self.load_balancing == RelayLoadBalancing::LeastConnections {
+                backend.clients.push(client);
+            }
+        }
+    }
+
+    /// Return true if the open connections must be tracked to choose a node
+    pub(super) fn tracks_open_connections(&self) -> bool {
+        self.load_balancing == RelayLoadBalancing::LeastConnections
+    }
+
+    /// Return the addresses of the workers which sent the open connections
+    pub(super) fn clients(&self) -> Vec<Address> {
+        let state = self.state.read().unwrap();
+        let mut clients: Vec<Address> = state
+            .backends
+            .iter()
+            .flat_map(|b| b.clients.iter().cloned())
+            .collect();
+        clients.sort();
+        clients.dedup();
+        clients
+    }
+
+    /// Close the connections sent by workers which don't exist anymore
+    pub(super) fn remove_clients(&self, closed: &[Address]) {
+        let mut state = self.state.write().unwrap();
+        for backend in state.backends.iter_mut() {
+            backend.clients.retain(|c| !closed.contains(c));
+        }
+    }
+
+    fn status(&self) -> Vec<RelayBackendStatus> {
+        self.state
+            .read()
+            .unwrap()
+            .backends
+            .iter()
+            .map(|b| RelayBackendStatus {
+                route: b.route.clone(),
+                connections: b.connections,
+                open_connections: self
+                    .tracks_open_connections()
+                    .then_some(b.clients.len() as u64),
+            })
+            .collect()
+    }
+
+    /// Return true if a message can be sent to `address` to reach one of the nodes.
+    /// Any address can be reached by a node which is the one running the relay service
+    fn is_next_hop(&self, address: &Address) -> bool {
+        self.state
+            .read()
+            .unwrap()
+            .backends
+            .iter()
+            .any(|b| b.route.is_empty() || b.route.next().ok() == Some(address))
+    }
+}
+
+/// Allow a relay to send messages to the first hop of the routes of its nodes
+#[derive(Debug)]
+pub(super) struct RelayBackendsAccessControl(pub(super) Arc<RelayBackends>);
+
+#[async_trait]
+impl OutgoingAccessControl for RelayBackendsAccessControl {
+    async fn is_authorized(&self, relay_msg: &RelayMessage) -> Result<bool> {
+        if self.0.is_next_hop(relay_msg.onward_route().next()?) {
+            ockam_core::allow()
+        } else {
+            ockam_core::deny()
+        }
+    }
+}
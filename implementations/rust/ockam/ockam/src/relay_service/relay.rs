@@ -1,43 +1,33 @@
+use crate::relay_service::registry::{RelayBackends, RelayBackendsAccessControl};
 use crate::Context;
+use ockam_core::compat::boxed::Box;
 use ockam_core::compat::sync::Arc;
-use ockam_core::compat::{boxed::Box, vec::Vec};
-use ockam_core::{
-    route, Address, AllowAll, AllowOnwardAddress, Any, IncomingAccessControl, LocalMessage,
-    OutgoingAccessControl, Result, Route, Routed, Worker,
-};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Address, Any, Error, IncomingAccessControl, Result, Routed, Worker};
 use ockam_node::WorkerBuilder;
-use tracing::info;
+use tracing::{info, warn};
 
+/// Worker forwarding the messages sent to a relay to one of the nodes registered for it
 pub(super) struct Relay {
-    forward_route: Route,
-    // this option will be `None` after this worker is initialized, because
-    // while initializing, the worker will send the payload contained in this
-    // field to the `forward_route`, to indicate a successful connection
-    payload: Option<Vec<u8>>,
+    address: Address,
+    backends: Arc<RelayBackends>,
 }
 
 impl Relay {
     pub(super) async fn create(
         ctx: &Context,
         address: Address,
-        forward_route: Route,
-        registration_payload: Vec<u8>,
+        backends: Arc<RelayBackends>,
         incoming_access_control: Arc<dyn IncomingAccessControl>,
     ) -> Result<()> {
-        info!("Created new alias {} for {}", address, forward_route);
+        info!("Created new alias {}", address);
 
-        // Should be able to reach last and second last hops
-        let outgoing_access_control: Arc<dyn OutgoingAccessControl> = if forward_route.len() == 1 {
-            // We are accessed with our node, no transport is involved
-            Arc::new(AllowAll)
-        } else {
-            let next_hop = forward_route.next()?.clone();
-            Arc::new(AllowOnwardAddress(next_hop))
-        };
+        // Should be able to reach the first hop of the route of each node
+        let outgoing_access_control = Arc::new(RelayBackendsAccessControl(backends.clone()));
 
         let relay = Self {
-            forward_route,
-            payload: Some(registration_payload.clone()),
+            address: address.clone(),
+            backends,
         };
 
         WorkerBuilder::new(relay)
@@ -51,61 +41,87 @@ impl Relay {
     }
 }
 
+impl Relay {
+    /// A connection is closed once the worker which sent it to the relay is stopped
+    async fn remove_closed_connections(&self, ctx: &Context) -> Result<()> {
+        let mut closed = vec![];
+        for client in self.backends.clients() {
+            if !ctx.is_worker_registered_at(client.clone()).await? {
+                closed.push(client);
+            }
+        }
+        if !closed.is_empty() {
+            self.backends.remove_clients(&closed);
+        }
+        Ok(())
+    }
+}
+
 #[crate::worker]
 impl Worker for Relay {
     type Context = Context;
     type Message = Any;
 
-    async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
-        let payload = self
-            .payload
-            .take()
-            .expect("payload must be available on init");
-
-        ctx.forward(
-            LocalMessage::new()
-                .with_onward_route(self.forward_route.clone())
-                .with_return_route(route![ctx.address()])
-                .with_payload(payload),
-        )
-        .await?;
-
-        // Remove the last hop so that just route to the node itself is left
-        self.forward_route.modify().pop_back();
-
-        Ok(())
-    }
-
     async fn handle_message(
         &mut self,
         ctx: &mut Self::Context,
         msg: Routed<Self::Message>,
     ) -> Result<()> {
-        let mut local_message = msg.into_local_message();
-
-        local_message = local_message
-            .pop_front_onward_route()?
-            .prepend_front_onward_route(&self.forward_route);
-
-        let next_hop = local_message.next_on_onward_route()?;
-        let prev_hop = local_message.return_route_ref().next()?;
-
-        if let Some(info) = ctx
-            .flow_controls()
-            .find_flow_control_with_producer_address(&next_hop)
-        {
-            ctx.flow_controls()
-                .add_consumer(prev_hop.clone(), info.flow_control_id());
+        let local_message = msg.into_local_message().pop_front_onward_route()?;
+        // The connection is tracked with the last local hop of the message. For a message
+        // coming from another node, this is the secure channel or the TCP connection which
+        // received it, which can carry several connections and outlive them
+        let client = local_message.return_route_ref().next()?.clone();
+        if self.backends.tracks_open_connections() {
+            self.remove_closed_connections(ctx).await?;
         }
 
-        if let Some(info) = ctx
-            .flow_controls()
-            .find_flow_control_with_producer_address(prev_hop)
-        {
-            ctx.flow_controls()
-                .add_consumer(next_hop.clone(), info.flow_control_id());
+        // Try the nodes in turn: a node which can't be reached anymore is removed,
+        // and the message is sent to the next one
+        let mut last_error = None;
+        for forward_route in self.backends.candidates() {
+            let local_message = local_message
+                .clone()
+                .prepend_front_onward_route(&forward_route);
+
+            let next_hop = local_message.next_on_onward_route()?;
+            let prev_hop = local_message.return_route_ref().next()?;
+
+            if let Some(info) = ctx
+                .flow_controls()
+                .find_flow_control_with_producer_address(&next_hop)
+            {
+                ctx.flow_controls()
+                    .add_consumer(prev_hop.clone(), info.flow_control_id());
+            }
+
+            if let Some(info) = ctx
+                .flow_controls()
+                .find_flow_control_with_producer_address(prev_hop)
+            {
+                ctx.flow_controls()
+                    .add_consumer(next_hop.clone(), info.flow_control_id());
+            }
+
+            match ctx.forward(local_message).await {
+                Ok(()) => {
+                    self.backends.record_connection(&forward_route, client);
+                    return Ok(());
+                }
+                Err(err) => {
+                    warn!(relay = %self.address, %forward_route, %err, "The node registered for this relay can't be reached, removing it");
+                    self.backends.remove(&forward_route);
+                    last_error = Some(err);
+                }
+            }
         }
 
-        ctx.forward(local_message).await
+        Err(last_error.unwrap_or_else(|| {
+            Error::new(
+                Origin::Node,
+                Kind::NotFound,
+                format!("No node is registered for the relay {}", self.address),
+            )
+        }))
     }
 }
@@ -1,15 +1,26 @@
 use crate::alloc::string::ToString;
+use crate::relay_service::registry::RelayBackends;
 use crate::relay_service::relay::Relay;
 use crate::{Context, RelayServiceOptions};
 use alloc::string::String;
 use ockam_core::compat::boxed::Box;
 use ockam_core::compat::sync::Arc;
-use ockam_core::{Address, DenyAll, Encodable, Mailbox, Mailboxes, Result, Routed, Worker};
-use ockam_identity::IdentitySecureChannelLocalInfo;
+use ockam_core::compat::vec::Vec;
+use ockam_core::{
+    route, Address, AllowAll, AllowOnwardAddress, DenyAll, Encodable, LocalMessage, Mailbox,
+    Mailboxes, OutgoingAccessControl, Result, Route, Routed, Worker,
+};
+use ockam_identity::{Identifier, IdentitySecureChannelLocalInfo};
 use ockam_node::WorkerBuilder;
 
 /// Alias worker to register remote workers under local names.
 ///
+/// Several nodes can register under the same name. The new connections to the relay
+/// are then balanced between them, as configured with [`RelayServiceOptions::load_balancing`],
+/// and fail over to another node when a node can't be reached anymore, i.e. when the first
+/// worker of its route, for example its TCP connection, was stopped. Once a node is
+/// registered, only nodes with the same identity can register under the same name.
+///
 /// To talk with this worker, you can use the
 /// [`RemoteRelay`](crate::remote::RemoteRelay) which is a compatible client for this server.
 #[non_exhaustive]
@@ -70,6 +81,9 @@ impl Worker for RelayService {
     ) -> Result<()> {
         let secure_channel_local_info =
             IdentitySecureChannelLocalInfo::find_info(message.local_message()).ok();
+        let their_identifier = secure_channel_local_info
+            .as_ref()
+            .map(|info| info.their_identity_id());
 
         let forward_route = message.return_route();
         let requested_relay_address = message.into_body()?;
@@ -134,15 +148,118 @@ impl Worker for RelayService {
         self.options
             .setup_flow_control_for_relay(ctx.flow_controls(), &final_relay_address);
 
-        Relay::create(
+        // add the node to the relay, unless the relay was stopped
+        let existing_backends = match self.options.registry.get(&final_relay_address) {
+            Some(backends) => ctx
+                .is_worker_registered_at(final_relay_address.clone())
+                .await?
+                .then_some(backends),
+            None => None,
+        };
+        let backends = if let Some(backends) = existing_backends {
+            debug!(%final_relay_address, "Adding a node to an existing relay");
+            Self::remove_unreachable_backends(ctx, &backends).await?;
+            backends
+        } else {
+            let backends = Arc::new(RelayBackends::new(self.options.load_balancing));
+            Relay::create(
+                ctx,
+                final_relay_address.clone(),
+                backends.clone(),
+                self.options.relays_incoming_access_control.clone(),
+            )
+            .await?;
+            self.options
+                .registry
+                .insert(final_relay_address.clone(), backends.clone());
+            backends
+        };
+
+        Self::register_backend(
             ctx,
-            final_relay_address,
+            &final_relay_address,
+            &backends,
             forward_route,
+            their_identifier,
             payload.to_vec(),
-            self.options.relays_incoming_access_control.clone(),
         )
-        .await?;
+        .await
+    }
+}
+
+impl RelayService {
+    /// Confirm the registration to the remote worker, and add its node to the relay
+    async fn register_backend(
+        ctx: &Context,
+        relay_address: &Address,
+        backends: &RelayBackends,
+        forward_route: Route,
+        identifier: Option<Identifier>,
+        payload: Vec<u8>,
+    ) -> Result<()> {
+        // Remove the last hop so that just route to the node itself is left. The node is
+        // added before the confirmation so that the relay can be used as soon as it is received
+        let mut node_route = forward_route.clone();
+        node_route.modify().pop_back();
+        if !backends.add(node_route.clone(), identifier) {
+            warn!(%relay_address, %forward_route, "Relay registration not authorized, the relay is registered by nodes with another identity, dropping.");
+            return Ok(());
+        }
 
+        info!(
+            "Registered {} for the alias {}",
+            forward_route, relay_address
+        );
+
+        // Should be able to reach last and second last hops
+        let outgoing_access_control: Arc<dyn OutgoingAccessControl> = if forward_route.len() == 1 {
+            // We are accessed with our node, no transport is involved
+            Arc::new(AllowAll)
+        } else {
+            let next_hop = forward_route.next()?.clone();
+            Arc::new(AllowOnwardAddress(next_hop))
+        };
+        let registration_ctx = match ctx
+            .new_detached_with_mailboxes(Mailboxes::main(
+                Address::random_tagged("RelayService.registration"),
+                Arc::new(DenyAll),
+                outgoing_access_control,
+            ))
+            .await
+        {
+            Ok(registration_ctx) => registration_ctx,
+            Err(e) => {
+                backends.remove(&node_route);
+                return Err(e);
+            }
+        };
+
+        // The relay address is the return route, so that the remote worker knows it
+        registration_ctx
+            .forward(
+                LocalMessage::new()
+                    .with_onward_route(forward_route)
+                    .with_return_route(route![relay_address.clone()])
+                    .with_payload(payload),
+            )
+            .await
+            .map_err(|e| {
+                backends.remove(&node_route);
+                e
+            })
+    }
+
+    /// Remove the nodes whose route starts with a worker which doesn't exist anymore,
+    /// for example when their connection was closed
+    async fn remove_unreachable_backends(ctx: &Context, backends: &RelayBackends) -> Result<()> {
+        for route in backends.routes() {
+            let Ok(next_hop) = route.next() else {
+                continue;
+            };
+            if !ctx.is_worker_registered_at(next_hop.clone()).await? {
+                backends.remove(&route);
+            }
+        }
         Ok(())
     }
 }
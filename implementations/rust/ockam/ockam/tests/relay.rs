@@ -1,7 +1,8 @@
+use ockam::compat::tokio::time::{sleep, timeout};
 use ockam::identity::{secure_channels, SecureChannelListenerOptions, SecureChannelOptions};
 use ockam::remote::{RemoteRelay, RemoteRelayOptions};
 use ockam::workers::Echoer;
-use ockam::{RelayService, RelayServiceOptions};
+use ockam::{RelayRegistry, RelayService, RelayServiceOptions};
 use ockam_core::{route, AllowAll, Result};
 use ockam_node::{Context, MessageReceiveOptions};
 use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions, TcpTransport};
@@ -250,3 +251,151 @@ async fn test4(ctx: &mut Context) -> Result<()> {
 
     Ok(())
 }
+
+// Cloud: Hosts a Relay service and listens on a tcp port. No flow control
+// Server: Connects twice to the Cloud using tcp and registers the same static Relay
+//   on both connections, to simulate two nodes registered with the same name
+// Client: Reaches the Server's Echoer through the Relay, while the server connections are closed
+#[ockam_macros::test]
+async fn test5(ctx: &mut Context) -> Result<()> {
+    let tcp_listener_options = TcpListenerOptions::new();
+    let registry = RelayRegistry::new();
+    let options = RelayServiceOptions::new()
+        .alias("static_forwarding_service")
+        .service_as_consumer(&tcp_listener_options.spawner_flow_control_id())
+        .relay_as_consumer(&tcp_listener_options.spawner_flow_control_id())
+        .registry(registry.clone());
+    RelayService::create(ctx, "forwarding_service", options).await?;
+    let cloud_tcp = TcpTransport::create(ctx).await?;
+    let cloud_listener = cloud_tcp
+        .listen("127.0.0.1:0", tcp_listener_options)
+        .await?;
+
+    ctx.start_worker("echoer", Echoer).await?;
+    let server_tcp = TcpTransport::create(ctx).await?;
+    let mut server_connections = vec![];
+    for _ in 0..2 {
+        let tcp_options = TcpConnectionOptions::new();
+        ctx.flow_controls()
+            .add_consumer("echoer", &tcp_options.flow_control_id());
+        let connection = server_tcp
+            .connect(cloud_listener.socket_string(), tcp_options)
+            .await?;
+        RemoteRelay::create_static(ctx, connection.clone(), "server", RemoteRelayOptions::new())
+            .await?;
+        server_connections.push(connection);
+    }
+
+    let relays = registry.relays();
+    assert_eq!(relays.len(), 1);
+    assert_eq!(relays[0].address().address(), "server");
+    assert_eq!(relays[0].backends().len(), 2);
+
+    let client_tcp = TcpTransport::create(ctx).await?;
+    let cloud_connection = client_tcp
+        .connect(cloud_listener.socket_string(), TcpConnectionOptions::new())
+        .await?;
+
+    // the connections are balanced between both registrations
+    for _ in 0..4 {
+        let resp = ctx
+            .send_and_receive::<String>(
+                route![cloud_connection.clone(), "server", "echoer"],
+                "Hello".to_string(),
+            )
+            .await?;
+        assert_eq!(resp, "Hello");
+    }
+    let relays = registry.relays();
+    for backend in relays[0].backends() {
+        assert_eq!(backend.connections(), 2);
+        // the open connections are only tracked to balance the least connections
+        assert_eq!(backend.open_connections(), None);
+    }
+
+    // the relay fails over to the remaining registration
+    let closed = server_connections.remove(0);
+    server_tcp
+        .disconnect(closed.sender_address().clone())
+        .await?;
+    sleep(Duration::from_millis(250)).await;
+
+    for _ in 0..2 {
+        let resp = ctx
+            .send_and_receive::<String>(
+                route![cloud_connection.clone(), "server", "echoer"],
+                "Hello".to_string(),
+            )
+            .await?;
+        assert_eq!(resp, "Hello");
+    }
+    assert_eq!(registry.relays()[0].backends().len(), 1);
+
+    Ok(())
+}
+
+// Cloud: Hosts a Relay service behind a secure channel listener
+// Server: Registers a static Relay over a secure channel, then registers it again over
+//   another secure channel with the same identity
+// Attacker: Tries to register the same static Relay with another identity
+#[ockam_macros::test]
+async fn test6(ctx: &mut Context) -> Result<()> {
+    let cloud_tcp_listener_options = TcpListenerOptions::new();
+    let cloud_secure_channel_listener_options = SecureChannelListenerOptions::new()
+        .as_consumer(&cloud_tcp_listener_options.spawner_flow_control_id());
+    let registry = RelayRegistry::new();
+    let options = RelayServiceOptions::new()
+        .alias("static_forwarding_service")
+        .service_as_consumer(&cloud_secure_channel_listener_options.spawner_flow_control_id())
+        .relay_as_consumer(&cloud_secure_channel_listener_options.spawner_flow_control_id())
+        .registry(registry.clone());
+    RelayService::create(ctx, "forwarding_service", options).await?;
+
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+    let cloud = identities_creation.create_identity().await?;
+    secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &cloud,
+            "cloud_listener",
+            cloud_secure_channel_listener_options,
+        )
+        .await?;
+    let cloud_tcp = TcpTransport::create(ctx).await?;
+    let cloud_listener = cloud_tcp
+        .listen("127.0.0.1:0", cloud_tcp_listener_options)
+        .await?;
+
+    let server = identities_creation.create_identity().await?;
+    let attacker = identities_creation.create_identity().await?;
+    let tcp = TcpTransport::create(ctx).await?;
+    let mut registrations = vec![];
+    for identifier in [&server, &server, &attacker] {
+        let connection = tcp
+            .connect(cloud_listener.socket_string(), TcpConnectionOptions::new())
+            .await?;
+        let channel = secure_channels
+            .create_secure_channel(
+                ctx,
+                identifier,
+                route![connection, "cloud_listener"],
+                SecureChannelOptions::new(),
+            )
+            .await?;
+        let registration = timeout(
+            Duration::from_secs(1),
+            RemoteRelay::create_static(ctx, channel, "server", RemoteRelayOptions::new()),
+        )
+        .await;
+        registrations.push(matches!(registration, Ok(Ok(_))));
+    }
+
+    // the registration with another identity is not confirmed
+    assert_eq!(registrations, vec![true, true, false]);
+    let relays = registry.relays();
+    assert_eq!(relays.len(), 1);
+    assert_eq!(relays[0].backends().len(), 2);
+
+    Ok(())
+}
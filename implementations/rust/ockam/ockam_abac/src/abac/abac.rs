@@ -9,10 +9,10 @@ use ockam_core::Result;
 use ockam_core::{Error, RelayMessage};
 
 use crate::expr::str;
-use crate::{eval, Env, Expr};
+use crate::{eval, Action, Env, Expr, PolicyAudit, PolicyDecision, Resource, CURRENT_TIME_KEY};
 use ockam_core::compat::format;
 use ockam_core::compat::str::FromStr;
-use ockam_core::compat::string::ToString;
+use ockam_core::compat::string::{String, ToString};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_identity::{
     Identifier, IdentitiesAttributes, IdentitySecureChannelLocalInfo,
@@ -39,6 +39,16 @@ pub struct Abac {
     identities_attributes: Arc<IdentitiesAttributes>,
     authority: Option<Identifier>,
     environment: Env,
+    audit: Option<PolicyAudit>,
+    resource: Option<Resource>,
+    action: Option<Action>,
+}
+
+/// Result of the evaluation of a policy expression
+struct Evaluation {
+    is_authorized: bool,
+    environment: Env,
+    reason: Option<String>,
 }
 
 /// Debug implementation printing out the policy expression only
@@ -59,8 +69,29 @@ impl Abac {
             identities_attributes,
             authority,
             environment,
+            audit: None,
+            resource: None,
+            action: None,
         }
     }
+
+    /// Record the decisions taken by this AccessControl
+    pub fn with_audit(mut self, audit: Option<PolicyAudit>) -> Self {
+        self.audit = audit;
+        self
+    }
+
+    /// Set the resource and action protected by this AccessControl, for the audit of decisions
+    pub fn with_resource_and_action(mut self, resource: Resource, action: Action) -> Self {
+        self.resource = Some(resource);
+        self.action = Some(action);
+        self
+    }
+
+    /// Return the audit recording the decisions of this AccessControl
+    pub fn audit(&self) -> Option<&PolicyAudit> {
+        self.audit.as_ref()
+    }
 }
 
 impl Abac {
@@ -125,14 +156,43 @@ impl Abac {
         identifier: &Identifier,
         expression: &Expr,
     ) -> Result<bool> {
-        Self::is_identity_authorized_static(
+        let evaluation = Self::evaluate(
             self.identities_attributes.clone(),
             &self.environment,
             self.authority.as_ref(),
             identifier,
             expression,
         )
-        .await
+        .await?;
+
+        if let Some(audit) = &self.audit {
+            let mut decision =
+                PolicyDecision::new(evaluation.is_authorized, Some(identifier), Some(expression))
+                    .with_subject_attributes(&evaluation.environment)
+                    .with_resource(self.resource.as_ref())
+                    .with_action(self.action.as_ref());
+            decision.reason = evaluation.reason;
+            audit.record(&decision).await;
+        }
+
+        Ok(evaluation.is_authorized)
+    }
+
+    /// Record a denial which happened before a policy expression could be evaluated,
+    /// for example because the message was not received via a secure channel.
+    pub async fn record_denial(
+        &self,
+        identifier: Option<&Identifier>,
+        expression: Option<&Expr>,
+        reason: &str,
+    ) {
+        if let Some(audit) = &self.audit {
+            let decision = PolicyDecision::new(false, identifier, expression)
+                .with_resource(self.resource.as_ref())
+                .with_action(self.action.as_ref())
+                .with_reason(reason);
+            audit.record(&decision).await;
+        }
     }
 
     /// Returns true if the identity is authorized
@@ -143,6 +203,25 @@ impl Abac {
         identifier: &Identifier,
         expression: &Expr,
     ) -> Result<bool> {
+        Ok(Self::evaluate(
+            identities_attributes,
+            environment,
+            authority,
+            identifier,
+            expression,
+        )
+        .await?
+        .is_authorized)
+    }
+
+    /// Return a copy of the environment populated with the subject identifier, the current time
+    /// and the subject attributes attested by the authority, as used to evaluate policies.
+    pub async fn subject_environment(
+        identities_attributes: Arc<IdentitiesAttributes>,
+        environment: &Env,
+        authority: Option<&Identifier>,
+        identifier: &Identifier,
+    ) -> Result<Env> {
         let mut environment = environment.clone();
 
         // add the identifier itself as a subject parameter
@@ -152,6 +231,11 @@ impl Abac {
             str(identifier.to_string()),
         );
 
+        // make the current time available to the `(now)` function, unless it is already set
+        if !environment.contains(CURRENT_TIME_KEY) {
+            environment.put_current_time(ockam_core::compat::time::now()?);
+        }
+
         // Get identity attributes and populate the environment:
         if let Some(authority) = authority {
             match identities_attributes
@@ -169,7 +253,6 @@ impl Abac {
                             Ok(key) => key,
                             Err(_) => {
                                 warn! {
-                                    policy = %expression,
                                     id     = %identifier,
                                     "attribute key is not utf-8"
                                 }
@@ -178,7 +261,6 @@ impl Abac {
                         };
                         if key.find(|c: char| c.is_whitespace()).is_some() {
                             warn! {
-                                policy = %expression,
                                 id     = %identifier,
                                 key    = %key,
                                 "attribute key with whitespace ignored"
@@ -188,7 +270,6 @@ impl Abac {
                             Ok(s) => {
                                 if environment.contains(key) {
                                     warn! {
-                                        policy = %expression,
                                         id     = %identifier,
                                         key    = %key,
                                         "attribute already present"
@@ -200,7 +281,6 @@ impl Abac {
                             }
                             Err(e) => {
                                 warn! {
-                                    policy = %expression,
                                     id     = %identifier,
                                     key    = %key,
                                     err    = %e,
@@ -219,8 +299,23 @@ impl Abac {
             }
         }
 
-        // Finally, evaluate the expression and return the result:
-        match eval(expression, &environment) {
+        Ok(environment)
+    }
+
+    /// Evaluate the expression in an environment populated with the subject attributes
+    async fn evaluate(
+        identities_attributes: Arc<IdentitiesAttributes>,
+        environment: &Env,
+        authority: Option<&Identifier>,
+        identifier: &Identifier,
+        expression: &Expr,
+    ) -> Result<Evaluation> {
+        let environment =
+            Self::subject_environment(identities_attributes, environment, authority, identifier)
+                .await?;
+
+        // Evaluate the expression and return the result:
+        let (is_authorized, reason) = match eval(expression, &environment) {
             Ok(Expr::Bool(b)) => {
                 debug! {
                     policy        = %expression,
@@ -228,7 +323,7 @@ impl Abac {
                     is_authorized = %b,
                     "policy evaluated"
                 }
-                Ok(b)
+                (b, None)
             }
             Ok(x) => {
                 warn! {
@@ -237,7 +332,10 @@ impl Abac {
                     expr   = %x,
                     "evaluation did not yield a boolean result"
                 }
-                Ok(false)
+                (
+                    false,
+                    Some(format!("evaluation did not yield a boolean result: {x}")),
+                )
             }
             Err(e) => {
                 warn! {
@@ -247,9 +345,14 @@ impl Abac {
                     env    = %environment,
                     "policy evaluation failed"
                 }
-                Ok(false)
+                (false, Some(format!("policy evaluation failed: {e}")))
             }
-        }
+        };
+        Ok(Evaluation {
+            is_authorized,
+            environment,
+            reason,
+        })
     }
 }
 
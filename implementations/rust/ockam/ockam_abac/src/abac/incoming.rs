@@ -10,7 +10,7 @@ use ockam_core::{IncomingAccessControl, RelayMessage};
 use crate::abac::Abac;
 use crate::abac::SUBJECT_KEY;
 use crate::Expr::*;
-use crate::{Env, Expr};
+use crate::{Env, Expr, PolicyAudit};
 use ockam_core::compat::format;
 use ockam_identity::{Identifier, IdentitiesAttributes};
 use tracing::debug;
@@ -68,6 +68,13 @@ impl IncomingAbac {
                     policy = %self.expression,
                     "identity identifier not found; access denied"
                 }
+                self.abac
+                    .record_denial(
+                        None,
+                        Some(&self.expression),
+                        "identity identifier not found",
+                    )
+                    .await;
 
                 return Ok(false);
             }
@@ -78,6 +85,12 @@ impl IncomingAbac {
             .await
     }
 
+    /// Record the decisions taken by this AccessControl
+    pub fn with_audit(mut self, audit: Option<PolicyAudit>) -> Self {
+        self.abac = self.abac.with_audit(audit);
+        self
+    }
+
     pub fn expression(&self) -> &Expr {
         &self.expression
     }
@@ -11,7 +11,7 @@ use ockam_core::{Address, DenyAll, Result};
 use crate::abac::Abac;
 use crate::abac::SUBJECT_KEY;
 use crate::Expr::*;
-use crate::{Env, Expr};
+use crate::{Env, Expr, PolicyAudit};
 use ockam_core::compat::format;
 use ockam_identity::{Identifier, IdentitiesAttributes};
 use ockam_node::Context;
@@ -80,6 +80,12 @@ impl OutgoingAbac {
         Self::create(ctx, identities_attributes, Some(authority), true.into()).await
     }
 
+    /// Record the decisions taken by this AccessControl
+    pub fn with_audit(mut self, audit: Option<PolicyAudit>) -> Self {
+        self.abac = self.abac.with_audit(audit);
+        self
+    }
+
     /// Returns true if the sender of the message is validated by the expression stored in AbacAccessControl
     pub async fn is_authorized_impl(&self, relay_msg: &RelayMessage) -> Result<bool> {
         let identifier = match Abac::get_outgoing_identifier(&self.ctx, relay_msg).await? {
@@ -89,6 +95,13 @@ impl OutgoingAbac {
                     policy = %self.expression,
                     "identity identifier not found; access denied"
                 }
+                self.abac
+                    .record_denial(
+                        None,
+                        Some(&self.expression),
+                        "identity identifier not found",
+                    )
+                    .await;
 
                 return Ok(false);
             }
@@ -0,0 +1,74 @@
+use crate::audit::{PolicyAuditSink, PolicyDecision};
+use ockam_core::async_trait;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tokio::sync::Mutex;
+use tokio::task::spawn_blocking;
+
+/// This sink appends each policy decision to a file, as one JSON object per line.
+/// The file is written on a blocking thread, so that the decisions don't block the runtime
+#[derive(Debug)]
+pub struct JsonlPolicyAuditSink {
+    path: PathBuf,
+    // Serialize the writes of several access controls sharing the same sink
+    lock: Mutex<()>,
+}
+
+impl JsonlPolicyAuditSink {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[async_trait]
+impl PolicyAuditSink for JsonlPolicyAuditSink {
+    async fn record(&self, decision: &PolicyDecision) -> Result<()> {
+        let mut line = serde_json::to_string(decision)
+            .map_err(|e| Error::new(Origin::Application, Kind::Serialization, e))?;
+        line.push('\n');
+
+        let _guard = self.lock.lock().await;
+        let path = self.path.clone();
+        spawn_blocking(move || {
+            let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+            file.write_all(line.as_bytes())
+        })
+        .await
+        .map_err(|e| Error::new(Origin::Application, Kind::Internal, e))?
+        .map_err(|e| Error::new(Origin::Application, Kind::Io, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn decisions_are_appended_as_json_lines() -> Result<()> {
+        let dir = tempfile::tempdir().unwrap();
+        let sink = JsonlPolicyAuditSink::new(dir.path().join("audit.jsonl"));
+
+        let first = PolicyDecision::new(false, None, None).with_reason("no policy found");
+        let second = PolicyDecision::new(true, None, None);
+        sink.record(&first).await?;
+        sink.record(&second).await?;
+
+        let content = std::fs::read_to_string(sink.path()).unwrap();
+        let decisions: Vec<PolicyDecision> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(decisions, vec![first, second]);
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+mod policy_audit;
+mod policy_decision;
+mod policy_decisions_repository;
+
+#[cfg(feature = "std")]
+mod jsonl_sink;
+#[cfg(feature = "std")]
+mod opentelemetry_sink;
+#[cfg(feature = "std")]
+pub(crate) mod policy_decisions_repository_sql;
+
+pub use policy_audit::*;
+pub use policy_decision::*;
+pub use policy_decisions_repository::*;
+
+#[cfg(feature = "std")]
+pub use jsonl_sink::*;
+#[cfg(feature = "std")]
+pub use opentelemetry_sink::*;
+#[cfg(feature = "std")]
+pub use policy_decisions_repository_sql::*;
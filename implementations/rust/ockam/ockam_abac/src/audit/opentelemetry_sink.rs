@@ -0,0 +1,41 @@
+use crate::audit::{PolicyAuditSink, PolicyDecision};
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::Result;
+use tracing::info;
+
+/// Target of the events emitted by the [`OpenTelemetryPolicyAuditSink`]
+pub const POLICY_AUDIT_EVENT_TARGET: &str = "ockam_abac::audit";
+
+/// This sink emits each policy decision as a structured tracing event.
+///
+/// When the node exports its traces and logs with OpenTelemetry, the event is attached
+/// to the span of the message being authorized and exported as a log record.
+#[derive(Debug, Default)]
+pub struct OpenTelemetryPolicyAuditSink;
+
+#[async_trait]
+impl PolicyAuditSink for OpenTelemetryPolicyAuditSink {
+    async fn record(&self, decision: &PolicyDecision) -> Result<()> {
+        let subject_attributes = decision
+            .subject_attributes
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>()
+            .join(",");
+        info! {
+            target: POLICY_AUDIT_EVENT_TARGET,
+            timestamp          = decision.timestamp,
+            allowed            = decision.allowed,
+            subject            = decision.subject.as_deref(),
+            subject_attributes = subject_attributes,
+            resource_name      = decision.resource_name.as_deref(),
+            resource_type      = decision.resource_type.as_deref(),
+            action             = decision.action.as_deref(),
+            policy             = decision.expression.as_deref(),
+            reason             = decision.reason.as_deref(),
+            "policy decision"
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use crate::audit::PolicyDecision;
+use core::fmt::{Debug, Formatter};
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+use tracing::warn;
+
+/// A destination for the policy decisions recorded by a [`PolicyAudit`]
+#[async_trait]
+pub trait PolicyAuditSink: Send + Sync + 'static {
+    /// Record a policy decision
+    async fn record(&self, decision: &PolicyDecision) -> Result<()>;
+}
+
+/// This struct sends the decisions taken by the ABAC access controls to a list of sinks.
+///
+/// By default only denials are recorded since every message going through an access
+/// control produces a decision.
+#[derive(Clone)]
+pub struct PolicyAudit {
+    sinks: Vec<Arc<dyn PolicyAuditSink>>,
+    record_allowed: bool,
+}
+
+impl Debug for PolicyAudit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PolicyAudit")
+            .field("sinks", &self.sinks.len())
+            .field("record_allowed", &self.record_allowed)
+            .finish()
+    }
+}
+
+impl PolicyAudit {
+    /// Create a new audit sending denials to the given sinks
+    pub fn new(sinks: Vec<Arc<dyn PolicyAuditSink>>) -> Self {
+        Self {
+            sinks,
+            record_allowed: false,
+        }
+    }
+
+    /// Also record the decisions allowing access
+    pub fn with_allowed_decisions(mut self, record_allowed: bool) -> Self {
+        self.record_allowed = record_allowed;
+        self
+    }
+
+    /// Add a sink to this audit
+    pub fn add_sink(mut self, sink: Arc<dyn PolicyAuditSink>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Return true if this audit does not have any sink
+    pub fn is_empty(&self) -> bool {
+        self.sinks.is_empty()
+    }
+
+    /// Send a decision to all the sinks.
+    ///
+    /// A failure to record a decision is logged but does not change the decision.
+    pub async fn record(&self, decision: &PolicyDecision) {
+        if decision.allowed && !self.record_allowed {
+            return;
+        }
+        for sink in self.sinks.iter() {
+            if let Err(e) = sink.record(decision).await {
+                warn! {
+                    policy = ?decision.expression,
+                    id     = ?decision.subject,
+                    err    = %e,
+                    "failed to record a policy decision"
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[tokio::test]
+    async fn only_denials_are_recorded_by_default() {
+        let sink = Arc::new(MemorySink::default());
+        let audit = PolicyAudit::new(vec![sink.clone()]);
+        let denied = PolicyDecision::new(false, None, None);
+        let allowed = PolicyDecision::new(true, None, None);
+
+        audit.record(&denied).await;
+        audit.record(&allowed).await;
+        assert_eq!(sink.decisions(), vec![denied.clone()]);
+
+        let audit = audit.with_allowed_decisions(true);
+        audit.record(&allowed).await;
+        assert_eq!(sink.decisions(), vec![denied, allowed]);
+    }
+
+    /// HELPERS
+    #[derive(Default)]
+    struct MemorySink {
+        decisions: Mutex<Vec<PolicyDecision>>,
+    }
+
+    impl MemorySink {
+        fn decisions(&self) -> Vec<PolicyDecision> {
+            self.decisions.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl PolicyAuditSink for MemorySink {
+        async fn record(&self, decision: &PolicyDecision) -> Result<()> {
+            self.decisions.lock().unwrap().push(decision.clone());
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::abac::SUBJECT_KEY;
+use crate::{Action, Env, Expr, Resource};
+use core::fmt::{Display, Formatter};
+use minicbor::{CborLen, Decode, Encode};
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::format;
+use ockam_core::compat::string::{String, ToString};
+use ockam_identity::Identifier;
+
+/// Record of an access control decision taken by evaluating a policy.
+///
+/// This is the entry stored by the [`crate::PolicyAudit`] sinks.
+#[derive(Clone, Debug, Encode, Decode, CborLen, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PolicyDecision {
+    /// UNIX timestamp in seconds
+    #[n(1)] pub timestamp: u64,
+    /// True if access was granted
+    #[n(2)] pub allowed: bool,
+    /// Identifier of the subject, if one could be found for the message
+    #[n(3)] pub subject: Option<String>,
+    /// Attributes of the subject, as they were used to evaluate the policy
+    #[n(4)] pub subject_attributes: BTreeMap<String, String>,
+    #[n(5)] pub resource_name: Option<String>,
+    #[n(6)] pub resource_type: Option<String>,
+    #[n(7)] pub action: Option<String>,
+    /// Policy expression which was evaluated, if a policy was found
+    #[n(8)] pub expression: Option<String>,
+    /// Explanation for a denial which did not come from the evaluation of the expression
+    #[n(9)] pub reason: Option<String>,
+}
+
+impl PolicyDecision {
+    /// Create a decision for a subject and an expression, at the current time
+    pub fn new(allowed: bool, subject: Option<&Identifier>, expression: Option<&Expr>) -> Self {
+        Self {
+            timestamp: ockam_core::compat::time::now().unwrap_or_default(),
+            allowed,
+            subject: subject.map(|s| s.to_string()),
+            subject_attributes: BTreeMap::new(),
+            resource_name: None,
+            resource_type: None,
+            action: None,
+            expression: expression.map(|e| e.to_string()),
+            reason: None,
+        }
+    }
+
+    /// Keep the subject attributes found in the environment used to evaluate the policy
+    pub fn with_subject_attributes(mut self, environment: &Env) -> Self {
+        let prefix = format!("{SUBJECT_KEY}.");
+        self.subject_attributes = environment
+            .entries()
+            .filter_map(|(k, v)| {
+                k.strip_prefix(prefix.as_str()).map(|k| {
+                    let v = match v {
+                        Expr::Str(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    (k.to_string(), v)
+                })
+            })
+            .collect();
+        self
+    }
+
+    pub fn with_resource(mut self, resource: Option<&Resource>) -> Self {
+        self.resource_name = resource.map(|r| r.resource_name.to_string());
+        self.resource_type = resource.map(|r| r.resource_type.to_string());
+        self
+    }
+
+    pub fn with_action(mut self, action: Option<&Action>) -> Self {
+        self.action = action.map(|a| a.to_string());
+        self
+    }
+
+    pub fn with_reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+}
+
+impl Display for PolicyDecision {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let none = "-".to_string();
+        write!(
+            f,
+            "{} {} subject={} resource={} action={} policy={}",
+            self.timestamp,
+            if self.allowed { "allow" } else { "deny" },
+            self.subject.as_ref().unwrap_or(&none),
+            self.resource_name.as_ref().unwrap_or(&none),
+            self.action.as_ref().unwrap_or(&none),
+            self.expression.as_ref().unwrap_or(&none),
+        )?;
+        if let Some(reason) = &self.reason {
+            write!(f, " reason=\"{reason}\"")?;
+        }
+        Ok(())
+    }
+}
+
+/// Criteria used to retrieve policy decisions from a [`crate::PolicyDecisionsRepository`]
+#[derive(Clone, Debug, Default, Encode, Decode, CborLen, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PolicyDecisionsQuery {
+    /// Only return the decisions taken for this resource name
+    #[n(1)] pub resource_name: Option<String>,
+    /// Only return the decisions taken for this subject
+    #[n(2)] pub subject: Option<String>,
+    /// Only return allowed (true) or denied (false) decisions
+    #[n(3)] pub allowed: Option<bool>,
+    /// Only return the decisions taken at or after this UNIX timestamp
+    #[n(4)] pub since: Option<u64>,
+    /// Maximum number of decisions to return, starting from the most recent ones
+    #[n(5)] pub limit: Option<u32>,
+}
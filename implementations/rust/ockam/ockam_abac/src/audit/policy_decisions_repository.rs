@@ -0,0 +1,19 @@
+use crate::audit::{PolicyDecision, PolicyDecisionsQuery};
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+
+/// This repository stores the decisions taken by the policy access controls of a node,
+/// so that they can be audited later on.
+#[async_trait]
+pub trait PolicyDecisionsRepository: Send + Sync + 'static {
+    /// Store a policy decision
+    async fn store_decision(&self, decision: &PolicyDecision) -> Result<()>;
+
+    /// Return the decisions matching a query, the most recent ones first
+    async fn get_decisions(&self, query: &PolicyDecisionsQuery) -> Result<Vec<PolicyDecision>>;
+
+    /// Delete the decisions taken before a given UNIX timestamp
+    async fn delete_decisions_before(&self, timestamp: u64) -> Result<()>;
+}
@@ -0,0 +1,218 @@
+use sqlx::*;
+use tracing::debug;
+
+use ockam_core::async_trait;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Result;
+use ockam_node::database::{Boolean, FromSqlxError, Nullable, SqlxDatabase, ToVoid};
+
+use crate::audit::{
+    PolicyAuditSink, PolicyDecision, PolicyDecisionsQuery, PolicyDecisionsRepository,
+};
+
+#[derive(Clone)]
+pub struct PolicyDecisionSqlxDatabase {
+    database: SqlxDatabase,
+    node_name: String,
+}
+
+impl PolicyDecisionSqlxDatabase {
+    /// Create a new database for policy decisions
+    pub fn new(database: SqlxDatabase, node_name: &str) -> Self {
+        debug!("create a repository for policy decisions");
+        Self {
+            database,
+            node_name: node_name.to_string(),
+        }
+    }
+
+    /// Create a new in-memory database for policy decisions
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(
+            SqlxDatabase::in_memory("policy_decisions").await?,
+            "default",
+        ))
+    }
+}
+
+#[async_trait]
+impl PolicyDecisionsRepository for PolicyDecisionSqlxDatabase {
+    async fn store_decision(&self, decision: &PolicyDecision) -> Result<()> {
+        let subject_attributes = serde_json::to_string(&decision.subject_attributes)
+            .map_err(|e| ockam_core::Error::new(Origin::Application, Kind::Serialization, e))?;
+        let query = query(
+            r#"INSERT INTO policy_decision (node_name, timestamp, allowed, subject, subject_attributes, resource_name, resource_type, action, expression, reason)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"#,
+        )
+        .bind(&self.node_name)
+        .bind(decision.timestamp as i64)
+        .bind(decision.allowed)
+        .bind(decision.subject.as_ref())
+        .bind(subject_attributes)
+        .bind(decision.resource_name.as_ref())
+        .bind(decision.resource_type.as_ref())
+        .bind(decision.action.as_ref())
+        .bind(decision.expression.as_ref())
+        .bind(decision.reason.as_ref());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn get_decisions(&self, q: &PolicyDecisionsQuery) -> Result<Vec<PolicyDecision>> {
+        // The conditions are added to the query in the same order as their bound values
+        let mut conditions = vec!["node_name = $1".to_string()];
+        let mut parameter = 1;
+        let mut condition = |column: &str, operator: &str| {
+            parameter += 1;
+            conditions.push(format!("{column} {operator} ${parameter}"));
+        };
+        if q.resource_name.is_some() {
+            condition("resource_name", "=")
+        }
+        if q.subject.is_some() {
+            condition("subject", "=")
+        }
+        if q.allowed.is_some() {
+            condition("allowed", "=")
+        }
+        if q.since.is_some() {
+            condition("timestamp", ">=")
+        }
+        let mut sql = format!(
+            r#"SELECT timestamp, allowed, subject, subject_attributes, resource_name, resource_type, action, expression, reason
+            FROM policy_decision
+            WHERE {}
+            ORDER BY timestamp DESC"#,
+            conditions.join(" AND ")
+        );
+        if let Some(limit) = q.limit {
+            sql.push_str(&format!(" LIMIT {limit}"));
+        }
+
+        let mut query = query_as(&sql).bind(&self.node_name);
+        if let Some(resource_name) = &q.resource_name {
+            query = query.bind(resource_name)
+        }
+        if let Some(subject) = &q.subject {
+            query = query.bind(subject)
+        }
+        if let Some(allowed) = q.allowed {
+            query = query.bind(allowed)
+        }
+        if let Some(since) = q.since {
+            query = query.bind(since as i64)
+        }
+        let rows: Vec<PolicyDecisionRow> =
+            query.fetch_all(&*self.database.pool).await.into_core()?;
+        rows.into_iter().map(|r| r.decision()).collect()
+    }
+
+    async fn delete_decisions_before(&self, timestamp: u64) -> Result<()> {
+        let query = query(r#"DELETE FROM policy_decision WHERE node_name = $1 AND timestamp < $2"#)
+            .bind(&self.node_name)
+            .bind(timestamp as i64);
+        query.execute(&*self.database.pool).await.void()
+    }
+}
+
+#[async_trait]
+impl PolicyAuditSink for PolicyDecisionSqlxDatabase {
+    async fn record(&self, decision: &PolicyDecision) -> Result<()> {
+        self.store_decision(decision).await
+    }
+}
+
+/// Low-level representation of a row in the policy_decision table
+#[derive(FromRow)]
+struct PolicyDecisionRow {
+    timestamp: i64,
+    allowed: Boolean,
+    subject: Nullable<String>,
+    subject_attributes: String,
+    resource_name: Nullable<String>,
+    resource_type: Nullable<String>,
+    action: Nullable<String>,
+    expression: Nullable<String>,
+    reason: Nullable<String>,
+}
+
+impl PolicyDecisionRow {
+    fn decision(self) -> Result<PolicyDecision> {
+        let subject_attributes: BTreeMap<String, String> =
+            serde_json::from_str(&self.subject_attributes)
+                .map_err(|e| ockam_core::Error::new(Origin::Application, Kind::Serialization, e))?;
+        Ok(PolicyDecision {
+            timestamp: self.timestamp as u64,
+            allowed: self.allowed.to_bool(),
+            subject: self.subject.to_option(),
+            subject_attributes,
+            resource_name: self.resource_name.to_option(),
+            resource_type: self.resource_type.to_option(),
+            action: self.action.to_option(),
+            expression: self.expression.to_option(),
+            reason: self.reason.to_option(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ockam_core::compat::sync::Arc;
+
+    #[tokio::test]
+    async fn test_repository() -> Result<()> {
+        let repo = policy_decisions_repository().await?;
+
+        let mut denied = PolicyDecision::new(false, None, None).with_reason("no policy found");
+        denied.timestamp = 10;
+        denied.resource_name = Some("outlet1".to_string());
+        let mut allowed = PolicyDecision::new(true, None, None);
+        allowed.timestamp = 20;
+        allowed.subject = Some("I0123".to_string());
+        allowed.resource_name = Some("outlet2".to_string());
+        allowed
+            .subject_attributes
+            .insert("component".to_string(), "db".to_string());
+        repo.store_decision(&denied).await?;
+        repo.store_decision(&allowed).await?;
+
+        // all decisions are returned, the most recent first
+        let decisions = repo.get_decisions(&PolicyDecisionsQuery::default()).await?;
+        assert_eq!(decisions, vec![allowed.clone(), denied.clone()]);
+
+        // decisions can be filtered
+        let query = PolicyDecisionsQuery {
+            allowed: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(repo.get_decisions(&query).await?, vec![denied.clone()]);
+
+        let query = PolicyDecisionsQuery {
+            resource_name: Some("outlet2".to_string()),
+            subject: Some("I0123".to_string()),
+            since: Some(15),
+            ..Default::default()
+        };
+        assert_eq!(repo.get_decisions(&query).await?, vec![allowed.clone()]);
+
+        let query = PolicyDecisionsQuery {
+            limit: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(repo.get_decisions(&query).await?, vec![allowed.clone()]);
+
+        // old decisions can be deleted
+        repo.delete_decisions_before(15).await?;
+        let decisions = repo.get_decisions(&PolicyDecisionsQuery::default()).await?;
+        assert_eq!(decisions, vec![allowed]);
+
+        Ok(())
+    }
+
+    /// HELPERS
+    async fn policy_decisions_repository() -> Result<Arc<dyn PolicyDecisionsRepository>> {
+        Ok(Arc::new(PolicyDecisionSqlxDatabase::create().await?))
+    }
+}
@@ -1,15 +1,27 @@
-use ockam_abac::{eval, parse, Env, Expr};
+use ockam_abac::{check, eval, parse, render_check_error, Env, Expr, CURRENT_TIME_KEY};
 use rustyline::error::ReadlineError;
 use rustyline::highlight::MatchingBracketHighlighter;
+use rustyline::history::DefaultHistory;
 use rustyline::validate::MatchingBracketValidator;
 use rustyline::{Config, EditMode, Editor, Result};
 use rustyline_derive::{Completer, Helper, Highlighter, Hinter, Validator};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 const HELP: &str = r#"Available commands:
   :def <id> <expression>  -- Add an expression to the environment.
+  :check <expression>     -- Check an expression without evaluating it.
   :env                    -- Show all current environment entries.
   :clear                  -- Remove all bindings from the environment.
-  :help | :h | :?         -- Show this help message."#;
+  :help | :h | :?         -- Show this help message.
+
+Available functions:
+  and, or, not, if, =, !=, <, >, <=, >=, member?, exists?
+  starts-with?, ends-with?, contains?, matches?  -- (starts-with? subject.team "infra-")
+  +, -, *, /                                     -- (> (+ subject.level 1) 3)
+  now, hour, minute, weekday                     -- (<= 9 (hour (now)) 17)
+
+`(now)` returns the current time in seconds since the Unix epoch,
+unless `current_time` is defined in the environment."#;
 
 #[derive(Completer, Helper, Highlighter, Hinter, Validator)]
 struct ReplHelper {
@@ -27,7 +39,7 @@ fn main() -> Result<()> {
         .build();
 
     let mut env = Env::new();
-    let mut repl = Editor::<ReplHelper>::with_config(c)?;
+    let mut repl = Editor::<ReplHelper, DefaultHistory>::with_config(c)?;
     repl.set_helper(Some(ReplHelper {
         highlighter: MatchingBracketHighlighter::new(),
         validator: MatchingBracketValidator::new(),
@@ -42,7 +54,7 @@ fn main() -> Result<()> {
                 } else {
                     match parse(&line) {
                         Ok(None) => continue,
-                        Ok(Some(e)) => match eval(&e, &env) {
+                        Ok(Some(e)) => match eval(&e, &with_current_time(&env)) {
                             Ok(x) => println!("{x}"),
                             Err(e) => eprintln!("error: {e}"),
                         },
@@ -67,7 +79,7 @@ fn on_command(line: &str, env: &mut Env) {
         (":def", rest) => match parse(rest) {
             Ok(Some(Expr::List(xs))) => {
                 if let [Expr::Ident(name), e] = &xs[..] {
-                    match eval(e, env) {
+                    match eval(e, &with_current_time(env)) {
                         Ok(x) => {
                             env.put(name, x);
                         }
@@ -80,6 +92,18 @@ fn on_command(line: &str, env: &mut Env) {
             Ok(_) => eprintln!("invalid :def command"),
             Err(e) => eprintln!("error: {e}"),
         },
+        (":check", rest) => {
+            let rest = rest.trim();
+            match check(rest) {
+                Ok(errors) if errors.is_empty() => println!("ok"),
+                Ok(errors) => {
+                    for e in errors {
+                        eprintln!("error: {}", render_check_error(rest, &e))
+                    }
+                }
+                Err(e) => eprintln!("error: {e}"),
+            }
+        }
         (":env", _) => {
             for (id, expr) in env.entries() {
                 println!("{id} {expr}")
@@ -90,3 +114,16 @@ fn on_command(line: &str, env: &mut Env) {
         (cmd, _) => eprintln!("unknown command {cmd}"),
     }
 }
+
+/// Return a copy of the environment where the current time is set, if not already defined.
+fn with_current_time(env: &Env) -> Env {
+    let mut env = env.clone();
+    if !env.contains(CURRENT_TIME_KEY) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        env.put_current_time(now);
+    }
+    env
+}
@@ -0,0 +1,556 @@
+//! Static checks for policy expressions.
+//!
+//! Policy expressions are only validated by [`crate::eval`] when they are evaluated, so
+//! a malformed expression can silently deny every request at runtime. The functions in this
+//! module walk an expression before it is used and report arity errors, unknown operators,
+//! type mismatches between literals and identifiers which are outside of the known namespaces.
+
+use core::fmt;
+
+use crate::error::ParseError;
+use crate::expr::Expr;
+use crate::parser::{parse_with_spans, Span, Spans};
+use ockam_core::compat::format;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::vec::Vec;
+
+/// Namespaces of the identifiers which can be bound in a policy environment.
+pub const IDENTIFIER_NAMESPACES: [&str; 3] = ["subject", "resource", "action"];
+
+/// A problem found by the checker, located in the source when it is known.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckError {
+    pub span: Option<Span>,
+    pub kind: CheckErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CheckErrorKind {
+    /// The first element of a list is not a known operator.
+    UnknownOperator(String),
+    /// The first element of a list is not an identifier.
+    NotAnOperator(Expr),
+    /// An operator is called with a wrong number of arguments.
+    Arity {
+        operator: String,
+        expected: Arity,
+        actual: usize,
+    },
+    /// An argument does not have the type expected by its operator.
+    InvalidType {
+        operator: String,
+        expected: Type,
+        actual: Type,
+    },
+    /// The arguments of an operator are expected to have the same type.
+    TypeMismatch(Type, Type),
+    /// An identifier is not prefixed with one of the [`IDENTIFIER_NAMESPACES`].
+    UnknownNamespace(String),
+}
+
+/// The number of arguments accepted by an operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arity {
+    Exactly(usize),
+    AtLeast(usize),
+}
+
+impl Arity {
+    fn accepts(self, n: usize) -> bool {
+        match self {
+            Arity::Exactly(m) => n == m,
+            Arity::AtLeast(m) => n >= m,
+        }
+    }
+}
+
+/// The statically known type of an expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Type {
+    Bool,
+    Int,
+    Float,
+    Str,
+    Seq,
+    /// The type of identifiers, which is only known once they are bound.
+    Unknown,
+}
+
+impl Type {
+    fn of_literal(e: &Expr) -> Type {
+        match e {
+            Expr::Bool(_) => Type::Bool,
+            Expr::Int(_) => Type::Int,
+            Expr::Float(_) => Type::Float,
+            Expr::Str(_) => Type::Str,
+            Expr::Seq(_) => Type::Seq,
+            Expr::Ident(_) | Expr::List(_) => Type::Unknown,
+        }
+    }
+
+    fn is_numeric(self) -> bool {
+        matches!(self, Type::Int | Type::Float)
+    }
+}
+
+/// Parse an expression and check it, returning all the errors found in the expression.
+pub fn check(input: &str) -> Result<Vec<CheckError>, ParseError> {
+    let mut errors = Vec::new();
+    if let Some((expr, spans)) = parse_with_spans(input)? {
+        Checker {
+            errors: &mut errors,
+        }
+        .check(&expr, Some(&spans));
+    }
+    Ok(errors)
+}
+
+/// Check an expression which has already been parsed. The returned errors have no span.
+pub fn check_expr(expr: &Expr) -> Vec<CheckError> {
+    let mut errors = Vec::new();
+    Checker {
+        errors: &mut errors,
+    }
+    .check(expr, None);
+    errors
+}
+
+/// Render an error together with the line of `input` where it is located, for example:
+///
+/// ```text
+/// (= subject.a 1 "x")
+///                ^^^ int and string are not of the same type
+/// ```
+pub fn render_check_error(input: &str, error: &CheckError) -> String {
+    let span = match error.span {
+        Some(span) if span.start <= span.end && span.end <= input.len() => span,
+        _ => return error.kind.to_string(),
+    };
+    let line_start = input[..span.start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = input[span.start..]
+        .find('\n')
+        .map(|i| span.start + i)
+        .unwrap_or(input.len());
+    let line = &input[line_start..line_end];
+    let offset = input[line_start..span.start].chars().count();
+    let width = input[span.start..span.end.min(line_end)]
+        .chars()
+        .count()
+        .max(1);
+    format!(
+        "{line}\n{}{} {}",
+        " ".repeat(offset),
+        "^".repeat(width),
+        error.kind
+    )
+}
+
+struct Checker<'a> {
+    errors: &'a mut Vec<CheckError>,
+}
+
+impl Checker<'_> {
+    fn error(&mut self, spans: Option<&Spans>, kind: CheckErrorKind) {
+        self.errors.push(CheckError {
+            span: spans.map(|s| s.span),
+            kind,
+        })
+    }
+
+    /// Check an expression and return its type.
+    fn check(&mut self, expr: &Expr, spans: Option<&Spans>) -> Type {
+        match expr {
+            Expr::Ident(id) => {
+                self.check_identifier(id, spans);
+                Type::Unknown
+            }
+            Expr::Seq(xs) => {
+                for (i, x) in xs.iter().enumerate() {
+                    self.check(x, child(spans, i));
+                }
+                Type::Seq
+            }
+            Expr::List(xs) => self.check_list(xs, spans),
+            other => Type::of_literal(other),
+        }
+    }
+
+    fn check_identifier(&mut self, id: &str, spans: Option<&Spans>) {
+        let known = IDENTIFIER_NAMESPACES.iter().any(|ns| {
+            id.strip_prefix(ns)
+                .and_then(|rest| rest.strip_prefix('.'))
+                .map(|rest| !rest.is_empty())
+                .unwrap_or(false)
+        });
+        if !known {
+            self.error(spans, CheckErrorKind::UnknownNamespace(id.to_string()))
+        }
+    }
+
+    fn check_list(&mut self, xs: &[Expr], spans: Option<&Spans>) -> Type {
+        let op = match xs.first() {
+            None => return Type::Unknown,
+            Some(Expr::Ident(op)) => op.as_str(),
+            Some(other) => {
+                self.error(
+                    child(spans, 0),
+                    CheckErrorKind::NotAnOperator(other.clone()),
+                );
+                return Type::Unknown;
+            }
+        };
+        let args = &xs[1..];
+        let arg_spans = |i: usize| child(spans, i + 1);
+
+        // `exists?` takes identifiers which are not evaluated.
+        if op == "exists?" {
+            self.check_arity(op, Arity::AtLeast(1), args.len(), spans);
+            for (i, x) in args.iter().enumerate() {
+                match x {
+                    Expr::Ident(id) => self.check_identifier(id, arg_spans(i)),
+                    other => self.error(
+                        arg_spans(i),
+                        CheckErrorKind::InvalidType {
+                            operator: op.to_string(),
+                            expected: Type::Unknown,
+                            actual: Type::of_literal(other),
+                        },
+                    ),
+                }
+            }
+            return Type::Bool;
+        }
+
+        let (arity, result) = match op {
+            "and" | "or" => (Arity::AtLeast(0), Type::Bool),
+            "not" => (Arity::Exactly(1), Type::Bool),
+            "if" => (Arity::Exactly(3), Type::Unknown),
+            "=" | "!=" | "<" | ">" | "<=" | ">=" => (Arity::AtLeast(2), Type::Bool),
+            "member?" | "starts-with?" | "ends-with?" | "contains?" | "matches?" => {
+                (Arity::Exactly(2), Type::Bool)
+            }
+            "+" | "-" | "*" => (Arity::AtLeast(1), Type::Unknown),
+            "/" => (Arity::AtLeast(2), Type::Unknown),
+            "now" => (Arity::Exactly(0), Type::Int),
+            "hour" | "minute" | "weekday" => (Arity::Exactly(1), Type::Int),
+            _ => {
+                self.error(
+                    child(spans, 0),
+                    CheckErrorKind::UnknownOperator(op.to_string()),
+                );
+                for (i, x) in args.iter().enumerate() {
+                    self.check(x, arg_spans(i));
+                }
+                return Type::Unknown;
+            }
+        };
+        self.check_arity(op, arity, args.len(), spans);
+
+        let types: Vec<Type> = args
+            .iter()
+            .enumerate()
+            .map(|(i, x)| self.check(x, arg_spans(i)))
+            .collect();
+
+        match op {
+            "and" | "or" | "not" => {
+                for (i, t) in types.iter().enumerate() {
+                    self.expect(op, Type::Bool, *t, arg_spans(i))
+                }
+                result
+            }
+            "if" => {
+                if let Some(t) = types.first() {
+                    self.expect(op, Type::Bool, *t, arg_spans(0))
+                }
+                match types.get(1..) {
+                    Some([a, b]) if a == b => *a,
+                    _ => Type::Unknown,
+                }
+            }
+            "=" | "!=" | "<" | ">" | "<=" | ">=" => {
+                self.same_types(&types, arg_spans);
+                result
+            }
+            "member?" => {
+                if let Some(t) = types.get(1) {
+                    self.expect(op, Type::Seq, *t, arg_spans(1))
+                }
+                result
+            }
+            "starts-with?" | "ends-with?" | "contains?" | "matches?" => {
+                for (i, t) in types.iter().enumerate() {
+                    self.expect(op, Type::Str, *t, arg_spans(i))
+                }
+                result
+            }
+            "+" | "-" | "*" | "/" => {
+                for (i, t) in types.iter().enumerate() {
+                    if *t != Type::Unknown && !t.is_numeric() {
+                        self.error(
+                            arg_spans(i),
+                            CheckErrorKind::InvalidType {
+                                operator: op.to_string(),
+                                expected: Type::Int,
+                                actual: *t,
+                            },
+                        )
+                    }
+                }
+                // Only numeric types are compared, other types were reported above.
+                let numeric: Vec<Type> = types
+                    .iter()
+                    .map(|t| if t.is_numeric() { *t } else { Type::Unknown })
+                    .collect();
+                self.same_types(&numeric, arg_spans);
+                match numeric.first() {
+                    Some(t) if t.is_numeric() && numeric.iter().all(|u| u == t) => *t,
+                    _ => Type::Unknown,
+                }
+            }
+            "hour" | "minute" | "weekday" => {
+                for (i, t) in types.iter().enumerate() {
+                    self.expect(op, Type::Int, *t, arg_spans(i))
+                }
+                result
+            }
+            _ => result,
+        }
+    }
+
+    fn check_arity(&mut self, op: &str, arity: Arity, actual: usize, spans: Option<&Spans>) {
+        if !arity.accepts(actual) {
+            self.error(
+                spans,
+                CheckErrorKind::Arity {
+                    operator: op.to_string(),
+                    expected: arity,
+                    actual,
+                },
+            )
+        }
+    }
+
+    /// Report an error if a known type differs from the expected one.
+    fn expect(&mut self, op: &str, expected: Type, actual: Type, spans: Option<&Spans>) {
+        if actual != Type::Unknown && actual != expected {
+            self.error(
+                spans,
+                CheckErrorKind::InvalidType {
+                    operator: op.to_string(),
+                    expected,
+                    actual,
+                },
+            )
+        }
+    }
+
+    /// Report the first argument whose known type differs from the previous known types.
+    fn same_types<'s>(&mut self, types: &[Type], arg_spans: impl Fn(usize) -> Option<&'s Spans>) {
+        let mut known: Option<Type> = None;
+        for (i, t) in types.iter().enumerate() {
+            if *t == Type::Unknown {
+                continue;
+            }
+            match known {
+                None => known = Some(*t),
+                Some(k) if k != *t => {
+                    self.error(arg_spans(i), CheckErrorKind::TypeMismatch(k, *t));
+                    return;
+                }
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+fn child(spans: Option<&Spans>, i: usize) -> Option<&Spans> {
+    spans.and_then(|s| s.children.get(i))
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Arity::Exactly(0) => f.write_str("no argument"),
+            Arity::Exactly(1) => f.write_str("one argument"),
+            Arity::Exactly(n) => write!(f, "{n} arguments"),
+            Arity::AtLeast(1) => f.write_str("at least one argument"),
+            Arity::AtLeast(n) => write!(f, "at least {n} arguments"),
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::Bool => f.write_str("bool"),
+            Type::Int => f.write_str("int"),
+            Type::Float => f.write_str("float"),
+            Type::Str => f.write_str("string"),
+            Type::Seq => f.write_str("list"),
+            Type::Unknown => f.write_str("identifier"),
+        }
+    }
+}
+
+impl fmt::Display for CheckErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CheckErrorKind::UnknownOperator(op) => write!(f, "unknown operator: {op}"),
+            CheckErrorKind::NotAnOperator(e) => write!(f, "expected an operator, found: {e}"),
+            CheckErrorKind::Arity {
+                operator,
+                expected,
+                actual,
+            } => write!(f, "'{operator}' requires {expected}, found {actual}"),
+            CheckErrorKind::InvalidType {
+                operator,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "'{operator}' expects a value of type {expected}, found {actual}"
+            ),
+            CheckErrorKind::TypeMismatch(a, b) => write!(f, "{a} and {b} are not of the same type"),
+            CheckErrorKind::UnknownNamespace(id) => write!(
+                f,
+                "identifier '{id}' must start with one of: {}",
+                IDENTIFIER_NAMESPACES
+                    .iter()
+                    .map(|ns| format!("{ns}."))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+impl fmt::Display for CheckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.span {
+            Some(span) => write!(f, "{}..{}: {}", span.start, span.end, self.kind),
+            None => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_expressions() {
+        for s in [
+            r#"(= subject.role "admin")"#,
+            r#"(and (= subject.a "x") (or (member? resource.id ["a" "b"]) (exists? action.id)))"#,
+            r#"(starts-with? subject.team "infra-")"#,
+            r#"(<= 9 (hour (now)) 17)"#,
+            r#"(> (+ subject.level 1) 3)"#,
+            r#"(if (= subject.a 1) (= subject.b 2) false)"#,
+        ] {
+            assert_eq!(check(s).unwrap(), Vec::new(), "{s}")
+        }
+    }
+
+    #[test]
+    fn unknown_operator() {
+        let errors = check(r#"(and (eq subject.a "x"))"#).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].kind,
+            CheckErrorKind::UnknownOperator("eq".to_string())
+        );
+        assert_eq!(errors[0].span, Some(Span::new(6, 8)));
+    }
+
+    #[test]
+    fn arity() {
+        let errors = check(r#"(or (not subject.a subject.b) (= (now 1) 2))"#).unwrap();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0].kind,
+            CheckErrorKind::Arity {
+                operator: "not".to_string(),
+                expected: Arity::Exactly(1),
+                actual: 2
+            }
+        );
+        assert_eq!(errors[0].span, Some(Span::new(4, 29)));
+        assert_eq!(errors[1].span, Some(Span::new(33, 40)));
+    }
+
+    #[test]
+    fn type_mismatch() {
+        let s = r#"(or (= subject.a 1 "x") (starts-with? subject.b 2) (not "yes") (member? 1 2))"#;
+        let errors = check(s).unwrap();
+        let kinds: Vec<_> = errors.iter().map(|e| e.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                CheckErrorKind::TypeMismatch(Type::Int, Type::Str),
+                CheckErrorKind::InvalidType {
+                    operator: "starts-with?".to_string(),
+                    expected: Type::Str,
+                    actual: Type::Int
+                },
+                CheckErrorKind::InvalidType {
+                    operator: "not".to_string(),
+                    expected: Type::Bool,
+                    actual: Type::Str
+                },
+                CheckErrorKind::InvalidType {
+                    operator: "member?".to_string(),
+                    expected: Type::Seq,
+                    actual: Type::Int
+                },
+            ]
+        );
+        assert_eq!(&s[errors[0].span.unwrap().start..][..3], r#""x""#);
+    }
+
+    #[test]
+    fn nested_types() {
+        let errors = check(r#"(= (+ 1 2) "3")"#).unwrap();
+        assert_eq!(
+            errors[0].kind,
+            CheckErrorKind::TypeMismatch(Type::Int, Type::Str)
+        );
+        let errors = check(r#"(+ 1 2.0)"#).unwrap();
+        assert_eq!(
+            errors[0].kind,
+            CheckErrorKind::TypeMismatch(Type::Int, Type::Float)
+        );
+    }
+
+    #[test]
+    fn unknown_namespace() {
+        let errors =
+            check(r#"(and (= subject.a "x") (= role "admin") (exists? resource))"#).unwrap();
+        let kinds: Vec<_> = errors.iter().map(|e| e.kind.clone()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                CheckErrorKind::UnknownNamespace("role".to_string()),
+                CheckErrorKind::UnknownNamespace("resource".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn without_spans() {
+        let e = crate::parse(r#"(= subject.a 1 "x")"#).unwrap().unwrap();
+        let errors = check_expr(&e);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].span, None);
+    }
+
+    #[test]
+    fn render() {
+        let s = r#"(= subject.a 1 "x")"#;
+        let errors = check(s).unwrap();
+        assert_eq!(
+            render_check_error(s, &errors[0]),
+            "(= subject.a 1 \"x\")\n               ^^^ int and string are not of the same type"
+        );
+    }
+}
@@ -6,6 +6,11 @@ use ockam_core::compat::format;
 use ockam_core::compat::string::{String, ToString};
 use ockam_core::compat::vec::vec;
 
+/// Key of the environment entry holding the current time, in seconds since the Unix epoch.
+///
+/// This entry is returned by the `(now)` function.
+pub const CURRENT_TIME_KEY: &str = "current_time";
+
 #[derive(Debug, Clone, Default)]
 pub struct Env(BTreeMap<String, Expr>);
 
@@ -44,6 +49,11 @@ impl Env {
         self
     }
 
+    /// Set the current time, in seconds since the Unix epoch, returned by `(now)`
+    pub fn put_current_time(&mut self, secs: u64) -> &mut Self {
+        self.put(CURRENT_TIME_KEY, Expr::Int(secs as i64))
+    }
+
     pub fn del(&mut self, k: &str) {
         self.0.remove(k);
     }
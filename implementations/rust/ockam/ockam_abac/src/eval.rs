@@ -1,8 +1,9 @@
 use core::cmp::Ordering;
 
-use crate::env::Env;
+use crate::env::{Env, CURRENT_TIME_KEY};
 use crate::error::EvalError;
 use crate::expr::{unit, Expr};
+use ockam_core::compat::format;
 use ockam_core::compat::string::ToString;
 use ockam_core::compat::vec::Vec;
 
@@ -22,7 +23,20 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
         Eq(usize),
         Gt(usize),
         Lt(usize),
+        Ge(usize),
+        Le(usize),
         Member,
+        StartsWith,
+        EndsWith,
+        Contains,
+        Matches,
+        Add(usize),
+        Sub(usize),
+        Mul(usize),
+        Div(usize),
+        Hour,
+        Minute,
+        Weekday,
         Seq(usize),
     }
 
@@ -125,6 +139,20 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
                             ctrl.push(Op::Not);
                             ctrl.push(Op::Eq(nargs))
                         }
+                        ">=" => {
+                            if nargs < 2 {
+                                let msg = "'>=' requires at least two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Ge(nargs))
+                        }
+                        "<=" => {
+                            if nargs < 2 {
+                                let msg = "'<=' requires at least two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Le(nargs))
+                        }
                         "member?" => {
                             if nargs != 2 {
                                 let msg = "'member?' requires two arguments";
@@ -132,6 +160,89 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
                             }
                             ctrl.push(Op::Member)
                         }
+                        "starts-with?" => {
+                            if nargs != 2 {
+                                let msg = "'starts-with?' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::StartsWith)
+                        }
+                        "ends-with?" => {
+                            if nargs != 2 {
+                                let msg = "'ends-with?' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::EndsWith)
+                        }
+                        "contains?" => {
+                            if nargs != 2 {
+                                let msg = "'contains?' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Contains)
+                        }
+                        "matches?" => {
+                            if nargs != 2 {
+                                let msg = "'matches?' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Matches)
+                        }
+                        "+" => {
+                            if nargs < 1 {
+                                let msg = "'+' requires at least one argument";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Add(nargs))
+                        }
+                        "-" => {
+                            if nargs < 1 {
+                                let msg = "'-' requires at least one argument";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Sub(nargs))
+                        }
+                        "*" => {
+                            if nargs < 1 {
+                                let msg = "'*' requires at least one argument";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Mul(nargs))
+                        }
+                        "/" => {
+                            if nargs < 2 {
+                                let msg = "'/' requires at least two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Div(nargs))
+                        }
+                        "now" => {
+                            // The current time is provided by the environment so that
+                            // evaluation stays deterministic for a given environment.
+                            if nargs != 0 {
+                                return Err(EvalError::malformed("'now' takes no argument"))
+                            }
+                            args.push(env.get(CURRENT_TIME_KEY)?.clone());
+                            continue
+                        }
+                        "hour" => {
+                            if nargs != 1 {
+                                return Err(EvalError::malformed("'hour' requires one argument"))
+                            }
+                            ctrl.push(Op::Hour)
+                        }
+                        "minute" => {
+                            if nargs != 1 {
+                                return Err(EvalError::malformed("'minute' requires one argument"))
+                            }
+                            ctrl.push(Op::Minute)
+                        }
+                        "weekday" => {
+                            if nargs != 1 {
+                                return Err(EvalError::malformed("'weekday' requires one argument"))
+                            }
+                            ctrl.push(Op::Weekday)
+                        }
                         "exists?" => {
                             let mut b = true;
                             for x in &xs[1 ..] {
@@ -235,6 +346,12 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
             Op::Gt(n) => eval_predicate(n, &mut args, |x, y| {
                 x.compare(y).map(|o| o == Some(Ordering::Greater))
             })?,
+            Op::Le(n) => eval_predicate(n, &mut args, |x, y| {
+                x.compare(y).map(|o| matches!(o, Some(Ordering::Less | Ordering::Equal)))
+            })?,
+            Op::Ge(n) => eval_predicate(n, &mut args, |x, y| {
+                x.compare(y).map(|o| matches!(o, Some(Ordering::Greater | Ordering::Equal)))
+            })?,
             Op::Member => {
                 let s = pop(&mut args);
                 let y = pop(&mut args);
@@ -255,6 +372,24 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
                     }
                 }
             }
+            Op::StartsWith => {
+                eval_string_predicate(&mut args, "starts-with?", |s, p| Ok(s.starts_with(p)))?
+            }
+            Op::EndsWith => {
+                eval_string_predicate(&mut args, "ends-with?", |s, p| Ok(s.ends_with(p)))?
+            }
+            Op::Contains => {
+                eval_string_predicate(&mut args, "contains?", |s, p| Ok(s.contains(p)))?
+            }
+            Op::Matches => eval_string_predicate(&mut args, "matches?", is_match)?,
+            Op::Add(n) => eval_arithmetic(n, &mut args, Arithmetic::Add)?,
+            Op::Sub(n) => eval_arithmetic(n, &mut args, Arithmetic::Sub)?,
+            Op::Mul(n) => eval_arithmetic(n, &mut args, Arithmetic::Mul)?,
+            Op::Div(n) => eval_arithmetic(n, &mut args, Arithmetic::Div)?,
+            Op::Hour    => eval_time(&mut args, "hour", |t| t.rem_euclid(86400) / 3600)?,
+            Op::Minute  => eval_time(&mut args, "minute", |t| t.rem_euclid(3600) / 60)?,
+            // The Unix epoch was a Thursday. Days are numbered from 0 (Sunday) to 6 (Saturday).
+            Op::Weekday => eval_time(&mut args, "weekday", |t| (t.div_euclid(86400) + 4).rem_euclid(7))?,
             Op::Seq(n) => {
                 let s = args.split_off(args.len() - n);
                 args.push(Expr::Seq(s))
@@ -293,10 +428,194 @@ where
     Ok(())
 }
 
+/// Evaluate a predicate taking a string and a pattern as arguments.
+fn eval_string_predicate<F>(args: &mut Vec<Expr>, name: &str, f: F) -> Result<(), EvalError>
+where
+    F: Fn(&str, &str) -> Result<bool, EvalError>,
+{
+    let p = pop(args);
+    let s = pop(args);
+    match (s, p) {
+        (Expr::Str(s), Expr::Str(p)) => args.push(Expr::Bool(f(&s, &p)?)),
+        (Expr::Str(_), other) | (other, _) => {
+            return Err(EvalError::malformed(format!(
+                "'{name}' expects string arguments, got {other}"
+            )))
+        }
+    }
+    Ok(())
+}
+
+/// Return true if the string matches the regular expression.
+///
+/// The regular expressions are compiled once, and cached for the next evaluations.
+#[cfg(feature = "std")]
+fn is_match(s: &str, pattern: &str) -> Result<bool, EvalError> {
+    use ockam_core::compat::boxed::Box;
+    use once_cell::race::OnceBox;
+    use std::sync::Mutex;
+
+    static CACHE: OnceBox<Mutex<RegexCache>> = OnceBox::new();
+    let regex = CACHE
+        .get_or_init(|| Box::new(Mutex::new(RegexCache::default())))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_compile(pattern)?;
+    Ok(regex.is_match(s))
+}
+
+/// Maximum number of compiled regular expressions kept in the cache
+#[cfg(feature = "std")]
+const MAX_CACHED_REGEXES: usize = 128;
+
+/// Compiled regular expressions, with the time of their last use.
+/// When the cache is full, the least recently used one is evicted.
+#[cfg(feature = "std")]
+#[derive(Default)]
+struct RegexCache {
+    regexes: std::collections::HashMap<String, (regex::Regex, u64)>,
+    clock: u64,
+}
+
+#[cfg(feature = "std")]
+impl RegexCache {
+    fn get_or_compile(&mut self, pattern: &str) -> Result<regex::Regex, EvalError> {
+        self.clock += 1;
+        if let Some((regex, last_used)) = self.regexes.get_mut(pattern) {
+            *last_used = self.clock;
+            return Ok(regex.clone());
+        }
+        let regex = regex::Regex::new(pattern)
+            .map_err(|e| EvalError::malformed(format!("invalid regular expression: {e}")))?;
+        if self.regexes.len() >= MAX_CACHED_REGEXES {
+            let least_recently_used = self
+                .regexes
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(pattern, _)| pattern.clone());
+            if let Some(pattern) = least_recently_used {
+                self.regexes.remove(&pattern);
+            }
+        }
+        self.regexes
+            .insert(pattern.to_string(), (regex.clone(), self.clock));
+        Ok(regex)
+    }
+}
+
+/// Regular expressions are only supported with the `std` feature.
+#[cfg(not(feature = "std"))]
+fn is_match(_s: &str, _pattern: &str) -> Result<bool, EvalError> {
+    Err(EvalError::malformed("'matches?' is not supported"))
+}
+
+/// Arithmetic operations.
+#[derive(Clone, Copy)]
+enum Arithmetic {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Arithmetic {
+    fn name(self) -> &'static str {
+        match self {
+            Arithmetic::Add => "+",
+            Arithmetic::Sub => "-",
+            Arithmetic::Mul => "*",
+            Arithmetic::Div => "/",
+        }
+    }
+
+    fn int(self, x: i64, y: i64) -> Result<i64, EvalError> {
+        let r = match self {
+            Arithmetic::Add => x.checked_add(y),
+            Arithmetic::Sub => x.checked_sub(y),
+            Arithmetic::Mul => x.checked_mul(y),
+            Arithmetic::Div => {
+                if y == 0 {
+                    return Err(EvalError::malformed("division by zero"));
+                }
+                x.checked_div(y)
+            }
+        };
+        r.ok_or_else(|| EvalError::malformed(format!("integer overflow in '{}'", self.name())))
+    }
+
+    fn float(self, x: f64, y: f64) -> f64 {
+        match self {
+            Arithmetic::Add => x + y,
+            Arithmetic::Sub => x - y,
+            Arithmetic::Mul => x * y,
+            Arithmetic::Div => x / y,
+        }
+    }
+}
+
+/// Fold the `n` topmost arguments with an arithmetic operation.
+///
+/// Integers and floats can not be mixed. `(- x)` negates `x`.
+fn eval_arithmetic(n: usize, args: &mut Vec<Expr>, op: Arithmetic) -> Result<(), EvalError> {
+    let start = args.len() - n;
+    let mut xs = args.split_off(start).into_iter();
+    let mut acc = match (op, xs.next()) {
+        (Arithmetic::Sub, Some(x)) if n == 1 => return negate(x).map(|x| args.push(x)),
+        (_, Some(x @ (Expr::Int(_) | Expr::Float(_)))) => x,
+        (_, Some(other)) => {
+            let msg = "arithmetic operations expect numeric arguments";
+            return Err(EvalError::InvalidType(other, msg));
+        }
+        (_, None) => unreachable!("arithmetic operations have at least one argument"),
+    };
+    for y in xs {
+        acc = match (acc, y) {
+            (Expr::Int(a), Expr::Int(b)) => Expr::Int(op.int(a, b)?),
+            (Expr::Float(a), Expr::Float(b)) => Expr::Float(op.float(a, b)),
+            (a, b) => return Err(EvalError::TypeMismatch(a, b)),
+        }
+    }
+    args.push(acc);
+    Ok(())
+}
+
+fn negate(x: Expr) -> Result<Expr, EvalError> {
+    match x {
+        Expr::Int(i) => i
+            .checked_neg()
+            .map(Expr::Int)
+            .ok_or_else(|| EvalError::malformed("integer overflow in '-'")),
+        Expr::Float(f) => Ok(Expr::Float(-f)),
+        other => {
+            let msg = "arithmetic operations expect numeric arguments";
+            Err(EvalError::InvalidType(other, msg))
+        }
+    }
+}
+
+/// Apply a function to a timestamp, expressed in seconds since the Unix epoch (UTC).
+fn eval_time<F>(args: &mut Vec<Expr>, name: &str, f: F) -> Result<(), EvalError>
+where
+    F: Fn(i64) -> i64,
+{
+    match pop(args) {
+        Expr::Int(t) => {
+            args.push(Expr::Int(f(t)));
+            Ok(())
+        }
+        other => Err(EvalError::malformed(format!(
+            "'{name}' expects a timestamp in seconds, got {other}"
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "std")]
+    use super::{RegexCache, MAX_CACHED_REGEXES};
     use crate::{
-        eval, subject_has_credential_attribute, subject_has_credential_policy_expression, Env, Expr,
+        eval, parse, subject_has_credential_attribute, subject_has_credential_policy_expression,
+        Env, Expr,
     };
 
     #[test]
@@ -324,4 +643,102 @@ mod tests {
         let res = eval(&check_credential_expression, &environment).unwrap();
         matches!(res, Expr::Bool(true));
     }
+
+    #[test]
+    fn string_predicates() {
+        let mut env = Env::new();
+        env.put("subject.team", Expr::Str("infra-eu".into()));
+
+        check(
+            "(starts-with? subject.team \"infra-\")",
+            &env,
+            Expr::CONST_TRUE,
+        );
+        check(
+            "(starts-with? subject.team \"dev-\")",
+            &env,
+            Expr::CONST_FALSE,
+        );
+        check("(ends-with? subject.team \"-eu\")", &env, Expr::CONST_TRUE);
+        check("(contains? subject.team \"ra-e\")", &env, Expr::CONST_TRUE);
+        check(
+            "(matches? subject.team \"^infra-(eu|us)$\")",
+            &env,
+            Expr::CONST_TRUE,
+        );
+        check(
+            "(matches? subject.team \"^infra-us$\")",
+            &env,
+            Expr::CONST_FALSE,
+        );
+
+        assert!(eval(&parse("(starts-with? 1 \"a\")").unwrap().unwrap(), &env).is_err());
+        assert!(eval(&parse("(matches? \"a\" \"(\")").unwrap().unwrap(), &env).is_err());
+    }
+
+    #[test]
+    fn arithmetic() {
+        let mut env = Env::new();
+        env.put("subject.clearance", Expr::Int(2));
+
+        check("(+ subject.clearance 1)", &env, Expr::Int(3));
+        check("(- subject.clearance 5)", &env, Expr::Int(-3));
+        check("(- subject.clearance)", &env, Expr::Int(-2));
+        check("(* subject.clearance 2 3)", &env, Expr::Int(12));
+        check("(/ 7 subject.clearance)", &env, Expr::Int(3));
+        check("(+ 1.5 2.0)", &env, Expr::Float(3.5));
+        check("(> (+ subject.clearance 1) 2)", &env, Expr::CONST_TRUE);
+        check("(>= subject.clearance 2 1)", &env, Expr::CONST_TRUE);
+        check("(<= subject.clearance 1)", &env, Expr::CONST_FALSE);
+
+        assert!(eval(&parse("(/ 1 0)").unwrap().unwrap(), &env).is_err());
+        assert!(eval(&parse("(+ 1 1.0)").unwrap().unwrap(), &env).is_err());
+        assert!(eval(&parse("(+ 9223372036854775807 1)").unwrap().unwrap(), &env).is_err());
+    }
+
+    #[test]
+    fn time() {
+        let mut env = Env::new();
+        assert!(eval(&parse("(now)").unwrap().unwrap(), &env).is_err());
+
+        // Tuesday 2024-10-01 10:30:00 UTC
+        env.put_current_time(1727778600);
+        check("(now)", &env, Expr::Int(1727778600));
+        check("(hour (now))", &env, Expr::Int(10));
+        check("(minute (now))", &env, Expr::Int(30));
+        check("(weekday (now))", &env, Expr::Int(2));
+        check("(<= 9 (hour (now)) 17)", &env, Expr::CONST_TRUE);
+        check("(< (now) 1727778000)", &env, Expr::CONST_FALSE);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn regex_cache() {
+        let mut cache = RegexCache::default();
+        assert!(cache.get_or_compile("(").is_err());
+        assert!(cache.get_or_compile("^a$").unwrap().is_match("a"));
+        for i in 0..MAX_CACHED_REGEXES {
+            // keep the first pattern in use
+            cache.get_or_compile("^a$").unwrap();
+            cache.get_or_compile(&format!("^{i}$")).unwrap();
+        }
+        assert_eq!(cache.regexes.len(), MAX_CACHED_REGEXES);
+        assert!(cache.regexes.contains_key("^a$"));
+        assert!(!cache.regexes.contains_key("^0$"));
+        assert!(cache.regexes.contains_key("^1$"));
+    }
+
+    fn run(s: &str, env: &Env) -> Expr {
+        eval(&parse(s).unwrap().unwrap(), env).unwrap()
+    }
+
+    /// Check the result of an expression with `equals`, since `==` only checks that
+    /// both values can be compared
+    fn check(s: &str, env: &Env, expected: Expr) {
+        let actual = run(s, env);
+        assert!(
+            matches!(actual.equals(&expected), Ok(true)),
+            "{s} evaluated to {actual}, expected {expected}"
+        );
+    }
 }
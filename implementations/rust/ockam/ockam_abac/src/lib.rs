@@ -11,8 +11,11 @@ mod env;
 mod error;
 mod eval;
 mod policy;
+mod trace;
 mod types;
 
+#[cfg(feature = "std")]
+mod checker;
 #[cfg(feature = "std")]
 mod parser;
 
@@ -20,13 +23,15 @@ pub mod expr;
 pub mod resource;
 
 mod abac;
+mod audit;
 mod boolean_expr;
 mod policy_expr;
 
 pub use abac::*;
+pub use audit::*;
 
 pub use boolean_expr::*;
-pub use env::Env;
+pub use env::{Env, CURRENT_TIME_KEY};
 pub use error::{EvalError, ParseError};
 pub use eval::eval;
 pub use expr::Expr;
@@ -35,10 +40,16 @@ pub use policy::{
 };
 pub use policy_expr::*;
 pub use resource::{Resource, ResourceType};
+pub use trace::{eval_with_trace, EvaluationStep};
 pub use types::{Action, ResourceName, Subject};
 
 #[cfg(feature = "std")]
-pub use parser::parse;
+pub use checker::{
+    check, check_expr, render_check_error, Arity, CheckError, CheckErrorKind, Type,
+    IDENTIFIER_NAMESPACES,
+};
+#[cfg(feature = "std")]
+pub use parser::{parse, parse_with_spans, Span, Spans};
 
 #[cfg(not(feature = "std"))]
 pub use ockam_executor::tokio;
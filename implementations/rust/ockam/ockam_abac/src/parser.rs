@@ -15,24 +15,83 @@ use wast::lexer::{FloatKind, TokenKind};
 fn ident_pattern() -> &'static Regex {
     static INSTANCE: OnceBox<Regex> = OnceBox::new();
     INSTANCE.get_or_init(|| {
-        Box::new(Regex::new("^[a-zA-Z!$%&*/<=>?~_^][a-zA-Z0-9!$%&*/<=>?~_^.+-@]*$").unwrap())
+        Box::new(Regex::new("^[a-zA-Z!$%&*/<=>?~_^+-][a-zA-Z0-9!$%&*/<=>?~_^.+-@]*$").unwrap())
     })
 }
 
-pub const OPERATORS: [&str; 10] = [
-    "and", "or", "not", "if", "<", ">", "=", "!=", "member?", "exists?",
+pub const OPERATORS: [&str; 24] = [
+    "and",
+    "or",
+    "not",
+    "if",
+    "<",
+    ">",
+    "=",
+    "!=",
+    ">=",
+    "<=",
+    "member?",
+    "exists?",
+    "starts-with?",
+    "ends-with?",
+    "contains?",
+    "matches?",
+    "+",
+    "-",
+    "*",
+    "/",
+    "now",
+    "hour",
+    "minute",
+    "weekday",
 ];
 
-#[rustfmt::skip]
+/// A byte range in the source of a parsed expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+}
+
+/// The spans of an expression and of its sub-expressions.
+///
+/// The `children` of a list or a sequence are in the same order as their elements.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spans {
+    pub span: Span,
+    pub children: Vec<Spans>,
+}
+
+impl Spans {
+    fn leaf(span: Span) -> Self {
+        Spans {
+            span,
+            children: Vec::new(),
+        }
+    }
+}
+
 pub fn parse(s: &str) -> Result<Option<Expr>, ParseError> {
+    Ok(parse_with_spans(s)?.map(|(e, _)| e))
+}
+
+/// Parse an expression and return the location of each sub-expression in the source.
+#[rustfmt::skip]
+pub fn parse_with_spans(s: &str) -> Result<Option<(Expr, Spans)>, ParseError> {
     /// A stack operation.
     enum Op {
         Next,
-        Value(Expr),
-        ListStart,
-        ListEnd,
-        SeqStart,
-        SeqEnd,
+        Value(Expr, Spans),
+        ListStart(usize),
+        ListEnd(usize),
+        SeqStart(usize),
+        SeqEnd(usize),
     }
 
     let lx = Lexer::new(s);
@@ -41,7 +100,7 @@ pub fn parse(s: &str) -> Result<Option<Expr>, ParseError> {
     let mut ctrl: Vec<Op> = Vec::new();
 
     // Result values.
-    let mut vals: Vec<Expr> = Vec::new();
+    let mut vals: Vec<(Expr, Spans)> = Vec::new();
 
     // Start by parsing the next expression.
     ctrl.push(Op::Next);
@@ -52,6 +111,8 @@ pub fn parse(s: &str) -> Result<Option<Expr>, ParseError> {
             Op::Next => match lx.parse(&mut parse_position)? {
                 None => continue,
                 Some(token) => {
+                    let span = Span::new(token.offset, token.offset + token.len as usize);
+                    let leaf = Spans::leaf(span);
                     match token.kind {
                         TokenKind::Whitespace | TokenKind::LineComment | TokenKind::BlockComment =>
                             ctrl.push(Op::Next),
@@ -59,60 +120,60 @@ pub fn parse(s: &str) -> Result<Option<Expr>, ParseError> {
                             let integer = token.integer(s, integer_kind);
                             let (s, r) = integer.val();
                             let x = i64::from_str_radix(s, r)?;
-                            ctrl.push(Op::Value(Expr::Int(x)));
+                            ctrl.push(Op::Value(Expr::Int(x), leaf));
                             ctrl.push(Op::Next)
                         }
                         TokenKind::Float(float_kind) => {
                             match float_kind {
                                 FloatKind::Inf { negative: true } =>
-                                    ctrl.push(Op::Value(Expr::Float(f64::NEG_INFINITY))),
+                                    ctrl.push(Op::Value(Expr::Float(f64::NEG_INFINITY), leaf)),
                                 FloatKind::Inf { negative: false } =>
-                                    ctrl.push(Op::Value(Expr::Float(f64::INFINITY))),
+                                    ctrl.push(Op::Value(Expr::Float(f64::INFINITY), leaf)),
                                 FloatKind::Nan { .. } =>
-                                    ctrl.push(Op::Value(Expr::Float(f64::NAN))),
+                                    ctrl.push(Op::Value(Expr::Float(f64::NAN), leaf)),
                                 FloatKind::NanVal { .. } =>
-                                    ctrl.push(Op::Value(Expr::Float(f64::NAN))),
+                                    ctrl.push(Op::Value(Expr::Float(f64::NAN), leaf)),
                                 FloatKind::Normal { .. } => {
                                     let float: f64 = FromStr::from_str(token.src(s))?;
-                                    ctrl.push(Op::Value(Expr::Float(float)))
+                                    ctrl.push(Op::Value(Expr::Float(float), leaf))
                                 }
                             }
                             ctrl.push(Op::Next)
                         }
                         TokenKind::String => {
-                            ctrl.push(Op::Value(Expr::Str(from_utf8(&token.string(s))?.to_string())));
+                            ctrl.push(Op::Value(Expr::Str(from_utf8(&token.string(s))?.to_string()), leaf));
                             ctrl.push(Op::Next)
                         }
                         TokenKind::LParen => {
-                            ctrl.push(Op::ListStart);
+                            ctrl.push(Op::ListStart(span.start));
                             ctrl.push(Op::Next)
                         }
                         TokenKind::RParen => {
-                            ctrl.push(Op::ListEnd)
+                            ctrl.push(Op::ListEnd(span.end))
                         }
                         TokenKind::Reserved if token.reserved(s) == "]" => {
-                            ctrl.push(Op::SeqEnd)
+                            ctrl.push(Op::SeqEnd(span.end))
                         }
                         TokenKind::Reserved if token.reserved(s) == "[" => {
-                            ctrl.push(Op::SeqStart);
+                            ctrl.push(Op::SeqStart(span.start));
                             ctrl.push(Op::Next)
                         }
                         TokenKind::Keyword if token.keyword(s) == "true" => {
-                            ctrl.push(Op::Value(Expr::Bool(true)));
+                            ctrl.push(Op::Value(Expr::Bool(true), leaf));
                             ctrl.push(Op::Next)
                         }
                         TokenKind::Keyword if token.keyword(s) == "false" => {
-                            ctrl.push(Op::Value(Expr::Bool(false)));
+                            ctrl.push(Op::Value(Expr::Bool(false), leaf));
                             ctrl.push(Op::Next)
                         }
                         TokenKind::Id => {
-                            ctrl.push(Op::Value(Expr::Ident(token.id(s)?.to_string())));
+                            ctrl.push(Op::Value(Expr::Ident(token.id(s)?.to_string()), leaf));
                             ctrl.push(Op::Next)
                         }
                         TokenKind::Keyword => {
                             let keyword = token.keyword(s);
                             if ident_pattern().is_match(keyword) {
-                                ctrl.push(Op::Value(Expr::Ident(keyword.to_string())));
+                                ctrl.push(Op::Value(Expr::Ident(keyword.to_string()), leaf));
                                 ctrl.push(Op::Next)
                             } else {
                                 return Err(ParseError::message(format!("invalid keyword token '{keyword}'")))
@@ -121,7 +182,7 @@ pub fn parse(s: &str) -> Result<Option<Expr>, ParseError> {
                         TokenKind::Reserved  => {
                             let reserved = token.reserved(s);
                             if ident_pattern().is_match(reserved) {
-                                ctrl.push(Op::Value(Expr::Ident(reserved.to_string())));
+                                ctrl.push(Op::Value(Expr::Ident(reserved.to_string()), leaf));
                                 ctrl.push(Op::Next)
                             } else {
                                 return Err(ParseError::message(format!("invalid reserved token '{reserved}'")))
@@ -130,7 +191,7 @@ pub fn parse(s: &str) -> Result<Option<Expr>, ParseError> {
                         TokenKind::Annotation => {
                             let annotation = token.annotation(s)?;
                             if ident_pattern().is_match(annotation.as_ref()) {
-                                ctrl.push(Op::Value(Expr::Ident(annotation.to_string())));
+                                ctrl.push(Op::Value(Expr::Ident(annotation.to_string()), leaf));
                                 ctrl.push(Op::Next)
                             } else {
                                 return Err(ParseError::message(format!("invalid annotation token '{annotation}'")))
@@ -139,46 +200,60 @@ pub fn parse(s: &str) -> Result<Option<Expr>, ParseError> {
                     }
                 }
             }
-            Op::Value(x) => vals.push(x),
-            Op::ListEnd => {
+            Op::Value(x, spans) => vals.push((x, spans)),
+            Op::ListEnd(end) => {
                 let mut v = Vec::new();
-                while let Some(x) = ctrl.pop() {
-                    match x {
-                        Op::ListStart => break,
-                        Op::Value(x)  => v.push(x),
-                        Op::ListEnd   => return Err(ParseError::message("')' without matching '('")),
-                        Op::SeqStart  => return Err(ParseError::message("'[' without matching ']'")),
-                        Op::SeqEnd    => return Err(ParseError::message("']' without matching '['")),
-                        Op::Next      => unreachable!("consecutive next operations are impossible")
+                let mut children = Vec::new();
+                let start = loop {
+                    match ctrl.pop() {
+                        Some(Op::ListStart(start)) => break start,
+                        Some(Op::Value(x, spans)) => {
+                            v.push(x);
+                            children.push(spans)
+                        }
+                        Some(Op::ListEnd(_))  => return Err(ParseError::message("')' without matching '('")),
+                        Some(Op::SeqStart(_)) => return Err(ParseError::message("'[' without matching ']'")),
+                        Some(Op::SeqEnd(_))   => return Err(ParseError::message("']' without matching '['")),
+                        Some(Op::Next)        => unreachable!("consecutive next operations are impossible"),
+                        None                  => return Err(ParseError::message("')' without matching '('")),
                     }
-                }
+                };
                 v.reverse();
-                ctrl.push(Op::Value(Expr::List(v)));
+                children.reverse();
+                let spans = Spans { span: Span::new(start, end), children };
+                ctrl.push(Op::Value(Expr::List(v), spans));
                 ctrl.push(Op::Next)
             }
-            Op::SeqEnd => {
+            Op::SeqEnd(end) => {
                 let mut v = Vec::new();
-                while let Some(x) = ctrl.pop() {
-                    match x {
-                        Op::SeqStart  => break,
-                        Op::Value(x)  => v.push(x),
-                        Op::ListEnd   => return Err(ParseError::message("')' without matching '('")),
-                        Op::ListStart => return Err(ParseError::message("'(' without matching ')'")),
-                        Op::SeqEnd    => return Err(ParseError::message("']' without matching '['")),
-                        Op::Next      => unreachable!("consecutive next operations are impossible")
+                let mut children = Vec::new();
+                let start = loop {
+                    match ctrl.pop() {
+                        Some(Op::SeqStart(start)) => break start,
+                        Some(Op::Value(x, spans)) => {
+                            v.push(x);
+                            children.push(spans)
+                        }
+                        Some(Op::ListEnd(_))   => return Err(ParseError::message("')' without matching '('")),
+                        Some(Op::ListStart(_)) => return Err(ParseError::message("'(' without matching ')'")),
+                        Some(Op::SeqEnd(_))    => return Err(ParseError::message("']' without matching '['")),
+                        Some(Op::Next)         => unreachable!("consecutive next operations are impossible"),
+                        None                   => return Err(ParseError::message("']' without matching '['")),
                     }
-                }
+                };
                 v.reverse();
+                children.reverse();
                 for (x, y) in v.iter().zip(v.iter().skip(1)) {
                     if let Err(EvalError::TypeMismatch(x, y)) = x.equals(y) {
                         return Err(ParseError::TypeMismatch(x, y))
                     }
                 }
-                ctrl.push(Op::Value(Expr::Seq(v)));
+                let spans = Spans { span: Span::new(start, end), children };
+                ctrl.push(Op::Value(Expr::Seq(v), spans));
                 ctrl.push(Op::Next)
             }
-            Op::ListStart => return Err(ParseError::message("unclosed '('")),
-            Op::SeqStart  => return Err(ParseError::message("unclosed '['"))
+            Op::ListStart(_) => return Err(ParseError::message("unclosed '('")),
+            Op::SeqStart(_)  => return Err(ParseError::message("unclosed '['"))
         }
     }
 
@@ -187,12 +262,14 @@ pub fn parse(s: &str) -> Result<Option<Expr>, ParseError> {
         1 => Some(vals.remove(0)),
         _ => {
             vals.reverse();
-            Some(Expr::List(vals))
+            let (v, children): (Vec<_>, Vec<_>) = vals.into_iter().unzip();
+            let spans = Spans { span: Span::new(0, s.len()), children };
+            Some((Expr::List(v), spans))
         }
     };
     match expression {
-        Some(e) => if is_operation(&e) {
-            Ok(Some(e))
+        Some((e, spans)) => if is_operation(&e) {
+            Ok(Some((e, spans)))
         } else {
             Err(ParseError::message(format!("The first identifier of the expression: `{s}` must be an operation. The available operations are: {}", OPERATORS.join(", "))))
         },
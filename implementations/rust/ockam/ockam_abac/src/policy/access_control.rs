@@ -1,6 +1,6 @@
 use crate::abac::Abac;
 use crate::policy::{IncomingPolicyAccessControl, OutgoingPolicyAccessControl};
-use crate::{Action, Env, Policies, Resource};
+use crate::{Action, Env, Policies, PolicyAudit, Resource};
 use core::fmt;
 use core::fmt::{Debug, Formatter};
 use ockam_core::compat::sync::Arc;
@@ -47,7 +47,8 @@ impl PolicyAccessControl {
         resource: Resource,
         action: Action,
     ) -> Self {
-        let abac = Abac::new(identities_attributes, authority, env);
+        let abac = Abac::new(identities_attributes, authority, env)
+            .with_resource_and_action(resource.clone(), action.clone());
         Self {
             abac,
             policies,
@@ -56,6 +57,12 @@ impl PolicyAccessControl {
         }
     }
 
+    /// Record the decisions taken by this AccessControl
+    pub fn with_audit(mut self, audit: Option<PolicyAudit>) -> Self {
+        self.abac = self.abac.with_audit(audit);
+        self
+    }
+
     pub fn create_incoming(&self) -> IncomingPolicyAccessControl {
         IncomingPolicyAccessControl {
             policy_access_control: self.clone(),
@@ -92,6 +99,9 @@ impl PolicyAccessControl {
                 action   = %self.action,
                 "no policy found; access denied"
             }
+            self.abac
+                .record_denial(Some(identifier), None, "no policy found")
+                .await;
             return Ok(false);
         };
 
@@ -33,6 +33,10 @@ impl IncomingAccessControl for IncomingPolicyAccessControl {
                 action   = %self.policy_access_control.action,
                 "no policy found; access denied"
             }
+            self.policy_access_control
+                .abac
+                .record_denial(None, None, "no policy found")
+                .await;
             return Ok(false);
         };
 
@@ -43,6 +47,10 @@ impl IncomingAccessControl for IncomingPolicyAccessControl {
                     policy = %expression,
                     "identity identifier not found; access denied"
                 }
+                self.policy_access_control
+                    .abac
+                    .record_denial(None, Some(&expression), "identity identifier not found")
+                    .await;
 
                 return Ok(false);
             }
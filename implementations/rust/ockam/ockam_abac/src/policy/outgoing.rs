@@ -42,6 +42,10 @@ impl OutgoingAccessControl for OutgoingPolicyAccessControl {
                 action   = %self.policy_access_control.action,
                 "no policy found; access denied"
             }
+            self.policy_access_control
+                .abac
+                .record_denial(None, None, "no policy found")
+                .await;
             return Ok(false);
         };
 
@@ -52,6 +56,10 @@ impl OutgoingAccessControl for OutgoingPolicyAccessControl {
                     policy = %expression,
                     "identity identifier not found; access denied"
                 }
+                self.policy_access_control
+                    .abac
+                    .record_denial(None, Some(&expression), "identity identifier not found")
+                    .await;
 
                 return Ok(false);
             }
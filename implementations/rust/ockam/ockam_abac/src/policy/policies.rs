@@ -1,7 +1,7 @@
 use crate::policy::ResourceTypePolicy;
 use crate::{
-    subject_has_credential_policy_expression, Action, Env, Expr, PolicyAccessControl, Resource,
-    ResourceName, ResourcePoliciesRepository, ResourcePolicy, ResourceType,
+    subject_has_credential_policy_expression, Action, Env, Expr, PolicyAccessControl, PolicyAudit,
+    Resource, ResourceName, ResourcePoliciesRepository, ResourcePolicy, ResourceType,
     ResourceTypePoliciesRepository,
 };
 use ockam_core::compat::sync::Arc;
@@ -15,6 +15,7 @@ use tracing::{debug, instrument};
 pub struct Policies {
     resources_policies_repository: Arc<dyn ResourcePoliciesRepository>,
     resource_types_policies_repository: Arc<dyn ResourceTypePoliciesRepository>,
+    audit: Option<PolicyAudit>,
 }
 
 impl Policies {
@@ -25,9 +26,21 @@ impl Policies {
         Self {
             resources_policies_repository,
             resource_types_policies_repository,
+            audit: None,
         }
     }
 
+    /// Record the decisions of the access controls created by these policies
+    pub fn with_audit(mut self, audit: PolicyAudit) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// Return the audit recording the decisions of the policy access controls
+    pub fn audit(&self) -> Option<&PolicyAudit> {
+        self.audit.as_ref()
+    }
+
     #[instrument(skip_all, fields(resource = %resource, action = %action, env = %env, authority = ?authority))]
     pub fn make_policy_access_control(
         &self,
@@ -49,6 +62,7 @@ impl Policies {
             resource,
             action.clone(),
         )
+        .with_audit(self.audit.clone())
     }
 
     pub async fn get_policies(&self) -> Result<(Vec<ResourcePolicy>, Vec<ResourceTypePolicy>)> {
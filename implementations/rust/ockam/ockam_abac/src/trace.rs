@@ -0,0 +1,105 @@
+use crate::env::Env;
+use crate::error::EvalError;
+use crate::eval::eval;
+use crate::expr::Expr;
+use minicbor::{CborLen, Decode, Encode};
+use ockam_core::compat::format;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::vec::Vec;
+
+/// The value of a sub-expression, computed while evaluating a policy expression.
+#[derive(Clone, Debug, Encode, Decode, CborLen, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(serde::Serialize))]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct EvaluationStep {
+    /// Nesting level of the sub-expression, 0 for the top-level expression
+    #[n(1)] pub depth: u32,
+    #[n(2)] pub expression: String,
+    /// Value of the sub-expression, or the reason why it could not be evaluated
+    #[n(3)] pub result: String,
+    #[n(4)] pub is_error: bool,
+}
+
+/// Evaluate an expression and return the value of each of its sub-expressions.
+///
+/// Operations and identifiers are listed in the order of the expression, each one before its
+/// arguments. Literals are omitted. Note that every argument is evaluated, even when the
+/// evaluation of the whole expression would stop early, like `and` after a false argument.
+pub fn eval_with_trace(expr: &Expr, env: &Env) -> (Result<Expr, EvalError>, Vec<EvaluationStep>) {
+    let mut steps = Vec::new();
+    trace(expr, env, 0, &mut steps);
+    (eval(expr, env), steps)
+}
+
+fn trace(expr: &Expr, env: &Env, depth: u32, steps: &mut Vec<EvaluationStep>) {
+    match expr {
+        Expr::Ident(_) => steps.push(step(expr, env, depth)),
+        Expr::List(xs) => {
+            steps.push(step(expr, env, depth));
+            match &xs[..] {
+                // the arguments of 'exists?' are not evaluated
+                [Expr::Ident(op), ..] if op == "exists?" => {}
+                [Expr::Ident(_), args @ ..] => {
+                    for x in args {
+                        trace(x, env, depth + 1, steps)
+                    }
+                }
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+}
+
+fn step(expr: &Expr, env: &Env, depth: u32) -> EvaluationStep {
+    let (result, is_error) = match eval(expr, env) {
+        Ok(value) => (value.to_string(), false),
+        Err(e) => (format!("{e}"), true),
+    };
+    EvaluationStep {
+        depth,
+        expression: expr.to_string(),
+        result,
+        is_error,
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::parse;
+
+    #[test]
+    fn trace_sub_expressions() {
+        let mut env = Env::new();
+        env.put("subject.role", Expr::Str("admin".into()));
+        let e = parse(r#"(and (= subject.role "admin") (< subject.level 3))"#)
+            .unwrap()
+            .unwrap();
+        let (result, steps) = eval_with_trace(&e, &env);
+
+        assert!(result.is_err());
+        let summary: Vec<_> = steps
+            .iter()
+            .map(|s| (s.depth, s.expression.as_str(), s.is_error))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    0,
+                    r#"(and (= subject.role "admin") (< subject.level 3))"#,
+                    true
+                ),
+                (1, r#"(= subject.role "admin")"#, false),
+                (2, "subject.role", false),
+                (1, "(< subject.level 3)", true),
+                (2, "subject.level", true),
+            ]
+        );
+        assert_eq!(steps[1].result, "true");
+        assert_eq!(steps[2].result, r#""admin""#);
+        assert_eq!(steps[4].result, "unbound identifier: subject.level");
+    }
+}
@@ -131,6 +131,22 @@ impl CredentialIssuer {
             )
             .await?;
 
+        // Keep track of the latest change hash of the member identity, which changes when the
+        // member rotates its keys and presents its new change history to the authority
+        let subject_latest_change_hash =
+            credential.get_credential_data()?.subject_latest_change_hash;
+        if let Some(change_hash) = subject_latest_change_hash {
+            if member.last_change_hash() != Some(&change_hash) {
+                self.members
+                    .update_member_change_hash(subject, &change_hash)
+                    .await?;
+                info!(
+                    "The change hash of the member {} is now {}",
+                    subject, change_hash
+                );
+            }
+        }
+
         info!("Successfully issued a credential for {}", subject);
 
         Ok(Some(credential))
@@ -8,7 +8,9 @@ use ockam_core::compat::sync::Arc;
 use ockam_core::Result;
 
 use crate::authenticator::common::EnrollerAccessControlChecks;
-use crate::authenticator::{AuthorityMember, AuthorityMembersRepository};
+use crate::authenticator::{
+    AuthorityMember, AuthorityMembersRepository, AuthorityRevokedMembersRepository,
+};
 
 /// Identity attribute key that indicates the role of the subject
 pub const OCKAM_ROLE_ATTRIBUTE_KEY: &str = "ockam-role";
@@ -26,6 +28,7 @@ pub type DirectAuthenticatorResult<T> = Either<T, DirectAuthenticatorError>;
 
 pub struct DirectAuthenticator {
     members: Arc<dyn AuthorityMembersRepository>,
+    revoked_members: Arc<dyn AuthorityRevokedMembersRepository>,
     identities_attributes: Arc<IdentitiesAttributes>,
     account_authority: Option<AccountAuthorityInfo>,
 }
@@ -63,11 +66,13 @@ impl AccountAuthorityInfo {
 impl DirectAuthenticator {
     pub fn new(
         members: Arc<dyn AuthorityMembersRepository>,
+        revoked_members: Arc<dyn AuthorityRevokedMembersRepository>,
         identities_attributes: Arc<IdentitiesAttributes>,
         account_authority: Option<AccountAuthorityInfo>,
     ) -> Self {
         Self {
             members,
+            revoked_members,
             identities_attributes,
             account_authority,
         }
@@ -286,6 +291,11 @@ impl DirectAuthenticator {
 
         self.members.delete_member(identifier).await?;
 
+        // Revoke the credentials which were already issued to that member
+        self.revoked_members
+            .revoke_member(identifier, enroller, now()?)
+            .await?;
+
         info!("Successfully deleted member {}", identifier);
 
         Ok(Either::Left(()))
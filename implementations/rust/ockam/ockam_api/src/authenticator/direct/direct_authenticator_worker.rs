@@ -10,7 +10,7 @@ use ockam_node::Context;
 
 use crate::authenticator::direct::types::AddMember;
 use crate::authenticator::direct::DirectAuthenticator;
-use crate::authenticator::AuthorityMembersRepository;
+use crate::authenticator::{AuthorityMembersRepository, AuthorityRevokedMembersRepository};
 
 use super::AccountAuthorityInfo;
 
@@ -21,12 +21,14 @@ pub struct DirectAuthenticatorWorker {
 impl DirectAuthenticatorWorker {
     pub fn new(
         members: Arc<dyn AuthorityMembersRepository>,
+        revoked_members: Arc<dyn AuthorityRevokedMembersRepository>,
         identities_attributes: Arc<IdentitiesAttributes>,
         account_authority: Option<AccountAuthorityInfo>,
     ) -> Self {
         Self {
             authenticator: DirectAuthenticator::new(
                 members,
+                revoked_members,
                 identities_attributes,
                 account_authority,
             ),
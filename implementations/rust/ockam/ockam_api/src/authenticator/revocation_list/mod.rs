@@ -0,0 +1,7 @@
+mod revocation_list_issuer;
+mod revocation_list_issuer_worker;
+mod revocation_list_retriever;
+
+pub use revocation_list_issuer::*;
+pub use revocation_list_issuer_worker::*;
+pub use revocation_list_retriever::*;
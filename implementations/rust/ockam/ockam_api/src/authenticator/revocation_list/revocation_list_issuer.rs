@@ -0,0 +1,45 @@
+use crate::authenticator::AuthorityRevokedMembersRepository;
+use ockam::identity::models::RevocationListAndPurposeKey;
+use ockam::identity::{Identifier, RevocationLists};
+use ockam_core::compat::sync::Arc;
+use ockam_core::Result;
+
+/// This struct publishes the list of members revoked by an Authority,
+/// signed with the Authority credentials purpose key
+pub struct RevocationListIssuer {
+    revoked_members: Arc<dyn AuthorityRevokedMembersRepository>,
+    revocation_lists: Arc<RevocationLists>,
+    issuer: Identifier,
+}
+
+impl RevocationListIssuer {
+    /// Create a new revocation list issuer
+    pub fn new(
+        revoked_members: Arc<dyn AuthorityRevokedMembersRepository>,
+        revocation_lists: Arc<RevocationLists>,
+        issuer: &Identifier,
+    ) -> Self {
+        Self {
+            revoked_members,
+            revocation_lists,
+            issuer: issuer.clone(),
+        }
+    }
+
+    /// Return the current revocation list
+    #[instrument(skip_all, fields(issuer = %self.issuer))]
+    pub async fn issue_revocation_list(&self) -> Result<RevocationListAndPurposeKey> {
+        let version = self.revoked_members.get_revocation_list_version().await?;
+        let revoked_subjects = self
+            .revoked_members
+            .get_revoked_members()
+            .await?
+            .into_iter()
+            .map(|m| m.into())
+            .collect();
+
+        self.revocation_lists
+            .issue_revocation_list(&self.issuer, version, revoked_subjects)
+            .await
+    }
+}
@@ -0,0 +1,78 @@
+use minicbor::Decoder;
+use tracing::trace;
+
+use crate::authenticator::revocation_list::RevocationListIssuer;
+use crate::authenticator::AuthorityRevokedMembersRepository;
+use ockam::identity::{Identifier, IdentitySecureChannelLocalInfo, RevocationLists};
+use ockam_core::api::{Method, RequestHeader, Response};
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
+use ockam_core::{Result, Routed, Worker};
+use ockam_node::Context;
+
+/// This struct runs as a Worker to serve the Authority revocation list
+pub struct RevocationListIssuerWorker {
+    revocation_list_issuer: RevocationListIssuer,
+}
+
+impl RevocationListIssuerWorker {
+    /// Create a new revocation list issuer worker
+    pub fn new(
+        revoked_members: Arc<dyn AuthorityRevokedMembersRepository>,
+        revocation_lists: Arc<RevocationLists>,
+        issuer: &Identifier,
+    ) -> Self {
+        Self {
+            revocation_list_issuer: RevocationListIssuer::new(
+                revoked_members,
+                revocation_lists,
+                issuer,
+            ),
+        }
+    }
+}
+
+#[ockam_core::worker]
+impl Worker for RevocationListIssuerWorker {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
+        let secure_channel_info = match IdentitySecureChannelLocalInfo::find_info(m.local_message())
+        {
+            Ok(secure_channel_info) => secure_channel_info,
+            Err(_e) => {
+                let resp = Response::bad_request_no_request("secure channel required").to_vec()?;
+                c.send(m.return_route(), resp).await?;
+                return Ok(());
+            }
+        };
+
+        let from = secure_channel_info.their_identity_id();
+        let return_route = m.return_route();
+        let body = m.into_body()?;
+        let mut dec = Decoder::new(&body);
+        let req: RequestHeader = dec.decode()?;
+        trace! {
+            target: "revocation_list_issuer",
+            from   = %from,
+            id     = %req.id(),
+            method = ?req.method(),
+            path   = %req.path(),
+            body   = %req.has_body(),
+            "request"
+        }
+        let res = match (req.method(), req.path()) {
+            (Some(Method::Get), "/") => {
+                match self.revocation_list_issuer.issue_revocation_list().await {
+                    Ok(list) => Response::ok().with_headers(&req).body(list).to_vec()?,
+                    Err(error) => Response::internal_error(&req, &error.to_string()).to_vec()?,
+                }
+            }
+            _ => Response::unknown_path(&req).to_vec()?,
+        };
+
+        c.send(return_route, res).await
+    }
+}
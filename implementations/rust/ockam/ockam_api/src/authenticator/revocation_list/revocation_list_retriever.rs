@@ -0,0 +1,113 @@
+use core::time::Duration;
+use tokio::select;
+use tokio::sync::watch;
+use tracing::{debug, info, warn};
+
+use crate::nodes::service::default_address::DefaultAddress;
+use ockam::identity::models::RevocationListAndPurposeKey;
+use ockam::identity::{get_default_timeout, Identifier, SecureChannels, SecureClient};
+use ockam_core::api::Request;
+use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
+use ockam_core::{Result, Route};
+use ockam_node::Context;
+use ockam_transport_core::Transport;
+
+/// Default interval between two retrievals of the Authority revocation list
+pub const DEFAULT_REVOCATION_LIST_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// This struct regularly retrieves the revocation list published by an Authority node.
+///
+/// Each new version of the list is verified and stored, and the secure channels established
+/// with newly revoked identities are closed.
+#[derive(Clone)]
+pub struct RevocationListRetriever {
+    ctx: Arc<Context>,
+    client: SecureClient,
+    secure_channels: Arc<SecureChannels>,
+    authority: Identifier,
+    refresh_interval: Duration,
+}
+
+impl RevocationListRetriever {
+    /// Create a new revocation list retriever
+    pub fn new(
+        ctx: Context,
+        transport: Arc<dyn Transport>,
+        secure_channels: Arc<SecureChannels>,
+        authority: Identifier,
+        authority_route: Route,
+        subject: Identifier,
+    ) -> Self {
+        let client = SecureClient::new(
+            secure_channels.clone(),
+            None,
+            transport,
+            authority_route,
+            &authority,
+            &subject,
+            get_default_timeout(),
+            get_default_timeout(),
+        );
+        Self {
+            ctx: Arc::new(ctx),
+            client,
+            secure_channels,
+            authority,
+            refresh_interval: DEFAULT_REVOCATION_LIST_REFRESH_INTERVAL,
+        }
+    }
+
+    /// Set the interval between two retrievals
+    pub fn with_refresh_interval(mut self, refresh_interval: Duration) -> Self {
+        self.refresh_interval = refresh_interval;
+        self
+    }
+
+    /// Retrieve the revocation list once and return the newly revoked identities
+    pub async fn retrieve(&self) -> Result<Vec<Identifier>> {
+        let revocation_list: RevocationListAndPurposeKey = self
+            .client
+            .ask(
+                &self.ctx,
+                DefaultAddress::REVOCATION_LIST_SERVICE,
+                Request::get("/"),
+            )
+            .await?
+            .success()?;
+
+        debug!("retrieved a revocation list from {}", self.authority);
+
+        self.secure_channels
+            .receive_revocation_list(&self.ctx, &self.authority, revocation_list)
+            .await
+    }
+
+    /// Retrieve the revocation list in the background, every `refresh_interval`,
+    /// until `true` is sent on the `shutdown` channel or the channel is closed
+    pub fn start(self, mut shutdown: watch::Receiver<bool>) {
+        ockam_node::spawn(async move {
+            info!(
+                "retrieving the revocation list of {} every {} seconds",
+                self.authority,
+                self.refresh_interval.as_secs()
+            );
+            loop {
+                if let Err(e) = self.retrieve().await {
+                    warn!(
+                        "could not retrieve the revocation list of {}: {e:?}",
+                        self.authority
+                    );
+                }
+                select! {
+                    _ = self.ctx.sleep(self.refresh_interval) => {}
+                    _ = shutdown.wait_for(|stopped| *stopped) => break,
+                }
+            }
+            debug!(
+                "stopped retrieving the revocation list of {}",
+                self.authority
+            );
+        });
+    }
+}
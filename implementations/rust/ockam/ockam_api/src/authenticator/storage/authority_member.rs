@@ -1,3 +1,4 @@
+use ockam::identity::models::ChangeHash;
 use ockam::identity::{Identifier, TimestampInSeconds};
 use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::str::FromStr;
@@ -14,6 +15,9 @@ pub struct AuthorityMember {
     // Was provided by TrustedIdentities argument during the Authority startup
     // pre-trusted identities can't be deleted using [`MembersStorage::delete_member()`]
     is_pre_trusted: bool,
+    // Latest change hash of the member identity, known when a credential was last issued.
+    // It is updated when the member rotates its identity keys
+    last_change_hash: Option<ChangeHash>,
 }
 
 impl AuthorityMember {
@@ -30,8 +34,15 @@ impl AuthorityMember {
             added_by,
             added_at,
             is_pre_trusted,
+            last_change_hash: None,
         }
     }
+
+    pub fn with_last_change_hash(mut self, last_change_hash: Option<ChangeHash>) -> Self {
+        self.last_change_hash = last_change_hash;
+        self
+    }
+
     pub fn identifier(&self) -> &Identifier {
         &self.identifier
     }
@@ -47,6 +58,9 @@ impl AuthorityMember {
     pub fn is_pre_trusted(&self) -> bool {
         self.is_pre_trusted
     }
+    pub fn last_change_hash(&self) -> Option<&ChangeHash> {
+        self.last_change_hash.as_ref()
+    }
 }
 
 // Low-level representation of a table row
@@ -57,6 +71,7 @@ pub(crate) struct AuthorityMemberRow {
     added_at: i64,
     is_pre_trusted: Boolean,
     attributes: Vec<u8>,
+    last_change_hash: Option<String>,
 }
 
 impl TryFrom<AuthorityMemberRow> for AuthorityMember {
@@ -69,6 +84,12 @@ impl TryFrom<AuthorityMemberRow> for AuthorityMember {
             Identifier::from_str(&value.added_by)?,
             TimestampInSeconds(value.added_at as u64),
             value.is_pre_trusted.to_bool(),
+        )
+        .with_last_change_hash(
+            value
+                .last_change_hash
+                .map(|hash| ChangeHash::from_str(&hash))
+                .transpose()?,
         );
 
         Ok(member)
@@ -1,4 +1,5 @@
 use crate::authenticator::{AuthorityMember, PreTrustedIdentities};
+use ockam::identity::models::ChangeHash;
 use ockam::identity::Identifier;
 use ockam_core::async_trait;
 use ockam_core::compat::boxed::Box;
@@ -20,6 +21,13 @@ pub trait AuthorityMembersRepository: Send + Sync + 'static {
     /// Add a member to the Project
     async fn add_member(&self, member: AuthorityMember) -> Result<()>;
 
+    /// Set the latest known change hash of a member identity
+    async fn update_member_change_hash(
+        &self,
+        identifier: &Identifier,
+        change_hash: &ChangeHash,
+    ) -> Result<()>;
+
     /// Remove the old pre-trusted members and store new pre-trusted members
     async fn bootstrap_pre_trusted_members(
         &self,
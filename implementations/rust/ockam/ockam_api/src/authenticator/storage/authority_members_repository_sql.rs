@@ -2,6 +2,7 @@ use core::ops::Deref;
 use sqlx::*;
 use tracing::debug;
 
+use ockam::identity::models::ChangeHash;
 use ockam::identity::Identifier;
 use ockam_core::async_trait;
 use ockam_core::Result;
@@ -34,7 +35,7 @@ impl AuthorityMembersSqlxDatabase {
 #[async_trait]
 impl AuthorityMembersRepository for AuthorityMembersSqlxDatabase {
     async fn get_member(&self, identifier: &Identifier) -> Result<Option<AuthorityMember>> {
-        let query = query_as("SELECT identifier, added_by, added_at, is_pre_trusted, attributes FROM authority_member WHERE identifier = $1")
+        let query = query_as("SELECT identifier, added_by, added_at, is_pre_trusted, attributes, last_change_hash FROM authority_member WHERE identifier = $1")
             .bind(identifier);
         let row: Option<AuthorityMemberRow> = query
             .fetch_optional(&*self.database.pool)
@@ -44,7 +45,7 @@ impl AuthorityMembersRepository for AuthorityMembersSqlxDatabase {
     }
 
     async fn get_members(&self) -> Result<Vec<AuthorityMember>> {
-        let query = query_as("SELECT identifier, added_by, added_at, is_pre_trusted, attributes FROM authority_member");
+        let query = query_as("SELECT identifier, added_by, added_at, is_pre_trusted, attributes, last_change_hash FROM authority_member");
         let row: Vec<AuthorityMemberRow> =
             query.fetch_all(&*self.database.pool).await.into_core()?;
         row.into_iter().map(|r| r.try_into()).collect()
@@ -73,6 +74,18 @@ impl AuthorityMembersRepository for AuthorityMembersSqlxDatabase {
         query.execute(&*self.database.pool).await.void()
     }
 
+    async fn update_member_change_hash(
+        &self,
+        identifier: &Identifier,
+        change_hash: &ChangeHash,
+    ) -> Result<()> {
+        let query =
+            query("UPDATE authority_member SET last_change_hash = $1 WHERE identifier = $2")
+                .bind(change_hash.to_string())
+                .bind(identifier);
+        query.execute(&*self.database.pool).await.void()
+    }
+
     async fn bootstrap_pre_trusted_members(
         &self,
         pre_trusted_identities: &PreTrustedIdentities,
@@ -106,7 +119,7 @@ mod tests {
     use super::*;
     use crate::authenticator::direct::OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE;
     use crate::authenticator::PreTrustedIdentity;
-    use ockam::identity::models::IDENTIFIER_LEN;
+    use ockam::identity::models::{CHANGE_HASH_LEN, IDENTIFIER_LEN};
     use ockam::identity::utils::now;
     use ockam::identity::Identifier;
     use ockam_core::compat::collections::BTreeMap;
@@ -176,6 +189,19 @@ mod tests {
             assert_eq!(members.len(), 1);
             assert!(members.contains(&member2));
 
+            // the change hash of a member is updated after a key rotation
+            let change_hash = ChangeHash([1; CHANGE_HASH_LEN]);
+            repository
+                .update_member_change_hash(&identifier2, &change_hash)
+                .await?;
+            let member2 = repository.get_member(&identifier2).await?.unwrap();
+            assert_eq!(member2.last_change_hash(), Some(&change_hash));
+
+            // adding the member again keeps its change hash
+            repository.add_member(member2.clone()).await?;
+            let member2 = repository.get_member(&identifier2).await?.unwrap();
+            assert_eq!(member2.last_change_hash(), Some(&change_hash));
+
             Ok(())
         })
         .await
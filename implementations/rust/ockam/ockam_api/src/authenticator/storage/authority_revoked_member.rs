@@ -0,0 +1,74 @@
+use ockam::identity::models::RevokedSubject;
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::compat::str::FromStr;
+use ockam_core::{Error, Result};
+
+/// Project member removed from the Authority node.
+/// The credentials issued to that member before `revoked_at` are revoked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthorityRevokedMember {
+    identifier: Identifier,
+    revoked_by: Identifier,
+    revoked_at: TimestampInSeconds,
+    // Version of the revocation list which contains this revocation
+    revision: u64,
+}
+
+impl AuthorityRevokedMember {
+    pub fn new(
+        identifier: Identifier,
+        revoked_by: Identifier,
+        revoked_at: TimestampInSeconds,
+        revision: u64,
+    ) -> Self {
+        Self {
+            identifier,
+            revoked_by,
+            revoked_at,
+            revision,
+        }
+    }
+    pub fn identifier(&self) -> &Identifier {
+        &self.identifier
+    }
+    pub fn revoked_by(&self) -> &Identifier {
+        &self.revoked_by
+    }
+    pub fn revoked_at(&self) -> TimestampInSeconds {
+        self.revoked_at
+    }
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+}
+
+impl From<AuthorityRevokedMember> for RevokedSubject {
+    fn from(value: AuthorityRevokedMember) -> Self {
+        RevokedSubject {
+            subject: value.identifier,
+            revoked_at: value.revoked_at,
+        }
+    }
+}
+
+// Low-level representation of a table row
+#[derive(sqlx::FromRow)]
+pub(crate) struct AuthorityRevokedMemberRow {
+    identifier: String,
+    revoked_by: String,
+    revoked_at: i64,
+    revision: i64,
+}
+
+impl TryFrom<AuthorityRevokedMemberRow> for AuthorityRevokedMember {
+    type Error = Error;
+
+    fn try_from(value: AuthorityRevokedMemberRow) -> Result<Self, Self::Error> {
+        Ok(AuthorityRevokedMember::new(
+            Identifier::from_str(&value.identifier)?,
+            Identifier::from_str(&value.revoked_by)?,
+            TimestampInSeconds(value.revoked_at as u64),
+            value.revision as u64,
+        ))
+    }
+}
@@ -0,0 +1,26 @@
+use crate::authenticator::AuthorityRevokedMember;
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+
+/// This repository stores the members revoked by the Authority node.
+/// It is used to publish the Authority revocation list
+#[async_trait]
+pub trait AuthorityRevokedMembersRepository: Send + Sync + 'static {
+    /// Revoke the credentials issued to a member before `revoked_at`.
+    /// This increments the version of the revocation list
+    async fn revoke_member(
+        &self,
+        identifier: &Identifier,
+        revoked_by: &Identifier,
+        revoked_at: TimestampInSeconds,
+    ) -> Result<()>;
+
+    /// Return all the revoked members
+    async fn get_revoked_members(&self) -> Result<Vec<AuthorityRevokedMember>>;
+
+    /// Return the current version of the revocation list
+    async fn get_revocation_list_version(&self) -> Result<u64>;
+}
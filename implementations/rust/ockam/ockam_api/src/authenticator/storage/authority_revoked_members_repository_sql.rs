@@ -0,0 +1,160 @@
+use sqlx::*;
+use tracing::debug;
+
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::async_trait;
+use ockam_core::Result;
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToVoid};
+
+use crate::authenticator::{
+    AuthorityRevokedMember, AuthorityRevokedMemberRow, AuthorityRevokedMembersRepository,
+};
+
+#[derive(Clone)]
+pub struct AuthorityRevokedMembersSqlxDatabase {
+    database: SqlxDatabase,
+}
+
+impl AuthorityRevokedMembersSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for authority revoked members");
+        Self { database }
+    }
+
+    /// Create a new in-memory database
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(
+            SqlxDatabase::in_memory("authority revoked members").await?,
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorityRevokedMembersRepository for AuthorityRevokedMembersSqlxDatabase {
+    async fn revoke_member(
+        &self,
+        identifier: &Identifier,
+        revoked_by: &Identifier,
+        revoked_at: TimestampInSeconds,
+    ) -> Result<()> {
+        let mut transaction = self.database.begin().await.into_core()?;
+
+        let query1 = query_as(
+            "SELECT revision FROM authority_revoked_member ORDER BY revision DESC LIMIT 1",
+        );
+        let last: Option<RevisionRow> =
+            query1.fetch_optional(&mut *transaction).await.into_core()?;
+        let revision = last.map(|r| r.revision).unwrap_or(0) + 1;
+
+        let query2 = query(
+            r#"
+             INSERT INTO authority_revoked_member (identifier, revoked_by, revoked_at, revision)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (identifier)
+             DO UPDATE SET revoked_by = $2, revoked_at = $3, revision = $4"#,
+        )
+        .bind(identifier)
+        .bind(revoked_by)
+        .bind(revoked_at)
+        .bind(revision);
+        query2.execute(&mut *transaction).await.void()?;
+
+        transaction.commit().await.void()
+    }
+
+    async fn get_revoked_members(&self) -> Result<Vec<AuthorityRevokedMember>> {
+        let query = query_as(
+            "SELECT identifier, revoked_by, revoked_at, revision FROM authority_revoked_member",
+        );
+        let rows: Vec<AuthorityRevokedMemberRow> =
+            query.fetch_all(&*self.database.pool).await.into_core()?;
+        rows.into_iter().map(|r| r.try_into()).collect()
+    }
+
+    async fn get_revocation_list_version(&self) -> Result<u64> {
+        let query = query_as(
+            "SELECT revision FROM authority_revoked_member ORDER BY revision DESC LIMIT 1",
+        );
+        let last: Option<RevisionRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        Ok(last.map(|r| r.revision as u64).unwrap_or(0))
+    }
+}
+
+// Low-level representation of a table row
+#[derive(FromRow)]
+struct RevisionRow {
+    revision: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam::identity::models::IDENTIFIER_LEN;
+    use ockam::identity::utils::now;
+    use ockam_core::compat::rand::RngCore;
+    use ockam_core::compat::sync::Arc;
+    use ockam_node::database::with_dbs;
+    use rand::thread_rng;
+
+    fn random_identifier() -> Identifier {
+        let mut data = [0u8; IDENTIFIER_LEN];
+
+        let mut rng = thread_rng();
+        rng.fill_bytes(&mut data);
+
+        Identifier(data)
+    }
+
+    #[tokio::test]
+    async fn test_authority_revoked_members_repository() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn AuthorityRevokedMembersRepository> =
+                Arc::new(AuthorityRevokedMembersSqlxDatabase::new(db));
+
+            assert_eq!(repository.get_revocation_list_version().await?, 0);
+            assert!(repository.get_revoked_members().await?.is_empty());
+
+            let enroller = random_identifier();
+            let identifier1 = random_identifier();
+            let identifier2 = random_identifier();
+            let timestamp1 = now()?;
+            let timestamp2 = timestamp1 + 10;
+
+            repository
+                .revoke_member(&identifier1, &enroller, timestamp1)
+                .await?;
+            repository
+                .revoke_member(&identifier2, &enroller, timestamp1)
+                .await?;
+            assert_eq!(repository.get_revocation_list_version().await?, 2);
+
+            // revoking a member again updates the revocation time and the version
+            repository
+                .revoke_member(&identifier1, &enroller, timestamp2)
+                .await?;
+            assert_eq!(repository.get_revocation_list_version().await?, 3);
+
+            let revoked = repository.get_revoked_members().await?;
+            assert_eq!(revoked.len(), 2);
+            assert!(revoked.contains(&AuthorityRevokedMember::new(
+                identifier1,
+                enroller.clone(),
+                timestamp2,
+                3
+            )));
+            assert!(revoked.contains(&AuthorityRevokedMember::new(
+                identifier2,
+                enroller,
+                timestamp1,
+                2
+            )));
+
+            Ok(())
+        })
+        .await
+    }
+}
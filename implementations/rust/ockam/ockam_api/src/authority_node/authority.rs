@@ -6,9 +6,11 @@ use crate::authenticator::direct::{AccountAuthorityInfo, DirectAuthenticatorWork
 use crate::authenticator::enrollment_tokens::{
     EnrollmentTokenAcceptorWorker, EnrollmentTokenIssuerWorker,
 };
+use crate::authenticator::revocation_list::RevocationListIssuerWorker;
 use crate::authenticator::{
     AuthorityEnrollmentTokenRepository, AuthorityEnrollmentTokenSqlxDatabase, AuthorityMember,
-    AuthorityMembersRepository, AuthorityMembersSqlxDatabase,
+    AuthorityMembersRepository, AuthorityMembersSqlxDatabase, AuthorityRevokedMembersRepository,
+    AuthorityRevokedMembersSqlxDatabase,
 };
 use ockam::identity::utils::now;
 use ockam::identity::{
@@ -34,10 +36,12 @@ use crate::nodes::service::default_address::DefaultAddress;
 //   - a credential issuer
 //   - an enrollment token issuer
 //   - an enrollment token acceptor
+//   - a revocation list service
 pub struct Authority {
     identifier: Identifier,
     secure_channels: Arc<SecureChannels>,
     members: Arc<dyn AuthorityMembersRepository>,
+    revoked_members: Arc<dyn AuthorityRevokedMembersRepository>,
     tokens: Arc<dyn AuthorityEnrollmentTokenRepository>,
     account_authority: Option<AccountAuthorityInfo>,
 }
@@ -74,6 +78,7 @@ impl Authority {
         };
 
         let members = Arc::new(AuthorityMembersSqlxDatabase::new(database.clone()));
+        let revoked_members = Arc::new(AuthorityRevokedMembersSqlxDatabase::new(database.clone()));
         let tokens = Arc::new(AuthorityEnrollmentTokenSqlxDatabase::new(database.clone()));
         let secure_channel_repository = Arc::new(SecureChannelSqlxDatabase::new(database.clone()));
 
@@ -105,6 +110,7 @@ impl Authority {
             identifier,
             secure_channels,
             members,
+            revoked_members,
             tokens,
             account_authority,
         })
@@ -166,6 +172,7 @@ impl Authority {
 
         let direct = DirectAuthenticatorWorker::new(
             self.members.clone(),
+            self.revoked_members.clone(),
             self.secure_channels.identities().identities_attributes(),
             self.account_authority.clone(),
         );
@@ -255,6 +262,33 @@ impl Authority {
         Ok(())
     }
 
+    /// Start the revocation list service to publish the list of identities
+    /// whose credentials have been revoked
+    pub async fn start_revocation_list_service(
+        &self,
+        ctx: &Context,
+        secure_channel_flow_control_id: &FlowControlId,
+    ) -> Result<()> {
+        let revocation_list_issuer = RevocationListIssuerWorker::new(
+            self.revoked_members.clone(),
+            self.secure_channels
+                .identities()
+                .credentials()
+                .revocation_lists(),
+            &self.identifier,
+        );
+
+        let address = DefaultAddress::REVOCATION_LIST_SERVICE.to_string();
+        ctx.flow_controls()
+            .add_consumer(address.clone(), secure_channel_flow_control_id);
+
+        ctx.start_worker(address.clone(), revocation_list_issuer)
+            .await?;
+
+        info!("started a revocation list service at '{address}'");
+        Ok(())
+    }
+
     /// Start the Okta service to retrieve attributes authenticated by Okta
     pub async fn start_okta(
         &self,
@@ -34,6 +34,11 @@ pub async fn start_node(
         .await?;
     debug!("credential issuer started");
 
+    authority
+        .start_revocation_list_service(ctx, &secure_channel_flow_control_id)
+        .await?;
+    debug!("revocation list service started");
+
     // start the Okta service (if the optional configuration has been provided)
     authority
         .start_okta(ctx, &secure_channel_flow_control_id, configuration)
@@ -39,8 +39,14 @@ impl CliState {
         };
 
         let vault = self.get_named_vault(vault_name).await?;
+        let signing_key_type = vault.signing_key_type();
         let identities = self.make_identities(self.make_vault(vault).await?).await?;
-        let identity = identities.identities_creation().create_identity().await?;
+        let identity = identities
+            .identities_creation()
+            .identity_builder()
+            .with_random_key(signing_key_type)
+            .build()
+            .await?;
         let named_identity = self
             .store_named_identity(&identity, name, vault_name)
             .await?;
@@ -72,8 +78,9 @@ impl CliState {
     }
 
     /// Create an identity with specific key id.
-    /// This method is used when the vault is a KMS vault and we just need to store a key id
-    /// for the identity key existing in the KMS
+    /// This method is used when the vault is a KMS vault, or a PKCS#11 vault, and we just need
+    /// to store a key id for the identity key existing in the KMS or in the token.
+    /// For a PKCS#11 token, the key id is the hex-encoded CKA_ID of the key
     #[instrument(skip_all, fields(name = %name, vault_name = %vault_name, key_id = %key_id))]
     pub async fn create_identity_with_key_id(
         &self,
@@ -83,18 +90,26 @@ impl CliState {
     ) -> Result<NamedIdentity> {
         let vault = self.get_named_vault(vault_name).await?;
 
-        // Check that the vault is an AWS KMS vault
-        if !vault.use_aws_kms() {
+        // Check that the vault is an AWS KMS vault or a PKCS#11 vault
+        let key_id = if vault.use_aws_kms() {
+            key_id.as_bytes().to_vec()
+        } else if vault.use_pkcs11() {
+            hex::decode(key_id).map_err(|e| {
+                Error::new(
+                    Origin::Api,
+                    Kind::Invalid,
+                    format!("The PKCS#11 key id {key_id} must be hex-encoded: {e}"),
+                )
+            })?
+        } else {
             return Err(Error::new(
                 Origin::Api,
                 Kind::Misuse,
-                format!("Vault {vault_name} is not a KMS vault"),
+                format!("Vault {vault_name} is not a KMS vault or a PKCS#11 vault"),
             ))?;
         };
 
-        let handle = SigningSecretKeyHandle::ECDSASHA256CurveP256(HandleToSecret::new(
-            key_id.as_bytes().to_vec(),
-        ));
+        let handle = SigningSecretKeyHandle::ECDSASHA256CurveP256(HandleToSecret::new(key_id));
 
         // create the identity
         let identities = self.make_identities(self.make_vault(vault).await?).await?;
@@ -109,6 +124,35 @@ impl CliState {
         self.store_named_identity(&identifier, name, vault_name)
             .await
     }
+
+    /// Rotate the key of a named identity: a new key is created in the identity vault
+    /// and a new change, signed by both the previous and the new key, is added to the
+    /// identity change history. The purpose keys of the identity are renewed with the
+    /// new key. Return the updated identity
+    #[instrument(skip_all, fields(name = %name))]
+    pub async fn rotate_identity(&self, name: &str) -> Result<Identity> {
+        let named_identity = self.get_named_identity(name).await?;
+        let vault = self.get_named_vault(&named_identity.vault_name()).await?;
+        let signing_key_type = vault.signing_key_type();
+        let identities = self.make_identities(self.make_vault(vault).await?).await?;
+        let identifier = named_identity.identifier();
+        let options = identities
+            .identities_creation()
+            .identity_builder()
+            .with_random_key(signing_key_type)
+            .build_options()
+            .await?;
+        identities
+            .identities_creation()
+            .rotate_identity_with_options(&identifier, options)
+            .await?;
+        identities
+            .purpose_keys()
+            .purpose_keys_creation()
+            .renew_purpose_keys(&identifier)
+            .await?;
+        self.get_identity(&identifier).await
+    }
 }
 
 /// The methods below allow to query identities:
@@ -547,4 +591,26 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_rotate_identity() -> Result<()> {
+        let cli = CliState::test().await?;
+        let named_identity = cli.create_identity_with_name("name").await?;
+        let identity = cli.get_identity(&named_identity.identifier()).await?;
+
+        // the identifier stays the same but a new change is added to the change history
+        let rotated = cli.rotate_identity("name").await?;
+        assert_eq!(rotated.identifier(), identity.identifier());
+        assert_eq!(rotated.changes().len(), identity.changes().len() + 1);
+        assert_ne!(
+            rotated.latest_change_hash()?,
+            identity.latest_change_hash()?
+        );
+        assert_eq!(
+            cli.get_identity(&named_identity.identifier()).await?,
+            rotated
+        );
+
+        Ok(())
+    }
 }
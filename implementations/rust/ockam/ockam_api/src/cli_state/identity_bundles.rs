@@ -0,0 +1,778 @@
+use std::sync::Arc;
+
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
+use minicbor::bytes::ByteArray;
+use minicbor::{CborLen, Decode, Encode};
+use rand::RngCore;
+use sqlx::*;
+use zeroize::Zeroizing;
+
+use ockam::identity::models::{
+    ChangeHistory, CredentialAndPurposeKey, PurposeKeyAttestation, PurposePublicKey,
+};
+use ockam::identity::storage::PurposeKeysSqlxDatabase;
+use ockam::identity::{
+    ChangeHistorySqlxDatabase, CredentialSqlxDatabase, Identifier, Identities, Identity, Purpose,
+    TimestampInSeconds, Vault,
+};
+use ockam::{FromSqlxError, SqlxDatabase, ToVoid};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Error;
+use ockam_node::database::Boolean;
+use ockam_vault::storage::{SecretsRepository, SecretsSqlxDatabase};
+use ockam_vault::{
+    ECDSASHA256CurveP256SecretKey, EdDSACurve25519SecretKey, SigningSecret, SigningSecretKeyHandle,
+    SoftwareVaultForSecureChannels, SoftwareVaultForSigning, X25519SecretKey,
+    X25519SecretKeyHandle,
+};
+
+use crate::cli_state::{
+    CliState, CliStateError, IdentitiesSqlxDatabase, NamedIdentity, NamedVault, Result,
+};
+
+/// Version of the format of an encrypted identity bundle
+const IDENTITY_BUNDLE_VERSION: u8 = 1;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+/// Maximum Argon2 memory cost accepted when importing a bundle, in KiB (256 MiB)
+const MAX_MEMORY_COST: u32 = 256 * 1024;
+/// Maximum Argon2 number of iterations accepted when importing a bundle
+const MAX_TIME_COST: u32 = 16;
+/// Maximum Argon2 degree of parallelism accepted when importing a bundle
+const MAX_PARALLELISM: u32 = 8;
+
+/// The methods below allow an identity to be moved from one machine to another, with its secrets.
+///
+/// An identity bundle contains:
+///
+///  - the change history of the identity
+///  - the secret of the current identity key
+///  - the secure channel and credential purpose keys of the identity, with their secrets
+///  - the credentials cached by the nodes using the identity
+///
+/// The bundle is encrypted with AES-256-GCM, using a key derived from a passphrase with Argon2id.
+///
+impl CliState {
+    /// Export a named identity, with its secrets, as an encrypted bundle
+    #[instrument(skip_all, fields(name = %name))]
+    pub async fn export_identity_with_secrets(
+        &self,
+        name: &str,
+        passphrase: &str,
+    ) -> Result<Vec<u8>> {
+        let named_identity = self.get_named_identity(name).await?;
+        let named_vault = self.get_named_vault(&named_identity.vault_name()).await?;
+        Self::check_exportable_vault(&named_vault)?;
+
+        let secrets = self.make_vault_secrets_repository(&named_vault).await?;
+        let vault = self.make_vault(named_vault).await?;
+        let identities = self.make_identities(vault.clone()).await?;
+        let identifier = named_identity.identifier();
+        let identity = identities.get_identity(&identifier).await?;
+
+        let identity_key = vault
+            .identity_vault
+            .get_secret_key_handle(&identity.get_latest_public_key()?)
+            .await?;
+        let mut signing_secrets = vec![Self::get_signing_secret(&secrets, &identity_key).await?];
+        let mut x25519_secrets = vec![];
+        let mut purpose_keys = vec![];
+
+        let purpose_keys_creation = identities.purpose_keys().purpose_keys_creation();
+        let purpose_keys_repository = identities.purpose_keys_repository();
+        if let Some(attestation) = purpose_keys_repository
+            .get_purpose_key(&identifier, Purpose::SecureChannel)
+            .await?
+        {
+            let purpose_key = purpose_keys_creation
+                .import_secure_channel_purpose_key(&attestation)
+                .await?;
+            x25519_secrets.push(Self::get_x25519_secret(&secrets, purpose_key.key()).await?);
+            purpose_keys.push(attestation);
+        }
+        if let Some(attestation) = purpose_keys_repository
+            .get_purpose_key(&identifier, Purpose::Credentials)
+            .await?
+        {
+            let purpose_key = purpose_keys_creation
+                .import_credential_purpose_key(&attestation)
+                .await?;
+            signing_secrets.push(Self::get_signing_secret(&secrets, purpose_key.key()).await?);
+            purpose_keys.push(attestation);
+        }
+
+        let mut credentials = vec![];
+        for node in self.get_nodes_by_identity_name(name).await? {
+            let cached_credentials = CredentialSqlxDatabase::new(self.database(), &node.name())
+                .get_all()
+                .await?;
+            for (credential, scope) in cached_credentials {
+                if credential.get_credential_data()?.subject.as_ref() == Some(&identifier) {
+                    credentials.push(BundleCredential {
+                        node_name: node.name(),
+                        scope,
+                        credential,
+                    });
+                }
+            }
+        }
+
+        let bundle = IdentityBundle {
+            change_history: identity.change_history().clone(),
+            signing_secrets,
+            x25519_secrets,
+            purpose_keys,
+            credentials,
+        };
+        EncryptedIdentityBundle::encrypt(&bundle, passphrase)?.encode()
+    }
+
+    /// Import an identity from an encrypted bundle, store its secrets in a vault and give it a name.
+    /// The whole bundle is verified before anything is stored
+    #[instrument(skip_all, fields(name = %name))]
+    pub async fn import_identity_with_secrets(
+        &self,
+        encrypted_bundle: &[u8],
+        passphrase: &str,
+        name: &str,
+        vault_name: &Option<String>,
+    ) -> Result<NamedIdentity> {
+        let bundle = EncryptedIdentityBundle::decode(encrypted_bundle)?.decrypt(passphrase)?;
+
+        if self.get_named_identity(name).await.is_ok() {
+            return Err(CliStateError::AlreadyExists {
+                resource: "identity".to_string(),
+                name: name.to_string(),
+            });
+        }
+        let named_vault = self.get_named_vault_or_default(vault_name).await?;
+        Self::check_exportable_vault(&named_vault)?;
+
+        let bundle = VerifiedIdentityBundle::verify(bundle).await?;
+        if let Some(existing) = self
+            .identities_repository()
+            .get_named_identity_by_identifier(&bundle.identifier)
+            .await?
+        {
+            return Err(CliStateError::AlreadyExists {
+                resource: "identity".to_string(),
+                name: existing.name(),
+            });
+        }
+
+        self.store_identity_bundle(bundle, name, &named_vault).await
+    }
+}
+
+/// Support methods
+impl CliState {
+    /// Only the secrets of software vaults can be exported or imported
+    fn check_exportable_vault(named_vault: &NamedVault) -> Result<()> {
+        if named_vault.use_aws_kms() || named_vault.use_pkcs11() {
+            return Err(Error::new(
+                Origin::Api,
+                Kind::Unsupported,
+                format!(
+                    "the keys of the vault {} are stored in a KMS or a PKCS#11 token and cannot be exported or imported",
+                    named_vault.name()
+                ),
+            ))?;
+        }
+        Ok(())
+    }
+
+    /// Store a verified identity bundle in one transaction.
+    ///
+    /// When the vault is stored in its own database file, and not in the main database,
+    /// its secrets are stored first, in a separate transaction
+    async fn store_identity_bundle(
+        &self,
+        bundle: VerifiedIdentityBundle,
+        name: &str,
+        named_vault: &NamedVault,
+    ) -> Result<NamedIdentity> {
+        let database = self.database();
+        let mut transaction = database.begin().await.into_core()?;
+
+        if named_vault.path().is_none() {
+            bundle.store_secrets(&mut transaction).await?;
+        } else {
+            let vault_database = self.make_vault_database(named_vault).await?;
+            let mut vault_transaction = vault_database.begin().await.into_core()?;
+            bundle.store_secrets(&mut vault_transaction).await?;
+            vault_transaction.commit().await.void()?;
+        }
+        bundle.store_identity(&mut transaction).await?;
+
+        // If there is no previously created identity we set this identity as the default one
+        let default_exists: Boolean = IdentitiesSqlxDatabase::default_identity_exists_query()
+            .fetch_one(&mut *transaction)
+            .await
+            .into_core()?;
+        let is_default = !default_exists.to_bool();
+        IdentitiesSqlxDatabase::store_named_identity_query(
+            &bundle.identifier,
+            name,
+            &named_vault.name(),
+            is_default,
+        )
+        .execute(&mut *transaction)
+        .await
+        .void()?;
+
+        transaction.commit().await.void()?;
+        Ok(NamedIdentity::new(
+            bundle.identifier,
+            name.to_string(),
+            named_vault.name(),
+            is_default,
+        ))
+    }
+
+    async fn make_vault_secrets_repository(
+        &self,
+        named_vault: &NamedVault,
+    ) -> Result<Arc<dyn SecretsRepository>> {
+        Ok(Arc::new(SecretsSqlxDatabase::new(
+            self.make_vault_database(named_vault).await?,
+        )))
+    }
+
+    async fn get_signing_secret(
+        secrets: &Arc<dyn SecretsRepository>,
+        handle: &SigningSecretKeyHandle,
+    ) -> Result<BundleSigningSecret> {
+        match secrets.get_signing_secret(handle).await? {
+            Some(secret) => Ok(secret.into()),
+            None => Err(Self::missing_secret())?,
+        }
+    }
+
+    async fn get_x25519_secret(
+        secrets: &Arc<dyn SecretsRepository>,
+        handle: &X25519SecretKeyHandle,
+    ) -> Result<ByteArray<KEY_LEN>> {
+        match secrets.get_x25519_secret(handle).await? {
+            Some(secret) => Ok(ByteArray::from(*secret.key())),
+            None => Err(Self::missing_secret())?,
+        }
+    }
+
+    fn missing_secret() -> Error {
+        Error::new(
+            Origin::Api,
+            Kind::NotFound,
+            "a secret of the identity is missing from its vault",
+        )
+    }
+}
+
+/// Content of an identity bundle, before encryption
+#[derive(Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+struct IdentityBundle {
+    #[n(1)] change_history: ChangeHistory,
+    #[n(2)] signing_secrets: Vec<BundleSigningSecret>,
+    #[n(3)] x25519_secrets: Vec<ByteArray<KEY_LEN>>,
+    #[n(4)] purpose_keys: Vec<PurposeKeyAttestation>,
+    #[n(5)] credentials: Vec<BundleCredential>,
+}
+
+/// Signing secret contained in an identity bundle
+#[derive(Encode, Decode, CborLen)]
+#[rustfmt::skip]
+enum BundleSigningSecret {
+    #[n(0)] EdDSACurve25519(#[n(0)] ByteArray<KEY_LEN>),
+    #[n(1)] ECDSASHA256CurveP256(#[n(0)] ByteArray<KEY_LEN>),
+}
+
+impl From<SigningSecret> for BundleSigningSecret {
+    fn from(secret: SigningSecret) -> Self {
+        let key = ByteArray::from(*secret.key());
+        match secret {
+            SigningSecret::EdDSACurve25519(_) => BundleSigningSecret::EdDSACurve25519(key),
+            SigningSecret::ECDSASHA256CurveP256(_) => {
+                BundleSigningSecret::ECDSASHA256CurveP256(key)
+            }
+        }
+    }
+}
+
+impl From<BundleSigningSecret> for SigningSecret {
+    fn from(secret: BundleSigningSecret) -> Self {
+        match secret {
+            BundleSigningSecret::EdDSACurve25519(key) => {
+                SigningSecret::EdDSACurve25519(EdDSACurve25519SecretKey::new(*key))
+            }
+            BundleSigningSecret::ECDSASHA256CurveP256(key) => {
+                SigningSecret::ECDSASHA256CurveP256(ECDSASHA256CurveP256SecretKey::new(*key))
+            }
+        }
+    }
+}
+
+/// Credential cached by a node, contained in an identity bundle
+#[derive(Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+struct BundleCredential {
+    #[n(1)] node_name: String,
+    #[n(2)] scope: String,
+    #[n(3)] credential: CredentialAndPurposeKey,
+}
+
+/// Content of an identity bundle, once its change history, its secrets and its purpose keys
+/// have been verified, ready to be stored
+struct VerifiedIdentityBundle {
+    identifier: Identifier,
+    change_history: ChangeHistory,
+    signing_secrets: Vec<(SigningSecretKeyHandle, SigningSecret)>,
+    x25519_secrets: Vec<(X25519SecretKeyHandle, X25519SecretKey)>,
+    purpose_keys: Vec<(Purpose, PurposeKeyAttestation)>,
+    credentials: Vec<VerifiedCredential>,
+}
+
+/// Cached credential of an identity bundle, with the values indexing it in the database
+struct VerifiedCredential {
+    node_name: String,
+    issuer: Identifier,
+    scope: String,
+    expires_at: TimestampInSeconds,
+    credential: CredentialAndPurposeKey,
+}
+
+impl VerifiedIdentityBundle {
+    /// Verify an identity bundle by importing it in a scratch in-memory database:
+    ///
+    ///  - the change history must be valid
+    ///  - the bundle must contain the secret of the identity key
+    ///  - the purpose keys must be attested by the identity and have their secrets in the bundle
+    ///
+    async fn verify(bundle: IdentityBundle) -> Result<Self> {
+        let database = SqlxDatabase::in_memory("identity-bundle").await?;
+        let secrets: Arc<dyn SecretsRepository> =
+            Arc::new(SecretsSqlxDatabase::new(database.clone()));
+        let vault = Vault::create_with_secrets_repository(secrets.clone());
+        let identities = Identities::create(database)
+            .with_vault(vault.clone())
+            .build();
+
+        let signing_vault = SoftwareVaultForSigning::new(secrets.clone());
+        let mut signing_secrets = vec![];
+        for secret in bundle.signing_secrets {
+            let secret = SigningSecret::from(secret);
+            let handle = signing_vault.import_key(secret.clone()).await?;
+            signing_secrets.push((handle, secret));
+        }
+        let secure_channel_vault = SoftwareVaultForSecureChannels::new(secrets);
+        let mut x25519_secrets = vec![];
+        for secret in bundle.x25519_secrets {
+            let secret = X25519SecretKey::new(*secret);
+            let handle = secure_channel_vault
+                .import_static_x25519_secret(secret.clone())
+                .await?;
+            x25519_secrets.push((handle, secret));
+        }
+
+        let identity = Identity::import_from_change_history(
+            None,
+            bundle.change_history.clone(),
+            vault.verifying_vault.clone(),
+        )
+        .await?;
+        let identifier = identity.identifier().clone();
+        identities
+            .identities_verification()
+            .import_from_change_history(Some(&identifier), bundle.change_history.clone())
+            .await?;
+        // check that the bundle contains the secret of the identity key
+        let identity_key = vault
+            .identity_vault
+            .get_secret_key_handle(&identity.get_latest_public_key()?)
+            .await?;
+        if !signing_secrets.iter().any(|(h, _)| h == &identity_key) {
+            return Err(CliState::missing_secret())?;
+        }
+
+        let purpose_keys_verification = identities.purpose_keys().purpose_keys_verification();
+        let mut purpose_keys = vec![];
+        for attestation in bundle.purpose_keys {
+            let data = purpose_keys_verification
+                .verify_purpose_key_attestation(Some(&identifier), &attestation)
+                .await?;
+            let purpose = match data.public_key {
+                PurposePublicKey::SecureChannelStatic(public_key) => {
+                    let handle = vault
+                        .secure_channel_vault
+                        .get_x25519_secret_key_handle(&public_key)
+                        .await?;
+                    if !x25519_secrets.iter().any(|(h, _)| h == &handle) {
+                        return Err(CliState::missing_secret())?;
+                    }
+                    Purpose::SecureChannel
+                }
+                PurposePublicKey::CredentialSigning(public_key) => {
+                    let handle = vault
+                        .credential_vault
+                        .get_secret_key_handle(&public_key.into())
+                        .await?;
+                    if !signing_secrets.iter().any(|(h, _)| h == &handle) {
+                        return Err(CliState::missing_secret())?;
+                    }
+                    Purpose::Credentials
+                }
+            };
+            purpose_keys.push((purpose, attestation));
+        }
+
+        let mut credentials = vec![];
+        for bundle_credential in bundle.credentials {
+            let credential = bundle_credential.credential;
+            credentials.push(VerifiedCredential {
+                node_name: bundle_credential.node_name,
+                issuer: credential
+                    .purpose_key_attestation
+                    .get_attestation_data()?
+                    .subject,
+                scope: bundle_credential.scope,
+                expires_at: credential.get_expires_at()?,
+                credential,
+            });
+        }
+
+        Ok(Self {
+            identifier,
+            change_history: bundle.change_history,
+            signing_secrets,
+            x25519_secrets,
+            purpose_keys,
+            credentials,
+        })
+    }
+
+    async fn store_secrets(&self, transaction: &mut Transaction<'_, Any>) -> Result<()> {
+        for (handle, secret) in &self.signing_secrets {
+            SecretsSqlxDatabase::store_signing_secret_query(handle, secret.clone())
+                .execute(&mut **transaction)
+                .await
+                .void()?;
+        }
+        for (handle, secret) in &self.x25519_secrets {
+            SecretsSqlxDatabase::store_x25519_secret_query(handle, secret.clone())
+                .execute(&mut **transaction)
+                .await
+                .void()?;
+        }
+        Ok(())
+    }
+
+    async fn store_identity(&self, transaction: &mut Transaction<'_, Any>) -> Result<()> {
+        ChangeHistorySqlxDatabase::insert_query(&self.identifier, &self.change_history)
+            .execute(&mut **transaction)
+            .await
+            .void()?;
+        for (purpose, attestation) in &self.purpose_keys {
+            PurposeKeysSqlxDatabase::set_purpose_key_query(&self.identifier, *purpose, attestation)
+                .execute(&mut **transaction)
+                .await
+                .void()?;
+        }
+        for credential in &self.credentials {
+            CredentialSqlxDatabase::put_query(
+                &self.identifier,
+                &credential.issuer,
+                &credential.scope,
+                credential.expires_at,
+                credential.credential.clone(),
+                &credential.node_name,
+            )
+            .execute(&mut **transaction)
+            .await
+            .void()?;
+        }
+        Ok(())
+    }
+}
+
+/// Identity bundle encrypted with a passphrase.
+/// The Argon2id parameters used to derive the encryption key are stored with the bundle
+#[derive(Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+struct EncryptedIdentityBundle {
+    #[n(1)] version: u8,
+    #[n(2)] memory_cost: u32,
+    #[n(3)] time_cost: u32,
+    #[n(4)] parallelism: u32,
+    #[n(5)] salt: ByteArray<SALT_LEN>,
+    #[n(6)] nonce: ByteArray<NONCE_LEN>,
+    #[cbor(with = "minicbor::bytes")]
+    #[n(7)] ciphertext: Vec<u8>,
+}
+
+impl EncryptedIdentityBundle {
+    fn encrypt(bundle: &IdentityBundle, passphrase: &str) -> Result<Self> {
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let mut encrypted_bundle = Self {
+            version: IDENTITY_BUNDLE_VERSION,
+            memory_cost: Params::DEFAULT_M_COST,
+            time_cost: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+            salt: salt.into(),
+            nonce: nonce.into(),
+            ciphertext: vec![],
+        };
+        let plaintext = Zeroizing::new(ockam_core::cbor_encode_preallocate(bundle)?);
+        let key = encrypted_bundle.key(passphrase)?;
+        let header = encrypted_bundle.header();
+        encrypted_bundle.ciphertext = Aes256Gcm::new(key.as_slice().into())
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext.as_slice(),
+                    aad: &header,
+                },
+            )
+            .map_err(|_| Self::error("cannot encrypt the identity bundle"))?;
+        Ok(encrypted_bundle)
+    }
+
+    fn decrypt(&self, passphrase: &str) -> Result<IdentityBundle> {
+        let key = self.key(passphrase)?;
+        let plaintext = Zeroizing::new(
+            Aes256Gcm::new(key.as_slice().into())
+                .decrypt(
+                    Nonce::from_slice(self.nonce.as_slice()),
+                    Payload {
+                        msg: self.ciphertext.as_slice(),
+                        aad: &self.header(),
+                    },
+                )
+                .map_err(|_| {
+                    Self::error("cannot decrypt the identity bundle, please check the passphrase")
+                })?,
+        );
+        Ok(minicbor::decode(&plaintext).map_err(Error::from)?)
+    }
+
+    /// All the fields preceding the ciphertext. They are authenticated with the ciphertext
+    /// so that they can't be modified, for example to weaken the key derivation
+    fn header(&self) -> Vec<u8> {
+        let mut header = vec![self.version];
+        header.extend_from_slice(&self.memory_cost.to_be_bytes());
+        header.extend_from_slice(&self.time_cost.to_be_bytes());
+        header.extend_from_slice(&self.parallelism.to_be_bytes());
+        header.extend_from_slice(self.salt.as_slice());
+        header.extend_from_slice(self.nonce.as_slice());
+        header
+    }
+
+    /// Derive the encryption key from the passphrase
+    fn key(&self, passphrase: &str) -> Result<Zeroizing<[u8; KEY_LEN]>> {
+        let params = Params::new(
+            self.memory_cost,
+            self.time_cost,
+            self.parallelism,
+            Some(KEY_LEN),
+        )
+        .map_err(|e| Self::error(format!("invalid key derivation parameters: {e}")))?;
+        let mut key = Zeroizing::new([0u8; KEY_LEN]);
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), self.salt.as_slice(), key.as_mut())
+            .map_err(|e| Self::error(format!("cannot derive the bundle key: {e}")))?;
+        Ok(key)
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        Ok(ockam_core::cbor_encode_preallocate(self)?)
+    }
+
+    fn decode(bytes: &[u8]) -> Result<Self> {
+        let encrypted_bundle: Self = minicbor::decode(bytes).map_err(Error::from)?;
+        if encrypted_bundle.version != IDENTITY_BUNDLE_VERSION {
+            return Err(Self::error(format!(
+                "unsupported identity bundle version {}",
+                encrypted_bundle.version
+            )))?;
+        }
+        // the key derivation parameters come from the bundle: they are bounded so that
+        // importing a bundle can't exhaust the memory or the CPU
+        if encrypted_bundle.memory_cost > MAX_MEMORY_COST
+            || encrypted_bundle.time_cost > MAX_TIME_COST
+            || encrypted_bundle.parallelism > MAX_PARALLELISM
+        {
+            return Err(Self::error(format!(
+                "the key derivation parameters of the identity bundle are too high: memory cost {}, time cost {}, parallelism {}",
+                encrypted_bundle.memory_cost, encrypted_bundle.time_cost, encrypted_bundle.parallelism
+            )))?;
+        }
+        Ok(encrypted_bundle)
+    }
+
+    fn error(message: impl Into<String>) -> Error {
+        Error::new(Origin::Api, Kind::Invalid, message.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam::identity::utils::AttributesBuilder;
+    use std::time::Duration;
+
+    use crate::authenticator::credential_issuer::PROJECT_MEMBER_SCHEMA;
+
+    const PASSPHRASE: &str = "correct horse battery staple";
+
+    #[tokio::test]
+    async fn test_export_import_identity_with_secrets() -> Result<()> {
+        let cli = CliState::test().await?;
+        let identity = cli.create_identity_with_name("alice").await?;
+        let identities = cli
+            .make_identities(
+                cli.make_vault(cli.get_named_vault(&identity.vault_name()).await?)
+                    .await?,
+            )
+            .await?;
+        let purpose_keys = identities.purpose_keys().purpose_keys_creation();
+        let secure_channel_key = purpose_keys
+            .get_or_create_secure_channel_purpose_key(&identity.identifier())
+            .await?;
+        purpose_keys
+            .get_or_create_credential_purpose_key(&identity.identifier())
+            .await?;
+
+        let bundle = cli
+            .export_identity_with_secrets("alice", PASSPHRASE)
+            .await?;
+
+        let exported_identity = cli.get_identity(&identity.identifier()).await?;
+
+        // a wrong passphrase is rejected
+        let other = CliState::test().await?;
+        let result = other
+            .import_identity_with_secrets(&bundle, "wrong passphrase", "alice", &None)
+            .await;
+        assert!(result.is_err());
+
+        // the identity can be imported on another machine and keeps its keys
+        let imported = other
+            .import_identity_with_secrets(&bundle, PASSPHRASE, "alice", &None)
+            .await?;
+        assert_eq!(imported.identifier(), identity.identifier());
+        assert_eq!(
+            other.get_identity(&imported.identifier()).await?,
+            exported_identity
+        );
+
+        let other_identities = other
+            .make_identities(
+                other
+                    .make_vault(other.get_named_vault(&imported.vault_name()).await?)
+                    .await?,
+            )
+            .await?;
+        let imported_key = other_identities
+            .purpose_keys()
+            .purpose_keys_creation()
+            .get_secure_channel_purpose_key(&imported.identifier())
+            .await?;
+        assert_eq!(imported_key.public_key(), secure_channel_key.public_key());
+        other_identities
+            .purpose_keys()
+            .purpose_keys_creation()
+            .get_credential_purpose_key(&imported.identifier())
+            .await?;
+
+        // the imported identity can still sign
+        let credential = other_identities
+            .credentials()
+            .credentials_creation()
+            .issue_credential(
+                &imported.identifier(),
+                &imported.identifier(),
+                AttributesBuilder::with_schema(PROJECT_MEMBER_SCHEMA).build(),
+                Duration::from_secs(60),
+            )
+            .await;
+        assert!(credential.is_ok());
+
+        // the same identity cannot be imported twice
+        let result = other
+            .import_identity_with_secrets(&bundle, PASSPHRASE, "alice2", &None)
+            .await;
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tampered_bundle_is_rejected() -> Result<()> {
+        let cli = CliState::test().await?;
+        cli.create_identity_with_name("alice").await?;
+        let bundle = cli
+            .export_identity_with_secrets("alice", PASSPHRASE)
+            .await?;
+
+        let mut encrypted_bundle = EncryptedIdentityBundle::decode(&bundle)?;
+        let last = encrypted_bundle.ciphertext.len() - 1;
+        encrypted_bundle.ciphertext[last] ^= 1;
+        assert!(encrypted_bundle.decrypt(PASSPHRASE).is_err());
+
+        // the header is authenticated as well
+        let mut encrypted_bundle = EncryptedIdentityBundle::decode(&bundle)?;
+        encrypted_bundle.time_cost += 1;
+        assert!(encrypted_bundle.decrypt(PASSPHRASE).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_excessive_key_derivation_parameters_are_rejected() -> Result<()> {
+        let cli = CliState::test().await?;
+        cli.create_identity_with_name("alice").await?;
+        let bundle = cli
+            .export_identity_with_secrets("alice", PASSPHRASE)
+            .await?;
+
+        let mut encrypted_bundle = EncryptedIdentityBundle::decode(&bundle)?;
+        encrypted_bundle.memory_cost = MAX_MEMORY_COST + 1;
+        assert!(EncryptedIdentityBundle::decode(&encrypted_bundle.encode()?).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nothing_is_stored_when_the_bundle_is_invalid() -> Result<()> {
+        let cli = CliState::test().await?;
+        let identity = cli.create_identity_with_name("alice").await?;
+        let bundle = cli
+            .export_identity_with_secrets("alice", PASSPHRASE)
+            .await?;
+
+        // remove the secret of the identity key from the bundle
+        let mut content = EncryptedIdentityBundle::decode(&bundle)?.decrypt(PASSPHRASE)?;
+        content.signing_secrets.clear();
+        let bundle = EncryptedIdentityBundle::encrypt(&content, PASSPHRASE)?.encode()?;
+
+        let other = CliState::test().await?;
+        let result = other
+            .import_identity_with_secrets(&bundle, PASSPHRASE, "alice", &None)
+            .await;
+        assert!(result.is_err());
+        assert!(other.get_named_identity("alice").await.is_err());
+        assert!(other
+            .change_history_repository()
+            .get_change_history(&identity.identifier())
+            .await?
+            .is_none());
+        Ok(())
+    }
+}
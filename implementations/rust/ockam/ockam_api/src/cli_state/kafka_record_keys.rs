@@ -0,0 +1,47 @@
+use super::Result;
+use crate::CliState;
+use rand::RngCore;
+
+/// Length of the secret from which the Kafka inlets derive the secrets encrypting the records keys
+const RECORD_KEYS_SECRET_LEN: usize = 32;
+
+impl CliState {
+    /// Return the secret used by the Kafka inlets of a node to encrypt the records keys.
+    /// A random secret is created the first time and then persisted, so that a given
+    /// record key is encrypted to the same value after a restart of the node.
+    #[instrument(skip_all)]
+    pub async fn get_or_create_kafka_record_keys_secret(&self, node_name: &str) -> Result<Vec<u8>> {
+        let repository = self.kafka_record_keys_repository();
+        if let Some(secret) = repository.get_record_keys_secret(node_name).await? {
+            return Ok(secret);
+        };
+
+        let mut secret = vec![0u8; RECORD_KEYS_SECRET_LEN];
+        rand::thread_rng().fill_bytes(&mut secret);
+        Ok(repository
+            .store_record_keys_secret(node_name, &secret)
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_or_create_kafka_record_keys_secret() -> Result<()> {
+        let cli = CliState::test().await?;
+
+        let secret = cli.get_or_create_kafka_record_keys_secret("node1").await?;
+        assert_eq!(secret.len(), RECORD_KEYS_SECRET_LEN);
+        assert_eq!(
+            cli.get_or_create_kafka_record_keys_secret("node1").await?,
+            secret
+        );
+        assert_ne!(
+            cli.get_or_create_kafka_record_keys_secret("node2").await?,
+            secret
+        );
+        Ok(())
+    }
+}
@@ -12,7 +12,9 @@ pub mod enrollments;
 pub mod error;
 pub mod identities;
 mod identities_attributes;
+mod identity_bundles;
 pub mod journeys;
+mod kafka_record_keys;
 pub mod nodes;
 pub mod policies;
 pub mod projects;
@@ -443,10 +443,7 @@ impl CliState {
 
     /// Return the nodes using a given identity
     #[instrument(skip_all, fields(identity_name = identity_name))]
-    pub(super) async fn get_nodes_by_identity_name(
-        &self,
-        identity_name: &str,
-    ) -> Result<Vec<NodeInfo>> {
+    pub async fn get_nodes_by_identity_name(&self, identity_name: &str) -> Result<Vec<NodeInfo>> {
         let identifier = self.get_identifier_by_name(identity_name).await?;
         Ok(self
             .nodes_repository()
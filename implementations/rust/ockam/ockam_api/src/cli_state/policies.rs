@@ -1,15 +1,78 @@
 use crate::cli_state::CliState;
-use ockam_abac::{Policies, ResourcePolicySqlxDatabase, ResourceTypePolicySqlxDatabase};
+use ockam_abac::{
+    JsonlPolicyAuditSink, OpenTelemetryPolicyAuditSink, Policies, PolicyAudit, PolicyAuditSink,
+    PolicyDecisionSqlxDatabase, PolicyDecisionsRepository, ResourcePolicySqlxDatabase,
+    ResourceTypePolicySqlxDatabase,
+};
+use ockam_core::env::get_env_with_default;
+use std::path::PathBuf;
 use std::sync::Arc;
+use tracing::warn;
+
+/// Comma-separated list of the sinks receiving the policy decisions of a node:
+/// `sql`, `jsonl`, `opentelemetry`. The policy decisions are not audited when it is not set.
+pub const OCKAM_POLICY_AUDIT: &str = "OCKAM_POLICY_AUDIT";
+
+/// If true, the decisions granting access are audited, in addition to the denials.
+pub const OCKAM_POLICY_AUDIT_ALLOWED: &str = "OCKAM_POLICY_AUDIT_ALLOWED";
 
 impl CliState {
     pub fn policies(&self, node_name: &str) -> Policies {
-        Policies::new(
+        let policies = Policies::new(
             Arc::new(ResourcePolicySqlxDatabase::new(self.database(), node_name)),
             Arc::new(ResourceTypePolicySqlxDatabase::new(
                 self.database(),
                 node_name,
             )),
-        )
+        );
+        match self.policy_audit(node_name) {
+            Some(audit) => policies.with_audit(audit),
+            None => policies,
+        }
+    }
+
+    /// Return the repository storing the policy decisions of a node
+    pub fn policy_decisions(&self, node_name: &str) -> Arc<dyn PolicyDecisionsRepository> {
+        Arc::new(PolicyDecisionSqlxDatabase::new(self.database(), node_name))
+    }
+
+    /// Return the path of the file where the policy decisions of a node are written
+    /// when the `jsonl` sink is enabled
+    pub fn policy_audit_path(&self, node_name: &str) -> PathBuf {
+        self.node_dir(node_name).join("policy_audit.jsonl")
+    }
+
+    /// Create the audit of policy decisions configured with the `OCKAM_POLICY_AUDIT` and
+    /// `OCKAM_POLICY_AUDIT_ALLOWED` environment variables.
+    /// The audit is opt-in since the decisions are stored without any retention limit.
+    fn policy_audit(&self, node_name: &str) -> Option<PolicyAudit> {
+        let sink_names: Vec<String> = get_env_with_default(OCKAM_POLICY_AUDIT, vec![])
+            .unwrap_or_else(|e| {
+                warn!(%e, "invalid policy audit configuration, the policy decisions are not audited");
+                vec![]
+            });
+
+        let mut sinks: Vec<Arc<dyn PolicyAuditSink>> = vec![];
+        for name in sink_names.iter().map(|n| n.trim()) {
+            match name {
+                "sql" => sinks.push(Arc::new(PolicyDecisionSqlxDatabase::new(
+                    self.database(),
+                    node_name,
+                ))),
+                "jsonl" => sinks.push(Arc::new(JsonlPolicyAuditSink::new(
+                    self.policy_audit_path(node_name),
+                ))),
+                "opentelemetry" | "otel" => sinks.push(Arc::new(OpenTelemetryPolicyAuditSink)),
+                "none" | "" => {}
+                other => warn!("unknown policy audit sink '{other}'"),
+            }
+        }
+        if sinks.is_empty() {
+            return None;
+        }
+
+        let record_allowed =
+            get_env_with_default(OCKAM_POLICY_AUDIT_ALLOWED, false).unwrap_or(false);
+        Some(PolicyAudit::new(sinks).with_allowed_decisions(record_allowed))
     }
 }
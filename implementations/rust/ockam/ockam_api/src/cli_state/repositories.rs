@@ -121,6 +121,19 @@ impl CliState {
         }
     }
 
+    pub(super) fn kafka_record_keys_repository(&self) -> Arc<dyn KafkaRecordKeysRepository> {
+        let database = self.database();
+        match database.configuration {
+            DatabaseConfiguration::SqlitePersistent { .. }
+            | DatabaseConfiguration::SqliteInMemory { .. } => Arc::new(AutoRetry::new(
+                KafkaRecordKeysSqlxDatabase::new(self.database()),
+            )),
+            DatabaseConfiguration::Postgres { .. } => {
+                Arc::new(KafkaRecordKeysSqlxDatabase::new(self.database()))
+            }
+        }
+    }
+
     pub(super) fn projects_repository(&self) -> Arc<dyn ProjectsRepository> {
         let database = self.database();
         match database.configuration {
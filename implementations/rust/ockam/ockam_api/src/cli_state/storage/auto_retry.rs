@@ -1,8 +1,9 @@
 use crate::cli_state::journeys::{Journey, ProjectJourney};
 use crate::cli_state::{
     EnrollmentsRepository, IdentitiesRepository, IdentityEnrollment, JourneysRepository,
-    NamedIdentity, NamedVault, NodeInfo, NodesRepository, ProjectsRepository, SpacesRepository,
-    TcpInlet, TcpPortalsRepository, UsersRepository, VaultType, VaultsRepository,
+    KafkaRecordKeysRepository, NamedIdentity, NamedVault, NodeInfo, NodesRepository,
+    ProjectsRepository, SpacesRepository, TcpInlet, TcpPortalsRepository, UsersRepository,
+    VaultType, VaultsRepository,
 };
 use crate::cloud::email_address::EmailAddress;
 use crate::cloud::enroll::auth0::UserInfo;
@@ -387,6 +388,21 @@ impl<T: TcpPortalsRepository> TcpPortalsRepository for AutoRetry<T> {
     }
 }
 
+#[async_trait]
+impl<T: KafkaRecordKeysRepository> KafkaRecordKeysRepository for AutoRetry<T> {
+    async fn store_record_keys_secret(
+        &self,
+        node_name: &str,
+        secret: &[u8],
+    ) -> ockam_core::Result<Vec<u8>> {
+        retry!(self.wrapped.store_record_keys_secret(node_name, secret))
+    }
+
+    async fn get_record_keys_secret(&self, node_name: &str) -> ockam_core::Result<Option<Vec<u8>>> {
+        retry!(self.wrapped.get_record_keys_secret(node_name))
+    }
+}
+
 #[async_trait]
 impl<T: UsersRepository> UsersRepository for AutoRetry<T> {
     async fn store_user(&self, user: &UserInfo) -> ockam_core::Result<()> {
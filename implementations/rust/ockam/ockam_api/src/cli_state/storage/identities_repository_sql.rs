@@ -1,5 +1,7 @@
 use core::str::FromStr;
 
+use sqlx::any::AnyArguments;
+use sqlx::query::{Query, QueryScalar};
 use sqlx::*;
 
 use ockam::identity::Identifier;
@@ -27,6 +29,31 @@ impl IdentitiesSqlxDatabase {
     pub async fn create() -> Result<Self> {
         Ok(Self::new(SqlxDatabase::in_memory("identities").await?))
     }
+
+    /// Return the query checking if there is a default identity, to execute it in a transaction
+    pub fn default_identity_exists_query<'a>() -> QueryScalar<'a, Any, Boolean, AnyArguments<'a>> {
+        query_scalar("SELECT EXISTS(SELECT 1 FROM named_identity WHERE is_default = $1)").bind(true)
+    }
+
+    /// Return the query storing a named identity, to execute it in a transaction
+    pub fn store_named_identity_query<'a>(
+        identifier: &'a Identifier,
+        name: &'a str,
+        vault_name: &'a str,
+        is_default: bool,
+    ) -> Query<'a, Any, AnyArguments<'a>> {
+        query(
+            r#"
+        INSERT INTO named_identity (identifier, name, vault_name, is_default)
+        VALUES ($1, $2, $3, $4)
+        ON CONFLICT (identifier)
+        DO UPDATE SET name = $2, vault_name = $3, is_default = $4"#,
+        )
+        .bind(identifier)
+        .bind(name)
+        .bind(vault_name)
+        .bind(is_default)
+    }
 }
 
 #[async_trait]
@@ -47,18 +74,10 @@ impl IdentitiesRepository for IdentitiesSqlxDatabase {
         let is_already_default: Boolean = query1.fetch_one(&mut *transaction).await.into_core()?;
         let is_already_default = is_already_default.to_bool();
 
-        let query2 = query(
-            r#"
-        INSERT INTO named_identity (identifier, name, vault_name, is_default)
-        VALUES ($1, $2, $3, $4)
-        ON CONFLICT (identifier)
-        DO UPDATE SET name = $2, vault_name = $3, is_default = $4"#,
-        )
-        .bind(identifier)
-        .bind(name)
-        .bind(vault_name)
-        .bind(is_already_default);
-        query2.execute(&mut *transaction).await.void()?;
+        Self::store_named_identity_query(identifier, name, vault_name, is_already_default)
+            .execute(&mut *transaction)
+            .await
+            .void()?;
 
         transaction.commit().await.void()?;
 
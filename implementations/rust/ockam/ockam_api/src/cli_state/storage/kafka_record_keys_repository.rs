@@ -0,0 +1,14 @@
+use ockam_core::async_trait;
+use ockam_core::Result;
+
+/// The KafkaRecordKeysRepository is responsible for accessing the secret used by the
+/// Kafka inlets of a node to encrypt the records keys
+#[async_trait]
+pub trait KafkaRecordKeysRepository: Send + Sync + 'static {
+    /// Store the records keys secret of a node if it doesn't have one yet,
+    /// and return the secret which is stored for that node
+    async fn store_record_keys_secret(&self, node_name: &str, secret: &[u8]) -> Result<Vec<u8>>;
+
+    /// Return the records keys secret of a node
+    async fn get_record_keys_secret(&self, node_name: &str) -> Result<Option<Vec<u8>>>;
+}
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use sqlx::*;
+use tracing::debug;
+
+use crate::cli_state::storage::kafka_record_keys_repository::KafkaRecordKeysRepository;
+use ockam::{FromSqlxError, SqlxDatabase, ToVoid};
+use ockam_core::async_trait;
+use ockam_core::Result;
+
+#[derive(Clone)]
+pub struct KafkaRecordKeysSqlxDatabase {
+    database: SqlxDatabase,
+}
+
+impl KafkaRecordKeysSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for kafka record keys");
+        Self { database }
+    }
+
+    /// Create a new in-memory database
+    #[allow(unused)]
+    pub async fn create() -> Result<Arc<Self>> {
+        Ok(Arc::new(Self::new(
+            SqlxDatabase::in_memory("kafka record keys").await?,
+        )))
+    }
+}
+
+#[async_trait]
+impl KafkaRecordKeysRepository for KafkaRecordKeysSqlxDatabase {
+    async fn store_record_keys_secret(&self, node_name: &str, secret: &[u8]) -> Result<Vec<u8>> {
+        let mut transaction = self.database.begin().await.into_core()?;
+
+        // a secret which is already stored is kept, so that concurrent inlets use the same one
+        let query = query(
+            r#"
+            INSERT INTO kafka_record_keys_secret (node_name, secret)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING"#,
+        )
+        .bind(node_name)
+        .bind(secret);
+        query.execute(&mut *transaction).await.void()?;
+
+        let query = query_as("SELECT secret FROM kafka_record_keys_secret WHERE node_name = $1")
+            .bind(node_name);
+        let row: RecordKeysSecretRow = query.fetch_one(&mut *transaction).await.into_core()?;
+        transaction.commit().await.void()?;
+        Ok(row.secret)
+    }
+
+    async fn get_record_keys_secret(&self, node_name: &str) -> Result<Option<Vec<u8>>> {
+        let query = query_as("SELECT secret FROM kafka_record_keys_secret WHERE node_name = $1")
+            .bind(node_name);
+        let row: Option<RecordKeysSecretRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        Ok(row.map(|r| r.secret))
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct RecordKeysSecretRow {
+    secret: Vec<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_node::database::with_dbs;
+
+    #[tokio::test]
+    async fn test_repository() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn KafkaRecordKeysRepository> =
+                Arc::new(KafkaRecordKeysSqlxDatabase::new(db));
+
+            let actual = repository.get_record_keys_secret("node_name").await?;
+            assert_eq!(actual, None);
+
+            let actual = repository
+                .store_record_keys_secret("node_name", &[1; 32])
+                .await?;
+            assert_eq!(actual, vec![1; 32]);
+
+            // the first secret is kept
+            let actual = repository
+                .store_record_keys_secret("node_name", &[2; 32])
+                .await?;
+            assert_eq!(actual, vec![1; 32]);
+            let actual = repository.get_record_keys_secret("node_name").await?;
+            assert_eq!(actual, Some(vec![1; 32]));
+
+            let actual = repository.get_record_keys_secret("other_node").await?;
+            assert_eq!(actual, None);
+
+            Ok(())
+        })
+        .await
+    }
+}
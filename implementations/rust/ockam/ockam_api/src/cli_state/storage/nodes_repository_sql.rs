@@ -148,6 +148,10 @@ impl NodesRepository for NodesSqlxDatabase {
             sqlx::query("DELETE FROM tcp_outlet_status WHERE node_name = $1").bind(node_name);
         query.execute(&mut *transaction).await.void()?;
 
+        let query = sqlx::query("DELETE FROM kafka_record_keys_secret WHERE node_name = $1")
+            .bind(node_name);
+        query.execute(&mut *transaction).await.void()?;
+
         let query = sqlx::query("DELETE FROM node_project WHERE node_name = $1").bind(node_name);
         query.execute(&mut *transaction).await.void()?;
 
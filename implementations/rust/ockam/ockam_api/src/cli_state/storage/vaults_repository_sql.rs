@@ -7,7 +7,7 @@ use ockam_core::async_trait;
 use ockam_core::Result;
 use ockam_node::database::{Boolean, Nullable};
 
-use crate::cli_state::{NamedVault, UseAwsKms, VaultType, VaultsRepository};
+use crate::cli_state::{NamedVault, UseAwsKms, UsePkcs11, VaultType, VaultsRepository};
 
 #[derive(Clone)]
 pub struct VaultsSqlxDatabase {
@@ -39,15 +39,17 @@ impl VaultsRepository for VaultsSqlxDatabase {
         let query = query(
             r#"
         INSERT INTO
-            vault (name, path, is_default, is_kms)
-            VALUES ($1, $2, $3, $4)
+            vault (name, path, is_default, is_kms, pkcs11_module, pkcs11_slot)
+            VALUES ($1, $2, $3, $4, $5, $6)
             ON CONFLICT (name)
-            DO UPDATE SET path = $2, is_default = $3, is_kms = $4"#,
+            DO UPDATE SET path = $2, is_default = $3, is_kms = $4, pkcs11_module = $5, pkcs11_slot = $6"#,
         )
         .bind(name)
         .bind(vault_type.path().map(|p| p.to_string_lossy().to_string()))
         .bind(!default_exists)
-        .bind(vault_type.use_aws_kms());
+        .bind(vault_type.use_aws_kms())
+        .bind(pkcs11_module(&vault_type))
+        .bind(pkcs11_slot(&vault_type));
         query.execute(&mut *transaction).await.void()?;
 
         transaction.commit().await.void()?;
@@ -55,10 +57,14 @@ impl VaultsRepository for VaultsSqlxDatabase {
     }
 
     async fn update_vault(&self, name: &str, vault_type: VaultType) -> Result<()> {
-        let query = query("UPDATE vault SET path = $1, is_kms = $2 WHERE name = $3")
-            .bind(vault_type.path().map(|p| p.to_string_lossy().to_string()))
-            .bind(vault_type.use_aws_kms())
-            .bind(name);
+        let query = query(
+            "UPDATE vault SET path = $1, is_kms = $2, pkcs11_module = $3, pkcs11_slot = $4 WHERE name = $5",
+        )
+        .bind(vault_type.path().map(|p| p.to_string_lossy().to_string()))
+        .bind(vault_type.use_aws_kms())
+        .bind(pkcs11_module(&vault_type))
+        .bind(pkcs11_slot(&vault_type))
+        .bind(name);
         query.execute(&*self.database.pool).await.void()
     }
 
@@ -68,7 +74,9 @@ impl VaultsRepository for VaultsSqlxDatabase {
     }
 
     async fn get_database_vault(&self) -> Result<Option<NamedVault>> {
-        let query = query_as("SELECT name, path, is_default, is_kms FROM vault WHERE path is NULL");
+        let query = query_as(
+            "SELECT name, path, is_default, is_kms, pkcs11_module, pkcs11_slot FROM vault WHERE path is NULL",
+        );
         let row: Option<VaultRow> = query
             .fetch_optional(&*self.database.pool)
             .await
@@ -77,8 +85,10 @@ impl VaultsRepository for VaultsSqlxDatabase {
     }
 
     async fn get_named_vault(&self, name: &str) -> Result<Option<NamedVault>> {
-        let query =
-            query_as("SELECT name, path, is_default, is_kms FROM vault WHERE name = $1").bind(name);
+        let query = query_as(
+            "SELECT name, path, is_default, is_kms, pkcs11_module, pkcs11_slot FROM vault WHERE name = $1",
+        )
+        .bind(name);
         let row: Option<VaultRow> = query
             .fetch_optional(&*self.database.pool)
             .await
@@ -87,7 +97,9 @@ impl VaultsRepository for VaultsSqlxDatabase {
     }
 
     async fn get_named_vaults(&self) -> Result<Vec<NamedVault>> {
-        let query = query_as("SELECT name, path, is_default, is_kms FROM vault");
+        let query = query_as(
+            "SELECT name, path, is_default, is_kms, pkcs11_module, pkcs11_slot FROM vault",
+        );
         let rows: Vec<VaultRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
         rows.iter().map(|r| r.named_vault()).collect()
     }
@@ -95,12 +107,24 @@ impl VaultsRepository for VaultsSqlxDatabase {
 
 // Database serialization / deserialization
 
+fn pkcs11_module(vault_type: &VaultType) -> Option<String> {
+    vault_type
+        .pkcs11()
+        .map(|p| p.module().to_string_lossy().to_string())
+}
+
+fn pkcs11_slot(vault_type: &VaultType) -> Option<i64> {
+    vault_type.pkcs11().map(|p| p.slot() as i64)
+}
+
 #[derive(FromRow)]
 pub(crate) struct VaultRow {
     name: String,
     path: Nullable<String>,
     is_default: Boolean,
     is_kms: Boolean,
+    pkcs11_module: Nullable<String>,
+    pkcs11_slot: Nullable<i64>,
 }
 
 impl VaultRow {
@@ -113,12 +137,20 @@ impl VaultRow {
     }
 
     pub(crate) fn vault_type(&self) -> VaultType {
-        match self.path.to_option() {
+        let vault_type = match self.path.to_option() {
             None => VaultType::database(UseAwsKms::from(self.is_kms.to_bool())),
             Some(p) => VaultType::local_file(
                 PathBuf::from(p).as_path(),
                 UseAwsKms::from(self.is_kms.to_bool()),
             ),
+        };
+        vault_type.with_pkcs11(self.pkcs11())
+    }
+
+    pub(crate) fn pkcs11(&self) -> Option<UsePkcs11> {
+        match (self.pkcs11_module.to_option(), self.pkcs11_slot.to_option()) {
+            (Some(module), Some(slot)) => Some(UsePkcs11::new(module, slot as u64)),
+            _ => None,
         }
     }
 
@@ -187,4 +219,30 @@ mod test {
         })
         .await
     }
+
+    #[tokio::test]
+    async fn test_store_pkcs11_vault() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn VaultsRepository> = Arc::new(VaultsSqlxDatabase::new(db));
+
+            // It is possible to create a vault storing its signing keys in a PKCS#11 token
+            let pkcs11 = UsePkcs11::new("/usr/lib/softhsm/libsofthsm2.so", 1234);
+            let vault_type = VaultType::local_file("path", UseAwsKms::No).with_pkcs11(Some(pkcs11));
+            let hsm = repository.store_vault("hsm", vault_type.clone()).await?;
+            let expected = NamedVault::new("hsm", vault_type.clone(), true);
+            assert_eq!(hsm, expected);
+
+            let result = repository.get_named_vault("hsm").await?;
+            assert_eq!(result, Some(expected));
+
+            // The PKCS#11 configuration is kept when the vault is moved
+            let vault_type = VaultType::local_file("path2", UseAwsKms::No)
+                .with_pkcs11(vault_type.pkcs11().cloned());
+            repository.update_vault("hsm", vault_type.clone()).await?;
+            let result = repository.get_named_vault("hsm").await?;
+            assert_eq!(result, Some(NamedVault::new("hsm", vault_type, true)));
+            Ok(())
+        })
+        .await
+    }
 }
@@ -6,9 +6,12 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use ockam::identity::{Identities, Vault};
+use ockam_core::env::get_env;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_node::database::SqlxDatabase;
+use ockam_vault::SigningKeyType;
 use ockam_vault_aws::AwsSigningVault;
+use ockam_vault_pkcs11::{Pkcs11Config, Pkcs11SigningVault};
 
 use crate::cli_state::{random_name, CliState, CliStateError, Result};
 use crate::colors::color_primary;
@@ -17,9 +20,13 @@ use crate::{fmt_log, fmt_ok};
 
 static DEFAULT_VAULT_NAME: &str = "default";
 
+/// Name of the environment variable containing the user PIN of a PKCS#11 token.
+/// The PIN is never stored in the database.
+pub const OCKAM_PKCS11_PIN: &str = "OCKAM_PKCS11_PIN";
+
 /// The methods below support the creation and update of local vaults
 ///
-///  - by default private keys are stored locally but they can also be stored in a KMS
+///  - by default private keys are stored locally but they can also be stored in a KMS or in a PKCS#11 token
 ///  - keys stored locally are stored with other application data in the local database if the default vault is used
 ///  - any additional vault stores its keys in a separate file
 ///
@@ -35,44 +42,20 @@ impl CliState {
         path: Option<PathBuf>,
         use_aws_kms: UseAwsKms,
     ) -> Result<NamedVault> {
-        let vaults_repository = self.vaults_repository();
-
-        // determine the vault name to use if not given by the user
-        let vault_name = match vault_name {
-            Some(vault_name) => vault_name.clone(),
-            None => self.make_vault_name().await?,
-        };
-
-        // verify that a vault with that name does not exist
-        if vaults_repository
-            .get_named_vault(&vault_name)
-            .await?
-            .is_some()
-        {
-            return Err(CliStateError::AlreadyExists {
-                resource: "vault".to_string(),
-                name: vault_name.to_string(),
-            });
-        }
+        self.create_vault(vault_name, path, use_aws_kms, None).await
+    }
 
-        // Determine if the vault needs to be created at a specific path
-        // or if data can be stored in the main database directly
-        match path {
-            None => match self.vaults_repository().get_database_vault().await? {
-                None => Ok(vaults_repository
-                    .store_vault(&vault_name, VaultType::database(use_aws_kms))
-                    .await?),
-                Some(_) => {
-                    let path = self.make_vault_path(&vault_name);
-                    Ok(self
-                        .create_local_vault(vault_name, &path, use_aws_kms)
-                        .await?)
-                }
-            },
-            Some(path) => Ok(self
-                .create_local_vault(vault_name, &path, use_aws_kms)
-                .await?),
-        }
+    /// Create a vault with a given name, storing its signing keys in a PKCS#11 token
+    /// The path follows the same rules as for the `create_named_vault` method
+    #[instrument(skip_all, fields(vault_name = vault_name.clone()))]
+    pub async fn create_named_pkcs11_vault(
+        &self,
+        vault_name: Option<String>,
+        path: Option<PathBuf>,
+        pkcs11: UsePkcs11,
+    ) -> Result<NamedVault> {
+        self.create_vault(vault_name, path, UseAwsKms::No, Some(pkcs11))
+            .await
     }
 
     /// Delete an existing vault
@@ -199,6 +182,7 @@ impl CliState {
                     vault_name.to_string(),
                     &self.make_vault_path(vault_name),
                     UseAwsKms::No,
+                    None,
                 )
                 .await?;
             self.notify_message(fmt_ok!(
@@ -268,12 +252,16 @@ impl CliState {
             VaultType::LocalFileVault {
                 path: old_path,
                 use_aws_kms,
+                pkcs11,
             } => {
                 // copy the file to the new location
                 std::fs::copy(&old_path, path)?;
                 // update the path in the database
                 repository
-                    .update_vault(vault_name, VaultType::local_file(path, use_aws_kms))
+                    .update_vault(
+                        vault_name,
+                        VaultType::local_file(path, use_aws_kms).with_pkcs11(pkcs11),
+                    )
                     .await?;
                 // remove the old file
                 std::fs::remove_file(old_path)?;
@@ -285,14 +273,7 @@ impl CliState {
     /// Make a concrete vault based on the NamedVault metadata
     #[instrument(skip_all, fields(vault_name = named_vault.name))]
     pub async fn make_vault(&self, named_vault: NamedVault) -> Result<Vault> {
-        let db = match named_vault.vault_type {
-            VaultType::DatabaseVault { .. } => self.database(),
-            VaultType::LocalFileVault { ref path, .. } =>
-            // TODO: Avoid creating multiple dbs with the same file
-            {
-                SqlxDatabase::create_sqlite(path.as_path()).await?
-            }
-        };
+        let db = self.make_vault_database(&named_vault).await?;
 
         if named_vault.vault_type.use_aws_kms() {
             let mut vault = Vault::create_with_database(db);
@@ -300,6 +281,16 @@ impl CliState {
             vault.identity_vault = aws_vault.clone();
             vault.credential_vault = aws_vault;
             Ok(vault)
+        } else if let Some(pkcs11) = named_vault.vault_type.pkcs11() {
+            let mut config = Pkcs11Config::new(pkcs11.module(), pkcs11.slot());
+            if let Some(pin) = get_env::<String>(OCKAM_PKCS11_PIN)? {
+                config = config.with_pin(pin);
+            }
+            let mut vault = Vault::create_with_database(db);
+            let pkcs11_vault = Arc::new(Pkcs11SigningVault::create_with_config(config).await?);
+            vault.identity_vault = pkcs11_vault.clone();
+            vault.credential_vault = pkcs11_vault;
+            Ok(vault)
         } else {
             Ok(Vault::create_with_database(db))
         }
@@ -308,6 +299,21 @@ impl CliState {
 
 /// Builder functions
 impl CliState {
+    /// Return the database where the secrets of a vault are stored
+    pub(crate) async fn make_vault_database(
+        &self,
+        named_vault: &NamedVault,
+    ) -> Result<SqlxDatabase> {
+        Ok(match named_vault.vault_type {
+            VaultType::DatabaseVault { .. } => self.database(),
+            VaultType::LocalFileVault { ref path, .. } =>
+            // TODO: Avoid creating multiple dbs with the same file
+            {
+                SqlxDatabase::create_sqlite(path.as_path()).await?
+            }
+        })
+    }
+
     /// Return an Identities struct using a specific Vault
     pub async fn make_identities(&self, vault: Vault) -> Result<Arc<Identities>> {
         Ok(Identities::create(self.database())
@@ -318,6 +324,57 @@ impl CliState {
 
 /// Private functions
 impl CliState {
+    /// Create a vault, storing its signing keys either locally, in an AWS KMS or in a PKCS#11 token
+    async fn create_vault(
+        &self,
+        vault_name: Option<String>,
+        path: Option<PathBuf>,
+        use_aws_kms: UseAwsKms,
+        pkcs11: Option<UsePkcs11>,
+    ) -> Result<NamedVault> {
+        let vaults_repository = self.vaults_repository();
+
+        // determine the vault name to use if not given by the user
+        let vault_name = match vault_name {
+            Some(vault_name) => vault_name.clone(),
+            None => self.make_vault_name().await?,
+        };
+
+        // verify that a vault with that name does not exist
+        if vaults_repository
+            .get_named_vault(&vault_name)
+            .await?
+            .is_some()
+        {
+            return Err(CliStateError::AlreadyExists {
+                resource: "vault".to_string(),
+                name: vault_name.to_string(),
+            });
+        }
+
+        // Determine if the vault needs to be created at a specific path
+        // or if data can be stored in the main database directly
+        match path {
+            None => match self.vaults_repository().get_database_vault().await? {
+                None => Ok(vaults_repository
+                    .store_vault(
+                        &vault_name,
+                        VaultType::database(use_aws_kms).with_pkcs11(pkcs11),
+                    )
+                    .await?),
+                Some(_) => {
+                    let path = self.make_vault_path(&vault_name);
+                    Ok(self
+                        .create_local_vault(vault_name, &path, use_aws_kms, pkcs11)
+                        .await?)
+                }
+            },
+            Some(path) => Ok(self
+                .create_local_vault(vault_name, &path, use_aws_kms, pkcs11)
+                .await?),
+        }
+    }
+
     /// Create the database vault if it doesn't exist already
     async fn create_database_vault(
         &self,
@@ -342,6 +399,7 @@ impl CliState {
         vault_name: String,
         path: &PathBuf,
         use_aws_kms: UseAwsKms,
+        pkcs11: Option<UsePkcs11>,
     ) -> Result<NamedVault> {
         // check if the new file can be created
         let path_taken = self
@@ -365,7 +423,10 @@ impl CliState {
         };
         Ok(self
             .vaults_repository()
-            .store_vault(&vault_name, VaultType::local_file(path, use_aws_kms))
+            .store_vault(
+                &vault_name,
+                VaultType::local_file(path, use_aws_kms).with_pkcs11(pkcs11),
+            )
             .await?)
     }
 
@@ -402,10 +463,12 @@ pub struct NamedVault {
 pub enum VaultType {
     DatabaseVault {
         use_aws_kms: UseAwsKms,
+        pkcs11: Option<UsePkcs11>,
     },
     LocalFileVault {
         path: PathBuf,
         use_aws_kms: UseAwsKms,
+        pkcs11: Option<UsePkcs11>,
     },
 }
 
@@ -422,6 +485,9 @@ impl Display for VaultType {
         if self.use_aws_kms() {
             writeln!(f, "Uses AWS KMS: true",)?;
         }
+        if let Some(pkcs11) = self.pkcs11() {
+            writeln!(f, "Uses PKCS#11: {pkcs11}")?;
+        }
         Ok(())
     }
 }
@@ -442,15 +508,68 @@ impl UseAwsKms {
     }
 }
 
+/// Location of the signing keys of a vault stored in a PKCS#11 token
+#[derive(Debug, PartialEq, Eq, Clone, serde::Serialize, serde::Deserialize)]
+pub struct UsePkcs11 {
+    module: PathBuf,
+    slot: u64,
+}
+
+impl UsePkcs11 {
+    pub fn new(module: impl Into<PathBuf>, slot: u64) -> Self {
+        Self {
+            module: module.into(),
+            slot,
+        }
+    }
+
+    /// Path to the PKCS#11 module (shared library)
+    pub fn module(&self) -> PathBuf {
+        self.module.clone()
+    }
+
+    /// Id of the slot holding the token
+    pub fn slot(&self) -> u64 {
+        self.slot
+    }
+}
+
+impl Display for UsePkcs11 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "module {}, slot {}", self.module.display(), self.slot)
+    }
+}
+
 impl VaultType {
     pub fn database(use_aws_kms: UseAwsKms) -> Self {
-        VaultType::DatabaseVault { use_aws_kms }
+        VaultType::DatabaseVault {
+            use_aws_kms,
+            pkcs11: None,
+        }
     }
 
     pub fn local_file(path: impl Into<PathBuf>, use_aws_kms: UseAwsKms) -> Self {
         VaultType::LocalFileVault {
             path: path.into(),
             use_aws_kms,
+            pkcs11: None,
+        }
+    }
+
+    /// Store the signing keys of this vault in a PKCS#11 token
+    pub fn with_pkcs11(self, pkcs11: Option<UsePkcs11>) -> Self {
+        match self {
+            VaultType::DatabaseVault { use_aws_kms, .. } => VaultType::DatabaseVault {
+                use_aws_kms,
+                pkcs11,
+            },
+            VaultType::LocalFileVault {
+                path, use_aws_kms, ..
+            } => VaultType::LocalFileVault {
+                path,
+                use_aws_kms,
+                pkcs11,
+            },
         }
     }
 
@@ -463,11 +582,15 @@ impl VaultType {
 
     pub fn use_aws_kms(&self) -> bool {
         match self {
-            VaultType::DatabaseVault { use_aws_kms } => use_aws_kms == &UseAwsKms::Yes,
-            VaultType::LocalFileVault {
-                path: _,
-                use_aws_kms,
-            } => use_aws_kms == &UseAwsKms::Yes,
+            VaultType::DatabaseVault { use_aws_kms, .. } => use_aws_kms == &UseAwsKms::Yes,
+            VaultType::LocalFileVault { use_aws_kms, .. } => use_aws_kms == &UseAwsKms::Yes,
+        }
+    }
+
+    pub fn pkcs11(&self) -> Option<&UsePkcs11> {
+        match self {
+            VaultType::DatabaseVault { pkcs11, .. } => pkcs11.as_ref(),
+            VaultType::LocalFileVault { pkcs11, .. } => pkcs11.as_ref(),
         }
     }
 }
@@ -502,6 +625,21 @@ impl NamedVault {
         self.vault_type.use_aws_kms()
     }
 
+    /// Return true if a PKCS#11 token is used to store signing keys
+    pub fn use_pkcs11(&self) -> bool {
+        self.vault_type.pkcs11().is_some()
+    }
+
+    /// Return the type of the signing keys created in this vault.
+    /// An AWS KMS or a PKCS#11 token only supports ECDSA P-256 keys
+    pub fn signing_key_type(&self) -> SigningKeyType {
+        if self.use_aws_kms() || self.use_pkcs11() {
+            SigningKeyType::ECDSASHA256CurveP256
+        } else {
+            SigningKeyType::EdDSACurve25519
+        }
+    }
+
     /// Return the vault path if the vault data is stored in a local file
     pub fn path(&self) -> Option<&Path> {
         self.vault_type.path()
@@ -538,6 +676,9 @@ impl Output for NamedVault {
         if self.vault_type.use_aws_kms() {
             writeln!(output, "Uses AWS KMS: true",)?;
         }
+        if let Some(pkcs11) = self.vault_type.pkcs11() {
+            writeln!(output, "Uses PKCS#11: {pkcs11}")?;
+        }
         Ok(output)
     }
 }
@@ -752,6 +893,40 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_create_pkcs11_vault() -> Result<()> {
+        let cli = CliState::test().await?;
+        let pkcs11 = UsePkcs11::new("/usr/lib/softhsm/libsofthsm2.so", 0);
+
+        let result = cli
+            .create_named_pkcs11_vault(Some("hsm".to_string()), None, pkcs11.clone())
+            .await?;
+        assert!(result.use_pkcs11());
+        assert!(!result.use_aws_kms());
+        assert_eq!(
+            result.vault_type(),
+            VaultType::database(UseAwsKms::No).with_pkcs11(Some(pkcs11))
+        );
+
+        // the PKCS#11 configuration is persisted
+        let result = cli.get_named_vault("hsm").await?;
+        assert!(result.use_pkcs11());
+
+        // the token only supports ECDSA P-256 keys
+        assert_eq!(
+            result.signing_key_type(),
+            SigningKeyType::ECDSASHA256CurveP256
+        );
+        assert_eq!(
+            cli.create_named_vault(Some("local".to_string()), None, UseAwsKms::No)
+                .await?
+                .signing_key_type(),
+            SigningKeyType::EdDSACurve25519
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_delete_vault() -> Result<()> {
         let cli = CliState::test().await?;
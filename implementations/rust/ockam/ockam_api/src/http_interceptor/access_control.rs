@@ -0,0 +1,256 @@
+use crate::ApiError;
+use minicbor::{CborLen, Decode, Encode};
+use ockam::identity::{Identifier, IdentitiesAttributes};
+use ockam_abac::expr::str;
+use ockam_abac::{Abac, Env, PolicyExpression};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Environment key containing the method of the HTTP request being authorized
+pub const HTTP_METHOD_KEY: &str = "http.method";
+
+/// Environment key containing the path of the HTTP request being authorized, without its query
+pub const HTTP_PATH_KEY: &str = "http.path";
+
+/// Policy applied to the HTTP requests matching a method and a path
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct HttpAccessRule {
+    /// Method of the requests, `None` for all methods
+    #[n(1)] method: Option<String>,
+    /// Path of the requests, a trailing `*` matches all the paths with the same prefix
+    #[n(2)] path: String,
+    #[n(3)] expression: PolicyExpression,
+}
+
+impl HttpAccessRule {
+    pub fn new(
+        method: Option<String>,
+        path: impl Into<String>,
+        expression: PolicyExpression,
+    ) -> Self {
+        Self {
+            method: method.map(|m| m.to_uppercase()),
+            path: path.into(),
+            expression,
+        }
+    }
+
+    pub fn method(&self) -> Option<&str> {
+        self.method.as_deref()
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn expression(&self) -> &PolicyExpression {
+        &self.expression
+    }
+
+    /// Return true if this rule applies to a request.
+    /// The path must be canonical, see [`canonical_path`], and must not contain the query part
+    /// of the request target
+    pub fn matches(&self, method: &str, path: &str) -> bool {
+        let method_matches = self
+            .method
+            .as_ref()
+            .map_or(true, |m| m.eq_ignore_ascii_case(method));
+        let path_matches = match self.path.strip_suffix('*') {
+            Some(prefix) => path.starts_with(prefix),
+            None => path == self.path,
+        };
+        method_matches && path_matches
+    }
+}
+
+/// Return the percent-decoded path of a request if it is canonical, `None` otherwise.
+///
+/// A server may resolve `//`, `.` and `..` segments, or encoded separators, differently from
+/// the rules matching the path as a plain string. Such paths are rejected so that a request
+/// can't reach a resource by getting around the rule protecting it.
+pub fn canonical_path(path: &str) -> Option<String> {
+    let path = path.strip_prefix('/')?;
+    let mut decoded = Vec::with_capacity(path.len() + 1);
+    decoded.push(b'/');
+    let mut bytes = path.bytes();
+    while let Some(b) = bytes.next() {
+        let b = if b == b'%' {
+            let high = (bytes.next()? as char).to_digit(16)?;
+            let low = (bytes.next()? as char).to_digit(16)?;
+            match (high * 16 + low) as u8 {
+                // an encoded separator or escape would be a different path for the server
+                b'/' | b'%' => return None,
+                b => b,
+            }
+        } else {
+            b
+        };
+        if b == b'\\' || b == 0 {
+            return None;
+        }
+        decoded.push(b);
+    }
+    let decoded = String::from_utf8(decoded).ok()?;
+
+    // only the last segment can be empty, for a path with a trailing slash
+    let mut segments = decoded[1..].split('/').peekable();
+    while let Some(segment) = segments.next() {
+        let is_last = segments.peek().is_none();
+        if segment == "." || segment == ".." || (segment.is_empty() && !is_last) {
+            return None;
+        }
+    }
+    Some(decoded)
+}
+
+/// Parse a rule from `<method> <path> <expression>`, for example `GET /api/* (= subject.role "reader")`.
+/// The method `*` matches all methods.
+impl FromStr for HttpAccessRule {
+    type Err = ApiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            ApiError::message(format!(
+                "invalid HTTP access rule '{s}', expected <method> <path> <expression>"
+            ))
+        };
+        let mut parts = s.trim().splitn(3, char::is_whitespace);
+        let method = parts.next().filter(|m| !m.is_empty()).ok_or_else(invalid)?;
+        let path = parts
+            .next()
+            .filter(|p| p.starts_with('/'))
+            .ok_or_else(invalid)?;
+        let expression = parts.next().map(|e| e.trim()).ok_or_else(invalid)?;
+        let expression = PolicyExpression::from_str(expression).map_err(|e| {
+            ApiError::message(format!(
+                "invalid expression in the HTTP access rule '{s}': {e}"
+            ))
+        })?;
+        let method = (method != "*").then(|| method.to_string());
+        Ok(Self::new(method, path, expression))
+    }
+}
+
+/// Authorize each HTTP request sent through a portal.
+///
+/// The first rule matching the request method and path is evaluated against the attributes
+/// of the identity which opened the portal connection. The request method and path are
+/// also available to the expression as `http.method` and `http.path`.
+/// Requests not matching any rule are denied.
+#[derive(Clone)]
+pub struct HttpAccessControl {
+    rules: Vec<HttpAccessRule>,
+    identities_attributes: Arc<IdentitiesAttributes>,
+    authority: Option<Identifier>,
+    environment: Env,
+}
+
+impl HttpAccessControl {
+    pub fn new(
+        rules: Vec<HttpAccessRule>,
+        identities_attributes: Arc<IdentitiesAttributes>,
+        authority: Option<Identifier>,
+        environment: Env,
+    ) -> Self {
+        Self {
+            rules,
+            identities_attributes,
+            authority,
+            environment,
+        }
+    }
+
+    /// Return true if the identity is allowed to send a request with the given method and path.
+    /// Requests with a non-canonical path are denied.
+    pub(crate) async fn is_authorized(
+        &self,
+        identifier: Option<&Identifier>,
+        method: &str,
+        path: &str,
+    ) -> ockam_core::Result<bool> {
+        let path = path.split_once('?').map_or(path, |(path, _query)| path);
+        let Some(path) = canonical_path(path) else {
+            debug!(%method, %path, "the request path is not canonical");
+            return Ok(false);
+        };
+        let Some(rule) = self.rules.iter().find(|r| r.matches(method, &path)) else {
+            debug!(%method, %path, "no HTTP access rule matches the request");
+            return Ok(false);
+        };
+        let Some(identifier) = identifier else {
+            debug!(%method, %path, "the portal connection has no identity");
+            return Ok(false);
+        };
+
+        let mut environment = self.environment.clone();
+        environment.put(HTTP_METHOD_KEY, str(method.to_uppercase()));
+        environment.put(HTTP_PATH_KEY, str(path));
+        Abac::new(
+            self.identities_attributes.clone(),
+            self.authority.clone(),
+            environment,
+        )
+        .is_identity_authorized(identifier, &rule.expression.to_expression())
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_http_access_rule() {
+        let rule = HttpAccessRule::from_str("get /api/* (= subject.role \"reader\")").unwrap();
+        assert_eq!(rule.method(), Some("GET"));
+        assert_eq!(rule.path(), "/api/*");
+        assert_eq!(
+            rule.expression(),
+            &PolicyExpression::from_str("(= subject.role \"reader\")").unwrap()
+        );
+
+        let rule = HttpAccessRule::from_str("* /health admin").unwrap();
+        assert_eq!(rule.method(), None);
+
+        assert!(HttpAccessRule::from_str("GET").is_err());
+        assert!(HttpAccessRule::from_str("GET api admin").is_err());
+        assert!(HttpAccessRule::from_str("GET /api").is_err());
+    }
+
+    #[test]
+    fn match_requests() {
+        let expression = PolicyExpression::from_str("admin").unwrap();
+        let rule = HttpAccessRule::new(Some("post".into()), "/api/*", expression.clone());
+        assert!(rule.matches("POST", "/api/write"));
+        assert!(!rule.matches("GET", "/api/write"));
+        assert!(!rule.matches("POST", "/health"));
+
+        let rule = HttpAccessRule::new(None, "/health", expression);
+        assert!(rule.matches("GET", "/health"));
+        assert!(rule.matches("HEAD", "/health"));
+        assert!(!rule.matches("GET", "/health/details"));
+    }
+
+    #[test]
+    fn canonical_paths() {
+        assert_eq!(canonical_path("/"), Some("/".into()));
+        assert_eq!(canonical_path("/api/write"), Some("/api/write".into()));
+        assert_eq!(canonical_path("/api/"), Some("/api/".into()));
+        assert_eq!(canonical_path("/%61pi/a%20b"), Some("/api/a b".into()));
+
+        assert_eq!(canonical_path("api"), None);
+        assert_eq!(canonical_path("//admin"), None);
+        assert_eq!(canonical_path("/api//write"), None);
+        assert_eq!(canonical_path("/api/../admin"), None);
+        assert_eq!(canonical_path("/api/%2e%2e/admin"), None);
+        assert_eq!(canonical_path("/api/./write"), None);
+        assert_eq!(canonical_path("/api%2fwrite"), None);
+        assert_eq!(canonical_path("/api%252fwrite"), None);
+        assert_eq!(canonical_path("/api\\..\\admin"), None);
+        assert_eq!(canonical_path("/api%00"), None);
+        assert_eq!(canonical_path("/api%2"), None);
+        assert_eq!(canonical_path("/api%zz"), None);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::http_interceptor::HttpAccessRule;
+use crate::ApiError;
+use minicbor::{CborLen, Decode, Encode};
+use std::str::FromStr;
+
+/// HTTP header added to the intercepted requests
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct HttpHeader {
+    #[n(1)] name: String,
+    #[n(2)] value: String,
+}
+
+impl HttpHeader {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+/// Parse a header from `<name>:<value>`, for example `X-Forwarded-Proto: https`
+impl FromStr for HttpHeader {
+    type Err = ApiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = s.split_once(':').ok_or_else(|| {
+            ApiError::message(format!(
+                "invalid HTTP header '{s}', expected <name>:<value>"
+            ))
+        })?;
+        let name = name.trim();
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            return Err(ApiError::message(format!(
+                "invalid HTTP header name in '{s}'"
+            )));
+        }
+        Ok(Self::new(name, value.trim()))
+    }
+}
+
+/// Configuration of a TCP outlet intercepting the HTTP/1.1 requests sent to its server
+#[derive(Debug, Clone, Default, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct HttpOutletConfig {
+    /// Headers added to each request, replacing the headers with the same name
+    #[n(1)] pub add_headers: Vec<HttpHeader>,
+    /// Names of the headers removed from each request
+    #[n(2)] pub strip_headers: Vec<String>,
+    /// Token sent as an `Authorization: Bearer` header with each request
+    #[n(3)] pub bearer_token: Option<String>,
+    /// Rules authorizing the requests per method and path.
+    /// If empty, all the requests reaching the outlet are accepted
+    #[n(4)] pub access_rules: Vec<HttpAccessRule>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_http_header() {
+        let header = HttpHeader::from_str("X-Forwarded-Proto: https").unwrap();
+        assert_eq!(header.name(), "X-Forwarded-Proto");
+        assert_eq!(header.value(), "https");
+
+        let header = HttpHeader::from_str("X-Empty:").unwrap();
+        assert_eq!(header.value(), "");
+
+        assert!(HttpHeader::from_str("X-Forwarded-Proto").is_err());
+        assert!(HttpHeader::from_str(": https").is_err());
+        assert!(HttpHeader::from_str("X Forwarded: https").is_err());
+    }
+}
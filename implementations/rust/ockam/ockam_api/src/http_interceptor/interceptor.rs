@@ -0,0 +1,216 @@
+use crate::http_interceptor::parser::{HttpRequestHead, HttpSegment, RequestState};
+use crate::http_interceptor::{HttpAccessControl, HttpHeader, TokenProvider};
+use ockam::identity::{Identifier, IdentitySecureChannelLocalInfo};
+use ockam_core::{async_trait, LocalInfo};
+use ockam_node::Context;
+use ockam_transport_tcp::{
+    Direction, PortalInterceptor, PortalInterceptorFactory, PortalInterceptorRejection,
+};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Token attached to each request in an `Authorization: <scheme> <token>` header
+#[derive(Clone)]
+struct Authorization {
+    scheme: String,
+    token_provider: Arc<dyn TokenProvider>,
+}
+
+#[derive(Clone, Default)]
+struct HttpInterceptorSettings {
+    add_headers: Vec<HttpHeader>,
+    strip_headers: Vec<String>,
+    authorization: Option<Authorization>,
+    access_control: Option<HttpAccessControl>,
+}
+
+/// Create interceptors rewriting and authorizing the HTTP/1.1 requests sent through a portal.
+///
+/// The requests are parsed as they go through the portal, then for each request:
+///  - the access control, if any, is checked for the request method and path.
+///    A denied request is answered with a `403 Forbidden` response, and the portal
+///    connection is closed
+///  - headers are removed, then added
+///  - an authorization token is added
+///
+/// Responses are not modified.
+#[derive(Clone, Default)]
+pub struct HttpInterceptorFactory {
+    settings: Arc<HttpInterceptorSettings>,
+}
+
+impl HttpInterceptorFactory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add headers to each request, replacing the headers with the same name
+    pub fn with_added_headers(mut self, headers: Vec<HttpHeader>) -> Self {
+        Arc::make_mut(&mut self.settings).add_headers = headers;
+        self
+    }
+
+    /// Remove the headers with the given names from each request
+    pub fn with_stripped_headers(mut self, names: Vec<String>) -> Self {
+        Arc::make_mut(&mut self.settings).strip_headers = names;
+        self
+    }
+
+    /// Add an `Authorization: <scheme> <token>` header to each request,
+    /// for example with the `Bearer` scheme
+    pub fn with_token(
+        mut self,
+        scheme: impl Into<String>,
+        token_provider: Arc<dyn TokenProvider>,
+    ) -> Self {
+        Arc::make_mut(&mut self.settings).authorization = Some(Authorization {
+            scheme: scheme.into(),
+            token_provider,
+        });
+        self
+    }
+
+    /// Authorize each request with the given access control
+    pub fn with_access_control(mut self, access_control: HttpAccessControl) -> Self {
+        Arc::make_mut(&mut self.settings).access_control = Some(access_control);
+        self
+    }
+}
+
+impl PortalInterceptorFactory for HttpInterceptorFactory {
+    fn create(&self) -> Arc<dyn PortalInterceptor> {
+        self.create_for_connection(&[])
+    }
+
+    fn create_for_connection(&self, local_info: &[LocalInfo]) -> Arc<dyn PortalInterceptor> {
+        let identifier = IdentitySecureChannelLocalInfo::find_info_from_list(local_info)
+            .ok()
+            .map(|info| info.their_identity_id());
+        Arc::new(HttpInterceptor {
+            state: Default::default(),
+            settings: self.settings.clone(),
+            identifier,
+        })
+    }
+}
+
+struct HttpInterceptor {
+    state: Mutex<RequestState>,
+    settings: Arc<HttpInterceptorSettings>,
+    /// Identity which opened the portal connection, if it was received via a secure channel
+    identifier: Option<Identifier>,
+}
+
+impl HttpInterceptor {
+    async fn authorize(&self, head: &HttpRequestHead) -> ockam_core::Result<()> {
+        let Some(access_control) = &self.settings.access_control else {
+            return Ok(());
+        };
+        let allowed = access_control
+            .is_authorized(self.identifier.as_ref(), &head.method, &head.path)
+            .await
+            .map_err(|e| {
+                error!(
+                    "Cannot authorize the request {} {}: {e}",
+                    head.method, head.path
+                );
+                reject(
+                    500,
+                    "Internal Server Error",
+                    "the request can't be authorized",
+                )
+            })?;
+
+        // access record of the request
+        info!(
+            identifier = ?self.identifier.as_ref().map(|i| i.to_string()),
+            method = %head.method,
+            path = %head.path,
+            allowed,
+            "http request"
+        );
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(reject(
+                403,
+                "Forbidden",
+                &format!("the request {} {} is not allowed", head.method, head.path),
+            ))
+        }
+    }
+
+    async fn rewrite(&self, head: &mut HttpRequestHead) {
+        for name in &self.settings.strip_headers {
+            head.remove_header(name);
+        }
+        for header in &self.settings.add_headers {
+            head.append_header(header.name(), header.value());
+        }
+        if let Some(authorization) = &self.settings.authorization {
+            let token = authorization.token_provider.get_token().await;
+            if token.is_none() {
+                error!("No authorization token available");
+            }
+            head.set_header(
+                "Authorization",
+                format!("{} {}", authorization.scheme, token.unwrap_or_default()),
+            );
+        }
+    }
+}
+
+#[async_trait]
+impl PortalInterceptor for HttpInterceptor {
+    async fn intercept(
+        &self,
+        _context: &mut Context,
+        direction: Direction,
+        buffer: &[u8],
+    ) -> ockam_core::Result<Option<Vec<u8>>> {
+        match direction {
+            Direction::FromOutletToInlet => Ok(Some(buffer.to_vec())),
+
+            Direction::FromInletToOutlet => {
+                let mut state = self.state.lock().await;
+                let mut out = Vec::with_capacity(buffer.len());
+                let segments = state.process_http_buffer(buffer).map_err(|e| {
+                    warn!("Cannot parse an HTTP request: {e}");
+                    reject(
+                        400,
+                        "Bad Request",
+                        "the request is not a valid HTTP/1.1 request",
+                    )
+                })?;
+                for segment in segments {
+                    match segment {
+                        HttpSegment::Head(mut head) => {
+                            self.authorize(&head).await?;
+                            self.rewrite(&mut head).await;
+                            head.write_into(&mut out);
+                        }
+                        HttpSegment::Body(body) => out.extend_from_slice(&body),
+                    }
+                }
+                Ok(Some(out))
+            }
+        }
+    }
+}
+
+/// Return an error rejecting a request with an HTTP error response.
+/// The portal connection is closed after the response is sent
+fn reject(status: u16, reason: &str, message: &str) -> ockam_core::Error {
+    let body = format!("{message}\n");
+    let response = format!(
+        "HTTP/1.1 {status} {reason}\r\n\
+         Content-Type: text/plain\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    );
+    PortalInterceptorRejection::new(message)
+        .with_response(response.into_bytes())
+        .into()
+}
@@ -0,0 +1,14 @@
+//! Portal interceptor for HTTP/1.1 requests.
+//!
+//! Requests can be rewritten, with headers added or removed and an authorization
+//! token attached, and authorized per method and path with an ABAC policy.
+mod access_control;
+mod config;
+mod interceptor;
+mod parser;
+mod token_provider;
+
+pub use access_control::*;
+pub use config::*;
+pub use interceptor::*;
+pub use token_provider::*;
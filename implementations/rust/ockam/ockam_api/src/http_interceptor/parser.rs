@@ -1,127 +1,145 @@
 use std::io::Write;
 
-use httparse::{Header, Status};
-use ockam_core::async_trait;
-use ockam_node::Context;
-use ockam_transport_tcp::{Direction, PortalInterceptor, PortalInterceptorFactory};
-use std::sync::Arc;
-use tokio::sync::Mutex;
-
+use httparse::Status;
 use ockam::errcode::{Kind, Origin};
 
-use tracing::{debug, error};
-
-use super::token_lease_refresher::TokenLeaseRefresher;
-
+/// Head of an HTTP/1.1 request: request line and headers
 #[derive(Debug, Clone, PartialEq)]
-enum RequestState {
-    ParsingHeader(Option<Vec<u8>>),
-    ParsingChunkedHeader(Option<Vec<u8>>),
-    RemainingInChunk(usize),
-    RemainingBody(usize),
+pub(crate) struct HttpRequestHead {
+    pub(crate) method: String,
+    pub(crate) path: String,
+    pub(crate) version: u8,
+    pub(crate) headers: Vec<(String, Vec<u8>)>,
 }
 
-struct HttpAuthInterceptorState {
-    state: RequestState,
-}
-
-struct HttpAuthInterceptor {
-    state: Arc<Mutex<HttpAuthInterceptorState>>,
-    token_refresher: TokenLeaseRefresher,
-}
-
-impl HttpAuthInterceptor {
-    fn new(token_refresher: TokenLeaseRefresher) -> Self {
-        let state = HttpAuthInterceptorState {
-            state: RequestState::ParsingHeader(None),
-        };
+impl HttpRequestHead {
+    fn from_request(req: &httparse::Request) -> Self {
         Self {
-            state: Arc::new(Mutex::new(state)),
-            token_refresher,
+            method: req.method.unwrap_or_default().to_string(),
+            path: req.path.unwrap_or_default().to_string(),
+            version: req.version.unwrap_or(1),
+            headers: req
+                .headers
+                .iter()
+                .map(|h| (h.name.to_string(), h.value.to_vec()))
+                .collect(),
         }
     }
-}
 
-pub struct HttpAuthInterceptorFactory {
-    token_refresher: TokenLeaseRefresher,
-}
+    /// Return the value of the first header with the given name
+    pub(crate) fn header(&self, name: &str) -> Option<&[u8]> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_slice())
+    }
 
-impl HttpAuthInterceptorFactory {
-    pub fn new(token_refresher: TokenLeaseRefresher) -> Self {
-        Self { token_refresher }
+    /// Remove all the headers with the given name
+    pub(crate) fn remove_header(&mut self, name: &str) {
+        self.headers.retain(|(n, _)| !n.eq_ignore_ascii_case(name))
     }
-}
 
-impl PortalInterceptorFactory for HttpAuthInterceptorFactory {
-    fn create(&self) -> Arc<dyn PortalInterceptor> {
-        Arc::new(HttpAuthInterceptor::new(self.token_refresher.clone()))
+    /// Replace the headers with the given name by a single header, added after the request line
+    pub(crate) fn set_header(&mut self, name: &str, value: impl Into<Vec<u8>>) {
+        self.remove_header(name);
+        self.headers.insert(0, (name.to_string(), value.into()));
     }
-}
 
-fn attach_auth_token_and_serialize_into(
-    req: &httparse::Request,
-    token: &str,
-    buffer: &mut Vec<u8>,
-) {
-    debug!("Serializing http req header");
-    write!(
-        buffer,
-        "{} {} HTTP/1.{}\r\n",
-        req.method.unwrap(),
-        req.path.unwrap(),
-        req.version.unwrap()
-    )
-    .unwrap();
+    /// Replace the headers with the given name by a single header, added after the other headers
+    pub(crate) fn append_header(&mut self, name: &str, value: impl Into<Vec<u8>>) {
+        self.remove_header(name);
+        self.headers.push((name.to_string(), value.into()));
+    }
 
-    write!(buffer, "Authorization: Token {}\r\n", token).unwrap();
-    for h in &*req.headers {
-        if !h.name.eq_ignore_ascii_case("Authorization") {
-            write!(buffer, "{}: ", h.name).unwrap();
-            buffer.extend_from_slice(h.value);
+    pub(crate) fn write_into(&self, buffer: &mut Vec<u8>) {
+        debug!("Serializing http req header");
+        write!(
+            buffer,
+            "{} {} HTTP/1.{}\r\n",
+            self.method, self.path, self.version
+        )
+        .unwrap();
+        for (name, value) in &self.headers {
+            write!(buffer, "{}: ", name).unwrap();
+            buffer.extend_from_slice(value);
             buffer.extend_from_slice(b"\r\n");
         }
+        buffer.extend_from_slice(b"\r\n");
     }
-    buffer.extend_from_slice(b"\r\n");
-}
 
-fn body_state(method: &str, headers: &[Header]) -> ockam_core::Result<RequestState> {
-    match method.to_uppercase().as_str() {
-        "POST" | "PUT" => {
-            for h in headers {
-                if h.name.eq_ignore_ascii_case("Content-Length") {
-                    if let Ok(str) = std::str::from_utf8(h.value) {
-                        return str.parse().map(RequestState::RemainingBody).map_err(|e| {
-                            ockam_core::Error::new(Origin::Transport, Kind::Invalid, e)
-                        });
-                    }
-                } else if h.name.eq_ignore_ascii_case("Transfer-Encoding")
-                    && String::from_utf8(h.value.to_vec()).is_ok_and(|s| s.contains("chunked"))
-                {
-                    return Ok(RequestState::ParsingChunkedHeader(None));
+    /// Return the state expecting the body of this request
+    fn body_state(&self) -> ockam_core::Result<RequestState> {
+        if self
+            .header("Transfer-Encoding")
+            .and_then(|v| std::str::from_utf8(v).ok())
+            .is_some_and(|s| s.contains("chunked"))
+        {
+            return Ok(RequestState::ParsingChunkedHeader(None));
+        }
+        match self.header("Content-Length") {
+            Some(value) => {
+                let length = std::str::from_utf8(value)
+                    .map_err(|e| ockam_core::Error::new(Origin::Transport, Kind::Invalid, e))?
+                    .trim()
+                    .parse()
+                    .map_err(|e| ockam_core::Error::new(Origin::Transport, Kind::Invalid, e))?;
+                if length == 0 {
+                    Ok(RequestState::ParsingHeader(None))
+                } else {
+                    Ok(RequestState::RemainingBody(length))
                 }
             }
-            // Not content-length, no chunked encoding, fail.
-            Err(ockam_core::Error::new(
-                Origin::Transport,
-                Kind::Invalid,
-                "No Content-Length nor chunked Transfer-Encoding",
-            ))
+            // A request without Content-Length nor chunked Transfer-Encoding has no body
+            None => Ok(RequestState::ParsingHeader(None)),
         }
-        _ => Ok(RequestState::ParsingHeader(None)),
+    }
+}
+
+/// Part of an HTTP/1.1 stream of requests
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum HttpSegment {
+    /// The head of a new request
+    Head(HttpRequestHead),
+    /// Body bytes of the current request, chunk sizes included
+    Body(Vec<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum RequestState {
+    ParsingHeader(Option<Vec<u8>>),
+    ParsingChunkedHeader(Option<Vec<u8>>),
+    RemainingInChunk(usize),
+    RemainingBody(usize),
+}
+
+impl Default for RequestState {
+    fn default() -> Self {
+        RequestState::ParsingHeader(None)
+    }
+}
+
+fn push_body(segments: &mut Vec<HttpSegment>, bytes: &[u8]) {
+    if let Some(HttpSegment::Body(body)) = segments.last_mut() {
+        body.extend_from_slice(bytes);
+    } else {
+        segments.push(HttpSegment::Body(bytes.to_vec()));
     }
 }
 
 impl RequestState {
-    /* Parse the incoming data,  attaching an Authorization header token to it.
+    /* Parse the incoming data into request heads and body bytes.
      * data is received in chunks, and there is no warranty on what we get on each:
      * incomplete requests,  multiple requests, etc.
      */
-    fn process_http_buffer(&mut self, buf: &[u8], token: &str) -> ockam_core::Result<Vec<u8>> {
-        let mut acc = Vec::with_capacity(buf.len());
+    pub(crate) fn process_http_buffer(
+        &mut self,
+        buf: &[u8],
+    ) -> ockam_core::Result<Vec<HttpSegment>> {
+        let mut segments = vec![];
         let mut cursor = buf;
         loop {
             if cursor.is_empty() {
-                return Ok(acc);
+                return Ok(segments);
             }
             match self {
                 RequestState::ParsingHeader(prev) => {
@@ -138,16 +156,17 @@ impl RequestState {
                         Ok(httparse::Status::Partial) if prev_size == 0 => {
                             // No previous buffered, need to copy and own the unparsed data
                             *self = RequestState::ParsingHeader(Some(cursor.to_vec()));
-                            return Ok(acc);
+                            return Ok(segments);
                         }
                         Ok(httparse::Status::Partial) => {
                             // There was a previous buffer, and we already added the newly data to it
-                            return Ok(acc);
+                            return Ok(segments);
                         }
                         Ok(httparse::Status::Complete(body_offset)) => {
                             cursor = &cursor[body_offset - prev_size..];
-                            attach_auth_token_and_serialize_into(&req, token, &mut acc);
-                            *self = body_state(req.method.unwrap(), req.headers)?;
+                            let head = HttpRequestHead::from_request(&req);
+                            *self = head.body_state()?;
+                            segments.push(HttpSegment::Head(head));
                         }
                         Err(e) => {
                             error!("Error parsing header: {:?}", e);
@@ -161,13 +180,13 @@ impl RequestState {
                 }
                 RequestState::RemainingBody(remaining) => {
                     if *remaining <= cursor.len() {
-                        acc.extend_from_slice(&cursor[..*remaining]);
+                        push_body(&mut segments, &cursor[..*remaining]);
                         cursor = &cursor[*remaining..];
                         *self = RequestState::ParsingHeader(None);
                     } else {
-                        acc.extend_from_slice(cursor);
+                        push_body(&mut segments, cursor);
                         *remaining -= cursor.len();
-                        return Ok(acc);
+                        return Ok(segments);
                     }
                 }
                 RequestState::ParsingChunkedHeader(prev) => {
@@ -182,20 +201,20 @@ impl RequestState {
                         Ok(Status::Complete((2, 0))) => {
                             // this is just a final \r\n.  The spec said it should end in a 0-sized
                             // chunk.. but having seen this on the wild as well.
-                            acc.extend_from_slice(&to_parse[..2]);
+                            push_body(&mut segments, &to_parse[..2]);
                             cursor = &cursor[2 - prev_size..];
                             *self = RequestState::ParsingHeader(None);
                         }
                         Ok(Status::Complete((3, 0))) => {
                             // this is just a proper 0\r\n final chunk.
-                            acc.extend_from_slice(&to_parse[..3]);
+                            push_body(&mut segments, &to_parse[..3]);
                             cursor = &cursor[3 - prev_size..];
                             // There must be a final \r\n.  And no more chunks,
                             // so just reuse the RemainingBody state for this
                             *self = RequestState::RemainingBody(2);
                         }
                         Ok(Status::Complete((pos, chunk_size))) => {
-                            acc.extend_from_slice(&to_parse[..pos]);
+                            push_body(&mut segments, &to_parse[..pos]);
                             cursor = &cursor[pos - prev_size..];
                             let complete_size = chunk_size + 2; //chunks ends in \r\n
                             *self =
@@ -204,11 +223,11 @@ impl RequestState {
                         Ok(Status::Partial) if prev_size == 0 => {
                             // No previous buffered, need to copy and own the unparsed data
                             *self = RequestState::ParsingChunkedHeader(Some(cursor.to_vec()));
-                            return Ok(acc);
+                            return Ok(segments);
                         }
                         Ok(Status::Partial) => {
                             // There was a previous buffer, and we already added the newly data to it
-                            return Ok(acc);
+                            return Ok(segments);
                         }
                         Err(e) => {
                             error!("Error parsing chunk size: {:?}.  Buffer: {:?}", e, prev);
@@ -222,13 +241,13 @@ impl RequestState {
                 }
                 RequestState::RemainingInChunk(size) => {
                     if cursor.len() >= *size {
-                        acc.extend_from_slice(&cursor[..*size]);
+                        push_body(&mut segments, &cursor[..*size]);
                         cursor = &cursor[*size..];
                         *self = RequestState::ParsingChunkedHeader(None);
                     } else {
-                        acc.extend_from_slice(cursor);
+                        push_body(&mut segments, cursor);
                         *size -= cursor.len();
-                        return Ok(acc);
+                        return Ok(segments);
                     }
                 }
             }
@@ -236,32 +255,6 @@ impl RequestState {
     }
 }
 
-#[async_trait]
-impl PortalInterceptor for HttpAuthInterceptor {
-    async fn intercept(
-        &self,
-        _context: &mut Context,
-        direction: Direction,
-        buffer: &[u8],
-    ) -> ockam_core::Result<Option<Vec<u8>>> {
-        match direction {
-            Direction::FromOutletToInlet => ockam_core::Result::Ok(Some(buffer.to_vec())),
-
-            Direction::FromInletToOutlet => {
-                let mut guard = self.state.lock().await;
-                let token = self.token_refresher.get_token().await;
-                if token.is_none() {
-                    error!("No authorization token available");
-                }
-                let out = guard
-                    .state
-                    .process_http_buffer(buffer, &token.unwrap_or_default())?;
-                Ok(Some(out))
-            }
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -283,6 +276,21 @@ Accept-Encoding: gzip, deflate, br\r\n\
 Transfer-Encoding: gzip, chunked\r\n\r\n\
 4\r\nWiki\r\n7\r\npedia i\r\n0\r\n\r\n";
 
+    /// Attach an authorization token to each request and serialize the result
+    fn process_http_buffer(request_state: &mut RequestState, buf: &[u8], token: &str) -> Vec<u8> {
+        let mut acc = vec![];
+        for segment in request_state.process_http_buffer(buf).unwrap() {
+            match segment {
+                HttpSegment::Head(mut head) => {
+                    head.set_header("Authorization", format!("Token {token}"));
+                    head.write_into(&mut acc);
+                }
+                HttpSegment::Body(body) => acc.extend_from_slice(&body),
+            }
+        }
+        acc
+    }
+
     #[test]
     fn parse_post_with_chunked_transfers() {
         let mut data = Vec::new();
@@ -293,7 +301,7 @@ Transfer-Encoding: gzip, chunked\r\n\r\n\
             let mut result = Vec::new();
             let mut request_state = RequestState::ParsingHeader(None);
             for chunk in data.chunks(size) {
-                let data_out = request_state.process_http_buffer(chunk, TOKEN).unwrap();
+                let data_out = process_http_buffer(&mut request_state, chunk, TOKEN);
                 result.extend_from_slice(&data_out);
             }
             assert_eq!(
@@ -328,7 +336,7 @@ field1=value1&field2=value2",
             let mut result = Vec::new();
             let mut request_state = RequestState::ParsingHeader(None);
             for chunk in data.chunks(size) {
-                let data_out = request_state.process_http_buffer(chunk, TOKEN).unwrap();
+                let data_out = process_http_buffer(&mut request_state, chunk, TOKEN);
                 result.extend_from_slice(&data_out);
             }
             assert_eq!(
@@ -356,11 +364,35 @@ field1=value1&field2=value2",
             let mut result = Vec::new();
             let mut request_state = RequestState::ParsingHeader(None);
             for chunk in data.chunks(size) {
-                let data_out = request_state.process_http_buffer(chunk, TOKEN).unwrap();
+                let data_out = process_http_buffer(&mut request_state, chunk, TOKEN);
                 result.extend_from_slice(&data_out);
             }
             assert_eq!(String::from_utf8(result).unwrap(), expected);
             assert_eq!(request_state, RequestState::ParsingHeader(None));
         }
     }
+
+    #[test]
+    fn parse_request_heads() {
+        let req = "GET /api/v2/query?org=ockam HTTP/1.1\r\nHost: localhost\r\n\r\n";
+        let mut request_state = RequestState::default();
+        let segments = request_state.process_http_buffer(req.as_bytes()).unwrap();
+        let HttpSegment::Head(head) = &segments[0] else {
+            panic!("expected a request head")
+        };
+        assert_eq!(segments.len(), 1);
+        assert_eq!(head.method, "GET");
+        assert_eq!(head.path, "/api/v2/query?org=ockam");
+        assert_eq!(head.header("host"), Some(b"localhost".as_slice()));
+
+        let mut head = head.clone();
+        head.remove_header("Host");
+        head.append_header("X-Forwarded-By", "ockam");
+        let mut buffer = vec![];
+        head.write_into(&mut buffer);
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "GET /api/v2/query?org=ockam HTTP/1.1\r\nX-Forwarded-By: ockam\r\n\r\n"
+        );
+    }
 }
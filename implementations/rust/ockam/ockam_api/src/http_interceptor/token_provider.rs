@@ -0,0 +1,29 @@
+use ockam_core::async_trait;
+
+/// Provide the token attached to the intercepted HTTP requests
+#[async_trait]
+pub trait TokenProvider: Send + Sync + 'static {
+    /// Return the current token, or `None` if no token is available yet
+    async fn get_token(&self) -> Option<String>;
+}
+
+/// Token provider always returning the same token
+#[derive(Clone)]
+pub struct FixedTokenProvider {
+    token: String,
+}
+
+impl FixedTokenProvider {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl TokenProvider for FixedTokenProvider {
+    async fn get_token(&self) -> Option<String> {
+        Some(self.token.clone())
+    }
+}
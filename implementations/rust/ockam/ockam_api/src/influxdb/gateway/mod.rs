@@ -1,2 +1 @@
-pub mod interceptor;
 pub mod token_lease_refresher;
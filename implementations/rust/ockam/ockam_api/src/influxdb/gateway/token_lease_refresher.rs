@@ -1,8 +1,9 @@
+use crate::http_interceptor::TokenProvider;
 use crate::influxdb::lease_issuer::node_service::InfluxDBTokenLessorNodeServiceTrait;
 use crate::nodes::InMemoryNode;
 use ockam::{compat::time::now, Address, Mailboxes};
 use ockam_core::errcode::{Kind, Origin};
-use ockam_core::{api::Error, AllowAll, DenyAll};
+use ockam_core::{api::Error, async_trait, AllowAll, DenyAll};
 use ockam_multiaddr::MultiAddr;
 use ockam_node::Context;
 use std::cmp::max;
@@ -54,6 +55,13 @@ impl TokenLeaseRefresher {
     }
 }
 
+#[async_trait]
+impl TokenProvider for TokenLeaseRefresher {
+    async fn get_token(&self) -> Option<String> {
+        TokenLeaseRefresher::get_token(self).await
+    }
+}
+
 async fn refresh_loop(
     token: Arc<RwLock<Option<String>>>,
     ctx: Context,
@@ -1,4 +1,4 @@
-use crate::influxdb::gateway::interceptor::HttpAuthInterceptorFactory;
+use crate::http_interceptor::{FixedTokenProvider, HttpInterceptorFactory};
 use crate::influxdb::gateway::token_lease_refresher::TokenLeaseRefresher;
 use crate::influxdb::{LeaseUsage, StartInfluxDBLeaseIssuerRequest};
 use crate::nodes::models::portal::{
@@ -23,6 +23,9 @@ use ockam_transport_tcp::{PortalInletInterceptor, PortalOutletInterceptor};
 use std::sync::Arc;
 use std::time::Duration;
 
+/// InfluxDB expects its tokens in an `Authorization: Token <token>` header
+const INFLUXDB_AUTHORIZATION_SCHEME: &str = "Token";
+
 impl NodeManagerWorker {
     pub(crate) async fn start_influxdb_outlet_service(
         &self,
@@ -37,6 +40,9 @@ impl NodeManagerWorker {
             policy_expression,
             ebpf,
             tls,
+            http: _,
+            postgres: _,
+            limits: _,
         } = body.tcp_outlet;
         let address = self
             .node_manager
@@ -120,6 +126,7 @@ impl NodeManagerWorker {
             disable_tcp_fallback,
             ebpf,
             tls_certificate_provider,
+            ..
         } = body.tcp_inlet.clone();
 
         //TODO: should be an easier way to tweak the multiaddr
@@ -230,8 +237,10 @@ impl NodeManagerWorker {
 
         let spawner_flow_control_id = FlowControls::generate_flow_control_id();
 
-        let token_refresher = TokenLeaseRefresher::new_with_fixed_token(token_to_use);
-        let http_interceptor_factory = Arc::new(HttpAuthInterceptorFactory::new(token_refresher));
+        let http_interceptor_factory = Arc::new(HttpInterceptorFactory::new().with_token(
+            INFLUXDB_AUTHORIZATION_SCHEME,
+            Arc::new(FixedTokenProvider::new(token_to_use)),
+        ));
 
         PortalOutletInterceptor::create(
             ctx,
@@ -280,7 +289,10 @@ impl NodeManagerWorker {
         let token_refresher =
             TokenLeaseRefresher::new(ctx, Arc::downgrade(&self.node_manager), lease_issuer_route)
                 .await?;
-        let http_interceptor_factory = Arc::new(HttpAuthInterceptorFactory::new(token_refresher));
+        let http_interceptor_factory = Arc::new(
+            HttpInterceptorFactory::new()
+                .with_token(INFLUXDB_AUTHORIZATION_SCHEME, Arc::new(token_refresher)),
+        );
 
         PortalInletInterceptor::create(
             ctx,
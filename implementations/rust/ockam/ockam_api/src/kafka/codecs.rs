@@ -0,0 +1,137 @@
+use crate::ApiError;
+use apache_avro::Schema;
+use ockam_core::Result;
+use prost::Message;
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor};
+use serde_json::Value;
+
+/// Conversion between the records of a topic and a JSON document,
+/// used to select the record fields to encrypt
+pub(crate) trait RecordCodec: Send + Sync {
+    /// Decode a record into a JSON document
+    fn decode(&self, record: &[u8]) -> Result<Value>;
+
+    /// Encode a JSON document back to a record
+    fn encode(&self, value: Value) -> Result<Vec<u8>>;
+}
+
+/// Records which are already JSON documents
+pub(crate) struct JsonCodec;
+
+impl RecordCodec for JsonCodec {
+    fn decode(&self, record: &[u8]) -> Result<Value> {
+        serde_json::from_slice(record).map_err(|e| ApiError::core(format!("invalid JSON: {e}")))
+    }
+
+    fn encode(&self, value: Value) -> Result<Vec<u8>> {
+        serde_json::to_vec(&value).map_err(|e| ApiError::core(format!("invalid JSON: {e}")))
+    }
+}
+
+/// Avro datums written with a locally supplied schema
+pub(crate) struct AvroCodec {
+    schema: Schema,
+}
+
+impl AvroCodec {
+    pub(crate) fn new(schema: &str) -> Result<Self> {
+        let schema = Schema::parse_str(schema)
+            .map_err(|e| ApiError::core(format!("invalid Avro schema: {e}")))?;
+        Ok(Self { schema })
+    }
+}
+
+impl RecordCodec for AvroCodec {
+    fn decode(&self, record: &[u8]) -> Result<Value> {
+        let mut reader = record;
+        let value = apache_avro::from_avro_datum(&self.schema, &mut reader, None)
+            .map_err(|e| ApiError::core(format!("invalid Avro record: {e}")))?;
+        Value::try_from(value)
+            .map_err(|e| ApiError::core(format!("cannot convert the Avro record to JSON: {e}")))
+    }
+
+    fn encode(&self, value: Value) -> Result<Vec<u8>> {
+        // the JSON document is resolved against the schema to recover
+        // the Avro types, for example records, longs, or bytes
+        let value = apache_avro::types::Value::from(value)
+            .resolve(&self.schema)
+            .map_err(|e| ApiError::core(format!("the record doesn't match the schema: {e}")))?;
+        apache_avro::to_avro_datum(&self.schema, value)
+            .map_err(|e| ApiError::core(format!("cannot encode the Avro record: {e}")))
+    }
+}
+
+/// Protobuf messages described by a serialized `FileDescriptorSet`
+pub(crate) struct ProtobufCodec {
+    descriptor: MessageDescriptor,
+}
+
+impl ProtobufCodec {
+    pub(crate) fn new(descriptor_set: &[u8], message_name: &str) -> Result<Self> {
+        let pool = DescriptorPool::decode(descriptor_set)
+            .map_err(|e| ApiError::core(format!("invalid Protobuf descriptor set: {e}")))?;
+        let descriptor = pool.get_message_by_name(message_name).ok_or_else(|| {
+            ApiError::core(format!(
+                "the message {message_name} is not part of the Protobuf descriptor set"
+            ))
+        })?;
+        Ok(Self { descriptor })
+    }
+}
+
+impl RecordCodec for ProtobufCodec {
+    fn decode(&self, record: &[u8]) -> Result<Value> {
+        let message = DynamicMessage::decode(self.descriptor.clone(), record)
+            .map_err(|e| ApiError::core(format!("invalid Protobuf record: {e}")))?;
+        serde_json::to_value(&message)
+            .map_err(|e| ApiError::core(format!("cannot convert the Protobuf record to JSON: {e}")))
+    }
+
+    fn encode(&self, value: Value) -> Result<Vec<u8>> {
+        let message = DynamicMessage::deserialize(self.descriptor.clone(), value)
+            .map_err(|e| ApiError::core(format!("the record doesn't match the message: {e}")))?;
+        Ok(message.encode_to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn avro_round_trip() {
+        let schema = r#"
+        {
+            "type": "record",
+            "name": "Order",
+            "fields": [
+                { "name": "id", "type": "long" },
+                {
+                    "name": "customer",
+                    "type": {
+                        "type": "record",
+                        "name": "Customer",
+                        "fields": [{ "name": "name", "type": "string" }]
+                    }
+                }
+            ]
+        }"#;
+        let codec = AvroCodec::new(schema).unwrap();
+        let document = json!({ "id": 1, "customer": { "name": "alice" } });
+
+        let record = codec.encode(document.clone()).unwrap();
+        assert_eq!(codec.decode(&record).unwrap(), document);
+
+        assert!(AvroCodec::new("{").is_err());
+        assert!(codec.encode(json!({ "id": "one" })).is_err());
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let document = json!({ "customer": { "name": "alice" } });
+        let record = JsonCodec.encode(document.clone()).unwrap();
+        assert_eq!(JsonCodec.decode(&record).unwrap(), document);
+        assert!(JsonCodec.decode(b"not json").is_err());
+    }
+}
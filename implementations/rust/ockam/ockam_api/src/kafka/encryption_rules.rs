@@ -0,0 +1,350 @@
+use crate::kafka::codecs::{AvroCodec, JsonCodec, ProtobufCodec, RecordCodec};
+use crate::kafka::record_key::RecordKeySecret;
+use crate::ApiError;
+use minicbor::{CborLen, Decode, Encode};
+use ockam_core::compat::collections::HashMap;
+use serde_json::Value;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Describe which fields of the records of a given topic must be encrypted
+/// and how these records are encoded.
+///
+/// Records of topics using the Avro or Protobuf format are decoded to find the fields to
+/// encrypt, then encoded back to their original format before being sent to the Kafka broker.
+/// The encrypted values are hex strings, so the encrypted fields must be strings in the Avro
+/// schema or in the Protobuf message.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TopicEncryptionRule {
+    #[n(1)] topic: String,
+    #[n(2)] fields: Vec<String>,
+    #[n(3)] format: RecordFormat,
+}
+
+impl TopicEncryptionRule {
+    /// Create a rule encrypting the given fields paths for JSON records
+    pub fn new(topic: impl Into<String>, fields: Vec<String>) -> Self {
+        Self {
+            topic: topic.into(),
+            fields,
+            format: RecordFormat::Json,
+        }
+    }
+
+    /// Set the format of the records of this topic
+    pub fn with_format(mut self, format: RecordFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    pub fn fields(&self) -> &[String] {
+        &self.fields
+    }
+
+    pub fn format(&self) -> &RecordFormat {
+        &self.format
+    }
+}
+
+/// Parse a rule from `<topic>:<path>[,<path>...]`, for example `orders:customer.address.*,total`
+impl FromStr for TopicEncryptionRule {
+    type Err = ApiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (topic, fields) = s.split_once(':').ok_or_else(|| {
+            ApiError::message(format!(
+                "invalid encryption rule '{s}', expected <topic>:<path>[,<path>...]"
+            ))
+        })?;
+        if topic.is_empty() {
+            return Err(ApiError::message(format!(
+                "the encryption rule '{s}' has no topic"
+            )));
+        }
+        let fields = fields
+            .split(',')
+            .map(|f| f.trim())
+            .filter(|f| !f.is_empty())
+            .map(|f| FieldPath::from_str(f).map(|_| f.to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+        if fields.is_empty() {
+            return Err(ApiError::message(format!(
+                "the encryption rule '{s}' has no fields"
+            )));
+        }
+        Ok(Self::new(topic, fields))
+    }
+}
+
+/// Encoding of the records of a topic
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub enum RecordFormat {
+    /// Records are JSON documents
+    #[n(0)] Json,
+    /// Records are Avro datums, without any schema registry header,
+    /// written with the given JSON schema
+    #[n(1)] Avro {
+        #[n(0)] schema: String,
+    },
+    /// Records are Protobuf messages of the given fully qualified type,
+    /// described in a serialized `FileDescriptorSet`
+    #[n(2)] Protobuf {
+        #[cbor(with = "minicbor::bytes")]
+        #[n(0)] descriptor_set: Vec<u8>,
+        #[n(1)] message_name: String,
+    },
+}
+
+/// A path to one or more fields of a record, for example `customer.address.*`.
+///
+/// Segments are separated with `.`; `*` matches all the fields of an object
+/// or all the elements of an array, and a number selects an array element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldPath {
+    segments: Vec<PathSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathSegment {
+    Key(String),
+    Wildcard,
+}
+
+impl FieldPath {
+    /// Return all the values of the document matching this path
+    pub fn find_mut<'a>(&self, value: &'a mut Value) -> Vec<&'a mut Value> {
+        let mut found = vec![];
+        Self::collect(&self.segments, value, &mut found);
+        found
+    }
+
+    fn collect<'a>(segments: &[PathSegment], value: &'a mut Value, found: &mut Vec<&'a mut Value>) {
+        let (segment, rest) = match segments.split_first() {
+            Some(split) => split,
+            None => {
+                found.push(value);
+                return;
+            }
+        };
+        match (segment, value) {
+            (PathSegment::Wildcard, Value::Object(map)) => {
+                for value in map.values_mut() {
+                    Self::collect(rest, value, found)
+                }
+            }
+            (PathSegment::Wildcard, Value::Array(items)) => {
+                for value in items.iter_mut() {
+                    Self::collect(rest, value, found)
+                }
+            }
+            (PathSegment::Key(key), Value::Object(map)) => {
+                if let Some(value) = map.get_mut(key) {
+                    Self::collect(rest, value, found)
+                }
+            }
+            (PathSegment::Key(key), Value::Array(items)) => {
+                if let Some(value) = key.parse::<usize>().ok().and_then(|i| items.get_mut(i)) {
+                    Self::collect(rest, value, found)
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl FromStr for FieldPath {
+    type Err = ApiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let segments = s
+            .split('.')
+            .map(|segment| match segment {
+                "" => Err(ApiError::message(format!(
+                    "the field path '{s}' contains an empty segment"
+                ))),
+                "*" => Ok(PathSegment::Wildcard),
+                key => Ok(PathSegment::Key(key.to_string())),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { segments })
+    }
+}
+
+impl Display for FieldPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let segments: Vec<&str> = self
+            .segments
+            .iter()
+            .map(|s| match s {
+                PathSegment::Key(key) => key.as_str(),
+                PathSegment::Wildcard => "*",
+            })
+            .collect();
+        write!(f, "{}", segments.join("."))
+    }
+}
+
+/// Fields to encrypt in the records of a topic, with the codec used to read these records
+#[derive(Clone)]
+pub(crate) struct FieldsEncryption {
+    pub(crate) fields: Vec<FieldPath>,
+    pub(crate) codec: Arc<dyn RecordCodec>,
+}
+
+/// Encryption rules used by a Kafka inlet, validated when the inlet is started
+#[derive(Clone, Default)]
+pub(crate) struct EncryptionRules {
+    default: Option<FieldsEncryption>,
+    topics: HashMap<String, FieldsEncryption>,
+    encrypt_headers: bool,
+    /// Secret from which the secrets encrypting the record keys of each topic are derived.
+    /// `None` when the record keys are not encrypted
+    record_keys_secret: Option<Vec<u8>>,
+}
+
+impl EncryptionRules {
+    /// Create rules from the fields encrypted for all topics, which are expected to be
+    /// JSON records, and from topic specific rules
+    pub(crate) fn new(
+        encrypted_fields: Vec<String>,
+        topic_rules: Vec<TopicEncryptionRule>,
+    ) -> ockam_core::Result<Self> {
+        let default = if encrypted_fields.is_empty() {
+            None
+        } else {
+            Some(FieldsEncryption {
+                fields: Self::parse_fields(&encrypted_fields)?,
+                codec: Arc::new(JsonCodec),
+            })
+        };
+
+        let mut topics = HashMap::new();
+        for rule in topic_rules {
+            let codec: Arc<dyn RecordCodec> = match &rule.format {
+                RecordFormat::Json => Arc::new(JsonCodec),
+                RecordFormat::Avro { schema } => Arc::new(AvroCodec::new(schema)?),
+                RecordFormat::Protobuf {
+                    descriptor_set,
+                    message_name,
+                } => Arc::new(ProtobufCodec::new(descriptor_set, message_name)?),
+            };
+            let fields = Self::parse_fields(&rule.fields)?;
+            topics.insert(rule.topic, FieldsEncryption { fields, codec });
+        }
+
+        Ok(Self {
+            default,
+            topics,
+            ..Default::default()
+        })
+    }
+
+    /// Encrypt the values of the records headers
+    pub(crate) fn with_headers_encryption(mut self, encrypt_headers: bool) -> Self {
+        self.encrypt_headers = encrypt_headers;
+        self
+    }
+
+    /// Deterministically encrypt the records keys with secrets derived from a shared secret
+    pub(crate) fn with_keys_encryption(mut self, record_keys_secret: Option<Vec<u8>>) -> Self {
+        self.record_keys_secret = record_keys_secret;
+        self
+    }
+
+    pub(crate) fn encrypt_headers(&self) -> bool {
+        self.encrypt_headers
+    }
+
+    /// Return the secret used to encrypt the record keys of a given topic.
+    /// `None` is returned when the keys are not encrypted.
+    pub(crate) fn record_key_secret(&self, topic: &str) -> Option<RecordKeySecret> {
+        let shared_secret = self.record_keys_secret.as_ref()?;
+        Some(RecordKeySecret::derive(shared_secret, topic))
+    }
+
+    /// Return the fields to encrypt for a given topic.
+    /// When `None` is returned, the whole record must be encrypted.
+    pub(crate) fn for_topic(&self, topic: &str) -> Option<&FieldsEncryption> {
+        self.topics.get(topic).or(self.default.as_ref())
+    }
+
+    fn parse_fields(fields: &[String]) -> ockam_core::Result<Vec<FieldPath>> {
+        fields
+            .iter()
+            .map(|f| FieldPath::from_str(f).map_err(|e| ApiError::core(e.to_string())))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parse_encryption_rule() {
+        let rule = TopicEncryptionRule::from_str("orders:customer.address.*, total").unwrap();
+        assert_eq!(rule.topic(), "orders");
+        assert_eq!(rule.fields(), &["customer.address.*", "total"]);
+        assert_eq!(rule.format(), &RecordFormat::Json);
+
+        assert!(TopicEncryptionRule::from_str("orders").is_err());
+        assert!(TopicEncryptionRule::from_str(":total").is_err());
+        assert!(TopicEncryptionRule::from_str("orders:").is_err());
+        assert!(TopicEncryptionRule::from_str("orders:customer..name").is_err());
+    }
+
+    #[test]
+    fn find_fields_matching_a_path() {
+        let mut document = json!({
+            "customer": {
+                "name": "alice",
+                "address": { "street": "main street", "city": "paris" }
+            },
+            "items": [{ "price": 1 }, { "price": 2 }]
+        });
+
+        let path = FieldPath::from_str("customer.address.*").unwrap();
+        let mut found = path.find_mut(&mut document);
+        found.sort_by_key(|v| v.to_string());
+        assert_eq!(found, vec![&json!("main street"), &json!("paris")]);
+
+        let path = FieldPath::from_str("items.*.price").unwrap();
+        assert_eq!(path.find_mut(&mut document), vec![&json!(1), &json!(2)]);
+
+        let path = FieldPath::from_str("items.1").unwrap();
+        assert_eq!(path.find_mut(&mut document), vec![&json!({ "price": 2 })]);
+
+        let path = FieldPath::from_str("customer.phone").unwrap();
+        assert!(path.find_mut(&mut document).is_empty());
+        assert_eq!(path.to_string(), "customer.phone");
+    }
+
+    #[test]
+    fn topic_rules_override_the_default_fields() {
+        let rules = EncryptionRules::new(
+            vec!["name".to_string()],
+            vec![TopicEncryptionRule::new(
+                "orders",
+                vec!["customer.*".to_string()],
+            )],
+        )
+        .unwrap();
+
+        let orders = rules.for_topic("orders").unwrap();
+        assert_eq!(orders.fields[0].to_string(), "customer.*");
+        let other = rules.for_topic("other").unwrap();
+        assert_eq!(other.fields[0].to_string(), "name");
+
+        let rules = EncryptionRules::new(vec![], vec![]).unwrap();
+        assert!(rules.for_topic("orders").is_none());
+    }
+}
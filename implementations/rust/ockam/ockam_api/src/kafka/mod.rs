@@ -1,13 +1,18 @@
 //!This service allows encrypted transparent communication from the kafka producer
 //! to the kafka consumer without any modification in the existing application.
 
+mod codecs;
+mod encryption_rules;
 mod inlet_controller;
 pub(crate) mod key_exchange;
 mod outlet_controller;
 pub(crate) mod protocol_aware;
+mod record_key;
 #[cfg(test)]
 mod tests;
 
+pub(crate) use encryption_rules::EncryptionRules;
+pub use encryption_rules::{FieldPath, RecordFormat, TopicEncryptionRule};
 pub(crate) use inlet_controller::KafkaInletController;
 pub use key_exchange::{ConsumerPublishing, ConsumerResolution};
 use ockam::identity::Identifier;
@@ -2,9 +2,9 @@ use crate::kafka::key_exchange::controller::KafkaKeyExchangeControllerImpl;
 use crate::kafka::key_exchange::KafkaKeyExchangeController;
 use crate::kafka::protocol_aware::{
     CorrelationId, KafkaMessageInterceptor, KafkaMessageInterceptorWrapper, RequestInfo,
-    TopicUuidMap, MAX_KAFKA_MESSAGE_SIZE,
+    SaslRawExchange, TopicUuidMap, MAX_KAFKA_MESSAGE_SIZE,
 };
-use crate::kafka::KafkaInletController;
+use crate::kafka::{EncryptionRules, KafkaInletController};
 use ockam_core::async_trait;
 use ockam_core::compat::collections::HashMap;
 use ockam_transport_tcp::{PortalInterceptor, PortalInterceptorFactory};
@@ -23,7 +23,8 @@ pub(crate) struct InletInterceptorImpl {
     key_exchange_controller: Arc<dyn KafkaKeyExchangeController>,
     inlet_map: KafkaInletController,
     encrypt_content: bool,
-    encrypted_fields: Vec<String>,
+    encryption_rules: EncryptionRules,
+    sasl: SaslRawExchange,
 }
 
 #[async_trait]
@@ -35,7 +36,7 @@ impl InletInterceptorImpl {
         uuid_to_name: TopicUuidMap,
         inlet_map: KafkaInletController,
         encrypt_content: bool,
-        encrypted_fields: Vec<String>,
+        encryption_rules: EncryptionRules,
     ) -> InletInterceptorImpl {
         Self {
             request_map: Arc::new(Mutex::new(Default::default())),
@@ -43,7 +44,8 @@ impl InletInterceptorImpl {
             key_exchange_controller,
             inlet_map,
             encrypt_content,
-            encrypted_fields,
+            encryption_rules,
+            sasl: Default::default(),
         }
     }
 
@@ -69,7 +71,7 @@ pub(crate) struct KafkaInletInterceptorFactory {
     uuid_to_name: TopicUuidMap,
     inlet_map: KafkaInletController,
     encrypt_content: bool,
-    encrypted_fields: Vec<String>,
+    encryption_rules: EncryptionRules,
 }
 
 impl KafkaInletInterceptorFactory {
@@ -77,14 +79,14 @@ impl KafkaInletInterceptorFactory {
         secure_channel_controller: KafkaKeyExchangeControllerImpl,
         inlet_map: KafkaInletController,
         encrypt_content: bool,
-        encrypted_fields: Vec<String>,
+        encryption_rules: EncryptionRules,
     ) -> Self {
         Self {
             secure_channel_controller,
             uuid_to_name: Default::default(),
             inlet_map,
             encrypt_content,
-            encrypted_fields,
+            encryption_rules,
         }
     }
 }
@@ -97,7 +99,7 @@ impl PortalInterceptorFactory for KafkaInletInterceptorFactory {
                 self.uuid_to_name.clone(),
                 self.inlet_map.clone(),
                 self.encrypt_content,
-                self.encrypted_fields.clone(),
+                self.encryption_rules.clone(),
             )),
             MAX_KAFKA_MESSAGE_SIZE,
         ))
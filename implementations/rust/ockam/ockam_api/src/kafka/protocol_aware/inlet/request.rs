@@ -1,16 +1,18 @@
+use crate::kafka::encryption_rules::FieldsEncryption;
 use crate::kafka::protocol_aware::inlet::InletInterceptorImpl;
 use crate::kafka::protocol_aware::utils::{decode_body, encode_request};
 use crate::kafka::protocol_aware::RequestInfo;
 use crate::kafka::protocol_aware::{InterceptError, KafkaMessageRequestInterceptor};
+use crate::kafka::record_key::{ENCRYPTED_HEADERS_HEADER, RECORD_KEY_SECRET_HEADER};
 use bytes::{Bytes, BytesMut};
 use kafka_protocol::messages::fetch_request::FetchRequest;
 use kafka_protocol::messages::produce_request::{PartitionProduceData, ProduceRequest};
 use kafka_protocol::messages::request_header::RequestHeader;
-use kafka_protocol::messages::{ApiKey, TopicName};
+use kafka_protocol::messages::{ApiKey, SaslHandshakeRequest, TopicName};
 use kafka_protocol::protocol::buf::ByteBuf;
-use kafka_protocol::protocol::Decodable;
+use kafka_protocol::protocol::{Decodable, StrBytes};
 use kafka_protocol::records::{
-    Compression, RecordBatchDecoder, RecordBatchEncoder, RecordEncodeOptions,
+    Compression, Record, RecordBatchDecoder, RecordBatchEncoder, RecordEncodeOptions,
 };
 use minicbor::encode::Encoder;
 use ockam_core::async_trait;
@@ -27,6 +29,15 @@ impl KafkaMessageRequestInterceptor for InletInterceptorImpl {
         context: &mut Context,
         mut original: BytesMut,
     ) -> Result<BytesMut, InterceptError> {
+        // raw SASL tokens don't have any kafka header
+        if self.sasl.next_request_is_raw() {
+            return Ok(original);
+        }
+        if original.len() < 4 {
+            warn!("kafka request too short");
+            return Err(InterceptError::InvalidData);
+        }
+
         // let's clone the view of the buffer without cloning the content
         let mut buffer = original.peek_bytes(0..original.len());
 
@@ -87,6 +98,21 @@ impl KafkaMessageRequestInterceptor for InletInterceptorImpl {
                 self.handle_fetch_request(context, &mut buffer, &header)
                     .await?;
             }
+            ApiKey::SaslHandshakeKey => {
+                // since v1 the SASL tokens are sent in SaslAuthenticate requests,
+                // which don't need to be intercepted
+                if header.request_api_version == 0 {
+                    let request: SaslHandshakeRequest = decode_body(&mut buffer, 0)?;
+                    self.sasl.handshake_requested(request.mechanism.as_str())?;
+                    self.request_map.lock().unwrap().insert(
+                        header.correlation_id,
+                        RequestInfo {
+                            request_api_key: api_key,
+                            request_api_version: header.request_api_version,
+                        },
+                    );
+                }
+            }
             ApiKey::MetadataKey | ApiKey::FindCoordinatorKey => {
                 self.request_map.lock().unwrap().insert(
                     header.correlation_id,
@@ -187,22 +213,27 @@ impl InletInterceptorImpl {
 
                     for record in records.iter_mut() {
                         if let Some(record_value) = record.value.take() {
-                            let buffer = if !self.encrypted_fields.is_empty() {
-                                // if we encrypt only specific fields, we assume the record must be
-                                // valid JSON map
-                                self.encrypt_specific_fields(
-                                    context,
-                                    topic_name,
-                                    data,
-                                    &record_value,
-                                )
-                                .await?
-                            } else {
-                                self.encrypt_whole_record(context, topic_name, data, record_value)
+                            let buffer = match self.encryption_rules.for_topic(topic_name) {
+                                Some(fields_encryption) => {
+                                    self.encrypt_specific_fields(
+                                        context,
+                                        topic_name,
+                                        data,
+                                        fields_encryption,
+                                        &record_value,
+                                    )
                                     .await?
+                                }
+                                None => {
+                                    self.encrypt_content(context, topic_name, data, record_value)
+                                        .await?
+                                }
                             };
                             record.value = Some(buffer.into());
                         }
+
+                        self.encrypt_key_and_headers(context, topic_name, data, record)
+                            .await?;
                     }
 
                     let mut encoded = BytesMut::new();
@@ -229,16 +260,18 @@ impl InletInterceptorImpl {
         )
     }
 
-    async fn encrypt_whole_record(
+    /// Encrypt some content for the consumer of the partition, wrapped
+    /// in a CBOR-encoded `KafkaEncryptedContent`
+    async fn encrypt_content(
         &self,
         context: &mut Context,
         topic_name: &TopicName,
         data: &mut PartitionProduceData,
-        record_value: Bytes,
+        content: Bytes,
     ) -> Result<Vec<u8>, InterceptError> {
         let encrypted_content = self
             .key_exchange_controller
-            .encrypt_content(context, topic_name, data.index, record_value.to_vec())
+            .encrypt_content(context, topic_name, data.index, content.to_vec())
             .await
             .map_err(InterceptError::Ockam)?;
 
@@ -251,42 +284,95 @@ impl InletInterceptorImpl {
         Ok(write_buffer)
     }
 
+    async fn encrypt_key_and_headers(
+        &self,
+        context: &mut Context,
+        topic_name: &TopicName,
+        data: &mut PartitionProduceData,
+        record: &mut Record,
+    ) -> Result<(), InterceptError> {
+        // the header names are kept in clear text, only their values are encrypted
+        if self.encryption_rules.encrypt_headers() {
+            for value in record.headers.values_mut() {
+                if let Some(header_value) = value.take() {
+                    let encrypted = self
+                        .encrypt_content(context, topic_name, data, header_value)
+                        .await?;
+                    *value = Some(encrypted.into());
+                }
+            }
+            record.headers.insert(
+                StrBytes::from_static_str(ENCRYPTED_HEADERS_HEADER),
+                Some(Bytes::new()),
+            );
+        }
+
+        // the key is encrypted deterministically with a secret which is sent to the
+        // consumer in a header, encrypted with the secure channel of the partition
+        if let Some(secret) = self.encryption_rules.record_key_secret(topic_name) {
+            if let Some(key) = record.key.take() {
+                let encrypted_key = secret.encrypt(&key).map_err(InterceptError::Ockam)?;
+                record.key = Some(encrypted_key.into());
+
+                let encrypted_secret = self
+                    .encrypt_content(context, topic_name, data, secret.to_bytes().into())
+                    .await?;
+                record.headers.insert(
+                    StrBytes::from_static_str(RECORD_KEY_SECRET_HEADER),
+                    Some(encrypted_secret.into()),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
     async fn encrypt_specific_fields(
         &self,
         context: &mut Context,
         topic_name: &TopicName,
         data: &mut PartitionProduceData,
+        fields_encryption: &FieldsEncryption,
         record_value: &Bytes,
     ) -> Result<Vec<u8>, InterceptError> {
-        let mut record_value = serde_json::from_slice::<serde_json::Value>(record_value)?;
-
-        if let serde_json::Value::Object(map) = &mut record_value {
-            for field in &self.encrypted_fields {
-                if let Some(value) = map.get_mut(field) {
-                    let encrypted_content = self
-                        .key_exchange_controller
-                        .encrypt_content(
-                            context,
-                            topic_name,
-                            data.index,
-                            serde_json::to_vec(value).map_err(|_| InterceptError::InvalidData)?,
-                        )
-                        .await
-                        .map_err(InterceptError::Ockam)?;
-
-                    let mut write_buffer = Vec::with_capacity(1024);
-                    let mut encoder = Encoder::new(&mut write_buffer);
-                    encoder
-                        .encode(encrypted_content)
-                        .map_err(|_| InterceptError::InvalidData)?;
-                    *value = serde_json::Value::String(hex::encode(&write_buffer));
-                }
-            }
-        } else {
+        // the record is decoded as a JSON document in order to find the fields to encrypt
+        let mut record_value = fields_encryption.codec.decode(record_value).map_err(|e| {
+            warn!("cannot decode the record to encrypt its fields: {e}");
+            InterceptError::Ockam(e)
+        })?;
+
+        if !record_value.is_object() {
             warn!("only JSON objects are supported for field encryption");
             return Err("Only JSON objects are supported".into());
         }
 
-        Ok(record_value.to_string().as_bytes().to_vec())
+        for field in &fields_encryption.fields {
+            for value in field.find_mut(&mut record_value) {
+                let encrypted_content = self
+                    .key_exchange_controller
+                    .encrypt_content(
+                        context,
+                        topic_name,
+                        data.index,
+                        serde_json::to_vec(value).map_err(|_| InterceptError::InvalidData)?,
+                    )
+                    .await
+                    .map_err(InterceptError::Ockam)?;
+
+                let mut write_buffer = Vec::with_capacity(1024);
+                let mut encoder = Encoder::new(&mut write_buffer);
+                encoder
+                    .encode(encrypted_content)
+                    .map_err(|_| InterceptError::InvalidData)?;
+                *value = serde_json::Value::String(hex::encode(&write_buffer));
+            }
+        }
+
+        // the record is sent to the broker in its original format, where
+        // the encrypted fields must be strings
+        fields_encryption.codec.encode(record_value).map_err(|e| {
+            warn!("cannot encode the record with its encrypted fields, the encrypted fields must be strings: {e}");
+            InterceptError::Ockam(e)
+        })
     }
 }
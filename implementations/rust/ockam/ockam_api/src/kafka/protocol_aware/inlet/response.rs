@@ -1,23 +1,30 @@
+use crate::kafka::encryption_rules::FieldsEncryption;
 use crate::kafka::protocol_aware::inlet::InletInterceptorImpl;
-use crate::kafka::protocol_aware::utils::{decode_body, encode_response};
+use crate::kafka::protocol_aware::utils::{decode_body, encode_response, supported_versions};
 use crate::kafka::protocol_aware::{
     InterceptError, KafkaEncryptedContent, KafkaMessageResponseInterceptor, RequestInfo,
 };
+use crate::kafka::record_key::{
+    RecordKeySecret, ENCRYPTED_HEADERS_HEADER, RECORD_KEY_SECRET_HEADER,
+};
 use crate::kafka::KafkaInletController;
 use bytes::{Bytes, BytesMut};
 use kafka_protocol::messages::{
     ApiKey, ApiVersionsResponse, FetchResponse, FindCoordinatorResponse, MetadataResponse,
-    ResponseHeader,
+    ResponseHeader, SaslHandshakeResponse,
 };
 use kafka_protocol::protocol::buf::ByteBuf;
 use kafka_protocol::protocol::{Decodable, StrBytes};
 use kafka_protocol::records::{
-    Compression, RecordBatchDecoder, RecordBatchEncoder, RecordEncodeOptions,
+    Compression, Record, RecordBatchDecoder, RecordBatchEncoder, RecordEncodeOptions,
 };
 use minicbor::Decoder;
 use ockam_core::async_trait;
 use ockam_node::Context;
 
+/// Error code returned by the broker when it doesn't support the version of a request
+const UNSUPPORTED_VERSION: i16 = 35;
+
 #[async_trait]
 impl KafkaMessageResponseInterceptor for InletInterceptorImpl {
     async fn intercept_response(
@@ -25,6 +32,15 @@ impl KafkaMessageResponseInterceptor for InletInterceptorImpl {
         context: &mut Context,
         mut original: BytesMut,
     ) -> Result<BytesMut, InterceptError> {
+        // raw SASL tokens don't have any kafka header
+        if self.sasl.next_response_is_raw() {
+            return Ok(original);
+        }
+        if original.len() < 4 {
+            warn!("kafka response too short");
+            return Err(InterceptError::InvalidData);
+        }
+
         // let's clone the view of the buffer without cloning the content
         let mut buffer = original.peek_bytes(0..original.len());
 
@@ -64,10 +80,24 @@ impl KafkaMessageResponseInterceptor for InletInterceptorImpl {
 
             match request_info.request_api_key {
                 ApiKey::ApiVersionsKey => {
-                    let response: ApiVersionsResponse =
+                    // when the broker doesn't support the request version, it replies with
+                    // a v0 response and the client retries with a lower version
+                    let error_code = buffer.peek_bytes(0..2).try_get_i16()?;
+                    if error_code != UNSUPPORTED_VERSION {
+                        return self.handle_api_versions_response(
+                            &mut buffer,
+                            &request_info,
+                            &header,
+                        );
+                    }
+                }
+
+                ApiKey::SaslHandshakeKey => {
+                    let response: SaslHandshakeResponse =
                         decode_body(&mut buffer, request_info.request_api_version)?;
-                    debug!("api versions response header: {:?}", header);
-                    debug!("api versions response: {:#?}", response);
+                    if response.error_code == 0 {
+                        self.sasl.handshake_completed();
+                    }
                 }
 
                 ApiKey::FetchKey => {
@@ -116,6 +146,41 @@ impl KafkaMessageResponseInterceptor for InletInterceptorImpl {
 }
 
 impl InletInterceptorImpl {
+    // the supported versions are restricted to the ones the interceptors can decode,
+    // so that clients don't negotiate versions which can't be intercepted
+    fn handle_api_versions_response(
+        &self,
+        buffer: &mut Bytes,
+        request_info: &RequestInfo,
+        header: &ResponseHeader,
+    ) -> Result<BytesMut, InterceptError> {
+        let mut response: ApiVersionsResponse =
+            decode_body(buffer, request_info.request_api_version)?;
+        debug!("api versions response: {:#?}", response);
+
+        response.api_keys.retain(|api_key, version| {
+            let Ok(api_key) = ApiKey::try_from(*api_key) else {
+                debug!("removing the unknown api {api_key} from the api versions");
+                return false;
+            };
+            match supported_versions(api_key) {
+                Some((min, max)) => {
+                    version.min_version = version.min_version.max(min);
+                    version.max_version = version.max_version.min(max);
+                    version.min_version <= version.max_version
+                }
+                None => true,
+            }
+        });
+
+        encode_response(
+            header,
+            &response,
+            request_info.request_api_version,
+            ApiKey::ApiVersionsKey,
+        )
+    }
+
     // for metadata we want to replace broker address and port
     // to dedicated tcp inlet ports
     async fn handle_metadata_response(
@@ -222,6 +287,21 @@ impl InletInterceptorImpl {
         // we take every record batch content, unwrap and decode it
         // using the relative secure channel
         for response in response.responses.iter_mut() {
+            // fetch responses using version >= 13 only contain the topic uuid
+            let topic_name = if request_info.request_api_version <= 12 {
+                Some(response.topic.0.to_string())
+            } else {
+                self.uuid_to_name
+                    .lock()
+                    .unwrap()
+                    .get(&response.topic_id.to_string())
+                    .cloned()
+            };
+            // an unknown topic falls back on the rules shared by all topics
+            let fields_encryption = self
+                .encryption_rules
+                .for_topic(topic_name.as_deref().unwrap_or_default());
+
             for partition in response.partitions.iter_mut() {
                 if let Some(content) = partition.records.take() {
                     let mut content = BytesMut::from(content.as_ref());
@@ -230,13 +310,21 @@ impl InletInterceptorImpl {
 
                     for record in records.iter_mut() {
                         if let Some(record_value) = record.value.take() {
-                            let decrypted_content = if self.encrypted_fields.is_empty() {
-                                self.decrypt_whole_record(context, record_value).await?
-                            } else {
-                                self.decrypt_specific_fields(context, record_value).await?
+                            let decrypted_content = match fields_encryption {
+                                Some(fields_encryption) => {
+                                    self.decrypt_specific_fields(
+                                        context,
+                                        fields_encryption,
+                                        record_value,
+                                    )
+                                    .await?
+                                }
+                                None => self.decrypt_content(context, record_value).await?,
                             };
                             record.value = Some(decrypted_content.into());
                         }
+
+                        self.decrypt_key_and_headers(context, record).await?;
                     }
 
                     let mut encoded = BytesMut::new();
@@ -262,13 +350,13 @@ impl InletInterceptorImpl {
         )
     }
 
-    async fn decrypt_whole_record(
+    /// Decrypt some content wrapped in a CBOR-encoded `KafkaEncryptedContent`
+    async fn decrypt_content(
         &self,
         context: &mut Context,
-        record_value: Bytes,
+        content: Bytes,
     ) -> Result<Vec<u8>, InterceptError> {
-        let message_wrapper: KafkaEncryptedContent =
-            Decoder::new(record_value.as_ref()).decode()?;
+        let message_wrapper: KafkaEncryptedContent = Decoder::new(content.as_ref()).decode()?;
 
         self.key_exchange_controller
             .decrypt_content(
@@ -280,50 +368,88 @@ impl InletInterceptorImpl {
             .map_err(InterceptError::Ockam)
     }
 
+    /// Decrypt the record key and headers when the producer encrypted them.
+    /// The headers added by the producer are removed before delivering the record.
+    async fn decrypt_key_and_headers(
+        &self,
+        context: &mut Context,
+        record: &mut Record,
+    ) -> Result<(), InterceptError> {
+        let secret_header = StrBytes::from_static_str(RECORD_KEY_SECRET_HEADER);
+        if let Some(encrypted_secret) = record.headers.shift_remove(&secret_header) {
+            let encrypted_secret = encrypted_secret.ok_or("The record key secret is missing")?;
+            let secret = self.decrypt_content(context, encrypted_secret).await?;
+            let secret = RecordKeySecret::from_bytes(&secret).map_err(InterceptError::Ockam)?;
+            if let Some(key) = record.key.take() {
+                let key = secret.decrypt(&key).map_err(InterceptError::Ockam)?;
+                record.key = Some(key.into());
+            }
+        }
+
+        let encrypted_headers = StrBytes::from_static_str(ENCRYPTED_HEADERS_HEADER);
+        if record.headers.shift_remove(&encrypted_headers).is_some() {
+            for value in record.headers.values_mut() {
+                if let Some(header_value) = value.take() {
+                    *value = Some(self.decrypt_content(context, header_value).await?.into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     async fn decrypt_specific_fields(
         &self,
         context: &mut Context,
+        fields_encryption: &FieldsEncryption,
         record_value: Bytes,
     ) -> Result<Vec<u8>, InterceptError> {
-        let mut record_value = serde_json::from_slice::<serde_json::Value>(&record_value)?;
+        let mut record_value = fields_encryption.codec.decode(&record_value).map_err(|e| {
+            error!("cannot decode the record to decrypt its fields: {e}");
+            InterceptError::Ockam(e)
+        })?;
+
+        if !record_value.is_object() {
+            error!(
+                "cannot decrypt specific fields, expected a JSON object but got a different type"
+            );
+            return Err("Only JSON objects are supported in the message".into());
+        }
 
-        if let serde_json::Value::Object(map) = &mut record_value {
-            for field in &self.encrypted_fields {
+        // the fields are decrypted in the reverse order of their encryption
+        // so that overlapping paths are correctly handled
+        for field in fields_encryption.fields.iter().rev() {
+            for value in field.find_mut(&mut record_value) {
                 // when the encrypted field is present is expected to be a hex encoded string
                 // wrapped by the KafkaEncryptedContent struct
-                if let Some(value) = map.get_mut(field) {
-                    let encrypted_content = if let serde_json::Value::String(string) = value {
-                        hex::decode(string).map_err(|_| "Encrypted is not a valid hex string")?
-                    } else {
-                        error!("encrypted field is not a hex string");
-                        return Err("The encrypted field is not a hex-encoded string".into());
-                    };
-
-                    let message_wrapper: KafkaEncryptedContent =
-                        Decoder::new(&encrypted_content).decode()?;
-
-                    let decrypted_content = self
-                        .key_exchange_controller
-                        .decrypt_content(
-                            context,
-                            &message_wrapper.consumer_decryptor_address,
-                            message_wrapper.content,
-                        )
-                        .await
-                        .map_err(InterceptError::Ockam)?;
+                let encrypted_content = if let serde_json::Value::String(string) = value {
+                    hex::decode(string).map_err(|_| "Encrypted is not a valid hex string")?
+                } else {
+                    error!("encrypted field is not a hex string");
+                    return Err("The encrypted field is not a hex-encoded string".into());
+                };
+
+                let message_wrapper: KafkaEncryptedContent =
+                    Decoder::new(&encrypted_content).decode()?;
+
+                let decrypted_content = self
+                    .key_exchange_controller
+                    .decrypt_content(
+                        context,
+                        &message_wrapper.consumer_decryptor_address,
+                        message_wrapper.content,
+                    )
+                    .await
+                    .map_err(InterceptError::Ockam)?;
 
-                    *value = serde_json::from_slice(decrypted_content.as_slice())?;
-                }
+                *value = serde_json::from_slice(decrypted_content.as_slice())?;
             }
-            serde_json::to_vec(&record_value).map_err(|error| {
-                error!("cannot serialize decrypted fields");
-                error.into()
-            })
-        } else {
-            error!(
-                "cannot decrypt specific fields, expected a JSON object but got a different type"
-            );
-            Err("Only JSON objects are supported in the message".into())
         }
+
+        // the record is delivered to the consumer in its original format
+        fields_encryption.codec.encode(record_value).map_err(|e| {
+            error!("cannot encode the decrypted record: {e}");
+            InterceptError::Ockam(e)
+        })
     }
 }
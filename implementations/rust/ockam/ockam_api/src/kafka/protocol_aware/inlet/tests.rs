@@ -2,9 +2,10 @@ use crate::kafka::key_exchange::KafkaKeyExchangeController;
 use crate::kafka::protocol_aware::inlet::InletInterceptorImpl;
 use crate::kafka::protocol_aware::{
     utils, KafkaEncryptedContent, KafkaMessageRequestInterceptor, KafkaMessageResponseInterceptor,
+    TopicUuidMap,
 };
-use crate::kafka::KafkaInletController;
-use bytes::BytesMut;
+use crate::kafka::{EncryptionRules, KafkaInletController, RecordFormat, TopicEncryptionRule};
+use bytes::{Bytes, BytesMut};
 use indexmap::IndexMap;
 use kafka_protocol::messages::fetch_response::{FetchableTopicResponse, PartitionData};
 use kafka_protocol::messages::produce_request::{PartitionProduceData, TopicProduceData};
@@ -65,7 +66,28 @@ impl KafkaKeyExchangeController for MockKafkaKeyExchangeController {
 
 const TEST_KAFKA_API_VERSION: i16 = 13;
 
+pub fn create_record(content: &[u8]) -> Record {
+    Record {
+        transactional: false,
+        control: false,
+        partition_leader_epoch: 0,
+        producer_id: 0,
+        producer_epoch: 0,
+        timestamp_type: TimestampType::Creation,
+        offset: 0,
+        sequence: 0,
+        timestamp: 0,
+        key: None,
+        value: Some(BytesMut::from(content).freeze()),
+        headers: Default::default(),
+    }
+}
+
 pub fn create_kafka_produce_request(content: &[u8]) -> BytesMut {
+    create_kafka_produce_request_with_record(create_record(content))
+}
+
+pub fn create_kafka_produce_request_with_record(record: Record) -> BytesMut {
     let header = RequestHeader::builder()
         .request_api_key(ApiKey::ProduceKey as i16)
         .request_api_version(TEST_KAFKA_API_VERSION)
@@ -78,21 +100,7 @@ pub fn create_kafka_produce_request(content: &[u8]) -> BytesMut {
     let mut encoded = BytesMut::new();
     RecordBatchEncoder::encode(
         &mut encoded,
-        [Record {
-            transactional: false,
-            control: false,
-            partition_leader_epoch: 0,
-            producer_id: 0,
-            producer_epoch: 0,
-            timestamp_type: TimestampType::Creation,
-            offset: 0,
-            sequence: 0,
-            timestamp: 0,
-            key: None,
-            value: Some(BytesMut::from(content).freeze()),
-            headers: Default::default(),
-        }]
-        .iter(),
+        [record].iter(),
         &RecordEncodeOptions {
             version: 2,
             compression: Compression::None,
@@ -133,6 +141,10 @@ pub fn create_kafka_produce_request(content: &[u8]) -> BytesMut {
 }
 
 pub fn create_kafka_fetch_response(content: &[u8]) -> BytesMut {
+    create_kafka_fetch_response_with_record(create_record(content))
+}
+
+pub fn create_kafka_fetch_response_with_record(record: Record) -> BytesMut {
     let header = ResponseHeader::builder()
         .correlation_id(1)
         .unknown_tagged_fields(Default::default())
@@ -142,21 +154,7 @@ pub fn create_kafka_fetch_response(content: &[u8]) -> BytesMut {
     let mut encoded = BytesMut::new();
     RecordBatchEncoder::encode(
         &mut encoded,
-        [Record {
-            transactional: false,
-            control: false,
-            partition_leader_epoch: 0,
-            producer_id: 0,
-            producer_epoch: 0,
-            timestamp_type: TimestampType::Creation,
-            offset: 0,
-            sequence: 0,
-            timestamp: 0,
-            key: None,
-            value: Some(BytesMut::from(content).freeze()),
-            headers: Default::default(),
-        }]
-        .iter(),
+        [record].iter(),
         &RecordEncodeOptions {
             version: 2,
             compression: Compression::None,
@@ -256,11 +254,15 @@ pub async fn json_encrypt_specific_fields(context: &mut Context) -> ockam::Resul
         Default::default(),
         KafkaInletController::stub(),
         true,
-        vec![
-            "field1".to_string(),
-            "field2".to_string(),
-            "field3".to_string(),
-        ],
+        EncryptionRules::new(
+            vec![
+                "field1".to_string(),
+                "field2".to_string(),
+                "field3".to_string(),
+            ],
+            vec![],
+        )
+        .unwrap(),
     );
 
     let encrypted_response = interceptor
@@ -334,11 +336,15 @@ pub async fn json_decrypt_specific_fields(context: &mut Context) -> ockam::Resul
         Default::default(),
         KafkaInletController::stub(),
         true,
-        vec![
-            "field1".to_string(),
-            "field2".to_string(),
-            "field3".to_string(),
-        ],
+        EncryptionRules::new(
+            vec![
+                "field1".to_string(),
+                "field2".to_string(),
+                "field3".to_string(),
+            ],
+            vec![],
+        )
+        .unwrap(),
     );
 
     interceptor.add_request(1, ApiKey::FetchKey, TEST_KAFKA_API_VERSION);
@@ -402,3 +408,249 @@ pub async fn json_decrypt_specific_fields(context: &mut Context) -> ockam::Resul
 
     Ok(())
 }
+
+#[ockam::test]
+pub async fn avro_encrypt_and_decrypt_nested_fields(context: &mut Context) -> ockam::Result<()> {
+    let schema = r#"
+    {
+        "type": "record",
+        "name": "Order",
+        "fields": [
+            { "name": "id", "type": "long" },
+            {
+                "name": "customer",
+                "type": {
+                    "type": "record",
+                    "name": "Customer",
+                    "fields": [
+                        { "name": "name", "type": "string" },
+                        {
+                            "name": "address",
+                            "type": {
+                                "type": "record",
+                                "name": "Address",
+                                "fields": [
+                                    { "name": "street", "type": "string" },
+                                    { "name": "city", "type": "string" }
+                                ]
+                            }
+                        }
+                    ]
+                }
+            }
+        ]
+    }"#;
+    let rule = TopicEncryptionRule::new("topic-name", vec!["customer.address.*".to_string()])
+        .with_format(RecordFormat::Avro {
+            schema: schema.to_string(),
+        });
+
+    // fetch responses only contain the topic uuid, which is nil in the test response
+    let uuid_to_name: TopicUuidMap = Default::default();
+    uuid_to_name.lock().unwrap().insert(
+        "00000000-0000-0000-0000-000000000000".to_string(),
+        "topic-name".to_string(),
+    );
+    let interceptor = InletInterceptorImpl::new(
+        Arc::new(MockKafkaKeyExchangeController {}),
+        uuid_to_name,
+        KafkaInletController::stub(),
+        true,
+        EncryptionRules::new(vec!["id".to_string()], vec![rule]).unwrap(),
+    );
+
+    let order = json!({
+        "id": 1,
+        "customer": {
+            "name": "alice",
+            "address": { "street": "main street", "city": "paris" }
+        }
+    });
+    let avro_schema = apache_avro::Schema::parse_str(schema).unwrap();
+    let avro_record = apache_avro::to_avro_datum(
+        &avro_schema,
+        apache_avro::types::Value::from(order.clone())
+            .resolve(&avro_schema)
+            .unwrap(),
+    )
+    .unwrap();
+
+    let encrypted_request = interceptor
+        .intercept_request(context, create_kafka_produce_request(&avro_record))
+        .await
+        .unwrap();
+
+    let request = parse_produce_request(&encrypted_request);
+    let mut batch_content = request
+        .topic_data
+        .first()
+        .unwrap()
+        .1
+        .partition_data
+        .first()
+        .cloned()
+        .unwrap()
+        .records
+        .unwrap();
+    let records = RecordBatchDecoder::decode(&mut batch_content).unwrap();
+    let record_content = records.first().unwrap().value.clone().unwrap();
+
+    // the broker receives an Avro record where only the address fields are encrypted
+    let json = serde_json::Value::try_from(
+        apache_avro::from_avro_datum(&avro_schema, &mut record_content.as_ref(), None).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(json["id"], json!(1));
+    assert_eq!(json["customer"]["name"], json!("alice"));
+    let street = json["customer"]["address"]["street"].as_str().unwrap();
+    assert_eq!(decode_field_value(street.to_string()), json!("main street"));
+
+    // a field which is not a string can't be encrypted in an Avro record
+    let interceptor_with_invalid_rule = InletInterceptorImpl::new(
+        Arc::new(MockKafkaKeyExchangeController {}),
+        Default::default(),
+        KafkaInletController::stub(),
+        true,
+        EncryptionRules::new(
+            vec![],
+            vec![
+                TopicEncryptionRule::new("topic-name", vec!["id".to_string()]).with_format(
+                    RecordFormat::Avro {
+                        schema: schema.to_string(),
+                    },
+                ),
+            ],
+        )
+        .unwrap(),
+    );
+    assert!(interceptor_with_invalid_rule
+        .intercept_request(context, create_kafka_produce_request(&avro_record))
+        .await
+        .is_err());
+
+    // the consumer receives the original Avro record
+    interceptor.add_request(1, ApiKey::FetchKey, TEST_KAFKA_API_VERSION);
+    let cleartext_response = interceptor
+        .intercept_response(context, create_kafka_fetch_response(&record_content))
+        .await
+        .unwrap();
+
+    let response = parse_fetch_response(&cleartext_response);
+    let partition_data = response
+        .responses
+        .first()
+        .unwrap()
+        .partitions
+        .first()
+        .unwrap();
+    let mut records = partition_data.records.clone().unwrap();
+    let records = RecordBatchDecoder::decode(&mut records).unwrap();
+    let value = records.first().unwrap().value.clone().unwrap();
+    assert_eq!(value.as_ref(), avro_record.as_slice());
+
+    Ok(())
+}
+
+#[ockam::test]
+pub async fn encrypt_and_decrypt_key_and_headers(context: &mut Context) -> ockam::Result<()> {
+    let interceptor = InletInterceptorImpl::new(
+        Arc::new(MockKafkaKeyExchangeController {}),
+        Default::default(),
+        KafkaInletController::stub(),
+        true,
+        EncryptionRules::default()
+            .with_headers_encryption(true)
+            .with_keys_encryption(Some(vec![1; 32])),
+    );
+
+    let mut record = create_record(b"value");
+    record.key = Some(Bytes::from_static(b"user-42"));
+    record.headers.insert(
+        StrBytes::from_static_str("user-id"),
+        Some(Bytes::from_static(b"42")),
+    );
+    record.headers.insert(
+        StrBytes::from_static_str("empty"),
+        Some(Bytes::from_static(b"")),
+    );
+
+    let mut encrypted_keys = vec![];
+    let mut encrypted_records = vec![];
+    for _ in 0..2 {
+        let encrypted_request = interceptor
+            .intercept_request(
+                context,
+                create_kafka_produce_request_with_record(record.clone()),
+            )
+            .await
+            .unwrap();
+
+        let request = parse_produce_request(&encrypted_request);
+        let mut batch_content = request
+            .topic_data
+            .first()
+            .unwrap()
+            .1
+            .partition_data
+            .first()
+            .cloned()
+            .unwrap()
+            .records
+            .unwrap();
+        let records = RecordBatchDecoder::decode(&mut batch_content).unwrap();
+        let encrypted_record = records.first().cloned().unwrap();
+
+        // the header names are visible to the broker, not their values
+        let user_id = encrypted_record
+            .headers
+            .get(&StrBytes::from_static_str("user-id"))
+            .cloned()
+            .unwrap()
+            .unwrap();
+        let user_id: KafkaEncryptedContent = Decoder::new(&user_id).decode().unwrap();
+        assert_eq!(user_id.content, b"encrypted:42");
+        let empty = encrypted_record
+            .headers
+            .get(&StrBytes::from_static_str("empty"))
+            .cloned()
+            .unwrap()
+            .unwrap();
+        let empty: KafkaEncryptedContent = Decoder::new(&empty).decode().unwrap();
+        assert_eq!(empty.content, b"encrypted:");
+
+        let encrypted_key = encrypted_record.key.clone().unwrap();
+        assert_ne!(encrypted_key.as_ref(), b"user-42");
+        encrypted_keys.push(encrypted_key);
+        encrypted_records.push(encrypted_record);
+    }
+
+    // the same key is always encrypted to the same value
+    assert_eq!(encrypted_keys[0], encrypted_keys[1]);
+
+    // the consumer receives the original key and headers
+    interceptor.add_request(1, ApiKey::FetchKey, TEST_KAFKA_API_VERSION);
+    let cleartext_response = interceptor
+        .intercept_response(
+            context,
+            create_kafka_fetch_response_with_record(encrypted_records.remove(0)),
+        )
+        .await
+        .unwrap();
+
+    let response = parse_fetch_response(&cleartext_response);
+    let partition_data = response
+        .responses
+        .first()
+        .unwrap()
+        .partitions
+        .first()
+        .unwrap();
+    let mut records = partition_data.records.clone().unwrap();
+    let records = RecordBatchDecoder::decode(&mut records).unwrap();
+    let decrypted_record = records.first().unwrap();
+    assert_eq!(decrypted_record.key, record.key);
+    assert_eq!(decrypted_record.headers, record.headers);
+    assert_eq!(decrypted_record.value, record.value);
+
+    Ok(())
+}
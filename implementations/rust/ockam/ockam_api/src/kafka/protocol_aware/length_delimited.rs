@@ -36,11 +36,10 @@ impl KafkaMessageDecoder {
                     ));
                 }
                 if current_message_length == 0 {
-                    return Err(Error::new(
-                        Origin::Transport,
-                        Kind::Io,
-                        "kafka message of size 0",
-                    ));
+                    // empty messages are only valid as raw SASL tokens,
+                    // the interceptors reject them otherwise
+                    kafka_messages.push(BytesMut::new());
+                    continue;
                 }
                 self.buffer = Some(BytesMut::with_capacity(incoming.remaining()));
                 self.current_message_length = current_message_length;
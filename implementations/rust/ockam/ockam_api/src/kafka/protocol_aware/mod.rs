@@ -15,9 +15,11 @@ mod tests;
 
 pub(crate) mod inlet;
 mod length_delimited;
+mod sasl;
 pub(super) mod utils;
 
 use crate::kafka::protocol_aware::length_delimited::{length_encode, KafkaMessageDecoder};
+pub(crate) use crate::kafka::protocol_aware::sasl::SaslRawExchange;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_transport_tcp::{Direction, PortalInterceptor};
 
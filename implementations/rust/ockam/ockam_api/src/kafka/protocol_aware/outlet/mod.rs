@@ -1,6 +1,6 @@
 use crate::kafka::protocol_aware::{
     CorrelationId, KafkaMessageInterceptor, KafkaMessageInterceptorWrapper, RequestInfo,
-    MAX_KAFKA_MESSAGE_SIZE,
+    SaslRawExchange, MAX_KAFKA_MESSAGE_SIZE,
 };
 use crate::kafka::KafkaOutletController;
 use ockam_core::compat::collections::HashMap;
@@ -47,6 +47,7 @@ pub(crate) struct OutletInterceptorImpl {
     request_map: Arc<Mutex<HashMap<CorrelationId, RequestInfo>>>,
     outlet_controller: KafkaOutletController,
     flow_control_id: FlowControlId,
+    sasl: SaslRawExchange,
 }
 
 impl OutletInterceptorImpl {
@@ -58,6 +59,7 @@ impl OutletInterceptorImpl {
             request_map: Arc::new(Mutex::new(HashMap::new())),
             outlet_controller,
             flow_control_id,
+            sasl: Default::default(),
         }
     }
 }
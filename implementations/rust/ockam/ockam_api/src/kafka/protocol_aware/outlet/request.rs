@@ -1,7 +1,8 @@
 use crate::kafka::protocol_aware::outlet::OutletInterceptorImpl;
+use crate::kafka::protocol_aware::utils::decode_body;
 use crate::kafka::protocol_aware::{InterceptError, KafkaMessageRequestInterceptor, RequestInfo};
 use bytes::BytesMut;
-use kafka_protocol::messages::{ApiKey, RequestHeader};
+use kafka_protocol::messages::{ApiKey, RequestHeader, SaslHandshakeRequest};
 use kafka_protocol::protocol::buf::ByteBuf;
 use kafka_protocol::protocol::Decodable;
 use ockam_core::async_trait;
@@ -21,6 +22,15 @@ impl KafkaMessageRequestInterceptor for OutletInterceptorImpl {
         // for more information see:
         // https://cwiki.apache.org/confluence/display/KAFKA/A+Guide+To+The+Kafka+Protocol#AGuideToTheKafkaProtocol-Requests
 
+        // raw SASL tokens don't have any kafka header
+        if self.sasl.next_request_is_raw() {
+            return Ok(original);
+        }
+        if original.len() < 4 {
+            warn!("kafka request too short");
+            return Err(InterceptError::InvalidData);
+        }
+
         let mut buffer = original.peek_bytes(0..original.len());
         let api_key_num = buffer.peek_bytes(0..2).try_get_i16()?;
         let api_key = ApiKey::try_from(api_key_num).map_err(|_| {
@@ -48,6 +58,19 @@ impl KafkaMessageRequestInterceptor for OutletInterceptorImpl {
             api_key
         );
 
+        // after a v0 SASL handshake, the SASL tokens are sent without any kafka header
+        if api_key == ApiKey::SaslHandshakeKey && header.request_api_version == 0 {
+            let request: SaslHandshakeRequest = decode_body(&mut buffer, 0)?;
+            self.sasl.handshake_requested(request.mechanism.as_str())?;
+            self.request_map.lock().unwrap().insert(
+                header.correlation_id,
+                RequestInfo {
+                    request_api_key: ApiKey::SaslHandshakeKey,
+                    request_api_version: header.request_api_version,
+                },
+            );
+        }
+
         // we only need to keep track of the metadata request/response
         // to dynamically create an outlet for each broker
         if api_key == ApiKey::MetadataKey {
@@ -2,7 +2,7 @@ use crate::kafka::protocol_aware::outlet::OutletInterceptorImpl;
 use crate::kafka::protocol_aware::utils::decode_body;
 use crate::kafka::protocol_aware::{InterceptError, KafkaMessageResponseInterceptor};
 use bytes::BytesMut;
-use kafka_protocol::messages::{ApiKey, MetadataResponse, ResponseHeader};
+use kafka_protocol::messages::{ApiKey, MetadataResponse, ResponseHeader, SaslHandshakeResponse};
 use kafka_protocol::protocol::buf::ByteBuf;
 use kafka_protocol::protocol::Decodable;
 use ockam_core::async_trait;
@@ -15,6 +15,15 @@ impl KafkaMessageResponseInterceptor for OutletInterceptorImpl {
         context: &mut Context,
         mut original: BytesMut,
     ) -> Result<BytesMut, InterceptError> {
+        // raw SASL tokens don't have any kafka header
+        if self.sasl.next_response_is_raw() {
+            return Ok(original);
+        }
+        if original.len() < 4 {
+            warn!("kafka response too short");
+            return Err(InterceptError::InvalidData);
+        }
+
         let mut buffer = original.peek_bytes(0..original.len());
 
         // we can/need to decode only mapped requests
@@ -52,6 +61,14 @@ impl KafkaMessageResponseInterceptor for OutletInterceptorImpl {
                 request_info.request_api_key
             );
 
+            if request_info.request_api_key == ApiKey::SaslHandshakeKey {
+                let response: SaslHandshakeResponse =
+                    decode_body(&mut buffer, request_info.request_api_version)?;
+                if response.error_code == 0 {
+                    self.sasl.handshake_completed();
+                }
+            }
+
             // the responses of metadata request contain the list of brokers addresses,
             // we override them with the outlets addresses
             if request_info.request_api_key == ApiKey::MetadataKey {
@@ -0,0 +1,95 @@
+use crate::kafka::protocol_aware::InterceptError;
+use ockam_core::compat::sync::{Arc, Mutex};
+
+/// Tracks the SASL authentication of a kafka connection.
+///
+/// After a successful `SaslHandshake` v0, the SASL tokens are exchanged as raw
+/// length-delimited frames without any kafka header, until the authentication completes.
+/// These frames must be passed through without being parsed.
+/// Since `SaslHandshake` v1 the tokens are sent in `SaslAuthenticate` requests, which are
+/// regular kafka messages and don't need any specific handling.
+#[derive(Clone, Default)]
+pub(crate) struct SaslRawExchange {
+    state: Arc<Mutex<SaslRawExchangeState>>,
+}
+
+#[derive(Default)]
+struct SaslRawExchangeState {
+    /// Number of tokens round trips of the mechanism requested in the handshake
+    requested_round_trips: Option<u8>,
+    pending_requests: u8,
+    pending_responses: u8,
+}
+
+impl SaslRawExchange {
+    /// Register a `SaslHandshake` v0 request.
+    /// Only the mechanisms with a fixed number of round trips can be followed.
+    pub(crate) fn handshake_requested(&self, mechanism: &str) -> Result<(), InterceptError> {
+        let round_trips = match mechanism {
+            "PLAIN" | "OAUTHBEARER" => 1,
+            "SCRAM-SHA-256" | "SCRAM-SHA-512" => 2,
+            _ => {
+                warn!("the SASL mechanism {mechanism} is not supported with SaslHandshake v0");
+                return Err("Unsupported SASL mechanism for SaslHandshake v0".into());
+            }
+        };
+        self.state.lock().unwrap().requested_round_trips = Some(round_trips);
+        Ok(())
+    }
+
+    /// Register a successful `SaslHandshake` v0 response,
+    /// the next messages are raw SASL tokens
+    pub(crate) fn handshake_completed(&self) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(round_trips) = state.requested_round_trips.take() {
+            state.pending_requests = round_trips;
+            state.pending_responses = round_trips;
+        }
+    }
+
+    /// Return true if the next request is a raw SASL token
+    pub(crate) fn next_request_is_raw(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.pending_requests > 0 {
+            state.pending_requests -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Return true if the next response is a raw SASL token
+    pub(crate) fn next_response_is_raw(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.pending_responses > 0 {
+            state.pending_responses -= 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_tokens_follow_a_successful_handshake() {
+        let sasl = SaslRawExchange::default();
+        assert!(!sasl.next_request_is_raw());
+
+        sasl.handshake_requested("SCRAM-SHA-256").unwrap();
+        assert!(!sasl.next_request_is_raw());
+
+        sasl.handshake_completed();
+        for _ in 0..2 {
+            assert!(sasl.next_request_is_raw());
+            assert!(sasl.next_response_is_raw());
+        }
+        assert!(!sasl.next_request_is_raw());
+        assert!(!sasl.next_response_is_raw());
+
+        assert!(sasl.handshake_requested("GSSAPI").is_err());
+    }
+}
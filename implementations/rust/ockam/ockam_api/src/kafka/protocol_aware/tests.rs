@@ -72,7 +72,7 @@ mod test {
             Default::default(),
             inlet_map,
             true,
-            vec![],
+            Default::default(),
         );
 
         let mut correlation_id = 0;
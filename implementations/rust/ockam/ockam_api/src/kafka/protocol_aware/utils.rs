@@ -1,8 +1,11 @@
 use crate::kafka::protocol_aware::InterceptError;
 use bytes::BytesMut;
-use kafka_protocol::messages::ApiKey;
+use kafka_protocol::messages::{
+    ApiKey, ApiVersionsRequest, FetchRequest, FindCoordinatorRequest, MetadataRequest,
+    ProduceRequest, SaslHandshakeRequest,
+};
 use kafka_protocol::protocol::buf::ByteBuf;
-use kafka_protocol::protocol::{Decodable, Encodable};
+use kafka_protocol::protocol::{Decodable, Encodable, Message};
 
 pub(crate) fn decode_body<T, B>(buffer: &mut B, api_version: i16) -> Result<T, InterceptError>
 where
@@ -52,3 +55,20 @@ pub(crate) fn encode_response<H: Encodable, T: Encodable>(
 
     Ok(buffer)
 }
+
+/// Return the range of versions of the messages decoded by the interceptors,
+/// or `None` when the messages of this api are not decoded
+pub(crate) fn supported_versions(api_key: ApiKey) -> Option<(i16, i16)> {
+    let versions = match api_key {
+        // records are always re-encoded as record batches v2,
+        // which are used since Produce v3 and Fetch v4
+        ApiKey::ProduceKey => return Some((3, ProduceRequest::VERSIONS.max)),
+        ApiKey::FetchKey => return Some((4, FetchRequest::VERSIONS.max)),
+        ApiKey::MetadataKey => MetadataRequest::VERSIONS,
+        ApiKey::FindCoordinatorKey => FindCoordinatorRequest::VERSIONS,
+        ApiKey::ApiVersionsKey => ApiVersionsRequest::VERSIONS,
+        ApiKey::SaslHandshakeKey => SaslHandshakeRequest::VERSIONS,
+        _ => return None,
+    };
+    Some((versions.min, versions.max))
+}
@@ -0,0 +1,126 @@
+use crate::ApiError;
+use aes_gcm::aead::Aead;
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use hmac::{Hmac, Mac};
+use ockam_core::Result;
+use sha2::Sha256;
+
+/// Record header containing the secret used to encrypt the record key,
+/// itself encrypted for the consumer
+pub(crate) const RECORD_KEY_SECRET_HEADER: &str = "ockam-record-key-secret";
+
+/// Record header marking records whose headers values are encrypted.
+/// Its value is empty, since null header values can't be decoded by the `kafka-protocol` crate
+pub(crate) const ENCRYPTED_HEADERS_HEADER: &str = "ockam-encrypted-headers";
+
+const KEY_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+/// Secret used to deterministically encrypt the keys of the records of a topic.
+///
+/// It is derived from a secret shared by the inlets producing records, so that all of them
+/// encrypt a given record key to the same value, even after a restart. The nonce is derived from the record key with HMAC-SHA256 before encrypting it
+/// with AES-256-GCM (synthetic IV). A given record key is then always encrypted to the
+/// same bytes, which preserves key based partitioning and log compaction.
+#[derive(Clone)]
+pub(crate) struct RecordKeySecret {
+    mac_key: [u8; KEY_LEN],
+    encryption_key: [u8; KEY_LEN],
+}
+
+impl RecordKeySecret {
+    /// Derive the secret of a topic from the secret shared by the inlets
+    pub(crate) fn derive(shared_secret: &[u8], topic: &str) -> Self {
+        Self {
+            mac_key: Self::derive_key(shared_secret, "mac", topic),
+            encryption_key: Self::derive_key(shared_secret, "encryption", topic),
+        }
+    }
+
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() != 2 * KEY_LEN {
+            return Err(ApiError::core("invalid record key secret length"));
+        }
+        let (mac_key, encryption_key) = bytes.split_at(KEY_LEN);
+        Ok(Self {
+            mac_key: mac_key.try_into().unwrap(),
+            encryption_key: encryption_key.try_into().unwrap(),
+        })
+    }
+
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        [self.mac_key, self.encryption_key].concat()
+    }
+
+    /// Encrypt a record key, the result is `nonce || ciphertext`
+    pub(crate) fn encrypt(&self, key: &[u8]) -> Result<Vec<u8>> {
+        let nonce = self.synthetic_nonce(key);
+        let ciphertext = Aes256Gcm::new(&self.encryption_key.into())
+            .encrypt(Nonce::from_slice(&nonce), key)
+            .map_err(|_| ApiError::core("cannot encrypt the record key"))?;
+        Ok([nonce.as_slice(), &ciphertext].concat())
+    }
+
+    pub(crate) fn decrypt(&self, encrypted_key: &[u8]) -> Result<Vec<u8>> {
+        if encrypted_key.len() < NONCE_LEN {
+            return Err(ApiError::core("the encrypted record key is too short"));
+        }
+        let (nonce, ciphertext) = encrypted_key.split_at(NONCE_LEN);
+        let key = Aes256Gcm::new(&self.encryption_key.into())
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| ApiError::core("cannot decrypt the record key"))?;
+        if self.synthetic_nonce(&key) != nonce {
+            return Err(ApiError::core(
+                "the record key nonce doesn't match its content",
+            ));
+        }
+        Ok(key)
+    }
+
+    fn derive_key(shared_secret: &[u8], purpose: &str, topic: &str) -> [u8; KEY_LEN] {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(shared_secret)
+            .expect("HMAC accepts keys of any length");
+        mac.update(b"ockam-kafka-record-key-");
+        mac.update(purpose.as_bytes());
+        mac.update(&[0]);
+        mac.update(topic.as_bytes());
+        mac.finalize().into_bytes().into()
+    }
+
+    fn synthetic_nonce(&self, key: &[u8]) -> [u8; NONCE_LEN] {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.mac_key)
+            .expect("HMAC accepts keys of any length");
+        mac.update(key);
+        let tag = mac.finalize().into_bytes();
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce.copy_from_slice(&tag[..NONCE_LEN]);
+        nonce
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_keys_are_encrypted_deterministically() {
+        let secret = RecordKeySecret::derive(&[1; 32], "topic");
+
+        let encrypted = secret.encrypt(b"user-42").unwrap();
+        assert_ne!(encrypted, b"user-42");
+        assert_eq!(encrypted, secret.encrypt(b"user-42").unwrap());
+        assert_ne!(encrypted, secret.encrypt(b"user-43").unwrap());
+
+        let secret = RecordKeySecret::from_bytes(&secret.to_bytes()).unwrap();
+        assert_eq!(secret.decrypt(&encrypted).unwrap(), b"user-42");
+
+        // the secret only depends on the shared secret and the topic
+        let same_secret = RecordKeySecret::derive(&[1; 32], "topic");
+        assert_eq!(encrypted, same_secret.encrypt(b"user-42").unwrap());
+        let other_topic = RecordKeySecret::derive(&[1; 32], "other-topic");
+        assert!(other_topic.decrypt(&encrypted).is_err());
+        let other_secret = RecordKeySecret::derive(&[2; 32], "topic");
+        assert!(other_secret.decrypt(&encrypted).is_err());
+        assert!(RecordKeySecret::from_bytes(&[0u8; 10]).is_err());
+    }
+}
@@ -5,10 +5,12 @@ use bytes::{Buf, BufMut, BytesMut};
 use indexmap::IndexMap;
 use kafka_protocol::messages::produce_request::{PartitionProduceData, TopicProduceData};
 use kafka_protocol::messages::{
+    api_versions_response::ApiVersion,
     fetch_request::{FetchPartition, FetchTopic},
     fetch_response::FetchableTopicResponse,
     fetch_response::PartitionData,
-    ApiKey, BrokerId, FetchRequest, FetchResponse, ProduceRequest, RequestHeader, ResponseHeader,
+    ApiKey, ApiVersionsRequest, ApiVersionsResponse, BrokerId, FetchRequest, FetchResponse,
+    ProduceRequest, RequestHeader, ResponseHeader, SaslHandshakeRequest, SaslHandshakeResponse,
     TopicName,
 };
 use kafka_protocol::protocol::Builder;
@@ -28,7 +30,7 @@ use uuid::Uuid;
 
 use crate::kafka::key_exchange::controller::KafkaKeyExchangeControllerImpl;
 use crate::kafka::protocol_aware::inlet::KafkaInletInterceptorFactory;
-use crate::kafka::protocol_aware::utils::{encode_request, encode_response};
+use crate::kafka::protocol_aware::utils::{encode_request, encode_response, supported_versions};
 use crate::kafka::{ConsumerPublishing, ConsumerResolution, KafkaInletController};
 use crate::test_utils::{NodeManagerHandle, TestNode};
 use ockam::compat::tokio::io::DuplexStream;
@@ -118,7 +120,7 @@ async fn create_kafka_service(
             secure_channel_controller,
             inlet_controller,
             true,
-            vec![],
+            Default::default(),
         )),
         Arc::new(AllowAll),
         Arc::new(AllowAll),
@@ -258,6 +260,241 @@ async fn producer__flow_with_mock_kafka__content_encryption_and_decryption(
     Ok(())
 }
 
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 60_000)]
+async fn sasl__handshake_v0_with_raw_tokens__passed_through(
+    context: &mut Context,
+) -> ockam::Result<()> {
+    TestNode::clean().await?;
+    let handle = crate::test_utils::start_manager_for_tests(context, None, None).await?;
+
+    let bootstrap_port = create_kafka_service(
+        context,
+        &handle,
+        "kafka_sasl_listener".into(),
+        "kafka_sasl_outlet".into(),
+    )
+    .await?;
+
+    let mut mock_kafka = TcpServerSimulator::start("127.0.0.1:0").await;
+    handle
+        .tcp
+        .create_outlet(
+            "kafka_sasl_outlet",
+            HostnamePort::new("127.0.0.1", mock_kafka.port),
+            TcpOutletOptions::new(),
+        )
+        .await?;
+
+    let mut kafka_client_connection = TcpStream::connect(format!("127.0.0.1:{bootstrap_port}"))
+        .await
+        .unwrap();
+
+    send_kafka_request(
+        &mut kafka_client_connection,
+        request_header(ApiKey::SaslHandshakeKey, 0, 1),
+        SaslHandshakeRequest::builder()
+            .mechanism(StrBytes::from_static_str("PLAIN"))
+            .build()
+            .unwrap(),
+        ApiKey::SaslHandshakeKey,
+        0,
+    )
+    .await;
+    let request = read_kafka_request::<&mut DuplexStream, RequestHeader, SaslHandshakeRequest>(
+        mock_kafka.stream(),
+        ApiKey::SaslHandshakeKey,
+        0,
+    )
+    .await;
+    assert_eq!(request.mechanism.as_str(), "PLAIN");
+
+    send_kafka_response(
+        mock_kafka.stream(),
+        response_header(1),
+        SaslHandshakeResponse::builder()
+            .error_code(0)
+            .mechanisms(vec![StrBytes::from_static_str("PLAIN")])
+            .build()
+            .unwrap(),
+        ApiKey::SaslHandshakeKey,
+        0,
+    )
+    .await;
+    let response = read_kafka_response::<&mut TcpStream, ResponseHeader, SaslHandshakeResponse>(
+        &mut kafka_client_connection,
+        ApiKey::SaslHandshakeKey,
+        0,
+    )
+    .await;
+    assert_eq!(response.error_code, 0);
+
+    // the SASL PLAIN token is sent without any kafka header,
+    // and the broker replies with an empty token
+    let token = b"\0alice\0secret";
+    send_packet(&mut kafka_client_connection, token).await;
+    assert_eq!(read_packet(mock_kafka.stream()).await, token);
+    send_packet(mock_kafka.stream(), &[]).await;
+    assert!(read_packet(&mut kafka_client_connection).await.is_empty());
+
+    // the next messages are regular kafka messages
+    send_kafka_request(
+        &mut kafka_client_connection,
+        request_header(ApiKey::ApiVersionsKey, 3, 2),
+        api_versions_request(),
+        ApiKey::ApiVersionsKey,
+        3,
+    )
+    .await;
+    let _request = read_kafka_request::<&mut DuplexStream, RequestHeader, ApiVersionsRequest>(
+        mock_kafka.stream(),
+        ApiKey::ApiVersionsKey,
+        3,
+    )
+    .await;
+
+    mock_kafka.destroy_and_wait().await;
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 60_000)]
+async fn api_versions__newer_broker_versions__restricted_to_supported_versions(
+    context: &mut Context,
+) -> ockam::Result<()> {
+    TestNode::clean().await?;
+    let handle = crate::test_utils::start_manager_for_tests(context, None, None).await?;
+
+    let bootstrap_port = create_kafka_service(
+        context,
+        &handle,
+        "kafka_versions_listener".into(),
+        "kafka_versions_outlet".into(),
+    )
+    .await?;
+
+    let mut mock_kafka = TcpServerSimulator::start("127.0.0.1:0").await;
+    handle
+        .tcp
+        .create_outlet(
+            "kafka_versions_outlet",
+            HostnamePort::new("127.0.0.1", mock_kafka.port),
+            TcpOutletOptions::new(),
+        )
+        .await?;
+
+    let mut kafka_client_connection = TcpStream::connect(format!("127.0.0.1:{bootstrap_port}"))
+        .await
+        .unwrap();
+
+    send_kafka_request(
+        &mut kafka_client_connection,
+        request_header(ApiKey::ApiVersionsKey, 3, 1),
+        api_versions_request(),
+        ApiKey::ApiVersionsKey,
+        3,
+    )
+    .await;
+    let _request = read_kafka_request::<&mut DuplexStream, RequestHeader, ApiVersionsRequest>(
+        mock_kafka.stream(),
+        ApiKey::ApiVersionsKey,
+        3,
+    )
+    .await;
+
+    // the broker supports versions which are more recent than the interceptor ones
+    // and an api which is unknown to the interceptor
+    let mut api_keys = IndexMap::new();
+    for (api_key, min_version, max_version) in [
+        (ApiKey::ProduceKey as i16, 0, 100),
+        (ApiKey::FetchKey as i16, 0, 100),
+        (ApiKey::ListOffsetsKey as i16, 0, 100),
+        (1000, 0, 1),
+    ] {
+        api_keys.insert(
+            api_key,
+            ApiVersion::builder()
+                .min_version(min_version)
+                .max_version(max_version)
+                .unknown_tagged_fields(Default::default())
+                .build()
+                .unwrap(),
+        );
+    }
+    send_kafka_response(
+        mock_kafka.stream(),
+        response_header(1),
+        ApiVersionsResponse::builder()
+            .error_code(0)
+            .api_keys(api_keys)
+            .throttle_time_ms(0)
+            .supported_features(Default::default())
+            .finalized_features_epoch(0)
+            .finalized_features(Default::default())
+            .unknown_tagged_fields(Default::default())
+            .build()
+            .unwrap(),
+        ApiKey::ApiVersionsKey,
+        3,
+    )
+    .await;
+
+    let response = read_kafka_response::<&mut TcpStream, ResponseHeader, ApiVersionsResponse>(
+        &mut kafka_client_connection,
+        ApiKey::ApiVersionsKey,
+        3,
+    )
+    .await;
+
+    let versions = |api_key: ApiKey| {
+        response
+            .api_keys
+            .get(&(api_key as i16))
+            .map(|v| (v.min_version, v.max_version))
+    };
+    assert_eq!(
+        versions(ApiKey::ProduceKey),
+        supported_versions(ApiKey::ProduceKey)
+    );
+    assert_eq!(
+        versions(ApiKey::FetchKey),
+        supported_versions(ApiKey::FetchKey)
+    );
+    assert_eq!(versions(ApiKey::ListOffsetsKey), Some((0, 100)));
+    assert!(response.api_keys.get(&1000).is_none());
+
+    mock_kafka.destroy_and_wait().await;
+    Ok(())
+}
+
+fn request_header(api_key: ApiKey, api_version: i16, correlation_id: i32) -> RequestHeader {
+    RequestHeader::builder()
+        .request_api_key(api_key as i16)
+        .request_api_version(api_version)
+        .correlation_id(correlation_id)
+        .client_id(Some(StrBytes::from_static_str("my-client-id")))
+        .unknown_tagged_fields(Default::default())
+        .build()
+        .unwrap()
+}
+
+fn response_header(correlation_id: i32) -> ResponseHeader {
+    ResponseHeader::builder()
+        .correlation_id(correlation_id)
+        .unknown_tagged_fields(Default::default())
+        .build()
+        .unwrap()
+}
+
+fn api_versions_request() -> ApiVersionsRequest {
+    ApiVersionsRequest::builder()
+        .client_software_name(StrBytes::from_static_str("my-client"))
+        .client_software_version(StrBytes::from_static_str("1.0.0"))
+        .unknown_tagged_fields(Default::default())
+        .build()
+        .unwrap()
+}
+
 async fn simulate_kafka_producer_and_read_request(
     producer_bootstrap_port: u16,
     producer_mock_kafka: &mut TcpServerSimulator,
@@ -270,6 +507,7 @@ async fn simulate_kafka_producer_and_read_request(
     read_kafka_request::<&mut DuplexStream, RequestHeader, ProduceRequest>(
         producer_mock_kafka.stream(),
         ApiKey::ProduceKey,
+        TEST_KAFKA_API_VERSION,
     )
     .await
 }
@@ -332,7 +570,14 @@ async fn send_kafka_produce_request(stream: &mut TcpStream) {
         .build()
         .unwrap();
 
-    send_kafka_request(stream, header, request, ApiKey::ProduceKey).await;
+    send_kafka_request(
+        stream,
+        header,
+        request,
+        ApiKey::ProduceKey,
+        TEST_KAFKA_API_VERSION,
+    )
+    .await;
 }
 
 // this is needed in order to make the consumer create the relays to the secure
@@ -366,17 +611,19 @@ async fn simulate_kafka_consumer_and_read_response(
             .await
             .unwrap();
     send_kafka_fetch_request(&mut kafka_client_connection).await;
-    let _fetch_request: FetchRequest = read_kafka_request::<
-        &mut DuplexStream,
-        RequestHeader,
-        FetchRequest,
-    >(mock_kafka_connection.stream(), ApiKey::FetchKey)
-    .await;
+    let _fetch_request: FetchRequest =
+        read_kafka_request::<&mut DuplexStream, RequestHeader, FetchRequest>(
+            mock_kafka_connection.stream(),
+            ApiKey::FetchKey,
+            TEST_KAFKA_API_VERSION,
+        )
+        .await;
 
     send_kafka_fetch_response(mock_kafka_connection.stream(), producer_request).await;
     read_kafka_response::<&mut TcpStream, ResponseHeader, FetchResponse>(
         &mut kafka_client_connection,
         ApiKey::FetchKey,
+        TEST_KAFKA_API_VERSION,
     )
     .await
 }
@@ -432,6 +679,7 @@ async fn send_kafka_fetch_response<S: AsyncWriteExt + Unpin>(
             .build()
             .unwrap(),
         ApiKey::FetchKey,
+        TEST_KAFKA_API_VERSION,
     )
     .await;
 }
@@ -478,49 +726,51 @@ async fn send_kafka_fetch_request(stream: &mut TcpStream) {
             .build()
             .unwrap(),
         ApiKey::FetchKey,
+        TEST_KAFKA_API_VERSION,
     )
     .await;
 }
 
 async fn send_kafka_request<S: AsyncWriteExt + Unpin, H: KafkaEncodable, T: KafkaEncodable>(
-    mut stream: S,
+    stream: S,
     header: H,
     body: T,
     api_key: ApiKey,
+    api_version: i16,
 ) {
-    let encoded = encode_request(&header, &body, TEST_KAFKA_API_VERSION, api_key).unwrap();
-
-    let mut request_buffer = BytesMut::new();
-    request_buffer.put_u32(encoded.len() as u32);
-    request_buffer.put_slice(&encoded);
-
+    let encoded = encode_request(&header, &body, api_version, api_key).unwrap();
     trace!("send_kafka_request...");
-    stream.write_all(&request_buffer).await.unwrap();
-    stream.flush().await.unwrap();
+    send_packet(stream, &encoded).await;
     trace!("send_kafka_request...done");
 }
 
 async fn send_kafka_response<S: AsyncWriteExt + Unpin, H: KafkaEncodable, T: KafkaEncodable>(
-    mut stream: S,
+    stream: S,
     header: H,
     body: T,
     api_key: ApiKey,
+    api_version: i16,
 ) {
-    let encoded = encode_response(&header, &body, TEST_KAFKA_API_VERSION, api_key).unwrap();
+    let encoded = encode_response(&header, &body, api_version, api_key).unwrap();
+    trace!("send_kafka_response...");
+    send_packet(stream, &encoded).await;
+    trace!("send_kafka_response...done");
+}
 
-    let mut request_buffer = BytesMut::new();
-    request_buffer.put_u32(encoded.len() as u32);
-    request_buffer.put_slice(&encoded);
+/// Send a length-delimited packet
+async fn send_packet<S: AsyncWriteExt + Unpin>(mut stream: S, content: &[u8]) {
+    let mut buffer = BytesMut::new();
+    buffer.put_u32(content.len() as u32);
+    buffer.put_slice(content);
 
-    trace!("send_kafka_response...");
-    stream.write_all(&request_buffer).await.unwrap();
+    stream.write_all(&buffer).await.unwrap();
     stream.flush().await.unwrap();
-    trace!("send_kafka_response...done");
 }
 
 async fn read_kafka_request<S: AsyncReadExt + Unpin, H: KafkaDecodable, T: KafkaDecodable>(
     mut stream: S,
     api_key: ApiKey,
+    api_version: i16,
 ) -> T {
     trace!("read_kafka_request...");
     let header_and_request_buffer = read_packet(&mut stream).await;
@@ -528,10 +778,10 @@ async fn read_kafka_request<S: AsyncReadExt + Unpin, H: KafkaDecodable, T: Kafka
 
     let _header = H::decode(
         &mut header_and_request_buffer,
-        api_key.request_header_version(TEST_KAFKA_API_VERSION),
+        api_key.request_header_version(api_version),
     )
     .unwrap();
-    let request = T::decode(&mut header_and_request_buffer, TEST_KAFKA_API_VERSION).unwrap();
+    let request = T::decode(&mut header_and_request_buffer, api_version).unwrap();
     trace!("read_kafka_request...done");
     request
 }
@@ -539,6 +789,7 @@ async fn read_kafka_request<S: AsyncReadExt + Unpin, H: KafkaDecodable, T: Kafka
 async fn read_kafka_response<S: AsyncReadExt + Unpin, H: KafkaDecodable, T: KafkaDecodable>(
     mut stream: S,
     api_key: ApiKey,
+    api_version: i16,
 ) -> T {
     trace!("read_kafka_response...");
     let header_and_request_buffer = read_packet(&mut stream).await;
@@ -546,15 +797,15 @@ async fn read_kafka_response<S: AsyncReadExt + Unpin, H: KafkaDecodable, T: Kafk
 
     let _header = H::decode(
         &mut header_and_request_buffer,
-        api_key.response_header_version(TEST_KAFKA_API_VERSION),
+        api_key.response_header_version(api_version),
     )
     .unwrap();
-    let request = T::decode(&mut header_and_request_buffer, TEST_KAFKA_API_VERSION).unwrap();
+    let request = T::decode(&mut header_and_request_buffer, api_version).unwrap();
     trace!("read_kafka_response...done");
     request
 }
 
-async fn read_packet<S: AsyncReadExt + Unpin>(stream: &mut S) -> [u8; 1024] {
+async fn read_packet<S: AsyncReadExt + Unpin>(stream: &mut S) -> Vec<u8> {
     trace!("read_packet...");
     let size = {
         let mut length_buffer = [0; 4];
@@ -565,9 +816,9 @@ async fn read_packet<S: AsyncReadExt + Unpin>(stream: &mut S) -> [u8; 1024] {
     };
     info!("incoming message size: {size}");
 
-    let mut header_and_request_buffer = [0; 1024];
+    let mut header_and_request_buffer = vec![0; size as usize];
     let read = stream
-        .read_exact(&mut header_and_request_buffer[0..size as usize])
+        .read_exact(&mut header_and_request_buffer)
         .await
         .unwrap();
     assert_eq!(size as usize, read);
@@ -339,7 +339,7 @@ async fn setup_only_worker(context: &mut Context, handle: &NodeManagerHandle) ->
                 Default::default(),
                 inlet_map,
                 true,
-                vec![],
+                Default::default(),
             )),
             TEST_MAX_KAFKA_MESSAGE_SIZE,
         )),
@@ -440,7 +440,7 @@ async fn kafka_portal_worker__metadata_exchange__response_changed(
                 Default::default(),
                 inlet_map.clone(),
                 true,
-                vec![],
+                Default::default(),
             )),
             MAX_KAFKA_MESSAGE_SIZE,
         )),
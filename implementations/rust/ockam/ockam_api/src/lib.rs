@@ -28,11 +28,13 @@ pub mod echoer;
 pub mod enroll;
 pub mod error;
 pub mod hop;
+pub mod http_interceptor;
 pub mod kafka;
 pub mod minicbor_url;
 pub mod nodes;
 pub mod okta;
 pub mod port_range;
+pub mod postgres;
 pub mod session;
 pub mod uppercase;
 mod version;
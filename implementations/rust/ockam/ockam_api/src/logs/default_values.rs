@@ -55,3 +55,9 @@ pub(crate) const DEFAULT_FOREGROUND_LOG_EXPORT_PORTAL_CUTOFF: Duration = Duratio
 // Maximum time for sending span batches when using a portal
 pub(crate) const DEFAULT_FOREGROUND_SPAN_EXPORT_PORTAL_CUTOFF: Duration =
     Duration::from_millis(300);
+
+// Maximum time between the export of metrics
+pub(crate) const DEFAULT_METRICS_EXPORT_INTERVAL: Duration = Duration::from_secs(30);
+
+// Maximum time for exporting a batch of metrics
+pub(crate) const DEFAULT_METRICS_EXPORT_TIMEOUT: Duration = Duration::from_secs(5);
@@ -117,6 +117,22 @@ pub(crate) const OCKAM_FOREGROUND_LOG_EXPORT_PORTAL_CUTOFF: &str =
 pub(crate) const OCKAM_FOREGROUND_SPAN_EXPORT_PORTAL_CUTOFF: &str =
     "OCKAM_FOREGROUND_SPAN_EXPORT_PORTAL_CUTOFF";
 
+///
+/// METRICS CONFIGURATION
+///
+
+/// Delay between the export of 2 batches of metrics.
+/// Accepted values, see DurationVar. For example: 30s
+pub(crate) const OCKAM_METRICS_EXPORT_INTERVAL: &str = "OCKAM_METRICS_EXPORT_INTERVAL";
+
+/// Timeout for trying to export a batch of metrics to the endpoint.
+/// Accepted values, see DurationVar. For example: 500ms
+pub(crate) const OCKAM_METRICS_EXPORT_TIMEOUT: &str = "OCKAM_METRICS_EXPORT_TIMEOUT";
+
+/// Write metrics locally instead of sending them to the OpenTelemetry collector.
+/// Accepted values, see MetricsOutput. For example: stdout, /tmp/metrics.txt
+pub(crate) const OCKAM_METRICS_OUTPUT: &str = "OCKAM_METRICS_OUTPUT";
+
 ///
 /// OPENTELEMETRY COLLECTOR ERRORS CONFIGURATION
 ///
@@ -3,9 +3,9 @@ use crate::cli_state::TcpInlet;
 use crate::config::UrlVar;
 use crate::logs::default_values::*;
 use crate::logs::env_variables::*;
-use crate::logs::ExportingEnabled;
+use crate::logs::{ExportingEnabled, MetricsOutput};
 use crate::CliState;
-use ockam_core::env::{get_env_with_default, FromString};
+use ockam_core::env::{get_env, get_env_with_default, FromString};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_node::Executor;
 use std::env::current_exe;
@@ -56,6 +56,13 @@ pub struct ExportingConfiguration {
     span_export_portal_cutoff: Option<Duration>,
     /// Maximum time for exporting a batch of log records (with no response)
     log_export_portal_cutoff: Option<Duration>,
+    /// Maximum time between 2 exports of metrics
+    metrics_export_interval: Duration,
+    /// Maximum time for exporting a batch of metrics (with a response)
+    metrics_export_timeout: Duration,
+    /// If set, metrics are written to the console or to a file instead of being sent
+    /// to the OpenTelemetry collector
+    metrics_output: Option<MetricsOutput>,
 }
 
 impl ExportingConfiguration {
@@ -114,6 +121,26 @@ impl ExportingConfiguration {
         self.opentelemetry_endpoint.clone()
     }
 
+    /// Return the maximum time between 2 exports of metrics
+    pub fn metrics_export_interval(&self) -> Duration {
+        self.metrics_export_interval
+    }
+
+    /// Return the maximum time for exporting a batch of metrics
+    pub fn metrics_export_timeout(&self) -> Duration {
+        self.metrics_export_timeout
+    }
+
+    /// Return the local output for metrics, if they must not be sent to the OpenTelemetry collector
+    pub fn metrics_output(&self) -> Option<MetricsOutput> {
+        self.metrics_output.clone()
+    }
+
+    /// Return true if metrics must be exported, either locally or to the OpenTelemetry collector
+    pub fn is_metrics_export_enabled(&self) -> bool {
+        self.metrics_output.is_some() || self.is_enabled()
+    }
+
     /// Create a tracing configuration for a user command running in the foreground.
     /// (meaning that the process will shut down once the command has been executed)
     pub fn foreground() -> ockam_core::Result<ExportingConfiguration> {
@@ -134,6 +161,9 @@ impl ExportingConfiguration {
                 is_ockam_developer: is_ockam_developer()?,
                 span_export_portal_cutoff: Some(foreground_span_export_portal_cutoff().unwrap()),
                 log_export_portal_cutoff: Some(foreground_log_export_portal_cutoff().unwrap()),
+                metrics_export_interval: metrics_export_interval()?,
+                metrics_export_timeout: metrics_export_timeout()?,
+                metrics_output: metrics_output()?,
             }),
         }
     }
@@ -157,6 +187,9 @@ impl ExportingConfiguration {
                 is_ockam_developer: is_ockam_developer()?,
                 span_export_portal_cutoff: None,
                 log_export_portal_cutoff: None,
+                metrics_export_interval: metrics_export_interval()?,
+                metrics_export_timeout: metrics_export_timeout()?,
+                metrics_output: metrics_output()?,
             }),
         }
     }
@@ -175,6 +208,9 @@ impl ExportingConfiguration {
             is_ockam_developer: is_ockam_developer()?,
             span_export_portal_cutoff: None,
             log_export_portal_cutoff: None,
+            metrics_export_interval: metrics_export_interval()?,
+            metrics_export_timeout: metrics_export_timeout()?,
+            metrics_output: metrics_output()?,
         })
     }
 
@@ -505,6 +541,24 @@ pub fn foreground_span_export_portal_cutoff() -> ockam_core::Result<Duration> {
     )
 }
 
+/// Return the delay between the export of 2 batches of metrics, defined by an environment variable
+pub fn metrics_export_interval() -> ockam_core::Result<Duration> {
+    get_env_with_default(
+        OCKAM_METRICS_EXPORT_INTERVAL,
+        DEFAULT_METRICS_EXPORT_INTERVAL,
+    )
+}
+
+/// Return the export timeout for metrics, defined by an environment variable
+pub fn metrics_export_timeout() -> ockam_core::Result<Duration> {
+    get_env_with_default(OCKAM_METRICS_EXPORT_TIMEOUT, DEFAULT_METRICS_EXPORT_TIMEOUT)
+}
+
+/// Return the local output for metrics, defined by an environment variable
+pub fn metrics_output() -> ockam_core::Result<Option<MetricsOutput>> {
+    get_env(OCKAM_METRICS_OUTPUT)
+}
+
 /// Delete the opentelemetry node and recreate it to restart the inlet
 async fn restart_opentelemetry_node() -> ockam_core::Result<Url> {
     let args = vec![
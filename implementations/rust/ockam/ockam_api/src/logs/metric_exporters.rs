@@ -0,0 +1,235 @@
+use crate::logs::MetricsOutput;
+use ockam_core::async_trait;
+use ockam_core::errcode::{Kind, Origin};
+use opentelemetry::metrics::{MetricsError, Result};
+use opentelemetry::KeyValue;
+use opentelemetry_sdk::metrics::data::{
+    DataPoint, Gauge, Histogram, HistogramDataPoint, Metric, ResourceMetrics, Sum, Temporality,
+};
+use opentelemetry_sdk::metrics::exporter::PushMetricsExporter;
+use opentelemetry_sdk::metrics::reader::TemporalitySelector;
+use opentelemetry_sdk::metrics::InstrumentKind;
+use std::fmt::{Debug, Display, Formatter, Write as _};
+use std::fs::OpenOptions;
+use std::io::{stdout, Write};
+use std::sync::Mutex;
+
+/// This exporter writes metrics as lines of text, to the console or to a file.
+/// It can be used to inspect the metrics of a node locally, without an OpenTelemetry collector.
+///
+/// Each line contains the time of the export, the metric name, its attributes and its value:
+///
+/// 2024-10-01T10:00:00Z ockam.node.workers{kind="worker"} 12
+///
+pub struct TextMetricsExporter {
+    output: MetricsOutput,
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl TextMetricsExporter {
+    /// Create an exporter writing to the console or to a file.
+    /// An existing file is truncated.
+    pub fn create(output: MetricsOutput) -> ockam_core::Result<TextMetricsExporter> {
+        let writer: Box<dyn Write + Send> = match &output {
+            MetricsOutput::Stdout => Box::new(stdout()),
+            MetricsOutput::File(path) => Box::new(
+                OpenOptions::new()
+                    .create(true)
+                    .truncate(true)
+                    .write(true)
+                    .open(path)
+                    .map_err(|e| {
+                        ockam_core::Error::new(
+                            Origin::Api,
+                            Kind::Io,
+                            format!("cannot open the metrics file {}: {e:?}", path.display()),
+                        )
+                    })?,
+            ),
+        };
+        Ok(Self::new(output, writer))
+    }
+
+    /// Create an exporter writing to a specific writer
+    pub fn new(output: MetricsOutput, writer: Box<dyn Write + Send>) -> TextMetricsExporter {
+        TextMetricsExporter {
+            output,
+            writer: Mutex::new(writer),
+        }
+    }
+
+    /// Format all the data points of some metrics, one per line
+    fn format(metrics: &ResourceMetrics) -> String {
+        let timestamp = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        let mut lines = String::new();
+        for scope_metrics in metrics.scope_metrics.iter() {
+            for metric in scope_metrics.metrics.iter() {
+                format_metric(&mut lines, &timestamp, metric);
+            }
+        }
+        lines
+    }
+}
+
+impl Debug for TextMetricsExporter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TextMetricsExporter")
+            .field("output", &self.output.to_string())
+            .finish()
+    }
+}
+
+impl TemporalitySelector for TextMetricsExporter {
+    fn temporality(&self, _kind: InstrumentKind) -> Temporality {
+        Temporality::Cumulative
+    }
+}
+
+#[async_trait]
+impl PushMetricsExporter for TextMetricsExporter {
+    async fn export(&self, metrics: &mut ResourceMetrics) -> Result<()> {
+        let lines = Self::format(metrics);
+        let mut writer = self
+            .writer
+            .lock()
+            .map_err(|e| MetricsError::Other(e.to_string()))?;
+        writer
+            .write_all(lines.as_bytes())
+            .and_then(|_| writer.flush())
+            .map_err(|e| MetricsError::Other(format!("cannot write metrics: {e}")))
+    }
+
+    async fn force_flush(&self) -> Result<()> {
+        let mut writer = self
+            .writer
+            .lock()
+            .map_err(|e| MetricsError::Other(e.to_string()))?;
+        writer
+            .flush()
+            .map_err(|e| MetricsError::Other(format!("cannot flush metrics: {e}")))
+    }
+
+    fn shutdown(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Format the data points of a metric, depending on its type
+fn format_metric(lines: &mut String, timestamp: &str, metric: &Metric) {
+    let data = metric.data.as_any();
+    let name = &metric.name;
+    if let Some(gauge) = data.downcast_ref::<Gauge<u64>>() {
+        format_data_points(lines, timestamp, name, &gauge.data_points)
+    } else if let Some(gauge) = data.downcast_ref::<Gauge<i64>>() {
+        format_data_points(lines, timestamp, name, &gauge.data_points)
+    } else if let Some(gauge) = data.downcast_ref::<Gauge<f64>>() {
+        format_data_points(lines, timestamp, name, &gauge.data_points)
+    } else if let Some(sum) = data.downcast_ref::<Sum<u64>>() {
+        format_data_points(lines, timestamp, name, &sum.data_points)
+    } else if let Some(sum) = data.downcast_ref::<Sum<i64>>() {
+        format_data_points(lines, timestamp, name, &sum.data_points)
+    } else if let Some(sum) = data.downcast_ref::<Sum<f64>>() {
+        format_data_points(lines, timestamp, name, &sum.data_points)
+    } else if let Some(histogram) = data.downcast_ref::<Histogram<u64>>() {
+        format_histogram_data_points(lines, timestamp, name, &histogram.data_points)
+    } else if let Some(histogram) = data.downcast_ref::<Histogram<f64>>() {
+        format_histogram_data_points(lines, timestamp, name, &histogram.data_points)
+    } else {
+        let _ = writeln!(lines, "{timestamp} {name} {:?}", metric.data);
+    }
+}
+
+fn format_data_points<T: Display>(
+    lines: &mut String,
+    timestamp: &str,
+    name: &str,
+    data_points: &[DataPoint<T>],
+) {
+    for data_point in data_points {
+        let attributes = format_attributes(&data_point.attributes);
+        let _ = writeln!(lines, "{timestamp} {name}{attributes} {}", data_point.value);
+    }
+}
+
+fn format_histogram_data_points<T: Display>(
+    lines: &mut String,
+    timestamp: &str,
+    name: &str,
+    data_points: &[HistogramDataPoint<T>],
+) {
+    for data_point in data_points {
+        let attributes = format_attributes(&data_point.attributes);
+        let _ = write!(
+            lines,
+            "{timestamp} {name}{attributes} count={} sum={}",
+            data_point.count, data_point.sum
+        );
+        if let (Some(min), Some(max)) = (&data_point.min, &data_point.max) {
+            let _ = write!(lines, " min={min} max={max}");
+        }
+        lines.push('\n');
+    }
+}
+
+fn format_attributes(attributes: &[KeyValue]) -> String {
+    if attributes.is_empty() {
+        return "".to_string();
+    }
+    let attributes = attributes
+        .iter()
+        .map(|kv| format!("{}=\"{}\"", kv.key.as_str(), kv.value))
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("{{{attributes}}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::metrics::MeterProvider;
+    use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider};
+    use opentelemetry_sdk::runtime;
+    use std::sync::Arc;
+
+    /// A writer which can be inspected after having been given to the exporter
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_text_metrics_exporter() {
+        let buffer = SharedBuffer::default();
+        let exporter = TextMetricsExporter::new(MetricsOutput::Stdout, Box::new(buffer.clone()));
+        let reader = PeriodicReader::builder(exporter, runtime::Tokio).build();
+        let provider = SdkMeterProvider::builder().with_reader(reader).build();
+
+        let meter = provider.meter("test");
+        let counter = meter.u64_counter("messages").init();
+        counter.add(3, &[KeyValue::new("worker_type", "Echoer")]);
+        let histogram = meter.f64_histogram("latency").init();
+        histogram.record(0.5, &[]);
+        histogram.record(1.5, &[]);
+
+        provider.force_flush().unwrap();
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+
+        assert!(
+            output.contains(r#" messages{worker_type="Echoer"} 3"#),
+            "{output}"
+        );
+        assert!(
+            output.contains(" latency count=2 sum=2 min=0.5 max=1.5"),
+            "{output}"
+        );
+        provider.shutdown().unwrap();
+    }
+}
@@ -8,6 +8,8 @@
 //      - In a log file for a background node.
 //      - In the console for other commands.
 //   - If OCKAM_TRACING=true then, _additionally_, the spans and logs messages are sent to an OpenTelemetry collector.
+//   - The metrics of the node runtime are sent to the OpenTelemetry collector as well,
+//     or written locally if OCKAM_METRICS_OUTPUT is set.
 ///
 mod current_span;
 mod default_values;
@@ -16,6 +18,7 @@ pub mod exporting_configuration;
 mod log_exporters;
 pub mod logging_configuration;
 mod logging_options;
+mod metric_exporters;
 pub mod setup;
 mod span_exporters;
 mod tracing_guard;
@@ -26,6 +29,7 @@ pub use exporting_configuration::*;
 pub use log_exporters::*;
 pub use logging_configuration::*;
 pub use logging_options::*;
+pub use metric_exporters::*;
 pub use setup::*;
 pub use span_exporters::*;
 pub use tracing_guard::*;
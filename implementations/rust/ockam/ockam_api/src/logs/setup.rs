@@ -6,6 +6,9 @@ use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::export::logs::LogExporter;
 use opentelemetry_sdk::export::trace::SpanExporter;
 use opentelemetry_sdk::logs::{BatchLogProcessor, LoggerProvider};
+use opentelemetry_sdk::metrics::exporter::PushMetricsExporter;
+use opentelemetry_sdk::metrics::reader::DefaultTemporalitySelector;
+use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider};
 use opentelemetry_sdk::propagation::TraceContextPropagator;
 use opentelemetry_sdk::trace::{BatchConfig, BatchConfigBuilder, BatchSpanProcessor};
 use opentelemetry_sdk::{self as sdk};
@@ -30,6 +33,7 @@ use ockam_node::Executor;
 use crate::logs::tracing_guard::TracingGuard;
 use crate::logs::{
     ExportingConfiguration, GlobalErrorHandler, LoggingConfiguration, OckamLogExporter,
+    TextMetricsExporter,
 };
 use crate::logs::{LogFormat, OckamSpanExporter};
 
@@ -40,12 +44,31 @@ impl LoggingTracing {
     /// The app name is used to set an attribute on all events specifying if the event
     /// has been created by the cli or by a local node.
     ///
+    /// The metrics of the node runtime are exported as well, if exporting is enabled
+    /// or if metrics must be written locally.
+    ///
     /// The TracingGuard is used to flush all events when dropped.
     pub fn setup(
         logging_configuration: &LoggingConfiguration,
         exporting_configuration: &ExportingConfiguration,
         app_name: &str,
         node_name: Option<String>,
+    ) -> TracingGuard {
+        let tracing_guard = Self::setup_logging_tracing(
+            logging_configuration,
+            exporting_configuration,
+            app_name,
+            node_name,
+        );
+        tracing_guard.with_meter_provider(create_meter_provider(app_name, exporting_configuration))
+    }
+
+    /// Setup logging and tracing, depending on what is enabled
+    fn setup_logging_tracing(
+        logging_configuration: &LoggingConfiguration,
+        exporting_configuration: &ExportingConfiguration,
+        app_name: &str,
+        node_name: Option<String>,
     ) -> TracingGuard {
         if exporting_configuration.is_enabled() && logging_configuration.is_enabled() {
             // set-up logging and tracing
@@ -217,6 +240,78 @@ fn create_span_exporter(
     .expect("can't create a span exporter")
 }
 
+/// Create a metrics exporter
+// Metrics are sent to an OpenTelemetry collector using gRPC
+fn create_metrics_exporter(
+    exporting_configuration: &ExportingConfiguration,
+) -> opentelemetry_otlp::MetricsExporter {
+    let metrics_export_timeout = exporting_configuration.metrics_export_timeout();
+    let endpoint = exporting_configuration.opentelemetry_endpoint().to_string();
+
+    Executor::execute_future(async move {
+        opentelemetry_otlp::new_exporter()
+            .tonic()
+            .with_endpoint(endpoint)
+            .with_timeout(metrics_export_timeout)
+            .with_metadata(get_otlp_headers())
+            .build_metrics_exporter(Box::new(DefaultTemporalitySelector::new()))
+            .expect("failed to create the metrics exporter")
+    })
+    .expect("can't create a metrics exporter")
+}
+
+/// Create a meter provider for the metrics of the node runtime, when they need to be exported.
+/// Metrics are either written locally, or sent to the OpenTelemetry collector
+fn create_meter_provider(
+    app_name: &str,
+    exporting_configuration: &ExportingConfiguration,
+) -> Option<SdkMeterProvider> {
+    if let Some(metrics_output) = exporting_configuration.metrics_output() {
+        match TextMetricsExporter::create(metrics_output) {
+            Ok(exporter) => Some(install_meter_provider(
+                app_name,
+                exporting_configuration,
+                exporter,
+            )),
+            Err(e) => {
+                warn!("cannot write metrics: {e}");
+                None
+            }
+        }
+    } else if exporting_configuration.is_enabled() {
+        Some(install_meter_provider(
+            app_name,
+            exporting_configuration,
+            create_metrics_exporter(exporting_configuration),
+        ))
+    } else {
+        None
+    }
+}
+
+/// Create a meter provider periodically exporting metrics with the provided exporter
+/// and set it as the global meter provider, used by the instruments of the node runtime
+fn install_meter_provider<E: PushMetricsExporter>(
+    app_name: &str,
+    exporting_configuration: &ExportingConfiguration,
+    metrics_exporter: E,
+) -> SdkMeterProvider {
+    let app = app_name.to_string();
+    let metrics_export_interval = exporting_configuration.metrics_export_interval();
+    Executor::execute_future(async move {
+        let reader = PeriodicReader::builder(metrics_exporter, sdk::runtime::Tokio)
+            .with_interval(metrics_export_interval)
+            .build();
+        let provider = SdkMeterProvider::builder()
+            .with_resource(make_resource(app))
+            .with_reader(reader)
+            .build();
+        global::set_meter_provider(provider.clone());
+        provider
+    })
+    .expect("Failed to build the meter provider")
+}
+
 /// Create the tracing layer for OpenTelemetry
 /// Spans are exported in batches
 fn create_opentelemetry_tracing_layer<
@@ -1,16 +1,18 @@
 use opentelemetry::global;
 use opentelemetry_sdk::logs::LoggerProvider;
+use opentelemetry_sdk::metrics::SdkMeterProvider;
 use opentelemetry_sdk::trace::TracerProvider;
 use tracing_appender::non_blocking::WorkerGuard;
 
 /// The Tracing guard contains a guard closing the logging appender
-/// and optionally the logger/tracer providers which can be used to force the flushing
-/// of spans and log records
+/// and optionally the logger/tracer/meter providers which can be used to force the flushing
+/// of spans, log records and metrics
 #[derive(Debug)]
 pub struct TracingGuard {
     _worker_guard: Option<WorkerGuard>,
     logger_provider: Option<LoggerProvider>,
     tracer_provider: Option<TracerProvider>,
+    meter_provider: Option<SdkMeterProvider>,
 }
 
 impl TracingGuard {
@@ -24,6 +26,7 @@ impl TracingGuard {
             _worker_guard: Some(worker_guard),
             logger_provider: Some(logger_provider),
             tracer_provider: Some(tracer_provider),
+            meter_provider: None,
         }
     }
 
@@ -33,6 +36,7 @@ impl TracingGuard {
             _worker_guard: Some(worker_guard),
             logger_provider: None,
             tracer_provider: None,
+            meter_provider: None,
         }
     }
 
@@ -42,14 +46,26 @@ impl TracingGuard {
             _worker_guard: None,
             logger_provider: None,
             tracer_provider: Some(tracer_provider),
+            meter_provider: None,
+        }
+    }
+
+    /// Keep the meter provider used to export metrics
+    pub fn with_meter_provider(self, meter_provider: Option<SdkMeterProvider>) -> TracingGuard {
+        TracingGuard {
+            meter_provider,
+            ..self
         }
     }
 
     pub fn shutdown(&self) {
         global::shutdown_tracer_provider();
+        if let Some(meter_provider) = self.meter_provider.as_ref() {
+            let _ = meter_provider.shutdown();
+        }
     }
 
-    /// Export the current batches of spans and log records
+    /// Export the current batches of spans, log records and metrics
     /// This is used right after a background node has started to get the first logs
     /// and in tests otherwise
     pub fn force_flush(&self) {
@@ -59,5 +75,8 @@ impl TracingGuard {
         if let Some(tracer_provider) = self.tracer_provider.as_ref() {
             tracer_provider.force_flush();
         }
+        if let Some(meter_provider) = self.meter_provider.as_ref() {
+            let _ = meter_provider.force_flush();
+        }
     }
 }
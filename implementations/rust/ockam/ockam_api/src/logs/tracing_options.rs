@@ -1,5 +1,6 @@
 use ockam_core::env::FromString;
 use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
 
 /// This data type specifies if tracing is enabled or not
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
@@ -28,3 +29,29 @@ impl FromString for ExportingEnabled {
         })
     }
 }
+
+/// This data type specifies where metrics are written when they are inspected locally,
+/// instead of being sent to an OpenTelemetry collector
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum MetricsOutput {
+    Stdout,
+    File(PathBuf),
+}
+
+impl Display for MetricsOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetricsOutput::Stdout => f.write_str("stdout"),
+            MetricsOutput::File(path) => f.write_str(&path.to_string_lossy()),
+        }
+    }
+}
+
+impl FromString for MetricsOutput {
+    fn from_string(s: &str) -> ockam_core::Result<Self> {
+        match s {
+            "stdout" => Ok(MetricsOutput::Stdout),
+            path => Ok(MetricsOutput::File(PathBuf::from(path))),
+        }
+    }
+}
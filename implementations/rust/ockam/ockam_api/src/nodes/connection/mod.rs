@@ -1,8 +1,10 @@
 mod plain_tcp;
+mod plain_udp;
 mod project;
 mod secure;
 
 use ockam::tcp::TcpConnection;
+use ockam::udp::UdpBind;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::Result;
@@ -16,6 +18,7 @@ use crate::local_multiaddr_to_route;
 use crate::nodes::service::default_address::DefaultAddress;
 use crate::nodes::NodeManager;
 pub(crate) use plain_tcp::PlainTcpInstantiator;
+pub(crate) use plain_udp::PlainUdpInstantiator;
 pub(crate) use project::ProjectInstantiator;
 pub(crate) use secure::SecureChannelInstantiator;
 use std::fmt::{Debug, Formatter};
@@ -36,6 +39,8 @@ pub struct Connection {
     pub(crate) secure_channel_encryptors: Vec<Address>,
     /// A TCP worker address if used when instantiating the connection
     pub(crate) tcp_connection: Option<TcpConnection>,
+    /// A UDP bind if used when instantiating the connection
+    pub(crate) udp_bind: Option<UdpBind>,
     /// If a flow control was created
     flow_control_id: Option<FlowControlId>,
 }
@@ -105,6 +110,24 @@ impl Connection {
             }
         }
 
+        if let Some(udp_bind) = self.udp_bind.as_ref() {
+            let address = udp_bind.sender_address().clone();
+            if let Some(udp_transport) = node_manager.udp_transport.as_ref() {
+                if let Err(error) = udp_transport.unbind(address.clone()).await {
+                    match error.code().kind {
+                        Kind::NotFound => {
+                            debug!("cannot find and unbind udp worker `{address}`");
+                        }
+                        _ => Err(ockam_core::Error::new(
+                            Origin::Node,
+                            Kind::Internal,
+                            format!("Failed to unbind udp worker {address}. {error}"),
+                        ))?,
+                    }
+                }
+            }
+        }
+
         Ok(())
     }
 }
@@ -134,6 +157,7 @@ pub(crate) struct ConnectionBuilder {
     pub(crate) flow_control_id: Option<FlowControlId>,
     pub(crate) secure_channel_encryptors: Vec<Address>,
     pub(crate) tcp_connection: Option<TcpConnection>,
+    pub(crate) udp_bind: Option<UdpBind>,
 }
 
 impl Debug for ConnectionBuilder {
@@ -163,6 +187,8 @@ pub struct Changes {
     pub secure_channel_encryptors: Vec<Address>,
     /// Optional, to keep track of tcp worker when created for the connection
     pub tcp_connection: Option<TcpConnection>,
+    /// Optional, to keep track of udp worker when created for the connection
+    pub udp_bind: Option<UdpBind>,
 }
 
 /// Takes in a [`MultiAddr`] and instantiate it, can be implemented for any protocol.
@@ -197,6 +223,7 @@ impl ConnectionBuilder {
             secure_channel_encryptors: vec![],
             flow_control_id: None,
             tcp_connection: None,
+            udp_bind: None,
         }
     }
 
@@ -207,6 +234,7 @@ impl ConnectionBuilder {
             original_addr: self.original_multiaddr,
             secure_channel_encryptors: self.secure_channel_encryptors,
             tcp_connection: self.tcp_connection,
+            udp_bind: self.udp_bind,
             flow_control_id: self.flow_control_id,
         }
     }
@@ -261,6 +289,17 @@ impl ConnectionBuilder {
                         self.tcp_connection = changes.tcp_connection;
                     }
 
+                    if changes.udp_bind.is_some() {
+                        if self.udp_bind.is_some() {
+                            return Err(ockam_core::Error::new(
+                                Origin::Transport,
+                                Kind::Unsupported,
+                                "multiple udp binds created in a `MultiAddr`",
+                            ));
+                        }
+                        self.udp_bind = changes.udp_bind;
+                    }
+
                     if changes.flow_control_id.is_some() {
                         self.flow_control_id = changes.flow_control_id;
                     }
@@ -280,6 +319,7 @@ impl ConnectionBuilder {
             current_multiaddr: self.current_multiaddr,
             flow_control_id: self.flow_control_id,
             tcp_connection: self.tcp_connection,
+            udp_bind: self.udp_bind,
         })
     }
 
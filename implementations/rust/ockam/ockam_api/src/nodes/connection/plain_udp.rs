@@ -0,0 +1,76 @@
+use crate::error::ApiError;
+use crate::nodes::connection::{Changes, ConnectionBuilder, Instantiator};
+use crate::route_to_multiaddr;
+
+use crate::nodes::NodeManager;
+use ockam::udp::{UdpBindArguments, UdpBindOptions};
+use ockam_core::{async_trait, route, Error, Route};
+use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Udp};
+use ockam_multiaddr::{Match, MultiAddr, Protocol};
+use ockam_node::compat::asynchronous::resolve_peer;
+use ockam_node::Context;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// Creates a UDP bind to a remote peer.
+pub(crate) struct PlainUdpInstantiator {}
+
+impl PlainUdpInstantiator {
+    pub(crate) fn new() -> Self {
+        Self {}
+    }
+}
+
+#[async_trait]
+impl Instantiator for PlainUdpInstantiator {
+    fn matches(&self) -> Vec<Match> {
+        vec![
+            // matches any ip address followed by a udp protocol
+            Match::any([DnsAddr::CODE, Ip4::CODE, Ip6::CODE]),
+            Udp::CODE.into(),
+        ]
+    }
+
+    async fn instantiate(
+        &self,
+        _ctx: &Context,
+        node_manager: &NodeManager,
+        _transport_route: Route,
+        extracted: (MultiAddr, MultiAddr, MultiAddr),
+    ) -> Result<Changes, Error> {
+        let (before, udp_piece, after) = extracted;
+
+        let udp_transport = node_manager
+            .udp_transport
+            .as_ref()
+            .ok_or_else(|| ApiError::core("UDP transport is not enabled on this node"))?;
+
+        let peer = resolve_peer(udp_piece.to_socket_addr()?).await?;
+        // bind to any interface, with the same address family as the peer
+        let bind_address = if peer.is_ipv4() {
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0)
+        } else {
+            SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0)
+        };
+        let arguments = UdpBindArguments::new()
+            .with_bind_socket_address(bind_address)
+            .with_peer_socket_address(peer);
+        let udp_bind = udp_transport.bind(arguments, UdpBindOptions::new()).await?;
+
+        let udp_route = route![udp_bind.sender_address().clone()];
+        let multiaddr = route_to_multiaddr(&udp_route).ok_or_else(|| {
+            ApiError::core(format!(
+                "Couldn't convert route to MultiAddr: udp_route={udp_route}"
+            ))
+        })?;
+
+        let current_multiaddr = ConnectionBuilder::combine(before, multiaddr, after)?;
+
+        Ok(Changes {
+            current_multiaddr,
+            flow_control_id: Some(udp_bind.flow_control_id().clone()),
+            secure_channel_encryptors: vec![],
+            tcp_connection: None,
+            udp_bind: Some(udp_bind),
+        })
+    }
+}
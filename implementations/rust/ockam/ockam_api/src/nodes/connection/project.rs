@@ -85,6 +85,7 @@ impl Instantiator for ProjectInstantiator {
             current_multiaddr,
             secure_channel_encryptors: vec![sc.encryptor_address().clone()],
             tcp_connection: tcp.tcp_connection,
+            udp_bind: None,
         })
     }
 }
@@ -74,6 +74,7 @@ impl Instantiator for SecureChannelInstantiator {
             flow_control_id: Some(sc.flow_control_id().clone()),
             secure_channel_encryptors: vec![sc.encryptor_address().clone()],
             tcp_connection: None,
+            udp_bind: None,
         })
     }
 }
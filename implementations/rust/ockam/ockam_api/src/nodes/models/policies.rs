@@ -1,10 +1,13 @@
 use minicbor::{CborLen, Decode, Encode};
+use ockam::identity::Identifier;
 use ockam_abac::{
-    Action, Expr, PolicyExpression, ResourceName, ResourcePolicy, ResourceType, ResourceTypePolicy,
+    Action, EvaluationStep, Expr, PolicyExpression, ResourceName, ResourcePolicy, ResourceType,
+    ResourceTypePolicy,
 };
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::Error;
 use serde::Serialize;
+use std::collections::BTreeMap;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
@@ -61,6 +64,34 @@ impl PoliciesList {
     }
 }
 
+/// Request to evaluate the policy of a resource for a given subject, without accessing the resource.
+///
+/// The subject is described either by an identifier, in which case its attributes are retrieved
+/// from the node, or by an explicit list of attributes.
+#[derive(Clone, Debug, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TestPolicyRequest {
+    #[n(1)] pub resource: ResourceTypeOrName,
+    #[n(2)] pub action: Action,
+    #[n(3)] pub subject: Option<Identifier>,
+    #[n(4)] pub attributes: Option<BTreeMap<String, String>>,
+    /// Expression to evaluate instead of the policy currently stored for the resource
+    #[n(5)] pub expression: Option<PolicyExpression>,
+}
+
+/// Result of the evaluation of a policy for a [`TestPolicyRequest`]
+#[derive(Debug, Encode, Decode, CborLen, Serialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PolicyTestResult {
+    #[n(1)] pub allowed: bool,
+    #[n(2)] pub expression: Expr,
+    /// Value of the expression, or the reason why it could not be evaluated
+    #[n(3)] pub result: String,
+    #[n(4)] pub steps: Vec<EvaluationStep>,
+}
+
 /// A view for the specific policy types returned by policies repositories. This is used
 /// to simplify the type returned by the NodeManager in the api requests.
 #[derive(Debug, Encode, Decode, CborLen, Serialize, PartialEq, Eq)]
@@ -6,6 +6,7 @@ use std::time::Duration;
 
 use minicbor::{CborLen, Decode, Encode};
 use ockam::identity::Identifier;
+use ockam::tcp::{InletLoadBalancing, PortalLimits, RateLimit, TcpOutletOptions};
 use ockam::transport::HostnamePort;
 use ockam_abac::PolicyExpression;
 use ockam_core::{Address, IncomingAccessControl, OutgoingAccessControl, Route};
@@ -14,6 +15,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::colors::color_primary;
 use crate::error::ApiError;
+use crate::http_interceptor::HttpOutletConfig;
+use crate::postgres::PostgresOutletConfig;
 
 use crate::output::Output;
 use crate::session::connection_status::ConnectionStatus;
@@ -57,6 +60,10 @@ pub struct CreateInlet {
     #[n(12)] pub(crate) ebpf: bool,
     /// TLS certificate provider route.
     #[n(13)] pub(crate) tls_certificate_provider: Option<MultiAddr>,
+    /// Other outlets receiving the connections of the inlet, in addition to `outlet_addr`.
+    #[n(14)] pub(crate) additional_outlet_addrs: Vec<MultiAddr>,
+    /// Strategy used to spread the connections between the outlets.
+    #[n(15)] pub(crate) load_balancing: Option<InletLoadBalancing>,
 }
 
 impl CreateInlet {
@@ -83,6 +90,8 @@ impl CreateInlet {
             disable_tcp_fallback,
             ebpf,
             tls_certificate_provider: None,
+            additional_outlet_addrs: vec![],
+            load_balancing: None,
         }
     }
 
@@ -110,6 +119,8 @@ impl CreateInlet {
             disable_tcp_fallback,
             ebpf,
             tls_certificate_provider: None,
+            additional_outlet_addrs: vec![],
+            load_balancing: None,
         }
     }
 
@@ -117,6 +128,15 @@ impl CreateInlet {
         self.tls_certificate_provider = Some(provider);
     }
 
+    /// Spread the connections of the inlet between its outlet and the `outlet_addrs`
+    pub fn set_additional_outlet_addrs(&mut self, outlet_addrs: Vec<MultiAddr>) {
+        self.additional_outlet_addrs = outlet_addrs;
+    }
+
+    pub fn set_load_balancing(&mut self, load_balancing: InletLoadBalancing) {
+        self.load_balancing = Some(load_balancing);
+    }
+
     pub fn set_wait_ms(&mut self, ms: u64) {
         self.wait_for_outlet_duration = Some(Duration::from_millis(ms))
     }
@@ -169,7 +189,13 @@ pub struct CreateOutlet {
     /// will be used.
     #[n(5)] pub policy_expression: Option<PolicyExpression>,
     /// Use eBPF and RawSocket to access TCP packets instead of TCP data stream.
-    #[n(6)] pub ebpf: bool
+    #[n(6)] pub ebpf: bool,
+    /// If set, the HTTP/1.1 requests sent to the outlet are intercepted
+    #[n(7)] pub http: Option<HttpOutletConfig>,
+    /// If set, the Postgres queries sent to the outlet are intercepted
+    #[n(8)] pub postgres: Option<PostgresOutletConfig>,
+    /// Limits of the connections to the outlet
+    #[n(9)] pub limits: Option<PortalLimitsConfig>,
 }
 
 impl CreateOutlet {
@@ -187,12 +213,84 @@ impl CreateOutlet {
             reachable_from_default_secure_channel,
             policy_expression: None,
             ebpf,
+            http: None,
+            postgres: None,
+            limits: None,
         }
     }
 
     pub fn set_policy_expression(&mut self, expression: PolicyExpression) {
         self.policy_expression = Some(expression);
     }
+
+    pub fn set_http(&mut self, http: HttpOutletConfig) {
+        self.http = Some(http);
+    }
+
+    pub fn set_postgres(&mut self, postgres: PostgresOutletConfig) {
+        self.postgres = Some(postgres);
+    }
+
+    pub fn set_limits(&mut self, limits: PortalLimitsConfig) {
+        self.limits = Some(limits);
+    }
+}
+
+/// Request body to delete an outlet
+#[derive(Clone, Debug, Default, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct DeleteOutlet {
+    /// If set, the outlet is drained before being deleted: it refuses new connections,
+    /// and its open connections keep running until they are closed or this duration elapses
+    #[n(1)] pub drain_timeout: Option<Duration>,
+}
+
+impl DeleteOutlet {
+    pub fn new(drain_timeout: Option<Duration>) -> Self {
+        Self { drain_timeout }
+    }
+}
+
+/// Limits of the connections to a portal
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PortalLimitsConfig {
+    /// Maximum number of concurrent connections
+    #[n(1)] pub max_connections: Option<u64>,
+    /// Maximum number of new connections per second
+    #[n(2)] pub connections_per_second: Option<u64>,
+    /// Maximum number of bytes per second, in both directions
+    #[n(3)] pub bytes_per_second: Option<u64>,
+    /// If true, the limits apply to the connections of each identity,
+    /// instead of all the connections
+    #[n(4)] pub per_identity: bool,
+}
+
+impl PortalLimitsConfig {
+    pub fn portal_limits(&self) -> PortalLimits {
+        let mut limits = PortalLimits::new();
+        if let Some(max_connections) = self.max_connections {
+            limits = limits.with_max_connections(max_connections as usize);
+        }
+        if let Some(connections_per_second) = self.connections_per_second {
+            limits = limits.with_connection_rate(RateLimit::new(connections_per_second));
+        }
+        if let Some(bytes_per_second) = self.bytes_per_second {
+            limits = limits.with_bandwidth(RateLimit::new(bytes_per_second));
+        }
+        limits
+    }
+
+    /// Set the limits on the options of an outlet
+    pub fn apply_to_outlet(&self, options: TcpOutletOptions) -> TcpOutletOptions {
+        if self.per_identity {
+            options.with_identity_limits(self.portal_limits())
+        } else {
+            options.with_limits(self.portal_limits())
+        }
+    }
 }
 
 /// Response body when interacting with a portal endpoint
@@ -208,6 +306,18 @@ pub struct InletStatus {
     #[n(5)] pub outlet_route: Option<String>,
     #[n(6)] pub status: ConnectionStatus,
     #[n(7)] pub outlet_addr: String,
+    /// Status of each outlet, when the inlet spreads its connections between several outlets
+    #[n(8)] pub outlets: Vec<InletOutletStatus>,
+}
+
+/// Status of one of the outlets of a load-balanced inlet
+#[derive(Clone, Debug, Encode, Decode, CborLen, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct InletOutletStatus {
+    #[n(1)] pub outlet_addr: String,
+    #[n(2)] pub outlet_route: Option<String>,
+    #[n(3)] pub status: ConnectionStatus,
 }
 
 impl InletStatus {
@@ -229,8 +339,13 @@ impl InletStatus {
             outlet_route: outlet_route.into(),
             status,
             outlet_addr: outlet_addr.into(),
+            outlets: vec![],
         }
     }
+
+    pub fn with_outlets(self, outlets: Vec<InletOutletStatus>) -> Self {
+        Self { outlets, ..self }
+    }
 }
 
 impl Display for InletStatus {
@@ -261,6 +376,15 @@ impl Display for InletStatus {
             fmt::INDENTATION,
             color_primary(&self.outlet_addr)
         )?;
+        for outlet in &self.outlets {
+            writeln!(
+                f,
+                "{}Outlet {} is {}",
+                fmt::INDENTATION,
+                color_primary(&outlet.outlet_addr),
+                outlet.status,
+            )?;
+        }
         Ok(())
     }
 }
@@ -336,3 +460,117 @@ pub enum OutletAccessControl {
     ),
     WithPolicyExpression(Option<PolicyExpression>),
 }
+
+/// Request body to create a UDP inlet
+#[derive(Clone, Debug, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CreateUdpInlet {
+    /// The address the inlet should listen at
+    #[n(1)] pub listen_addr: HostnamePort,
+    /// The address of the UDP outlet
+    #[n(2)] pub outlet_addr: MultiAddr,
+    /// A human-friendly alias for this inlet
+    #[n(3)] pub alias: String,
+    /// An authorised identity for secure channels.
+    /// Only set for non-project addresses as for projects the project's
+    /// authorised identity will be used.
+    #[n(4)] pub authorized: Option<Identifier>,
+    /// The expression for the access control policy for this inlet.
+    /// If not set, the policy set for the [UDP inlet resource type](ockam_abac::ResourceType::UdpInlet)
+    /// will be used.
+    #[n(5)] pub policy_expression: Option<PolicyExpression>,
+    /// Duration after which a session without traffic is closed
+    #[n(6)] pub idle_timeout: Option<Duration>,
+}
+
+/// Request body to create a UDP outlet
+#[derive(Clone, Debug, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CreateUdpOutlet {
+    /// The address of the UDP server receiving the datagrams
+    #[n(1)] pub hostname_port: HostnamePort,
+    /// The address of the outlet worker
+    #[n(2)] pub worker_addr: Option<Address>,
+    /// The expression for the access control policy for this outlet.
+    /// If not set, the policy set for the [UDP outlet resource type](ockam_abac::ResourceType::UdpOutlet)
+    /// will be used.
+    #[n(3)] pub policy_expression: Option<PolicyExpression>,
+    /// Duration after which a session without traffic is closed
+    #[n(4)] pub idle_timeout: Option<Duration>,
+    /// Allow the outlet to be reachable from the default secure channel, useful when we want to
+    /// tighten the flow control
+    #[n(5)] pub reachable_from_default_secure_channel: bool,
+}
+
+/// Response body when interacting with a UDP inlet
+#[derive(Clone, Debug, Encode, Decode, CborLen, Serialize, PartialEq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct UdpInletStatus {
+    #[n(1)] pub alias: String,
+    #[n(2)] pub bind_addr: String,
+    #[n(3)] pub outlet_addr: String,
+    #[n(4)] pub outlet_route: String,
+}
+
+impl Display for UdpInletStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "UDP Inlet {} at {}",
+            color_primary(&self.alias),
+            color_primary(&self.bind_addr),
+        )?;
+        write!(
+            f,
+            "{}Outlet Address: {}",
+            fmt::INDENTATION,
+            color_primary(&self.outlet_addr)
+        )
+    }
+}
+
+impl Output for UdpInletStatus {
+    fn item(&self) -> crate::Result<String> {
+        Ok(self.padded_display())
+    }
+}
+
+/// Response body when interacting with a UDP outlet
+#[derive(Clone, Debug, Encode, Decode, CborLen, Serialize, PartialEq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct UdpOutletStatus {
+    #[n(1)] pub to: HostnamePort,
+    #[n(2)] pub worker_addr: Address,
+}
+
+impl UdpOutletStatus {
+    pub fn worker_route(&self) -> Result<MultiAddr, ockam_core::Error> {
+        try_address_to_multiaddr(&self.worker_addr)
+            .map_err(|_| ApiError::core("Invalid Worker Address"))
+    }
+}
+
+impl Display for UdpOutletStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "UDP Outlet at {} sends datagrams to {}",
+            color_primary(
+                self.worker_route()
+                    .map_err(|_| std::fmt::Error)?
+                    .to_string()
+            ),
+            color_primary(self.to.to_string()),
+        )
+    }
+}
+
+impl Output for UdpOutletStatus {
+    fn item(&self) -> Result<String, ApiError> {
+        Ok(self.padded_display())
+    }
+}
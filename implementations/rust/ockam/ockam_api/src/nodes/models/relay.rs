@@ -3,7 +3,7 @@ use std::fmt::Display;
 
 use ockam::identity::Identifier;
 use ockam::remote::RemoteRelayInfo;
-use ockam::route;
+use ockam::{route, RelayStatus};
 use ockam_core::flow_control::FlowControlId;
 use ockam_multiaddr::MultiAddr;
 
@@ -91,6 +91,36 @@ pub struct RelayInfo {
     #[n(6)] destination_address: MultiAddr,
     #[n(7)] name: String,
     #[n(8)] last_failure: Option<String>,
+    /// Nodes registered for a relay hosted by this node
+    #[n(9)] backends: Vec<RelayBackendInfo>,
+}
+
+/// Node registered for a relay hosted by a node
+#[derive(Debug, Clone, Encode, Decode, CborLen, serde::Serialize, serde::Deserialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RelayBackendInfo {
+    /// Route from the relay to the node
+    #[n(1)] route: String,
+    /// Number of connections routed to the node
+    #[n(2)] connections: u64,
+    /// Number of connections routed to the node which are still open.
+    /// They are only tracked when the relay service balances the least connections
+    #[n(3)] open_connections: Option<u64>,
+}
+
+impl RelayBackendInfo {
+    pub fn route(&self) -> &str {
+        &self.route
+    }
+
+    pub fn connections(&self) -> u64 {
+        self.connections
+    }
+
+    pub fn open_connections(&self) -> Option<u64> {
+        self.open_connections
+    }
 }
 
 impl RelayInfo {
@@ -108,6 +138,35 @@ impl RelayInfo {
             flow_control_id: None,
             connection_status,
             last_failure: None,
+            backends: vec![],
+        }
+    }
+
+    /// Create the representation of a relay hosted by this node. It is up as long as
+    /// a node is registered for it
+    pub fn from_hosted_relay(status: &RelayStatus) -> Self {
+        let address = route_to_multiaddr(&route![status.address().clone()]).unwrap_or_default();
+        let connection_status = if status.backends().is_empty() {
+            ConnectionStatus::Down
+        } else {
+            ConnectionStatus::Up
+        };
+        Self {
+            remote_address: Some(status.address().address().to_string()),
+            backends: status
+                .backends()
+                .iter()
+                .map(|backend| RelayBackendInfo {
+                    route: backend.route().to_string(),
+                    connections: backend.connections(),
+                    open_connections: backend.open_connections(),
+                })
+                .collect(),
+            ..Self::new(
+                address,
+                status.address().address().to_string(),
+                connection_status,
+            )
         }
     }
 
@@ -135,6 +194,7 @@ impl RelayInfo {
             destination_address: self.destination_address,
             name: self.name,
             last_failure: self.last_failure,
+            backends: self.backends,
         }
     }
 
@@ -148,6 +208,7 @@ impl RelayInfo {
             destination_address: self.destination_address,
             name: self.name,
             last_failure: Some(last_failure),
+            backends: self.backends,
         }
     }
 
@@ -175,6 +236,10 @@ impl RelayInfo {
         &self.flow_control_id
     }
 
+    pub fn backends(&self) -> &[RelayBackendInfo] {
+        &self.backends
+    }
+
     pub fn remote_address_ma(&self) -> Result<Option<MultiAddr>, ockam_core::Error> {
         if let Some(addr) = &self.remote_address {
             route_to_multiaddr(&route![addr.to_string()])
@@ -215,6 +280,20 @@ impl Display for RelayInfo {
                     .unwrap_or("N/A".into())
             ),
         )?;
+        for backend in &self.backends {
+            write!(
+                f,
+                "Registered node at {} with {} connections",
+                color_primary(&backend.route),
+                color_primary(backend.connections.to_string())
+            )?;
+            match backend.open_connections {
+                Some(open_connections) => {
+                    writeln!(f, ", {} open", color_primary(open_connections.to_string()))?
+                }
+                None => writeln!(f)?,
+            }
+        }
         Ok(())
     }
 }
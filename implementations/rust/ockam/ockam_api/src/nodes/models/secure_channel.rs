@@ -6,7 +6,9 @@ use minicbor::{CborLen, Decode, Encode};
 use serde::Serialize;
 
 use ockam::identity::models::CredentialAndPurposeKey;
-use ockam::identity::{Identifier, SecureChannel, SecureChannelListener};
+use ockam::identity::{
+    Identifier, RekeyEvent, RekeyEvents, SecureChannel, SecureChannelListener, TimestampInSeconds,
+};
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{route, Address, Result};
 use ockam_multiaddr::MultiAddr;
@@ -14,7 +16,7 @@ use ockam_multiaddr::MultiAddr;
 use crate::colors::color_primary;
 use crate::error::ApiError;
 use crate::nodes::registry::SecureChannelInfo;
-use crate::output::Output;
+use crate::output::{human_readable_time, Output};
 use crate::{route_to_multiaddr, try_route_to_multiaddr};
 
 //Requests
@@ -211,6 +213,23 @@ impl DeleteSecureChannelResponse {
     }
 }
 
+/// Response body when instructing a node to present the latest change history
+/// of its identity on its secure channels, after a key rotation
+#[derive(Debug, Clone, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PresentChangeHistoryResponse {
+    #[n(1)] pub notified_secure_channels: u64,
+}
+
+impl PresentChangeHistoryResponse {
+    pub fn new(notified_secure_channels: usize) -> Self {
+        Self {
+            notified_secure_channels: notified_secure_channels as u64,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Encode, Decode, CborLen, Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
@@ -219,6 +238,28 @@ pub struct ShowSecureChannelResponse {
     #[n(2)] pub route: Option<String>,
     #[n(3)] pub authorized_identifiers: Option<Vec<String>>,
     #[n(4)] pub flow_control_id: Option<FlowControlId>,
+    #[n(5)] pub rekey_count: Option<u64>,
+    #[n(6)] pub rekey_events: Option<Vec<SecureChannelRekeyEvent>>,
+}
+
+/// Renewal of the encryption key of a secure channel
+#[derive(Debug, Clone, Encode, Decode, CborLen, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SecureChannelRekeyEvent {
+    #[n(1)] pub timestamp: u64,
+    #[n(2)] pub reason: String,
+    #[n(3)] pub nonce: u64,
+}
+
+impl From<RekeyEvent> for SecureChannelRekeyEvent {
+    fn from(event: RekeyEvent) -> Self {
+        Self {
+            timestamp: event.timestamp.0,
+            reason: event.reason.to_string(),
+            nonce: event.nonce,
+        }
+    }
 }
 
 impl ShowSecureChannelResponse {
@@ -237,15 +278,29 @@ impl ShowSecureChannelResponse {
                 })
                 .unwrap_or(None),
             flow_control_id: info.map(|info| info.sc().flow_control_id().clone()),
+            rekey_count: None,
+            rekey_events: None,
         }
     }
+
+    pub fn with_rekey_events(mut self, rekey_events: &RekeyEvents) -> Self {
+        self.rekey_count = Some(rekey_events.count());
+        self.rekey_events = Some(
+            rekey_events
+                .last_events()
+                .into_iter()
+                .map(SecureChannelRekeyEvent::from)
+                .collect(),
+        );
+        self
+    }
 }
 
 impl Output for ShowSecureChannelResponse {
     fn item(&self) -> crate::Result<String> {
         let s = match &self.channel {
             Some(addr) => {
-                format!(
+                let mut s = format!(
                     "\n  Secure Channel:\n{} {}\n{} {}\n{} {}",
                     "  •         At: ".light_magenta(),
                     try_route_to_multiaddr(&route![addr.to_string()])?
@@ -261,7 +316,23 @@ impl Output for ShowSecureChannelResponse {
                         .map(|id| id.clone().light_yellow().to_string())
                         .collect::<Vec<String>>()
                         .join("\n\t")
-                )
+                );
+                if let Some(rekey_count) = self.rekey_count {
+                    s.push_str(&format!(
+                        "\n{} {}",
+                        "  •     Rekeys: ".light_magenta(),
+                        rekey_count.to_string().light_yellow()
+                    ));
+                }
+                if let Some(last) = self.rekey_events.as_ref().and_then(|e| e.last()) {
+                    s.push_str(&format!(
+                        "\n{} {} ({})",
+                        "  • Last rekey: ".light_magenta(),
+                        human_readable_time(TimestampInSeconds(last.timestamp)).light_yellow(),
+                        last.reason
+                    ));
+                }
+                s
             }
             None => format!("{}", "Channel not found".red()),
         };
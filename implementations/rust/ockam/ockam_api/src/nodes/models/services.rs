@@ -1,5 +1,5 @@
 use crate::colors::{color_primary, color_warn};
-use crate::kafka::{ConsumerPublishing, ConsumerResolution};
+use crate::kafka::{ConsumerPublishing, ConsumerResolution, TopicEncryptionRule};
 use crate::output::Output;
 use minicbor::{CborLen, Decode, Encode};
 use ockam_abac::PolicyExpression;
@@ -100,6 +100,10 @@ pub struct StartKafkaInletRequest {
     #[n(8)] consumer_policy_expression: Option<PolicyExpression>,
     #[n(9)] producer_policy_expression: Option<PolicyExpression>,
     #[n(10)] encrypted_fields: Vec<String>,
+    #[n(11)] encryption_rules: Vec<TopicEncryptionRule>,
+    #[n(12)] encrypt_headers: bool,
+    #[n(13)] encrypt_keys: bool,
+    #[n(14)] record_keys_secret: Option<String>,
 }
 
 impl StartKafkaInletRequest {
@@ -110,6 +114,10 @@ impl StartKafkaInletRequest {
         kafka_outlet_route: MultiAddr,
         encrypt_content: bool,
         encrypted_fields: Vec<String>,
+        encryption_rules: Vec<TopicEncryptionRule>,
+        encrypt_headers: bool,
+        encrypt_keys: bool,
+        record_keys_secret: Option<String>,
         consumer_resolution: ConsumerResolution,
         consumer_publishing: ConsumerPublishing,
         inlet_policy_expression: Option<PolicyExpression>,
@@ -127,6 +135,10 @@ impl StartKafkaInletRequest {
             consumer_policy_expression,
             producer_policy_expression,
             encrypted_fields,
+            encryption_rules,
+            encrypt_headers,
+            encrypt_keys,
+            record_keys_secret,
         }
     }
 
@@ -148,6 +160,22 @@ impl StartKafkaInletRequest {
         self.encrypted_fields.clone()
     }
 
+    pub fn encryption_rules(&self) -> Vec<TopicEncryptionRule> {
+        self.encryption_rules.clone()
+    }
+
+    pub fn encrypt_headers(&self) -> bool {
+        self.encrypt_headers
+    }
+
+    pub fn encrypt_keys(&self) -> bool {
+        self.encrypt_keys
+    }
+
+    pub fn record_keys_secret(&self) -> Option<String> {
+        self.record_keys_secret.clone()
+    }
+
     pub fn consumer_resolution(&self) -> ConsumerResolution {
         self.consumer_resolution.clone()
     }
@@ -1,13 +1,16 @@
 use crate::cli_state::random_name;
+use crate::nodes::connection::Connection;
 use crate::DefaultAddress;
 
 use ockam::identity::Identifier;
 use ockam::identity::{SecureChannel, SecureChannelListener};
+use ockam::RelayRegistry;
 use ockam_core::compat::collections::BTreeMap;
 use ockam_core::{Address, Route};
 use ockam_multiaddr::MultiAddr;
 use ockam_node::compat::asynchronous::{Mutex, RwLock};
 use ockam_transport_core::HostnamePort;
+use ockam_transport_tcp::{TcpInlet, TcpTransport};
 
 use crate::session::session::Session;
 use std::borrow::Borrow;
@@ -127,6 +130,10 @@ pub(crate) struct InletInfo {
     pub(crate) bind_addr: String,
     pub(crate) outlet_addr: MultiAddr,
     pub(crate) session: Arc<Mutex<Session>>,
+    /// Sessions to the other outlets, when the inlet spreads its connections between several outlets
+    pub(crate) additional_outlets: Vec<(MultiAddr, Arc<Mutex<Session>>)>,
+    /// Inlet shared by the sessions of all the outlets
+    pub(crate) shared_inlet: Option<Arc<TcpInlet>>,
 }
 
 impl InletInfo {
@@ -135,6 +142,56 @@ impl InletInfo {
             bind_addr: bind_addr.to_owned(),
             outlet_addr,
             session: Arc::new(Mutex::new(session)),
+            additional_outlets: vec![],
+            shared_inlet: None,
+        }
+    }
+
+    /// Create the information of an inlet spreading its connections between several outlets,
+    /// with one session per outlet. The first outlet is the main one
+    pub(crate) fn new_balanced(
+        bind_addr: &str,
+        outlets: Vec<(MultiAddr, Session)>,
+        shared_inlet: TcpInlet,
+    ) -> Option<Self> {
+        let mut outlets = outlets
+            .into_iter()
+            .map(|(outlet_addr, session)| (outlet_addr, Arc::new(Mutex::new(session))));
+        let (outlet_addr, session) = outlets.next()?;
+        Some(Self {
+            bind_addr: bind_addr.to_owned(),
+            outlet_addr,
+            session,
+            additional_outlets: outlets.collect(),
+            shared_inlet: Some(Arc::new(shared_inlet)),
+        })
+    }
+
+    /// Return true if the inlet spreads its connections between several outlets
+    pub(crate) fn is_balanced(&self) -> bool {
+        self.shared_inlet.is_some()
+    }
+
+    /// Return all the outlets of the inlet, with their session
+    pub(crate) fn outlets(&self) -> Vec<(MultiAddr, Arc<Mutex<Session>>)> {
+        let mut outlets = vec![(self.outlet_addr.clone(), self.session.clone())];
+        outlets.extend(self.additional_outlets.iter().cloned());
+        outlets
+    }
+
+    /// Stop the sessions of the inlet, then the inlet itself if it is shared by several sessions
+    pub(crate) async fn stop(&self, tcp_transport: &TcpTransport) {
+        for (_, session) in self.outlets() {
+            session.lock().await.stop().await;
+        }
+        if let Some(processor_address) = self
+            .shared_inlet
+            .as_ref()
+            .and_then(|inlet| inlet.processor_address())
+        {
+            if let Err(err) = tcp_transport.stop_inlet(processor_address.clone()).await {
+                error!(%err, "Failed to stop the inlet at {}", self.bind_addr);
+            }
         }
     }
 }
@@ -143,6 +200,9 @@ impl InletInfo {
 pub struct OutletInfo {
     pub(crate) to: HostnamePort,
     pub(crate) worker_addr: Address,
+    /// Address of the outlet receiving the messages intercepted at `worker_addr`,
+    /// for outlets with a portal interceptor
+    pub(crate) intercepted_outlet_addr: Option<Address>,
 }
 
 impl OutletInfo {
@@ -151,10 +211,33 @@ impl OutletInfo {
             Some(addr) => addr.clone(),
             None => Address::from_string(""),
         };
-        Self { to, worker_addr }
+        Self {
+            to,
+            worker_addr,
+            intercepted_outlet_addr: None,
+        }
+    }
+
+    pub(crate) fn with_intercepted_outlet(mut self, intercepted_outlet_addr: Address) -> Self {
+        self.intercepted_outlet_addr = Some(intercepted_outlet_addr);
+        self
     }
 }
 
+#[derive(Clone)]
+pub(crate) struct UdpInletInfo {
+    pub(crate) bind_addr: String,
+    pub(crate) outlet_addr: MultiAddr,
+    pub(crate) processor_address: Address,
+    pub(crate) connection: Connection,
+}
+
+#[derive(Clone)]
+pub(crate) struct UdpOutletInfo {
+    pub(crate) to: HostnamePort,
+    pub(crate) worker_addr: Address,
+}
+
 #[derive(Clone)]
 pub struct RegistryRelayInfo {
     pub(crate) destination_address: MultiAddr,
@@ -171,8 +254,12 @@ pub(crate) struct Registry {
     pub(crate) kafka_services: RegistryOf<Address, KafkaServiceInfo>,
     pub(crate) hop_services: RegistryOf<Address, HopServiceInfo>,
     pub(crate) relays: RegistryOf<String, RegistryRelayInfo>,
+    /// Relays created by the relay service of this node
+    pub(crate) hosted_relays: RelayRegistry,
     pub(crate) inlets: RegistryOf<String, InletInfo>,
     pub(crate) outlets: RegistryOf<Address, OutletInfo>,
+    pub(crate) udp_inlets: RegistryOf<String, UdpInletInfo>,
+    pub(crate) udp_outlets: RegistryOf<Address, UdpOutletInfo>,
     pub(crate) influxdb_services: RegistryOf<Address, ()>, // TODO: what should we persist here?
 }
 
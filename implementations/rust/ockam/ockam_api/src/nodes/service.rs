@@ -8,6 +8,7 @@ use ockam_core::api::{RequestHeader, Response};
 pub(crate) mod background_node_client;
 pub mod default_address;
 mod flow_controls;
+mod identity_rotation;
 pub(crate) mod in_memory_node;
 pub mod kafka_services;
 pub mod messages;
@@ -19,11 +20,13 @@ mod secure_channel;
 pub mod tcp_inlets;
 pub mod tcp_outlets;
 mod transport;
+pub mod udp_portals;
 pub mod workers;
 
 mod certificate_provider;
 mod http;
 mod manager;
+mod metrics;
 mod trust;
 mod worker;
 
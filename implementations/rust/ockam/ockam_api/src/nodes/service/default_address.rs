@@ -5,6 +5,7 @@ pub struct DefaultAddress;
 
 impl DefaultAddress {
     pub const OUTLET_SERVICE: &'static str = "outlet";
+    pub const UDP_OUTLET_SERVICE: &'static str = "udp_outlet";
     pub const RELAY_SERVICE: &'static str = "forwarding_service";
     pub const STATIC_RELAY_SERVICE: &'static str = "static_forwarding_service";
     pub const UPPERCASE_SERVICE: &'static str = "uppercase";
@@ -16,6 +17,7 @@ impl DefaultAddress {
     pub const RENDEZVOUS_SERVICE: &'static str = "rendezvous";
     pub const DIRECT_AUTHENTICATOR: &'static str = "direct_authenticator";
     pub const CREDENTIAL_ISSUER: &'static str = "credential_issuer";
+    pub const REVOCATION_LIST_SERVICE: &'static str = "revocation_list";
     pub const ENROLLMENT_TOKEN_ISSUER: &'static str = "enrollment_token_issuer";
     pub const ENROLLMENT_TOKEN_ACCEPTOR: &'static str = "enrollment_token_acceptor";
     pub const OKTA_IDENTITY_PROVIDER: &'static str = "okta";
@@ -30,7 +32,8 @@ impl DefaultAddress {
     }
 
     pub fn is_valid(name: &str) -> bool {
-        matches!(name, |Self::OUTLET_SERVICE| Self::RELAY_SERVICE
+        matches!(name, |Self::OUTLET_SERVICE| Self::UDP_OUTLET_SERVICE
+            | Self::RELAY_SERVICE
             | Self::STATIC_RELAY_SERVICE
             | Self::UPPERCASE_SERVICE
             | Self::ECHO_SERVICE
@@ -39,6 +42,7 @@ impl DefaultAddress {
             | Self::KEY_EXCHANGER_LISTENER
             | Self::DIRECT_AUTHENTICATOR
             | Self::CREDENTIAL_ISSUER
+            | Self::REVOCATION_LIST_SERVICE
             | Self::ENROLLMENT_TOKEN_ISSUER
             | Self::ENROLLMENT_TOKEN_ACCEPTOR
             | Self::OKTA_IDENTITY_PROVIDER
@@ -50,6 +54,7 @@ impl DefaultAddress {
     pub fn iter() -> impl Iterator<Item = &'static str> {
         [
             Self::OUTLET_SERVICE,
+            Self::UDP_OUTLET_SERVICE,
             Self::RELAY_SERVICE,
             Self::STATIC_RELAY_SERVICE,
             Self::UPPERCASE_SERVICE,
@@ -59,6 +64,7 @@ impl DefaultAddress {
             Self::KEY_EXCHANGER_LISTENER,
             Self::DIRECT_AUTHENTICATOR,
             Self::CREDENTIAL_ISSUER,
+            Self::REVOCATION_LIST_SERVICE,
             Self::ENROLLMENT_TOKEN_ISSUER,
             Self::ENROLLMENT_TOKEN_ACCEPTOR,
             Self::OKTA_IDENTITY_PROVIDER,
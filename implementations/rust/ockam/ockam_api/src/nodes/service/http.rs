@@ -5,6 +5,7 @@ use std::sync::Arc;
 use http_body_util::combinators::BoxBody;
 use http_body_util::{BodyExt, Empty, Full};
 use hyper::body::Bytes;
+use hyper::header::CONTENT_TYPE;
 use hyper::server::conn::http1;
 use hyper::service::service_fn;
 use hyper::{Method, Request, Response};
@@ -13,6 +14,7 @@ use hyper_util::rt::TokioIo;
 use serde::Serialize;
 use tokio::net::TcpListener;
 
+use crate::nodes::service::metrics::OPENMETRICS_CONTENT_TYPE;
 use crate::nodes::NodeManager;
 use crate::{HttpError, Result};
 
@@ -20,6 +22,8 @@ use crate::{HttpError, Result};
 ///
 /// This server is complementary to the node's API and is intended to be used
 /// for health checks and monitoring of the node's status.
+/// The `/metrics` endpoint exposes the node metrics in the OpenMetrics format
+/// so that they can be scraped by Prometheus.
 ///
 /// It is not intended to be a full-fledged HTTP version of the node's API.
 pub struct HttpServer {
@@ -80,6 +84,13 @@ impl HttpServer {
             (&Method::GET, ["show"]) => {
                 Self::json_response(node_manager.get_node_resources().await?)
             }
+            (&Method::GET, ["metrics"]) => {
+                let metrics = node_manager.get_metrics().await?;
+                Ok(Response::builder()
+                    .header(CONTENT_TYPE, OPENMETRICS_CONTENT_TYPE)
+                    .body(Full::new(Bytes::from(metrics)).boxed())
+                    .map_err(HttpError::from)?)
+            }
             _ => {
                 warn!("Request received for a non supported endpoint: {req:?}");
                 Ok(Response::builder()
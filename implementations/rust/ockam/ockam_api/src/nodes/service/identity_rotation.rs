@@ -0,0 +1,95 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use ockam::Result;
+use ockam_core::api::{Error, Response};
+use ockam_core::AsyncTryClone;
+use ockam_node::Context;
+use tokio::select;
+
+use crate::nodes::models::secure_channel::PresentChangeHistoryResponse;
+use crate::nodes::{NodeManager, NodeManagerWorker};
+
+/// IDENTITY ROTATION
+impl NodeManagerWorker {
+    pub(super) async fn present_change_history(
+        &self,
+        ctx: &Context,
+    ) -> Result<Response<PresentChangeHistoryResponse>, Response<Error>> {
+        let notified = self.node_manager.present_change_history(ctx).await?;
+        Ok(Response::ok().body(PresentChangeHistoryResponse::new(notified)))
+    }
+}
+
+impl NodeManager {
+    /// Present the latest change history of the node identity to the other side of all the
+    /// secure channels established by the node, for example after the identity key has been
+    /// rotated by `ockam identity rotate`.
+    /// Return the number of secure channels which were notified
+    pub async fn present_change_history(&self, ctx: &Context) -> Result<usize> {
+        let notified = self
+            .secure_channels
+            .present_change_history(ctx, &self.node_identifier)
+            .await?;
+        info!(
+            "presented the change history of {} on {notified} secure channels",
+            self.node_identifier
+        );
+        Ok(notified)
+    }
+
+    /// Rotate the key of the node identity, then present the new change history on
+    /// all the secure channels established by the node.
+    /// The purpose keys are renewed since they are attested by the previous key.
+    /// Return the number of secure channels which were notified
+    pub async fn rotate_identity(&self, ctx: &Context) -> Result<usize> {
+        let identities = self.secure_channels.identities();
+        identities
+            .identities_creation()
+            .rotate_identity(&self.node_identifier)
+            .await?;
+        identities
+            .purpose_keys()
+            .purpose_keys_creation()
+            .renew_purpose_keys(&self.node_identifier)
+            .await?;
+        info!(
+            "rotated the key of the node identity {}",
+            self.node_identifier
+        );
+        self.present_change_history(ctx).await
+    }
+
+    /// Rotate the key of the node identity in the background, every `rotation_interval`.
+    /// The rotation stops when the node manager is stopped or dropped
+    pub(super) async fn start_identity_rotation(
+        self: &Arc<Self>,
+        ctx: &Context,
+        rotation_interval: Duration,
+    ) -> Result<()> {
+        let node_manager = Arc::downgrade(self);
+        let node_identifier = self.node_identifier.clone();
+        let mut shutdown = self.shutdown.subscribe();
+        let ctx = ctx.async_try_clone().await?;
+        ockam_node::spawn(async move {
+            info!(
+                "rotating the key of the node identity {node_identifier} every {} seconds",
+                rotation_interval.as_secs()
+            );
+            loop {
+                select! {
+                    _ = ctx.sleep(rotation_interval) => {}
+                    _ = shutdown.wait_for(|stopped| *stopped) => break,
+                }
+                let Some(node_manager) = node_manager.upgrade() else {
+                    break;
+                };
+                if let Err(e) = node_manager.rotate_identity(&ctx).await {
+                    warn!("could not rotate the key of the node identity {node_identifier}: {e:?}");
+                }
+            }
+            debug!("stopped the rotation of the node identity {node_identifier}");
+        });
+        Ok(())
+    }
+}
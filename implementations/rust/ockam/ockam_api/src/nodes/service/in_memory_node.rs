@@ -182,8 +182,10 @@ impl InMemoryNode {
     }
 
     pub async fn stop(&self, ctx: &Context) -> Result<()> {
-        for session in self.registry.inlets.values().await {
-            session.session.lock().await.stop().await;
+        self.stop_background_tasks();
+
+        for inlet in self.registry.inlets.values().await {
+            inlet.stop(&self.tcp_transport).await;
         }
 
         for session in self.registry.relays.values().await {
@@ -5,8 +5,9 @@ use crate::kafka::protocol_aware::inlet::KafkaInletInterceptorFactory;
 use crate::kafka::protocol_aware::outlet::KafkaOutletInterceptorFactory;
 use crate::kafka::KafkaOutletController;
 use crate::kafka::{
-    kafka_policy_expression, ConsumerPublishing, ConsumerResolution, KafkaInletController,
-    KAFKA_OUTLET_BOOTSTRAP_ADDRESS, KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
+    kafka_policy_expression, ConsumerPublishing, ConsumerResolution, EncryptionRules,
+    KafkaInletController, TopicEncryptionRule, KAFKA_OUTLET_BOOTSTRAP_ADDRESS,
+    KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
 };
 use crate::nodes::models::portal::OutletAccessControl;
 use crate::nodes::models::services::{
@@ -46,6 +47,10 @@ impl NodeManagerWorker {
                 request.project_route(),
                 request.encrypt_content(),
                 request.encrypted_fields(),
+                request.encryption_rules(),
+                request.encrypt_headers(),
+                request.encrypt_keys(),
+                request.record_keys_secret(),
                 request.consumer_resolution(),
                 request.consumer_publishing(),
                 request.inlet_policy_expression(),
@@ -119,12 +124,26 @@ impl InMemoryNode {
         outlet_node_multiaddr: MultiAddr,
         encrypt_content: bool,
         encrypted_fields: Vec<String>,
+        encryption_rules: Vec<TopicEncryptionRule>,
+        encrypt_headers: bool,
+        encrypt_keys: bool,
+        record_keys_secret: Option<String>,
         consumer_resolution: ConsumerResolution,
         consumer_publishing: ConsumerPublishing,
         inlet_policy_expression: Option<PolicyExpression>,
         consumer_policy_expression: Option<PolicyExpression>,
         producer_policy_expression: Option<PolicyExpression>,
     ) -> Result<()> {
+        // the rules are validated before creating any resource
+        let record_keys_secret = if encrypt_keys {
+            Some(self.record_keys_secret(record_keys_secret).await?)
+        } else {
+            None
+        };
+        let encryption_rules = EncryptionRules::new(encrypted_fields, encryption_rules)?
+            .with_headers_encryption(encrypt_headers)
+            .with_keys_encryption(record_keys_secret);
+
         let consumer_policy_access_control = self
             .policy_access_control(
                 self.project_authority().clone(),
@@ -237,7 +256,7 @@ impl InMemoryNode {
                 secure_channel_controller,
                 inlet_controller,
                 encrypt_content,
-                encrypted_fields,
+                encryption_rules,
             )),
             Arc::new(policy_access_control.create_incoming()),
             Arc::new(policy_access_control.create_outgoing(context).await?),
@@ -255,6 +274,27 @@ impl InMemoryNode {
         Ok(())
     }
 
+    /// Return the secret used to encrypt the records keys: either the hex encoded secret shared
+    /// with other inlets, or the secret persisted for this node
+    async fn record_keys_secret(&self, record_keys_secret: Option<String>) -> Result<Vec<u8>> {
+        match record_keys_secret {
+            Some(secret) => {
+                let secret = hex::decode(secret)
+                    .map_err(|_| ApiError::core("the records keys secret must be hex encoded"))?;
+                if secret.len() < 32 {
+                    return Err(ApiError::core(
+                        "the records keys secret must be at least 32 bytes long",
+                    ));
+                }
+                Ok(secret)
+            }
+            None => Ok(self
+                .cli_state
+                .get_or_create_kafka_record_keys_secret(&self.node_name())
+                .await?),
+        }
+    }
+
     pub async fn start_kafka_outlet_service(
         &self,
         context: &Context,
@@ -1,7 +1,8 @@
+use crate::authenticator::revocation_list::RevocationListRetriever;
 use crate::cloud::project::Project;
 use crate::cloud::{AuthorityNodeClient, ControllerClient, CredentialsEnabled, ProjectNodeClient};
 use crate::nodes::connection::{
-    Connection, ConnectionBuilder, PlainTcpInstantiator, ProjectInstantiator,
+    Connection, ConnectionBuilder, PlainTcpInstantiator, PlainUdpInstantiator, ProjectInstantiator,
     SecureChannelInstantiator,
 };
 use crate::nodes::models::portal::OutletStatus;
@@ -26,7 +27,7 @@ use ockam::tcp::TcpTransport;
 use ockam::udp::{
     UdpPunctureNegotiationListener, UdpPunctureNegotiationListenerOptions, UdpTransport,
 };
-use ockam::{RelayService, RelayServiceOptions};
+use ockam::{RelayLoadBalancing, RelayService, RelayServiceOptions};
 use ockam_abac::expr::str;
 use ockam_abac::{
     Action, Env, Policies, PolicyAccessControl, PolicyExpression, Resource, ResourceType, Resources,
@@ -41,6 +42,7 @@ use ockam_node::Context;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::watch;
 
 /// Node manager provides high-level operations to
 ///  - send messages
@@ -59,6 +61,8 @@ pub struct NodeManager {
     pub(crate) credential_retriever_creators: CredentialRetrieverCreators,
     pub(super) project_authority: Option<Identifier>,
     pub(crate) registry: Arc<Registry>,
+    /// Set to true when the node is stopped, to stop the background tasks of the node manager
+    pub(super) shutdown: watch::Sender<bool>,
 }
 
 impl NodeManager {
@@ -85,6 +89,7 @@ impl NodeManager {
             .await?;
 
         let secure_channels = cli_state.secure_channels(&node_name).await?;
+        let shutdown = watch::Sender::new(false);
 
         let project_member_credential_retriever_creator: Option<
             Arc<dyn CredentialRetrieverCreator>,
@@ -98,6 +103,17 @@ impl NodeManager {
                 )))
             }
             NodeManagerCredentialRetrieverOptions::Remote { info, scope } => {
+                // Keep track of the credentials revoked by the project authority
+                RevocationListRetriever::new(
+                    ctx.async_try_clone().await?,
+                    Arc::new(transport_options.tcp_transport.clone()),
+                    secure_channels.clone(),
+                    info.issuer.clone(),
+                    info.route.clone(),
+                    node_identifier.clone(),
+                )
+                .start(shutdown.subscribe());
+
                 Some(Arc::new(RemoteCredentialRetrieverCreator::new(
                     ctx.async_try_clone().await?,
                     Arc::new(transport_options.tcp_transport.clone()),
@@ -154,11 +170,16 @@ impl NodeManager {
             credential_retriever_creators,
             project_authority: trust_options.project_authority,
             registry,
+            shutdown,
         };
 
         debug!("initializing services");
-        s.initialize_services(ctx, general_options.start_default_services)
-            .await?;
+        s.initialize_services(
+            ctx,
+            general_options.start_default_services,
+            general_options.relay_load_balancing,
+        )
+        .await?;
 
         let s = Arc::new(s);
 
@@ -198,6 +219,11 @@ impl NodeManager {
             }
         }
 
+        if let Some(identity_rotation_interval) = general_options.identity_rotation_interval {
+            s.start_identity_rotation(ctx, identity_rotation_interval)
+                .await?;
+        }
+
         info!("created a node manager for the node: {}", s.node_name);
 
         Ok(s)
@@ -207,6 +233,7 @@ impl NodeManager {
         &self,
         ctx: &Context,
         api_flow_control_id: &FlowControlId,
+        relay_load_balancing: RelayLoadBalancing,
     ) -> ockam_core::Result<SecureChannelListener> {
         // Start services
         ctx.flow_controls()
@@ -228,7 +255,9 @@ impl NodeManager {
             .alias(DefaultAddress::STATIC_RELAY_SERVICE)
             .service_as_consumer(api_flow_control_id)
             .relay_as_consumer(api_flow_control_id)
-            .prefix("forward_to_");
+            .prefix("forward_to_")
+            .load_balancing(relay_load_balancing)
+            .registry(self.registry.hosted_relays.clone());
 
         let options = if let Some(authority) = &self.project_authority {
             let policy_access_control = self
@@ -264,12 +293,13 @@ impl NodeManager {
         &mut self,
         ctx: &Context,
         start_default_services: bool,
+        relay_load_balancing: RelayLoadBalancing,
     ) -> ockam_core::Result<()> {
         let api_flow_control_id = self.api_transport_flow_control_id.clone();
 
         if start_default_services {
             self.api_sc_listener = Some(
-                self.initialize_default_services(ctx, &api_flow_control_id)
+                self.initialize_default_services(ctx, &api_flow_control_id, relay_load_balancing)
                     .await?,
             );
         }
@@ -317,6 +347,8 @@ impl NodeManager {
             .await?
             .instantiate(ctx, self, PlainTcpInstantiator::new())
             .await?
+            .instantiate(ctx, self, PlainUdpInstantiator::new())
+            .await?
             .instantiate(
                 ctx,
                 self,
@@ -374,6 +406,11 @@ impl NodeManager {
         &self.tcp_transport
     }
 
+    /// Stop the tasks running periodically in the background, when the node is stopped
+    pub fn stop_background_tasks(&self) {
+        self.shutdown.send_replace(true);
+    }
+
     pub async fn list_outlets(&self) -> Vec<OutletStatus> {
         self.registry
             .outlets
@@ -614,6 +651,8 @@ pub struct NodeManagerGeneralOptions {
     pub(super) start_default_services: bool,
     pub(super) http_server_port: Option<u16>,
     pub(super) persistent: bool,
+    pub(super) identity_rotation_interval: Option<Duration>,
+    pub(super) relay_load_balancing: RelayLoadBalancing,
 }
 
 impl NodeManagerGeneralOptions {
@@ -630,8 +669,26 @@ impl NodeManagerGeneralOptions {
             start_default_services,
             http_server_port,
             persistent,
+            identity_rotation_interval: None,
+            relay_load_balancing: RelayLoadBalancing::default(),
         }
     }
+
+    /// Rotate the key of the node identity at a regular interval
+    pub fn with_identity_rotation_interval(
+        mut self,
+        identity_rotation_interval: Option<Duration>,
+    ) -> Self {
+        self.identity_rotation_interval = identity_rotation_interval;
+        self
+    }
+
+    /// Strategy used by the relay service of the node to choose the node receiving a new
+    /// connection, when several nodes registered a relay with the same name
+    pub fn with_relay_load_balancing(mut self, relay_load_balancing: RelayLoadBalancing) -> Self {
+        self.relay_load_balancing = relay_load_balancing;
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -0,0 +1,351 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use ockam_core::Result;
+use ockam_node::WorkerStats;
+use ockam_transport_tcp::TcpPortalStats;
+
+use crate::nodes::NodeManager;
+use crate::session::connection_status::ConnectionStatus;
+use crate::session::session::Session;
+
+/// Content type of the `/metrics` endpoint
+pub(crate) const OPENMETRICS_CONTENT_TYPE: &str =
+    "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+impl NodeManager {
+    /// Return the node metrics, encoded with the OpenMetrics text format
+    /// so that they can be scraped by Prometheus
+    pub async fn get_metrics(&self) -> Result<String> {
+        let mut metrics = OpenMetricsEncoder::default();
+
+        let ctx = self.tcp_transport.ctx();
+        Self::encode_worker_metrics(&mut metrics, ctx.list_worker_stats().await?);
+        self.encode_secure_channel_metrics(&mut metrics);
+        self.encode_portal_metrics(&mut metrics);
+        self.encode_session_metrics(&mut metrics).await;
+
+        let flow_controls = ctx.flow_controls();
+        metrics.gauge(
+            "ockam_flow_control_producers",
+            "Number of flow control producers",
+            vec![(vec![], flow_controls.number_of_producers() as u64)],
+        );
+        metrics.gauge(
+            "ockam_flow_control_consumers",
+            "Number of flow control consumers",
+            vec![(vec![], flow_controls.number_of_consumers() as u64)],
+        );
+        metrics.gauge(
+            "ockam_flow_control_spawners",
+            "Number of flow control spawners",
+            vec![(vec![], flow_controls.number_of_spawners() as u64)],
+        );
+
+        Ok(metrics.finish())
+    }
+
+    /// Encode the statistics of the workers, summed by worker type.
+    /// Workers are not labelled by address since addresses are often random, which would make
+    /// the number of series grow with each new worker
+    fn encode_worker_metrics(metrics: &mut OpenMetricsEncoder, stats: Vec<WorkerStats>) {
+        let mut by_type: BTreeMap<(&'static str, bool), WorkerStats> = BTreeMap::new();
+        for s in stats {
+            by_type
+                .entry((s.worker_type, s.processor))
+                .and_modify(|sum| {
+                    sum.routed_messages += s.routed_messages;
+                    sum.mailbox_size += s.mailbox_size;
+                    sum.mailbox_capacity += s.mailbox_capacity;
+                    sum.dropped_messages += s.dropped_messages;
+                    sum.rejected_messages += s.rejected_messages;
+                })
+                .or_insert(s);
+        }
+
+        let samples = |value: fn(&WorkerStats) -> u64| -> Vec<(Labels, u64)> {
+            by_type
+                .iter()
+                .map(|((worker_type, processor), s)| {
+                    let kind = if *processor { "processor" } else { "worker" };
+                    let labels = vec![
+                        ("worker_type", worker_type.to_string()),
+                        ("kind", kind.to_string()),
+                    ];
+                    (labels, value(s))
+                })
+                .collect()
+        };
+
+        metrics.counter(
+            "ockam_worker_routed_messages",
+            "Number of messages routed to the workers of a given type",
+            samples(|s| s.routed_messages),
+        );
+        metrics.gauge(
+            "ockam_worker_mailbox_messages",
+            "Number of messages waiting in the mailboxes of the workers of a given type",
+            samples(|s| s.mailbox_size),
+        );
+        metrics.gauge(
+            "ockam_worker_mailbox_capacity",
+            "Maximum number of messages waiting in the mailboxes of the workers of a given type",
+            samples(|s| s.mailbox_capacity),
+        );
+        metrics.counter(
+            "ockam_worker_mailbox_dropped_messages",
+            "Number of messages dropped because a worker mailbox was full",
+            samples(|s| s.dropped_messages),
+        );
+        metrics.counter(
+            "ockam_worker_mailbox_rejected_messages",
+            "Number of messages rejected because a worker mailbox was full",
+            samples(|s| s.rejected_messages),
+        );
+    }
+
+    fn encode_secure_channel_metrics(&self, metrics: &mut OpenMetricsEncoder) {
+        let stats = self.secure_channels.secure_channel_registry().stats();
+        metrics.gauge(
+            "ockam_secure_channels_open",
+            "Number of open secure channels",
+            vec![(vec![], stats.open_channels)],
+        );
+        metrics.counter(
+            "ockam_secure_channel_handshakes_started",
+            "Number of secure channel handshakes started",
+            vec![(vec![], stats.handshakes_started)],
+        );
+        metrics.counter(
+            "ockam_secure_channel_handshakes_completed",
+            "Number of secure channel handshakes completed",
+            vec![(vec![], stats.handshakes_completed)],
+        );
+        metrics.counter(
+            "ockam_secure_channel_handshakes_failed",
+            "Number of secure channel handshakes that failed or timed out",
+            vec![(vec![], stats.handshakes_failed)],
+        );
+    }
+
+    fn encode_portal_metrics(&self, metrics: &mut OpenMetricsEncoder) {
+        let stats = self.tcp_transport.registry().get_all_portal_stats();
+        let samples = |value: fn(&TcpPortalStats) -> u64| -> Vec<(Labels, u64)> {
+            stats
+                .iter()
+                .map(|s| {
+                    let portal = if s.is_inlet() { "inlet" } else { "outlet" };
+                    let labels = vec![
+                        ("address", s.address().to_string()),
+                        ("portal", portal.to_string()),
+                    ];
+                    (labels, value(s))
+                })
+                .collect()
+        };
+
+        metrics.gauge(
+            "ockam_portal_connections",
+            "Number of open portal connections",
+            samples(|s| s.active_connections()),
+        );
+        metrics.counter(
+            "ockam_portal_connections_opened",
+            "Number of portal connections opened",
+            samples(|s| s.total_connections()),
+        );
+        metrics.counter(
+            "ockam_portal_received_bytes",
+            "Number of bytes read from the TCP connections of a portal",
+            samples(|s| s.bytes_in()),
+        );
+        metrics.counter(
+            "ockam_portal_sent_bytes",
+            "Number of bytes written to the TCP connections of a portal",
+            samples(|s| s.bytes_out()),
+        );
+    }
+
+    async fn encode_session_metrics(&self, metrics: &mut OpenMetricsEncoder) {
+        let mut relays = vec![];
+        let mut sessions = vec![];
+        for (alias, info) in self.registry.relays.entries().await {
+            let (up, replacing) = session_status(&*info.session.lock().await);
+            relays.push((vec![("relay", alias.clone())], up));
+            let labels = vec![("kind", "relay".to_string()), ("name", alias)];
+            sessions.push((labels, up, replacing));
+        }
+        for (alias, info) in self.registry.inlets.entries().await {
+            for (outlet_addr, session) in info.outlets() {
+                let (up, replacing) = session_status(&*session.lock().await);
+                let mut labels = vec![("kind", "inlet".to_string()), ("name", alias.clone())];
+                // an inlet spreading its connections between several outlets has one session per outlet
+                if info.is_balanced() {
+                    labels.push(("outlet", outlet_addr.to_string()));
+                }
+                sessions.push((labels, up, replacing));
+            }
+        }
+
+        metrics.gauge(
+            "ockam_relay_up",
+            "1 if the relay is connected, 0 otherwise",
+            relays,
+        );
+        metrics.gauge(
+            "ockam_session_up",
+            "1 if the session connection is up, 0 otherwise",
+            sessions
+                .iter()
+                .map(|(labels, up, _)| (labels.clone(), *up))
+                .collect(),
+        );
+        metrics.gauge(
+            "ockam_session_replacing",
+            "1 if the session connection is being replaced, 0 otherwise",
+            sessions
+                .into_iter()
+                .map(|(labels, _, replacing)| (labels, replacing))
+                .collect(),
+        );
+    }
+}
+
+fn session_status(session: &Session) -> (u64, u64) {
+    let up = session.connection_status() == ConnectionStatus::Up;
+    (up as u64, session.is_being_replaced() as u64)
+}
+
+/// Encoder for the OpenMetrics text exposition format
+///
+/// See <https://github.com/OpenObservability/OpenMetrics/blob/main/specification/OpenMetrics.md>
+#[derive(Default)]
+pub(crate) struct OpenMetricsEncoder {
+    output: String,
+}
+
+type Labels = Vec<(&'static str, String)>;
+
+impl OpenMetricsEncoder {
+    /// Add a counter metric family. The `_total` suffix is added to the samples names
+    pub(crate) fn counter(&mut self, name: &str, help: &str, samples: Vec<(Labels, u64)>) {
+        self.family(name, "counter", help, "_total", samples)
+    }
+
+    /// Add a gauge metric family
+    pub(crate) fn gauge(&mut self, name: &str, help: &str, samples: Vec<(Labels, u64)>) {
+        self.family(name, "gauge", help, "", samples)
+    }
+
+    /// Return the encoded metrics
+    pub(crate) fn finish(mut self) -> String {
+        self.output.push_str("# EOF\n");
+        self.output
+    }
+
+    fn family(
+        &mut self,
+        name: &str,
+        kind: &str,
+        help: &str,
+        suffix: &str,
+        samples: Vec<(Labels, u64)>,
+    ) {
+        let _ = writeln!(self.output, "# TYPE {name} {kind}");
+        let _ = writeln!(self.output, "# HELP {name} {}", escape(help));
+        for (labels, value) in samples {
+            let _ = write!(self.output, "{name}{suffix}");
+            if !labels.is_empty() {
+                let labels = labels
+                    .iter()
+                    .map(|(k, v)| format!("{k}=\"{}\"", escape(v)))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                let _ = write!(self.output, "{{{labels}}}");
+            }
+            let _ = writeln!(self.output, " {value}");
+        }
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_metrics() {
+        let mut metrics = OpenMetricsEncoder::default();
+        metrics.gauge(
+            "ockam_secure_channels_open",
+            "Open channels",
+            vec![(vec![], 2)],
+        );
+        metrics.counter(
+            "ockam_portal_sent_bytes",
+            "Sent bytes",
+            vec![
+                (vec![("address", "outlet".to_string())], 10),
+                (vec![("address", "in\"let\\".to_string())], 20),
+            ],
+        );
+        metrics.gauge("ockam_relay_up", "Relay status", vec![]);
+
+        assert_eq!(
+            metrics.finish(),
+            r#"# TYPE ockam_secure_channels_open gauge
+# HELP ockam_secure_channels_open Open channels
+ockam_secure_channels_open 2
+# TYPE ockam_portal_sent_bytes counter
+# HELP ockam_portal_sent_bytes Sent bytes
+ockam_portal_sent_bytes_total{address="outlet"} 10
+ockam_portal_sent_bytes_total{address="in\"let\\"} 20
+# TYPE ockam_relay_up gauge
+# HELP ockam_relay_up Relay status
+# EOF
+"#
+        );
+    }
+
+    #[test]
+    fn test_encode_worker_metrics_by_type() {
+        let stats = |address: &str, worker_type, routed_messages| WorkerStats {
+            address: address.into(),
+            processor: false,
+            worker_type,
+            routed_messages,
+            mailbox_size: 1,
+            mailbox_capacity: 10,
+            max_mailbox_size: 2,
+            dropped_messages: 0,
+            rejected_messages: 0,
+        };
+        let mut metrics = OpenMetricsEncoder::default();
+        NodeManager::encode_worker_metrics(
+            &mut metrics,
+            vec![
+                stats("a1b2c3", "SecureChannel", 3),
+                stats("d4e5f6", "SecureChannel", 4),
+                stats("echo", "Echoer", 5),
+            ],
+        );
+        let output = metrics.finish();
+
+        assert!(output.contains(
+            r#"ockam_worker_routed_messages_total{worker_type="Echoer",kind="worker"} 5"#
+        ));
+        assert!(output.contains(
+            r#"ockam_worker_routed_messages_total{worker_type="SecureChannel",kind="worker"} 7"#
+        ));
+        assert!(output.contains(
+            r#"ockam_worker_mailbox_capacity{worker_type="SecureChannel",kind="worker"} 20"#
+        ));
+        assert!(!output.contains("a1b2c3"));
+    }
+}
@@ -1,10 +1,19 @@
-use ockam_abac::{Action, PolicyExpression};
+use ockam_abac::expr::str;
+use ockam_abac::{
+    eval_with_trace, subject_has_credential_attribute, subject_identifier_attribute, Abac, Action,
+    Env, Expr, PolicyDecision, PolicyDecisionsQuery, PolicyExpression, CURRENT_TIME_KEY,
+    SUBJECT_KEY,
+};
 use ockam_core::api::{Error, Request, Response};
+use ockam_core::compat::time::now;
+use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{async_trait, Result};
 use ockam_node::Context;
 use std::str::FromStr;
 
-use crate::nodes::models::policies::{PoliciesList, Policy, ResourceTypeOrName, SetPolicyRequest};
+use crate::nodes::models::policies::{
+    PoliciesList, Policy, PolicyTestResult, ResourceTypeOrName, SetPolicyRequest, TestPolicyRequest,
+};
 use crate::nodes::{BackgroundNodeClient, NodeManagerWorker};
 
 use super::NodeManager;
@@ -57,6 +66,32 @@ impl NodeManagerWorker {
             Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
         }
     }
+
+    pub(super) async fn get_policy_decisions(
+        &self,
+        query: PolicyDecisionsQuery,
+    ) -> Result<Response<Vec<PolicyDecision>>, Response<Error>> {
+        match self.node_manager.get_policy_decisions(&query).await {
+            Ok(decisions) => Ok(Response::ok().body(decisions)),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
+
+    pub(super) async fn test_policy(
+        &self,
+        request: TestPolicyRequest,
+    ) -> Result<Response<PolicyTestResult>, Response<Error>> {
+        match self.node_manager.test_policy(request).await {
+            Ok(result) => Ok(Response::ok().body(result)),
+            Err(e) if e.code().kind == Kind::NotFound => {
+                Err(Response::not_found_no_request(&e.to_string()))
+            }
+            Err(e) if e.code().kind == Kind::Invalid => {
+                Err(Response::bad_request_no_request(&e.to_string()))
+            }
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
 }
 
 impl NodeManager {
@@ -129,6 +164,129 @@ impl NodeManager {
         }
     }
 
+    /// Return the audited policy decisions matching a query, the most recent ones first
+    pub async fn get_policy_decisions(
+        &self,
+        query: &PolicyDecisionsQuery,
+    ) -> Result<Vec<PolicyDecision>> {
+        self.cli_state
+            .policy_decisions(&self.node_name)
+            .get_decisions(query)
+            .await
+    }
+
+    /// Evaluate the policy of a resource for a subject, without accessing the resource.
+    ///
+    /// When explicit attributes are given, they are considered as the attributes of a
+    /// valid credential for the subject.
+    pub async fn test_policy(&self, request: TestPolicyRequest) -> Result<PolicyTestResult> {
+        let expression: Expr = match request.expression {
+            Some(expression) => expression.into(),
+            None => self
+                .get_enforced_expression(&request.resource, &request.action)
+                .await?
+                .ok_or_else(|| {
+                    ockam_core::Error::new(
+                        Origin::Api,
+                        Kind::NotFound,
+                        format!(
+                            "No policy found for resource '{}' and action '{}'",
+                            request.resource, request.action
+                        ),
+                    )
+                })?,
+        };
+
+        // Populate the environment like the access controls created by the node
+        let mut env = Env::new();
+        if let ResourceTypeOrName::Name(resource_name) = &request.resource {
+            env.put("resource.id", str(resource_name.as_str()));
+        }
+        env.put("action.id", str(request.action.as_ref()));
+
+        let env = match (request.subject, request.attributes) {
+            (subject, Some(attributes)) => {
+                if let Some(subject) = subject {
+                    env.put(
+                        subject_identifier_attribute().to_string(),
+                        str(subject.to_string()),
+                    );
+                }
+                env.put(
+                    subject_has_credential_attribute().to_string(),
+                    Expr::CONST_TRUE,
+                );
+                for (key, value) in attributes {
+                    env.put(format!("{SUBJECT_KEY}.{key}"), str(value));
+                }
+                if !env.contains(CURRENT_TIME_KEY) {
+                    env.put_current_time(now()?);
+                }
+                env
+            }
+            (Some(subject), None) => {
+                Abac::subject_environment(
+                    self.cli_state.identities_attributes(&self.node_name),
+                    &env,
+                    self.project_authority().as_ref(),
+                    &subject,
+                )
+                .await?
+            }
+            (None, None) => {
+                return Err(ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::Invalid,
+                    "A subject identifier or some subject attributes must be provided",
+                ))
+            }
+        };
+
+        let (result, steps) = eval_with_trace(&expression, &env);
+        let (allowed, result) = match result {
+            Ok(value) => (value.is_true(), value.to_string()),
+            Err(e) => (false, e.to_string()),
+        };
+        Ok(PolicyTestResult {
+            allowed,
+            expression,
+            result,
+            steps,
+        })
+    }
+
+    /// Return the expression enforced for an action on a resource.
+    ///
+    /// As in the policy access controls, a resource name without its own policy
+    /// is checked with the policy of its resource type.
+    async fn get_enforced_expression(
+        &self,
+        resource: &ResourceTypeOrName,
+        action: &Action,
+    ) -> Result<Option<Expr>> {
+        match resource {
+            ResourceTypeOrName::Type(resource_type) => Ok(self
+                .policies()
+                .get_policy_for_resource_type(resource_type, action)
+                .await?
+                .map(|p| p.expression)),
+            ResourceTypeOrName::Name(resource_name) => {
+                match self.resources().get_resource(resource_name).await? {
+                    Some(resource) => {
+                        self.policies()
+                            .get_expression_for_resource(&resource, action)
+                            .await
+                    }
+                    None => Ok(self
+                        .policies()
+                        .get_policy_for_resource_name(resource_name, action)
+                        .await?
+                        .map(|p| p.expression)),
+                }
+            }
+        }
+    }
+
     pub async fn delete_policy(&self, resource: ResourceTypeOrName, action: &str) -> Result<()> {
         let action = Action::from_str(action)?;
         match resource {
@@ -179,6 +337,18 @@ pub trait Policies {
         resource: &ResourceTypeOrName,
         action: &Action,
     ) -> miette::Result<()>;
+
+    async fn get_policy_decisions(
+        &self,
+        ctx: &Context,
+        query: &PolicyDecisionsQuery,
+    ) -> miette::Result<Vec<PolicyDecision>>;
+
+    async fn test_policy(
+        &self,
+        ctx: &Context,
+        request: &TestPolicyRequest,
+    ) -> miette::Result<PolicyTestResult>;
 }
 
 #[async_trait]
@@ -225,4 +395,72 @@ impl Policies for BackgroundNodeClient {
         self.tell(ctx, request).await?;
         Ok(())
     }
+
+    async fn get_policy_decisions(
+        &self,
+        ctx: &Context,
+        query: &PolicyDecisionsQuery,
+    ) -> miette::Result<Vec<PolicyDecision>> {
+        let request = Request::get("/policy/audit").body(query);
+        self.ask(ctx, request).await
+    }
+
+    async fn test_policy(
+        &self,
+        ctx: &Context,
+        request: &TestPolicyRequest,
+    ) -> miette::Result<PolicyTestResult> {
+        let request = Request::get("/policy/test").body(request);
+        self.ask(ctx, request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{start_manager_for_tests, TestNode};
+    use ockam_abac::{Resource, ResourceName, ResourceType};
+    use std::collections::BTreeMap;
+
+    #[ockam_macros::test]
+    async fn test_policy_of_a_resource_uses_the_policy_of_its_resource_type(
+        context: &mut Context,
+    ) -> Result<()> {
+        TestNode::clean().await?;
+        let handle = start_manager_for_tests(context, None, None).await?;
+        let node_manager = &handle.node_manager;
+
+        // only the resource type has a policy
+        node_manager
+            .resources()
+            .store_resource(&Resource::new("outlet-1", ResourceType::TcpOutlet))
+            .await?;
+        node_manager
+            .set_policy(
+                ResourceTypeOrName::Type(ResourceType::TcpOutlet),
+                Action::HandleMessage.as_ref(),
+                PolicyExpression::from_str("(= subject.role \"admin\")")?,
+            )
+            .await?;
+
+        let request = |role: &str| TestPolicyRequest {
+            resource: ResourceTypeOrName::Name(ResourceName::from("outlet-1")),
+            action: Action::HandleMessage,
+            subject: None,
+            attributes: Some(BTreeMap::from([("role".to_string(), role.to_string())])),
+            expression: None,
+        };
+
+        let result = node_manager.test_policy(request("admin")).await?;
+        assert!(result.allowed);
+        let result = node_manager.test_policy(request("guest")).await?;
+        assert!(!result.allowed);
+
+        // an unknown resource without a policy is reported as not found
+        let mut unknown = request("admin");
+        unknown.resource = ResourceTypeOrName::Name(ResourceName::from("outlet-2"));
+        let error = node_manager.test_policy(unknown).await.unwrap_err();
+        assert_eq!(error.code().kind, Kind::NotFound);
+        Ok(())
+    }
 }
@@ -106,7 +106,8 @@ impl NodeManagerWorker {
 
 impl NodeManager {
     /// This function returns a representation of the relays currently
-    /// registered on this node
+    /// registered on this node, followed by the relays hosted by this node
+    /// with the nodes registered for each of them
     pub async fn get_relays(&self) -> Vec<RelayInfo> {
         let mut relays = vec![];
         for (_, registry_info) in self.registry.relays.entries().await {
@@ -118,6 +119,9 @@ impl NodeManager {
             );
             relays.push(info);
         }
+        for status in self.registry.hosted_relays.relays() {
+            relays.push(RelayInfo::from_hosted_relay(&status));
+        }
 
         trace!(?relays, "Relays retrieved");
         relays
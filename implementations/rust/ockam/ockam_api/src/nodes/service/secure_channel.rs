@@ -96,15 +96,18 @@ impl NodeManagerWorker {
     ) -> Result<Response<ShowSecureChannelResponse>, Response<Error>> {
         let ShowSecureChannelRequest { channel: address } = show_secure_channel;
 
-        let response =
-            self.node_manager
-                .get_secure_channel(&address)
-                .await
-                .map(|secure_channel| {
-                    Response::ok().body(ShowSecureChannelResponse::new(Some(secure_channel)))
-                })?;
+        let secure_channel = self.node_manager.get_secure_channel(&address).await?;
+        let mut response = ShowSecureChannelResponse::new(Some(secure_channel));
+        if let Some(entry) = self
+            .node_manager
+            .secure_channels
+            .secure_channel_registry()
+            .get_channel_by_encryptor_address(&address)
+        {
+            response = response.with_rekey_events(entry.rekey_events());
+        }
 
-        Ok(response)
+        Ok(Response::ok().body(response))
     }
 }
 
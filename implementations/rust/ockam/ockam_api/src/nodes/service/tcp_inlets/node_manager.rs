@@ -1,18 +1,20 @@
+use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 
 use crate::address::get_free_address_for;
 use ockam::identity::Identifier;
+use ockam::tcp::InletLoadBalancing;
 use ockam::Result;
 use ockam_abac::{PolicyExpression, Resource, ResourceType};
 use ockam_core::errcode::{Kind, Origin};
-use ockam_core::{AsyncTryClone, Route};
+use ockam_core::{route, AsyncTryClone, Route};
 use ockam_multiaddr::MultiAddr;
 use ockam_node::compat::asynchronous::Mutex;
 use ockam_node::Context;
 use ockam_transport_core::HostnamePort;
 
-use crate::nodes::models::portal::InletStatus;
+use crate::nodes::models::portal::{InletOutletStatus, InletStatus};
 use crate::nodes::registry::InletInfo;
 use crate::nodes::service::tcp_inlets::InletSessionReplacer;
 use crate::nodes::NodeManager;
@@ -64,47 +66,7 @@ impl NodeManager {
             None
         };
 
-        // the port could be zero, to simplify the following code we
-        // resolve the address to a full socket address
-        let socket_addr =
-            ockam_node::compat::asynchronous::resolve_peer(listen_addr.to_string()).await?;
-        let listen_addr = if listen_addr.port() == 0 {
-            get_free_address_for(&socket_addr.ip().to_string())
-                .map_err(|err| ockam_core::Error::new(Origin::Transport, Kind::Invalid, err))?
-        } else {
-            socket_addr
-        };
-
-        // Check registry for duplicated alias or bind address
-        {
-            let registry = &self.registry.inlets;
-
-            // Check that there is no entry in the registry with the same alias
-            if registry.contains_key(&alias).await {
-                let message = format!("A TCP inlet with alias '{alias}' already exists");
-                return Err(ockam_core::Error::new(
-                    Origin::Node,
-                    Kind::AlreadyExists,
-                    message,
-                ));
-            }
-
-            // Check that there is no entry in the registry with the same TCP bind address
-            if registry
-                .values()
-                .await
-                .iter()
-                .any(|inlet| inlet.bind_addr == listen_addr.to_string())
-            {
-                let message =
-                    format!("A TCP inlet with bind tcp address '{listen_addr}' already exists");
-                return Err(ockam_core::Error::new(
-                    Origin::Node,
-                    Kind::AlreadyExists,
-                    message,
-                ));
-            }
-        }
+        let listen_addr = self.new_inlet_listen_addr(&listen_addr, &alias).await?;
 
         let replacer = InletSessionReplacer {
             node_manager: Arc::downgrade(self),
@@ -122,6 +84,7 @@ impl NodeManager {
             disable_tcp_fallback,
             tls_certificate_provider,
             inlet: None,
+            shared_inlet: false,
             connection: None,
             main_route: None,
             additional_secure_channel: None,
@@ -199,11 +162,190 @@ impl NodeManager {
         Ok(tcp_inlet_status)
     }
 
+    /// Resolve the address an inlet listens at, and check that no other inlet
+    /// uses the same alias or the same address
+    async fn new_inlet_listen_addr(
+        &self,
+        listen_addr: &HostnamePort,
+        alias: &str,
+    ) -> Result<SocketAddr> {
+        // the port could be zero, to simplify the following code we
+        // resolve the address to a full socket address
+        let socket_addr =
+            ockam_node::compat::asynchronous::resolve_peer(listen_addr.to_string()).await?;
+        let listen_addr = if listen_addr.port() == 0 {
+            get_free_address_for(&socket_addr.ip().to_string())
+                .map_err(|err| ockam_core::Error::new(Origin::Transport, Kind::Invalid, err))?
+        } else {
+            socket_addr
+        };
+
+        // Check registry for duplicated alias or bind address
+        let registry = &self.registry.inlets;
+
+        // Check that there is no entry in the registry with the same alias
+        if registry.contains_key(alias).await {
+            let message = format!("A TCP inlet with alias '{alias}' already exists");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::AlreadyExists,
+                message,
+            ));
+        }
+
+        // Check that there is no entry in the registry with the same TCP bind address
+        if registry
+            .values()
+            .await
+            .iter()
+            .any(|inlet| inlet.bind_addr == listen_addr.to_string())
+        {
+            let message =
+                format!("A TCP inlet with bind tcp address '{listen_addr}' already exists");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::AlreadyExists,
+                message,
+            ));
+        }
+
+        Ok(listen_addr)
+    }
+
+    /// Create an inlet spreading its connections between several outlets.
+    ///
+    /// A session is created for each outlet and checks that the outlet can still be reached.
+    /// When it can't, the outlet is taken out of the rotation until the session is restored.
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip_all)]
+    pub async fn create_balanced_inlet(
+        self: &Arc<Self>,
+        ctx: &Context,
+        listen_addr: HostnamePort,
+        outlet_addrs: Vec<MultiAddr>,
+        load_balancing: InletLoadBalancing,
+        alias: String,
+        policy_expression: Option<PolicyExpression>,
+        wait_for_outlet_duration: Option<Duration>,
+        authorized: Option<Identifier>,
+        wait_connection: bool,
+        secure_channel_identifier: Option<Identifier>,
+        tls_certificate_provider: Option<MultiAddr>,
+    ) -> Result<InletStatus> {
+        info!("Handling request to create a load-balanced inlet portal");
+        debug! {
+            listen_addr = %listen_addr,
+            outlet_addrs = ?outlet_addrs,
+            %load_balancing,
+            %alias,
+            "Creating load-balanced inlet portal"
+        }
+
+        let Some(main_outlet_addr) = outlet_addrs.first().cloned() else {
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::Invalid,
+                "At least one outlet address is required to create an inlet",
+            ));
+        };
+        let listen_addr = self.new_inlet_listen_addr(&listen_addr, &alias).await?;
+
+        let mut replacers = vec![];
+        for outlet_addr in &outlet_addrs {
+            replacers.push(InletSessionReplacer {
+                node_manager: Arc::downgrade(self),
+                udp_transport: None,
+                context: ctx.async_try_clone().await?,
+                listen_addr: listen_addr.to_string(),
+                outlet_addr: outlet_addr.clone(),
+                prefix_route: route![],
+                suffix_route: route![],
+                authorized: authorized.clone(),
+                wait_for_outlet_duration: wait_for_outlet_duration.unwrap_or(MAX_CONNECT_TIME),
+                resource: Resource::new(alias.clone(), ResourceType::TcpInlet),
+                policy_expression: policy_expression.clone(),
+                secure_channel_identifier: secure_channel_identifier.clone(),
+                disable_tcp_fallback: false,
+                tls_certificate_provider: tls_certificate_provider.clone(),
+                inlet: None,
+                shared_inlet: true,
+                connection: None,
+                main_route: None,
+                additional_secure_channel: None,
+                udp_puncture: None,
+                additional_route: None,
+                ebpf: false,
+            });
+        }
+
+        // The routes to the outlets are only known once each session is connected,
+        // so they start paused
+        let options = replacers[0]
+            .inlet_options(self)
+            .await?
+            .paused()
+            .with_load_balancing(load_balancing);
+        let inlets = self
+            .tcp_transport
+            .create_balanced_inlet(
+                listen_addr.to_string(),
+                outlet_addrs.iter().map(|_| route![]).collect(),
+                options,
+            )
+            .await?;
+        let shared_inlet = inlets[0].clone();
+
+        let mut outlets: Vec<(_, Session)> = vec![];
+        for ((outlet_addr, mut replacer), inlet) in
+            outlet_addrs.into_iter().zip(replacers).zip(inlets)
+        {
+            replacer.inlet = Some(Arc::new(inlet));
+            let replacer: Arc<Mutex<dyn SessionReplacer>> = Arc::new(Mutex::new(replacer));
+            let session = match Session::create(ctx, replacer, None).await {
+                Ok(session) => session,
+                Err(err) => {
+                    for (_, mut session) in outlets {
+                        session.stop().await;
+                    }
+                    let _ = shared_inlet.stop(ctx).await;
+                    return Err(err);
+                }
+            };
+            outlets.push((outlet_addr, session));
+        }
+
+        for (outlet_addr, session) in outlets.iter_mut() {
+            if wait_connection {
+                if let Err(err) = session.initial_connect().await {
+                    warn!(%outlet_addr, "Failed to connect the inlet to the outlet: {err}");
+                }
+            }
+            session.start_monitoring().await?;
+        }
+
+        // The stored inlet only records where the inlet listens, it is not used to recreate
+        // the inlet. So only the first outlet is kept, like the main outlet of the inlet status,
+        // and the table of the inlets keeps one outlet address per inlet
+        let _ = self
+            .cli_state
+            .create_tcp_inlet(&self.node_name, &listen_addr, &main_outlet_addr, &alias)
+            .await?;
+
+        let inlet_info = InletInfo::new_balanced(&listen_addr.to_string(), outlets, shared_inlet)
+            .ok_or_else(|| {
+            ockam_core::Error::new(Origin::Node, Kind::Internal, "No outlet session")
+        })?;
+        let status = Self::inlet_status(&alias, &inlet_info).await;
+        self.registry.inlets.insert(alias, inlet_info).await;
+
+        Ok(status)
+    }
+
     pub async fn delete_inlet(&self, alias: &str) -> Result<InletStatus> {
         info!(%alias, "Handling request to delete inlet portal");
         if let Some(inlet_to_delete) = self.registry.inlets.remove(alias).await {
             debug!(%alias, "Successfully removed inlet from node registry");
-            inlet_to_delete.session.lock().await.stop().await;
+            inlet_to_delete.stop(&self.tcp_transport).await;
             self.resources().delete_resource(&alias.into()).await?;
             self.cli_state
                 .delete_tcp_inlet(&self.node_name, alias)
@@ -231,40 +373,7 @@ impl NodeManager {
     pub async fn show_inlet(&self, alias: &str) -> Option<InletStatus> {
         info!(%alias, "Handling request to show inlet portal");
         if let Some(inlet_info) = self.registry.inlets.get(alias).await {
-            let session = inlet_info.session.lock().await;
-            let connection_status = session.connection_status();
-            let outcome = session.last_outcome();
-            drop(session);
-            if let Some(outcome) = outcome {
-                if let ReplacerOutputKind::Inlet(status) = outcome {
-                    let address = match &status.worker {
-                        Some(address) => address.address().to_string(),
-                        None => "<>".to_string(),
-                    };
-
-                    Some(InletStatus::new(
-                        inlet_info.bind_addr.to_string(),
-                        address,
-                        alias,
-                        None,
-                        status.route.to_string(),
-                        connection_status,
-                        inlet_info.outlet_addr.to_string(),
-                    ))
-                } else {
-                    panic!("Unexpected outcome: {:?}", outcome)
-                }
-            } else {
-                Some(InletStatus::new(
-                    inlet_info.bind_addr.to_string(),
-                    None,
-                    alias,
-                    None,
-                    None,
-                    connection_status,
-                    inlet_info.outlet_addr.to_string(),
-                ))
-            }
+            Some(Self::inlet_status(alias, &inlet_info).await)
         } else {
             error!(%alias, "Inlet not found in the node registry");
             None
@@ -274,48 +383,65 @@ impl NodeManager {
     pub async fn list_inlets(&self) -> Vec<InletStatus> {
         let mut res = vec![];
         for (alias, info) in self.registry.inlets.entries().await {
-            let session = info.session.lock().await;
+            res.push(Self::inlet_status(&alias, &info).await);
+        }
+
+        res
+    }
+
+    /// Return the status of an inlet. An inlet spreading its connections between several
+    /// outlets is up as long as one of its outlets is up
+    async fn inlet_status(alias: &str, info: &InletInfo) -> InletStatus {
+        let mut outlets = vec![];
+        for (outlet_addr, session) in info.outlets() {
+            let session = session.lock().await;
             let connection_status = session.connection_status();
             let outcome = session.last_outcome();
             drop(session);
 
-            let status = if let Some(outcome) = outcome {
-                match &outcome {
-                    ReplacerOutputKind::Inlet(status) => {
-                        let address = match &status.worker {
-                            Some(address) => address.address().to_string(),
-                            None => "<>".to_string(),
-                        };
-
-                        InletStatus::new(
-                            &info.bind_addr,
-                            address,
-                            alias,
-                            None,
-                            status.route.to_string(),
-                            connection_status,
-                            info.outlet_addr.to_string(),
-                        )
-                    }
-                    _ => {
-                        panic!("Unexpected outcome: {:?}", outcome)
-                    }
-                }
-            } else {
-                InletStatus::new(
-                    &info.bind_addr,
-                    None,
-                    alias,
-                    None,
-                    None,
-                    connection_status,
-                    info.outlet_addr.to_string(),
-                )
+            let status = match outcome {
+                Some(ReplacerOutputKind::Inlet(status)) => Some(status),
+                Some(outcome) => panic!("Unexpected outcome: {:?}", outcome),
+                None => None,
             };
-
-            res.push(status);
+            outlets.push((outlet_addr, connection_status, status));
         }
 
-        res
+        let (_, main_connection_status, main_status) = &outlets[0];
+        let connection_status = if outlets
+            .iter()
+            .any(|(_, status, _)| *status == ConnectionStatus::Up)
+        {
+            ConnectionStatus::Up
+        } else {
+            *main_connection_status
+        };
+        let status = InletStatus::new(
+            &info.bind_addr,
+            main_status.as_ref().map(|status| match &status.worker {
+                Some(address) => address.address().to_string(),
+                None => "<>".to_string(),
+            }),
+            alias,
+            None,
+            main_status.as_ref().map(|status| status.route.to_string()),
+            connection_status,
+            info.outlet_addr.to_string(),
+        );
+
+        if info.is_balanced() {
+            status.with_outlets(
+                outlets
+                    .into_iter()
+                    .map(|(outlet_addr, status, outcome)| InletOutletStatus {
+                        outlet_addr: outlet_addr.to_string(),
+                        outlet_route: outcome.map(|outcome| outcome.route.to_string()),
+                        status,
+                    })
+                    .collect(),
+            )
+        } else {
+            status
+        }
     }
 }
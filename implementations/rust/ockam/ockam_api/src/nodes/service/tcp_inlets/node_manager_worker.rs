@@ -30,7 +30,40 @@ impl NodeManagerWorker {
             disable_tcp_fallback,
             ebpf,
             tls_certificate_provider,
+            additional_outlet_addrs,
+            load_balancing,
         } = create_inlet;
+
+        if !additional_outlet_addrs.is_empty() {
+            if enable_udp_puncture || ebpf {
+                return Err(Response::bad_request_no_request(
+                    "An inlet with several outlets can't use UDP puncture or eBPF",
+                ));
+            }
+            let mut outlet_addrs = vec![outlet_addr];
+            outlet_addrs.extend(additional_outlet_addrs);
+            return match self
+                .node_manager
+                .create_balanced_inlet(
+                    ctx,
+                    listen_addr,
+                    outlet_addrs,
+                    load_balancing.unwrap_or_default(),
+                    alias,
+                    policy_expression,
+                    wait_for_outlet_duration,
+                    authorized,
+                    wait_connection,
+                    secure_channel_identifier,
+                    tls_certificate_provider,
+                )
+                .await
+            {
+                Ok(status) => Ok(Response::ok().body(status)),
+                Err(e) => Err(Response::bad_request_no_request(&format!("{e:?}"))),
+            };
+        }
+
         match self
             .node_manager
             .create_inlet(
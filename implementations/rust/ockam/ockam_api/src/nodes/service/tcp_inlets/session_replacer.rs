@@ -45,6 +45,10 @@ pub(super) struct InletSessionReplacer {
 
     // current status
     pub(super) inlet: Option<Arc<TcpInlet>>,
+    /// Set when the inlet spreads its connections between several outlets. In that case
+    /// `inlet` controls the route to `outlet_addr` only, and closing the session takes this
+    /// route out of the rotation instead of stopping the inlet
+    pub(super) shared_inlet: bool,
     pub(super) main_route: Option<Route>,
 
     pub(super) connection: Option<Connection>,
@@ -103,7 +107,10 @@ impl InletSessionReplacer {
             .await
     }
 
-    async fn inlet_options(&self, node_manager: &NodeManager) -> Result<TcpInletOptions> {
+    pub(super) async fn inlet_options(
+        &self,
+        node_manager: &NodeManager,
+    ) -> Result<TcpInletOptions> {
         let (incoming_ac, outgoing_ac) = self.access_control(node_manager).await?;
         let options = TcpInletOptions::new()
             .with_incoming_access_control(incoming_ac)
@@ -158,9 +165,9 @@ impl InletSessionReplacer {
         // Finally, attempt to create/update inlet using the new route
         let inlet_address = match self.inlet.clone() {
             Some(inlet) => {
-                inlet.unpause(
+                inlet.unpause_with_outlet_route(
                     self.context.flow_controls(),
-                    normalized_stripped_route.clone(),
+                    normalized_route.clone(),
                 )?;
 
                 inlet.processor_address().cloned()
@@ -221,6 +228,12 @@ impl InletSessionReplacer {
     }
 
     async fn close_inlet(&mut self) {
+        if self.shared_inlet {
+            // The inlet is stopped once all its sessions are stopped
+            self.pause_inlet().await;
+            return;
+        }
+
         if let Some(inlet) = self.inlet.take() {
             // The previous inlet worker needs to be stopped:
             let result = inlet.stop(&self.context).await;
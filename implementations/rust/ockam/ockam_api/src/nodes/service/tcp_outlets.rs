@@ -1,16 +1,28 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use ockam::flow_control::FlowControls;
 use ockam::tcp::TcpOutletOptions;
 use ockam::transport::HostnamePort;
 use ockam::{Address, Result};
-use ockam_abac::{Action, PolicyExpression, Resource, ResourceType};
+use ockam_abac::expr::str;
+use ockam_abac::{Action, Env, PolicyExpression, Resource, ResourceType};
 use ockam_core::api::{Error, Request, RequestHeader, Response};
 use ockam_core::async_trait;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_node::Context;
+use ockam_transport_tcp::{PortalInterceptorFactory, PortalOutletInterceptor, TcpTransport};
 
-use crate::nodes::models::portal::{CreateOutlet, OutletAccessControl, OutletStatus};
+use crate::http_interceptor::{
+    FixedTokenProvider, HttpAccessControl, HttpInterceptorFactory, HttpOutletConfig,
+};
+use crate::nodes::models::portal::{
+    CreateOutlet, DeleteOutlet, OutletAccessControl, OutletStatus, PortalLimitsConfig,
+};
 use crate::nodes::registry::OutletInfo;
 use crate::nodes::service::default_address::DefaultAddress;
 use crate::nodes::BackgroundNodeClient;
+use crate::postgres::{PostgresInterceptorFactory, PostgresOutletConfig};
 
 use super::{NodeManager, NodeManagerWorker};
 
@@ -28,21 +40,64 @@ impl NodeManagerWorker {
             policy_expression,
             tls,
             ebpf,
+            http,
+            postgres,
+            limits,
         } = create_outlet;
 
-        match self
-            .node_manager
-            .create_outlet(
-                ctx,
-                hostname_port,
-                tls,
-                worker_addr,
-                reachable_from_default_secure_channel,
-                OutletAccessControl::WithPolicyExpression(policy_expression),
-                ebpf,
-            )
-            .await
-        {
+        let result = match (http, postgres) {
+            (Some(_), Some(_)) => Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::Invalid,
+                "an outlet can't intercept both HTTP requests and Postgres queries",
+            )),
+            (Some(http), None) => {
+                self.node_manager
+                    .create_http_outlet(
+                        ctx,
+                        hostname_port,
+                        tls,
+                        worker_addr,
+                        reachable_from_default_secure_channel,
+                        policy_expression,
+                        ebpf,
+                        http,
+                        limits,
+                    )
+                    .await
+            }
+            (None, Some(postgres)) => {
+                self.node_manager
+                    .create_postgres_outlet(
+                        ctx,
+                        hostname_port,
+                        tls,
+                        worker_addr,
+                        reachable_from_default_secure_channel,
+                        policy_expression,
+                        ebpf,
+                        postgres,
+                        limits,
+                    )
+                    .await
+            }
+            (None, None) => {
+                self.node_manager
+                    .create_outlet_with_limits(
+                        ctx,
+                        hostname_port,
+                        tls,
+                        worker_addr,
+                        reachable_from_default_secure_channel,
+                        OutletAccessControl::WithPolicyExpression(policy_expression),
+                        ebpf,
+                        limits,
+                    )
+                    .await
+            }
+        };
+
+        match result {
             Ok(outlet_status) => Ok(Response::ok().body(outlet_status)),
             Err(e) => Err(Response::bad_request_no_request(&format!("{e:?}"))),
         }
@@ -51,8 +106,13 @@ impl NodeManagerWorker {
     pub(super) async fn delete_outlet(
         &self,
         worker_addr: &Address,
+        delete_outlet: DeleteOutlet,
     ) -> Result<Response<OutletStatus>, Response<Error>> {
-        match self.node_manager.delete_outlet(worker_addr).await {
+        match self
+            .node_manager
+            .delete_outlet(worker_addr, delete_outlet.drain_timeout)
+            .await
+        {
             Ok(res) => match res {
                 Some(outlet_info) => Ok(Response::ok().body(OutletStatus::new(
                     outlet_info.to,
@@ -100,6 +160,35 @@ impl NodeManager {
         access_control: OutletAccessControl,
         ebpf: bool,
     ) -> Result<OutletStatus> {
+        self.create_outlet_with_limits(
+            ctx,
+            to,
+            tls,
+            worker_addr,
+            reachable_from_default_secure_channel,
+            access_control,
+            ebpf,
+            None,
+        )
+        .await
+    }
+
+    /// Create an outlet limiting the number of connections and their bandwidth
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip_all)]
+    pub async fn create_outlet_with_limits(
+        &self,
+        ctx: &Context,
+        to: HostnamePort,
+        tls: bool,
+        worker_addr: Option<Address>,
+        reachable_from_default_secure_channel: bool,
+        access_control: OutletAccessControl,
+        ebpf: bool,
+        limits: Option<PortalLimitsConfig>,
+    ) -> Result<OutletStatus> {
+        Self::check_no_ebpf_limits(ebpf, &limits)?;
+
         let worker_addr = self
             .registry
             .outlets
@@ -142,6 +231,10 @@ impl NodeManager {
                 .with_incoming_access_control(incoming_ac)
                 .with_outgoing_access_control(outgoing_ac)
                 .with_tls(tls);
+            let options = match &limits {
+                Some(limits) => limits.apply_to_outlet(options),
+                None => options,
+            };
             let options = if self.project_authority().is_none() {
                 options.as_consumer(&self.api_transport_flow_control_id)
             } else {
@@ -162,12 +255,287 @@ impl NodeManager {
             }
         };
 
-        let res = if ebpf {
+        if let Err(e) = self
+            .start_tcp_outlet(worker_addr.clone(), to.clone(), options, ebpf)
+            .await
+        {
+            warn!(at = %to, err = %e, "Failed to create TCP outlet");
+            let message = format!("Failed to create outlet: {}", e);
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::Internal,
+                message,
+            ));
+        }
+
+        // TODO: Use better way to store outlets?
+        self.registry
+            .outlets
+            .insert(
+                worker_addr.clone(),
+                OutletInfo::new(to.clone(), Some(&worker_addr)),
+            )
+            .await;
+
+        Ok(self
+            .cli_state
+            .create_tcp_outlet(&self.node_name, &to, &worker_addr, &None)
+            .await?)
+    }
+
+    /// Create an outlet intercepting the HTTP/1.1 requests sent to its server
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip_all)]
+    pub async fn create_http_outlet(
+        &self,
+        ctx: &Context,
+        to: HostnamePort,
+        tls: bool,
+        worker_addr: Option<Address>,
+        reachable_from_default_secure_channel: bool,
+        policy_expression: Option<PolicyExpression>,
+        ebpf: bool,
+        http: HttpOutletConfig,
+        limits: Option<PortalLimitsConfig>,
+    ) -> Result<OutletStatus> {
+        Self::check_no_ebpf_interception(ebpf)?;
+        let worker_addr = self
+            .registry
+            .outlets
+            .generate_worker_addr(worker_addr)
+            .await;
+        info!(
+            "Handling request to create HTTP outlet portal to {to} with worker {:?}",
+            worker_addr
+        );
+        let resource = Resource::new(worker_addr.address(), ResourceType::TcpOutlet);
+
+        let mut interceptor_factory = HttpInterceptorFactory::new()
+            .with_added_headers(http.add_headers)
+            .with_stripped_headers(http.strip_headers);
+        if let Some(token) = http.bearer_token {
+            interceptor_factory =
+                interceptor_factory.with_token("Bearer", Arc::new(FixedTokenProvider::new(token)));
+        }
+        if !http.access_rules.is_empty() {
+            interceptor_factory = interceptor_factory.with_access_control(HttpAccessControl::new(
+                http.access_rules,
+                self.cli_state.identities_attributes(&self.node_name),
+                self.project_authority(),
+                Self::interceptor_environment(&resource),
+            ));
+        }
+
+        self.create_intercepted_outlet(
+            ctx,
+            to,
+            tls,
+            worker_addr,
+            resource,
+            reachable_from_default_secure_channel,
+            policy_expression,
+            Arc::new(interceptor_factory),
+            limits,
+        )
+        .await
+    }
+
+    /// Create an outlet intercepting the Postgres queries sent to its server
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip_all)]
+    pub async fn create_postgres_outlet(
+        &self,
+        ctx: &Context,
+        to: HostnamePort,
+        tls: bool,
+        worker_addr: Option<Address>,
+        reachable_from_default_secure_channel: bool,
+        policy_expression: Option<PolicyExpression>,
+        ebpf: bool,
+        postgres: PostgresOutletConfig,
+        limits: Option<PortalLimitsConfig>,
+    ) -> Result<OutletStatus> {
+        Self::check_no_ebpf_interception(ebpf)?;
+        let worker_addr = self
+            .registry
+            .outlets
+            .generate_worker_addr(worker_addr)
+            .await;
+        info!(
+            "Handling request to create Postgres outlet portal to {to} with worker {:?}",
+            worker_addr
+        );
+        let resource = Resource::new(worker_addr.address(), ResourceType::TcpOutlet);
+
+        let interceptor_factory = PostgresInterceptorFactory::new(
+            postgres,
+            self.cli_state.identities_attributes(&self.node_name),
+            self.project_authority(),
+            Self::interceptor_environment(&resource),
+        )
+        .with_audit(self.policies().audit().cloned(), resource.clone());
+
+        self.create_intercepted_outlet(
+            ctx,
+            to,
+            tls,
+            worker_addr,
+            resource,
+            reachable_from_default_secure_channel,
+            policy_expression,
+            Arc::new(interceptor_factory),
+            limits,
+        )
+        .await
+    }
+
+    /// eBPF outlets handle the TCP traffic in the kernel, so their messages
+    /// can't go through an interceptor
+    fn check_no_ebpf_interception(ebpf: bool) -> Result<()> {
+        if ebpf {
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::Invalid,
+                "an eBPF outlet can't intercept HTTP requests or Postgres queries",
+            ));
+        }
+        Ok(())
+    }
+
+    /// eBPF outlets handle the TCP traffic in the kernel, so their connections
+    /// can't be counted nor throttled
+    fn check_no_ebpf_limits(ebpf: bool, limits: &Option<PortalLimitsConfig>) -> Result<()> {
+        if ebpf && limits.is_some() {
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::Invalid,
+                "an eBPF outlet can't limit its connections or their bandwidth",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Environment of the policies evaluated by the interceptor of an outlet
+    fn interceptor_environment(resource: &Resource) -> Env {
+        let mut env = Env::new();
+        env.put("resource.id", str(resource.resource_name.as_str()));
+        env.put("action.id", str(Action::HandleMessage.as_ref()));
+        env
+    }
+
+    /// Create an outlet with a portal interceptor.
+    ///
+    /// The interceptor is started at the outlet worker address and forwards the messages
+    /// to a TCP outlet which can only be reached via the interceptor.
+    #[allow(clippy::too_many_arguments)]
+    async fn create_intercepted_outlet(
+        &self,
+        ctx: &Context,
+        to: HostnamePort,
+        tls: bool,
+        worker_addr: Address,
+        resource: Resource,
+        reachable_from_default_secure_channel: bool,
+        policy_expression: Option<PolicyExpression>,
+        interceptor_factory: Arc<dyn PortalInterceptorFactory>,
+        limits: Option<PortalLimitsConfig>,
+    ) -> Result<OutletStatus> {
+        let outlet_addr: Address = format!("{}_outlet", worker_addr.address()).into();
+
+        if self.registry.outlets.contains_key(&worker_addr).await {
+            let message = format!("A TCP outlet with address '{worker_addr}' already exists");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::AlreadyExists,
+                message,
+            ));
+        }
+
+        let policy_access_control = self
+            .policy_access_control(
+                self.project_authority(),
+                resource,
+                Action::HandleMessage,
+                policy_expression,
+            )
+            .await?;
+        let incoming_ac = Arc::new(policy_access_control.create_incoming());
+        let outgoing_ac = Arc::new(policy_access_control.create_outgoing(ctx).await?);
+
+        // the outlet is only reachable via the interceptor
+        let spawner_flow_control_id = FlowControls::generate_flow_control_id();
+        let options = TcpOutletOptions::new()
+            .with_incoming_access_control(incoming_ac.clone())
+            .with_outgoing_access_control(outgoing_ac.clone())
+            .with_tls(tls)
+            .as_consumer(&spawner_flow_control_id);
+        let options = match &limits {
+            Some(limits) => limits.apply_to_outlet(options),
+            None => options,
+        };
+        self.start_tcp_outlet(outlet_addr.clone(), to.clone(), options, false)
+            .await?;
+
+        if let Err(e) = PortalOutletInterceptor::create_for_outlet(
+            ctx,
+            worker_addr.clone(),
+            outlet_addr.clone(),
+            Some(spawner_flow_control_id.clone()),
+            interceptor_factory,
+            outgoing_ac,
+            incoming_ac,
+        )
+        .await
+        {
+            let _ = self.tcp_transport.stop_outlet(outlet_addr).await;
+            return Err(e);
+        }
+
+        // Accept messages from the default secure channel listener
+        let flow_controls = ctx.flow_controls();
+        if reachable_from_default_secure_channel {
+            if let Some(flow_control_id) = flow_controls
+                .get_flow_control_with_spawner(&DefaultAddress::SECURE_CHANNEL_LISTENER.into())
+            {
+                flow_controls.add_consumer(worker_addr.clone(), &flow_control_id);
+            }
+        }
+        if self.project_authority().is_none() {
+            flow_controls.add_consumer(worker_addr.clone(), &self.api_transport_flow_control_id);
+        }
+        // this spawner flow control id is used to control the communication
+        // between the interceptors created for each connection and the outlet
+        flow_controls.add_spawner(worker_addr.clone(), &spawner_flow_control_id);
+
+        self.registry
+            .outlets
+            .insert(
+                worker_addr.clone(),
+                OutletInfo::new(to.clone(), Some(&worker_addr))
+                    .with_intercepted_outlet(outlet_addr),
+            )
+            .await;
+
+        Ok(self
+            .cli_state
+            .create_tcp_outlet(&self.node_name, &to, &worker_addr, &None)
+            .await?)
+    }
+
+    async fn start_tcp_outlet(
+        &self,
+        worker_addr: Address,
+        to: HostnamePort,
+        options: TcpOutletOptions,
+        ebpf: bool,
+    ) -> Result<()> {
+        if ebpf {
             #[cfg(ebpf_alias)]
             {
                 self.tcp_transport
-                    .create_raw_outlet(worker_addr.clone(), to.clone(), options)
+                    .create_raw_outlet(worker_addr, to, options)
                     .await
+                    .map(|_| ())
             }
             #[cfg(not(ebpf_alias))]
             {
@@ -179,38 +547,19 @@ impl NodeManager {
             }
         } else {
             self.tcp_transport
-                .create_outlet(worker_addr.clone(), to.clone(), options)
+                .create_outlet(worker_addr, to, options)
                 .await
-        };
-
-        Ok(match res {
-            Ok(_) => {
-                // TODO: Use better way to store outlets?
-                self.registry
-                    .outlets
-                    .insert(
-                        worker_addr.clone(),
-                        OutletInfo::new(to.clone(), Some(&worker_addr)),
-                    )
-                    .await;
-
-                self.cli_state
-                    .create_tcp_outlet(&self.node_name, &to, &worker_addr, &None)
-                    .await?
-            }
-            Err(e) => {
-                warn!(at = %to, err = %e, "Failed to create TCP outlet");
-                let message = format!("Failed to create outlet: {}", e);
-                return Err(ockam_core::Error::new(
-                    Origin::Node,
-                    Kind::Internal,
-                    message,
-                ));
-            }
-        })
+                .map(|_| ())
+        }
     }
 
-    pub async fn delete_outlet(&self, worker_addr: &Address) -> Result<Option<OutletInfo>> {
+    /// Delete an outlet. If a drain timeout is given, the outlet refuses new connections
+    /// and is only stopped once its open connections are closed, or when the timeout elapses
+    pub async fn delete_outlet(
+        &self,
+        worker_addr: &Address,
+        drain_timeout: Option<Duration>,
+    ) -> Result<Option<OutletInfo>> {
         info!(%worker_addr, "Handling request to delete outlet portal");
         if let Some(deleted_outlet) = self.registry.outlets.remove(worker_addr).await {
             debug!(%worker_addr, "Successfully removed outlet from node registry");
@@ -222,12 +571,46 @@ impl NodeManager {
                 .delete_resource(&worker_addr.address().into())
                 .await?;
 
-            if let Err(e) = self
-                .tcp_transport
-                .stop_outlet(deleted_outlet.worker_addr.clone())
-                .await
-            {
-                warn!(%worker_addr, %e, "Failed to stop outlet worker");
+            // When there is an interceptor, the TCP outlet is behind it. It is stopped first,
+            // so that the interceptor keeps forwarding the traffic of a draining outlet
+            let tcp_outlet_addr = deleted_outlet
+                .intercepted_outlet_addr
+                .clone()
+                .unwrap_or_else(|| deleted_outlet.worker_addr.clone());
+            let interceptor_addr = deleted_outlet
+                .intercepted_outlet_addr
+                .as_ref()
+                .map(|_| deleted_outlet.worker_addr.clone());
+            match drain_timeout {
+                // The drain can last for a long time, so it is done in the background
+                // in order to not block the node manager
+                Some(drain_timeout) => {
+                    info!(%worker_addr, ?drain_timeout, "Draining outlet");
+                    let tcp_transport = self.tcp_transport.clone();
+                    let worker_addr = worker_addr.clone();
+                    ockam_node::spawn(async move {
+                        let drained = tcp_transport
+                            .drain_outlet(tcp_outlet_addr, drain_timeout)
+                            .await;
+                        Self::outlet_stopped(
+                            &tcp_transport,
+                            &worker_addr,
+                            drained,
+                            interceptor_addr,
+                        )
+                        .await;
+                    });
+                }
+                None => {
+                    let stopped = self.tcp_transport.stop_outlet(tcp_outlet_addr).await;
+                    Self::outlet_stopped(
+                        &self.tcp_transport,
+                        worker_addr,
+                        stopped,
+                        interceptor_addr,
+                    )
+                    .await;
+                }
             }
             trace!(%worker_addr, "Successfully stopped outlet");
             Ok(Some(deleted_outlet))
@@ -237,6 +620,23 @@ impl NodeManager {
         }
     }
 
+    /// Stop the interceptor in front of an outlet, once the outlet is stopped
+    async fn outlet_stopped(
+        tcp_transport: &TcpTransport,
+        worker_addr: &Address,
+        stopped: Result<()>,
+        interceptor_addr: Option<Address>,
+    ) {
+        if let Err(e) = stopped {
+            warn!(%worker_addr, %e, "Failed to stop outlet worker");
+        }
+        if let Some(interceptor_addr) = interceptor_addr {
+            if let Err(e) = tcp_transport.stop_outlet(interceptor_addr).await {
+                warn!(%worker_addr, %e, "Failed to stop the outlet interceptor");
+            }
+        }
+    }
+
     pub(super) async fn show_outlet(&self, worker_addr: &Address) -> Option<OutletStatus> {
         info!(%worker_addr, "Handling request to show outlet portal");
         if let Some(outlet_to_show) = self.registry.outlets.get(worker_addr).await {
@@ -255,6 +655,7 @@ impl NodeManager {
 
 #[async_trait]
 pub trait Outlets {
+    #[allow(clippy::too_many_arguments)]
     async fn create_outlet(
         &self,
         ctx: &Context,
@@ -263,6 +664,9 @@ pub trait Outlets {
         from: Option<&Address>,
         policy_expression: Option<PolicyExpression>,
         ebpf: bool,
+        http: Option<HttpOutletConfig>,
+        postgres: Option<PostgresOutletConfig>,
+        limits: Option<PortalLimitsConfig>,
     ) -> miette::Result<OutletStatus>;
 }
 
@@ -277,11 +681,23 @@ impl Outlets for BackgroundNodeClient {
         from: Option<&Address>,
         policy_expression: Option<PolicyExpression>,
         ebpf: bool,
+        http: Option<HttpOutletConfig>,
+        postgres: Option<PostgresOutletConfig>,
+        limits: Option<PortalLimitsConfig>,
     ) -> miette::Result<OutletStatus> {
         let mut payload = CreateOutlet::new(to, tls, from.cloned(), true, ebpf);
         if let Some(policy_expression) = policy_expression {
             payload.set_policy_expression(policy_expression);
         }
+        if let Some(http) = http {
+            payload.set_http(http);
+        }
+        if let Some(postgres) = postgres {
+            payload.set_postgres(postgres);
+        }
+        if let Some(limits) = limits {
+            payload.set_limits(limits);
+        }
         let req = Request::post("/node/outlet").body(payload);
         let result: OutletStatus = self.ask(ctx, req).await?;
         Ok(result)
@@ -0,0 +1,458 @@
+use std::time::Duration;
+
+use ockam::identity::Identifier;
+use ockam::transport::HostnamePort;
+use ockam::udp::{UdpInletOptions, UdpOutletOptions, UdpTransport};
+use ockam::{Address, Result};
+use ockam_abac::{Action, PolicyExpression, Resource, ResourceType};
+use ockam_core::api::{Error, Request, Response};
+use ockam_core::async_trait;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_multiaddr::MultiAddr;
+use ockam_node::Context;
+
+use crate::cli_state::random_name;
+use crate::nodes::models::portal::{
+    CreateUdpInlet, CreateUdpOutlet, UdpInletStatus, UdpOutletStatus,
+};
+use crate::nodes::registry::{UdpInletInfo, UdpOutletInfo};
+use crate::nodes::service::default_address::DefaultAddress;
+use crate::nodes::BackgroundNodeClient;
+
+use super::{NodeManager, NodeManagerWorker};
+
+impl NodeManagerWorker {
+    #[instrument(skip_all)]
+    pub(super) async fn create_udp_inlet(
+        &self,
+        ctx: &Context,
+        create_inlet: CreateUdpInlet,
+    ) -> Result<Response<UdpInletStatus>, Response<Error>> {
+        let CreateUdpInlet {
+            listen_addr,
+            outlet_addr,
+            alias,
+            authorized,
+            policy_expression,
+            idle_timeout,
+        } = create_inlet;
+
+        match self
+            .node_manager
+            .create_udp_inlet(
+                ctx,
+                listen_addr,
+                outlet_addr,
+                alias,
+                authorized,
+                policy_expression,
+                idle_timeout,
+            )
+            .await
+        {
+            Ok(status) => Ok(Response::ok().body(status)),
+            Err(e) => Err(Response::bad_request_no_request(&format!("{e:?}"))),
+        }
+    }
+
+    pub(super) async fn delete_udp_inlet(
+        &self,
+        ctx: &Context,
+        alias: &str,
+    ) -> Result<Response<UdpInletStatus>, Response<Error>> {
+        match self.node_manager.delete_udp_inlet(ctx, alias).await {
+            Ok(Some(status)) => Ok(Response::ok().body(status)),
+            Ok(None) => Err(Response::not_found_no_request(&format!(
+                "UDP inlet with alias {alias} not found"
+            ))),
+            Err(e) => Err(Response::bad_request_no_request(&format!("{e:?}"))),
+        }
+    }
+
+    pub(super) async fn get_udp_inlets(
+        &self,
+    ) -> Result<Response<Vec<UdpInletStatus>>, Response<Error>> {
+        Ok(Response::ok().body(self.node_manager.list_udp_inlets().await))
+    }
+
+    #[instrument(skip_all)]
+    pub(super) async fn create_udp_outlet(
+        &self,
+        ctx: &Context,
+        create_outlet: CreateUdpOutlet,
+    ) -> Result<Response<UdpOutletStatus>, Response<Error>> {
+        let CreateUdpOutlet {
+            hostname_port,
+            worker_addr,
+            policy_expression,
+            idle_timeout,
+            reachable_from_default_secure_channel,
+        } = create_outlet;
+
+        match self
+            .node_manager
+            .create_udp_outlet(
+                ctx,
+                hostname_port,
+                worker_addr,
+                policy_expression,
+                idle_timeout,
+                reachable_from_default_secure_channel,
+            )
+            .await
+        {
+            Ok(status) => Ok(Response::ok().body(status)),
+            Err(e) => Err(Response::bad_request_no_request(&format!("{e:?}"))),
+        }
+    }
+
+    pub(super) async fn delete_udp_outlet(
+        &self,
+        worker_addr: &Address,
+    ) -> Result<Response<UdpOutletStatus>, Response<Error>> {
+        match self.node_manager.delete_udp_outlet(worker_addr).await {
+            Ok(Some(status)) => Ok(Response::ok().body(status)),
+            Ok(None) => Err(Response::not_found_no_request(&format!(
+                "UDP outlet with address {worker_addr} not found"
+            ))),
+            Err(e) => Err(Response::bad_request_no_request(&format!("{e:?}"))),
+        }
+    }
+
+    pub(super) async fn get_udp_outlets(
+        &self,
+    ) -> Result<Response<Vec<UdpOutletStatus>>, Response<Error>> {
+        Ok(Response::ok().body(self.node_manager.list_udp_outlets().await))
+    }
+}
+
+impl NodeManager {
+    fn udp_portal_transport(&self) -> Result<&UdpTransport> {
+        self.udp_transport.as_ref().ok_or(ockam_core::Error::new(
+            Origin::Transport,
+            Kind::Invalid,
+            "UDP portals require a node created with the --udp flag",
+        ))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip_all)]
+    pub async fn create_udp_inlet(
+        &self,
+        ctx: &Context,
+        listen_addr: HostnamePort,
+        outlet_addr: MultiAddr,
+        alias: String,
+        authorized: Option<Identifier>,
+        policy_expression: Option<PolicyExpression>,
+        idle_timeout: Option<Duration>,
+    ) -> Result<UdpInletStatus> {
+        info!(%listen_addr, %outlet_addr, %alias, "Handling request to create UDP inlet portal");
+        let udp_transport = self.udp_portal_transport()?;
+
+        if self.registry.udp_inlets.contains_key(&alias).await {
+            let message = format!("A UDP inlet with alias '{alias}' already exists");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::AlreadyExists,
+                message,
+            ));
+        }
+
+        let (incoming_ac, outgoing_ac) = self
+            .access_control(
+                ctx,
+                self.project_authority(),
+                Resource::new(alias.clone(), ResourceType::UdpInlet),
+                Action::HandleMessage,
+                policy_expression,
+            )
+            .await?;
+
+        let connection = self
+            .make_connection(ctx, &outlet_addr, self.identifier(), authorized, None)
+            .await?;
+        let outlet_route = connection.route()?;
+
+        let options = UdpInletOptions::new()
+            .with_incoming_access_control(incoming_ac)
+            .with_outgoing_access_control(outgoing_ac);
+        let options = match idle_timeout {
+            Some(idle_timeout) => options.with_idle_timeout(idle_timeout),
+            None => options,
+        };
+
+        let inlet = match udp_transport
+            .create_inlet(listen_addr.to_string(), outlet_route.clone(), options)
+            .await
+        {
+            Ok(inlet) => inlet,
+            Err(e) => {
+                warn!(at = %listen_addr, err = %e, "Failed to create UDP inlet");
+                let _ = connection.close(ctx, self).await;
+                return Err(e);
+            }
+        };
+
+        let info = UdpInletInfo {
+            bind_addr: inlet.socket_address().to_string(),
+            outlet_addr,
+            processor_address: inlet.processor_address().clone(),
+            connection,
+        };
+        self.registry
+            .udp_inlets
+            .insert(alias.clone(), info.clone())
+            .await;
+
+        Ok(UdpInletStatus::new(&alias, &info, &outlet_route))
+    }
+
+    pub async fn delete_udp_inlet(
+        &self,
+        ctx: &Context,
+        alias: &str,
+    ) -> Result<Option<UdpInletStatus>> {
+        info!(%alias, "Handling request to delete UDP inlet portal");
+        let Some(inlet) = self.registry.udp_inlets.remove(alias).await else {
+            warn!(%alias, "UDP inlet not found in the node registry");
+            return Ok(None);
+        };
+
+        let outlet_route = inlet.connection.route()?;
+        if let Err(e) = self
+            .udp_portal_transport()?
+            .stop_inlet(inlet.processor_address.clone())
+            .await
+        {
+            warn!(%alias, %e, "Failed to stop UDP inlet processor");
+        }
+        inlet.connection.close(ctx, self).await?;
+        self.resources().delete_resource(&alias.into()).await?;
+
+        Ok(Some(UdpInletStatus::new(alias, &inlet, &outlet_route)))
+    }
+
+    pub async fn list_udp_inlets(&self) -> Vec<UdpInletStatus> {
+        self.registry
+            .udp_inlets
+            .entries()
+            .await
+            .iter()
+            .filter_map(|(alias, info)| {
+                let outlet_route = info.connection.route().ok()?;
+                Some(UdpInletStatus::new(alias, info, &outlet_route))
+            })
+            .collect()
+    }
+
+    #[instrument(skip_all)]
+    pub async fn create_udp_outlet(
+        &self,
+        ctx: &Context,
+        to: HostnamePort,
+        worker_addr: Option<Address>,
+        policy_expression: Option<PolicyExpression>,
+        idle_timeout: Option<Duration>,
+        reachable_from_default_secure_channel: bool,
+    ) -> Result<UdpOutletStatus> {
+        info!(%to, ?worker_addr, "Handling request to create UDP outlet portal");
+        let udp_transport = self.udp_portal_transport()?;
+
+        let worker_addr = match worker_addr {
+            Some(worker_addr) => worker_addr,
+            None => {
+                // Use the default address if it's not in use
+                let default: Address = DefaultAddress::UDP_OUTLET_SERVICE.into();
+                if self.registry.udp_outlets.contains_key(&default).await {
+                    random_name().into()
+                } else {
+                    default
+                }
+            }
+        };
+        if self.registry.udp_outlets.contains_key(&worker_addr).await {
+            let message = format!("A UDP outlet with address '{worker_addr}' already exists");
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::AlreadyExists,
+                message,
+            ));
+        }
+
+        let (incoming_ac, outgoing_ac) = self
+            .access_control(
+                ctx,
+                self.project_authority(),
+                Resource::new(worker_addr.address(), ResourceType::UdpOutlet),
+                Action::HandleMessage,
+                policy_expression,
+            )
+            .await?;
+
+        let options = UdpOutletOptions::new()
+            .with_incoming_access_control(incoming_ac)
+            .with_outgoing_access_control(outgoing_ac);
+        let options = match idle_timeout {
+            Some(idle_timeout) => options.with_idle_timeout(idle_timeout),
+            None => options,
+        };
+        let options = if self.project_authority().is_none() {
+            options.as_consumer(&self.api_transport_flow_control_id)
+        } else {
+            options
+        };
+        // Accept messages from the default secure channel listener
+        let options = match ctx
+            .flow_controls()
+            .get_flow_control_with_spawner(&DefaultAddress::SECURE_CHANNEL_LISTENER.into())
+        {
+            Some(flow_control_id) if reachable_from_default_secure_channel => {
+                options.as_consumer(&flow_control_id)
+            }
+            _ => options,
+        };
+
+        udp_transport
+            .create_outlet(worker_addr.clone(), to.clone(), options)
+            .await?;
+
+        let info = UdpOutletInfo { to, worker_addr };
+        self.registry
+            .udp_outlets
+            .insert(info.worker_addr.clone(), info.clone())
+            .await;
+
+        Ok(info.into())
+    }
+
+    pub async fn delete_udp_outlet(
+        &self,
+        worker_addr: &Address,
+    ) -> Result<Option<UdpOutletStatus>> {
+        info!(%worker_addr, "Handling request to delete UDP outlet portal");
+        let Some(outlet) = self.registry.udp_outlets.remove(worker_addr).await else {
+            warn!(%worker_addr, "UDP outlet not found in the node registry");
+            return Ok(None);
+        };
+
+        if let Err(e) = self
+            .udp_portal_transport()?
+            .stop_outlet(outlet.worker_addr.clone())
+            .await
+        {
+            warn!(%worker_addr, %e, "Failed to stop UDP outlet worker");
+        }
+        self.resources()
+            .delete_resource(&worker_addr.address().into())
+            .await?;
+
+        Ok(Some(outlet.into()))
+    }
+
+    pub async fn list_udp_outlets(&self) -> Vec<UdpOutletStatus> {
+        self.registry
+            .udp_outlets
+            .values()
+            .await
+            .into_iter()
+            .map(UdpOutletStatus::from)
+            .collect()
+    }
+}
+
+impl UdpInletStatus {
+    fn new(alias: &str, info: &UdpInletInfo, outlet_route: &ockam::Route) -> Self {
+        Self {
+            alias: alias.to_string(),
+            bind_addr: info.bind_addr.clone(),
+            outlet_addr: info.outlet_addr.to_string(),
+            outlet_route: outlet_route.to_string(),
+        }
+    }
+}
+
+impl From<UdpOutletInfo> for UdpOutletStatus {
+    fn from(info: UdpOutletInfo) -> Self {
+        Self {
+            to: info.to,
+            worker_addr: info.worker_addr,
+        }
+    }
+}
+
+#[async_trait]
+pub trait UdpPortals {
+    async fn create_udp_inlet(
+        &self,
+        ctx: &Context,
+        request: CreateUdpInlet,
+    ) -> miette::Result<UdpInletStatus>;
+
+    async fn delete_udp_inlet(&self, ctx: &Context, alias: &str) -> miette::Result<UdpInletStatus>;
+
+    async fn list_udp_inlets(&self, ctx: &Context) -> miette::Result<Vec<UdpInletStatus>>;
+
+    async fn create_udp_outlet(
+        &self,
+        ctx: &Context,
+        request: CreateUdpOutlet,
+    ) -> miette::Result<UdpOutletStatus>;
+
+    async fn delete_udp_outlet(
+        &self,
+        ctx: &Context,
+        worker_addr: &Address,
+    ) -> miette::Result<UdpOutletStatus>;
+
+    async fn list_udp_outlets(&self, ctx: &Context) -> miette::Result<Vec<UdpOutletStatus>>;
+}
+
+#[async_trait]
+impl UdpPortals for BackgroundNodeClient {
+    #[instrument(skip_all)]
+    async fn create_udp_inlet(
+        &self,
+        ctx: &Context,
+        request: CreateUdpInlet,
+    ) -> miette::Result<UdpInletStatus> {
+        let req = Request::post("/node/udp/inlet").body(request);
+        self.ask(ctx, req).await
+    }
+
+    #[instrument(skip_all)]
+    async fn delete_udp_inlet(&self, ctx: &Context, alias: &str) -> miette::Result<UdpInletStatus> {
+        let req = Request::delete(format!("/node/udp/inlet/{alias}"));
+        self.ask(ctx, req).await
+    }
+
+    #[instrument(skip_all)]
+    async fn list_udp_inlets(&self, ctx: &Context) -> miette::Result<Vec<UdpInletStatus>> {
+        self.ask(ctx, Request::get("/node/udp/inlet")).await
+    }
+
+    #[instrument(skip_all)]
+    async fn create_udp_outlet(
+        &self,
+        ctx: &Context,
+        request: CreateUdpOutlet,
+    ) -> miette::Result<UdpOutletStatus> {
+        let req = Request::post("/node/udp/outlet").body(request);
+        self.ask(ctx, req).await
+    }
+
+    #[instrument(skip_all)]
+    async fn delete_udp_outlet(
+        &self,
+        ctx: &Context,
+        worker_addr: &Address,
+    ) -> miette::Result<UdpOutletStatus> {
+        let req = Request::delete(format!("/node/udp/outlet/{}", worker_addr.address()));
+        self.ask(ctx, req).await
+    }
+
+    #[instrument(skip_all)]
+    async fn list_udp_outlets(&self, ctx: &Context) -> miette::Result<Vec<UdpOutletStatus>> {
+        self.ask(ctx, Request::get("/node/udp/outlet")).await
+    }
+}
@@ -1,4 +1,5 @@
 use crate::nodes::models::policies::SetPolicyRequest;
+use crate::nodes::models::portal::DeleteOutlet;
 use crate::nodes::registry::KafkaServiceKind;
 use crate::nodes::service::{encode_response, TARGET};
 use crate::nodes::{InMemoryNode, NODEMANAGER_ADDR};
@@ -97,6 +98,9 @@ impl NodeManagerWorker {
             (Delete, ["node", "secure_channel"]) => {
                 encode_response(req, self.delete_secure_channel(dec.decode()?, ctx).await)?
             }
+            (Post, ["node", "identity", "change_history"]) => {
+                encode_response(req, self.present_change_history(ctx).await)?
+            }
             (Get, ["node", "show_secure_channel"]) => {
                 encode_response(req, self.show_secure_channel(dec.decode()?).await)?
             }
@@ -185,13 +189,36 @@ impl NodeManagerWorker {
             }
             (Delete, ["node", "outlet", addr]) => {
                 let addr: Address = addr.to_string().into();
-                encode_response(req, self.delete_outlet(&addr).await)?
+                // older clients don't send a body
+                let delete_outlet: DeleteOutlet = if req.has_body() {
+                    dec.decode()?
+                } else {
+                    DeleteOutlet::default()
+                };
+                encode_response(req, self.delete_outlet(&addr, delete_outlet).await)?
             }
             (Delete, ["node", "inlet", alias]) => {
                 encode_response(req, self.delete_inlet(alias).await)?
             }
             (Delete, ["node", "portal"]) => todo!(),
 
+            // ==*== UDP Inlets & Outlets ==*==
+            (Get, ["node", "udp", "inlet"]) => encode_response(req, self.get_udp_inlets().await)?,
+            (Get, ["node", "udp", "outlet"]) => encode_response(req, self.get_udp_outlets().await)?,
+            (Post, ["node", "udp", "inlet"]) => {
+                encode_response(req, self.create_udp_inlet(ctx, dec.decode()?).await)?
+            }
+            (Post, ["node", "udp", "outlet"]) => {
+                encode_response(req, self.create_udp_outlet(ctx, dec.decode()?).await)?
+            }
+            (Delete, ["node", "udp", "inlet", alias]) => {
+                encode_response(req, self.delete_udp_inlet(ctx, alias).await)?
+            }
+            (Delete, ["node", "udp", "outlet", addr]) => {
+                let addr: Address = addr.to_string().into();
+                encode_response(req, self.delete_udp_outlet(&addr).await)?
+            }
+
             // ==*== InfluxDB Inlets & Outlets  ==*==
             (Post, ["node", "influxdb_inlet"]) => encode_response(
                 req,
@@ -219,6 +246,12 @@ impl NodeManagerWorker {
                         .await,
                 )?
             }
+            (Get, ["policy", "audit"]) => {
+                encode_response(req, self.get_policy_decisions(dec.decode()?).await)?
+            }
+            (Get, ["policy", "test"]) => {
+                encode_response(req, self.test_policy(dec.decode()?).await)?
+            }
             (Get, ["policy", action]) => {
                 encode_response(req, self.get_policy(action, dec.decode()?).await)?
             }
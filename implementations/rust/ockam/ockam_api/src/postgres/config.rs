@@ -0,0 +1,17 @@
+use minicbor::{CborLen, Decode, Encode};
+use ockam_abac::PolicyExpression;
+
+/// Configuration of a TCP outlet intercepting the Postgres queries sent to its server
+#[derive(Debug, Clone, Default, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PostgresOutletConfig {
+    /// Policy authorizing the read-only statements. If not set, they are always accepted
+    #[n(1)] pub read_policy: Option<PolicyExpression>,
+    /// Policy authorizing all the other statements. If not set, they are always accepted
+    #[n(2)] pub write_policy: Option<PolicyExpression>,
+    /// Database user set in the startup message, when it is not mapped from an attribute
+    #[n(3)] pub user: Option<String>,
+    /// Name of the identity attribute containing the database user of the connecting identity
+    #[n(4)] pub user_attribute: Option<String>,
+}
@@ -0,0 +1,514 @@
+use crate::postgres::protocol::{
+    encode_error_response_into, encode_query_into, FrontendDecoder, FrontendMessage,
+    FrontendSegment, StartupMessage,
+};
+use crate::postgres::{classify_query, PostgresOutletConfig, StatementKind};
+use ockam::identity::{Identifier, IdentitiesAttributes, IdentitySecureChannelLocalInfo};
+use ockam_abac::{Abac, Action, Env, Expr, PolicyAudit, Resource};
+use ockam_core::{async_trait, LocalInfo};
+use ockam_node::Context;
+use ockam_transport_tcp::{
+    Direction, PortalInterceptor, PortalInterceptorFactory, PortalInterceptorRejection,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Maximum length of the queries recorded in the audit events
+const MAX_AUDITED_QUERY_LENGTH: usize = 1024;
+
+/// SQLSTATE of the errors sent when the connection is refused
+const INVALID_AUTHORIZATION_SPECIFICATION: &str = "28000";
+/// SQLSTATE of the errors sent when a statement is denied
+const INSUFFICIENT_PRIVILEGE: &str = "42501";
+/// SQLSTATE of the errors sent when the connection can't be intercepted
+const REJECTED_CONNECTION: &str = "08004";
+
+#[derive(Clone)]
+struct PostgresInterceptorSettings {
+    read_expression: Option<Expr>,
+    write_expression: Option<Expr>,
+    user: Option<String>,
+    user_attribute: Option<String>,
+    identities_attributes: Arc<IdentitiesAttributes>,
+    authority: Option<Identifier>,
+    abac: Abac,
+}
+
+/// Create interceptors authorizing the queries sent to a Postgres server through a portal.
+///
+/// For each connection:
+///  - the database user of the startup message is replaced with the user mapped
+///    from the connecting identity, if configured
+///  - each statement is classified as a read or a write, and authorized with the
+///    corresponding policy. A denied query is replaced with a statement raising an
+///    `insufficient_privilege` error, so that the client gets an error and the
+///    connection stays open
+///  - an audit event is logged for each query
+///
+/// When a connection is refused, because no database user is mapped to the identity or
+/// replication is denied, or when a function call is denied, the client receives an
+/// `ErrorResponse` and the connection is closed.
+///
+/// The connection can't be intercepted if the client and the server encrypt it,
+/// so clients must disable SSL/GSSAPI encryption. The portal is already encrypted.
+/// When the server accepts to encrypt the connection, its answer is replaced with an
+/// `ErrorResponse` so that the client closes the connection.
+#[derive(Clone)]
+pub struct PostgresInterceptorFactory {
+    settings: Arc<PostgresInterceptorSettings>,
+}
+
+impl PostgresInterceptorFactory {
+    pub fn new(
+        config: PostgresOutletConfig,
+        identities_attributes: Arc<IdentitiesAttributes>,
+        authority: Option<Identifier>,
+        environment: Env,
+    ) -> Self {
+        let abac = Abac::new(
+            identities_attributes.clone(),
+            authority.clone(),
+            environment,
+        );
+        Self {
+            settings: Arc::new(PostgresInterceptorSettings {
+                read_expression: config.read_policy.map(|e| e.to_expression()),
+                write_expression: config.write_policy.map(|e| e.to_expression()),
+                user: config.user,
+                user_attribute: config.user_attribute,
+                identities_attributes,
+                authority,
+                abac,
+            }),
+        }
+    }
+
+    /// Record the authorization decisions in the policy audit
+    pub fn with_audit(mut self, audit: Option<PolicyAudit>, resource: Resource) -> Self {
+        let settings = Arc::make_mut(&mut self.settings);
+        settings.abac = settings
+            .abac
+            .clone()
+            .with_audit(audit)
+            .with_resource_and_action(resource, Action::HandleMessage);
+        self
+    }
+}
+
+impl PortalInterceptorFactory for PostgresInterceptorFactory {
+    fn create(&self) -> Arc<dyn PortalInterceptor> {
+        self.create_for_connection(&[])
+    }
+
+    fn create_for_connection(&self, local_info: &[LocalInfo]) -> Arc<dyn PortalInterceptor> {
+        let identifier = IdentitySecureChannelLocalInfo::find_info_from_list(local_info)
+            .ok()
+            .map(|info| info.their_identity_id());
+        Arc::new(PostgresInterceptor {
+            settings: self.settings.clone(),
+            identifier,
+            state: Default::default(),
+            awaiting_encryption_response: AtomicBool::new(false),
+        })
+    }
+}
+
+/// State of the client side of a connection
+#[derive(Default)]
+struct ConnectionState {
+    decoder: FrontendDecoder,
+    user: Option<String>,
+    database: Option<String>,
+}
+
+struct PostgresInterceptor {
+    settings: Arc<PostgresInterceptorSettings>,
+    /// Identity which opened the portal connection, if it was received via a secure channel
+    identifier: Option<Identifier>,
+    state: Mutex<ConnectionState>,
+    /// True when the client asked to encrypt the connection and the server didn't answer yet
+    awaiting_encryption_response: AtomicBool,
+}
+
+impl PostgresInterceptor {
+    async fn is_authorized(&self, kind: StatementKind) -> ockam_core::Result<bool> {
+        let expression = match kind {
+            StatementKind::Read => &self.settings.read_expression,
+            StatementKind::Write => &self.settings.write_expression,
+        };
+        let Some(expression) = expression else {
+            return Ok(true);
+        };
+        match &self.identifier {
+            Some(identifier) => {
+                self.settings
+                    .abac
+                    .is_identity_authorized(identifier, expression)
+                    .await
+            }
+            None => {
+                self.settings
+                    .abac
+                    .record_denial(None, Some(expression), "no identity")
+                    .await;
+                Ok(false)
+            }
+        }
+    }
+
+    /// Set the database user of the connection, and check that replication
+    /// connections are allowed to write
+    async fn rewrite_startup(
+        &self,
+        startup: &mut StartupMessage,
+        state: &mut ConnectionState,
+    ) -> ockam_core::Result<()> {
+        let mapped_user = match &self.settings.user_attribute {
+            Some(attribute) => self.user_from_attribute(attribute).await?,
+            None => None,
+        };
+        match mapped_user.or_else(|| self.settings.user.clone()) {
+            Some(user) => startup.set_parameter("user", user),
+            None if self.settings.user_attribute.is_some() => {
+                return Err(rejected(
+                    INVALID_AUTHORIZATION_SPECIFICATION,
+                    format!(
+                        "no database user is mapped to the identity {}",
+                        display_identifier(self.identifier.as_ref())
+                    ),
+                ))
+            }
+            None => {}
+        }
+        state.user = startup.parameter("user").map(|u| u.to_string());
+        state.database = startup
+            .parameter("database")
+            .map(|d| d.to_string())
+            .or_else(|| state.user.clone());
+
+        let replication = startup
+            .parameter("replication")
+            .is_some_and(|r| !matches!(r, "false" | "off" | "no" | "0"));
+        if replication && !self.is_authorized(StatementKind::Write).await? {
+            return Err(rejected(
+                INVALID_AUTHORIZATION_SPECIFICATION,
+                "replication connections are not allowed by the Ockam policy of this portal".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn user_from_attribute(&self, attribute: &str) -> ockam_core::Result<Option<String>> {
+        let (Some(identifier), Some(authority)) = (&self.identifier, &self.settings.authority)
+        else {
+            return Ok(None);
+        };
+        let attributes = self
+            .settings
+            .identities_attributes
+            .get_attributes(identifier, authority)
+            .await?;
+        Ok(attributes
+            .and_then(|entry| entry.attrs().get(attribute.as_bytes()).cloned())
+            .and_then(|user| String::from_utf8(user).ok()))
+    }
+
+    /// Return the query to send to the server: the query itself if it is authorized,
+    /// or a statement raising an error otherwise
+    async fn authorize_query(
+        &self,
+        query: String,
+        state: &ConnectionState,
+    ) -> ockam_core::Result<String> {
+        let kind = classify_query(&query);
+        let allowed = self.is_authorized(kind).await?;
+
+        // audit record of the query
+        info!(
+            identifier = ?self.identifier.as_ref().map(|i| i.to_string()),
+            user = ?state.user,
+            database = ?state.database,
+            statement = %kind,
+            allowed,
+            query = %truncate(&query, MAX_AUDITED_QUERY_LENGTH),
+            "postgres query"
+        );
+
+        if allowed {
+            Ok(query)
+        } else {
+            Ok(denied_statement(kind))
+        }
+    }
+}
+
+#[async_trait]
+impl PortalInterceptor for PostgresInterceptor {
+    async fn intercept(
+        &self,
+        _context: &mut Context,
+        direction: Direction,
+        buffer: &[u8],
+    ) -> ockam_core::Result<Option<Vec<u8>>> {
+        match direction {
+            Direction::FromOutletToInlet => {
+                if self
+                    .awaiting_encryption_response
+                    .swap(false, Ordering::SeqCst)
+                    && matches!(buffer.first(), Some(b'S') | Some(b'G'))
+                {
+                    // the answer comes from the server, so the client is notified in its place
+                    let message = "the postgres server accepted to encrypt the connection, which \
+                                   prevents its interception. Please disable SSL and GSSAPI \
+                                   encryption on the client, for example with sslmode=disable";
+                    warn!("{message}");
+                    let mut out = vec![];
+                    encode_error_response_into(&mut out, "FATAL", REJECTED_CONNECTION, message);
+                    return Ok(Some(out));
+                }
+                Ok(Some(buffer.to_vec()))
+            }
+
+            Direction::FromInletToOutlet => {
+                let mut state = self.state.lock().await;
+                let mut out = Vec::with_capacity(buffer.len());
+                for segment in state.decoder.decode(buffer)? {
+                    let message = match segment {
+                        FrontendSegment::Raw(bytes) => {
+                            out.extend_from_slice(&bytes);
+                            continue;
+                        }
+                        FrontendSegment::Message(message) => message,
+                    };
+                    match message {
+                        FrontendMessage::EncryptionRequest(bytes) => {
+                            self.awaiting_encryption_response
+                                .store(true, Ordering::SeqCst);
+                            out.extend_from_slice(&bytes);
+                        }
+                        FrontendMessage::Startup(mut startup) => {
+                            self.rewrite_startup(&mut startup, &mut state).await?;
+                            startup.encode_into(&mut out);
+                        }
+                        FrontendMessage::Query(query) => {
+                            let query = self.authorize_query(query, &state).await?;
+                            encode_query_into(&mut out, &query);
+                        }
+                        FrontendMessage::Parse(mut parse) => {
+                            parse.query = self.authorize_query(parse.query, &state).await?;
+                            parse.encode_into(&mut out);
+                        }
+                        // a function can modify data, and there is no statement to replace it with
+                        FrontendMessage::FunctionCall(bytes) => {
+                            if !self.is_authorized(StatementKind::Write).await? {
+                                return Err(rejected(
+                                    INSUFFICIENT_PRIVILEGE,
+                                    "function calls are not allowed by the Ockam policy of this \
+                                     portal"
+                                        .into(),
+                                ));
+                            }
+                            out.extend_from_slice(&bytes);
+                        }
+                    }
+                }
+                Ok(Some(out))
+            }
+        }
+    }
+}
+
+/// Statement sent instead of a denied query, returning an error to the client
+fn denied_statement(kind: StatementKind) -> String {
+    format!(
+        "DO $ockam$ BEGIN RAISE EXCEPTION USING ERRCODE = 'insufficient_privilege', \
+         MESSAGE = '{kind} statements are not allowed by the Ockam policy of this portal'; \
+         END $ockam$"
+    )
+}
+
+/// Error closing the connection, after sending an `ErrorResponse` to the client.
+/// The client can't continue the session, so the error is always fatal
+fn rejected(code: &str, message: String) -> ockam_core::Error {
+    let mut response = vec![];
+    encode_error_response_into(&mut response, "FATAL", code, &message);
+    PortalInterceptorRejection::new(message)
+        .with_response(response)
+        .into()
+}
+
+fn display_identifier(identifier: Option<&Identifier>) -> String {
+    identifier.map_or("<none>".to_string(), |i| i.to_string())
+}
+
+fn truncate(query: &str, max_length: usize) -> &str {
+    match query.char_indices().nth(max_length) {
+        Some((end, _)) => &query[..end],
+        None => query,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::postgres::protocol::ParseMessage;
+    use ockam::identity::identities;
+    use ockam_abac::PolicyExpression;
+    use std::str::FromStr;
+
+    #[ockam_macros::test]
+    async fn rewrite_the_client_messages(context: &mut Context) -> ockam_core::Result<()> {
+        let config = PostgresOutletConfig {
+            write_policy: Some(PolicyExpression::from_str(
+                "(= subject.has_credential true)",
+            )?),
+            user: Some("ockam".into()),
+            ..Default::default()
+        };
+        // there is no identity, so the write statements are denied
+        let interceptor = create_interceptor(config).await?;
+
+        let mut startup = StartupMessage {
+            protocol_version: 196608,
+            parameters: vec![
+                ("user".into(), "alice".into()),
+                ("database".into(), "app".into()),
+            ],
+        };
+        let insert = ParseMessage {
+            name: "s1".into(),
+            query: "INSERT INTO t VALUES ($1)".into(),
+            parameter_types: vec![23],
+        };
+        let sync = [b'S', 0, 0, 0, 4];
+
+        let mut stream = vec![];
+        startup.encode_into(&mut stream);
+        encode_query_into(&mut stream, "SELECT 1");
+        insert.encode_into(&mut stream);
+        stream.extend_from_slice(&sync);
+
+        let mut rewritten = vec![];
+        for chunk in stream.chunks(7) {
+            let out = interceptor
+                .intercept(context, Direction::FromInletToOutlet, chunk)
+                .await?;
+            rewritten.extend(out.unwrap());
+        }
+
+        let mut expected = vec![];
+        startup.set_parameter("user", "ockam");
+        startup.encode_into(&mut expected);
+        encode_query_into(&mut expected, "SELECT 1");
+        ParseMessage {
+            query: denied_statement(StatementKind::Write),
+            ..insert
+        }
+        .encode_into(&mut expected);
+        expected.extend_from_slice(&sync);
+        assert_eq!(rewritten, expected);
+        Ok(())
+    }
+
+    #[ockam_macros::test]
+    async fn refused_connections_and_function_calls_are_answered_with_an_error(
+        context: &mut Context,
+    ) -> ockam_core::Result<()> {
+        let mut startup = vec![];
+        StartupMessage {
+            protocol_version: 196608,
+            parameters: vec![("user".into(), "alice".into())],
+        }
+        .encode_into(&mut startup);
+
+        // no database user can be mapped without an identity
+        let interceptor = create_interceptor(PostgresOutletConfig {
+            user_attribute: Some("db_user".into()),
+            ..Default::default()
+        })
+        .await?;
+        let error = interceptor
+            .intercept(context, Direction::FromInletToOutlet, &startup)
+            .await
+            .unwrap_err();
+        assert_error_response(&error, INVALID_AUTHORIZATION_SPECIFICATION);
+
+        let interceptor = create_interceptor(PostgresOutletConfig {
+            write_policy: Some(PolicyExpression::from_str(
+                "(= subject.has_credential true)",
+            )?),
+            ..Default::default()
+        })
+        .await?;
+        interceptor
+            .intercept(context, Direction::FromInletToOutlet, &startup)
+            .await?;
+        let function_call = [b'F', 0, 0, 0, 4];
+        let error = interceptor
+            .intercept(context, Direction::FromInletToOutlet, &function_call)
+            .await
+            .unwrap_err();
+        assert_error_response(&error, INSUFFICIENT_PRIVILEGE);
+        Ok(())
+    }
+
+    #[ockam_macros::test]
+    async fn an_encrypted_connection_is_answered_with_an_error(
+        context: &mut Context,
+    ) -> ockam_core::Result<()> {
+        let interceptor = create_interceptor(PostgresOutletConfig::default()).await?;
+        let mut ssl_request = vec![0, 0, 0, 8];
+        ssl_request.extend_from_slice(&80877103_i32.to_be_bytes());
+        let out = interceptor
+            .intercept(context, Direction::FromInletToOutlet, &ssl_request)
+            .await?;
+        assert_eq!(out, Some(ssl_request));
+
+        let out = interceptor
+            .intercept(context, Direction::FromOutletToInlet, b"S")
+            .await?
+            .unwrap();
+        assert_eq!(out[0], b'E');
+        assert!(contains(&out, REJECTED_CONNECTION.as_bytes()));
+        Ok(())
+    }
+
+    async fn create_interceptor(
+        config: PostgresOutletConfig,
+    ) -> ockam_core::Result<Arc<dyn PortalInterceptor>> {
+        let identities_attributes = identities().await?.identities_attributes();
+        Ok(
+            PostgresInterceptorFactory::new(config, identities_attributes, None, Env::new())
+                .create_for_connection(&[]),
+        )
+    }
+
+    fn assert_error_response(error: &ockam_core::Error, code: &str) {
+        let rejection = PortalInterceptorRejection::find(error).unwrap();
+        assert_eq!(rejection.response()[0], b'E');
+        assert!(contains(rejection.response(), code.as_bytes()));
+        assert!(contains(
+            rejection.response(),
+            rejection.reason().as_bytes()
+        ));
+    }
+
+    fn contains(bytes: &[u8], value: &[u8]) -> bool {
+        bytes.windows(value.len()).any(|w| w == value)
+    }
+
+    #[test]
+    fn denied_statements_raise_an_error() {
+        let statement = denied_statement(StatementKind::Read);
+        assert!(statement.contains("insufficient_privilege"));
+        assert!(statement.contains("read statements are not allowed"));
+    }
+
+    #[test]
+    fn truncate_queries() {
+        assert_eq!(truncate("SELECT 1", 100), "SELECT 1");
+        assert_eq!(truncate("SELECT 1", 6), "SELECT");
+        assert_eq!(truncate("SELECT 'é'", 9), "SELECT 'é");
+    }
+}
@@ -0,0 +1,13 @@
+//! Portal interceptor for the Postgres frontend/backend protocol.
+//!
+//! The queries sent to a Postgres server are authorized per statement type, read-only or
+//! write, with ABAC policies evaluated against the attributes of the connecting identity.
+//! The database user can be mapped from an identity attribute, and each query is audited.
+mod config;
+mod interceptor;
+mod protocol;
+mod statement;
+
+pub use config::*;
+pub use interceptor::*;
+pub use statement::*;
@@ -0,0 +1,427 @@
+//! Framing of the messages sent by a Postgres client, and of the errors sent back to it, see
+//! <https://www.postgresql.org/docs/current/protocol-message-formats.html>
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Result;
+
+const SSL_REQUEST_CODE: i32 = 80877103;
+const GSSENC_REQUEST_CODE: i32 = 80877104;
+const CANCEL_REQUEST_CODE: i32 = 80877102;
+const PROTOCOL_MAJOR_VERSION: i32 = 3;
+
+/// Maximum size of the messages buffered to be inspected
+const MAX_INSPECTED_MESSAGE_SIZE: usize = 64 * 1024 * 1024;
+
+/// Part of the frontend stream, either a message to inspect or bytes to forward as they are
+#[derive(Debug, PartialEq)]
+pub(crate) enum FrontendSegment {
+    Message(FrontendMessage),
+    Raw(Vec<u8>),
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum FrontendMessage {
+    /// SSLRequest or GSSENCRequest, answered by a single byte from the server
+    EncryptionRequest(Vec<u8>),
+    Startup(StartupMessage),
+    /// Simple query
+    Query(String),
+    /// Parse step of the extended query protocol
+    Parse(ParseMessage),
+    /// FunctionCall, which is forwarded as it is
+    FunctionCall(Vec<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StartupMessage {
+    pub(crate) protocol_version: i32,
+    pub(crate) parameters: Vec<(String, String)>,
+}
+
+impl StartupMessage {
+    pub(crate) fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub(crate) fn set_parameter(&mut self, name: &str, value: impl Into<String>) {
+        let value = value.into();
+        match self.parameters.iter_mut().find(|(n, _)| n == name) {
+            Some((_, v)) => *v = value,
+            None => self.parameters.push((name.to_string(), value)),
+        }
+    }
+
+    fn decode(message: &[u8]) -> Result<Self> {
+        let protocol_version = read_i32(message, 4);
+        let mut reader = Reader::new(&message[8..]);
+        let mut parameters = vec![];
+        loop {
+            let name = reader.cstring()?;
+            if name.is_empty() {
+                break;
+            }
+            parameters.push((name, reader.cstring()?));
+        }
+        Ok(Self {
+            protocol_version,
+            parameters,
+        })
+    }
+
+    pub(crate) fn encode_into(&self, out: &mut Vec<u8>) {
+        let start = out.len();
+        out.extend_from_slice(&[0; 4]);
+        out.extend_from_slice(&self.protocol_version.to_be_bytes());
+        for (name, value) in &self.parameters {
+            write_cstring(out, name);
+            write_cstring(out, value);
+        }
+        out.push(0);
+        let length = (out.len() - start) as i32;
+        out[start..start + 4].copy_from_slice(&length.to_be_bytes());
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ParseMessage {
+    pub(crate) name: String,
+    pub(crate) query: String,
+    pub(crate) parameter_types: Vec<i32>,
+}
+
+impl ParseMessage {
+    fn decode(body: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(body);
+        let name = reader.cstring()?;
+        let query = reader.cstring()?;
+        let count = reader.i16()?;
+        let parameter_types = (0..count)
+            .map(|_| reader.i32())
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            name,
+            query,
+            parameter_types,
+        })
+    }
+
+    pub(crate) fn encode_into(&self, out: &mut Vec<u8>) {
+        let mut body = vec![];
+        write_cstring(&mut body, &self.name);
+        write_cstring(&mut body, &self.query);
+        body.extend_from_slice(&(self.parameter_types.len() as i16).to_be_bytes());
+        for parameter_type in &self.parameter_types {
+            body.extend_from_slice(&parameter_type.to_be_bytes());
+        }
+        write_message(out, b'P', &body);
+    }
+}
+
+pub(crate) fn encode_query_into(out: &mut Vec<u8>, query: &str) {
+    let mut body = vec![];
+    write_cstring(&mut body, query);
+    write_message(out, b'Q', &body);
+}
+
+/// Encode an ErrorResponse, as sent by the server, with a severity like `ERROR` or `FATAL`
+/// and a SQLSTATE code
+pub(crate) fn encode_error_response_into(
+    out: &mut Vec<u8>,
+    severity: &str,
+    code: &str,
+    message: &str,
+) {
+    let mut body = vec![];
+    for (field, value) in [
+        (b'S', severity),
+        (b'V', severity),
+        (b'C', code),
+        (b'M', message),
+    ] {
+        body.push(field);
+        write_cstring(&mut body, value);
+    }
+    body.push(0);
+    write_message(out, b'E', &body);
+}
+
+/// Split the stream sent by a Postgres client into messages.
+///
+/// Only the startup message, the queries, and the function calls are buffered until they are
+/// complete. The other messages, for example the bound parameters or the COPY data, are
+/// forwarded as soon as they are received.
+#[derive(Debug, Default)]
+pub(crate) struct FrontendDecoder {
+    /// True once the startup message has been received, all the messages are then typed
+    startup_done: bool,
+    buffer: Vec<u8>,
+    /// Remaining bytes of a message forwarded without being inspected
+    passthrough: usize,
+}
+
+impl FrontendDecoder {
+    pub(crate) fn decode(&mut self, data: &[u8]) -> Result<Vec<FrontendSegment>> {
+        self.buffer.extend_from_slice(data);
+        let mut segments = vec![];
+
+        loop {
+            if self.passthrough > 0 {
+                if self.buffer.is_empty() {
+                    break;
+                }
+                let n = self.passthrough.min(self.buffer.len());
+                push_raw(&mut segments, self.buffer.drain(..n));
+                self.passthrough -= n;
+                continue;
+            }
+
+            if !self.startup_done {
+                // Int32 length, followed by an Int32 request code or protocol version
+                if self.buffer.len() < 8 {
+                    break;
+                }
+                let length = message_length(read_i32(&self.buffer, 0), 8)?;
+                check_inspected_size(length)?;
+                if self.buffer.len() < length {
+                    break;
+                }
+                let message: Vec<u8> = self.buffer.drain(..length).collect();
+                match read_i32(&message, 4) {
+                    SSL_REQUEST_CODE | GSSENC_REQUEST_CODE => segments.push(
+                        FrontendSegment::Message(FrontendMessage::EncryptionRequest(message)),
+                    ),
+                    CANCEL_REQUEST_CODE => push_raw(&mut segments, message),
+                    version if version >> 16 == PROTOCOL_MAJOR_VERSION => {
+                        self.startup_done = true;
+                        segments.push(FrontendSegment::Message(FrontendMessage::Startup(
+                            StartupMessage::decode(&message)?,
+                        )));
+                    }
+                    version => {
+                        return Err(protocol_error(format!(
+                            "unsupported postgres protocol version {}.{}",
+                            version >> 16,
+                            version & 0xffff
+                        )))
+                    }
+                }
+            } else {
+                // Byte1 message type, followed by an Int32 length which doesn't include the type
+                if self.buffer.len() < 5 {
+                    break;
+                }
+                let tag = self.buffer[0];
+                let length = message_length(read_i32(&self.buffer, 1), 4)? + 1;
+                if !matches!(tag, b'Q' | b'P' | b'F') {
+                    self.passthrough = length;
+                    continue;
+                }
+                check_inspected_size(length)?;
+                if self.buffer.len() < length {
+                    break;
+                }
+                let message: Vec<u8> = self.buffer.drain(..length).collect();
+                let message = match tag {
+                    b'Q' => FrontendMessage::Query(Reader::new(&message[5..]).cstring()?),
+                    b'P' => FrontendMessage::Parse(ParseMessage::decode(&message[5..])?),
+                    _ => FrontendMessage::FunctionCall(message),
+                };
+                segments.push(FrontendSegment::Message(message));
+            }
+        }
+        Ok(segments)
+    }
+}
+
+/// Append bytes to the last raw segment, or create a new one
+fn push_raw(segments: &mut Vec<FrontendSegment>, bytes: impl IntoIterator<Item = u8>) {
+    match segments.last_mut() {
+        Some(FrontendSegment::Raw(raw)) => raw.extend(bytes),
+        _ => segments.push(FrontendSegment::Raw(bytes.into_iter().collect())),
+    }
+}
+
+fn message_length(length: i32, minimum: usize) -> Result<usize> {
+    match usize::try_from(length) {
+        Ok(length) if length < minimum => Err(protocol_error(format!(
+            "invalid postgres message length {length}"
+        ))),
+        Ok(length) => Ok(length),
+        Err(_) => Err(protocol_error(format!(
+            "invalid postgres message length {length}"
+        ))),
+    }
+}
+
+fn check_inspected_size(length: usize) -> Result<()> {
+    if length > MAX_INSPECTED_MESSAGE_SIZE {
+        Err(protocol_error(format!(
+            "postgres message too large to be inspected: {length} bytes"
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+fn read_i32(bytes: &[u8], at: usize) -> i32 {
+    i32::from_be_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+fn write_cstring(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(s.as_bytes());
+    out.push(0);
+}
+
+fn write_message(out: &mut Vec<u8>, tag: u8, body: &[u8]) {
+    out.push(tag);
+    out.extend_from_slice(&((body.len() + 4) as i32).to_be_bytes());
+    out.extend_from_slice(body);
+}
+
+fn protocol_error(message: String) -> ockam_core::Error {
+    ockam_core::Error::new(Origin::Transport, Kind::Protocol, message)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    fn cstring(&mut self) -> Result<String> {
+        let end =
+            self.bytes.iter().position(|b| *b == 0).ok_or_else(|| {
+                protocol_error("unterminated string in a postgres message".into())
+            })?;
+        let s = String::from_utf8(self.bytes[..end].to_vec())
+            .map_err(|_| protocol_error("invalid UTF-8 string in a postgres message".into()))?;
+        self.bytes = &self.bytes[end + 1..];
+        Ok(s)
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        if self.bytes.len() < N {
+            return Err(protocol_error("truncated postgres message".into()));
+        }
+        let (value, rest) = self.bytes.split_at(N);
+        self.bytes = rest;
+        Ok(value.try_into().unwrap())
+    }
+
+    fn i16(&mut self) -> Result<i16> {
+        self.take().map(i16::from_be_bytes)
+    }
+
+    fn i32(&mut self) -> Result<i32> {
+        self.take().map(i32::from_be_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn startup() -> StartupMessage {
+        StartupMessage {
+            protocol_version: 196608,
+            parameters: vec![
+                ("user".into(), "alice".into()),
+                ("database".into(), "app".into()),
+            ],
+        }
+    }
+
+    #[test]
+    fn decode_messages_split_across_buffers() {
+        let mut stream = vec![];
+        startup().encode_into(&mut stream);
+        encode_query_into(&mut stream, "SELECT 1");
+        // Sync, forwarded as it is
+        stream.extend_from_slice(&[b'S', 0, 0, 0, 4]);
+        let parse = ParseMessage {
+            name: "s1".into(),
+            query: "SELECT $1".into(),
+            parameter_types: vec![23],
+        };
+        parse.encode_into(&mut stream);
+
+        for chunk_size in [1, 3, 7, stream.len()] {
+            let mut decoder = FrontendDecoder::default();
+            let mut messages = vec![];
+            let mut raw = vec![];
+            for chunk in stream.chunks(chunk_size) {
+                for segment in decoder.decode(chunk).unwrap() {
+                    match segment {
+                        FrontendSegment::Message(message) => messages.push(message),
+                        FrontendSegment::Raw(bytes) => raw.extend(bytes),
+                    }
+                }
+            }
+            assert_eq!(
+                messages,
+                vec![
+                    FrontendMessage::Startup(startup()),
+                    FrontendMessage::Query("SELECT 1".into()),
+                    FrontendMessage::Parse(parse.clone()),
+                ]
+            );
+            assert_eq!(raw, vec![b'S', 0, 0, 0, 4]);
+        }
+    }
+
+    #[test]
+    fn decode_an_encryption_request_before_the_startup_message() {
+        let mut stream = vec![0, 0, 0, 8];
+        stream.extend_from_slice(&SSL_REQUEST_CODE.to_be_bytes());
+        startup().encode_into(&mut stream);
+
+        let segments = FrontendDecoder::default().decode(&stream).unwrap();
+        assert_eq!(
+            segments,
+            vec![
+                FrontendSegment::Message(FrontendMessage::EncryptionRequest(stream[..8].to_vec())),
+                FrontendSegment::Message(FrontendMessage::Startup(startup())),
+            ]
+        );
+    }
+
+    #[test]
+    fn rewrite_the_startup_parameters() {
+        let mut message = startup();
+        message.set_parameter("user", "bob");
+        message.set_parameter("application_name", "psql");
+
+        let mut bytes = vec![];
+        message.encode_into(&mut bytes);
+        let decoded = StartupMessage::decode(&bytes).unwrap();
+        assert_eq!(decoded.parameter("user"), Some("bob"));
+        assert_eq!(decoded.parameter("database"), Some("app"));
+        assert_eq!(decoded.parameter("application_name"), Some("psql"));
+    }
+
+    #[test]
+    fn reject_invalid_messages() {
+        let mut decoder = FrontendDecoder::default();
+        assert!(decoder.decode(&[0, 0, 0, 8, 0, 2, 0, 0]).is_err());
+
+        let mut decoder = FrontendDecoder::default();
+        let mut stream = vec![];
+        startup().encode_into(&mut stream);
+        stream.extend_from_slice(&[b'Q', 0, 0, 0, 2]);
+        assert!(decoder.decode(&stream).is_err());
+    }
+
+    #[test]
+    fn encode_an_error_response() {
+        let mut bytes = vec![];
+        encode_error_response_into(&mut bytes, "FATAL", "28000", "denied");
+        let mut expected = vec![b'E', 0, 0, 0, 34];
+        expected.extend_from_slice(b"SFATAL\0VFATAL\0C28000\0Mdenied\0\0");
+        assert_eq!(bytes, expected);
+    }
+}
@@ -0,0 +1,335 @@
+use std::fmt::{Display, Formatter};
+
+/// Kind of SQL statement, used to authorize the queries sent to a Postgres server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatementKind {
+    /// The statement only reads data
+    Read,
+    /// The statement can modify data, the schema, or the privileges of the session
+    Write,
+}
+
+impl StatementKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatementKind::Read => "read",
+            StatementKind::Write => "write",
+        }
+    }
+}
+
+impl Display for StatementKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Keywords starting a statement which can be explained, prepared, or used in a cursor
+const STATEMENT_KEYWORDS: [&str; 11] = [
+    "SELECT", "WITH", "VALUES", "TABLE", "INSERT", "UPDATE", "DELETE", "MERGE", "CREATE",
+    "EXECUTE", "DECLARE",
+];
+
+/// Built-in functions which modify the session, the data, or access the server file system
+const SIDE_EFFECT_FUNCTIONS: [&str; 9] = [
+    "SET_CONFIG",
+    "NEXTVAL",
+    "SETVAL",
+    "LO_IMPORT",
+    "LO_EXPORT",
+    "PG_READ_FILE",
+    "PG_READ_BINARY_FILE",
+    "PG_TERMINATE_BACKEND",
+    "PG_RELOAD_CONF",
+];
+
+/// Classify a query, which can contain several statements separated by `;`.
+///
+/// The classification is lexical and conservative: a statement which is not known to be
+/// read-only is a write. Note that a `SELECT` can still modify data when it calls
+/// functions with side effects, so the database user should also have the right privileges.
+pub fn classify_query(query: &str) -> StatementKind {
+    if tokenize(query)
+        .iter()
+        .any(|statement| classify_statement(statement) == StatementKind::Write)
+    {
+        StatementKind::Write
+    } else {
+        StatementKind::Read
+    }
+}
+
+/// A keyword or an unquoted identifier, in upper case,
+/// with the parenthesis depth where it was found
+#[derive(Debug)]
+struct Word {
+    text: String,
+    depth: usize,
+}
+
+fn classify_statement(words: &[Word]) -> StatementKind {
+    let Some(first) = words.first() else {
+        return StatementKind::Read;
+    };
+    let contains = |keyword: &str| words.iter().any(|w| w.text == keyword);
+    if SIDE_EFFECT_FUNCTIONS.iter().any(|f| contains(f)) {
+        return StatementKind::Write;
+    }
+    let top_level_position = |keyword: &str| {
+        words
+            .iter()
+            .position(|w| w.depth == first.depth && w.text == keyword)
+    };
+
+    match first.text.as_str() {
+        "SELECT" | "VALUES" | "TABLE" | "WITH" => {
+            // data modifying common table expressions, SELECT INTO, and row locks
+            if ["INSERT", "UPDATE", "DELETE", "MERGE", "INTO", "SHARE"]
+                .iter()
+                .any(|k| contains(k))
+            {
+                StatementKind::Write
+            } else {
+                StatementKind::Read
+            }
+        }
+        // EXPLAIN ANALYZE executes the explained statement
+        "EXPLAIN" => {
+            if !contains("ANALYZE") && !contains("ANALYSE") {
+                return StatementKind::Read;
+            }
+            match words[1..]
+                .iter()
+                .position(|w| w.depth == first.depth && STATEMENT_KEYWORDS.contains(&&*w.text))
+            {
+                Some(position) => classify_statement(&words[position + 1..]),
+                None => StatementKind::Write,
+            }
+        }
+        "PREPARE" => match top_level_position("AS") {
+            Some(position) => classify_statement(&words[position + 1..]),
+            None => StatementKind::Write,
+        },
+        "DECLARE" => match top_level_position("FOR") {
+            Some(position) => classify_statement(&words[position + 1..]),
+            None => StatementKind::Write,
+        },
+        // only a copy to the client is a read, a copy to a file or a program runs on the server
+        "COPY" => match top_level_position("TO") {
+            Some(position)
+                if words.get(position + 1).map(|w| w.text.as_str()) == Some("STDOUT")
+                    && top_level_position("FROM").is_none()
+                    && !["INSERT", "UPDATE", "DELETE", "MERGE", "PROGRAM"]
+                        .iter()
+                        .any(|k| contains(k)) =>
+            {
+                StatementKind::Read
+            }
+            _ => StatementKind::Write,
+        },
+        // changing the role of the session can grant more privileges
+        "SET" => {
+            if contains("ROLE") || contains("AUTHORIZATION") || contains("SESSION_AUTHORIZATION") {
+                StatementKind::Write
+            } else {
+                StatementKind::Read
+            }
+        }
+        "SHOW" | "BEGIN" | "START" | "COMMIT" | "END" | "ROLLBACK" | "ABORT" | "SAVEPOINT"
+        | "RELEASE" | "RESET" | "DISCARD" | "DEALLOCATE" | "CLOSE" | "FETCH" | "MOVE"
+        | "LISTEN" | "UNLISTEN" => StatementKind::Read,
+        _ => StatementKind::Write,
+    }
+}
+
+/// Split a query into statements, made of the words found outside of
+/// comments, string literals, and quoted identifiers
+fn tokenize(query: &str) -> Vec<Vec<Word>> {
+    let bytes = query.as_bytes();
+    let mut statements = vec![];
+    let mut words: Vec<Word> = vec![];
+    let mut depth = 0;
+    let mut i = 0;
+
+    let is_word_byte = |b: u8| b.is_ascii_alphanumeric() || b == b'_' || b == b'$' || b >= 0x80;
+
+    while i < bytes.len() {
+        let next = bytes.get(i + 1).copied();
+        match bytes[i] {
+            b'-' if next == Some(b'-') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if next == Some(b'*') => {
+                // block comments can be nested
+                let mut comment_depth = 0;
+                while i < bytes.len() {
+                    if bytes[i..].starts_with(b"/*") {
+                        comment_depth += 1;
+                        i += 2;
+                    } else if bytes[i..].starts_with(b"*/") {
+                        comment_depth -= 1;
+                        i += 2;
+                        if comment_depth == 0 {
+                            break;
+                        }
+                    } else {
+                        i += 1;
+                    }
+                }
+            }
+            b'\'' => i = skip_quoted(bytes, i, b'\'', false),
+            b'"' => i = skip_quoted(bytes, i, b'"', false),
+            b'$' => {
+                // dollar quoted string: $$...$$ or $tag$...$tag$
+                let tag_end = bytes[i + 1..]
+                    .iter()
+                    .position(|b| !(b.is_ascii_alphanumeric() || *b == b'_' || *b >= 0x80))
+                    .map(|p| i + 1 + p);
+                match tag_end {
+                    Some(end) if bytes[end] == b'$' && !bytes[i + 1].is_ascii_digit() => {
+                        let tag = &bytes[i..=end];
+                        i = match find(&bytes[end + 1..], tag) {
+                            Some(p) => end + 1 + p + tag.len(),
+                            None => bytes.len(),
+                        };
+                    }
+                    // positional parameter, for example $1
+                    _ => i += 1,
+                }
+            }
+            b'(' => {
+                depth += 1;
+                i += 1;
+            }
+            b')' => {
+                depth = usize::saturating_sub(depth, 1);
+                i += 1;
+            }
+            b';' => {
+                if !words.is_empty() {
+                    statements.push(std::mem::take(&mut words));
+                }
+                depth = 0;
+                i += 1;
+            }
+            b if b.is_ascii_digit() => {
+                while i < bytes.len() && is_word_byte(bytes[i]) {
+                    i += 1;
+                }
+            }
+            b if is_word_byte(b) => {
+                let start = i;
+                while i < bytes.len() && is_word_byte(bytes[i]) {
+                    i += 1;
+                }
+                let word = &query[start..i];
+                // escape string constants, for example E'it\'s'
+                if word.eq_ignore_ascii_case("E") && bytes.get(i) == Some(&b'\'') {
+                    i = skip_quoted(bytes, i, b'\'', true);
+                } else {
+                    words.push(Word {
+                        text: word.to_uppercase(),
+                        depth,
+                    });
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    if !words.is_empty() {
+        statements.push(words);
+    }
+    statements
+}
+
+/// Return the position after a quoted string or identifier starting at `start`.
+/// The quote character is escaped by doubling it, or with a backslash in escape strings
+fn skip_quoted(bytes: &[u8], start: usize, quote: u8, backslash_escapes: bool) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        if backslash_escapes && bytes[i] == b'\\' {
+            i += 2;
+        } else if bytes[i] == quote {
+            if bytes.get(i + 1) == Some(&quote) {
+                i += 2;
+            } else {
+                return i + 1;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    bytes.len()
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use StatementKind::*;
+
+    #[test]
+    fn classify_queries() {
+        let queries = [
+            ("SELECT * FROM accounts WHERE id = $1", Read),
+            ("select name from accounts; show search_path", Read),
+            (
+                "WITH recent AS (SELECT * FROM orders) SELECT count(*) FROM recent",
+                Read,
+            ),
+            ("EXPLAIN SELECT * FROM accounts", Read),
+            ("COPY (SELECT * FROM accounts) TO STDOUT", Read),
+            ("BEGIN; SELECT 1; COMMIT", Read),
+            ("SET search_path TO public", Read),
+            ("DECLARE c CURSOR FOR SELECT * FROM accounts", Read),
+            ("", Read),
+            ("-- a comment only", Read),
+            ("SELECT 'DELETE FROM accounts', \"update\" FROM t", Read),
+            ("SELECT $body$ DROP TABLE t $body$", Read),
+            ("SELECT E'it\\'s; DROP TABLE t'", Read),
+            ("SELECT /* nested /* DELETE */ comment */ 1", Read),
+            ("INSERT INTO accounts VALUES (1)", Write),
+            ("update accounts set name = 'x'", Write),
+            ("SELECT 1; DROP TABLE accounts", Write),
+            (
+                "WITH d AS (DELETE FROM orders RETURNING *) SELECT * FROM d",
+                Write,
+            ),
+            ("SELECT * INTO copy FROM accounts", Write),
+            ("SELECT * FROM accounts FOR UPDATE", Write),
+            ("SELECT * FROM accounts FOR KEY SHARE", Write),
+            ("EXPLAIN ANALYZE DELETE FROM accounts", Write),
+            ("EXPLAIN (ANALYZE) SELECT * FROM accounts", Read),
+            ("PREPARE p AS UPDATE accounts SET name = $1", Write),
+            (
+                "PREPARE p (int) AS SELECT * FROM accounts WHERE id = $1",
+                Read,
+            ),
+            ("COPY accounts FROM STDIN", Write),
+            ("COPY accounts TO STDOUT", Read),
+            ("COPY accounts TO PROGRAM 'rm -rf /'", Write),
+            ("COPY accounts TO '/tmp/accounts.csv'", Write),
+            ("COPY (DELETE FROM accounts RETURNING *) TO STDOUT", Write),
+            ("SET ROLE admin", Write),
+            ("SET LOCAL role = admin", Write),
+            ("SET SESSION AUTHORIZATION admin", Write),
+            ("SET session_authorization = admin", Write),
+            ("SELECT set_config('role', 'admin', false)", Write),
+            ("SELECT nextval('ids')", Write),
+            ("SELECT pg_read_file('/etc/passwd')", Write),
+            ("CREATE TABLE t (id int)", Write),
+            ("VACUUM accounts", Write),
+            ("CALL do_something()", Write),
+        ];
+        for (query, expected) in queries {
+            assert_eq!(classify_query(query), expected, "{query}");
+        }
+    }
+}
@@ -6,7 +6,7 @@ use ockam_node::{Context, NodeBuilder};
 use sqlx::__rt::timeout;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::ops::Deref;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
@@ -134,6 +134,14 @@ pub async fn start_manager_for_tests(
 pub struct EchoServerHandle {
     pub chosen_addr: HostnamePort,
     close: Arc<AtomicBool>,
+    connections: Arc<AtomicUsize>,
+}
+
+impl EchoServerHandle {
+    /// Number of connections accepted by the server
+    pub fn connections(&self) -> usize {
+        self.connections.load(Ordering::Relaxed)
+    }
 }
 
 impl Drop for EchoServerHandle {
@@ -151,9 +159,11 @@ pub async fn start_tcp_echo_server() -> EchoServerHandle {
 
     let chosen_addr = listener.local_addr().unwrap();
     let close = Arc::new(AtomicBool::new(false));
+    let connections = Arc::new(AtomicUsize::new(0));
 
     {
         let close = close.clone();
+        let connections = connections.clone();
         tokio::spawn(async move {
             loop {
                 let result = match timeout(Duration::from_millis(200), listener.accept()).await {
@@ -168,6 +178,7 @@ pub async fn start_tcp_echo_server() -> EchoServerHandle {
 
                 let (mut socket, _) = result.expect("Failed to accept connection");
                 socket.set_nodelay(true).unwrap();
+                connections.fetch_add(1, Ordering::Relaxed);
 
                 tokio::spawn(async move {
                     let mut buf = vec![0; 1024];
@@ -196,6 +207,7 @@ pub async fn start_tcp_echo_server() -> EchoServerHandle {
     EchoServerHandle {
         chosen_addr: HostnamePort::from(chosen_addr),
         close,
+        connections,
     }
 }
 
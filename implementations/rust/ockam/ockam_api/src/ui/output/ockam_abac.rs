@@ -1,6 +1,7 @@
-use crate::colors::color_primary;
-use crate::output::Output;
-use ockam_abac::{ResourcePolicy, ResourceTypePolicy};
+use crate::colors::{color_error, color_ok, color_primary};
+use crate::output::{human_readable_time, Output};
+use ockam::identity::TimestampInSeconds;
+use ockam_abac::{PolicyDecision, ResourcePolicy, ResourceTypePolicy};
 
 use std::fmt::Write;
 
@@ -37,3 +38,50 @@ impl Output for ResourcePolicy {
         Ok(output)
     }
 }
+
+impl Output for PolicyDecision {
+    fn item(&self) -> crate::Result<String> {
+        let mut output = String::new();
+        let decision = if self.allowed {
+            color_ok("allowed")
+        } else {
+            color_error("denied")
+        };
+        writeln!(
+            output,
+            "{}: {decision}",
+            human_readable_time(TimestampInSeconds(self.timestamp))
+        )?;
+        if let Some(subject) = &self.subject {
+            writeln!(output, "Subject: {}", color_primary(subject))?;
+        }
+        if !self.subject_attributes.is_empty() {
+            let attributes = self
+                .subject_attributes
+                .iter()
+                .map(|(k, v)| format!("{k}={v}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(output, "Subject attributes: {}", color_primary(attributes))?;
+        }
+        if let Some(resource_name) = &self.resource_name {
+            let resource_type = self.resource_type.as_deref().unwrap_or("-");
+            writeln!(
+                output,
+                "Resource: {} ({})",
+                color_primary(resource_name),
+                resource_type
+            )?;
+        }
+        if let Some(action) = &self.action {
+            writeln!(output, "Action: {}", color_primary(action))?;
+        }
+        if let Some(expression) = &self.expression {
+            writeln!(output, "Expression: {}", color_primary(expression))?;
+        }
+        if let Some(reason) = &self.reason {
+            writeln!(output, "Reason: {}", color_primary(reason))?;
+        }
+        Ok(output.trim_end().to_string())
+    }
+}
@@ -148,6 +148,23 @@ impl<W: TerminalWriter + Debug> Terminal<W> {
         ))
     }
 
+    /// Ask the user for a passphrase, which is not echoed.
+    /// When `confirm` is true, the passphrase must be typed twice.
+    /// Return `None` if the user cannot be asked for an input
+    pub fn prompt_passphrase(&self, msg: impl AsRef<str>, confirm: bool) -> Result<Option<String>> {
+        if !self.can_ask_for_user_input() {
+            return Ok(None);
+        }
+        let mut prompt = dialoguer::Password::new().with_prompt(fmt_log!("{}", msg.as_ref()));
+        if confirm {
+            prompt = prompt.with_confirmation(
+                fmt_log!("Repeat the passphrase"),
+                fmt_warn!("The passphrases don't match"),
+            );
+        }
+        Ok(Some(prompt.interact().map_err(UiError::Dialoguer)?))
+    }
+
     pub fn confirmed_with_flag_or_prompt(
         &self,
         flag: bool,
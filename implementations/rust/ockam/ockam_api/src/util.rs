@@ -3,11 +3,12 @@ use std::net::{SocketAddrV4, SocketAddrV6};
 use miette::miette;
 
 use ockam::tcp::{TcpConnection, TcpConnectionOptions, TcpTransport, TCP};
+use ockam::udp::UDP;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{Address, Error, Result, Route, TransportType, LOCAL};
 use ockam_multiaddr::proto::{
-    DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp, Worker,
+    DnsAddr, Ip4, Ip6, Node, Project, Quic, Secure, Service, Space, Tcp, Udp, Worker,
 };
 use ockam_multiaddr::{Code, MultiAddr, Protocol};
 
@@ -204,7 +205,8 @@ pub async fn multiaddr_to_route(
 
 /// Resolve all the multiaddresses which represent transport addresses
 /// For example /tcp/127.0.0.1/port/4000 is transformed to the Address (TCP, "127.0.0.1:4000")
-/// The creation of a TCP worker and the substitution of that transport address to a worker address
+/// and /ip4/127.0.0.1/udp/4000 is transformed to the Address (UDP, "127.0.0.1:4000")
+/// The creation of a TCP or UDP worker and the substitution of that transport address to a worker address
 /// is done later with `context.resolve_transport_route(route)`
 pub fn multiaddr_to_transport_route(ma: &MultiAddr) -> Option<Route> {
     let mut route = Route::new();
@@ -214,18 +216,46 @@ pub fn multiaddr_to_transport_route(ma: &MultiAddr) -> Option<Route> {
         match p.code() {
             Ip4::CODE => {
                 let ip4 = p.cast::<Ip4>()?;
-                let port = it.next()?.cast::<Tcp>()?;
-                let socket_addr = SocketAddrV4::new(*ip4, *port);
-                route = route.append(Address::new_with_string(TCP, socket_addr.to_string()))
+                let p = it.next()?;
+                match p.code() {
+                    Tcp::CODE => {
+                        let port = p.cast::<Tcp>()?;
+                        let socket_addr = SocketAddrV4::new(*ip4, *port);
+                        route = route.append(Address::new_with_string(TCP, socket_addr.to_string()))
+                    }
+                    Udp::CODE => {
+                        let port = p.cast::<Udp>()?;
+                        let socket_addr = SocketAddrV4::new(*ip4, *port);
+                        route = route.append(Address::new_with_string(UDP, socket_addr.to_string()))
+                    }
+                    other => {
+                        error!(target: "ockam_api", code = %other, "unsupported protocol after ip4");
+                        return None;
+                    }
+                }
             }
             Ip6::CODE => {
                 let ip6 = p.cast::<Ip6>()?;
-                let port = it.next()?.cast::<Tcp>()?;
-                let socket_addr = SocketAddrV6::new(*ip6, *port, 0, 0);
-                route = route.append(Address::new_with_string(
-                    TransportType::new(1),
-                    socket_addr.to_string(),
-                ))
+                let p = it.next()?;
+                match p.code() {
+                    Tcp::CODE => {
+                        let port = p.cast::<Tcp>()?;
+                        let socket_addr = SocketAddrV6::new(*ip6, *port, 0, 0);
+                        route = route.append(Address::new_with_string(
+                            TransportType::new(1),
+                            socket_addr.to_string(),
+                        ))
+                    }
+                    Udp::CODE => {
+                        let port = p.cast::<Udp>()?;
+                        let socket_addr = SocketAddrV6::new(*ip6, *port, 0, 0);
+                        route = route.append(Address::new_with_string(UDP, socket_addr.to_string()))
+                    }
+                    other => {
+                        error!(target: "ockam_api", code = %other, "unsupported protocol after ip6");
+                        return None;
+                    }
+                }
             }
             DnsAddr::CODE => {
                 let host = p.cast::<DnsAddr>()?;
@@ -237,6 +267,13 @@ pub fn multiaddr_to_transport_route(ma: &MultiAddr) -> Option<Route> {
                         let _ = it.next();
                         continue;
                     }
+                    if p.code() == Udp::CODE {
+                        let port = p.cast::<Udp>()?;
+                        let addr = format!("{}:{}", &*host, *port);
+                        route = route.append(Address::new_with_string(UDP, addr));
+                        let _ = it.next();
+                        continue;
+                    }
                 }
             }
             Worker::CODE => {
@@ -372,9 +409,54 @@ pub fn local_worker(code: &Code) -> Result<bool> {
         | Ip4::CODE
         | Ip6::CODE
         | Tcp::CODE
+        | Udp::CODE
+        | Quic::CODE
         | Secure::CODE => Ok(false),
         Worker::CODE | Service::CODE => Ok(true),
 
         _ => Err(ApiError::core(format!("unknown transport type: {code}"))),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_multiaddr_to_transport_route() {
+        let ma = MultiAddr::from_str("/ip4/127.0.0.1/tcp/4000/service/api").unwrap();
+        let route = multiaddr_to_transport_route(&ma).unwrap();
+        assert_eq!(
+            route,
+            Route::new()
+                .append(Address::new_with_string(TCP, "127.0.0.1:4000"))
+                .append(Address::new_with_string(LOCAL, "api"))
+                .into()
+        );
+
+        let ma = MultiAddr::from_str("/ip4/127.0.0.1/udp/4000/service/api").unwrap();
+        let route = multiaddr_to_transport_route(&ma).unwrap();
+        assert_eq!(
+            route,
+            Route::new()
+                .append(Address::new_with_string(UDP, "127.0.0.1:4000"))
+                .append(Address::new_with_string(LOCAL, "api"))
+                .into()
+        );
+
+        let ma = MultiAddr::from_str("/dnsaddr/localhost/udp/4000/service/api").unwrap();
+        let route = multiaddr_to_transport_route(&ma).unwrap();
+        assert_eq!(
+            route,
+            Route::new()
+                .append(Address::new_with_string(UDP, "localhost:4000"))
+                .append(Address::new_with_string(LOCAL, "api"))
+                .into()
+        );
+
+        // there is no QUIC transport yet
+        let ma = MultiAddr::from_str("/ip4/127.0.0.1/udp/4000/quic/service/api").unwrap();
+        assert!(multiaddr_to_transport_route(&ma).is_none());
+    }
+}
@@ -70,7 +70,7 @@ async fn credential(ctx: &mut Context) -> Result<()> {
     ctx.flow_controls()
         .add_consumer(auth_worker_addr.clone(), &sc_flow_control_id);
     let auth = CredentialIssuerWorker::new(
-        members,
+        members.clone(),
         identities.identities_attributes(),
         identities.credentials(),
         &auth_identifier,
@@ -114,5 +114,29 @@ async fn credential(ctx: &mut Context) -> Result<()> {
             .map
             .get::<ByteSlice>(b"attr".as_slice().into())
     );
+
+    // The member record follows the latest change hash of the member identity
+    let member = members.get_member(&member_identifier).await?.unwrap();
+    assert_eq!(
+        member.last_change_hash(),
+        Some(member_identity.latest_change_hash()?)
+    );
+
+    identities
+        .identities_creation()
+        .rotate_identity(&member_identifier)
+        .await?;
+    let rotated_identity = identities.get_identity(&member_identifier).await?;
+    let _: CredentialAndPurposeKey = client.ask(ctx, Request::post("/")).await?.success()?;
+
+    let member = members.get_member(&member_identifier).await?.unwrap();
+    assert_ne!(
+        rotated_identity.latest_change_hash()?,
+        member_identity.latest_change_hash()?
+    );
+    assert_eq!(
+        member.last_change_hash(),
+        Some(rotated_identity.latest_change_hash()?)
+    );
     Ok(())
 }
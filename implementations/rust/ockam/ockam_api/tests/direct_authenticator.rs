@@ -1,13 +1,18 @@
 use crate::common::common::{change_client_identifier, start_authority, AuthorityInfo};
+use ockam::identity::models::RevocationListAndPurposeKey;
 use ockam::identity::secure_channels;
 use ockam::identity::utils::now;
 use ockam_api::authenticator::direct::Members;
 use ockam_api::authenticator::direct::{
     OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE, OCKAM_ROLE_ATTRIBUTE_KEY,
 };
+use ockam_api::cloud::HasSecureClient;
+use ockam_api::nodes::service::default_address::DefaultAddress;
+use ockam_core::api::Request;
 use ockam_core::Result;
 use ockam_node::Context;
 use std::collections::BTreeMap;
+use std::time::Duration;
 
 mod common;
 
@@ -570,3 +575,68 @@ async fn member_cant_do_anything(ctx: &mut Context) -> Result<()> {
 
     Ok(())
 }
+
+#[ockam_macros::test]
+async fn deleted_member_is_revoked(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+
+    let AuthorityInfo {
+        authority_identifier,
+        admins,
+    } = start_authority(ctx, secure_channels.clone(), 1).await?;
+    let admin = &admins[0];
+
+    let member = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let added_at = now()?;
+    admin
+        .client
+        .add_member(ctx, member.clone(), BTreeMap::default())
+        .await
+        .unwrap();
+    // only the credentials created before the second of the revocation are revoked
+    ctx.sleep(Duration::from_secs(1)).await;
+    admin
+        .client
+        .delete_member(ctx, member.clone())
+        .await
+        .unwrap();
+
+    // the authority now publishes a revocation list containing the deleted member
+    let revocation_list: RevocationListAndPurposeKey = admin
+        .client
+        .get_secure_client()
+        .ask(
+            ctx,
+            DefaultAddress::REVOCATION_LIST_SERVICE,
+            Request::get("/"),
+        )
+        .await?
+        .success()?;
+
+    let revocation_lists = secure_channels
+        .identities()
+        .credentials()
+        .revocation_lists();
+    let data = revocation_lists
+        .verify_revocation_list(&authority_identifier, &revocation_list)
+        .await?;
+    assert_eq!(data.version, 1);
+    assert_eq!(data.revoked_subjects.len(), 1);
+    assert_eq!(data.revoked_subjects[0].subject, member);
+
+    let revoked = secure_channels
+        .receive_revocation_list(ctx, &authority_identifier, revocation_list)
+        .await?;
+    assert_eq!(revoked, vec![member.clone()]);
+    assert!(
+        revocation_lists
+            .is_revoked(&authority_identifier, &member, added_at)
+            .await?
+    );
+
+    Ok(())
+}
@@ -1,4 +1,7 @@
+use ockam::tcp::InletLoadBalancing;
+use ockam_abac::PolicyExpression;
 use ockam_api::config::lookup::InternetAddress;
+use ockam_api::http_interceptor::{HttpAccessRule, HttpOutletConfig};
 use ockam_api::nodes::models::portal::OutletAccessControl;
 use ockam_api::test_utils::{
     start_manager_for_tests, start_passthrough_server, start_tcp_echo_server, Disruption, TestNode,
@@ -80,6 +83,160 @@ async fn inlet_outlet_local_successful(context: &mut Context) -> ockam::Result<(
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn inlet_balanced_between_local_outlets(context: &mut Context) -> ockam::Result<()> {
+    TestNode::clean().await?;
+    // one server per outlet, to count the connections received by each outlet
+    let echo_server_handles = [start_tcp_echo_server().await, start_tcp_echo_server().await];
+    let node_manager_handle = start_manager_for_tests(context, None, None).await?;
+
+    for (outlet, echo_server_handle) in ["outlet1", "outlet2"].iter().zip(&echo_server_handles) {
+        node_manager_handle
+            .node_manager
+            .create_outlet(
+                context,
+                echo_server_handle.chosen_addr.clone(),
+                false,
+                Some(Address::from_string(*outlet)),
+                true,
+                OutletAccessControl::AccessControl((Arc::new(AllowAll), Arc::new(AllowAll))),
+                false,
+            )
+            .await?;
+    }
+
+    let inlet_status = node_manager_handle
+        .node_manager
+        .create_balanced_inlet(
+            context,
+            HostnamePort::new("127.0.0.1", 0),
+            vec![
+                MultiAddr::from_str("/secure/api/service/outlet1")?,
+                MultiAddr::from_str("/secure/api/service/outlet2")?,
+            ],
+            InletLoadBalancing::RoundRobin,
+            "alias".to_string(),
+            None,
+            None,
+            None,
+            true,
+            None,
+            None,
+        )
+        .await?;
+
+    assert_eq!(inlet_status.status, ConnectionStatus::Up);
+    assert_eq!(inlet_status.outlet_addr, "/secure/api/service/outlet1");
+    assert_eq!(inlet_status.outlets.len(), 2);
+    assert!(inlet_status
+        .outlets
+        .iter()
+        .all(|outlet| outlet.status == ConnectionStatus::Up));
+
+    // the connections are sent to both outlets
+    for _ in 0..4 {
+        let mut socket = TcpStream::connect(&inlet_status.bind_addr).await.unwrap();
+        socket.write_all(b"hello").await.unwrap();
+
+        let mut buf = [0u8; 5];
+        socket.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"hello");
+    }
+    // the connections are sent to each outlet in turn
+    for echo_server_handle in &echo_server_handles {
+        assert_eq!(echo_server_handle.connections(), 2);
+    }
+
+    let inlet_status = node_manager_handle
+        .node_manager
+        .delete_inlet("alias")
+        .await?;
+    assert_eq!(inlet_status.status, ConnectionStatus::Down);
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn denied_http_request_is_answered_with_forbidden(
+    context: &mut Context,
+) -> ockam::Result<()> {
+    TestNode::clean().await?;
+    let echo_server_handle = start_tcp_echo_server().await;
+    let node_manager_handle = start_manager_for_tests(context, None, None).await?;
+
+    let http = HttpOutletConfig {
+        access_rules: vec![HttpAccessRule::from_str(
+            "GET /public/* (= subject.has_credential true)",
+        )
+        .unwrap()],
+        ..Default::default()
+    };
+    node_manager_handle
+        .node_manager
+        .create_http_outlet(
+            context,
+            echo_server_handle.chosen_addr.clone(),
+            false,
+            Some(Address::from_string("outlet")),
+            true,
+            Some(PolicyExpression::from_str(
+                "(= subject.has_credential true)",
+            )?),
+            false,
+            http,
+            None,
+        )
+        .await?;
+
+    let inlet_status = node_manager_handle
+        .node_manager
+        .create_inlet(
+            context,
+            HostnamePort::new("127.0.0.1", 0),
+            route![],
+            route![],
+            MultiAddr::from_str("/secure/api/service/outlet")?,
+            "alias".to_string(),
+            None,
+            None,
+            None,
+            true,
+            None,
+            false,
+            false,
+            false,
+            None,
+        )
+        .await?;
+
+    // an allowed request reaches the echo server
+    let request = b"GET /public/index.html HTTP/1.1\r\nHost: localhost\r\n\r\n";
+    let mut socket = TcpStream::connect(&inlet_status.bind_addr).await.unwrap();
+    socket.write_all(request).await.unwrap();
+    let mut buf = vec![0u8; request.len()];
+    socket.read_exact(&mut buf).await.unwrap();
+    assert_eq!(buf, request);
+
+    // a denied request is answered with a 403 response, then the connection is closed
+    let mut socket = TcpStream::connect(&inlet_status.bind_addr).await.unwrap();
+    socket
+        .write_all(b"GET /admin HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .await
+        .unwrap();
+    let mut response = String::new();
+    timeout(Duration::from_secs(5), socket.read_to_string(&mut response))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(
+        response.starts_with("HTTP/1.1 403 Forbidden\r\n"),
+        "unexpected response: {response}"
+    );
+    assert!(response.ends_with("the request GET /admin is not allowed\n"));
+
+    Ok(())
+}
+
 #[test]
 fn portal_node_goes_down_reconnect() {
     // in this test we manually create three nodes with a shared runtime, then:
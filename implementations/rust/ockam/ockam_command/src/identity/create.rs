@@ -32,7 +32,8 @@ pub struct CreateCommand {
     #[arg(long, value_name = "VAULT_NAME")]
     pub vault: Option<String>,
 
-    /// Key ID to use for the identity creation
+    /// Key ID to use for the identity creation, with a KMS or a PKCS#11 vault.
+    /// For a PKCS#11 vault, this is the hex-encoded CKA_ID of a key created with the vault label
     #[arg(short, long)]
     pub key_id: Option<String>,
 
@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+use ockam_node::Context;
+
+use crate::identity::get_passphrase;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/export/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/export/after_long_help.txt");
+
+/// Export an identity, optionally with its secrets
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ExportCommand {
+    /// Name of the identity to export. The default identity is used if no name is given
+    name: Option<String>,
+
+    /// Export the identity secrets, purpose keys and cached credentials,
+    /// encrypted with a passphrase
+    #[arg(long)]
+    with_secrets: bool,
+
+    /// Write the exported identity to a file instead of the standard output
+    #[arg(long, short, value_name = "FILE")]
+    file: Option<PathBuf>,
+}
+
+#[async_trait]
+impl Command for ExportCommand {
+    const NAME: &'static str = "identity export";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let name = opts.state.get_identity_name_or_default(&self.name).await?;
+        let exported = if self.with_secrets {
+            let passphrase = get_passphrase(&opts, true)?;
+            hex::encode(
+                opts.state
+                    .export_identity_with_secrets(&name, &passphrase)
+                    .await?,
+            )
+        } else {
+            let identity = opts.state.get_identity_by_optional_name(&self.name).await?;
+            identity.change_history().export_as_string()?
+        };
+
+        match &self.file {
+            Some(path) => {
+                std::fs::write(path, &exported)?;
+                opts.terminal
+                    .stdout()
+                    .plain(fmt_ok!(
+                        "The identity {} was exported to {}",
+                        color_primary(&name),
+                        color_primary(path.to_string_lossy())
+                    ))
+                    .machine(path.to_string_lossy())
+                    .json(serde_json::json!({
+                        "name": &name,
+                        "path": path.to_string_lossy(),
+                        "with_secrets": self.with_secrets
+                    }))
+                    .write_line()?;
+            }
+            None => {
+                opts.terminal
+                    .stdout()
+                    .plain(&exported)
+                    .machine(&exported)
+                    .json(serde_json::json!({
+                        "name": &name,
+                        "encoded": &exported,
+                        "with_secrets": self.with_secrets
+                    }))
+                    .write_line()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+
+    use super::*;
+
+    #[test]
+    fn command_can_be_parsed_from_name() {
+        let cmd = parse_cmd_from_args(
+            ExportCommand::NAME,
+            &[
+                "i".to_string(),
+                "--with-secrets".to_string(),
+                "--file".to_string(),
+                "i.bundle".to_string(),
+            ],
+        );
+        assert!(cmd.is_ok());
+    }
+}
@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use ockam_api::cli_state::random_name;
+use ockam_api::colors::color_primary;
+use ockam_api::terminal::notification::NotificationHandler;
+use ockam_api::{fmt_log, fmt_ok};
+use ockam_node::Context;
+
+use crate::identity::get_passphrase;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/import/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/import/after_long_help.txt");
+
+/// Import an identity exported with its secrets
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ImportCommand {
+    /// Path to a file created with `ockam identity export --with-secrets`
+    #[arg(value_name = "FILE")]
+    file: PathBuf,
+
+    /// Name of the imported identity
+    #[arg(long, hide_default_value = true, default_value_t = random_name())]
+    name: String,
+
+    /// The name of the Vault where the identity secrets will be stored
+    #[arg(long, value_name = "VAULT_NAME")]
+    vault: Option<String>,
+}
+
+#[async_trait]
+impl Command for ImportCommand {
+    const NAME: &'static str = "identity import";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let _notification_handler = NotificationHandler::start(&opts.state, opts.terminal.clone());
+        let bundle = hex::decode(std::fs::read_to_string(&self.file)?.trim())?;
+        let passphrase = get_passphrase(&opts, false)?;
+        let identity = opts
+            .state
+            .import_identity_with_secrets(&bundle, &passphrase, &self.name, &self.vault)
+            .await?;
+        let identifier = identity.identifier().to_string();
+
+        opts.terminal
+            .stdout()
+            .plain(
+                fmt_ok!(
+                    "The identity {} was imported with its secrets\n",
+                    color_primary(identity.name())
+                ) + &fmt_log!(
+                    "{} has Identifier {}",
+                    color_primary(identity.name()),
+                    color_primary(&identifier)
+                ),
+            )
+            .machine(&identifier)
+            .json(serde_json::json!({
+                "name": identity.name(),
+                "identifier": &identifier
+            }))
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+
+    use super::*;
+
+    #[test]
+    fn command_can_be_parsed_from_name() {
+        let cmd = parse_cmd_from_args(
+            ImportCommand::NAME,
+            &[
+                "i.bundle".to_string(),
+                "--name".to_string(),
+                "i".to_string(),
+            ],
+        );
+        assert!(cmd.is_ok());
+    }
+}
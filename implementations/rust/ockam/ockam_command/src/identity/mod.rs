@@ -1,8 +1,13 @@
 use clap::{Args, Subcommand};
+use miette::{miette, IntoDiagnostic};
+use ockam_core::env::get_env;
 
 pub use create::CreateCommand;
 pub(crate) use delete::DeleteCommand;
+pub(crate) use export::ExportCommand;
+pub(crate) use import::ImportCommand;
 pub(crate) use list::ListCommand;
+pub(crate) use rotate::RotateCommand;
 pub(crate) use show::ShowCommand;
 
 use crate::identity::default::DefaultCommand;
@@ -11,11 +16,18 @@ use crate::{docs, Command, CommandGlobalOpts};
 mod create;
 mod default;
 mod delete;
+mod export;
+mod import;
 mod list;
+mod rotate;
 mod show;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
 
+/// Name of the environment variable containing the passphrase used to encrypt or decrypt
+/// an identity exported with its secrets
+pub const OCKAM_IDENTITY_PASSPHRASE: &str = "OCKAM_IDENTITY_PASSPHRASE";
+
 /// Manage Identities
 #[derive(Clone, Debug, Args)]
 #[command(
@@ -35,6 +47,9 @@ pub enum IdentitySubcommand {
     List(ListCommand),
     Default(DefaultCommand),
     Delete(DeleteCommand),
+    Rotate(RotateCommand),
+    Export(ExportCommand),
+    Import(ImportCommand),
 }
 
 impl IdentityCommand {
@@ -45,6 +60,9 @@ impl IdentityCommand {
             IdentitySubcommand::List(c) => c.run(opts),
             IdentitySubcommand::Delete(c) => c.run(opts),
             IdentitySubcommand::Default(c) => c.run(opts),
+            IdentitySubcommand::Rotate(c) => c.run(opts),
+            IdentitySubcommand::Export(c) => c.run(opts),
+            IdentitySubcommand::Import(c) => c.run(opts),
         }
     }
 
@@ -55,7 +73,27 @@ impl IdentityCommand {
             IdentitySubcommand::List(c) => c.name(),
             IdentitySubcommand::Delete(c) => c.name(),
             IdentitySubcommand::Default(c) => c.name(),
+            IdentitySubcommand::Rotate(c) => c.name(),
+            IdentitySubcommand::Export(c) => c.name(),
+            IdentitySubcommand::Import(c) => c.name(),
         }
         .to_string()
     }
 }
+
+/// Return the passphrase of an identity bundle, read from the environment or asked to the user.
+/// When `confirm` is true, the user must type the passphrase twice
+fn get_passphrase(opts: &CommandGlobalOpts, confirm: bool) -> miette::Result<String> {
+    if let Some(passphrase) = get_env::<String>(OCKAM_IDENTITY_PASSPHRASE).into_diagnostic()? {
+        return Ok(passphrase);
+    }
+    match opts
+        .terminal
+        .prompt_passphrase("Enter the passphrase of the identity bundle", confirm)?
+    {
+        Some(passphrase) if !passphrase.is_empty() => Ok(passphrase),
+        _ => Err(miette!(
+            "A passphrase is required. Please set the {OCKAM_IDENTITY_PASSPHRASE} environment variable"
+        )),
+    }
+}
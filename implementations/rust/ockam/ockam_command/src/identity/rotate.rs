@@ -0,0 +1,101 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use ockam_api::colors::color_primary;
+use ockam_api::nodes::models::secure_channel::PresentChangeHistoryResponse;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::terminal::notification::NotificationHandler;
+use ockam_api::{fmt_log, fmt_ok, fmt_warn};
+use ockam_core::api::Request;
+use ockam_node::Context;
+
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/rotate/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/rotate/after_long_help.txt");
+
+/// Rotate the key of an identity
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct RotateCommand {
+    /// Name of the identity to rotate. The default identity is used if no name is given
+    name: Option<String>,
+}
+
+#[async_trait]
+impl Command for RotateCommand {
+    const NAME: &'static str = "identity rotate";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let _notification_handler = NotificationHandler::start(&opts.state, opts.terminal.clone());
+        let name = opts.state.get_identity_name_or_default(&self.name).await?;
+        let identity = opts.state.rotate_identity(&name).await?;
+        let change_hash = identity.latest_change_hash()?.to_string();
+
+        let mut plain = fmt_ok!(
+            "The key of the identity {} was rotated\n",
+            color_primary(&name)
+        ) + &fmt_log!(
+            "Its latest change hash is {}\n",
+            color_primary(&change_hash)
+        );
+
+        // The running nodes using that identity present the new change history to their peers
+        for node in opts.state.get_nodes_by_identity_name(&name).await? {
+            if !node.is_running() {
+                continue;
+            }
+            let node_client =
+                BackgroundNodeClient::create_to_node(ctx, &opts.state, &node.name()).await?;
+            match node_client
+                .ask::<(), PresentChangeHistoryResponse>(
+                    ctx,
+                    Request::post("/node/identity/change_history"),
+                )
+                .await
+            {
+                Ok(response) => {
+                    plain += &fmt_log!(
+                        "The node {} presented the new change history on {} secure channel(s)\n",
+                        color_primary(node.name()),
+                        response.notified_secure_channels
+                    )
+                }
+                Err(e) => {
+                    plain += &fmt_warn!(
+                        "The node {} could not present the new change history: {e}\n",
+                        color_primary(node.name())
+                    )
+                }
+            }
+        }
+
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .machine(&change_hash)
+            .json(serde_json::json!({
+                "identifier": identity.identifier().to_string(),
+                "change_hash": &change_hash
+            }))
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+
+    use super::*;
+
+    #[test]
+    fn command_can_be_parsed_from_name() {
+        let cmd = parse_cmd_from_args(RotateCommand::NAME, &["i".to_string()]);
+        assert!(cmd.is_ok());
+    }
+}
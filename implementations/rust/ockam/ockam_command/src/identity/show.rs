@@ -202,6 +202,9 @@ impl Display for ShowIdentity {
                 "    revoke_all_purpose_keys: {}",
                 change.revoke_all_purpose_keys
             )?;
+            if let Some(threshold) = &change.threshold {
+                writeln!(f, "    threshold:               {}", threshold)?;
+            }
         }
         Ok(())
     }
@@ -218,6 +221,8 @@ struct Change {
     pub identifier: String,
     pub primary_public_key: VerifyingPublicKeyDisplay,
     pub revoke_all_purpose_keys: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<String>,
 }
 
 impl From<VerifiedChange> for Change {
@@ -226,6 +231,11 @@ impl From<VerifiedChange> for Change {
             identifier: hex::encode(value.change_hash()),
             primary_public_key: VerifyingPublicKeyDisplay(value.primary_public_key().to_owned()),
             revoke_all_purpose_keys: value.data().revoke_all_purpose_keys,
+            threshold: value
+                .data()
+                .threshold_public_keys
+                .as_ref()
+                .map(|keys| format!("{} of {} keys", keys.threshold, keys.public_keys.len())),
         }
     }
 }
@@ -97,7 +97,7 @@ impl Command for InfluxDBCreateCommand {
                         if let Some(pb) = pb.as_ref() {
                             pb.set_message(format!(
                                 "Waiting for TCP Inlet {} to be available... Retrying momentarily\n",
-                                color_primary(&self.tcp_inlet.to)
+                                color_primary(self.tcp_inlet.to.join(", "))
                             ));
                         }
                         tokio::time::sleep(self.tcp_inlet.retry_wait).await
@@ -119,20 +119,20 @@ impl Command for InfluxDBCreateCommand {
 
         let plain = if self.tcp_inlet.no_connection_wait {
             created_message + &fmt_log!("It will automatically connect to the TCP Outlet at {} as soon as it is available",
-                color_primary(&self.tcp_inlet.to)
+                color_primary(self.tcp_inlet.to.join(", "))
             )
         } else if inlet_status.status == ConnectionStatus::Up {
             created_message
                 + &fmt_log!(
                     "sending traffic to the TCP Outlet at {}",
-                    color_primary(&self.tcp_inlet.to)
+                    color_primary(self.tcp_inlet.to.join(", "))
                 )
         } else {
             fmt_warn!(
                 "A InfluxDB Inlet was created in the Node {} bound to {} but failed to connect to the TCP Outlet at {}\n",
                 color_primary(&node_name),
                  color_primary(self.tcp_inlet.from.to_string()),
-                color_primary(&self.tcp_inlet.to)
+                color_primary(self.tcp_inlet.to.join(", "))
             ) + &fmt_info!("It will retry to connect automatically")
         };
 
@@ -150,6 +150,9 @@ impl Command for InfluxDBCreateCommand {
 impl InfluxDBCreateCommand {
     async fn parse_args(mut self, opts: &CommandGlobalOpts) -> miette::Result<Self> {
         self.tcp_inlet = self.tcp_inlet.parse_args(opts).await?;
+        if self.tcp_inlet.to.len() > 1 {
+            Err(miette!("An InfluxDB Inlet can only have one --to route"))?
+        }
         if self
             .lease_manager_route
             .as_ref()
@@ -9,7 +9,7 @@ use ockam::transport::HostnamePort;
 use ockam_abac::PolicyExpression;
 use ockam_api::colors::{color_primary, color_warn};
 use ockam_api::config::lookup::InternetAddress;
-use ockam_api::kafka::{ConsumerPublishing, ConsumerResolution};
+use ockam_api::kafka::{ConsumerPublishing, ConsumerResolution, RecordFormat, TopicEncryptionRule};
 use ockam_api::nodes::models::services::{StartKafkaInletRequest, StartServiceRequest};
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_api::output::Output;
@@ -102,6 +102,55 @@ pub struct CreateCommand {
     )]
     pub encrypted_fields: Vec<String>,
 
+    /// The fields to encrypt in the records of a given topic, as `<TOPIC>:<PATH>[,<PATH>...]`.
+    /// A path selects nested fields with `.`, and `*` matches all the fields of an object
+    /// or all the elements of an array, for example `orders:customer.address.*,total`.
+    /// These rules take precedence over `--encrypted-field` for their topic.
+    #[arg(
+        long = "encryption-rule",
+        alias = "encryption-rules",
+        value_name = "TOPIC:PATHS"
+    )]
+    pub encryption_rules: Vec<TopicEncryptionRule>,
+
+    /// The Avro schema of the records of a topic which has an encryption rule,
+    /// as `<TOPIC>:<SCHEMA_FILE>`. Records are expected to be Avro datums without any
+    /// schema registry header. Records are sent to the broker as Avro datums, so the
+    /// encrypted fields must be strings.
+    #[arg(
+        long = "avro-schema",
+        alias = "avro-schemas",
+        value_name = "TOPIC:FILE"
+    )]
+    pub avro_schemas: Vec<String>,
+
+    /// The Protobuf message type of the records of a topic which has an encryption rule,
+    /// as `<TOPIC>:<DESCRIPTOR_SET_FILE>:<MESSAGE_NAME>`. The descriptor set file can be
+    /// generated with `protoc --include_imports --descriptor_set_out`. Records are sent to
+    /// the broker as Protobuf messages, so the encrypted fields must be strings.
+    #[arg(
+        long = "protobuf-descriptor",
+        alias = "protobuf-descriptors",
+        value_name = "TOPIC:FILE:MESSAGE"
+    )]
+    pub protobuf_descriptors: Vec<String>,
+
+    /// Encrypt the values of the records headers. The headers names are not encrypted.
+    #[arg(long, default_value_t = false)]
+    pub encrypt_headers: bool,
+
+    /// Encrypt the records keys. The same key is always encrypted to the same value,
+    /// so that key based partitioning and log compaction keep working. The keys are encrypted
+    /// with a secret which is persisted for this node, unless `--record-keys-secret` is set.
+    #[arg(long, default_value_t = false)]
+    pub encrypt_keys: bool,
+
+    /// A hex encoded secret of at least 32 bytes, used to encrypt the records keys. It must be
+    /// the same for all the inlets producing records to a topic, so that they encrypt a given
+    /// key to the same value.
+    #[arg(long, value_name = "HEX", requires = "encrypt_keys")]
+    pub record_keys_secret: Option<String>,
+
     /// Policy expression that will be used for access control to the Kafka Inlet.
     /// If you don't provide it, the policy set for the "tcp-inlet" resource type will be used.
     ///
@@ -147,6 +196,7 @@ impl Command for CreateCommand {
             .into());
         }
 
+        let encryption_rules = self.encryption_rules()?;
         let at_node = self.node_opts.at_node.clone();
         let addr = self.addr.clone();
         let to = process_nodes_multiaddr(&self.to, &opts.state).await?;
@@ -188,6 +238,10 @@ impl Command for CreateCommand {
                 to.clone(),
                 !self.disable_content_encryption,
                 self.encrypted_fields,
+                encryption_rules,
+                self.encrypt_headers,
+                self.encrypt_keys,
+                self.record_keys_secret,
                 consumer_resolution,
                 consumer_publishing,
                 self.inlet_policy_expression,
@@ -219,6 +273,50 @@ impl Command for CreateCommand {
     }
 }
 
+impl CreateCommand {
+    /// Return the encryption rules, with the record formats read from the schema files
+    fn encryption_rules(&self) -> miette::Result<Vec<TopicEncryptionRule>> {
+        let mut formats: Vec<(&str, RecordFormat)> = vec![];
+        for value in &self.avro_schemas {
+            let (topic, path) = value.split_once(':').ok_or_else(|| {
+                miette!("Invalid Avro schema '{value}', expected <TOPIC>:<SCHEMA_FILE>")
+            })?;
+            let schema = std::fs::read_to_string(path)
+                .map_err(|e| miette!("Failed to read the Avro schema {path}: {e}"))?;
+            formats.push((topic, RecordFormat::Avro { schema }));
+        }
+        for value in &self.protobuf_descriptors {
+            let (topic, rest) = value.split_once(':').ok_or_else(|| {
+                miette!("Invalid Protobuf descriptor '{value}', expected <TOPIC>:<DESCRIPTOR_SET_FILE>:<MESSAGE_NAME>")
+            })?;
+            let (path, message_name) = rest.rsplit_once(':').ok_or_else(|| {
+                miette!("Invalid Protobuf descriptor '{value}', expected <TOPIC>:<DESCRIPTOR_SET_FILE>:<MESSAGE_NAME>")
+            })?;
+            let descriptor_set = std::fs::read(path)
+                .map_err(|e| miette!("Failed to read the Protobuf descriptor set {path}: {e}"))?;
+            formats.push((
+                topic,
+                RecordFormat::Protobuf {
+                    descriptor_set,
+                    message_name: message_name.to_string(),
+                },
+            ));
+        }
+
+        let mut rules = self.encryption_rules.clone();
+        for (topic, format) in formats {
+            let rule = rules
+                .iter_mut()
+                .find(|r| r.topic() == topic)
+                .ok_or_else(|| {
+                    miette!("The topic {topic} has a record format but no encryption rule")
+                })?;
+            *rule = rule.clone().with_format(format);
+        }
+        Ok(rules)
+    }
+}
+
 #[derive(Serialize)]
 struct KafkaInletOutput {
     node_name: String,
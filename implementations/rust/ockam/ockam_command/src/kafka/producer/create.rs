@@ -49,6 +49,12 @@ impl CreateCommand {
             avoid_publishing: false,
             disable_content_encryption: false,
             encrypted_fields: vec![],
+            encryption_rules: vec![],
+            avro_schemas: vec![],
+            protobuf_descriptors: vec![],
+            encrypt_headers: false,
+            encrypt_keys: false,
+            record_keys_secret: None,
             inlet_policy_expression: None,
             consumer_policy_expression: None,
             producer_policy_expression: None,
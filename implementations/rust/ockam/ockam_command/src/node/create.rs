@@ -1,4 +1,5 @@
 use std::fmt::Write;
+use std::time::Duration;
 use std::{path::PathBuf, str::FromStr};
 
 use async_trait::async_trait;
@@ -11,6 +12,7 @@ use opentelemetry::KeyValue;
 use regex::Regex;
 use tracing::instrument;
 
+use ockam::RelayLoadBalancing;
 use ockam_api::cli_state::random_name;
 use ockam_api::colors::{color_error, color_primary};
 use ockam_api::terminal::notification::NotificationHandler;
@@ -24,6 +26,7 @@ use crate::service::config::Config;
 use crate::shared_args::TrustOpts;
 use crate::util::embedded_node_that_is_not_stopped;
 use crate::util::foreground_args::ForegroundArgs;
+use crate::util::parsers::duration_parser;
 use crate::util::{async_cmd, local_cmd};
 use crate::value_parsers::is_url;
 use crate::{docs, Command, CommandGlobalOpts, Result};
@@ -73,6 +76,7 @@ pub struct CreateCommand {
 
     /// Enable the HTTP server for the node that will listen to in a random free port.
     /// To specify a port, use `--http-server-port` instead.
+    /// The server exposes the node metrics, in the OpenMetrics format, at `/metrics`.
     #[arg(
         long,
         visible_alias = "enable-http-server",
@@ -109,6 +113,25 @@ pub struct CreateCommand {
     #[arg(long = "identity", value_name = "IDENTITY_NAME")]
     pub identity: Option<String>,
 
+    /// Rotate the key of the node identity at this interval, for example `30d`.
+    /// The new change history of the identity is presented on the node secure channels
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    pub identity_rotation_interval: Option<Duration>,
+
+    /// When the node is stopped, drain its TCP portals first. New connections are refused,
+    /// while the open connections keep running until they are closed, or until this duration
+    /// elapses, for at most 255 seconds
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    pub drain_timeout: Option<Duration>,
+
+    /// Strategy used by the relay service of the node to choose the node receiving a new
+    /// connection, when several nodes registered a relay with the same name:
+    /// `round-robin` or `least-connections`.
+    /// With `least-connections` a connection stays open until the secure channel or TCP
+    /// connection which carried it to the relay is closed
+    #[arg(long, value_name = "STRATEGY")]
+    pub relay_load_balancing: Option<RelayLoadBalancing>,
+
     #[command(flatten)]
     pub trust_opts: TrustOpts,
 
@@ -134,6 +157,9 @@ impl Default for CreateCommand {
             udp: false,
             launch_configuration: None,
             identity: None,
+            identity_rotation_interval: None,
+            drain_timeout: None,
+            relay_load_balancing: None,
             trust_opts: node_manager_defaults.trust_opts,
             opentelemetry_context: None,
             foreground_args: ForegroundArgs {
@@ -317,7 +343,9 @@ pub fn parse_launch_config(config_or_path: &str) -> Result<Config> {
 
 #[cfg(test)]
 mod tests {
+    use crate::node::NodeSubcommand;
     use crate::run::parser::resource::utils::parse_cmd_from_args;
+    use crate::OckamSubcommand;
 
     use super::*;
 
@@ -327,6 +355,61 @@ mod tests {
         assert!(cmd.is_ok());
     }
 
+    #[test]
+    fn identity_rotation_interval_is_parsed() {
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &[
+                "n".to_string(),
+                "--identity-rotation-interval".to_string(),
+                "30d".to_string(),
+            ],
+        )
+        .unwrap();
+        match cmd {
+            OckamSubcommand::Node(cmd) => match cmd.subcommand {
+                NodeSubcommand::Create(cmd) => assert_eq!(
+                    cmd.identity_rotation_interval,
+                    Some(Duration::from_secs(30 * 24 * 3600))
+                ),
+                _ => panic!("expected a node create command"),
+            },
+            _ => panic!("expected a node command"),
+        }
+    }
+
+    #[test]
+    fn relay_load_balancing_is_parsed() {
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &[
+                "n".to_string(),
+                "--relay-load-balancing".to_string(),
+                "least-connections".to_string(),
+            ],
+        )
+        .unwrap();
+        match cmd {
+            OckamSubcommand::Node(cmd) => match cmd.subcommand {
+                NodeSubcommand::Create(cmd) => assert_eq!(
+                    cmd.relay_load_balancing,
+                    Some(RelayLoadBalancing::LeastConnections)
+                ),
+                _ => panic!("expected a node create command"),
+            },
+            _ => panic!("expected a node command"),
+        }
+        assert!(parse_cmd_from_args(
+            CreateCommand::NAME,
+            &[
+                "n".to_string(),
+                "--relay-load-balancing".to_string(),
+                "fewest".to_string(),
+            ],
+        )
+        .is_err());
+    }
+
     #[test]
     fn has_name_arg() {
         // True if it's a node name
@@ -108,7 +108,9 @@ impl CreateCommand {
                 self.launch_configuration.is_none(),
                 http_server_port,
                 true,
-            ),
+            )
+            .with_identity_rotation_interval(self.identity_rotation_interval)
+            .with_relay_load_balancing(self.relay_load_balancing.unwrap_or_default()),
             NodeManagerTransportOptions::new(
                 tcp_listener.flow_control_id().clone(),
                 tcp,
@@ -159,7 +161,13 @@ impl CreateCommand {
         .await?;
 
         // Clean up and exit
-        let _ = ctx.stop().await;
+        let _ = match self.drain_timeout {
+            Some(drain_timeout) => {
+                let seconds = u8::try_from(drain_timeout.as_secs()).unwrap_or(u8::MAX);
+                ctx.stop_with_drain(seconds).await
+            }
+            None => ctx.stop().await,
+        };
         let _ = opts.state.stop_node(&node_name).await;
         if self.foreground_args.child_process {
             opts.shutdown();
@@ -56,6 +56,9 @@ pub async fn spawn_node(opts: &CommandGlobalOpts, cmd: CreateCommand) -> miette:
         skip_is_running_check,
         name,
         identity: identity_name,
+        identity_rotation_interval,
+        drain_timeout,
+        relay_load_balancing,
         tcp_listener_address: address,
         http_server,
         http_server_port,
@@ -103,6 +106,21 @@ pub async fn spawn_node(opts: &CommandGlobalOpts, cmd: CreateCommand) -> miette:
         args.push(identity_name);
     }
 
+    if let Some(identity_rotation_interval) = identity_rotation_interval {
+        args.push("--identity-rotation-interval".to_string());
+        args.push(format!("{}s", identity_rotation_interval.as_secs()));
+    }
+
+    if let Some(drain_timeout) = drain_timeout {
+        args.push("--drain-timeout".to_string());
+        args.push(format!("{}s", drain_timeout.as_secs()));
+    }
+
+    if let Some(relay_load_balancing) = relay_load_balancing {
+        args.push("--relay-load-balancing".to_string());
+        args.push(relay_load_balancing.to_string());
+    }
+
     if let Some(config) = launch_configuration {
         args.push("--launch-config".to_string());
         args.push(serde_json::to_string(&config).unwrap());
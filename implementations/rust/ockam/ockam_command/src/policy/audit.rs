@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use clap::Args;
+use miette::IntoDiagnostic;
+
+use ockam::identity::Identifier;
+use ockam::Context;
+use ockam_abac::{PolicyDecisionsQuery, ResourceName};
+use ockam_api::nodes::{BackgroundNodeClient, Policies};
+use ockam_core::compat::time::now;
+
+use crate::docs;
+use crate::util::parsers::duration_parser;
+use crate::{Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/audit/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/audit/after_long_help.txt");
+
+/// Show the decisions taken by the policies of a node
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct AuditCommand {
+    #[arg(long, display_order = 900, id = "NODE_NAME")]
+    pub at: Option<String>,
+
+    /// Only show the decisions taken for this resource name
+    #[arg(long)]
+    pub resource: Option<ResourceName>,
+
+    /// Only show the decisions taken for this subject identifier
+    #[arg(long)]
+    pub subject: Option<Identifier>,
+
+    /// Only show the decisions which granted access
+    #[arg(long, conflicts_with = "denied")]
+    pub allowed: bool,
+
+    /// Only show the decisions which denied access
+    #[arg(long)]
+    pub denied: bool,
+
+    /// Only show the decisions taken during this period of time, for example: 30m, 2h, 1d
+    #[arg(long, value_parser = duration_parser)]
+    pub since: Option<Duration>,
+
+    /// Maximum number of decisions to show, starting from the most recent ones
+    #[arg(long, default_value_t = 50)]
+    pub limit: u32,
+}
+
+#[async_trait]
+impl Command for AuditCommand {
+    const NAME: &'static str = "policy audit";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
+        let decisions = node.get_policy_decisions(ctx, &self.query()?).await?;
+
+        let plain = opts.terminal.build_list(
+            &decisions,
+            &format!("No policy decisions found on node {}", node.node_name()),
+        )?;
+        let json = serde_json::to_string(&decisions).into_diagnostic()?;
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(json)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+impl AuditCommand {
+    fn query(&self) -> miette::Result<PolicyDecisionsQuery> {
+        let allowed = match (self.allowed, self.denied) {
+            (true, _) => Some(true),
+            (_, true) => Some(false),
+            _ => None,
+        };
+        let since = match self.since {
+            Some(since) => Some(now().into_diagnostic()?.saturating_sub(since.as_secs())),
+            None => None,
+        };
+        Ok(PolicyDecisionsQuery {
+            resource_name: self.resource.as_ref().map(|r| r.to_string()),
+            subject: self.subject.as_ref().map(|s| s.to_string()),
+            allowed,
+            since,
+            limit: Some(self.limit),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+
+    use super::*;
+
+    #[test]
+    fn command_can_be_parsed_from_name() {
+        let cmd = parse_cmd_from_args(
+            AuditCommand::NAME,
+            &[
+                "--denied".to_string(),
+                "--since".to_string(),
+                "1h".to_string(),
+            ],
+        );
+        assert!(cmd.is_ok());
+
+        let cmd = parse_cmd_from_args(
+            AuditCommand::NAME,
+            &["--denied".to_string(), "--allowed".to_string()],
+        );
+        assert!(cmd.is_err());
+    }
+}
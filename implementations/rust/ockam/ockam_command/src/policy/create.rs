@@ -16,7 +16,7 @@ use crate::docs;
 use crate::node::util::initialize_default_node;
 use crate::{Command, CommandGlobalOpts};
 
-use super::resource_type_parser;
+use super::{policy_expression_parser, resource_type_parser};
 
 const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
@@ -40,7 +40,12 @@ pub struct CreateCommand {
     #[arg(long)]
     pub resource: Option<ResourceName>,
 
-    #[arg(long, visible_alias = "expression", id = "POLICY_EXPRESSION")]
+    #[arg(
+        long,
+        visible_alias = "expression",
+        id = "POLICY_EXPRESSION",
+        value_parser = policy_expression_parser
+    )]
     pub allow: PolicyExpression,
 }
 
@@ -98,4 +103,20 @@ mod tests {
         );
         assert!(cmd.is_ok());
     }
+
+    #[test]
+    fn invalid_expressions_are_rejected() {
+        for expression in [
+            "(= subject.a 1 \"b\")",
+            "(not subject.a subject.b)",
+            "(eq subject.a \"b\")",
+            "(= role \"admin\")",
+        ] {
+            let cmd = parse_cmd_from_args(
+                CreateCommand::NAME,
+                &["--expression".to_string(), expression.to_string()],
+            );
+            assert!(cmd.is_err(), "{expression}");
+        }
+    }
 }
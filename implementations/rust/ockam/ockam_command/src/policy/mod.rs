@@ -3,18 +3,22 @@ use std::str::FromStr;
 use clap::{Args, Subcommand};
 use miette::miette;
 
-use ockam_abac::ResourceType;
+use ockam_abac::{check, render_check_error, PolicyExpression, ResourceType};
 
+use crate::policy::audit::AuditCommand;
 pub use crate::policy::create::CreateCommand;
 use crate::policy::delete::DeleteCommand;
 use crate::policy::list::ListCommand;
 use crate::policy::show::ShowCommand;
+use crate::policy::test::TestCommand;
 use crate::{Command, CommandGlobalOpts};
 
+mod audit;
 mod create;
 mod delete;
 mod list;
 mod show;
+mod test;
 
 #[derive(Clone, Debug, Args)]
 pub struct PolicyCommand {
@@ -29,6 +33,8 @@ pub enum PolicySubcommand {
     Show(ShowCommand),
     Delete(DeleteCommand),
     List(ListCommand),
+    Audit(AuditCommand),
+    Test(TestCommand),
 }
 
 impl PolicySubcommand {
@@ -38,6 +44,8 @@ impl PolicySubcommand {
             PolicySubcommand::Show(c) => c.name(),
             PolicySubcommand::Delete(c) => c.name(),
             PolicySubcommand::List(c) => c.name(),
+            PolicySubcommand::Audit(c) => c.name(),
+            PolicySubcommand::Test(c) => c.name(),
         }
     }
 }
@@ -49,6 +57,8 @@ impl PolicyCommand {
             PolicySubcommand::Show(c) => c.run(opts),
             PolicySubcommand::Delete(c) => c.run(opts),
             PolicySubcommand::List(c) => c.run(opts),
+            PolicySubcommand::Audit(c) => c.run(opts),
+            PolicySubcommand::Test(c) => c.run(opts),
         }
     }
 
@@ -63,3 +73,25 @@ pub(crate) fn resource_type_parser(input: &str) -> miette::Result<ResourceType>
         miette!(format!("Valid values are: {valid_values}"))
     })
 }
+
+/// Parse a policy expression and check it before it gets stored on a node.
+///
+/// Full policy expressions are only validated when they are evaluated, so arity errors,
+/// type mismatches or unknown identifiers are reported here with their location.
+pub(crate) fn policy_expression_parser(input: &str) -> miette::Result<PolicyExpression> {
+    let expression = PolicyExpression::from_str(input).map_err(|e| miette!(e.to_string()))?;
+    if let PolicyExpression::FullExpression(_) = expression {
+        let errors = check(input).map_err(|e| miette!(e.to_string()))?;
+        if !errors.is_empty() {
+            let errors: Vec<String> = errors
+                .iter()
+                .map(|e| render_check_error(input, e))
+                .collect();
+            return Err(miette!(format!(
+                "Invalid policy expression:\n{}",
+                errors.join("\n")
+            )));
+        }
+    }
+    Ok(expression)
+}
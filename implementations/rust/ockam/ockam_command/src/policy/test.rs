@@ -0,0 +1,169 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::{miette, IntoDiagnostic};
+
+use ockam::identity::Identifier;
+use ockam::Context;
+use ockam_abac::{Action, PolicyExpression, ResourceName, ResourceType};
+use ockam_api::colors::{color_error, color_primary};
+use ockam_api::nodes::models::policies::{ResourceTypeOrName, TestPolicyRequest};
+use ockam_api::nodes::{BackgroundNodeClient, Policies};
+use ockam_api::{fmt_err, fmt_log, fmt_ok};
+
+use crate::docs;
+use crate::{Command, CommandGlobalOpts};
+
+use super::{policy_expression_parser, resource_type_parser};
+
+const LONG_ABOUT: &str = include_str!("./static/test/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/test/after_long_help.txt");
+
+/// Evaluate the policy of a resource for a given subject
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct TestCommand {
+    #[arg(long, display_order = 900, id = "NODE_NAME")]
+    pub at: Option<String>,
+
+    #[arg(
+        long,
+        conflicts_with = "resource",
+        required_unless_present = "resource",
+        value_parser = resource_type_parser
+    )]
+    pub resource_type: Option<ResourceType>,
+
+    #[arg(long)]
+    pub resource: Option<ResourceName>,
+
+    /// The action performed on the resource
+    #[arg(long, default_value_t = Action::HandleMessage)]
+    pub action: Action,
+
+    /// Identifier of the subject. Its attributes are retrieved from the node
+    #[arg(long, required_unless_present = "attributes")]
+    pub subject: Option<Identifier>,
+
+    /// Attributes of the subject in `key=value` format
+    #[arg(short, long = "attribute", value_name = "ATTRIBUTE")]
+    pub attributes: Vec<String>,
+
+    /// Policy expression to evaluate instead of the policy stored for the resource
+    #[arg(
+        long,
+        visible_alias = "expression",
+        id = "POLICY_EXPRESSION",
+        value_parser = policy_expression_parser
+    )]
+    pub allow: Option<PolicyExpression>,
+}
+
+#[async_trait]
+impl Command for TestCommand {
+    const NAME: &'static str = "policy test";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
+        let result = node.test_policy(ctx, &self.request()?).await?;
+
+        let mut plain = if result.allowed {
+            fmt_ok!("Access is {}", color_primary("allowed"))
+        } else {
+            fmt_err!("Access is {}", color_error("denied"))
+        };
+        writeln!(plain)?;
+        writeln!(plain, "{}", fmt_log!("Policy: {}", result.expression))?;
+        for step in result.steps.iter() {
+            let indentation = "  ".repeat(step.depth as usize);
+            let value = if step.is_error {
+                color_error(&step.result).to_string()
+            } else {
+                color_primary(&step.result).to_string()
+            };
+            writeln!(
+                plain,
+                "{}",
+                fmt_log!("{indentation}{} => {value}", step.expression)
+            )?;
+        }
+
+        let json = serde_json::to_string(&result).into_diagnostic()?;
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(json)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+impl TestCommand {
+    fn request(&self) -> miette::Result<TestPolicyRequest> {
+        let mut resource_type = self.resource_type.clone();
+        // Backwards compatibility, like for `policy create`
+        if let Some(resource) = self.resource.as_ref() {
+            if let Ok(t) = ResourceType::from_str(resource.as_str()) {
+                resource_type = Some(t);
+            }
+        }
+        let resource = ResourceTypeOrName::new(resource_type.as_ref(), self.resource.as_ref())
+            .into_diagnostic()?;
+        Ok(TestPolicyRequest {
+            resource,
+            action: self.action.clone(),
+            subject: self.subject.clone(),
+            attributes: self.attributes()?,
+            expression: self.allow.clone(),
+        })
+    }
+
+    fn attributes(&self) -> miette::Result<Option<BTreeMap<String, String>>> {
+        if self.attributes.is_empty() {
+            return Ok(None);
+        }
+        let mut attributes = BTreeMap::new();
+        for attr in &self.attributes {
+            let mut parts = attr.splitn(2, '=');
+            let key = parts.next().ok_or(miette!("key expected"))?;
+            let value = parts.next().ok_or(miette!("value expected"))?;
+            attributes.insert(key.to_string(), value.to_string());
+        }
+        Ok(Some(attributes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+
+    use super::*;
+
+    #[test]
+    fn command_can_be_parsed_from_name() {
+        let cmd = parse_cmd_from_args(
+            TestCommand::NAME,
+            &[
+                "--resource".to_string(),
+                "my-outlet".to_string(),
+                "--attribute".to_string(),
+                "component=db".to_string(),
+            ],
+        );
+        assert!(cmd.is_ok());
+
+        // a subject is required
+        let cmd = parse_cmd_from_args(
+            TestCommand::NAME,
+            &["--resource".to_string(), "my-outlet".to_string()],
+        );
+        assert!(cmd.is_err());
+    }
+}
@@ -52,6 +52,7 @@ impl KafkaInlet {
 mod tests {
     use super::*;
     use ockam::transport::HostnamePort;
+    use ockam_api::kafka::TopicEncryptionRule;
     use ockam_core::env::FromString;
     use ockam_multiaddr::MultiAddr;
 
@@ -67,6 +68,10 @@ mod tests {
               encrypted-fields:
                 - one
                 - two
+              encryption-rules:
+                - orders:customer.address.*,total
+              avro-schemas:
+                - orders:orders.avsc
         "#;
         let parsed: KafkaInlet = serde_yaml::from_str(unnamed).unwrap();
         let default_node_name = "n1".to_string();
@@ -94,6 +99,14 @@ mod tests {
             cmds[0].encrypted_fields,
             vec!["one".to_string(), "two".to_string()]
         );
+        assert_eq!(
+            cmds[0].encryption_rules,
+            vec![TopicEncryptionRule::new(
+                "orders",
+                vec!["customer.address.*".to_string(), "total".to_string()]
+            )]
+        );
+        assert_eq!(cmds[0].avro_schemas, vec!["orders:orders.avsc".to_string()]);
 
         let named = r#"
             kafka-inlet:
@@ -57,6 +57,8 @@ use crate::tcp::connection::TcpConnectionCommand;
 use crate::tcp::inlet::TcpInletCommand;
 use crate::tcp::listener::TcpListenerCommand;
 use crate::tcp::outlet::TcpOutletCommand;
+use crate::udp::inlet::UdpInletCommand;
+use crate::udp::outlet::UdpOutletCommand;
 use crate::util::async_cmd;
 use crate::vault::VaultCommand;
 use crate::worker::WorkerCommand;
@@ -88,6 +90,8 @@ pub enum OckamSubcommand {
     TcpConnection(TcpConnectionCommand),
     TcpOutlet(TcpOutletCommand),
     TcpInlet(TcpInletCommand),
+    UdpOutlet(UdpOutletCommand),
+    UdpInlet(UdpInletCommand),
 
     #[command(name = "influxdb-inlet")]
     InfluxDBInlet(InfluxDBInletCommand),
@@ -152,6 +156,8 @@ impl OckamSubcommand {
             OckamSubcommand::TcpConnection(c) => c.run(opts),
             OckamSubcommand::TcpOutlet(c) => c.run(opts),
             OckamSubcommand::TcpInlet(c) => c.run(opts),
+            OckamSubcommand::UdpOutlet(c) => c.run(opts),
+            OckamSubcommand::UdpInlet(c) => c.run(opts),
 
             OckamSubcommand::InfluxDBInlet(c) => c.run(opts),
             OckamSubcommand::InfluxDBOutlet(c) => c.run(opts),
@@ -302,6 +308,8 @@ impl OckamSubcommand {
             OckamSubcommand::TcpConnection(c) => c.name(),
             OckamSubcommand::TcpOutlet(c) => c.name(),
             OckamSubcommand::TcpInlet(c) => c.name(),
+            OckamSubcommand::UdpOutlet(c) => c.name(),
+            OckamSubcommand::UdpInlet(c) => c.name(),
             OckamSubcommand::InfluxDBInlet(c) => c.name(),
             OckamSubcommand::InfluxDBOutlet(c) => c.name(),
             OckamSubcommand::Rendezvous(c) => c.name(),
@@ -14,6 +14,7 @@ use crate::shared_args::OptionalTimeoutArg;
 use crate::tcp::util::alias_parser;
 use crate::{docs, Command, CommandGlobalOpts, Error};
 use ockam::identity::Identifier;
+use ockam::tcp::InletLoadBalancing;
 use ockam::transport::HostnamePort;
 use ockam::Context;
 use ockam_abac::PolicyExpression;
@@ -24,11 +25,11 @@ use ockam_api::cli_state::journeys::{
 };
 use ockam_api::cli_state::{random_name, CliState};
 use ockam_api::colors::color_primary;
-use ockam_api::nodes::models::portal::InletStatus;
-use ockam_api::nodes::service::tcp_inlets::Inlets;
+use ockam_api::nodes::models::portal::{CreateInlet, InletStatus};
+use ockam_api::nodes::service::tcp_inlets::create_inlet_payload;
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_api::{fmt_info, fmt_log, fmt_ok, fmt_warn, ConnectionStatus};
-use ockam_core::api::{Reply, Status};
+use ockam_core::api::{Reply, Request, Status};
 use ockam_multiaddr::proto;
 use ockam_multiaddr::{MultiAddr, Protocol as _};
 use ockam_node::compat::asynchronous::resolve_peer;
@@ -60,8 +61,20 @@ pub struct CreateCommand {
     /// or just the name of the service as `outlet` or `/service/outlet`.
     /// If you are passing just the service name, consider using `--via` to specify the
     /// relay name (e.g. `ockam tcp-inlet create --to outlet --via myrelay`).
-    #[arg(long, display_order = 900, id = "ROUTE", default_value_t = default_to_addr())]
-    pub to: String,
+    ///
+    /// Repeat this argument to spread the TCP connections between several TCP Outlets
+    /// (e.g. `ockam tcp-inlet create --to outlet1 --to outlet2`). Each TCP Outlet is checked
+    /// regularly, and the TCP Outlets that can't be reached stop receiving new connections
+    /// until they are available again.
+    #[arg(long, display_order = 900, id = "ROUTE", default_values_t = [default_to_addr()])]
+    pub to: Vec<String>,
+
+    /// Strategy used to choose the TCP Outlet of each new TCP connection, when several
+    /// routes are passed with `--to`: `round-robin`, `random` or `sticky-client-ip`.
+    /// With `sticky-client-ip` the connections from the same IP address always go to the same
+    /// TCP Outlet, as long as it is available.
+    #[arg(long, display_order = 900, value_name = "STRATEGY")]
+    pub load_balancing: Option<InletLoadBalancing>,
 
     /// Name of the relay that this TCP Inlet will use to connect to the TCP Outlet.
     ///
@@ -180,23 +193,10 @@ impl Command for CreateCommand {
                 ));
             }
 
+            let payload = cmd.create_inlet_payload(&opts.state).await?;
             loop {
                 let result: Reply<InletStatus> = node
-                    .create_inlet(
-                        ctx,
-                        &cmd.from,
-                        &cmd.to(),
-                        &cmd.alias,
-                        &cmd.authorized,
-                        &cmd.allow,
-                        cmd.connection_wait,
-                        !cmd.no_connection_wait,
-                        &cmd.secure_channel_identifier(&opts.state).await?,
-                        cmd.udp,
-                        cmd.no_tcp_fallback,
-                        cmd.ebpf,
-                        &cmd.tls_certificate_provider,
-                    )
+                    .ask_and_get_reply(ctx, Request::post("/node/inlet").body(payload.clone()))
                     .await?;
 
                 match result {
@@ -218,7 +218,7 @@ impl Command for CreateCommand {
                         if let Some(pb) = pb.as_ref() {
                             pb.set_message(format!(
                                 "Waiting for TCP Inlet {} to be available... Retrying momentarily\n",
-                                color_primary(&cmd.to)
+                                color_primary(cmd.to.join(", "))
                             ));
                         }
                         tokio::time::sleep(cmd.retry_wait).await
@@ -239,20 +239,20 @@ impl Command for CreateCommand {
 
         let plain = if cmd.no_connection_wait {
             created_message + &fmt_log!("It will automatically connect to the TCP Outlet at {} as soon as it is available",
-                color_primary(&cmd.to)
+                color_primary(cmd.to.join(", "))
             )
         } else if inlet_status.status == ConnectionStatus::Up {
             created_message
                 + &fmt_log!(
                     "sending traffic to the TCP Outlet at {}",
-                    color_primary(&cmd.to)
+                    color_primary(cmd.to.join(", "))
                 )
         } else {
             fmt_warn!(
                 "A TCP Inlet was created in the Node {} bound to {} but failed to connect to the TCP Outlet at {}\n",
                 color_primary(&node_name),
                  color_primary(cmd.from.to_string()),
-                color_primary(&cmd.to)
+                color_primary(cmd.to.join(", "))
             ) + &fmt_info!("It will retry to connect automatically")
         };
 
@@ -268,8 +268,39 @@ impl Command for CreateCommand {
 }
 
 impl CreateCommand {
+    /// Route to the first TCP Outlet
     pub fn to(&self) -> MultiAddr {
-        MultiAddr::from_str(&self.to).unwrap()
+        MultiAddr::from_str(&self.to[0]).unwrap()
+    }
+
+    /// Routes to the other TCP Outlets, when the connections are spread between several TCP Outlets
+    pub fn additional_to(&self) -> Vec<MultiAddr> {
+        self.to[1..]
+            .iter()
+            .map(|to| MultiAddr::from_str(to).unwrap())
+            .collect()
+    }
+
+    async fn create_inlet_payload(&self, state: &CliState) -> miette::Result<CreateInlet> {
+        let mut payload = create_inlet_payload(
+            &self.from,
+            &self.to(),
+            &self.alias,
+            &self.authorized,
+            &self.allow,
+            self.connection_wait,
+            !self.no_connection_wait,
+            &self.secure_channel_identifier(state).await?,
+            self.udp,
+            self.no_tcp_fallback,
+            self.ebpf,
+            &self.tls_certificate_provider,
+        );
+        payload.set_additional_outlet_addrs(self.additional_to());
+        if let Some(load_balancing) = self.load_balancing {
+            payload.set_load_balancing(load_balancing);
+        }
+        Ok(payload)
     }
 
     pub async fn secure_channel_identifier(
@@ -292,7 +323,7 @@ impl CreateCommand {
         let mut attributes = HashMap::new();
         attributes.insert(TCP_INLET_AT, node_name.to_string());
         attributes.insert(TCP_INLET_FROM, self.from.to_string());
-        attributes.insert(TCP_INLET_TO, self.to.clone());
+        attributes.insert(TCP_INLET_TO, self.to.join(", "));
         attributes.insert(TCP_INLET_ALIAS, inlet.alias.clone());
         attributes.insert(TCP_INLET_CONNECTION_STATUS, inlet.status.to_string());
         attributes.insert(NODE_NAME, node_name.to_string());
@@ -307,12 +338,25 @@ impl CreateCommand {
             .await
             .into_diagnostic()?;
         port_is_free_guard(&from)?;
-        self.to = Self::parse_arg_to(&opts.state, self.to, self.via.as_ref()).await?;
-        if self.to().matches(0, &[proto::Project::CODE.into()]) && self.authorized.is_some() {
+        let mut to = vec![];
+        for route in self.to {
+            to.push(Self::parse_arg_to(&opts.state, route, self.via.as_ref()).await?);
+        }
+        self.to = to;
+        if self.authorized.is_some()
+            && std::iter::once(self.to())
+                .chain(self.additional_to())
+                .any(|to| to.matches(0, &[proto::Project::CODE.into()]))
+        {
             return Err(miette!(
                 "--authorized can not be used with project addresses"
             ))?;
         }
+        if self.to.len() > 1 && (self.udp || self.ebpf) {
+            return Err(miette!(
+                "--udp and --ebpf can not be used with several --to routes"
+            ))?;
+        }
         self.tls_certificate_provider = if let Some(tls_certificate_provider) =
             &self.tls_certificate_provider
         {
@@ -396,6 +440,8 @@ mod tests {
     use ockam_api::nodes::InMemoryNode;
 
     use crate::run::parser::resource::utils::parse_cmd_from_args;
+    use crate::tcp::inlet::TcpInletSubCommand;
+    use crate::OckamSubcommand;
 
     use super::*;
 
@@ -405,6 +451,41 @@ mod tests {
         assert!(cmd.is_ok());
     }
 
+    #[test]
+    fn several_routes_to_outlets() {
+        let cmd = create_command(&[
+            "--to",
+            "/node/n1/service/outlet",
+            "--to",
+            "/node/n2/service/outlet",
+            "--load-balancing",
+            "sticky-client-ip",
+        ]);
+        assert_eq!(cmd.to().to_string(), "/node/n1/service/outlet");
+        assert_eq!(
+            cmd.additional_to(),
+            vec![MultiAddr::from_str("/node/n2/service/outlet").unwrap()]
+        );
+        assert_eq!(cmd.load_balancing, Some(InletLoadBalancing::StickyClientIp));
+
+        // a single route is used by default
+        let cmd = create_command(&[]);
+        assert_eq!(cmd.to, vec![default_to_addr()]);
+        assert!(cmd.additional_to().is_empty());
+        assert_eq!(cmd.load_balancing, None);
+    }
+
+    fn create_command(args: &[&str]) -> CreateCommand {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        match parse_cmd_from_args(CreateCommand::NAME, &args).unwrap() {
+            OckamSubcommand::TcpInlet(cmd) => match cmd.subcommand {
+                TcpInletSubCommand::Create(cmd) => cmd,
+                _ => panic!("unexpected tcp-inlet subcommand"),
+            },
+            _ => panic!("unexpected command"),
+        }
+    }
+
     #[ockam_macros::test]
     async fn parse_arg_to(ctx: &mut Context) -> ockam_core::Result<()> {
         // Setup
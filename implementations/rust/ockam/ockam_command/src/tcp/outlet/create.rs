@@ -5,9 +5,10 @@ use async_trait::async_trait;
 use clap::builder::FalseyValueParser;
 use clap::Args;
 use colorful::Colorful;
-use miette::IntoDiagnostic;
+use miette::{miette, IntoDiagnostic};
 
 use crate::node::util::initialize_default_node;
+use crate::util::parsers::bandwidth_parser;
 use crate::{docs, Command, CommandGlobalOpts};
 use ockam::transport::HostnamePort;
 use ockam::Address;
@@ -19,9 +20,11 @@ use ockam_api::cli_state::journeys::{
 };
 use ockam_api::colors::color_primary;
 use ockam_api::fmt_ok;
-use ockam_api::nodes::models::portal::OutletStatus;
+use ockam_api::http_interceptor::{HttpAccessRule, HttpHeader, HttpOutletConfig};
+use ockam_api::nodes::models::portal::{OutletStatus, PortalLimitsConfig};
 use ockam_api::nodes::service::tcp_outlets::Outlets;
 use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::postgres::PostgresOutletConfig;
 
 const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
 const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
@@ -72,6 +75,94 @@ pub struct CreateCommand {
     /// If `OCKAM_EBPF` env variable is set to 1, this argument will be `true`.
     #[arg(long, env = "OCKAM_EBPF", value_parser = FalseyValueParser::default(), hide = true)]
     pub ebpf: bool,
+
+    /// Intercept the HTTP/1.1 requests sent to the TCP server.
+    /// This is implied by any of the other `--http-*` arguments
+    #[arg(long, display_order = 905)]
+    pub http: bool,
+
+    /// Header added to each HTTP request, replacing the headers with the same name.
+    /// For example `--http-add-header "X-Forwarded-Proto: https"`
+    #[arg(
+        long = "http-add-header",
+        alias = "http-add-headers",
+        value_name = "NAME:VALUE",
+        display_order = 906
+    )]
+    pub http_add_headers: Vec<HttpHeader>,
+
+    /// Name of a header removed from each HTTP request
+    #[arg(
+        long = "http-strip-header",
+        alias = "http-strip-headers",
+        value_name = "NAME",
+        display_order = 907
+    )]
+    pub http_strip_headers: Vec<String>,
+
+    /// Token sent in an `Authorization: Bearer <TOKEN>` header with each HTTP request,
+    /// replacing the authorization sent by the client
+    #[arg(long, value_name = "TOKEN", display_order = 908)]
+    pub http_bearer_token: Option<String>,
+
+    /// Policy expression authorizing the HTTP requests with a given method and path:
+    /// `<METHOD> <PATH> <EXPRESSION>`. The method `*` matches all methods and a trailing `*`
+    /// in the path matches all the paths with the same prefix.
+    /// The first rule matching a request is evaluated, and requests matching no rule are denied.
+    /// A denied request is answered with a `403 Forbidden` response, then its connection is closed.
+    /// The expression can also check the request with the `http.method` and `http.path` attributes.
+    /// For example `--http-allow 'GET /api/* (= subject.role "reader")'`
+    #[arg(
+        long = "http-allow",
+        alias = "http-allows",
+        value_name = "RULE",
+        display_order = 909
+    )]
+    pub http_access_rules: Vec<HttpAccessRule>,
+
+    /// Intercept the queries sent to a Postgres server.
+    /// This is implied by any of the other `--postgres-*` arguments.
+    /// The clients must disable SSL, for example with `sslmode=disable`
+    #[arg(long, display_order = 910)]
+    pub postgres: bool,
+
+    /// Policy expression authorizing the read-only statements, for example `SELECT` or `SHOW`.
+    /// If not set, the read-only statements are allowed
+    #[arg(long, value_name = "POLICY_EXPRESSION", display_order = 911)]
+    pub postgres_allow_read: Option<PolicyExpression>,
+
+    /// Policy expression authorizing all the other statements, for example `INSERT` or `CREATE`.
+    /// If not set, all the statements are allowed
+    #[arg(long, value_name = "POLICY_EXPRESSION", display_order = 912)]
+    pub postgres_allow_write: Option<PolicyExpression>,
+
+    /// Database user used for all the connections, unless it is mapped from an identity attribute
+    #[arg(long, value_name = "USER", display_order = 913)]
+    pub postgres_user: Option<String>,
+
+    /// Name of the identity attribute containing the database user of each connection.
+    /// Connections from identities without this attribute use `--postgres-user`, or are rejected
+    #[arg(long, value_name = "ATTRIBUTE", display_order = 914)]
+    pub postgres_user_attribute: Option<String>,
+
+    /// Maximum number of concurrent connections to the outlet.
+    /// New connections are closed when the limit is reached
+    #[arg(long, value_name = "CONNECTIONS", display_order = 915)]
+    pub max_connections: Option<u64>,
+
+    /// Maximum number of bytes per second sent through the outlet, in both directions,
+    /// for example `10MB/s`. The connections are slowed down when the limit is reached
+    #[arg(long, value_name = "BYTES_PER_SECOND", value_parser = bandwidth_parser, display_order = 916)]
+    pub rate_limit: Option<u64>,
+
+    /// Maximum number of new connections per second to the outlet.
+    /// New connections are closed when the limit is reached
+    #[arg(long, value_name = "CONNECTIONS_PER_SECOND", display_order = 917)]
+    pub connection_rate_limit: Option<u64>,
+
+    /// Apply the limits to the connections of each identity, instead of all the connections
+    #[arg(long, display_order = 918)]
+    pub limit_per_identity: bool,
 }
 
 #[async_trait]
@@ -79,6 +170,13 @@ impl Command for CreateCommand {
     const NAME: &'static str = "tcp-outlet create";
 
     async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let http = self.http_config();
+        let postgres = self.postgres_config();
+        if http.is_some() && postgres.is_some() {
+            return Err(miette!(
+                "The --http and --postgres arguments can't be used together"
+            ))?;
+        }
         initialize_default_node(ctx, &opts).await?;
 
         let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
@@ -98,6 +196,9 @@ impl Command for CreateCommand {
                 self.from.clone().map(Address::from).as_ref(),
                 self.allow.clone(),
                 self.ebpf,
+                http,
+                postgres,
+                self.limits_config(),
             )
             .await?
         };
@@ -123,6 +224,50 @@ impl Command for CreateCommand {
 }
 
 impl CreateCommand {
+    /// Return the configuration of the HTTP interception, if enabled
+    fn http_config(&self) -> Option<HttpOutletConfig> {
+        let http = self.http
+            || !self.http_add_headers.is_empty()
+            || !self.http_strip_headers.is_empty()
+            || self.http_bearer_token.is_some()
+            || !self.http_access_rules.is_empty();
+        http.then(|| HttpOutletConfig {
+            add_headers: self.http_add_headers.clone(),
+            strip_headers: self.http_strip_headers.clone(),
+            bearer_token: self.http_bearer_token.clone(),
+            access_rules: self.http_access_rules.clone(),
+        })
+    }
+
+    /// Return the configuration of the Postgres interception, if enabled
+    fn postgres_config(&self) -> Option<PostgresOutletConfig> {
+        let postgres = self.postgres
+            || self.postgres_allow_read.is_some()
+            || self.postgres_allow_write.is_some()
+            || self.postgres_user.is_some()
+            || self.postgres_user_attribute.is_some();
+        postgres.then(|| PostgresOutletConfig {
+            read_policy: self.postgres_allow_read.clone(),
+            write_policy: self.postgres_allow_write.clone(),
+            user: self.postgres_user.clone(),
+            user_attribute: self.postgres_user_attribute.clone(),
+        })
+    }
+
+    /// Return the limits of the connections, if any
+    fn limits_config(&self) -> Option<PortalLimitsConfig> {
+        let limits = PortalLimitsConfig {
+            max_connections: self.max_connections,
+            connections_per_second: self.connection_rate_limit,
+            bytes_per_second: self.rate_limit,
+            per_identity: self.limit_per_identity,
+        };
+        (limits.max_connections.is_some()
+            || limits.connections_per_second.is_some()
+            || limits.bytes_per_second.is_some())
+        .then_some(limits)
+    }
+
     pub async fn add_outlet_created_journey_event(
         &self,
         opts: &CommandGlobalOpts,
@@ -147,6 +292,8 @@ impl CreateCommand {
 #[cfg(test)]
 mod tests {
     use crate::run::parser::resource::utils::parse_cmd_from_args;
+    use crate::tcp::outlet::TcpOutletSubCommand;
+    use crate::OckamSubcommand;
 
     use super::*;
 
@@ -158,4 +305,80 @@ mod tests {
         );
         assert!(cmd.is_ok());
     }
+
+    fn parse(args: &[&str]) -> CreateCommand {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        match parse_cmd_from_args(CreateCommand::NAME, &args).unwrap() {
+            OckamSubcommand::TcpOutlet(cmd) => match cmd.subcommand {
+                TcpOutletSubCommand::Create(cmd) => cmd,
+                _ => panic!("expected a tcp-outlet create command"),
+            },
+            _ => panic!("expected a tcp-outlet command"),
+        }
+    }
+
+    #[test]
+    fn http_arguments_enable_the_http_interception() {
+        let cmd = parse(&[
+            "--to",
+            "127.0.0.1:5000",
+            "--http-strip-header",
+            "Cookie",
+            "--http-allow",
+            "GET /api/* (= subject.role \"reader\")",
+        ]);
+        let http = cmd.http_config().unwrap();
+        assert_eq!(http.strip_headers, vec!["Cookie".to_string()]);
+        assert_eq!(http.access_rules[0].path(), "/api/*");
+
+        let cmd = parse(&["--to", "127.0.0.1:5000"]);
+        assert!(cmd.http_config().is_none());
+    }
+
+    #[test]
+    fn postgres_arguments_enable_the_postgres_interception() {
+        let cmd = parse(&[
+            "--to",
+            "127.0.0.1:5432",
+            "--postgres-allow-write",
+            "(= subject.role \"admin\")",
+            "--postgres-user-attribute",
+            "db_user",
+        ]);
+        let postgres = cmd.postgres_config().unwrap();
+        assert!(postgres.read_policy.is_none());
+        assert!(postgres.write_policy.is_some());
+        assert_eq!(postgres.user_attribute, Some("db_user".to_string()));
+        assert!(cmd.http_config().is_none());
+
+        let cmd = parse(&["--to", "127.0.0.1:5432"]);
+        assert!(cmd.postgres_config().is_none());
+    }
+
+    #[test]
+    fn limit_arguments() {
+        let cmd = parse(&[
+            "--to",
+            "127.0.0.1:5000",
+            "--max-connections",
+            "10",
+            "--rate-limit",
+            "2MB/s",
+            "--limit-per-identity",
+        ]);
+        let limits = cmd.limits_config().unwrap();
+        assert_eq!(limits.max_connections, Some(10));
+        assert_eq!(limits.bytes_per_second, Some(2 * 1024 * 1024));
+        assert_eq!(limits.connections_per_second, None);
+        assert!(limits.per_identity);
+
+        let cmd = parse(&["--to", "127.0.0.1:5000", "--limit-per-identity"]);
+        assert!(cmd.limits_config().is_none());
+
+        let args: Vec<String> = ["--to", "127.0.0.1:5000", "--rate-limit", "fast"]
+            .iter()
+            .map(|a| a.to_string())
+            .collect();
+        assert!(parse_cmd_from_args(CreateCommand::NAME, &args).is_err());
+    }
 }
@@ -6,13 +6,15 @@ use console::Term;
 use ockam::Context;
 use ockam_api::colors::color_primary;
 use ockam_api::fmt_ok;
-use ockam_api::nodes::models::portal::OutletStatus;
+use ockam_api::nodes::models::portal::{DeleteOutlet, OutletStatus};
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_api::terminal::{Terminal, TerminalStream};
 use ockam_core::api::Request;
+use std::time::Duration;
 
 use crate::node::NodeOpts;
 use crate::tcp::util::alias_parser;
+use crate::util::parsers::duration_parser;
 use crate::{docs, Command, CommandGlobalOpts};
 
 use crate::terminal::tui::DeleteCommandTui;
@@ -45,6 +47,12 @@ pub struct DeleteCommand {
     /// Delete all the TCP Outlets
     #[arg(long, group = "tcp-outlets")]
     all: bool,
+
+    /// Drain the TCP Outlet before deleting it. New connections are refused, while the open
+    /// connections keep running until they are closed, or until this duration elapses.
+    /// The command returns without waiting for the end of the drain
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    drain_timeout: Option<Duration>,
 }
 
 #[async_trait]
@@ -117,7 +125,8 @@ impl<'a> DeleteCommandTui for DeleteTui<'a> {
         self.node
             .tell(
                 self.ctx,
-                Request::delete(format!("/node/outlet/{item_name}")),
+                Request::delete(format!("/node/outlet/{item_name}"))
+                    .body(DeleteOutlet::new(self.cmd.drain_timeout)),
             )
             .await?;
         self.terminal()
@@ -134,3 +143,34 @@ impl<'a> DeleteCommandTui for DeleteTui<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+    use crate::tcp::outlet::TcpOutletSubCommand;
+    use crate::OckamSubcommand;
+
+    use super::*;
+
+    #[test]
+    fn drain_timeout_is_parsed() {
+        let cmd = parse_cmd_from_args(
+            DeleteCommand::NAME,
+            &[
+                "myoutlet".to_string(),
+                "--drain-timeout".to_string(),
+                "30s".to_string(),
+            ],
+        )
+        .unwrap();
+        match cmd {
+            OckamSubcommand::TcpOutlet(cmd) => match cmd.subcommand {
+                TcpOutletSubCommand::Delete(cmd) => {
+                    assert_eq!(cmd.drain_timeout, Some(Duration::from_secs(30)))
+                }
+                _ => panic!("expected a tcp-outlet delete command"),
+            },
+            _ => panic!("expected a tcp-outlet command"),
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam::identity::Identifier;
+use ockam::transport::HostnamePort;
+use ockam::Context;
+use ockam_abac::PolicyExpression;
+use ockam_api::address::extract_address_value;
+use ockam_api::cli_state::random_name;
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+use ockam_api::nodes::models::portal::CreateUdpInlet;
+use ockam_api::nodes::service::udp_portals::UdpPortals;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_multiaddr::MultiAddr;
+
+use crate::tcp::util::alias_parser;
+use crate::util::parsers::{duration_parser, hostname_parser};
+use crate::util::process_nodes_multiaddr;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
+
+/// Create a UDP Inlet
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct CreateCommand {
+    /// Node on which to start the UDP Inlet. If you don't provide it, the default node will be used
+    #[arg(long, display_order = 900, id = "NODE_NAME", value_parser = extract_address_value)]
+    pub at: Option<String>,
+
+    /// Address on which to accept datagrams, in the format `<ip>:<port>`
+    #[arg(long, display_order = 900, id = "SOCKET_ADDRESS", value_parser = hostname_parser)]
+    pub from: HostnamePort,
+
+    /// Route to the UDP Outlet, for example `/node/n1/service/udp_outlet`
+    #[arg(long, display_order = 900, id = "ROUTE")]
+    pub to: MultiAddr,
+
+    /// Authorized identity for the secure channel connection
+    #[arg(long, name = "AUTHORIZED", display_order = 900)]
+    pub authorized: Option<Identifier>,
+
+    /// Assign a name to this UDP Inlet
+    #[arg(long, display_order = 900, id = "ALIAS", value_parser = alias_parser, default_value_t = random_name(), hide_default_value = true)]
+    pub alias: String,
+
+    /// Policy expression that will be used for access control to the UDP Inlet.
+    /// If you don't provide it, the policy set for the "udp-inlet" resource type will be used.
+    ///
+    /// You can check the fallback policy with `ockam policy show --resource-type udp-inlet`.
+    #[arg(
+        hide = true,
+        long,
+        visible_alias = "expression",
+        display_order = 900,
+        id = "POLICY_EXPRESSION"
+    )]
+    pub allow: Option<PolicyExpression>,
+
+    /// Duration after which a session without any traffic is closed, for example `90s` or `5m`
+    #[arg(long, display_order = 900, id = "IDLE_TIMEOUT", value_parser = duration_parser)]
+    pub idle_timeout: Option<Duration>,
+}
+
+#[async_trait]
+impl Command for CreateCommand {
+    const NAME: &'static str = "udp-inlet create";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
+        let outlet_addr = process_nodes_multiaddr(&self.to, &opts.state).await?;
+        let request = CreateUdpInlet {
+            listen_addr: self.from.clone(),
+            outlet_addr,
+            alias: self.alias.clone(),
+            authorized: self.authorized.clone(),
+            policy_expression: self.allow.clone(),
+            idle_timeout: self.idle_timeout,
+        };
+        let inlet_status = node.create_udp_inlet(ctx, request).await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "Created a new UDP Inlet in the Node {} bound to {}\n",
+                color_primary(node.node_name()),
+                color_primary(&inlet_status.bind_addr)
+            ))
+            .machine(&inlet_status.bind_addr)
+            .json(serde_json::to_string(&inlet_status).into_diagnostic()?)
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+
+    use super::*;
+
+    #[test]
+    fn command_can_be_parsed_from_name() {
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &[
+                "--from".to_string(),
+                "127.0.0.1:5353".to_string(),
+                "--to".to_string(),
+                "/node/n1/service/udp_outlet".to_string(),
+                "--idle-timeout".to_string(),
+                "5m".to_string(),
+            ],
+        );
+        assert!(cmd.is_ok());
+
+        // the outlet route is required
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &["--from".to_string(), "127.0.0.1:5353".to_string()],
+        );
+        assert!(cmd.is_err());
+    }
+}
@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+
+use ockam::Context;
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+use ockam_api::nodes::service::udp_portals::UdpPortals;
+use ockam_api::nodes::BackgroundNodeClient;
+
+use crate::node::NodeOpts;
+use crate::tcp::util::alias_parser;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/delete/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/delete/after_long_help.txt");
+
+/// Delete a UDP Inlet
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct DeleteCommand {
+    /// Alias of the UDP Inlet to delete
+    #[arg(display_order = 900, id = "ALIAS", value_parser = alias_parser)]
+    alias: String,
+
+    #[command(flatten)]
+    node_opts: NodeOpts,
+}
+
+#[async_trait]
+impl Command for DeleteCommand {
+    const NAME: &'static str = "udp-inlet delete";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+        node.delete_udp_inlet(ctx, &self.alias).await?;
+
+        let node_name = node.node_name();
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "UDP Inlet with alias {} on node {} has been deleted",
+                color_primary(&self.alias),
+                color_primary(&node_name)
+            ))
+            .machine(&self.alias)
+            .json(serde_json::json!({ "alias": self.alias, "node": node_name }))
+            .write_line()?;
+        Ok(())
+    }
+}
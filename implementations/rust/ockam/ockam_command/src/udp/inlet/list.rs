@@ -0,0 +1,49 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_info;
+use ockam_api::nodes::service::udp_portals::UdpPortals;
+use ockam_api::nodes::BackgroundNodeClient;
+
+use crate::node::NodeOpts;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/list/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
+
+/// List the UDP Inlets of a node
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ListCommand {
+    #[command(flatten)]
+    node_opts: NodeOpts,
+}
+
+#[async_trait]
+impl Command for ListCommand {
+    const NAME: &'static str = "udp-inlet list";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+        let inlets = node.list_udp_inlets(ctx).await?;
+
+        let empty_message = fmt_info!(
+            "No UDP Inlets found on node {}",
+            color_primary(node.node_name())
+        );
+        let list = opts.terminal.build_list(&inlets, &empty_message)?;
+        opts.terminal
+            .stdout()
+            .plain(list)
+            .json(serde_json::to_string(&inlets).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
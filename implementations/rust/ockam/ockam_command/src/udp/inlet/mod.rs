@@ -0,0 +1,52 @@
+use clap::{Args, Subcommand};
+
+use create::CreateCommand;
+use delete::DeleteCommand;
+use list::ListCommand;
+
+use crate::{docs, Command, CommandGlobalOpts};
+
+pub mod create;
+mod delete;
+mod list;
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/after_long_help.txt");
+
+/// Manage UDP Inlets
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct UdpInletCommand {
+    #[command(subcommand)]
+    pub subcommand: UdpInletSubCommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum UdpInletSubCommand {
+    Create(CreateCommand),
+    Delete(DeleteCommand),
+    List(ListCommand),
+}
+
+impl UdpInletCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            UdpInletSubCommand::Create(c) => c.run(opts),
+            UdpInletSubCommand::Delete(c) => c.run(opts),
+            UdpInletSubCommand::List(c) => c.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            UdpInletSubCommand::Create(c) => c.name(),
+            UdpInletSubCommand::Delete(c) => c.name(),
+            UdpInletSubCommand::List(c) => c.name(),
+        }
+    }
+}
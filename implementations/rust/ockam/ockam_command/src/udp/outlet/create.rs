@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam::transport::HostnamePort;
+use ockam::{Address, Context};
+use ockam_abac::PolicyExpression;
+use ockam_api::address::extract_address_value;
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+use ockam_api::nodes::models::portal::CreateUdpOutlet;
+use ockam_api::nodes::service::udp_portals::UdpPortals;
+use ockam_api::nodes::BackgroundNodeClient;
+
+use crate::util::parsers::{duration_parser, hostname_parser};
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
+
+/// Create a UDP Outlet that runs adjacent to a UDP server
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct CreateCommand {
+    /// UDP address where your UDP server is running: domain:port. Your Outlet will send datagrams to it
+    #[arg(long, display_order = 900, id = "HOSTNAME_PORT", value_parser = hostname_parser)]
+    pub to: HostnamePort,
+
+    /// Address of your UDP Outlet, which is used by UDP Inlets to reach it.
+    /// If you don't provide it, `/service/udp_outlet` will be used
+    #[arg(long, display_order = 901, id = "OUTLET_ADDRESS", value_parser = extract_address_value)]
+    pub from: Option<String>,
+
+    /// Your UDP Outlet will be created on this node. If you don't provide it, the default
+    /// node will be used
+    #[arg(long, display_order = 902, id = "NODE_NAME", value_parser = extract_address_value)]
+    pub at: Option<String>,
+
+    /// Policy expression that will be used for access control to the UDP Outlet.
+    /// If you don't provide it, the policy set for the "udp-outlet" resource type will be used.
+    ///
+    /// You can check the fallback policy with `ockam policy show --resource-type udp-outlet`.
+    #[arg(
+        hide = true,
+        long,
+        visible_alias = "expression",
+        display_order = 903,
+        id = "POLICY_EXPRESSION"
+    )]
+    pub allow: Option<PolicyExpression>,
+
+    /// Duration after which a session without any traffic is closed, for example `90s` or `5m`
+    #[arg(long, display_order = 904, id = "IDLE_TIMEOUT", value_parser = duration_parser)]
+    pub idle_timeout: Option<Duration>,
+
+    /// Don't make the UDP Outlet reachable from the secure channels created by the default
+    /// secure channel listener of the node
+    #[arg(long, display_order = 905, default_value = "false")]
+    pub no_default_secure_channel: bool,
+}
+
+#[async_trait]
+impl Command for CreateCommand {
+    const NAME: &'static str = "udp-outlet create";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
+        let request = CreateUdpOutlet {
+            hostname_port: self.to.clone(),
+            worker_addr: self.from.clone().map(Address::from),
+            policy_expression: self.allow.clone(),
+            idle_timeout: self.idle_timeout,
+            reachable_from_default_secure_channel: !self.no_default_secure_channel,
+        };
+        let outlet_status = node.create_udp_outlet(ctx, request).await?;
+        let worker_route = outlet_status.worker_route().into_diagnostic()?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "Created a new UDP Outlet in the Node {} at {} bound to {}\n",
+                color_primary(node.node_name()),
+                color_primary(worker_route.to_string()),
+                color_primary(self.to.to_string())
+            ))
+            .machine(worker_route)
+            .json(serde_json::to_string(&outlet_status).into_diagnostic()?)
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+
+    use super::*;
+
+    #[test]
+    fn command_can_be_parsed_from_name() {
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &[
+                "--to".to_string(),
+                "127.0.0.1:53".to_string(),
+                "--from".to_string(),
+                "dns".to_string(),
+            ],
+        );
+        assert!(cmd.is_ok());
+    }
+}
@@ -0,0 +1,55 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+
+use ockam::{Address, Context};
+use ockam_api::address::extract_address_value;
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+use ockam_api::nodes::service::udp_portals::UdpPortals;
+use ockam_api::nodes::BackgroundNodeClient;
+
+use crate::node::NodeOpts;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/delete/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/delete/after_long_help.txt");
+
+/// Delete a UDP Outlet
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct DeleteCommand {
+    /// Address of the UDP Outlet to delete
+    #[arg(display_order = 900, id = "OUTLET_ADDRESS", value_parser = extract_address_value)]
+    address: String,
+
+    #[command(flatten)]
+    node_opts: NodeOpts,
+}
+
+#[async_trait]
+impl Command for DeleteCommand {
+    const NAME: &'static str = "udp-outlet delete";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+        node.delete_udp_outlet(ctx, &Address::from(self.address.as_str()))
+            .await?;
+
+        let node_name = node.node_name();
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "UDP Outlet with address {} on node {} has been deleted",
+                color_primary(&self.address),
+                color_primary(&node_name)
+            ))
+            .machine(&self.address)
+            .json(serde_json::json!({ "address": self.address, "node": node_name }))
+            .write_line()?;
+        Ok(())
+    }
+}
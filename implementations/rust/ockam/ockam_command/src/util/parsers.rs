@@ -46,3 +46,28 @@ pub(crate) fn project_name_parser(s: &str) -> Result<String> {
 pub(crate) fn duration_parser(arg: &str) -> std::result::Result<Duration, clap::Error> {
     parse_duration(arg).map_err(|_| Error::raw(ErrorKind::InvalidValue, "Invalid duration."))
 }
+
+/// Parse a number of bytes per second, for example `500000`, `512KB`, or `10MB/s`.
+/// The `K`, `M`, and `G` units are powers of 1024
+pub(crate) fn bandwidth_parser(arg: &str) -> std::result::Result<u64, clap::Error> {
+    let invalid = || Error::raw(ErrorKind::InvalidValue, format!("Invalid bandwidth: {arg}"));
+    let value = arg.trim();
+    let value = value.strip_suffix("/s").unwrap_or(value);
+    let value = value
+        .strip_suffix("iB")
+        .or_else(|| value.strip_suffix('B'))
+        .unwrap_or(value);
+    let (number, multiplier) = match value.char_indices().last() {
+        Some((i, 'K' | 'k')) => (&value[..i], 1024),
+        Some((i, 'M' | 'm')) => (&value[..i], 1024 * 1024),
+        Some((i, 'G' | 'g')) => (&value[..i], 1024 * 1024 * 1024),
+        _ => (value, 1),
+    };
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(multiplier))
+        .filter(|n| *n > 0)
+        .ok_or_else(invalid)
+}
@@ -3,7 +3,7 @@ use std::path::PathBuf;
 use async_trait::async_trait;
 use clap::Args;
 use colorful::Colorful;
-use ockam_api::cli_state::UseAwsKms;
+use ockam_api::cli_state::{UseAwsKms, UsePkcs11};
 use ockam_api::{fmt_info, fmt_ok};
 
 use ockam_node::Context;
@@ -28,6 +28,19 @@ pub struct CreateCommand {
 
     #[arg(long, default_value = "false")]
     pub aws_kms: bool,
+
+    /// Path to a PKCS#11 module used to store the signing keys in an HSM
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "slot",
+        conflicts_with = "aws_kms"
+    )]
+    pub pkcs11_module: Option<PathBuf>,
+
+    /// Id of the PKCS#11 slot holding the token
+    #[arg(long, value_name = "SLOT_ID", requires = "pkcs11_module")]
+    pub slot: Option<u64>,
 }
 
 #[async_trait]
@@ -41,10 +54,18 @@ impl Command for CreateCommand {
         ))?;
         }
 
-        let vault = opts
-            .state
-            .create_named_vault(self.name, self.path, UseAwsKms::from(self.aws_kms))
-            .await?;
+        let vault = match (self.pkcs11_module, self.slot) {
+            (Some(module), Some(slot)) => {
+                opts.state
+                    .create_named_pkcs11_vault(self.name, self.path, UsePkcs11::new(module, slot))
+                    .await?
+            }
+            _ => {
+                opts.state
+                    .create_named_vault(self.name, self.path, UseAwsKms::from(self.aws_kms))
+                    .await?
+            }
+        };
 
         opts.terminal
             .stdout()
@@ -67,4 +88,24 @@ mod tests {
         let cmd = parse_cmd_from_args(CreateCommand::NAME, &[]);
         assert!(cmd.is_ok());
     }
+
+    #[test]
+    fn pkcs11_module_requires_a_slot() {
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &["--pkcs11-module".to_string(), "libsofthsm2.so".to_string()],
+        );
+        assert!(cmd.is_err());
+
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &[
+                "--pkcs11-module".to_string(),
+                "libsofthsm2.so".to_string(),
+                "--slot".to_string(),
+                "0".to_string(),
+            ],
+        );
+        assert!(cmd.is_ok());
+    }
 }
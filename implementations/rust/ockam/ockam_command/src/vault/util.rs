@@ -57,9 +57,10 @@ impl Output for VaultOutput {
         .to_string()
         .color(OckamColor::PrimaryResource.color());
 
-        Ok(match self.vault.vault_type() {
+        let mut output = match self.vault.vault_type() {
             VaultType::DatabaseVault {
                 use_aws_kms: UseAwsKms::No,
+                ..
             } => formatdoc!(
                 r#"Name: {name}
                    Type: {vault_type}"#,
@@ -68,6 +69,7 @@ impl Output for VaultOutput {
             ),
             VaultType::DatabaseVault {
                 use_aws_kms: UseAwsKms::Yes,
+                ..
             } => formatdoc!(
                 r#"Name: {name}
             Type: {vault_type}
@@ -78,6 +80,7 @@ impl Output for VaultOutput {
             VaultType::LocalFileVault {
                 path,
                 use_aws_kms: UseAwsKms::No,
+                ..
             } => formatdoc!(
                 r#"Name: {name}
             Type: {vault_type}
@@ -92,6 +95,7 @@ impl Output for VaultOutput {
             VaultType::LocalFileVault {
                 path,
                 use_aws_kms: UseAwsKms::Yes,
+                ..
             } => formatdoc!(
                 r#"Name: {name}
             Type: External
@@ -104,6 +108,16 @@ impl Output for VaultOutput {
                     .color(OckamColor::PrimaryResource.color()),
                 uses_aws_kms = uses_aws_kms,
             ),
-        })
+        };
+
+        if let Some(pkcs11) = self.vault.vault_type().pkcs11() {
+            output.push_str(&format!(
+                "\nUses PKCS#11: {}",
+                pkcs11
+                    .to_string()
+                    .color(OckamColor::PrimaryResource.color())
+            ));
+        }
+        Ok(output)
     }
 }
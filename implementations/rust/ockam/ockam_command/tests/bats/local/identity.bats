@@ -99,3 +99,44 @@ teardown() {
   run_success "$OCKAM" identity show --full --encoding hex
   assert_output "$exported"
 }
+
+@test "identity - rotate" {
+  run_success "$OCKAM" identity create i
+  run_success "$OCKAM" identity show i
+  identifier=$output
+
+  # The identifier stays the same and a new change is added
+  run_success "$OCKAM" identity rotate i
+  run_success "$OCKAM" identity show i
+  assert_output "$identifier"
+  run_success "$OCKAM" identity show i --full
+  assert_output --partial "Change[1]:"
+
+  # The identity can be rotated while a node is using it
+  run_success "$OCKAM" node create n --identity i
+  run_success "$OCKAM" identity rotate i
+  run_success "$OCKAM" identity show i --full
+  assert_output --partial "Change[2]:"
+}
+
+@test "identity - export with secrets and import" {
+  export OCKAM_IDENTITY_PASSPHRASE=passphrase
+  run_success "$OCKAM" identity create i
+  run_success "$OCKAM" identity show i
+  identifier=$output
+
+  run_success "$OCKAM" identity export i --with-secrets --file "$BATS_TEST_TMPDIR/i.bundle"
+  run_success "$OCKAM" identity delete i --yes
+
+  # The bundle can't be decrypted with another passphrase
+  OCKAM_IDENTITY_PASSPHRASE=other run_failure "$OCKAM" identity import "$BATS_TEST_TMPDIR/i.bundle" --name i
+
+  run_success "$OCKAM" identity import "$BATS_TEST_TMPDIR/i.bundle" --name i
+  run_success "$OCKAM" identity show i
+  assert_output "$identifier"
+
+  # The imported identity can still be used, and rotated
+  run_success "$OCKAM" identity rotate i
+  run_success "$OCKAM" identity show i
+  assert_output "$identifier"
+}
@@ -42,11 +42,39 @@ teardown() {
   assert_output --partial "\"remote_address\": \"forward_to_blue\""
   assert_output --partial "\"remote_address\": \"forward_to_red\""
 
+  # The relays hosted by a node are listed with the nodes registered for them
+  run_success $OCKAM relay list --to /node/n1 --output json
+  assert_output --partial "\"name\": \"forward_to_blue\""
+  assert_output --partial "\"name\": \"forward_to_red\""
+  assert_output --partial "\"connections\": 0"
+
   # Test listing node with no relays
-  run_success $OCKAM relay list --to /node/n1
+  run_success --separate-stderr "$OCKAM" node create n3
+  run_success $OCKAM relay list --to /node/n3
   assert_output --partial "[]"
 }
 
+@test "relay - several nodes register the same relay" {
+  run_success --separate-stderr "$OCKAM" node create n1
+  run_success --separate-stderr "$OCKAM" node create n2
+  run_success --separate-stderr "$OCKAM" node create n3
+
+  run_success $OCKAM relay create blue --at /node/n1 --to /node/n2
+  run_success $OCKAM relay create blue --at /node/n1 --to /node/n3
+
+  # The messages are sent to both nodes
+  for i in {1..4}; do
+    msg=$(random_str)
+    run_success "$OCKAM" message send --timeout 5 "$msg" --to /node/n1/service/forward_to_blue/service/uppercase
+    assert_output "$(to_uppercase "$msg")"
+  done
+
+  run_success $OCKAM relay list --to /node/n1 --output json --jq '.[] | select(.name == "forward_to_blue") | .backends | length'
+  assert_output "2"
+  run_success $OCKAM relay list --to /node/n1 --output json --jq '[.[] | select(.name == "forward_to_blue") | .backends[].connections] | min'
+  assert_output "2"
+}
+
 @test "relay - CRUD" {
   run_success --separate-stderr "$OCKAM" node create n1
   run_success --separate-stderr "$OCKAM" node create n2
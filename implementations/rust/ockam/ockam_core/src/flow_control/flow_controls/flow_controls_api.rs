@@ -124,6 +124,22 @@ impl FlowControls {
         producers.get(&producer_address).cloned()
     }
 
+    /// Number of registered Producers
+    pub fn number_of_producers(&self) -> usize {
+        self.producers.read().unwrap().len()
+    }
+
+    /// Number of registered Consumers, counted once per [`FlowControlId`]
+    pub fn number_of_consumers(&self) -> usize {
+        let consumers = self.consumers.read().unwrap();
+        consumers.values().map(|info| info.0.len()).sum()
+    }
+
+    /// Number of registered Spawners
+    pub fn number_of_spawners(&self) -> usize {
+        self.spawners.read().unwrap().len()
+    }
+
     /// Get all [`FlowControlId`]s for which given Address is a consumer
     /// TODO: Optimize
     pub fn get_flow_control_ids_for_consumer(&self, address: &Address) -> Vec<FlowControlId> {
@@ -195,3 +195,21 @@ fn test_cleanup() {
         .is_empty());
     assert!(flow_controls.spawners.read().unwrap().is_empty());
 }
+
+#[test]
+fn test_counts() {
+    let flow_controls = FlowControls::new();
+
+    let flow_control_id = FlowControls::generate_flow_control_id();
+    flow_controls.add_producer(Address::random_local(), &flow_control_id, None, vec![]);
+    flow_controls.add_consumer(Address::random_local(), &flow_control_id);
+    flow_controls.add_consumer(Address::random_local(), &flow_control_id);
+
+    let spawner_flow_control_id = FlowControls::generate_flow_control_id();
+    flow_controls.add_spawner(Address::random_local(), &spawner_flow_control_id);
+    flow_controls.add_consumer(Address::random_local(), &spawner_flow_control_id);
+
+    assert_eq!(flow_controls.number_of_producers(), 1);
+    assert_eq!(flow_controls.number_of_consumers(), 3);
+    assert_eq!(flow_controls.number_of_spawners(), 1);
+}
@@ -4,7 +4,7 @@ use ockam_vault::{VaultForSigning, VaultForVerifyingSignatures};
 use crate::models::{CredentialData, PurposeKeyAttestationData};
 use crate::{
     CredentialsCreation, CredentialsVerification, IdentitiesCreation, IdentityAttributesRepository,
-    PurposeKeys,
+    PurposeKeys, RevocationListRepository, RevocationLists,
 };
 
 /// Structure with both [`CredentialData`] and [`PurposeKeyAttestationData`] that we get
@@ -24,6 +24,7 @@ pub struct Credentials {
     purpose_keys: Arc<PurposeKeys>,
     identities_creation: Arc<IdentitiesCreation>,
     identity_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+    revocation_list_repository: Arc<dyn RevocationListRepository>,
 }
 
 impl Credentials {
@@ -34,6 +35,7 @@ impl Credentials {
         purpose_keys: Arc<PurposeKeys>,
         identities_creation: Arc<IdentitiesCreation>,
         identity_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+        revocation_list_repository: Arc<dyn RevocationListRepository>,
     ) -> Self {
         Self {
             credential_vault,
@@ -41,6 +43,7 @@ impl Credentials {
             purpose_keys,
             identities_creation,
             identity_attributes_repository,
+            revocation_list_repository,
         }
     }
 
@@ -65,6 +68,19 @@ impl Credentials {
             self.purpose_keys.purpose_keys_verification(),
             self.verifying_vault.clone(),
             self.identity_attributes_repository.clone(),
+            self.revocation_list_repository.clone(),
+        ))
+    }
+
+    /// Return [`RevocationLists`]
+    pub fn revocation_lists(&self) -> Arc<RevocationLists> {
+        Arc::new(RevocationLists::new(
+            self.purpose_keys.purpose_keys_creation(),
+            self.purpose_keys.purpose_keys_verification(),
+            self.credential_vault.clone(),
+            self.verifying_vault.clone(),
+            self.revocation_list_repository.clone(),
+            self.identity_attributes_repository.clone(),
         ))
     }
 }
@@ -13,7 +13,7 @@ use crate::models::{
 use crate::utils::now;
 use crate::{
     CredentialAndPurposeKeyData, IdentityAttributesRepository, IdentityError,
-    PurposeKeyVerification, TimestampInSeconds,
+    PurposeKeyVerification, RevocationListRepository, RevocationLists, TimestampInSeconds,
 };
 
 /// We allow Credentials to be created in the future related to this machine's time due to
@@ -25,6 +25,7 @@ pub struct CredentialsVerification {
     purpose_keys_verification: Arc<PurposeKeyVerification>,
     verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
     identities_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+    revocation_list_repository: Arc<dyn RevocationListRepository>,
 }
 
 impl CredentialsVerification {
@@ -33,31 +34,53 @@ impl CredentialsVerification {
         purpose_keys_verification: Arc<PurposeKeyVerification>,
         verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
         identities_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+        revocation_list_repository: Arc<dyn RevocationListRepository>,
     ) -> Self {
         Self {
             purpose_keys_verification,
             verifying_vault,
             identities_attributes_repository,
+            revocation_list_repository,
         }
     }
 }
 
 impl CredentialsVerification {
-    /// Verify a [`Credential`]
+    /// Verify a [`Credential`] and check that it hasn't been revoked by its issuer
     pub async fn verify_credential(
         &self,
         expected_subject: Option<&Identifier>,
         authorities: &[Identifier],
         credential_and_purpose_key: &CredentialAndPurposeKey,
     ) -> Result<CredentialAndPurposeKeyData> {
-        Self::verify_credential_static(
+        let data = Self::verify_credential_static(
             self.purpose_keys_verification.clone(),
             self.verifying_vault.clone(),
             expected_subject,
             authorities,
             credential_and_purpose_key,
         )
-        .await
+        .await?;
+
+        debug!("verify revocation");
+        if let Some(subject) = &data.credential_data.subject {
+            if RevocationLists::is_revoked_static(
+                self.revocation_list_repository.clone(),
+                &data.purpose_key_data.subject,
+                subject,
+                data.credential_data.created_at,
+            )
+            .await?
+            {
+                warn!(
+                    "the credential of {} was revoked by {}",
+                    subject, data.purpose_key_data.subject
+                );
+                return Err(IdentityError::CredentialRevoked)?;
+            }
+        }
+
+        Ok(data)
     }
 
     /// Verify a [`Credential`]
@@ -214,7 +237,8 @@ impl CredentialsVerification {
                     now()?,
                     Some(credential_data.credential_data.expires_at),
                     Some(credential_data.purpose_key_data.subject),
-                ),
+                )
+                .with_credential_created_at(credential_data.credential_data.created_at),
             )
             .await?;
 
@@ -0,0 +1,342 @@
+use tracing::{debug, info, warn};
+
+use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+use ockam_vault::{VaultForSigning, VaultForVerifyingSignatures};
+
+use crate::models::{
+    Identifier, PurposePublicKey, RevocationList, RevocationListAndPurposeKey, RevocationListData,
+    RevokedSubject,
+};
+use crate::utils::now;
+use crate::{
+    IdentityAttributesRepository, IdentityError, PurposeKeyCreation, PurposeKeyVerification,
+    RevocationListRepository, TimestampInSeconds,
+};
+
+/// Service for issuing, verifying and storing [`RevocationList`]s.
+///
+/// A [`RevocationList`] is signed by an Authority with its credentials purpose key.
+/// Every credential issued by that Authority for a revoked subject, and created at or before
+/// the revocation time, is considered as invalid.
+pub struct RevocationLists {
+    purpose_keys_creation: Arc<PurposeKeyCreation>,
+    purpose_keys_verification: Arc<PurposeKeyVerification>,
+    credential_vault: Arc<dyn VaultForSigning>,
+    verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
+    revocation_list_repository: Arc<dyn RevocationListRepository>,
+    identity_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+}
+
+impl RevocationLists {
+    ///Constructor
+    pub fn new(
+        purpose_keys_creation: Arc<PurposeKeyCreation>,
+        purpose_keys_verification: Arc<PurposeKeyVerification>,
+        credential_vault: Arc<dyn VaultForSigning>,
+        verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
+        revocation_list_repository: Arc<dyn RevocationListRepository>,
+        identity_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+    ) -> Self {
+        Self {
+            purpose_keys_creation,
+            purpose_keys_verification,
+            credential_vault,
+            verifying_vault,
+            revocation_list_repository,
+            identity_attributes_repository,
+        }
+    }
+}
+
+impl RevocationLists {
+    /// Issue a [`RevocationList`] signed by the `issuer` credentials purpose key
+    pub async fn issue_revocation_list(
+        &self,
+        issuer: &Identifier,
+        version: u64,
+        revoked_subjects: Vec<RevokedSubject>,
+    ) -> Result<RevocationListAndPurposeKey> {
+        let issuer_purpose_key = self
+            .purpose_keys_creation
+            .get_or_create_credential_purpose_key(issuer)
+            .await?;
+
+        let data = RevocationListData {
+            version,
+            created_at: now()?,
+            revoked_subjects,
+        };
+        let data = ockam_core::cbor_encode_preallocate(data)?;
+
+        let versioned_data = RevocationList::create_versioned_data(data);
+        let versioned_data = ockam_core::cbor_encode_preallocate(&versioned_data)?;
+
+        let versioned_data_hash = self.verifying_vault.sha256(&versioned_data).await?;
+
+        let signature = self
+            .credential_vault
+            .sign(issuer_purpose_key.key(), &versioned_data_hash.0)
+            .await?;
+
+        Ok(RevocationListAndPurposeKey {
+            revocation_list: RevocationList {
+                data: versioned_data,
+                signature: signature.into(),
+            },
+            purpose_key_attestation: issuer_purpose_key.attestation().clone(),
+        })
+    }
+
+    /// Verify a [`RevocationList`] and return its data
+    pub async fn verify_revocation_list(
+        &self,
+        authority: &Identifier,
+        revocation_list_and_purpose_key: &RevocationListAndPurposeKey,
+    ) -> Result<RevocationListData> {
+        debug!("verify purpose key attestation");
+        let purpose_key_data = self
+            .purpose_keys_verification
+            .verify_purpose_key_attestation(
+                None,
+                &revocation_list_and_purpose_key.purpose_key_attestation,
+            )
+            .await?;
+
+        debug!("verify issuer");
+        if &purpose_key_data.subject != authority {
+            warn!(
+                "unknown authority on a revocation list: {}. Accepted authority: {}",
+                purpose_key_data.subject, authority
+            );
+            return Err(IdentityError::UnknownAuthority)?;
+        }
+
+        debug!("verify purpose key type");
+        let public_key = match purpose_key_data.public_key {
+            PurposePublicKey::SecureChannelStatic(_) => {
+                return Err(IdentityError::InvalidKeyType)?;
+            }
+            PurposePublicKey::CredentialSigning(public_key) => public_key,
+        };
+
+        debug!("verify signature");
+        let revocation_list = &revocation_list_and_purpose_key.revocation_list;
+        let versioned_data_hash = self.verifying_vault.sha256(&revocation_list.data).await?;
+        if !self
+            .verifying_vault
+            .verify_signature(
+                &public_key.into(),
+                &versioned_data_hash.0,
+                &revocation_list.signature.clone().into(),
+            )
+            .await?
+        {
+            return Err(IdentityError::RevocationListVerificationFailed)?;
+        }
+
+        let data = revocation_list.get_revocation_list_data()?;
+
+        debug!("verify dates");
+        if data.created_at < purpose_key_data.created_at
+            || data.created_at > purpose_key_data.expires_at
+        {
+            // The revocation list must be created while the purpose key is valid
+            return Err(IdentityError::RevocationListVerificationFailed)?;
+        }
+
+        Ok(data)
+    }
+
+    /// Receive a [`RevocationList`] from an authority: verify it and store it if it is more
+    /// recent than the one we already know.
+    ///
+    /// The attributes attested by that authority for the revoked subjects are deleted.
+    /// Return the subjects which were not revoked by the previously stored list.
+    pub async fn receive_revocation_list(
+        &self,
+        authority: &Identifier,
+        revocation_list: RevocationListAndPurposeKey,
+    ) -> Result<Vec<Identifier>> {
+        let data = self
+            .verify_revocation_list(authority, &revocation_list)
+            .await?;
+
+        let previous = match self
+            .revocation_list_repository
+            .get_revocation_list(authority)
+            .await?
+        {
+            Some(previous) => Some(previous.get_revocation_list_data()?),
+            None => None,
+        };
+
+        if let Some(previous) = &previous {
+            if previous.version >= data.version {
+                debug!(
+                    "ignoring revocation list version {} from {}, the current version is {}",
+                    data.version, authority, previous.version
+                );
+                return Ok(vec![]);
+            }
+        }
+
+        let newly_revoked: Vec<Identifier> = data
+            .revoked_subjects
+            .iter()
+            .filter(|revoked| {
+                previous.as_ref().map_or(true, |previous| {
+                    !previous.revoked_subjects.contains(revoked)
+                })
+            })
+            .map(|revoked| revoked.subject.clone())
+            .collect();
+
+        self.revocation_list_repository
+            .put_revocation_list(authority, revocation_list, &data)
+            .await?;
+
+        for subject in newly_revoked.iter() {
+            self.identity_attributes_repository
+                .delete_attributes(subject, authority)
+                .await?;
+        }
+
+        info!(
+            "stored the revocation list version {} from {}. Newly revoked subjects: {:?}",
+            data.version, authority, newly_revoked
+        );
+
+        Ok(newly_revoked)
+    }
+
+    /// Return the latest [`RevocationList`] received from an authority
+    pub async fn get_revocation_list(
+        &self,
+        authority: &Identifier,
+    ) -> Result<Option<RevocationListAndPurposeKey>> {
+        self.revocation_list_repository
+            .get_revocation_list(authority)
+            .await
+    }
+
+    /// Return true if the credentials created for `subject` by `authority` at `created_at`
+    /// have been revoked
+    pub async fn is_revoked(
+        &self,
+        authority: &Identifier,
+        subject: &Identifier,
+        created_at: TimestampInSeconds,
+    ) -> Result<bool> {
+        Self::is_revoked_static(
+            self.revocation_list_repository.clone(),
+            authority,
+            subject,
+            created_at,
+        )
+        .await
+    }
+
+    /// Return true if the credentials created for `subject` by `authority` at `created_at`
+    /// have been revoked
+    pub async fn is_revoked_static(
+        revocation_list_repository: Arc<dyn RevocationListRepository>,
+        authority: &Identifier,
+        subject: &Identifier,
+        created_at: TimestampInSeconds,
+    ) -> Result<bool> {
+        Ok(revocation_list_repository
+            .get_revoked_at(authority, subject)
+            .await?
+            .map_or(false, |revoked_at| created_at < revoked_at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identities;
+
+    #[tokio::test]
+    async fn test_receive_revocation_list() -> Result<()> {
+        let identities = identities().await?;
+        let authority = identities.identities_creation().create_identity().await?;
+        let other = identities.identities_creation().create_identity().await?;
+        let subject1 = identities.identities_creation().create_identity().await?;
+        let subject2 = identities.identities_creation().create_identity().await?;
+        let revocation_lists = identities.credentials().revocation_lists();
+        let now = now()?;
+        let before_now = TimestampInSeconds(*now - 1);
+
+        let revoked1 = RevokedSubject {
+            subject: subject1.clone(),
+            revoked_at: now,
+        };
+        let revoked2 = RevokedSubject {
+            subject: subject2.clone(),
+            revoked_at: now,
+        };
+
+        let list1 = revocation_lists
+            .issue_revocation_list(&authority, 1, vec![revoked1.clone()])
+            .await?;
+        let list2 = revocation_lists
+            .issue_revocation_list(&authority, 2, vec![revoked1, revoked2])
+            .await?;
+
+        // the list must be signed by the expected authority
+        assert!(revocation_lists
+            .receive_revocation_list(&other, list1.clone())
+            .await
+            .is_err());
+
+        let revoked = revocation_lists
+            .receive_revocation_list(&authority, list1.clone())
+            .await?;
+        assert_eq!(revoked, vec![subject1.clone()]);
+        assert!(
+            revocation_lists
+                .is_revoked(&authority, &subject1, before_now)
+                .await?
+        );
+        // a member added again during the second of its revocation gets a valid credential
+        assert!(
+            !revocation_lists
+                .is_revoked(&authority, &subject1, now)
+                .await?
+        );
+        assert!(
+            !revocation_lists
+                .is_revoked(&authority, &subject1, now + 1)
+                .await?
+        );
+        assert!(
+            !revocation_lists
+                .is_revoked(&authority, &subject2, before_now)
+                .await?
+        );
+
+        let revoked = revocation_lists
+            .receive_revocation_list(&authority, list2.clone())
+            .await?;
+        assert_eq!(revoked, vec![subject2.clone()]);
+        assert!(
+            revocation_lists
+                .is_revoked(&authority, &subject2, before_now)
+                .await?
+        );
+
+        // an older list doesn't replace a newer one
+        let revoked = revocation_lists
+            .receive_revocation_list(&authority, list1)
+            .await?;
+        assert!(revoked.is_empty());
+        assert_eq!(
+            revocation_lists.get_revocation_list(&authority).await?,
+            Some(list2)
+        );
+
+        Ok(())
+    }
+}
@@ -72,6 +72,20 @@ pub enum IdentityError {
     UnknownRole,
     /// Handshake ended up in an internal invalid state
     HandshakeInternalError,
+    /// Unknown version of the RevocationList
+    UnknownRevocationListVersion,
+    /// Invalid data_type value for RevocationList
+    InvalidRevocationListDataType,
+    /// RevocationList Verification Failed
+    RevocationListVerificationFailed,
+    /// Credential was revoked by its issuer
+    CredentialRevoked,
+    /// Invalid set of threshold keys
+    InvalidThreshold,
+    /// Not enough signatures were collected from the threshold keys of a Change
+    NotEnoughThresholdSignatures,
+    /// The Change must be signed by a threshold of keys, see `PendingChange`
+    ThresholdSignaturesRequired,
 }
 
 impl ockam_core::compat::error::Error for IdentityError {}
@@ -4,7 +4,7 @@ use ockam_vault::{SigningSecretKeyHandle, VaultForSigning, VaultForVerifyingSign
 
 use crate::identities::identities_verification::IdentitiesVerification;
 use crate::identities::identity_builder::IdentityBuilder;
-use crate::models::Identifier;
+use crate::models::{Identifier, PendingChange};
 use crate::IdentityOptions;
 use crate::{ChangeHistoryRepository, IdentitiesKeys, Identity, IdentityError};
 
@@ -102,6 +102,79 @@ impl IdentitiesCreation {
         Ok(())
     }
 
+    /// Create a [`PendingChange`] for a new `Identity`, or for a new key of an existing
+    /// `Identity`, which still needs to be signed by threshold keys before it can be applied
+    pub async fn create_pending_change(
+        &self,
+        identifier: Option<&Identifier>,
+        options: IdentityOptions,
+    ) -> Result<PendingChange> {
+        let identity = match identifier {
+            Some(identifier) => Some(
+                self.identities_verification()
+                    .get_identity(identifier)
+                    .await?,
+            ),
+            None => None,
+        };
+        self.identities_keys()
+            .create_pending_change(identity.as_ref(), options)
+            .await
+    }
+
+    /// Sign a [`PendingChange`] with a threshold key stored in this vault
+    pub async fn sign_pending_change(
+        &self,
+        identifier: Option<&Identifier>,
+        pending_change: PendingChange,
+        signing_secret_key_handle: &SigningSecretKeyHandle,
+    ) -> Result<PendingChange> {
+        let identity = match identifier {
+            Some(identifier) => Some(
+                self.identities_verification()
+                    .get_identity(identifier)
+                    .await?,
+            ),
+            None => None,
+        };
+        self.identities_keys()
+            .sign_pending_change(identity.as_ref(), pending_change, signing_secret_key_handle)
+            .await
+    }
+
+    /// Assemble the signatures collected for a [`PendingChange`] and store the resulting
+    /// `Identity`. A new `Identity` is created if `identifier` is `None`
+    pub async fn apply_pending_change(
+        &self,
+        identifier: Option<&Identifier>,
+        pending_change: PendingChange,
+    ) -> Result<Identifier> {
+        let identity = match identifier {
+            Some(identifier) => Some(
+                self.identities_verification()
+                    .get_identity(identifier)
+                    .await?,
+            ),
+            None => None,
+        };
+        let is_new = identity.is_none();
+        let identity = self
+            .identities_keys()
+            .assemble_pending_change(identity, pending_change)
+            .await?;
+
+        if is_new {
+            self.repository
+                .store_change_history(identity.identifier(), identity.change_history().clone())
+                .await?;
+        } else {
+            self.identities_verification()
+                .update_identity(&identity)
+                .await?;
+        }
+        Ok(identity.identifier().clone())
+    }
+
     /// Import an existing Identity from its binary format
     /// Its secret is expected to exist in the Vault (either generated there, or some Vault
     /// implementations may allow importing a secret)
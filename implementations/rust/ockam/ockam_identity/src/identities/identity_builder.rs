@@ -2,7 +2,7 @@ use ockam_core::compat::sync::Arc;
 use ockam_core::Result;
 use ockam_vault::{SigningKeyType, SigningSecretKeyHandle};
 
-use crate::models::TimestampInSeconds;
+use crate::models::{PendingChange, ThresholdPublicKeys, TimestampInSeconds};
 use crate::utils::now;
 use crate::IdentityOptions;
 use crate::{Identifier, IdentitiesCreation};
@@ -30,6 +30,7 @@ pub struct IdentityBuilder {
     revoke_all_purpose_keys: bool,
     key: Key,
     ttl: Ttl,
+    threshold_public_keys: Option<ThresholdPublicKeys>,
 }
 
 impl IdentityBuilder {
@@ -40,6 +41,7 @@ impl IdentityBuilder {
             revoke_all_purpose_keys: false,
             key: Key::Generate(SigningKeyType::EdDSACurve25519),
             ttl: Ttl::CreatedNowWithTtl(DEFAULT_IDENTITY_TTL),
+            threshold_public_keys: None,
         }
     }
 
@@ -80,6 +82,15 @@ impl IdentityBuilder {
         self
     }
 
+    /// Require the next change of the Identity to be signed by a threshold of these keys
+    pub fn with_threshold_public_keys(
+        mut self,
+        threshold_public_keys: ThresholdPublicKeys,
+    ) -> Self {
+        self.threshold_public_keys = Some(threshold_public_keys);
+        self
+    }
+
     /// Create the corresponding [`IdentityOptions`] object
     pub async fn build_options(self) -> Result<IdentityOptions> {
         let key = match self.key {
@@ -111,6 +122,12 @@ impl IdentityBuilder {
             attestations_valid_from,
             attestations_valid_until,
         );
+        let options = match self.threshold_public_keys {
+            Some(threshold_public_keys) => {
+                options.with_threshold_public_keys(threshold_public_keys)
+            }
+            None => options,
+        };
 
         Ok(options)
     }
@@ -125,4 +142,15 @@ impl IdentityBuilder {
             .create_identity_with_options(options)
             .await
     }
+
+    /// Create a [`PendingChange`] for a new [`Identity`], to be signed by its threshold keys
+    pub async fn build_pending_change(self) -> Result<PendingChange> {
+        let identities_creation = self.identities_creation.clone();
+
+        let options = self.build_options().await?;
+
+        identities_creation
+            .create_pending_change(None, options)
+            .await
+    }
 }
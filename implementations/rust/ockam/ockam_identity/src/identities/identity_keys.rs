@@ -1,8 +1,12 @@
 use crate::identity::Identity;
-use crate::models::{Change, ChangeData, ChangeHash, ChangeHistory};
+use crate::models::{
+    Change, ChangeData, ChangeHash, ChangeHistory, ChangeSignature, PendingChange,
+    ThresholdChangeSignature, ThresholdPublicKeys,
+};
 use crate::{IdentityError, IdentityOptions};
 
 use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
 
 use ockam_vault::{SigningSecretKeyHandle, VaultForSigning, VaultForVerifyingSignatures};
@@ -16,7 +20,7 @@ pub struct IdentitiesKeys {
 
 impl IdentitiesKeys {
     pub(crate) async fn create_initial_key(&self, options: IdentityOptions) -> Result<Identity> {
-        let change = self.make_change(options, None).await?;
+        let change = self.make_change(options, None, false).await?;
         let change_history = ChangeHistory(vec![change]);
 
         let identity = Identity::import_from_change_history(
@@ -53,6 +57,10 @@ impl IdentitiesKeys {
             Some(last_change) => last_change,
             None => return Err(IdentityError::EmptyIdentity)?,
         };
+        if last_change.data().threshold_public_keys.is_some() {
+            // The new change must be signed by a threshold of keys, use a PendingChange
+            return Err(IdentityError::ThresholdSignaturesRequired)?;
+        }
 
         let last_secret_key = self.get_secret_key(&identity).await?;
 
@@ -60,6 +68,7 @@ impl IdentitiesKeys {
             .make_change(
                 options,
                 Some((last_change.change_hash().clone(), last_secret_key.clone())),
+                Self::has_threshold_keys(&identity),
             )
             .await?;
 
@@ -94,6 +103,157 @@ impl IdentitiesKeys {
     }
 }
 
+/// Changes signed by a threshold of keys
+///
+/// A change which must be signed by several keys, possibly held in different vaults, is
+/// created as a [`PendingChange`]. Each key holder adds a signature with
+/// [`IdentitiesKeys::sign_pending_change`], then the signatures are assembled into a valid
+/// [`Change`] with [`IdentitiesKeys::assemble_pending_change`].
+impl IdentitiesKeys {
+    /// Create a [`PendingChange`] adding a new key to an Identity, or creating a new Identity
+    /// if `identity` is `None`.
+    /// The pending change is signed with the new primary key and, unless the Identity is
+    /// controlled by a threshold of keys, with the previous primary key
+    pub async fn create_pending_change(
+        &self,
+        identity: Option<&Identity>,
+        options: IdentityOptions,
+    ) -> Result<PendingChange> {
+        let previous = match identity {
+            Some(identity) => {
+                let last_change = identity.get_latest_change()?;
+                let previous_key = if last_change.data().threshold_public_keys.is_none() {
+                    Some(self.get_secret_key(identity).await?)
+                } else {
+                    None
+                };
+                Some((last_change.change_hash().clone(), previous_key))
+            }
+            None => None,
+        };
+        let threshold_history = identity.map_or(false, Self::has_threshold_keys);
+        self.make_pending_change(options, previous, threshold_history)
+            .await
+    }
+
+    /// Sign a [`PendingChange`] with a key of this vault. That key must be one of the
+    /// threshold keys of the pending change, or one of the threshold keys of the latest
+    /// change of the `identity` being modified
+    pub async fn sign_pending_change(
+        &self,
+        identity: Option<&Identity>,
+        mut pending_change: PendingChange,
+        signing_secret_key_handle: &SigningSecretKeyHandle,
+    ) -> Result<PendingChange> {
+        let change_data = pending_change.get_change_data()?;
+        let latest_change = match identity {
+            Some(identity) => Some(identity.get_latest_change()?),
+            None => None,
+        };
+
+        // Only sign a change following the latest known change of the identity
+        if latest_change.as_ref().map(|c| c.change_hash()) != change_data.previous_change.as_ref() {
+            return Err(IdentityError::ConsistencyError)?;
+        }
+
+        let public_key = self
+            .identity_vault
+            .get_verifying_public_key(signing_secret_key_handle)
+            .await?;
+        let key_index = change_data
+            .threshold_public_keys
+            .as_ref()
+            .and_then(|k| k.index_of(&public_key));
+        let previous_key_index = latest_change
+            .as_ref()
+            .and_then(|c| c.data().threshold_public_keys.as_ref())
+            .and_then(|k| k.index_of(&public_key));
+        if key_index.is_none() && previous_key_index.is_none() {
+            return Err(IdentityError::WrongSecretKey)?;
+        }
+
+        let hash = self.verifying_vault.sha256(&pending_change.data).await?;
+        let signature: ChangeSignature = self
+            .identity_vault
+            .sign(signing_secret_key_handle, &hash.0)
+            .await?
+            .into();
+        if let Some(key_index) = key_index {
+            Self::add_threshold_signature(
+                &mut pending_change.threshold_signatures,
+                key_index,
+                signature.clone(),
+            );
+        }
+        if let Some(key_index) = previous_key_index {
+            Self::add_threshold_signature(
+                &mut pending_change.previous_threshold_signatures,
+                key_index,
+                signature,
+            );
+        }
+        Ok(pending_change)
+    }
+
+    /// Assemble the signatures of a [`PendingChange`] into a [`Change`], then add it to the
+    /// `identity`, or create a new Identity if `identity` is `None`.
+    /// The resulting Identity is fully verified
+    pub async fn assemble_pending_change(
+        &self,
+        identity: Option<Identity>,
+        pending_change: PendingChange,
+    ) -> Result<Identity> {
+        let change_data = pending_change.get_change_data()?;
+        let previous_threshold_public_keys = match &identity {
+            Some(identity) => identity
+                .get_latest_change()?
+                .data()
+                .threshold_public_keys
+                .clone(),
+            None => None,
+        };
+        Self::check_threshold_signatures(
+            change_data.threshold_public_keys.as_ref(),
+            &pending_change.threshold_signatures,
+        )?;
+        Self::check_threshold_signatures(
+            previous_threshold_public_keys.as_ref(),
+            &pending_change.previous_threshold_signatures,
+        )?;
+
+        let signature = match pending_change.signature {
+            Some(signature) => signature,
+            None => return Err(IdentityError::IdentityVerificationFailed)?,
+        };
+        let change = Change {
+            data: pending_change.data,
+            signature,
+            previous_signature: pending_change.previous_signature,
+            threshold_signatures: change_data
+                .threshold_public_keys
+                .map(|_| pending_change.threshold_signatures),
+            previous_threshold_signatures: previous_threshold_public_keys
+                .map(|_| pending_change.previous_threshold_signatures),
+        };
+
+        match identity {
+            Some(identity) => {
+                identity
+                    .add_change(change, self.verifying_vault.clone())
+                    .await
+            }
+            None => {
+                Identity::import_from_change_history(
+                    None,
+                    ChangeHistory(vec![change]),
+                    self.verifying_vault.clone(),
+                )
+                .await
+            }
+        }
+    }
+}
+
 /// Private  functions
 impl IdentitiesKeys {
     /// Create a new key
@@ -101,26 +261,74 @@ impl IdentitiesKeys {
         &self,
         identity_options: IdentityOptions,
         previous: Option<(ChangeHash, SigningSecretKeyHandle)>,
+        threshold_history: bool,
     ) -> Result<Change> {
+        if identity_options.threshold_public_keys.is_some() {
+            // The new change must be signed by a threshold of keys, use a PendingChange
+            return Err(IdentityError::ThresholdSignaturesRequired)?;
+        }
+        let pending_change = self
+            .make_pending_change(
+                identity_options,
+                previous.map(|(x, y)| (x, Some(y))),
+                threshold_history,
+            )
+            .await?;
+
+        match pending_change.signature {
+            Some(signature) => Ok(Change {
+                data: pending_change.data,
+                signature,
+                previous_signature: pending_change.previous_signature,
+                threshold_signatures: None,
+                previous_threshold_signatures: None,
+            }),
+            None => Err(IdentityError::IdentityVerificationFailed)?,
+        }
+    }
+
+    /// Return true if a change of the identity introduced threshold keys. The following
+    /// changes keep the version of that change, since a version can't be downgraded
+    fn has_threshold_keys(identity: &Identity) -> bool {
+        identity
+            .changes()
+            .iter()
+            .any(|change| change.data().threshold_public_keys.is_some())
+    }
+
+    /// Create a new key, as a change which might still need to be signed by threshold keys.
+    /// `threshold_history` is true if a previous change of the identity has threshold keys
+    async fn make_pending_change(
+        &self,
+        identity_options: IdentityOptions,
+        previous: Option<(ChangeHash, Option<SigningSecretKeyHandle>)>,
+        threshold_history: bool,
+    ) -> Result<PendingChange> {
         let secret_key = identity_options.signing_secret_key_handle;
         let public_key = self
             .identity_vault
             .get_verifying_public_key(&secret_key)
             .await?;
-        let (previous_change, previous_key) = previous
-            .map(|(x, y)| (Some(x), Some(y)))
-            .unwrap_or((None, None));
+        let (previous_change, previous_key) =
+            previous.map(|(x, y)| (Some(x), y)).unwrap_or((None, None));
+        let uses_threshold_keys =
+            threshold_history || identity_options.threshold_public_keys.is_some();
         let change_data = ChangeData {
             previous_change,
             primary_public_key: public_key.into(),
             revoke_all_purpose_keys: identity_options.revoke_all_purpose_keys,
             attestations_valid_from: identity_options.attestations_valid_from,
             attestations_valid_until: identity_options.attestations_valid_until,
+            threshold_public_keys: identity_options.threshold_public_keys,
         };
 
         let change_data = ockam_core::cbor_encode_preallocate(&change_data)?;
 
-        let versioned_data = Change::create_versioned_data(change_data);
+        let versioned_data = if uses_threshold_keys {
+            Change::create_threshold_versioned_data(change_data)
+        } else {
+            Change::create_versioned_data(change_data)
+        };
         let versioned_data = ockam_core::cbor_encode_preallocate(&versioned_data)?;
 
         let hash = self.verifying_vault.sha256(&versioned_data).await?;
@@ -129,7 +337,8 @@ impl IdentitiesKeys {
         let self_signature = self_signature.into();
 
         // If we have previous_key passed we should sign using it
-        // If there is no previous_key - we're creating new identity, so we just generated the key
+        // If there is no previous_key - we're creating new identity, so we just generated the key,
+        // or the previous change is controlled by threshold keys, which sign it separately
         let previous_signature = match previous_key {
             Some(previous_key) => {
                 let previous_signature = self.identity_vault.sign(&previous_key, &hash.0).await?;
@@ -139,13 +348,38 @@ impl IdentitiesKeys {
             None => None,
         };
 
-        let change = Change {
-            data: versioned_data,
-            signature: self_signature,
-            previous_signature,
-        };
+        let mut pending_change = PendingChange::new(versioned_data);
+        pending_change.signature = Some(self_signature);
+        pending_change.previous_signature = previous_signature;
+
+        Ok(pending_change)
+    }
+
+    /// Check that enough threshold signatures were collected
+    fn check_threshold_signatures(
+        threshold_public_keys: Option<&ThresholdPublicKeys>,
+        signatures: &[ThresholdChangeSignature],
+    ) -> Result<()> {
+        if let Some(threshold_public_keys) = threshold_public_keys {
+            if signatures.len() < threshold_public_keys.threshold as usize {
+                return Err(IdentityError::NotEnoughThresholdSignatures)?;
+            }
+        }
+        Ok(())
+    }
 
-        Ok(change)
+    /// Add a signature to a list of threshold signatures, replacing any previous signature
+    /// made with the same key
+    fn add_threshold_signature(
+        signatures: &mut Vec<ThresholdChangeSignature>,
+        key_index: u8,
+        signature: ChangeSignature,
+    ) {
+        signatures.retain(|s| s.key_index != key_index);
+        signatures.push(ThresholdChangeSignature {
+            key_index,
+            signature,
+        });
     }
 }
 
@@ -1,3 +1,4 @@
+use crate::models::ThresholdPublicKeys;
 use crate::TimestampInSeconds;
 use ockam_vault::SigningSecretKeyHandle;
 
@@ -7,6 +8,7 @@ pub struct IdentityOptions {
     pub(super) revoke_all_purpose_keys: bool,
     pub(super) attestations_valid_from: TimestampInSeconds,
     pub(super) attestations_valid_until: TimestampInSeconds,
+    pub(super) threshold_public_keys: Option<ThresholdPublicKeys>,
 }
 
 impl IdentityOptions {
@@ -22,9 +24,19 @@ impl IdentityOptions {
             revoke_all_purpose_keys,
             attestations_valid_from,
             attestations_valid_until,
+            threshold_public_keys: None,
         }
     }
 
+    /// Require the new change, and the next one, to be signed by a threshold of keys
+    pub fn with_threshold_public_keys(
+        mut self,
+        threshold_public_keys: ThresholdPublicKeys,
+    ) -> Self {
+        self.threshold_public_keys = Some(threshold_public_keys);
+        self
+    }
+
     /// New key
     pub fn signing_secret_key_handle(&self) -> &SigningSecretKeyHandle {
         &self.signing_secret_key_handle
@@ -44,4 +56,9 @@ impl IdentityOptions {
     pub fn attestations_valid_until(&self) -> TimestampInSeconds {
         self.attestations_valid_until
     }
+
+    /// Keys which must sign the new change, and the next one
+    pub fn threshold_public_keys(&self) -> Option<&ThresholdPublicKeys> {
+        self.threshold_public_keys.as_ref()
+    }
 }
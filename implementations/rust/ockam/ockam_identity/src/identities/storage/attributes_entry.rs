@@ -21,6 +21,7 @@ pub struct AttributesEntry {
     #[n(2)] added_at: TimestampInSeconds,
     #[n(3)] expires_at: Option<TimestampInSeconds>,
     #[n(4)] attested_by: Option<Identifier>,
+    #[n(5)] credential_created_at: Option<TimestampInSeconds>,
 }
 
 fn serialize_attributes<S>(
@@ -88,9 +89,16 @@ impl AttributesEntry {
             added_at,
             expires_at,
             attested_by,
+            credential_created_at: None,
         }
     }
 
+    /// Set the creation time of the credential attesting these attributes
+    pub fn with_credential_created_at(mut self, credential_created_at: TimestampInSeconds) -> Self {
+        self.credential_created_at = Some(credential_created_at);
+        self
+    }
+
     /// The entry attributes
     pub fn attrs(&self) -> &BTreeMap<Vec<u8>, Vec<u8>> {
         &self.attributes
@@ -121,6 +129,11 @@ impl AttributesEntry {
     pub fn attested_by(&self) -> Option<Identifier> {
         self.attested_by.to_owned()
     }
+
+    /// Creation time of the credential attesting these attributes, if they come from a credential
+    pub fn credential_created_at(&self) -> Option<TimestampInSeconds> {
+        self.credential_created_at
+    }
 }
 
 impl AttributesEntry {
@@ -137,6 +150,7 @@ impl AttributesEntry {
             added_at: now()?,
             expires_at,
             attested_by,
+            credential_created_at: None,
         })
     }
 }
@@ -123,7 +123,8 @@ impl ChangeHistoryRepository for ChangeHistorySqlxDatabase {
 }
 
 impl ChangeHistorySqlxDatabase {
-    fn insert_query<'a>(
+    /// Return the query storing the change history of an identity, to execute it in a transaction
+    pub fn insert_query<'a>(
         identifier: &'a Identifier,
         change_history: &'a ChangeHistory,
     ) -> Query<'a, Any, AnyArguments<'a>> {
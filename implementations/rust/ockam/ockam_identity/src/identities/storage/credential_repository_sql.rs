@@ -1,6 +1,8 @@
+use sqlx::any::AnyArguments;
 use sqlx::encode::IsNull;
 use sqlx::error::BoxDynError;
 use sqlx::postgres::any::AnyArgumentBuffer;
+use sqlx::query::Query;
 use sqlx::*;
 use tracing::debug;
 
@@ -57,6 +59,28 @@ impl CredentialSqlxDatabase {
 
         Ok(res)
     }
+
+    /// Return the query caching a credential for a node, to execute it in a transaction
+    pub fn put_query<'a>(
+        subject: &'a Identifier,
+        issuer: &'a Identifier,
+        scope: &'a str,
+        expires_at: TimestampInSeconds,
+        credential: CredentialAndPurposeKey,
+        node_name: &'a str,
+    ) -> Query<'a, Any, AnyArguments<'a>> {
+        query(
+            r#"INSERT INTO credential (subject_identifier, issuer_identifier, scope, credential, expires_at, node_name)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (subject_identifier, issuer_identifier, scope)
+            DO UPDATE SET credential = $4, expires_at = $5, node_name = $6"#)
+            .bind(subject)
+            .bind(issuer)
+            .bind(scope)
+            .bind(credential)
+            .bind(expires_at)
+            .bind(node_name)
+    }
 }
 
 #[async_trait]
@@ -89,18 +113,17 @@ impl CredentialRepository for CredentialSqlxDatabase {
         expires_at: TimestampInSeconds,
         credential: CredentialAndPurposeKey,
     ) -> Result<()> {
-        let query = query(
-            r#"INSERT INTO credential (subject_identifier, issuer_identifier, scope, credential, expires_at, node_name)
-            VALUES ($1, $2, $3, $4, $5, $6)
-            ON CONFLICT (subject_identifier, issuer_identifier, scope)
-            DO UPDATE SET credential = $4, expires_at = $5, node_name = $6"#)
-            .bind(subject)
-            .bind(issuer)
-            .bind(scope)
-            .bind(credential)
-            .bind(expires_at)
-            .bind(self.node_name.clone());
-        query.execute(&*self.database.pool).await.void()
+        Self::put_query(
+            subject,
+            issuer,
+            scope,
+            expires_at,
+            credential,
+            &self.node_name,
+        )
+        .execute(&*self.database.pool)
+        .await
+        .void()
     }
 
     async fn delete(&self, subject: &Identifier, issuer: &Identifier, scope: &str) -> Result<()> {
@@ -17,6 +17,10 @@ pub trait IdentityAttributesRepository: Send + Sync + 'static {
     /// Previous values gets overridden.
     async fn put_attributes(&self, subject: &Identifier, entry: AttributesEntry) -> Result<()>;
 
+    /// Remove the attributes attested by a given identity for a subject
+    async fn delete_attributes(&self, subject: &Identifier, attested_by: &Identifier)
+        -> Result<()>;
+
     /// Remove all expired attributes
     async fn delete_expired_attributes(&self, now: TimestampInSeconds) -> Result<()>;
 }
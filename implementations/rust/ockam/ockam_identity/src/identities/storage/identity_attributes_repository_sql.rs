@@ -48,7 +48,7 @@ impl IdentityAttributesRepository for IdentityAttributesSqlxDatabase {
         attested_by: &Identifier,
     ) -> Result<Option<AttributesEntry>> {
         let query = query_as(
-            "SELECT identifier, attributes, added, expires, attested_by FROM identity_attributes WHERE identifier = $1 AND attested_by = $2 AND node_name = $3"
+            "SELECT identifier, attributes, added, expires, attested_by, credential_created_at FROM identity_attributes WHERE identifier = $1 AND attested_by = $2 AND node_name = $3"
             )
             .bind(identity)
             .bind(attested_by)
@@ -63,16 +63,31 @@ impl IdentityAttributesRepository for IdentityAttributesSqlxDatabase {
     async fn put_attributes(&self, subject: &Identifier, entry: AttributesEntry) -> Result<()> {
         let query = query(
             r#"
-            INSERT INTO identity_attributes (identifier, attributes, added, expires, attested_by, node_name)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            INSERT INTO identity_attributes (identifier, attributes, added, expires, attested_by, node_name, credential_created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             ON CONFLICT (identifier, node_name)
-            DO UPDATE SET attributes = $2, added = $3, expires = $4, attested_by = $5, node_name = $6"#)
+            DO UPDATE SET attributes = $2, added = $3, expires = $4, attested_by = $5, node_name = $6, credential_created_at = $7"#)
             .bind(subject)
             .bind(&entry)
             .bind(entry.added_at())
             .bind(entry.expires_at())
             .bind(entry.attested_by())
-            .bind(&self.node_name);
+            .bind(&self.node_name)
+            .bind(entry.credential_created_at());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn delete_attributes(
+        &self,
+        subject: &Identifier,
+        attested_by: &Identifier,
+    ) -> Result<()> {
+        let query = query(
+            "DELETE FROM identity_attributes WHERE identifier = $1 AND attested_by = $2 AND node_name = $3",
+        )
+        .bind(subject)
+        .bind(attested_by)
+        .bind(&self.node_name);
         query.execute(&*self.database.pool).await.void()
     }
 
@@ -110,6 +125,7 @@ struct IdentityAttributesRow {
     added: i64,
     expires: Nullable<i64>,
     attested_by: Nullable<String>,
+    credential_created_at: Nullable<i64>,
 }
 
 impl IdentityAttributesRow {
@@ -132,12 +148,13 @@ impl IdentityAttributesRow {
             .map(|v| Identifier::from_str(&v))
             .transpose()?;
 
-        Ok(AttributesEntry::new(
-            attributes,
-            added,
-            expires,
-            attested_by,
-        ))
+        let entry = AttributesEntry::new(attributes, added, expires, attested_by);
+        Ok(match self.credential_created_at.to_option() {
+            Some(created_at) => {
+                entry.with_credential_created_at(TimestampInSeconds(created_at as u64))
+            }
+            None => entry,
+        })
     }
 }
 
@@ -164,7 +181,9 @@ mod tests {
             let identifier1 = create_identity().await?;
             let attributes1 = create_attributes_entry(&identifier1, now, Some(2.into())).await?;
             let identifier2 = create_identity().await?;
-            let attributes2 = create_attributes_entry(&identifier2, now, Some(2.into())).await?;
+            let attributes2 = create_attributes_entry(&identifier2, now, Some(2.into()))
+                .await?
+                .with_credential_created_at(now - 10.into());
 
             repository
                 .put_attributes(&identifier1, attributes1.clone())
@@ -0,0 +1,32 @@
+use crate::models::{RevocationListAndPurposeKey, RevocationListData};
+use crate::{Identifier, TimestampInSeconds};
+use async_trait::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::Result;
+
+/// This trait supports the persistence of the revocation lists received from authorities
+#[async_trait]
+pub trait RevocationListRepository: Send + Sync + 'static {
+    /// Get the latest revocation list stored for a given authority
+    async fn get_revocation_list(
+        &self,
+        authority: &Identifier,
+    ) -> Result<Option<RevocationListAndPurposeKey>>;
+
+    /// Put a revocation list for a given authority (overwriting).
+    /// The revoked subjects of the previous list are replaced with the ones in `data`
+    async fn put_revocation_list(
+        &self,
+        authority: &Identifier,
+        revocation_list: RevocationListAndPurposeKey,
+        data: &RevocationListData,
+    ) -> Result<()>;
+
+    /// Return the time before which the credentials of a subject are revoked by an authority,
+    /// if that subject was revoked
+    async fn get_revoked_at(
+        &self,
+        authority: &Identifier,
+        subject: &Identifier,
+    ) -> Result<Option<TimestampInSeconds>>;
+}
@@ -0,0 +1,225 @@
+use sqlx::*;
+use tracing::debug;
+
+use ockam_core::async_trait;
+use ockam_core::Result;
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToVoid};
+
+use crate::models::{Identifier, RevocationListAndPurposeKey, RevocationListData};
+use crate::{RevocationListRepository, TimestampInSeconds};
+
+/// Implementation of `RevocationListRepository` trait based on an underlying database
+/// using sqlx as its API, and Sqlite as its driver
+#[derive(Clone)]
+pub struct RevocationListSqlxDatabase {
+    database: SqlxDatabase,
+    node_name: String,
+}
+
+impl RevocationListSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase, node_name: &str) -> Self {
+        debug!("create a repository for revocation lists");
+        Self {
+            database,
+            node_name: node_name.to_string(),
+        }
+    }
+
+    /// Create a new in-memory database
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(
+            SqlxDatabase::in_memory("revocation list").await?,
+            "default",
+        ))
+    }
+}
+
+#[async_trait]
+impl RevocationListRepository for RevocationListSqlxDatabase {
+    async fn get_revocation_list(
+        &self,
+        authority: &Identifier,
+    ) -> Result<Option<RevocationListAndPurposeKey>> {
+        let query = query_as(
+            "SELECT revocation_list FROM revocation_list WHERE authority_identifier = $1 AND node_name = $2",
+        )
+        .bind(authority)
+        .bind(&self.node_name);
+        let row: Option<RevocationListRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        row.map(|r| r.revocation_list()).transpose()
+    }
+
+    async fn put_revocation_list(
+        &self,
+        authority: &Identifier,
+        revocation_list: RevocationListAndPurposeKey,
+        data: &RevocationListData,
+    ) -> Result<()> {
+        let mut transaction = self.database.begin().await.into_core()?;
+
+        let query1 = query(
+            r#"INSERT INTO revocation_list (authority_identifier, version, revocation_list, node_name)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (authority_identifier, node_name)
+            DO UPDATE SET version = $2, revocation_list = $3"#,
+        )
+        .bind(authority)
+        .bind(data.version as i64)
+        .bind(revocation_list.encode_as_cbor_bytes()?)
+        .bind(&self.node_name);
+        query1.execute(&mut *transaction).await.void()?;
+
+        let query2 =
+            query("DELETE FROM revoked_subject WHERE authority_identifier = $1 AND node_name = $2")
+                .bind(authority)
+                .bind(&self.node_name);
+        query2.execute(&mut *transaction).await.void()?;
+
+        for revoked_subject in data.revoked_subjects.iter() {
+            let query3 = query(
+                r#"INSERT INTO revoked_subject (authority_identifier, subject_identifier, revoked_at, node_name)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (authority_identifier, subject_identifier, node_name)
+                DO UPDATE SET revoked_at = $3"#,
+            )
+            .bind(authority)
+            .bind(&revoked_subject.subject)
+            .bind(revoked_subject.revoked_at)
+            .bind(&self.node_name);
+            query3.execute(&mut *transaction).await.void()?;
+        }
+
+        transaction.commit().await.void()
+    }
+
+    async fn get_revoked_at(
+        &self,
+        authority: &Identifier,
+        subject: &Identifier,
+    ) -> Result<Option<TimestampInSeconds>> {
+        let query = query_as(
+            "SELECT revoked_at FROM revoked_subject WHERE authority_identifier = $1 AND subject_identifier = $2 AND node_name = $3",
+        )
+        .bind(authority)
+        .bind(subject)
+        .bind(&self.node_name);
+        let row: Option<RevokedSubjectRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        Ok(row.map(|r| TimestampInSeconds(r.revoked_at as u64)))
+    }
+}
+
+// Low-level representation of a table row
+#[derive(FromRow)]
+struct RevocationListRow {
+    revocation_list: Vec<u8>,
+}
+
+impl RevocationListRow {
+    fn revocation_list(&self) -> Result<RevocationListAndPurposeKey> {
+        RevocationListAndPurposeKey::decode_from_cbor_bytes(&self.revocation_list)
+    }
+}
+
+#[derive(FromRow)]
+struct RevokedSubjectRow {
+    revoked_at: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use ockam_core::compat::sync::Arc;
+    use ockam_node::database::with_dbs;
+
+    use super::*;
+    use crate::identities;
+    use crate::models::RevokedSubject;
+
+    #[tokio::test]
+    async fn test_revocation_list_repository() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn RevocationListRepository> =
+                Arc::new(RevocationListSqlxDatabase::new(db.clone(), "node"));
+            let other_node_repository: Arc<dyn RevocationListRepository> =
+                Arc::new(RevocationListSqlxDatabase::new(db, "other_node"));
+
+            let identities = identities().await?;
+            let authority = identities.identities_creation().create_identity().await?;
+            let subject1 = identities.identities_creation().create_identity().await?;
+            let subject2 = identities.identities_creation().create_identity().await?;
+            let revocation_lists = identities.credentials().revocation_lists();
+
+            let result = repository.get_revocation_list(&authority).await?;
+            assert_eq!(result, None);
+
+            let revocation_list1 = revocation_lists
+                .issue_revocation_list(
+                    &authority,
+                    1,
+                    vec![RevokedSubject {
+                        subject: subject1.clone(),
+                        revoked_at: TimestampInSeconds(10),
+                    }],
+                )
+                .await?;
+            repository
+                .put_revocation_list(
+                    &authority,
+                    revocation_list1.clone(),
+                    &revocation_list1.get_revocation_list_data()?,
+                )
+                .await?;
+
+            let result = repository.get_revocation_list(&authority).await?;
+            assert_eq!(result, Some(revocation_list1));
+            let result = repository.get_revoked_at(&authority, &subject1).await?;
+            assert_eq!(result, Some(TimestampInSeconds(10)));
+
+            // the revocation lists are stored for each node
+            let result = other_node_repository
+                .get_revocation_list(&authority)
+                .await?;
+            assert_eq!(result, None);
+            let result = other_node_repository
+                .get_revoked_at(&authority, &subject1)
+                .await?;
+            assert_eq!(result, None);
+            let result = repository.get_revoked_at(&authority, &subject2).await?;
+            assert_eq!(result, None);
+
+            let revocation_list2 = revocation_lists
+                .issue_revocation_list(
+                    &authority,
+                    2,
+                    vec![RevokedSubject {
+                        subject: subject2.clone(),
+                        revoked_at: TimestampInSeconds(20),
+                    }],
+                )
+                .await?;
+            repository
+                .put_revocation_list(
+                    &authority,
+                    revocation_list2.clone(),
+                    &revocation_list2.get_revocation_list_data()?,
+                )
+                .await?;
+
+            let result = repository.get_revocation_list(&authority).await?;
+            assert_eq!(result, Some(revocation_list2));
+            let result = repository.get_revoked_at(&authority, &subject1).await?;
+            assert_eq!(result, None);
+            let result = repository.get_revoked_at(&authority, &subject2).await?;
+            assert_eq!(result, Some(TimestampInSeconds(20)));
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -2,7 +2,8 @@
 /// of the same Identity, that was known to us earlier
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum IdentityHistoryComparison {
-    /// No difference
+    /// No difference. Changes are compared by hash, so the same change may have been
+    /// assembled with a different set of threshold signatures
     Equal,
     /// Some changes don't match between current identity and known identity
     Conflict,
@@ -1,4 +1,7 @@
-use crate::models::{Change, ChangeData, ChangeHash, ChangeSignature, VersionedData};
+use crate::models::{
+    Change, ChangeData, ChangeHash, ChangeSignature, ThresholdChangeSignature, ThresholdPublicKeys,
+    VersionedData,
+};
 use crate::verified_change::VerifiedChange;
 use crate::{Identity, IdentityError};
 
@@ -66,6 +69,11 @@ impl Identity {
         for change in new_changes.iter() {
             let change_details = Self::get_change_details(change, vault.clone()).await?;
 
+            if let Some(threshold_public_keys) = &change_details.change_data.threshold_public_keys {
+                // The threshold must be reachable with a set of distinct keys
+                threshold_public_keys.check()?;
+            }
+
             if let Some(previous_change_details) = previous_change_details {
                 if previous_change_details.version > change_details.version {
                     // Version downgrade
@@ -150,7 +158,25 @@ impl Identity {
         let new_change_details = Self::get_change_details(new_change, vault.clone()).await?;
 
         if let Some(last_verified_change) = last_verified_change {
-            if let Some(previous_signature) = &new_change.previous_signature {
+            if let Some(previous_threshold_public_keys) =
+                &last_verified_change.data().threshold_public_keys
+            {
+                // The change must be authorized by a threshold of the keys of the previous change,
+                // the previous primary key alone can't authorize it
+                if new_change.previous_signature.is_some() {
+                    return Err(IdentityError::IdentityVerificationFailed)?;
+                }
+                Self::verify_threshold_signatures(
+                    previous_threshold_public_keys,
+                    new_change_details.change_full_hash,
+                    new_change.previous_threshold_signatures.as_deref(),
+                    vault.clone(),
+                )
+                .await?;
+            } else if let Some(previous_signature) = &new_change.previous_signature {
+                if new_change.previous_threshold_signatures.is_some() {
+                    return Err(IdentityError::IdentityVerificationFailed)?;
+                }
                 if !Self::verify_change_signature(
                     last_verified_change.primary_public_key(),
                     new_change_details.change_full_hash,
@@ -165,6 +191,9 @@ impl Identity {
                 // Previous signature should be present if it's not the first change
                 return Err(IdentityError::IdentityVerificationFailed)?;
             }
+        } else if new_change.previous_threshold_signatures.is_some() {
+            // There are no previous threshold keys for the first change
+            return Err(IdentityError::IdentityVerificationFailed)?;
         }
 
         if !Self::verify_change_signature(
@@ -175,13 +204,71 @@ impl Identity {
                 .into(),
             new_change_details.change_full_hash,
             &new_change.signature,
-            vault,
+            vault.clone(),
         )
         .await?
         {
             return Err(IdentityError::IdentityVerificationFailed)?;
         }
 
+        match &new_change_details.change_data.threshold_public_keys {
+            Some(threshold_public_keys) => {
+                Self::verify_threshold_signatures(
+                    threshold_public_keys,
+                    new_change_details.change_full_hash,
+                    new_change.threshold_signatures.as_deref(),
+                    vault,
+                )
+                .await?
+            }
+            None => {
+                if new_change.threshold_signatures.is_some() {
+                    return Err(IdentityError::IdentityVerificationFailed)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that all the signatures are valid and made by distinct keys of the
+    /// [`ThresholdPublicKeys`] set, and that there are at least `threshold` of them
+    async fn verify_threshold_signatures(
+        threshold_public_keys: &ThresholdPublicKeys,
+        hash: [u8; 32],
+        signatures: Option<&[ThresholdChangeSignature]>,
+        vault: Arc<dyn VaultForVerifyingSignatures>,
+    ) -> Result<()> {
+        let signatures = match signatures {
+            Some(signatures) => signatures,
+            None => return Err(IdentityError::IdentityVerificationFailed)?,
+        };
+
+        let mut signers: Vec<u8> = Vec::with_capacity(signatures.len());
+        for signature in signatures {
+            if signers.contains(&signature.key_index) {
+                return Err(IdentityError::IdentityVerificationFailed)?;
+            }
+            let public_key = match threshold_public_keys.get(signature.key_index) {
+                Some(public_key) => public_key,
+                None => return Err(IdentityError::IdentityVerificationFailed)?,
+            };
+            if !Self::verify_change_signature(
+                &public_key,
+                hash,
+                &signature.signature,
+                vault.clone(),
+            )
+            .await?
+            {
+                return Err(IdentityError::IdentityVerificationFailed)?;
+            }
+            signers.push(signature.key_index);
+        }
+
+        if signers.len() < threshold_public_keys.threshold as usize {
+            return Err(IdentityError::IdentityVerificationFailed)?;
+        }
         Ok(())
     }
 }
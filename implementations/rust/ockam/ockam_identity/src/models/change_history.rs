@@ -15,6 +15,13 @@ pub struct ChangeHistory(#[n(0)] pub Vec<Change>);
 /// `data_type` value in [`VersionedData`] struct when used with [`Change`]
 pub const CHANGE_DATA_TYPE: u8 = 1;
 
+/// `version` value in [`VersionedData`] struct when used with [`Change`]
+pub const CHANGE_VERSION: u8 = 1;
+
+/// `version` value in [`VersionedData`] struct when used with a [`Change`] of an Identity
+/// controlled by threshold keys, so that it is rejected by the verifiers not supporting them
+pub const THRESHOLD_CHANGE_VERSION: u8 = 2;
+
 /// Individual Identity change which implies replacing the old key
 #[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
 #[rustfmt::skip]
@@ -27,8 +34,14 @@ pub struct Change {
     /// Self-signature over the data using the key from this same [`Change`]
     #[n(1)] pub signature: ChangeSignature,
     /// Self-signature over the data using the key
-    /// from the previous [`Change`] in the [`ChangeHistory`]
+    /// from the previous [`Change`] in the [`ChangeHistory`].
+    /// It's absent if the previous [`Change`] is controlled by a threshold of keys
     #[n(2)] pub previous_signature: Option<ChangeSignature>,
+    /// Signatures over the data using the [`ThresholdPublicKeys`] of this same [`Change`]
+    #[n(3)] pub threshold_signatures: Option<Vec<ThresholdChangeSignature>>,
+    /// Signatures over the data using the [`ThresholdPublicKeys`]
+    /// of the previous [`Change`] in the [`ChangeHistory`]
+    #[n(4)] pub previous_threshold_signatures: Option<Vec<ThresholdChangeSignature>>,
 }
 
 /// [`Change`] signature
@@ -62,6 +75,54 @@ pub struct ChangeData {
     ///  1. Sign a [`super::PurposeKeyAttestation`] that is tied to this Identifier
     ///  2. Sign [`ChangeData`] that belongs to the same [`ChangeHistory`] and goes straight after this one
     #[n(4)] pub attestations_valid_until: TimestampInSeconds,
+    /// Optional set of keys controlling the [`ChangeHistory`] in addition to the Primary Public Key.
+    /// If present, this [`Change`] and the next one must be signed by at least
+    /// [`ThresholdPublicKeys::threshold`] of these keys
+    #[n(5)] pub threshold_public_keys: Option<ThresholdPublicKeys>,
+}
+
+/// Maximum number of keys in a [`ThresholdPublicKeys`] set
+pub const MAX_THRESHOLD_PUBLIC_KEYS: usize = 16;
+
+/// Set of keys, of which at least `threshold` must sign a [`Change`]
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct ThresholdPublicKeys {
+    /// Minimum number of signatures
+    #[n(0)] pub threshold: u8,
+    /// Public keys which can sign a [`Change`]
+    #[n(1)] pub public_keys: Vec<PrimaryPublicKey>,
+}
+
+/// [`Change`] signature made with one of the keys of a [`ThresholdPublicKeys`] set
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct ThresholdChangeSignature {
+    /// Index of the signing key in [`ThresholdPublicKeys::public_keys`]
+    #[n(0)] pub key_index: u8,
+    /// Signature over the [`Change`] data
+    #[n(1)] pub signature: ChangeSignature,
+}
+
+/// [`Change`] which is still collecting signatures.
+///
+/// When a [`Change`] must be signed by a threshold of keys, possibly held in different vaults,
+/// a [`PendingChange`] is exported and sent to the holders of these keys, who add their
+/// signature to it. The signatures are then assembled into a valid [`Change`]
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct PendingChange {
+    /// CBOR serialized [`super::VersionedData`] of the [`ChangeData`] to sign
+    #[cbor(with = "minicbor::bytes")]
+    #[n(0)] pub data: Vec<u8>,
+    /// Self-signature using the Primary Public Key of this [`Change`]
+    #[n(1)] pub signature: Option<ChangeSignature>,
+    /// Signature using the Primary Public Key of the previous [`Change`]
+    #[n(2)] pub previous_signature: Option<ChangeSignature>,
+    /// Signatures collected so far from the [`ThresholdPublicKeys`] of this [`Change`]
+    #[n(3)] pub threshold_signatures: Vec<ThresholdChangeSignature>,
+    /// Signatures collected so far from the [`ThresholdPublicKeys`] of the previous [`Change`]
+    #[n(4)] pub previous_threshold_signatures: Vec<ThresholdChangeSignature>,
 }
 
 /// [`Change`]'s public key
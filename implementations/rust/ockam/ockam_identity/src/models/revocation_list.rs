@@ -0,0 +1,89 @@
+use crate::models::{CredentialSignature, Identifier, PurposeKeyAttestation, TimestampInSeconds};
+use minicbor::{CborLen, Decode, Encode};
+use ockam_core::compat::string::String;
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+
+use crate::alloc::string::ToString;
+
+/// `data_type` value in [`VersionedData`] struct when used with [`RevocationList`]
+pub const REVOCATION_LIST_DATA_TYPE: u8 = 4;
+
+/// List of subjects whose credentials were revoked by an Authority
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct RevocationList {
+    /// CBOR serialized [`super::VersionedData`]
+    /// where VersionedData::data is CBOR serialized [`RevocationListData`]
+    /// and VersionedData::data_type is [`REVOCATION_LIST_DATA_TYPE`]
+    #[cbor(with = "minicbor::bytes")]
+    #[n(0)] pub data: Vec<u8>,
+    /// Signature over data field using corresponding Credentials [`super::PurposeKeyAttestation`]
+    #[n(1)] pub signature: CredentialSignature,
+}
+
+/// Data inside a [`RevocationList`]
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct RevocationListData {
+    /// Version of the list. A new version is published every time a subject is revoked,
+    /// a list with a lower version never replaces a list with a higher one
+    #[n(0)] pub version: u64,
+    /// Creation [`TimestampInSeconds`] (UTC)
+    #[n(1)] pub created_at: TimestampInSeconds,
+    /// Revoked subjects
+    #[n(2)] pub revoked_subjects: Vec<RevokedSubject>,
+}
+
+/// A subject for which all the credentials created before `revoked_at` are revoked
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct RevokedSubject {
+    /// Subject of the revoked credentials
+    #[n(0)] pub subject: Identifier,
+    /// Credentials created before that [`TimestampInSeconds`] (UTC) are revoked. A credential
+    /// created during that second, for a member added again right after its removal, is valid
+    #[n(1)] pub revoked_at: TimestampInSeconds,
+}
+
+/// [`RevocationList`] and the corresponding [`PurposeKeyAttestation`] that was used to sign that
+/// [`RevocationList`] and will be used to verify it
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct RevocationListAndPurposeKey {
+    /// [`RevocationList`]
+    #[n(0)] pub revocation_list: RevocationList,
+    /// Corresponding [`PurposeKeyAttestation`] that was used to sign that
+    /// [`RevocationList`] and will be used to verify it
+    #[n(1)] pub purpose_key_attestation: PurposeKeyAttestation,
+}
+
+impl RevocationListAndPurposeKey {
+    /// Encode the revocation list as a hex String
+    pub fn encode_as_string(&self) -> Result<String> {
+        Ok(hex::encode(self.encode_as_cbor_bytes()?))
+    }
+
+    /// Encode the revocation list as a CBOR bytes
+    pub fn encode_as_cbor_bytes(&self) -> Result<Vec<u8>> {
+        ockam_core::cbor_encode_preallocate(self)
+    }
+
+    /// Decode the revocation list from bytes
+    pub fn decode_from_cbor_bytes(bytes: &[u8]) -> Result<RevocationListAndPurposeKey> {
+        Ok(minicbor::decode(bytes)?)
+    }
+
+    /// Decode the revocation list from an hex string
+    pub fn decode_from_string(as_hex: &str) -> Result<RevocationListAndPurposeKey> {
+        let hex_decoded = hex::decode(as_hex.as_bytes())
+            .map_err(|e| Error::new(Origin::Api, Kind::Serialization, e.to_string()))?;
+        Self::decode_from_cbor_bytes(&hex_decoded)
+    }
+
+    /// Return the encoded revocation list data
+    pub fn get_revocation_list_data(&self) -> Result<RevocationListData> {
+        self.revocation_list.get_revocation_list_data()
+    }
+}
@@ -6,8 +6,9 @@ use ockam_vault::{Signature, VerifyingPublicKey};
 
 use crate::alloc::string::ToString;
 use crate::models::{
-    Change, ChangeData, ChangeHistory, ChangeSignature, PrimaryPublicKey, VersionedData,
-    CHANGE_DATA_TYPE,
+    Change, ChangeData, ChangeHistory, ChangeSignature, PendingChange, PrimaryPublicKey,
+    ThresholdPublicKeys, VersionedData, CHANGE_DATA_TYPE, CHANGE_VERSION,
+    MAX_THRESHOLD_PUBLIC_KEYS, THRESHOLD_CHANGE_VERSION,
 };
 use crate::IdentityError;
 
@@ -15,7 +16,17 @@ impl Change {
     /// Create [`VersionedData`] with corresponding version and data_type
     pub fn create_versioned_data(data: Vec<u8>) -> VersionedData {
         VersionedData {
-            version: 1,
+            version: CHANGE_VERSION,
+            data_type: CHANGE_DATA_TYPE,
+            data,
+        }
+    }
+
+    /// Create [`VersionedData`] for a change introducing threshold keys, or following
+    /// such a change
+    pub fn create_threshold_versioned_data(data: Vec<u8>) -> VersionedData {
+        VersionedData {
+            version: THRESHOLD_CHANGE_VERSION,
             data_type: CHANGE_DATA_TYPE,
             data,
         }
@@ -25,7 +36,9 @@ impl Change {
 impl ChangeData {
     /// Extract [`ChangeData`] from [`VersionedData`]
     pub fn get_data(versioned_data: &VersionedData) -> Result<Self> {
-        if versioned_data.version != 1 {
+        if versioned_data.version != CHANGE_VERSION
+            && versioned_data.version != THRESHOLD_CHANGE_VERSION
+        {
             return Err(IdentityError::UnknownIdentityVersion)?;
         }
 
@@ -33,7 +46,16 @@ impl ChangeData {
             return Err(IdentityError::InvalidIdentityDataType)?;
         }
 
-        Ok(minicbor::decode(&versioned_data.data)?)
+        let change_data: Self = minicbor::decode(&versioned_data.data)?;
+
+        // Threshold keys must not be ignored by the verifiers which don't support them
+        if change_data.threshold_public_keys.is_some()
+            && versioned_data.version != THRESHOLD_CHANGE_VERSION
+        {
+            return Err(IdentityError::IdentityVerificationFailed)?;
+        }
+
+        Ok(change_data)
     }
 }
 
@@ -62,6 +84,90 @@ impl ChangeHistory {
     }
 }
 
+impl ThresholdPublicKeys {
+    /// Create a set of keys, of which at least `threshold` must sign a [`Change`]
+    pub fn new(threshold: u8, public_keys: Vec<VerifyingPublicKey>) -> Result<Self> {
+        let threshold_public_keys = Self {
+            threshold,
+            public_keys: public_keys.into_iter().map(|k| k.into()).collect(),
+        };
+        threshold_public_keys.check()?;
+        Ok(threshold_public_keys)
+    }
+
+    /// Check that the threshold can be reached and that all the keys are distinct
+    pub fn check(&self) -> Result<()> {
+        if self.threshold == 0
+            || self.threshold as usize > self.public_keys.len()
+            || self.public_keys.len() > MAX_THRESHOLD_PUBLIC_KEYS
+        {
+            return Err(IdentityError::InvalidThreshold)?;
+        }
+        for (i, public_key) in self.public_keys.iter().enumerate() {
+            if self.public_keys[..i].contains(public_key) {
+                return Err(IdentityError::InvalidThreshold)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the index of a public key in this set
+    pub fn index_of(&self, public_key: &VerifyingPublicKey) -> Option<u8> {
+        self.public_keys
+            .iter()
+            .position(|k| &VerifyingPublicKey::from(k.clone()) == public_key)
+            .map(|i| i as u8)
+    }
+
+    /// Return the public key at a given index
+    pub fn get(&self, key_index: u8) -> Option<VerifyingPublicKey> {
+        self.public_keys
+            .get(key_index as usize)
+            .map(|k| k.clone().into())
+    }
+}
+
+impl PendingChange {
+    /// Create a [`PendingChange`] without any signature
+    pub fn new(data: Vec<u8>) -> Self {
+        Self {
+            data,
+            signature: None,
+            previous_signature: None,
+            threshold_signatures: Vec::new(),
+            previous_threshold_signatures: Vec::new(),
+        }
+    }
+
+    /// Return the [`ChangeData`] to sign
+    pub fn get_change_data(&self) -> Result<ChangeData> {
+        ChangeData::get_data(&minicbor::decode(&self.data)?)
+    }
+
+    /// Export [`PendingChange`] to a binary format using CBOR
+    pub fn export(&self) -> Result<Vec<u8>> {
+        ockam_core::cbor_encode_preallocate(self)
+    }
+
+    /// Export [`PendingChange`] to a hex encoded string
+    pub fn export_as_string(&self) -> Result<String> {
+        Ok(hex::encode(self.export()?))
+    }
+
+    /// Import [`PendingChange`] from a binary format using CBOR
+    pub fn import(data: &[u8]) -> Result<Self> {
+        Ok(minicbor::decode(data)?)
+    }
+
+    /// Import [`PendingChange`] from a hex-encoded string
+    pub fn import_from_string(data: &str) -> Result<Self> {
+        Self::import(
+            &hex::decode(data)
+                .map_err(|e| Error::new(Origin::Identity, Kind::Serialization, e.to_string()))?,
+        )
+    }
+}
+
 impl From<PrimaryPublicKey> for VerifyingPublicKey {
     fn from(value: PrimaryPublicKey) -> Self {
         match value {
@@ -115,6 +115,28 @@ impl PurposeKeyCreation {
         builder.build().await
     }
 
+    /// Create new Purpose Keys, attested by the latest key of an Identity, replacing the
+    /// existing ones. This must be done after the Identity key is rotated, since the
+    /// existing Purpose Keys are attested by the previous key and can't be verified anymore
+    pub async fn renew_purpose_keys(&self, identifier: &Identifier) -> Result<()> {
+        let repository = self.repository();
+        if repository
+            .get_purpose_key(identifier, Purpose::SecureChannel)
+            .await?
+            .is_some()
+        {
+            self.create_secure_channel_purpose_key(identifier).await?;
+        }
+        if repository
+            .get_purpose_key(identifier, Purpose::Credentials)
+            .await?
+            .is_some()
+        {
+            self.create_credential_purpose_key(identifier).await?;
+        }
+        Ok(())
+    }
+
     /// Attest a Purpose Key
     pub async fn attest_purpose_key(
         &self,
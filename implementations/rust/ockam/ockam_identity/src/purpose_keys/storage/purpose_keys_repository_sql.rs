@@ -1,8 +1,10 @@
 use core::str::FromStr;
 
+use sqlx::any::AnyArguments;
 use sqlx::encode::IsNull;
 use sqlx::error::BoxDynError;
 use sqlx::postgres::any::AnyArgumentBuffer;
+use sqlx::query::Query;
 use sqlx::*;
 use tracing::debug;
 
@@ -35,17 +37,14 @@ impl PurposeKeysSqlxDatabase {
     pub async fn create() -> Result<Self> {
         Ok(Self::new(SqlxDatabase::in_memory("purpose keys").await?))
     }
-}
 
-#[async_trait]
-impl PurposeKeysRepository for PurposeKeysSqlxDatabase {
-    async fn set_purpose_key(
-        &self,
-        subject: &Identifier,
+    /// Return the query storing a purpose key attestation, to execute it in a transaction
+    pub fn set_purpose_key_query<'a>(
+        subject: &'a Identifier,
         purpose: Purpose,
-        purpose_key_attestation: &PurposeKeyAttestation,
-    ) -> Result<()> {
-        let query = query(
+        purpose_key_attestation: &'a PurposeKeyAttestation,
+    ) -> Query<'a, Any, AnyArguments<'a>> {
+        query(
             r#"
             INSERT INTO purpose_key (identifier, purpose, purpose_key_attestation)
             VALUES ($1, $2, $3)
@@ -54,8 +53,22 @@ impl PurposeKeysRepository for PurposeKeysSqlxDatabase {
         )
         .bind(subject)
         .bind(purpose)
-        .bind(purpose_key_attestation);
-        query.execute(&*self.database.pool).await.void()
+        .bind(purpose_key_attestation)
+    }
+}
+
+#[async_trait]
+impl PurposeKeysRepository for PurposeKeysSqlxDatabase {
+    async fn set_purpose_key(
+        &self,
+        subject: &Identifier,
+        purpose: Purpose,
+        purpose_key_attestation: &PurposeKeyAttestation,
+    ) -> Result<()> {
+        Self::set_purpose_key_query(subject, purpose, purpose_key_attestation)
+            .execute(&*self.database.pool)
+            .await
+            .void()
     }
 
     async fn delete_purpose_key(&self, subject: &Identifier, purpose: Purpose) -> Result<()> {
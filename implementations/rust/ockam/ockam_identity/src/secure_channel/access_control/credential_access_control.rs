@@ -6,15 +6,17 @@ use ockam_core::Result;
 use ockam_core::{async_trait, RelayMessage};
 
 use crate::secure_channel::local_info::IdentitySecureChannelLocalInfo;
-use crate::{Identifier, IdentitiesAttributes};
+use crate::{Identifier, IdentitiesAttributes, RevocationLists};
 
 /// Access control checking that message senders have a specific set of attributes
+/// and that their credentials have not been revoked by the authority
 #[derive(Clone)]
 pub struct CredentialAccessControl {
     // FIXME: Can we use ABAC instead?
     required_attributes: Vec<(Vec<u8>, Vec<u8>)>,
     authority: Identifier,
     identities_attributes: Arc<IdentitiesAttributes>,
+    revocation_lists: Arc<RevocationLists>,
 }
 
 impl CredentialAccessControl {
@@ -23,11 +25,13 @@ impl CredentialAccessControl {
         required_attributes: &[(Vec<u8>, Vec<u8>)],
         authority: Identifier,
         identities_attributes: Arc<IdentitiesAttributes>,
+        revocation_lists: Arc<RevocationLists>,
     ) -> Self {
         Self {
             required_attributes: required_attributes.to_vec(),
             authority,
             identities_attributes,
+            revocation_lists,
         }
     }
 }
@@ -48,15 +52,28 @@ impl IncomingAccessControl for CredentialAccessControl {
         if let Ok(msg_identity_id) =
             IdentitySecureChannelLocalInfo::find_info(relay_message.local_message())
         {
+            let their_identity_id = msg_identity_id.their_identity_id();
             let attributes = match self
                 .identities_attributes
-                .get_attributes(&msg_identity_id.their_identity_id(), &self.authority)
+                .get_attributes(&their_identity_id, &self.authority)
                 .await?
             {
                 Some(a) => a,
                 None => return Ok(false), // No attributes for that Identity
             };
 
+            // the revocation applies to the credentials created before the revocation time
+            let created_at = attributes
+                .credential_created_at()
+                .unwrap_or(attributes.added_at());
+            if self
+                .revocation_lists
+                .is_revoked(&self.authority, &their_identity_id, created_at)
+                .await?
+            {
+                return Ok(false); // The credential for these attributes was revoked
+            }
+
             for required_attribute in self.required_attributes.iter() {
                 let attr_val = match attributes.attrs().get(&required_attribute.0) {
                     Some(v) => v,
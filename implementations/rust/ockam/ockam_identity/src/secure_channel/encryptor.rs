@@ -5,13 +5,18 @@ use ockam_core::{Error, Result};
 use ockam_vault::{AeadSecretKeyHandle, VaultForSecureChannels};
 use tracing_attributes::instrument;
 
-use crate::{Nonce, MAX_NONCE};
+use crate::secure_channel::rekey::{KeyUsage, RekeyEvent, RekeyEvents, RekeyPolicy, RekeyReason};
+use crate::utils::now;
+use crate::{IdentityError, Nonce, MAX_NONCE};
 
 pub(crate) struct Encryptor {
     key: AeadSecretKeyHandle,
     nonce: Nonce,
     vault: Arc<dyn VaultForSecureChannels>,
     rekeying: bool,
+    rekey_policy: RekeyPolicy,
+    rekey_events: RekeyEvents,
+    key_usage: Option<KeyUsage>,
 }
 
 // To simplify the implementation, we use the same constant for the size of the message
@@ -48,8 +53,33 @@ impl Encryptor {
         vault.convert_secret_buffer_to_aead_key(buffer).await
     }
 
+    /// If the rekey policy requires a new key, skip the remaining nonces of the current
+    /// interval, so that the next nonce starts a new interval and triggers a rekey.
+    /// The decryptor accepts a nonce up to one interval ahead, so it renews its key as well.
+    fn apply_rekey_policy(&mut self) -> Result<Option<RekeyReason>> {
+        if !self.rekeying {
+            return Ok(None);
+        }
+        let now = now()?;
+        let usage = self.key_usage.get_or_insert_with(|| KeyUsage::new(now));
+        let Some(reason) = self.rekey_policy.check(usage, now) else {
+            return Ok(None);
+        };
+
+        let nonce = self.nonce.value();
+        if nonce == 0 || nonce % KEY_RENEWAL_INTERVAL != 0 {
+            let next_interval_start = (nonce / KEY_RENEWAL_INTERVAL + 1)
+                .checked_mul(KEY_RENEWAL_INTERVAL)
+                .filter(|n| *n < MAX_NONCE.value())
+                .ok_or(IdentityError::InvalidNonce)?;
+            self.nonce = next_interval_start.into();
+        }
+        Ok(Some(reason))
+    }
+
     #[instrument(skip_all)]
     pub async fn encrypt(&mut self, destination: &mut Vec<u8>, payload: &[u8]) -> Result<()> {
+        let rekey_reason = self.apply_rekey_policy()?;
         let current_nonce = self.nonce;
 
         self.nonce.increment()?;
@@ -61,6 +91,19 @@ impl Encryptor {
             let new_key = Self::rekey(&self.vault, &self.key).await?;
             let old_key = core::mem::replace(&mut self.key, new_key);
             self.vault.delete_aead_secret_key(old_key).await?;
+
+            let timestamp = now()?;
+            self.key_usage = Some(KeyUsage::new(timestamp));
+            self.rekey_events.record(RekeyEvent {
+                timestamp,
+                reason: rekey_reason.unwrap_or(RekeyReason::Interval),
+                nonce: current_nonce.value(),
+            });
+        }
+
+        if let Some(usage) = self.key_usage.as_mut() {
+            usage.messages += 1;
+            usage.bytes += payload.len() as u64;
         }
 
         destination.extend_from_slice(&current_nonce.to_noise_nonce());
@@ -89,9 +132,24 @@ impl Encryptor {
             nonce,
             vault,
             rekeying,
+            rekey_policy: RekeyPolicy::default(),
+            rekey_events: RekeyEvents::default(),
+            key_usage: now().ok().map(KeyUsage::new),
         }
     }
 
+    /// Renew the key according to a [`RekeyPolicy`], on top of the regular renewal,
+    /// and record the renewals in the given [`RekeyEvents`]
+    pub(crate) fn with_rekey_policy(
+        mut self,
+        rekey_policy: RekeyPolicy,
+        rekey_events: RekeyEvents,
+    ) -> Self {
+        self.rekey_policy = rekey_policy;
+        self.rekey_events = rekey_events;
+        self
+    }
+
     #[instrument(skip_all)]
     pub(crate) async fn shutdown(&self) -> Result<()> {
         if !self.vault.delete_aead_secret_key(self.key.clone()).await? {
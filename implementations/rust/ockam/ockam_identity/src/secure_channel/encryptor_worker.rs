@@ -13,7 +13,7 @@ use ockam_core::{
 use ockam_core::{Any, Result, Routed, Worker};
 use ockam_node::Context;
 
-use crate::models::CredentialAndPurposeKey;
+use crate::models::{ChangeHistory, CredentialAndPurposeKey};
 use crate::secure_channel::addresses::Addresses;
 use crate::secure_channel::api::{EncryptionRequest, EncryptionResponse};
 use crate::secure_channel::encryptor::{Encryptor, SIZE_OF_ENCRYPT_OVERHEAD};
@@ -63,6 +63,7 @@ pub(crate) struct EncryptorWorker {
     change_history_repository: Arc<dyn ChangeHistoryRepository>,
     credential_retriever: Option<Arc<dyn CredentialRetriever>>,
     last_presented_credential: Option<CredentialAndPurposeKey>,
+    last_presented_change_history: Option<ChangeHistory>,
     shared_state: SecureChannelSharedState,
 }
 
@@ -88,6 +89,7 @@ impl EncryptorWorker {
             change_history_repository,
             credential_retriever,
             last_presented_credential,
+            last_presented_change_history: None,
             shared_state,
         }
     }
@@ -205,8 +207,9 @@ impl EncryptorWorker {
         Ok(())
     }
 
-    /// Asks credential retriever for a new credential and presents it to the other side, including
-    /// the latest change_history
+    /// Presents our latest change_history to the other side, together with a new credential if
+    /// there is a credential retriever. Nothing is sent if neither the credential nor the
+    /// change_history changed since they were last presented, for example after a key rotation
     #[instrument(skip_all)]
     async fn handle_refresh_credentials(&mut self, ctx: &<Self as Worker>::Context) -> Result<()> {
         debug!(
@@ -214,49 +217,39 @@ impl EncryptorWorker {
             self.addresses.encryptor
         );
 
-        let credential_retriever = match &self.credential_retriever {
-            Some(credential_retriever) => credential_retriever,
-            None => return Err(IdentityError::NoCredentialRetriever)?,
+        let credential = match &self.credential_retriever {
+            Some(credential_retriever) => match credential_retriever.retrieve().await {
+                Ok(credential) => Some(credential),
+                Err(err) => {
+                    error!(
+                        "Credentials refresh failed for {} with error={}",
+                        self.addresses.encryptor, err,
+                    );
+                    return Err(err);
+                }
+            },
+            None => None,
         };
 
-        let credential = match credential_retriever.retrieve().await {
-            Ok(credential) => credential,
-            Err(err) => {
-                error!(
-                    "Credentials refresh failed for {} with error={}",
-                    self.addresses.encryptor, err,
-                );
-                return Err(err);
-            }
-        };
+        let change_history = self.get_change_history().await?;
 
-        if Some(&credential) == self.last_presented_credential.as_ref() {
-            // Credential hasn't actually changed
+        let credential_changed =
+            credential.is_some() && credential != self.last_presented_credential;
+        let change_history_changed =
+            Some(&change_history) != self.last_presented_change_history.as_ref();
+
+        if !credential_changed && !change_history_changed {
+            // Neither the credential nor the change history have actually changed
             warn!(
-                "Credentials refresh for {} cancelled since credential hasn't changed",
+                "Credentials refresh for {} cancelled since neither the credential nor the change history have changed",
                 self.addresses.encryptor
             );
             return Ok(());
         }
 
-        let change_history = self
-            .change_history_repository
-            .get_change_history(&self.my_identifier)
-            .await?
-            .ok_or_else(|| {
-                Error::new(
-                    Origin::Api,
-                    Kind::NotFound,
-                    format!(
-                        "no change history found for identifier {}",
-                        self.my_identifier
-                    ),
-                )
-            })?;
-
         let msg = RefreshCredentialsMessage {
-            change_history,
-            credentials: vec![credential.clone()],
+            change_history: change_history.clone(),
+            credentials: credential.clone().into_iter().collect(),
         };
         let msg = SecureChannelMessage::RefreshCredentials(msg);
         let msg = Self::add_padding(msg);
@@ -277,11 +270,31 @@ impl EncryptorWorker {
         )
         .await?;
 
-        self.last_presented_credential = Some(credential);
+        if credential.is_some() {
+            self.last_presented_credential = credential;
+        }
+        self.last_presented_change_history = Some(change_history);
 
         Ok(())
     }
 
+    /// Return our current change history
+    async fn get_change_history(&self) -> Result<ChangeHistory> {
+        self.change_history_repository
+            .get_change_history(&self.my_identifier)
+            .await?
+            .ok_or_else(|| {
+                Error::new(
+                    Origin::Api,
+                    Kind::NotFound,
+                    format!(
+                        "no change history found for identifier {}",
+                        self.my_identifier
+                    ),
+                )
+            })
+    }
+
     async fn send_close_channel(&mut self, ctx: &Context) -> Result<()> {
         let msg = SecureChannelMessage::Close;
         let msg = Self::add_padding(msg);
@@ -325,6 +338,11 @@ impl Worker for EncryptorWorker {
             credential_retriever.subscribe(&self.addresses.encryptor_internal)?;
         }
 
+        if !self.key_exchange_only {
+            // This is the change history which was presented during the handshake
+            self.last_presented_change_history = Some(self.get_change_history().await?);
+        }
+
         Ok(())
     }
 
@@ -16,6 +16,8 @@ pub enum XXError {
     ExceededMaxMessageLen,
     /// Invalid internal state.
     InvalidInternalState,
+    /// The other party does not support the hybrid key exchange.
+    HybridKeyExchangeNotSupported,
 }
 
 impl StdError for XXError {}
@@ -29,6 +31,12 @@ impl fmt::Display for XXError {
                 write!(f, "exceeded maximum allowed message length for noise")
             }
             Self::InvalidInternalState => write!(f, "invalid internal state"),
+            Self::HybridKeyExchangeNotSupported => {
+                write!(
+                    f,
+                    "the other party does not support the hybrid key exchange"
+                )
+            }
         }
     }
 }
@@ -41,6 +49,7 @@ impl From<XXError> for Error {
             XXError::MessageLenMismatch => Kind::Misuse,
             XXError::ExceededMaxMessageLen => Kind::Invalid,
             XXError::InvalidInternalState => Kind::Internal,
+            XXError::HybridKeyExchangeNotSupported => Kind::Unsupported,
         };
 
         Error::new(Origin::KeyExchange, kind, err)
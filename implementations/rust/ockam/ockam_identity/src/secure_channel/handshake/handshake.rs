@@ -5,15 +5,18 @@ use ockam_core::compat::vec::Vec;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
 use ockam_vault::{
-    AeadSecretKeyHandle, HKDFNumberOfOutputs, SecretBufferHandle, VaultForSecureChannels,
-    X25519PublicKey, X25519SecretKeyHandle, X25519_PUBLIC_KEY_LENGTH,
+    AeadSecretKeyHandle, HKDFNumberOfOutputs, MlKem768Ciphertext, MlKem768EncapsulationKey,
+    MlKem768SecretKeyHandle, SecretBufferHandle, VaultForSecureChannels, X25519PublicKey,
+    X25519SecretKeyHandle, ML_KEM_768_CIPHERTEXT_LENGTH, ML_KEM_768_ENCAPSULATION_KEY_LENGTH,
+    X25519_PUBLIC_KEY_LENGTH,
 };
 use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
 use Status::*;
 
 use crate::secure_channel::handshake::error::XXError;
 use crate::secure_channel::handshake::handshake_state_machine::{HandshakeKeys, Status};
-use crate::secure_channel::Role;
+use crate::secure_channel::{HybridKeyExchange, Role};
 
 /// The number of bytes in a SHA256 digest
 pub const SHA256_SIZE: usize = 32;
@@ -21,14 +24,26 @@ pub const SHA256_SIZE: usize = 32;
 pub const AES_GCM_TAGSIZE: usize = 16;
 /// Maximum allowed noise message size
 pub const NOISE_MAX_MESSAGE_SIZE: usize = 65535;
+/// The number of bytes of an ML-KEM-768 ciphertext once encrypted
+const ENCRYPTED_ML_KEM_768_CIPHERTEXT_LENGTH: usize =
+    ML_KEM_768_CIPHERTEXT_LENGTH + AES_GCM_TAGSIZE;
 
 /// Implementation of a Handshake for the noise protocol
 /// The first members are used in the implementation of some of the protocol steps, for example to
 /// encrypt messages
 /// The variables used in the protocol itself: s, e, rs, re,... are handled in `HandshakeState`
+///
+/// When the hybrid key exchange is enabled, the initiator sends an ephemeral ML-KEM-768 public key
+/// 'ekem' as the payload of message 1. A responder supporting the hybrid key exchange encapsulates
+/// a shared secret for that key and sends the encrypted ciphertext right after its ephemeral key
+/// in message 2. Both parties then mix the shared secret into the chaining key.
+/// A responder not supporting it ignores the payload of message 1 and sends a regular message 2.
+/// Since the payload of message 1 is part of the handshake hash, removing 'ekem' makes the
+/// handshake fail.
 pub(super) struct Handshake {
     vault: Arc<dyn VaultForSecureChannels>,
     protocol_name: [u8; 32],
+    hybrid_key_exchange: HybridKeyExchange,
     pub(super) state: HandshakeState,
 }
 
@@ -56,9 +71,25 @@ impl Handshake {
         state.mix_hash(&e_pub_key.0);
         let mut message1 = e_pub_key.0.to_vec();
 
+        // output ekem.pubKey as the message 1 payload for a hybrid key exchange
+        let payload = if self.hybrid_key_exchange.is_enabled() {
+            if !payload.is_empty() {
+                return Err(XXError::InvalidInternalState)?;
+            }
+            let kem = self
+                .vault
+                .generate_ephemeral_ml_kem_768_secret_key()
+                .await?;
+            let kem_pub_key = self.vault.get_ml_kem_768_encapsulation_key(&kem).await?;
+            state.kem = Some(kem);
+            kem_pub_key.0.to_vec()
+        } else {
+            payload.to_vec()
+        };
+
         // output message 1 payload
-        message1.extend_from_slice(payload);
-        state.mix_hash(payload);
+        message1.extend_from_slice(&payload);
+        state.mix_hash(&payload);
 
         if message1.len() > NOISE_MAX_MESSAGE_SIZE {
             return Err(XXError::ExceededMaxMessageLen)?;
@@ -85,6 +116,16 @@ impl Handshake {
         let payload = Self::read_message1_payload(message1)?;
         state.mix_hash(payload);
 
+        // read ekem.pubKey if the initiator asks for a hybrid key exchange
+        if self.hybrid_key_exchange.is_enabled() {
+            if let Ok(kem_pub_key) = <[u8; ML_KEM_768_ENCAPSULATION_KEY_LENGTH]>::try_from(payload)
+            {
+                state.rkem = Some(MlKem768EncapsulationKey(kem_pub_key));
+            } else if self.hybrid_key_exchange.is_required() {
+                return Err(XXError::HybridKeyExchangeNotSupported)?;
+            }
+        }
+
         self.state = state;
         Ok(payload.to_vec())
     }
@@ -103,6 +144,16 @@ impl Handshake {
         let dh = self.dh(state.e()?, state.re()?).await?;
         self.hkdf(&mut state, dh).await?;
 
+        // for a hybrid key exchange, encrypt and output the ML-KEM ciphertext
+        // ck, k = HKDF(ck, ENCAPS(rkem), 2)
+        if let Some(rkem) = state.rkem.take() {
+            let (ciphertext, shared_secret) = self.vault.ml_kem_768_encapsulate(&rkem).await?;
+            let c = self.encrypt_and_hash(&mut state, &ciphertext.0).await?;
+            message2.extend_from_slice(c.as_slice());
+            self.hkdf(&mut state, shared_secret).await?;
+            state.is_hybrid = true;
+        }
+
         // encrypt and output s.pubKey
         let s_pub_key = self.get_public_key(state.s()?).await?;
         let c = self.encrypt_and_hash(&mut state, &s_pub_key.0).await?;
@@ -140,8 +191,27 @@ impl Handshake {
         let dh = self.dh(state.e()?, state.re()?).await?;
         self.hkdf(&mut state, dh).await?;
 
+        // for a hybrid key exchange, decrypt the ML-KEM ciphertext if the responder sent one
+        // ck, k = HKDF(ck, DECAPS(kem, ciphertext), 2)
+        let mut message2 = Self::read_message2_end(message2)?;
+        if state.kem.is_some() {
+            match self.decrypt_and_decapsulate(&mut state, message2).await? {
+                Some(shared_secret) => {
+                    self.hkdf(&mut state, shared_secret).await?;
+                    state.is_hybrid = true;
+                    message2 = &message2[ENCRYPTED_ML_KEM_768_CIPHERTEXT_LENGTH..];
+                }
+                None if self.hybrid_key_exchange.is_required() => {
+                    return Err(XXError::HybridKeyExchangeNotSupported)?;
+                }
+                None => {
+                    warn!("the responder does not support the hybrid key exchange, falling back to X25519 only");
+                }
+            }
+        }
+
         // decrypt rs.pubKey
-        let rs_pub_key = Self::read_message2_encrypted_key(message2)?;
+        let rs_pub_key = Self::read_encrypted_key(message2)?;
         let rs_pub_key = self.hash_and_decrypt(&mut state, rs_pub_key).await?;
         let rs_pub_key = X25519PublicKey(
             rs_pub_key
@@ -155,7 +225,7 @@ impl Handshake {
         self.hkdf(&mut state, dh).await?;
 
         // decrypt payload
-        let c = Self::read_message2_payload(message2)?;
+        let c = Self::read_encrypted_payload(message2)?;
         let payload = self.hash_and_decrypt(&mut state, c).await?;
 
         self.state = state;
@@ -196,7 +266,7 @@ impl Handshake {
 
         let mut state = self.state.clone();
         // decrypt rs key
-        let rs_pub_key = Self::read_message3_encrypted_key(message3)?;
+        let rs_pub_key = Self::read_encrypted_key(message3)?;
         let rs_pub_key = self.hash_and_decrypt(&mut state, rs_pub_key).await?;
         let rs_pub_key = X25519PublicKey(
             rs_pub_key
@@ -210,7 +280,7 @@ impl Handshake {
         self.hkdf(&mut state, dh).await?;
 
         // decrypt payload
-        let c = Self::read_message3_payload(message3)?;
+        let c = Self::read_encrypted_payload(message3)?;
         let payload = self.hash_and_decrypt(&mut state, c).await?;
         self.state = state;
         Ok(payload)
@@ -231,6 +301,9 @@ impl Handshake {
             encryption_key,
             decryption_key,
         });
+        if state.is_hybrid {
+            debug!("the handshake was completed with a hybrid X25519 and ML-KEM-768 key exchange");
+        }
         // now remove the ephemeral keys which are not useful anymore
         self.state = state;
         self.delete_ephemeral_keys().await?;
@@ -251,6 +324,7 @@ impl Handshake {
     pub(super) async fn new(
         vault: Arc<dyn VaultForSecureChannels>,
         static_key: X25519SecretKeyHandle,
+        hybrid_key_exchange: HybridKeyExchange,
     ) -> Result<Handshake> {
         // 1. generate an ephemeral key pair for this handshake and set it to e
         let ephemeral_key = Self::generate_ephemeral_key(vault.clone()).await?;
@@ -260,6 +334,7 @@ impl Handshake {
         Ok(Handshake {
             vault,
             protocol_name: *PROTOCOL_NAME,
+            hybrid_key_exchange,
             state: HandshakeState::new(static_key, ephemeral_key),
         })
     }
@@ -339,6 +414,35 @@ impl Handshake {
         Ok((k1, k2))
     }
 
+    /// Decrypt the ML-KEM ciphertext at the beginning of 'message' and decapsulate the shared secret
+    /// Return None if the message does not start with a ciphertext encrypted with the key 'k',
+    /// which is the case when the responder does not support the hybrid key exchange
+    async fn decrypt_and_decapsulate(
+        &self,
+        state: &mut HandshakeState,
+        message: &[u8],
+    ) -> Result<Option<SecretBufferHandle>> {
+        let Ok(c) = Self::read_encrypted_kem_ciphertext(message) else {
+            return Ok(None);
+        };
+        // the state is only modified if the decryption succeeds
+        let mut new_state = state.clone();
+        let Ok(ciphertext) = self.hash_and_decrypt(&mut new_state, c).await else {
+            return Ok(None);
+        };
+        let ciphertext = MlKem768Ciphertext(
+            ciphertext
+                .try_into()
+                .map_err(|_| XXError::MessageLenMismatch)?,
+        );
+        let shared_secret = self
+            .vault
+            .ml_kem_768_decapsulate(new_state.kem()?, &ciphertext)
+            .await?;
+        *state = new_state;
+        Ok(Some(shared_secret))
+    }
+
     /// Decrypt a ciphertext 'c' using the key 'k' and the additional data 'h'
     async fn hash_and_decrypt(&self, state: &mut HandshakeState, c: &[u8]) -> Result<Vec<u8>> {
         let mut nonce = [0u8; 12];
@@ -375,6 +479,13 @@ impl Handshake {
             .delete_ephemeral_x25519_secret_key(self.state.take_e()?)
             .await?;
 
+        if let Some(kem) = self.state.kem.take() {
+            _ = self
+                .vault
+                .delete_ephemeral_ml_kem_768_secret_key(kem)
+                .await?;
+        }
+
         Ok(())
     }
 }
@@ -408,26 +519,26 @@ impl Handshake {
         Self::read_end::<X25519_PUBLIC_KEY_LENGTH>(message)
     }
 
-    /// Read the message 2 encrypted key, which is present after the public key
-    fn read_message2_encrypted_key(message: &[u8]) -> Result<&[u8]> {
-        const L: usize = X25519_PUBLIC_KEY_LENGTH + AES_GCM_TAGSIZE;
-        Self::read_middle::<X25519_PUBLIC_KEY_LENGTH, L>(message)
+    /// Read the end of message 2, which is present after the public key
+    fn read_message2_end(message: &[u8]) -> Result<&[u8]> {
+        Self::read_end::<X25519_PUBLIC_KEY_LENGTH>(message)
     }
 
-    /// Read the message 2 encrypted payload, which is present after the encrypted key
-    fn read_message2_payload(message: &[u8]) -> Result<&[u8]> {
-        const L: usize = 2 * X25519_PUBLIC_KEY_LENGTH + AES_GCM_TAGSIZE;
-        Self::read_end::<L>(message)
+    /// Read the encrypted ML-KEM ciphertext at the beginning of the end of message 2
+    fn read_encrypted_kem_ciphertext(message: &[u8]) -> Result<&[u8]> {
+        Ok(Self::read_start::<ENCRYPTED_ML_KEM_768_CIPHERTEXT_LENGTH>(
+            message,
+        )?)
     }
 
-    /// Read the message 3 encrypted key at the beginning of the message
-    fn read_message3_encrypted_key(message: &[u8]) -> Result<&[u8]> {
+    /// Read the encrypted key at the beginning of message 3, or of the end of message 2
+    fn read_encrypted_key(message: &[u8]) -> Result<&[u8]> {
         const L: usize = X25519_PUBLIC_KEY_LENGTH + AES_GCM_TAGSIZE;
         Ok(Self::read_start::<L>(message)?)
     }
 
-    /// Read the message 3 payload which is present after the encrypted key
-    fn read_message3_payload(message: &[u8]) -> Result<&[u8]> {
+    /// Read the encrypted payload which is present after the encrypted key
+    fn read_encrypted_payload(message: &[u8]) -> Result<&[u8]> {
         const L: usize = X25519_PUBLIC_KEY_LENGTH + AES_GCM_TAGSIZE;
         Self::read_end::<L>(message)
     }
@@ -450,15 +561,6 @@ impl Handshake {
         Ok(message[N..].try_into().unwrap())
     }
 
-    /// Read 'length' bytes of the message after the first 'drop_length' bytes
-    fn read_middle<const N: usize, const L: usize>(message: &[u8]) -> Result<&[u8]> {
-        if message.len() < N + L {
-            return Err(XXError::MessageLenMismatch)?;
-        }
-
-        Ok(message[N..(N + L)].try_into().unwrap())
-    }
-
     /// Read the bytes of a key at the beginning of a message
     fn read_key(message: &[u8]) -> Result<&[u8; X25519_PUBLIC_KEY_LENGTH]> {
         Self::read_start::<X25519_PUBLIC_KEY_LENGTH>(message)
@@ -473,6 +575,9 @@ pub(super) struct HandshakeState {
     k: Option<AeadSecretKeyHandle>,
     re: Option<X25519PublicKey>,
     pub(super) rs: Option<X25519PublicKey>,
+    kem: Option<MlKem768SecretKeyHandle>,
+    rkem: Option<MlKem768EncapsulationKey>,
+    is_hybrid: bool,
     n: u64,
     h: [u8; SHA256_SIZE],
     ck: Option<SecretBufferHandle>,
@@ -491,6 +596,9 @@ impl HandshakeState {
             k: None,
             re: None,
             rs: None,
+            kem: None,
+            rkem: None,
+            is_hybrid: false,
             n: 0,
             h: [0u8; SHA256_SIZE],
             ck: None,
@@ -561,6 +669,16 @@ impl HandshakeState {
         })
     }
 
+    pub(super) fn kem(&self) -> Result<&MlKem768SecretKeyHandle> {
+        self.kem.as_ref().ok_or_else(|| {
+            Error::new(
+                Origin::KeyExchange,
+                Kind::Invalid,
+                "key id kem should have been set",
+            )
+        })
+    }
+
     pub(super) fn k(&self) -> Result<&AeadSecretKeyHandle> {
         self.k.as_ref().ok_or_else(|| {
             Error::new(
@@ -677,6 +795,61 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_hybrid_key_exchange() -> Result<()> {
+        let vault = SoftwareVaultForSecureChannels::create().await?;
+        let (initiator, responder) = run_handshake(
+            vault.clone(),
+            HybridKeyExchange::Required,
+            HybridKeyExchange::Preferred,
+        )
+        .await?;
+
+        assert!(initiator.state.is_hybrid);
+        assert!(responder.state.is_hybrid);
+        check_keys(vault.clone(), &initiator, &responder).await?;
+        assert_eq!(vault.number_of_ephemeral_ml_kem_768_secrets(), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_key_exchange_fallback() -> Result<()> {
+        let vault = SoftwareVaultForSecureChannels::create().await?;
+        let (initiator, responder) = run_handshake(
+            vault.clone(),
+            HybridKeyExchange::Preferred,
+            HybridKeyExchange::Disabled,
+        )
+        .await?;
+
+        assert!(!initiator.state.is_hybrid);
+        assert!(!responder.state.is_hybrid);
+        check_keys(vault.clone(), &initiator, &responder).await?;
+        assert_eq!(vault.number_of_ephemeral_ml_kem_768_secrets(), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hybrid_key_exchange_required() -> Result<()> {
+        let vault = SoftwareVaultForSecureChannels::create().await?;
+        let result = run_handshake(
+            vault.clone(),
+            HybridKeyExchange::Required,
+            HybridKeyExchange::Disabled,
+        )
+        .await;
+        assert!(result.is_err());
+
+        let result = run_handshake(
+            vault.clone(),
+            HybridKeyExchange::Disabled,
+            HybridKeyExchange::Required,
+        )
+        .await;
+        assert!(result.is_err());
+        Ok(())
+    }
+
     // --------------------
     // TESTS IMPLEMENTATION
     // --------------------
@@ -758,6 +931,82 @@ mod tests {
         Ok(())
     }
 
+    async fn run_handshake(
+        vault: Arc<SoftwareVaultForSecureChannels>,
+        initiator_hybrid_key_exchange: HybridKeyExchange,
+        responder_hybrid_key_exchange: HybridKeyExchange,
+    ) -> Result<(Handshake, Handshake)> {
+        let initiator_static_key = vault.generate_static_x25519_secret_key().await?;
+        let mut initiator = Handshake::new(
+            vault.clone(),
+            initiator_static_key,
+            initiator_hybrid_key_exchange,
+        )
+        .await?;
+
+        let responder_static_key = vault.generate_static_x25519_secret_key().await?;
+        let mut responder = Handshake::new(
+            vault.clone(),
+            responder_static_key,
+            responder_hybrid_key_exchange,
+        )
+        .await?;
+        initiator.initialize().await?;
+        responder.initialize().await?;
+
+        let message1 = initiator.encode_message1(&[]).await?;
+        responder.decode_message1(&message1).await?;
+        let message2 = responder.encode_message2(b"responder").await?;
+        assert_eq!(initiator.decode_message2(&message2).await?, b"responder");
+        let message3 = initiator.encode_message3(b"initiator").await?;
+        assert_eq!(responder.decode_message3(&message3).await?, b"initiator");
+
+        initiator.set_final_state(Role::Initiator).await?;
+        responder.set_final_state(Role::Responder).await?;
+        Ok((initiator, responder))
+    }
+
+    async fn check_keys(
+        vault: Arc<SoftwareVaultForSecureChannels>,
+        initiator: &Handshake,
+        responder: &Handshake,
+    ) -> Result<()> {
+        let initiator_keys = initiator.get_handshake_keys().unwrap();
+        let responder_keys = responder.get_handshake_keys().unwrap();
+        let nonce = [0u8; 12];
+
+        let mut ciphertext = Vec::new();
+        vault
+            .aead_encrypt(
+                &mut ciphertext,
+                &initiator_keys.encryption_key,
+                b"hello",
+                &nonce,
+                &[],
+            )
+            .await?;
+        let plaintext = vault
+            .aead_decrypt(&responder_keys.decryption_key, &ciphertext, &nonce, &[])
+            .await?;
+        assert_eq!(plaintext, b"hello");
+
+        let mut ciphertext = Vec::new();
+        vault
+            .aead_encrypt(
+                &mut ciphertext,
+                &responder_keys.encryption_key,
+                b"hello",
+                &nonce,
+                &[],
+            )
+            .await?;
+        let plaintext = vault
+            .aead_decrypt(&initiator_keys.decryption_key, &ciphertext, &nonce, &[])
+            .await?;
+        assert_eq!(plaintext, b"hello");
+        Ok(())
+    }
+
     impl Handshake {
         /// Initialize the handshake
         async fn new_with_keys(
@@ -769,6 +1018,7 @@ mod tests {
             Ok(Handshake {
                 vault,
                 protocol_name,
+                hybrid_key_exchange: HybridKeyExchange::Disabled,
                 state: HandshakeState::new(static_key, ephemeral_key),
             })
         }
@@ -787,6 +1037,7 @@ mod tests {
             Ok(Handshake {
                 vault,
                 protocol_name,
+                hybrid_key_exchange: HybridKeyExchange::Disabled,
                 state: HandshakeState::new(static_key, ephemeral_key),
             })
         }
@@ -30,7 +30,7 @@ use crate::secure_channel::handshake::handshake_state_machine::{
 };
 use crate::secure_channel::handshake::initiator_state_machine::InitiatorStateMachine;
 use crate::secure_channel::handshake::responder_state_machine::ResponderStateMachine;
-use crate::secure_channel::{Addresses, Role};
+use crate::secure_channel::{Addresses, HybridKeyExchange, RekeyEvents, RekeyPolicy, Role};
 use crate::{
     ChangeHistoryRepository, CredentialRetriever, IdentityError, PersistedSecureChannel,
     SecureChannelPurposeKey, SecureChannelRegistryEntry, SecureChannelRepository, SecureChannels,
@@ -47,6 +47,7 @@ pub(crate) struct HandshakeWorker {
     addresses: Addresses,
     role: Role,
     key_exchange_only: bool,
+    rekey_policy: RekeyPolicy,
     remote_route: Option<Route>,
     decryptor_handler: Option<DecryptorHandler>,
 
@@ -68,6 +69,10 @@ impl Worker for HandshakeWorker {
     /// Initialize the state machine with an `Initialize` event
     /// Depending on the state machine role there might be a message to send to the other party
     async fn initialize(&mut self, context: &mut Self::Context) -> Result<()> {
+        self.secure_channels
+            .secure_channel_registry
+            .handshake_started();
+
         if let Some(credential_retriever) = &self.credential_retriever {
             credential_retriever.initialize().await?;
         }
@@ -112,7 +117,13 @@ impl Worker for HandshakeWorker {
         if self.decryptor_handler.is_some() {
             self.handle_decrypt(context, message).await
         } else {
-            self.handle_handshake(context, message).await
+            let result = self.handle_handshake(context, message).await;
+            if result.is_err() {
+                self.secure_channels
+                    .secure_channel_registry
+                    .handshake_failed();
+            }
+            result
         }
     }
 
@@ -147,11 +158,14 @@ impl HandshakeWorker {
         timeout: Option<Duration>,
         role: Role,
         key_exchange_only: bool,
+        rekey_policy: RekeyPolicy,
+        hybrid_key_exchange: HybridKeyExchange,
         secure_channel_repository: Option<Arc<dyn SecureChannelRepository>>,
         encryptor_remote_route: Arc<RwLock<RemoteRoute>>,
     ) -> Result<Option<Identifier>> {
         let vault = secure_channels.identities.vault().secure_channel_vault;
         let identities = secure_channels.identities();
+        let secure_channel_registry = secure_channels.secure_channel_registry();
 
         let state_machine: Box<dyn StateMachine> = if role.is_initiator() {
             Box::new(
@@ -163,6 +177,7 @@ impl HandshakeWorker {
                     credential_retriever.clone(),
                     trust_policy,
                     authority.clone(),
+                    hybrid_key_exchange,
                 )
                 .await?,
             )
@@ -176,6 +191,7 @@ impl HandshakeWorker {
                     credential_retriever.clone(),
                     trust_policy,
                     authority.clone(),
+                    hybrid_key_exchange,
                 )
                 .await?,
             )
@@ -199,6 +215,7 @@ impl HandshakeWorker {
             my_identifier: my_identifier.clone(),
             role,
             key_exchange_only,
+            rekey_policy,
             remote_route: remote_route.clone(),
             addresses: addresses.clone(),
             decryptor_handler: None,
@@ -233,6 +250,7 @@ impl HandshakeWorker {
                     match res {
                         Ok(their_identifier) => Some(their_identifier),
                         Err(err) => {
+                            secure_channel_registry.handshake_failed();
                             error!(
                             "Timeout {:?} or error reached when creating secure channel for: {}. Encryptor: {}. Error: {err:?}",
                             timeout, my_identifier, addresses.encryptor
@@ -395,6 +413,8 @@ impl HandshakeWorker {
             self.shared_state.clone(),
         );
 
+        let rekey_events = RekeyEvents::default();
+
         // create a separate encryptor worker which will be started independently
         {
             let (rekeying, credential_retriever) = if self.key_exchange_only {
@@ -414,7 +434,8 @@ impl HandshakeWorker {
                     0.into(),
                     self.secure_channels.identities.vault().secure_channel_vault,
                     rekeying,
-                ),
+                )
+                .with_rekey_policy(self.rekey_policy.clone(), rekey_events.clone()),
                 self.my_identifier.clone(),
                 self.change_history_repository.clone(),
                 credential_retriever,
@@ -477,17 +498,19 @@ impl HandshakeWorker {
         let info = SecureChannelRegistryEntry::new(
             self.addresses.encryptor.clone(),
             self.addresses.encryptor_api.clone(),
+            self.addresses.encryptor_internal.clone(),
             self.addresses.decryptor_remote.clone(),
             self.addresses.decryptor_api.clone(),
             self.role.is_initiator(),
             self.my_identifier.clone(),
             handshake_results.their_identifier,
             their_decryptor_address,
+            rekey_events,
         );
 
-        self.secure_channels
-            .secure_channel_registry()
-            .register_channel(info)?;
+        let registry = self.secure_channels.secure_channel_registry();
+        registry.register_channel(info)?;
+        registry.handshake_completed();
 
         Ok(decryptor)
     }
@@ -566,6 +589,8 @@ impl HandshakeWorker {
             addresses,
             role,
             key_exchange_only,
+            // only used when the encryptor is created after a handshake
+            rekey_policy: RekeyPolicy::default(),
             remote_route,
             decryptor_handler,
             authority,
@@ -17,7 +17,9 @@ use crate::secure_channel::handshake::handshake_state_machine::{
     Action, CommonStateMachine, Event, HandshakeKeys, HandshakeResults, IdentityAndCredentials,
     StateMachine, Status,
 };
-use crate::{CredentialRetriever, Identities, Role, SecureChannelPurposeKey, TrustPolicy};
+use crate::{
+    CredentialRetriever, HybridKeyExchange, Identities, Role, SecureChannelPurposeKey, TrustPolicy,
+};
 
 /// Implementation of a state machine for the key exchange on the initiator side
 #[async_trait]
@@ -97,6 +99,7 @@ impl InitiatorStateMachine {
 }
 
 impl InitiatorStateMachine {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         vault: Arc<dyn VaultForSecureChannels>,
         identities: Arc<Identities>,
@@ -105,6 +108,7 @@ impl InitiatorStateMachine {
         credential_retriever: Option<Arc<dyn CredentialRetriever>>,
         trust_policy: Arc<dyn TrustPolicy>,
         authority: Option<Identifier>,
+        hybrid_key_exchange: HybridKeyExchange,
     ) -> Result<InitiatorStateMachine> {
         let common = CommonStateMachine::new(
             identities,
@@ -117,7 +121,8 @@ impl InitiatorStateMachine {
 
         Ok(InitiatorStateMachine {
             common,
-            handshake: Handshake::new(vault, purpose_key.key().clone()).await?,
+            handshake: Handshake::new(vault, purpose_key.key().clone(), hybrid_key_exchange)
+                .await?,
         })
     }
 }
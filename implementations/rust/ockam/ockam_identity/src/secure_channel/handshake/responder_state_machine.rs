@@ -17,7 +17,9 @@ use crate::secure_channel::handshake::handshake_state_machine::{
     Action, CommonStateMachine, Event, HandshakeKeys, HandshakeResults, IdentityAndCredentials,
     StateMachine, Status,
 };
-use crate::{CredentialRetriever, Identities, Role, SecureChannelPurposeKey, TrustPolicy};
+use crate::{
+    CredentialRetriever, HybridKeyExchange, Identities, Role, SecureChannelPurposeKey, TrustPolicy,
+};
 
 /// Implementation of a state machine for the key exchange on the responder side
 #[async_trait]
@@ -100,6 +102,7 @@ impl ResponderStateMachine {
 }
 
 impl ResponderStateMachine {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         vault: Arc<dyn VaultForSecureChannels>,
         identities: Arc<Identities>,
@@ -108,6 +111,7 @@ impl ResponderStateMachine {
         credential_retriever: Option<Arc<dyn CredentialRetriever>>,
         trust_policy: Arc<dyn TrustPolicy>,
         authority: Option<Identifier>,
+        hybrid_key_exchange: HybridKeyExchange,
     ) -> Result<ResponderStateMachine> {
         let common = CommonStateMachine::new(
             identities,
@@ -120,7 +124,8 @@ impl ResponderStateMachine {
 
         Ok(ResponderStateMachine {
             common,
-            handshake: Handshake::new(vault, purpose_key.key().clone()).await?,
+            handshake: Handshake::new(vault, purpose_key.key().clone(), hybrid_key_exchange)
+                .await?,
         })
     }
 }
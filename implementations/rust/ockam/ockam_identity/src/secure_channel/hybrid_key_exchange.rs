@@ -0,0 +1,44 @@
+use core::fmt::{Display, Formatter};
+
+/// Use of a post-quantum hybrid key exchange during the secure channel handshake.
+///
+/// In the hybrid mode, the X25519 Diffie-Hellman keys of the Noise XX handshake are complemented
+/// with an ML-KEM-768 (Kyber768) shared secret, encapsulated by the responder for an ephemeral
+/// key sent by the initiator in the first handshake message.
+/// The resulting secure channel keys remain secret as long as either X25519 or ML-KEM-768 is not broken.
+///
+/// The hybrid mode is negotiated during the handshake: peers which don't support it
+/// ignore the initiator ML-KEM key and complete an X25519-only handshake.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HybridKeyExchange {
+    /// Only use X25519
+    #[default]
+    Disabled,
+    /// Use the hybrid key exchange if the other side supports it, otherwise fall back to X25519 only
+    Preferred,
+    /// Use the hybrid key exchange and fail the handshake if the other side doesn't support it
+    Required,
+}
+
+impl HybridKeyExchange {
+    /// Return true if the hybrid key exchange can be used
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self, HybridKeyExchange::Disabled)
+    }
+
+    /// Return true if the handshake must fail when the hybrid key exchange can't be used
+    pub fn is_required(&self) -> bool {
+        matches!(self, HybridKeyExchange::Required)
+    }
+}
+
+impl Display for HybridKeyExchange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let s = match self {
+            HybridKeyExchange::Disabled => "disabled",
+            HybridKeyExchange::Preferred => "preferred",
+            HybridKeyExchange::Required => "required",
+        };
+        write!(f, "{s}")
+    }
+}
@@ -111,6 +111,8 @@ impl Worker for SecureChannelListenerWorker {
             None,
             Role::Responder,
             self.options.key_exchange_only,
+            self.options.rekey_policy.clone(),
+            self.options.hybrid_key_exchange,
             self.secure_channel_repository.clone(),
             RemoteRoute::create(),
         )
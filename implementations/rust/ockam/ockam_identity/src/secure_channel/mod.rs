@@ -6,6 +6,7 @@ mod decryptor;
 mod encryptor;
 mod encryptor_worker;
 pub(crate) mod handshake;
+mod hybrid_key_exchange;
 mod key_tracker;
 mod listener;
 mod local_info;
@@ -14,6 +15,7 @@ mod nonce;
 mod nonce_tracker;
 mod options;
 mod registry;
+mod rekey;
 mod role;
 
 /// List of trust policies to setup ABAC controls
@@ -25,18 +27,23 @@ pub use api::*;
 pub(crate) use decryptor::*;
 pub(crate) use encryptor_worker::*;
 pub(crate) use handshake::*;
+pub use hybrid_key_exchange::*;
 pub(crate) use listener::*;
 pub use local_info::*;
 pub use message::*;
 pub use nonce::*;
 pub use options::*;
 pub use registry::*;
+pub use rekey::*;
 pub(crate) use role::*;
 pub use trust_policy::*;
 
 #[cfg(test)]
 mod tests {
+    use crate::secure_channel::encryptor::KEY_RENEWAL_INTERVAL;
     use crate::secure_channel::{decryptor::Decryptor, encryptor::Encryptor};
+    use crate::{RekeyEvents, RekeyPolicy, RekeyReason};
+    use core::time::Duration;
     use ockam_core::compat::rand::RngCore;
     use ockam_core::Result;
     use ockam_vault::{SoftwareVaultForSecureChannels, VaultForSecureChannels};
@@ -132,6 +139,78 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_encrypt_decrypt_with_max_messages() {
+        let (encryptor, mut decryptor) = create_encryptor_decryptor().await.unwrap();
+        let rekey_events = RekeyEvents::default();
+        let mut encryptor = encryptor.with_rekey_policy(
+            RekeyPolicy::new().with_max_messages(5),
+            rekey_events.clone(),
+        );
+
+        for n in 0..20 {
+            let msg = vec![n; 10];
+            let mut ciphertext = Vec::new();
+            encryptor.encrypt(&mut ciphertext, &msg).await.unwrap();
+            assert_eq!(msg, decryptor.decrypt(&ciphertext).await.unwrap().0);
+        }
+
+        // the key is renewed every 5 messages
+        assert_eq!(rekey_events.count(), 3);
+        let events = rekey_events.last_events();
+        assert!(events.iter().all(|e| e.reason == RekeyReason::MaxMessages));
+        assert_eq!(
+            events.iter().map(|e| e.nonce).collect::<Vec<_>>(),
+            vec![
+                KEY_RENEWAL_INTERVAL,
+                2 * KEY_RENEWAL_INTERVAL,
+                3 * KEY_RENEWAL_INTERVAL
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_decrypt_with_max_bytes() {
+        let (encryptor, mut decryptor) = create_encryptor_decryptor().await.unwrap();
+        let rekey_events = RekeyEvents::default();
+        let mut encryptor = encryptor
+            .with_rekey_policy(RekeyPolicy::new().with_max_bytes(64), rekey_events.clone());
+
+        for n in 0..3 {
+            let msg = vec![n; 40];
+            let mut ciphertext = Vec::new();
+            encryptor.encrypt(&mut ciphertext, &msg).await.unwrap();
+            assert_eq!(msg, decryptor.decrypt(&ciphertext).await.unwrap().0);
+        }
+
+        // the third message is encrypted with a new key
+        assert_eq!(rekey_events.count(), 1);
+        assert_eq!(rekey_events.last_events()[0].reason, RekeyReason::MaxBytes);
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_decrypt_with_max_key_age() {
+        let (encryptor, mut decryptor) = create_encryptor_decryptor().await.unwrap();
+        let rekey_events = RekeyEvents::default();
+        let mut encryptor = encryptor.with_rekey_policy(
+            RekeyPolicy::new().with_max_key_age(Duration::from_secs(0)),
+            rekey_events.clone(),
+        );
+
+        // every message is encrypted with a new key
+        for n in 0..5 {
+            let msg = vec![n];
+            let mut ciphertext = Vec::new();
+            encryptor.encrypt(&mut ciphertext, &msg).await.unwrap();
+            assert_eq!(msg, decryptor.decrypt(&ciphertext).await.unwrap().0);
+        }
+        assert_eq!(rekey_events.count(), 5);
+        assert!(rekey_events
+            .last_events()
+            .iter()
+            .all(|e| e.reason == RekeyReason::MaxKeyAge));
+    }
+
     async fn create_encryptor_decryptor() -> Result<(Encryptor, Decryptor)> {
         let vault1 = SoftwareVaultForSecureChannels::create().await?;
         let vault2 = SoftwareVaultForSecureChannels::create().await?;
@@ -6,7 +6,7 @@ use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl,
 use ockam_core::{Address, OutgoingAccessControl, Result};
 
 use crate::models::CredentialAndPurposeKey;
-use crate::secure_channel::Addresses;
+use crate::secure_channel::{Addresses, HybridKeyExchange, RekeyPolicy};
 use crate::{
     CredentialRetrieverCreator, Identifier, IdentityError, MemoryCredentialRetrieverCreator,
     TrustEveryonePolicy, TrustPolicy,
@@ -37,6 +37,8 @@ pub struct SecureChannelOptions {
     pub(crate) key_exchange_only: bool,
     // Secure Channel will be persisted (currently only supported for key_exchange_only = true)
     pub(crate) is_persistent: bool,
+    pub(crate) rekey_policy: RekeyPolicy,
+    pub(crate) hybrid_key_exchange: HybridKeyExchange,
 }
 
 impl fmt::Debug for SecureChannelOptions {
@@ -57,6 +59,8 @@ impl SecureChannelOptions {
             timeout: DEFAULT_TIMEOUT,
             key_exchange_only: false,
             is_persistent: false,
+            rekey_policy: RekeyPolicy::default(),
+            hybrid_key_exchange: HybridKeyExchange::default(),
         }
     }
 
@@ -97,6 +101,19 @@ impl SecureChannelOptions {
         self
     }
 
+    /// Renew the encryption key according to the given [`RekeyPolicy`], on top of
+    /// the regular renewal every 32 messages
+    pub fn with_rekey_policy(mut self, rekey_policy: RekeyPolicy) -> Self {
+        self.rekey_policy = rekey_policy;
+        self
+    }
+
+    /// Complement the X25519 key exchange with ML-KEM-768, see [`HybridKeyExchange`]
+    pub fn with_hybrid_key_exchange(mut self, hybrid_key_exchange: HybridKeyExchange) -> Self {
+        self.hybrid_key_exchange = hybrid_key_exchange;
+        self
+    }
+
     /// Freshly generated [`FlowControlId`]
     pub fn producer_flow_control_id(&self) -> FlowControlId {
         self.flow_control_id.clone()
@@ -185,6 +202,8 @@ pub struct SecureChannelListenerOptions {
     pub(crate) key_exchange_only: bool,
     // Secure Channel will be persisted (currently only supported for key_exchange_only = true)
     pub(crate) is_persistent: bool,
+    pub(crate) rekey_policy: RekeyPolicy,
+    pub(crate) hybrid_key_exchange: HybridKeyExchange,
 }
 
 impl fmt::Debug for SecureChannelListenerOptions {
@@ -207,6 +226,8 @@ impl SecureChannelListenerOptions {
             credential_retriever_creator: None,
             key_exchange_only: false,
             is_persistent: false,
+            rekey_policy: RekeyPolicy::default(),
+            hybrid_key_exchange: HybridKeyExchange::default(),
         }
     }
 
@@ -250,6 +271,19 @@ impl SecureChannelListenerOptions {
         self
     }
 
+    /// Renew the encryption key according to the given [`RekeyPolicy`], on top of
+    /// the regular renewal every 32 messages
+    pub fn with_rekey_policy(mut self, rekey_policy: RekeyPolicy) -> Self {
+        self.rekey_policy = rekey_policy;
+        self
+    }
+
+    /// Complement the X25519 key exchange with ML-KEM-768, see [`HybridKeyExchange`]
+    pub fn with_hybrid_key_exchange(mut self, hybrid_key_exchange: HybridKeyExchange) -> Self {
+        self.hybrid_key_exchange = hybrid_key_exchange;
+        self
+    }
+
     /// Freshly generated [`FlowControlId`]
     pub fn spawner_flow_control_id(&self) -> FlowControlId {
         self.flow_control_id.clone()
@@ -1,9 +1,11 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
 use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::sync::{Arc, RwLock};
 use ockam_core::compat::vec::Vec;
 use ockam_core::{Address, Result};
 
 use crate::models::Identifier;
+use crate::secure_channel::RekeyEvents;
 use crate::IdentityError;
 
 /// Known information about particular SecureChannel
@@ -11,12 +13,14 @@ use crate::IdentityError;
 pub struct SecureChannelRegistryEntry {
     encryptor_messaging_address: Address,
     encryptor_api_address: Address,
+    encryptor_internal_address: Address,
     decryptor_messaging_address: Address,
     decryptor_api_address: Address,
     is_initiator: bool,
     my_id: Identifier,
     their_id: Identifier,
     their_decryptor_address: Address,
+    rekey_events: RekeyEvents,
 }
 
 impl SecureChannelRegistryEntry {
@@ -25,22 +29,26 @@ impl SecureChannelRegistryEntry {
     pub fn new(
         encryptor_messaging_address: Address,
         encryptor_api_address: Address,
+        encryptor_internal_address: Address,
         decryptor_messaging_address: Address,
         decryptor_api_address: Address,
         is_initiator: bool,
         my_id: Identifier,
         their_id: Identifier,
         their_decryptor_address: Address,
+        rekey_events: RekeyEvents,
     ) -> Self {
         Self {
             encryptor_messaging_address,
             encryptor_api_address,
+            encryptor_internal_address,
             decryptor_messaging_address,
             decryptor_api_address,
             is_initiator,
             my_id,
             their_id,
             their_decryptor_address,
+            rekey_events,
         }
     }
 
@@ -54,6 +62,12 @@ impl SecureChannelRegistryEntry {
         &self.encryptor_api_address
     }
 
+    /// Encryptor internal address, used to ask the encryptor to present
+    /// our latest credential and change history to the other side
+    pub fn encryptor_internal_address(&self) -> &Address {
+        &self.encryptor_internal_address
+    }
+
     /// Decryptor messaging address
     pub fn decryptor_messaging_address(&self) -> &Address {
         &self.decryptor_messaging_address
@@ -83,6 +97,31 @@ impl SecureChannelRegistryEntry {
     pub fn their_decryptor_address(&self) -> Address {
         self.their_decryptor_address.clone()
     }
+
+    /// Renewals of the encryption key of this channel
+    pub fn rekey_events(&self) -> &RekeyEvents {
+        &self.rekey_events
+    }
+}
+
+/// Statistics about the secure channels of a node
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SecureChannelStats {
+    /// Number of secure channels currently open
+    pub open_channels: u64,
+    /// Number of handshakes started since the registry was created
+    pub handshakes_started: u64,
+    /// Number of handshakes which successfully created a secure channel
+    pub handshakes_completed: u64,
+    /// Number of handshakes which failed or timed out
+    pub handshakes_failed: u64,
+}
+
+#[derive(Default)]
+struct HandshakeCounters {
+    started: AtomicUsize,
+    completed: AtomicUsize,
+    failed: AtomicUsize,
 }
 
 /// Registry of all known Secure Channels
@@ -90,6 +129,7 @@ impl SecureChannelRegistryEntry {
 pub struct SecureChannelRegistry {
     // Encryptor address is used as a key
     registry: Arc<RwLock<BTreeMap<Address, SecureChannelRegistryEntry>>>,
+    handshakes: Arc<HandshakeCounters>,
 }
 
 impl SecureChannelRegistry {
@@ -97,6 +137,7 @@ impl SecureChannelRegistry {
     pub fn new() -> Self {
         Self {
             registry: Default::default(),
+            handshakes: Default::default(),
         }
     }
 }
@@ -154,4 +195,26 @@ impl SecureChannelRegistry {
             .find(|(_, entry)| entry.decryptor_messaging_address == *decryptor_address)
             .map(|(_, entry)| entry.clone())
     }
+
+    /// Return the number of open channels and handshakes
+    pub fn stats(&self) -> SecureChannelStats {
+        SecureChannelStats {
+            open_channels: self.registry.read().unwrap().len() as u64,
+            handshakes_started: self.handshakes.started.load(Ordering::Relaxed) as u64,
+            handshakes_completed: self.handshakes.completed.load(Ordering::Relaxed) as u64,
+            handshakes_failed: self.handshakes.failed.load(Ordering::Relaxed) as u64,
+        }
+    }
+
+    pub(crate) fn handshake_started(&self) {
+        self.handshakes.started.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn handshake_completed(&self) {
+        self.handshakes.completed.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn handshake_failed(&self) {
+        self.handshakes.failed.fetch_add(1, Ordering::Relaxed);
+    }
 }
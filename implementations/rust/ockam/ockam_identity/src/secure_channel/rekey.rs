@@ -0,0 +1,179 @@
+use core::fmt;
+use core::fmt::{Display, Formatter};
+use core::time::Duration;
+
+use ockam_core::compat::collections::VecDeque;
+use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::compat::vec::Vec;
+
+use crate::models::TimestampInSeconds;
+
+/// Maximum number of rekey events kept for each secure channel
+pub const MAX_REKEY_EVENTS: usize = 16;
+
+/// Policy deciding when the encryption key of a secure channel is renewed.
+///
+/// Independently of this policy, the key is always renewed every 32 messages.
+/// The policy allows to renew the key earlier, when any of its limits is reached.
+/// The limits are checked before a message is encrypted, so an idle channel renews its key
+/// when its next message is sent.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RekeyPolicy {
+    max_key_age: Option<Duration>,
+    max_messages: Option<u64>,
+    max_bytes: Option<u64>,
+}
+
+impl RekeyPolicy {
+    /// Policy only renewing the key every 32 messages
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Renew the key once it has been in use for the given duration
+    pub fn with_max_key_age(mut self, max_key_age: Duration) -> Self {
+        self.max_key_age = Some(max_key_age);
+        self
+    }
+
+    /// Renew the key once it has encrypted the given number of messages
+    pub fn with_max_messages(mut self, max_messages: u64) -> Self {
+        self.max_messages = Some(max_messages);
+        self
+    }
+
+    /// Renew the key once it has encrypted the given number of bytes
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Maximum duration a key is used for
+    pub fn max_key_age(&self) -> Option<Duration> {
+        self.max_key_age
+    }
+
+    /// Maximum number of messages encrypted with a key
+    pub fn max_messages(&self) -> Option<u64> {
+        self.max_messages
+    }
+
+    /// Maximum number of bytes encrypted with a key
+    pub fn max_bytes(&self) -> Option<u64> {
+        self.max_bytes
+    }
+
+    /// Return the reason to renew a key given its usage, if any limit is reached
+    pub(crate) fn check(&self, usage: &KeyUsage, now: TimestampInSeconds) -> Option<RekeyReason> {
+        if let Some(max_key_age) = self.max_key_age {
+            if now.0.saturating_sub(usage.created_at.0) >= max_key_age.as_secs() {
+                return Some(RekeyReason::MaxKeyAge);
+            }
+        }
+        if let Some(max_messages) = self.max_messages {
+            if usage.messages >= max_messages {
+                return Some(RekeyReason::MaxMessages);
+            }
+        }
+        if let Some(max_bytes) = self.max_bytes {
+            if usage.bytes >= max_bytes {
+                return Some(RekeyReason::MaxBytes);
+            }
+        }
+        None
+    }
+}
+
+/// Usage of the current encryption key
+#[derive(Clone, Debug)]
+pub(crate) struct KeyUsage {
+    pub(crate) created_at: TimestampInSeconds,
+    pub(crate) messages: u64,
+    pub(crate) bytes: u64,
+}
+
+impl KeyUsage {
+    pub(crate) fn new(created_at: TimestampInSeconds) -> Self {
+        Self {
+            created_at,
+            messages: 0,
+            bytes: 0,
+        }
+    }
+}
+
+/// Reason why the encryption key of a secure channel was renewed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RekeyReason {
+    /// The key encrypted 32 messages
+    Interval,
+    /// The key reached the maximum age of the [`RekeyPolicy`]
+    MaxKeyAge,
+    /// The key reached the maximum number of messages of the [`RekeyPolicy`]
+    MaxMessages,
+    /// The key reached the maximum number of bytes of the [`RekeyPolicy`]
+    MaxBytes,
+}
+
+impl Display for RekeyReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            RekeyReason::Interval => "interval",
+            RekeyReason::MaxKeyAge => "max-key-age",
+            RekeyReason::MaxMessages => "max-messages",
+            RekeyReason::MaxBytes => "max-bytes",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// Renewal of the encryption key of a secure channel
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RekeyEvent {
+    /// When the key was renewed
+    pub timestamp: TimestampInSeconds,
+    /// Why the key was renewed
+    pub reason: RekeyReason,
+    /// First nonce encrypted with the new key
+    pub nonce: u64,
+}
+
+/// Rekey events of a secure channel, shared between its encryptor and the
+/// [`SecureChannelRegistry`](crate::SecureChannelRegistry)
+#[derive(Clone, Debug, Default)]
+pub struct RekeyEvents {
+    inner: Arc<RwLock<RekeyEventsState>>,
+}
+
+#[derive(Debug, Default)]
+struct RekeyEventsState {
+    count: u64,
+    last_events: VecDeque<RekeyEvent>,
+}
+
+impl RekeyEvents {
+    /// Total number of key renewals
+    pub fn count(&self) -> u64 {
+        self.inner.read().unwrap().count
+    }
+
+    /// Most recent key renewals, oldest first. At most [`MAX_REKEY_EVENTS`] are kept
+    pub fn last_events(&self) -> Vec<RekeyEvent> {
+        self.inner
+            .read()
+            .unwrap()
+            .last_events
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    pub(crate) fn record(&self, event: RekeyEvent) {
+        let mut state = self.inner.write().unwrap();
+        state.count += 1;
+        if state.last_events.len() == MAX_REKEY_EVENTS {
+            state.last_events.pop_front();
+        }
+        state.last_events.push_back(event);
+    }
+}
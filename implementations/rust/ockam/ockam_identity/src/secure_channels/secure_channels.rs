@@ -1,13 +1,14 @@
 use core::sync::atomic::AtomicBool;
 use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
 use ockam_core::flow_control::FlowControls;
 use ockam_core::Result;
-use ockam_core::{Address, Route};
+use ockam_core::{route, Address, Route};
 use ockam_node::{Context, WorkerBuilder};
-use tracing::info;
+use tracing::{debug, info, warn};
 
 use crate::identities::Identities;
-use crate::models::Identifier;
+use crate::models::{Identifier, RevocationListAndPurposeKey};
 use crate::secure_channel::handshake_worker::HandshakeWorker;
 use crate::secure_channel::{
     Addresses, DecryptorHandler, RemoteRoute, Role, SecureChannelListenerOptions,
@@ -17,7 +18,7 @@ use crate::secure_channel::{
 #[cfg(feature = "storage")]
 use crate::SecureChannelsBuilder;
 use crate::{
-    IdentityError, SecureChannel, SecureChannelListener, SecureChannelRegistryEntry,
+    IdentityError, RekeyEvents, SecureChannel, SecureChannelListener, SecureChannelRegistryEntry,
     SecureChannelRepository, Vault,
 };
 
@@ -172,6 +173,8 @@ impl SecureChannels {
             Some(options.timeout),
             Role::Initiator,
             options.key_exchange_only,
+            options.rekey_policy,
+            options.hybrid_key_exchange,
             secure_channel_repository,
             encryptor_remote_route.clone(),
         )
@@ -278,12 +281,14 @@ impl SecureChannels {
         let info = SecureChannelRegistryEntry::new(
             addresses.encryptor.clone(),
             addresses.encryptor_api.clone(),
+            addresses.encryptor_internal.clone(),
             addresses.decryptor_remote.clone(),
             addresses.decryptor_api.clone(),
             role.is_initiator(),
             my_identifier.clone(),
             their_identifier.clone(),
             Address::random_local(), // Random, unused for now
+            RekeyEvents::default(),
         );
 
         self.secure_channel_registry.register_channel(info)?;
@@ -295,4 +300,79 @@ impl SecureChannels {
     pub async fn stop_secure_channel(&self, ctx: &Context, channel: &Address) -> Result<()> {
         ctx.stop_worker(channel.clone()).await
     }
+
+    /// Verify and store a [`RevocationListAndPurposeKey`] received from an authority.
+    /// Then stop all the secure channels established with a subject newly revoked by that list.
+    /// Return the newly revoked subjects.
+    pub async fn receive_revocation_list(
+        &self,
+        ctx: &Context,
+        authority: &Identifier,
+        revocation_list: RevocationListAndPurposeKey,
+    ) -> Result<Vec<Identifier>> {
+        let revoked = self
+            .identities
+            .credentials()
+            .revocation_lists()
+            .receive_revocation_list(authority, revocation_list)
+            .await?;
+
+        for channel in self.secure_channel_registry.get_channel_list() {
+            if revoked.contains(channel.their_id()) {
+                info!(
+                    "stopping the secure channel {} with the revoked identity {}",
+                    channel.encryptor_messaging_address(),
+                    channel.their_id()
+                );
+                if let Err(e) = self
+                    .stop_secure_channel(ctx, channel.encryptor_messaging_address())
+                    .await
+                {
+                    warn!(
+                        "could not stop the secure channel {}: {e:?}",
+                        channel.encryptor_messaging_address()
+                    );
+                }
+            }
+        }
+
+        Ok(revoked)
+    }
+
+    /// Present the latest change history of an identity to the other side of all the
+    /// secure channels established with that identity, for example after a key rotation.
+    /// Return the number of secure channels which were notified.
+    pub async fn present_change_history(
+        &self,
+        ctx: &Context,
+        identifier: &Identifier,
+    ) -> Result<usize> {
+        let mut notified = 0;
+        for channel in self.secure_channel_registry.get_channel_list() {
+            if channel.my_id() != identifier {
+                continue;
+            }
+            match ctx
+                .send(route![channel.encryptor_internal_address().clone()], ())
+                .await
+            {
+                Ok(_) => {
+                    debug!(
+                        "presenting the change history of {} on the secure channel {}",
+                        identifier,
+                        channel.encryptor_messaging_address()
+                    );
+                    notified += 1;
+                }
+                Err(e) => {
+                    warn!(
+                        "could not present the change history on the secure channel {}: {e:?}",
+                        channel.encryptor_messaging_address()
+                    );
+                }
+            }
+        }
+
+        Ok(notified)
+    }
 }
@@ -8,8 +8,8 @@ use crate::purpose_keys::storage::PurposeKeysRepository;
 use crate::secure_channel::SecureChannelRegistry;
 use crate::secure_channels::SecureChannels;
 use crate::{
-    CredentialRepository, IdentitiesBuilder, IdentityAttributesRepository, SecureChannelRepository,
-    Vault,
+    CredentialRepository, IdentitiesBuilder, IdentityAttributesRepository,
+    RevocationListRepository, SecureChannelRepository, Vault,
 };
 
 /// This struct supports all the services related to secure channels
@@ -84,6 +84,17 @@ impl SecureChannelsBuilder {
         self
     }
 
+    /// Set a specific revocation lists repository
+    pub fn with_revocation_list_repository(
+        mut self,
+        repository: Arc<dyn RevocationListRepository>,
+    ) -> Self {
+        self.identities_builder = self
+            .identities_builder
+            .with_revocation_list_repository(repository);
+        self
+    }
+
     /// Set a specific secure channels repository
     pub fn with_secure_channel_repository(
         mut self,
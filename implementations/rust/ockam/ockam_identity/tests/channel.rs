@@ -7,9 +7,9 @@ use ockam_identity::models::{CredentialSchemaIdentifier, Identifier};
 use ockam_identity::secure_channels::secure_channels;
 use ockam_identity::utils::AttributesBuilder;
 use ockam_identity::{
-    DecryptionResponse, EncryptionRequest, EncryptionResponse, IdentityAccessControlBuilder,
-    IdentitySecureChannelLocalInfo, SecureChannelListenerOptions, SecureChannelOptions,
-    SecureChannels, TrustEveryonePolicy, TrustIdentifierPolicy, Vault,
+    DecryptionResponse, EncryptionRequest, EncryptionResponse, HybridKeyExchange,
+    IdentityAccessControlBuilder, IdentitySecureChannelLocalInfo, SecureChannelListenerOptions,
+    SecureChannelOptions, SecureChannels, TrustEveryonePolicy, TrustIdentifierPolicy, Vault,
     IDENTITY_SECURE_CHANNEL_IDENTIFIER,
 };
 use ockam_node::{Context, MessageReceiveOptions, WorkerBuilder};
@@ -212,6 +212,59 @@ async fn test_channel_send_credentials(context: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn test_channel_stats(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &bob,
+            "bob_listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+    let alice_channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["bob_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+
+    // once a message has been received, both sides of the channel are registered
+    child_ctx
+        .send(
+            route![alice_channel, child_ctx.address()],
+            "Hello, Bob!".to_string(),
+        )
+        .await?;
+    child_ctx.receive::<String>().await?;
+
+    let stats = secure_channels.secure_channel_registry().stats();
+    assert_eq!(stats.open_channels, 2);
+    assert_eq!(stats.handshakes_started, 2);
+    assert_eq!(stats.handshakes_completed, 2);
+    assert_eq!(stats.handshakes_failed, 0);
+
+    Ok(())
+}
+
 #[ockam_macros::test]
 async fn test_channel_rejected_trust_policy(ctx: &mut Context) -> Result<()> {
     let secure_channels = secure_channels().await?;
@@ -269,6 +322,87 @@ async fn test_channel_rejected_trust_policy(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn test_channel_hybrid_key_exchange(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_options =
+        SecureChannelListenerOptions::new().with_hybrid_key_exchange(HybridKeyExchange::Preferred);
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(ctx, &bob, "bob_listener", bob_options)
+        .await?;
+
+    let alice_options =
+        SecureChannelOptions::new().with_hybrid_key_exchange(HybridKeyExchange::Required);
+    let alice_channel = secure_channels
+        .create_secure_channel(ctx, &alice, route!["bob_listener"], alice_options)
+        .await?;
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+
+    child_ctx
+        .send(
+            route![alice_channel, child_ctx.address()],
+            "Hello, Bob!".to_string(),
+        )
+        .await?;
+
+    let msg = child_ctx.receive::<String>().await?;
+    assert_eq!("Hello, Bob!", msg.into_body()?);
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn test_channel_hybrid_key_exchange_not_supported(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &bob,
+            "bob_listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+
+    // the hybrid key exchange is preferred: fall back to X25519 only
+    let alice_options =
+        SecureChannelOptions::new().with_hybrid_key_exchange(HybridKeyExchange::Preferred);
+    let result = secure_channels
+        .create_secure_channel(ctx, &alice, route!["bob_listener"], alice_options)
+        .await;
+    assert!(result.is_ok());
+
+    // the hybrid key exchange is required: fail the handshake
+    let alice_options = SecureChannelOptions::new()
+        .with_hybrid_key_exchange(HybridKeyExchange::Required)
+        .with_timeout(Duration::from_millis(500));
+    let result = secure_channels
+        .create_secure_channel(ctx, &alice, route!["bob_listener"], alice_options)
+        .await;
+    assert!(result.is_err());
+
+    Ok(())
+}
+
 #[ockam_macros::test]
 async fn test_channel_send_multiple_messages_both_directions(ctx: &mut Context) -> Result<()> {
     let secure_channels = secure_channels().await?;
@@ -1134,3 +1268,102 @@ async fn address_metadata__encryptor__should_be_terminal(ctx: &mut Context) -> R
 
     Ok(())
 }
+
+#[ockam_macros::test]
+async fn test_channel_present_change_history_after_rotation(ctx: &mut Context) -> Result<()> {
+    let alice_secure_channels = secure_channels().await?;
+    let bob_secure_channels = secure_channels().await?;
+
+    let alice = alice_secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let bob = bob_secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+
+    bob_secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &bob,
+            "bob_listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+    alice_secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["bob_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+    ctx.sleep(Duration::from_millis(250)).await;
+
+    let alice_change_history_repository = alice_secure_channels
+        .identities()
+        .change_history_repository();
+    let bob_change_history_repository =
+        bob_secure_channels.identities().change_history_repository();
+
+    // Bob knows the change history presented by Alice during the handshake
+    let known_by_bob = bob_change_history_repository
+        .get_change_history(&alice)
+        .await?;
+    assert_eq!(
+        known_by_bob,
+        alice_change_history_repository
+            .get_change_history(&alice)
+            .await?
+    );
+
+    alice_secure_channels
+        .identities()
+        .identities_creation()
+        .rotate_identity(&alice)
+        .await?;
+    let rotated = alice_change_history_repository
+        .get_change_history(&alice)
+        .await?;
+    assert_ne!(known_by_bob, rotated);
+
+    let notified = alice_secure_channels
+        .present_change_history(ctx, &alice)
+        .await?;
+    assert_eq!(notified, 1);
+
+    ctx.sleep(Duration::from_millis(250)).await;
+
+    // Bob now knows the rotated change history
+    let known_by_bob = bob_change_history_repository
+        .get_change_history(&alice)
+        .await?;
+    assert_eq!(known_by_bob, rotated);
+
+    // Only the secure channels established by Alice are notified
+    let notified = bob_secure_channels
+        .present_change_history(ctx, &alice)
+        .await?;
+    assert_eq!(notified, 0);
+
+    // Alice can still establish secure channels with the purpose key attested by the new key
+    alice_secure_channels
+        .identities()
+        .purpose_keys()
+        .purpose_keys_creation()
+        .renew_purpose_keys(&alice)
+        .await?;
+    alice_secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["bob_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+
+    Ok(())
+}
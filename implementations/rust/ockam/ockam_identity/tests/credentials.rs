@@ -4,9 +4,9 @@ use std::time::Duration;
 use ockam_core::compat::sync::Arc;
 use ockam_core::{async_trait, Any, DenyAll};
 use ockam_core::{route, Result, Routed, Worker};
-use ockam_identity::models::CredentialSchemaIdentifier;
+use ockam_identity::models::{CredentialSchemaIdentifier, RevokedSubject};
 use ockam_identity::secure_channels::secure_channels;
-use ockam_identity::utils::AttributesBuilder;
+use ockam_identity::utils::{now, AttributesBuilder};
 use ockam_identity::{
     CredentialAccessControl, SecureChannelListenerOptions, SecureChannelOptions,
     TrustIdentifierPolicy,
@@ -216,8 +216,12 @@ async fn access_control(ctx: &mut Context) -> Result<()> {
     };
 
     let required_attributes = vec![(b"is_superuser".to_vec(), b"true".to_vec())];
-    let access_control =
-        CredentialAccessControl::new(&required_attributes, authority, identities_attributes);
+    let access_control = CredentialAccessControl::new(
+        &required_attributes,
+        authority,
+        identities_attributes,
+        credentials.revocation_lists(),
+    );
 
     ctx.flow_controls()
         .add_consumer("counter", listener.flow_control_id());
@@ -244,6 +248,110 @@ async fn access_control(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn revoked_credential(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities = secure_channels.identities();
+    let identities_creation = identities.identities_creation();
+    let identities_attributes = identities.identities_attributes();
+    let credentials = identities.credentials();
+
+    let authority = identities_creation.create_identity().await?;
+    let server = identities_creation.create_identity().await?;
+    let client = identities_creation.create_identity().await?;
+
+    let options = SecureChannelListenerOptions::new().with_authority(authority.clone());
+    let listener = secure_channels
+        .create_secure_channel_listener(ctx, &server, "listener", options)
+        .await?;
+
+    let credential = credentials
+        .credentials_creation()
+        .issue_credential(
+            &authority,
+            &client,
+            AttributesBuilder::with_schema(CredentialSchemaIdentifier(0))
+                .with_attribute("is_superuser", "true")
+                .build(),
+            Duration::from_secs(60 * 60),
+        )
+        .await?;
+    let channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &client,
+            route!["listener"],
+            SecureChannelOptions::new()
+                .with_trust_policy(TrustIdentifierPolicy::new(server.clone()))
+                .with_credential(credential.clone())?,
+        )
+        .await?;
+
+    let counter = Arc::new(AtomicI8::new(0));
+    let worker = CountingWorker {
+        msgs_count: counter.clone(),
+    };
+
+    let required_attributes = vec![(b"is_superuser".to_vec(), b"true".to_vec())];
+    let access_control = CredentialAccessControl::new(
+        &required_attributes,
+        authority.clone(),
+        identities_attributes,
+        credentials.revocation_lists(),
+    );
+
+    ctx.flow_controls()
+        .add_consumer("counter", listener.flow_control_id());
+
+    WorkerBuilder::new(worker)
+        .with_address("counter")
+        .with_incoming_access_control(access_control)
+        .with_outgoing_access_control(DenyAll)
+        .start(ctx)
+        .await?;
+
+    ctx.send(route![channel.clone(), "counter"], "Hello".to_string())
+        .await?;
+    ctx.sleep(Duration::from_millis(100)).await;
+    assert_eq!(counter.load(Ordering::Relaxed), 1);
+
+    // the authority revokes the client credentials
+    let revocation_list = credentials
+        .revocation_lists()
+        .issue_revocation_list(
+            &authority,
+            1,
+            vec![RevokedSubject {
+                subject: client.clone(),
+                // the credentials created before that time are revoked
+                revoked_at: now()? + 1,
+            }],
+        )
+        .await?;
+
+    let revoked = secure_channels
+        .receive_revocation_list(ctx, &authority, revocation_list)
+        .await?;
+    assert_eq!(revoked, vec![client.clone()]);
+    ctx.sleep(Duration::from_millis(100)).await;
+
+    // the channels established with the client are closed
+    assert!(secure_channels
+        .secure_channel_registry()
+        .get_channel_list()
+        .iter()
+        .all(|c| c.their_id() != &client));
+
+    // the revoked credential can't be verified anymore
+    assert!(credentials
+        .credentials_verification()
+        .verify_credential(Some(&client), &[authority], &credential)
+        .await
+        .is_err());
+
+    Ok(())
+}
+
 #[allow(non_snake_case)]
 #[ockam_macros::test]
 async fn missing_authority__handshake_should_succeed(ctx: &mut Context) -> Result<()> {
@@ -179,3 +179,50 @@ async fn create_with_rotated_identity() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn renew_purpose_keys_after_rotation() -> Result<()> {
+    let identities = identities().await?;
+    let identities_creation = identities.identities_creation();
+    let purpose_keys_creation = identities.purpose_keys().purpose_keys_creation();
+
+    let identifier = identities_creation.create_identity().await?;
+    purpose_keys_creation
+        .create_credential_purpose_key(&identifier)
+        .await?;
+    purpose_keys_creation
+        .create_secure_channel_purpose_key(&identifier)
+        .await?;
+
+    // The purpose keys are attested by the previous key of the identity
+    identities_creation.rotate_identity(&identifier).await?;
+    let res = purpose_keys_creation
+        .get_credential_purpose_key(&identifier)
+        .await;
+    assert!(res.is_err());
+    let res = purpose_keys_creation
+        .get_secure_channel_purpose_key(&identifier)
+        .await;
+    assert!(res.is_err());
+
+    purpose_keys_creation
+        .renew_purpose_keys(&identifier)
+        .await?;
+    let identity = identities.get_identity(&identifier).await?;
+    let purpose_key = purpose_keys_creation
+        .get_credential_purpose_key(&identifier)
+        .await?;
+    assert_eq!(
+        &purpose_key.data().subject_latest_change_hash,
+        identity.latest_change_hash()?
+    );
+    let purpose_key = purpose_keys_creation
+        .get_secure_channel_purpose_key(&identifier)
+        .await?;
+    assert_eq!(
+        &purpose_key.data().subject_latest_change_hash,
+        identity.latest_change_hash()?
+    );
+
+    Ok(())
+}
@@ -0,0 +1,244 @@
+use ockam_core::Result;
+use ockam_identity::models::{
+    ChangeData, PendingChange, ThresholdPublicKeys, VersionedData, CHANGE_VERSION,
+    THRESHOLD_CHANGE_VERSION,
+};
+use ockam_identity::{Identities, IdentitiesKeys, Identity, Vault};
+use ockam_vault::{SigningKeyType, SigningSecretKeyHandle, VerifyingPublicKey};
+
+/// A signer holding one of the threshold keys in its own vault
+struct Signer {
+    identities_keys: IdentitiesKeys,
+    key: SigningSecretKeyHandle,
+    public_key: VerifyingPublicKey,
+}
+
+impl Signer {
+    async fn create() -> Result<Self> {
+        let vault = Vault::create().await?;
+        let key = vault
+            .identity_vault
+            .generate_signing_secret_key(SigningKeyType::EdDSACurve25519)
+            .await?;
+        let public_key = vault.identity_vault.get_verifying_public_key(&key).await?;
+        let identities_keys = IdentitiesKeys::new(vault.identity_vault, vault.verifying_vault);
+        Ok(Self {
+            identities_keys,
+            key,
+            public_key,
+        })
+    }
+
+    /// Sign a pending change received as a string, as it would be transmitted offline
+    async fn sign(&self, identity: Option<&Identity>, pending_change: &str) -> Result<String> {
+        let pending_change = PendingChange::import_from_string(pending_change)?;
+        self.identities_keys
+            .sign_pending_change(identity, pending_change, &self.key)
+            .await?
+            .export_as_string()
+    }
+}
+
+async fn create_signers() -> Result<(Vec<Signer>, ThresholdPublicKeys)> {
+    let mut signers = vec![];
+    let mut public_keys = vec![];
+    for _ in 0..3 {
+        let signer = Signer::create().await?;
+        public_keys.push(signer.public_key.clone());
+        signers.push(signer);
+    }
+    Ok((signers, ThresholdPublicKeys::new(2, public_keys)?))
+}
+
+#[tokio::test]
+async fn test_create_and_rotate_threshold_identity() -> Result<()> {
+    let identities = Identities::builder().await?.build();
+    let identities_creation = identities.identities_creation();
+    let (signers, threshold_public_keys) = create_signers().await?;
+
+    // Create an identity controlled by 2 of 3 keys
+    let pending_change = identities_creation
+        .identity_builder()
+        .with_threshold_public_keys(threshold_public_keys.clone())
+        .build_pending_change()
+        .await?
+        .export_as_string()?;
+    let pending_change = signers[0].sign(None, &pending_change).await?;
+    let pending_change = signers[2].sign(None, &pending_change).await?;
+    let identifier = identities_creation
+        .apply_pending_change(None, PendingChange::import_from_string(&pending_change)?)
+        .await?;
+
+    // The identity can't be rotated by its primary key alone
+    assert!(identities_creation
+        .rotate_identity(&identifier)
+        .await
+        .is_err());
+
+    // Rotate the identity with the approval of 2 of the previous keys,
+    // and keep it controlled by the same keys
+    let identity = identities.get_identity(&identifier).await?;
+    let options = identities_creation
+        .identity_builder()
+        .with_threshold_public_keys(threshold_public_keys)
+        .build_options()
+        .await?;
+    let pending_change = identities_creation
+        .create_pending_change(Some(&identifier), options)
+        .await?
+        .export_as_string()?;
+    let pending_change = signers[1].sign(Some(&identity), &pending_change).await?;
+    let pending_change = signers[2].sign(Some(&identity), &pending_change).await?;
+    let pending_change = PendingChange::import_from_string(&pending_change)?;
+    assert_eq!(pending_change.threshold_signatures.len(), 2);
+    assert_eq!(pending_change.previous_threshold_signatures.len(), 2);
+    identities_creation
+        .apply_pending_change(Some(&identifier), pending_change)
+        .await?;
+
+    let identity = identities.get_identity(&identifier).await?;
+    assert_eq!(identity.changes().len(), 2);
+
+    // The full history can be verified by anyone
+    Identity::import(
+        Some(&identifier),
+        &identity.export()?,
+        Vault::create_verifying_vault(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_not_enough_threshold_signatures() -> Result<()> {
+    let identities = Identities::builder().await?.build();
+    let identities_creation = identities.identities_creation();
+    let (signers, threshold_public_keys) = create_signers().await?;
+
+    let pending_change = identities_creation
+        .identity_builder()
+        .with_threshold_public_keys(threshold_public_keys)
+        .build_pending_change()
+        .await?
+        .export_as_string()?;
+
+    // Signing twice with the same key only counts once
+    let pending_change = signers[0].sign(None, &pending_change).await?;
+    let pending_change = signers[0].sign(None, &pending_change).await?;
+    let pending_change = PendingChange::import_from_string(&pending_change)?;
+    assert_eq!(pending_change.threshold_signatures.len(), 1);
+
+    assert!(identities_creation
+        .apply_pending_change(None, pending_change)
+        .await
+        .is_err());
+
+    // Creating the identity directly is not possible either
+    let (_, threshold_public_keys) = create_signers().await?;
+    assert!(identities_creation
+        .identity_builder()
+        .with_threshold_public_keys(threshold_public_keys)
+        .build()
+        .await
+        .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unknown_threshold_key() -> Result<()> {
+    let identities = Identities::builder().await?.build();
+    let identities_creation = identities.identities_creation();
+    let (_, threshold_public_keys) = create_signers().await?;
+
+    let pending_change = identities_creation
+        .identity_builder()
+        .with_threshold_public_keys(threshold_public_keys)
+        .build_pending_change()
+        .await?
+        .export_as_string()?;
+
+    let other = Signer::create().await?;
+    assert!(other.sign(None, &pending_change).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_duplicated_threshold_signatures() -> Result<()> {
+    let identities = Identities::builder().await?.build();
+    let identities_creation = identities.identities_creation();
+    let (signers, threshold_public_keys) = create_signers().await?;
+
+    let pending_change = identities_creation
+        .identity_builder()
+        .with_threshold_public_keys(threshold_public_keys)
+        .build_pending_change()
+        .await?
+        .export_as_string()?;
+    let pending_change = signers[0].sign(None, &pending_change).await?;
+    let pending_change = signers[1].sign(None, &pending_change).await?;
+    let identifier = identities_creation
+        .apply_pending_change(None, PendingChange::import_from_string(&pending_change)?)
+        .await?;
+
+    // Replace the second signature with a copy of the first one
+    let mut change_history = identities.get_change_history(&identifier).await?;
+    let threshold_signatures = change_history.0[0].threshold_signatures.as_mut().unwrap();
+    threshold_signatures[1] = threshold_signatures[0].clone();
+
+    assert!(Identity::import_from_change_history(
+        Some(&identifier),
+        change_history.clone(),
+        Vault::create_verifying_vault(),
+    )
+    .await
+    .is_err());
+
+    // Removing the threshold signatures altogether fails too
+    change_history.0[0].threshold_signatures = None;
+    assert!(Identity::import_from_change_history(
+        Some(&identifier),
+        change_history,
+        Vault::create_verifying_vault(),
+    )
+    .await
+    .is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_threshold_changes_version() -> Result<()> {
+    let identities = Identities::builder().await?.build();
+    let identities_creation = identities.identities_creation();
+    let (_signers, threshold_public_keys) = create_signers().await?;
+
+    // A change with threshold keys can't be verified by the versions not supporting them
+    let pending_change = identities_creation
+        .identity_builder()
+        .with_threshold_public_keys(threshold_public_keys)
+        .build_pending_change()
+        .await?;
+    let versioned_data: VersionedData = minicbor::decode(&pending_change.data)?;
+    assert_eq!(versioned_data.version, THRESHOLD_CHANGE_VERSION);
+    assert!(ChangeData::get_data(&versioned_data)?
+        .threshold_public_keys
+        .is_some());
+
+    // The threshold keys can't be hidden from the verifiers with an older version
+    let downgraded = VersionedData {
+        version: CHANGE_VERSION,
+        ..versioned_data
+    };
+    assert!(ChangeData::get_data(&downgraded).is_err());
+
+    // The changes of regular identities keep the original version
+    let identifier = identities_creation.create_identity().await?;
+    let identity = identities.get_identity(&identifier).await?;
+    let versioned_data: VersionedData = minicbor::decode(&identity.change_history().0[0].data)?;
+    assert_eq!(versioned_data.version, CHANGE_VERSION);
+
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use super::{Buffer, Checked, Code, Codec, Protocol};
-use crate::proto::{DnsAddr, Node, Project, Secure, Service, Space, Tcp, Worker};
+use crate::proto::{DnsAddr, Node, Project, Quic, Secure, Service, Space, Tcp, Udp, Worker};
 use crate::{Error, ProtoValue};
 use core::fmt;
 use unsigned_varint::decode;
@@ -9,9 +9,13 @@ pub struct StdCodec;
 impl Codec for StdCodec {
     fn split_str<'a>(
         &self,
-        _prefix: &str,
+        prefix: &str,
         input: &'a str,
     ) -> Result<(Checked<&'a str>, &'a str), Error> {
+        // protocols without a value leave the whole input as the remainder
+        if prefix == Quic::PREFIX {
+            return Ok((Checked(""), input));
+        }
         if let Some(p) = input.find('/') {
             let (x, y) = input.split_at(p);
             Ok((Checked(x), y))
@@ -42,13 +46,14 @@ impl Codec for StdCodec {
                 let (x, y) = input.split_at(16);
                 Ok((Checked(x), y))
             }
-            Tcp::CODE => {
+            c @ Tcp::CODE | c @ Udp::CODE => {
                 if input.len() < 2 {
-                    return Err(Error::required_bytes(Tcp::CODE, 2));
+                    return Err(Error::required_bytes(c, 2));
                 }
                 let (x, y) = input.split_at(2);
                 Ok((Checked(x), y))
             }
+            Quic::CODE => Ok((Checked(&[]), input)),
             c @ Worker::CODE
             | c @ DnsAddr::CODE
             | c @ Service::CODE
@@ -75,6 +80,8 @@ impl Codec for StdCodec {
             #[cfg(feature = "std")]
             crate::proto::Ip6::CODE => crate::proto::Ip6::read_bytes(input).is_ok(),
             Tcp::CODE => Tcp::read_bytes(input).is_ok(),
+            Udp::CODE => Udp::read_bytes(input).is_ok(),
+            Quic::CODE => Quic::read_bytes(input).is_ok(),
             DnsAddr::CODE => DnsAddr::read_bytes(input).is_ok(),
             Service::CODE => Service::read_bytes(input).is_ok(),
             Node::CODE => Node::read_bytes(input).is_ok(),
@@ -93,6 +100,8 @@ impl Codec for StdCodec {
             #[cfg(feature = "std")]
             crate::proto::Ip6::CODE => crate::proto::Ip6::read_bytes(val.data())?.write_bytes(buf),
             Tcp::CODE => Tcp::read_bytes(val.data())?.write_bytes(buf),
+            Udp::CODE => Udp::read_bytes(val.data())?.write_bytes(buf),
+            Quic::CODE => Quic::read_bytes(val.data())?.write_bytes(buf),
             DnsAddr::CODE => DnsAddr::read_bytes(val.data())?.write_bytes(buf),
             Service::CODE => Service::read_bytes(val.data())?.write_bytes(buf),
             Node::CODE => Node::read_bytes(val.data())?.write_bytes(buf),
@@ -129,6 +138,14 @@ impl Codec for StdCodec {
                 Tcp::read_str(value)?.write_bytes(buf);
                 Ok(())
             }
+            Udp::PREFIX => {
+                Udp::read_str(value)?.write_bytes(buf);
+                Ok(())
+            }
+            Quic::PREFIX => {
+                Quic::read_str(value)?.write_bytes(buf);
+                Ok(())
+            }
             DnsAddr::PREFIX => {
                 DnsAddr::read_str(value)?.write_bytes(buf);
                 Ok(())
@@ -182,6 +199,14 @@ impl Codec for StdCodec {
                 Tcp::read_bytes(value)?.write_str(f)?;
                 Ok(())
             }
+            Udp::CODE => {
+                Udp::read_bytes(value)?.write_str(f)?;
+                Ok(())
+            }
+            Quic::CODE => {
+                Quic::read_bytes(value)?.write_str(f)?;
+                Ok(())
+            }
             DnsAddr::CODE => {
                 DnsAddr::read_bytes(value)?.write_str(f)?;
                 Ok(())
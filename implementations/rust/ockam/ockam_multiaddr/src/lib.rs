@@ -27,7 +27,7 @@ use once_cell::race::OnceBox;
 use std::net::{SocketAddrV4, SocketAddrV6};
 use tinyvec::{Array, ArrayVec, TinyVec};
 
-use crate::proto::{DnsAddr, Ip4, Ip6, Tcp};
+use crate::proto::{DnsAddr, Ip4, Ip6, Tcp, Udp};
 pub use error::Error;
 use ockam_core::env::FromString;
 pub use registry::{Registry, RegistryBuilder};
@@ -595,27 +595,26 @@ impl MultiAddr {
 
     /// If the input MultiAddr is "/dnsaddr/localhost/tcp/4000/service/api",
     /// then this will return string format of the SocketAddr: "127.0.0.1:4000".
+    ///
+    /// The port can either be a TCP or a UDP port.
     pub fn to_socket_addr(&self) -> Result<String, Error> {
         let mut it = self.iter().peekable();
         while let Some(p) = it.next() {
             match p.code() {
                 Ip4::CODE => {
                     let ip4 = p.cast::<Ip4>().unwrap();
-                    let port = it.next().unwrap().cast::<Tcp>().unwrap();
-                    return Ok(SocketAddrV4::new(*ip4, *port).to_string());
+                    let port = it.next().and_then(|p| port(&p)).ok_or_else(no_port)?;
+                    return Ok(SocketAddrV4::new(*ip4, port).to_string());
                 }
                 Ip6::CODE => {
                     let ip6 = p.cast::<Ip6>().unwrap();
-                    let port = it.next().unwrap().cast::<Tcp>().unwrap();
-                    return Ok(SocketAddrV6::new(*ip6, *port, 0, 0).to_string());
+                    let port = it.next().and_then(|p| port(&p)).ok_or_else(no_port)?;
+                    return Ok(SocketAddrV6::new(*ip6, port, 0, 0).to_string());
                 }
                 DnsAddr::CODE => {
                     let host = p.cast::<DnsAddr>().unwrap();
-                    if let Some(p) = it.peek() {
-                        if p.code() == Tcp::CODE {
-                            let port = p.cast::<Tcp>().unwrap();
-                            return Ok(format!("{}:{}", &*host, *port));
-                        }
+                    if let Some(port) = it.peek().and_then(port) {
+                        return Ok(format!("{}:{}", &*host, port));
                     }
                 }
                 other => {
@@ -627,6 +626,19 @@ impl MultiAddr {
     }
 }
 
+/// Return the port of a TCP or UDP protocol value.
+fn port(p: &ProtoValue) -> Option<u16> {
+    match p.code() {
+        Tcp::CODE => p.cast::<Tcp>().map(|port| *port),
+        Udp::CODE => p.cast::<Udp>().map(|port| *port),
+        _ => None,
+    }
+}
+
+fn no_port() -> Error {
+    Error::message("No port found")
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Match {
     Val(Code),
@@ -1,6 +1,6 @@
 use super::{Code, Codec, Protocol};
 use crate::codec::StdCodec;
-use crate::proto::{DnsAddr, Node, Project, Secure, Service, Space, Tcp, Worker};
+use crate::proto::{DnsAddr, Node, Project, Quic, Secure, Service, Space, Tcp, Udp, Worker};
 use alloc::collections::btree_map::BTreeMap;
 use alloc::sync::Arc;
 use core::fmt;
@@ -27,6 +27,8 @@ impl Default for Registry {
         let mut r = RegistryBuilder::new();
         r.register(Worker::CODE, Worker::PREFIX, std_codec.clone());
         r.register(Tcp::CODE, Tcp::PREFIX, std_codec.clone());
+        r.register(Udp::CODE, Udp::PREFIX, std_codec.clone());
+        r.register(Quic::CODE, Quic::PREFIX, std_codec.clone());
         r.register(DnsAddr::CODE, DnsAddr::PREFIX, std_codec.clone());
         #[allow(clippy::redundant_clone)]
         r.register(Service::CODE, Service::PREFIX, std_codec.clone());
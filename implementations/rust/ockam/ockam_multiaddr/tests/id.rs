@@ -1,5 +1,7 @@
 use core::fmt;
-use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Node, Project, Secure, Service, Space, Tcp};
+use ockam_multiaddr::proto::{
+    DnsAddr, Ip4, Ip6, Node, Project, Quic, Secure, Service, Space, Tcp, Udp,
+};
 use ockam_multiaddr::{Code, Match, MultiAddr, Protocol};
 use quickcheck::{quickcheck, Arbitrary, Gen};
 use rand::distributions::{Alphanumeric, DistString};
@@ -100,6 +102,14 @@ quickcheck! {
                         addr.push_back(Tcp::new(0)).unwrap();
                         prot.push_back(Tcp::CODE);
                     }
+                    Udp::CODE => {
+                        addr.push_back(Udp::new(0)).unwrap();
+                        prot.push_back(Udp::CODE);
+                    }
+                    Quic::CODE => {
+                        addr.push_back(Quic::new()).unwrap();
+                        prot.push_back(Quic::CODE);
+                    }
                     DnsAddr::CODE => {
                         addr.push_back(DnsAddr::new("localhost")).unwrap();
                         prot.push_back(DnsAddr::CODE);
@@ -145,6 +155,8 @@ quickcheck! {
 
 const PROTOS: &[Code] = &[
     Tcp::CODE,
+    Udp::CODE,
+    Quic::CODE,
     DnsAddr::CODE,
     Ip4::CODE,
     Ip6::CODE,
@@ -161,6 +173,8 @@ impl Arbitrary for Addr {
         for _ in 0..g.size() {
             match *g.choose(PROTOS).unwrap() {
                 Tcp::CODE => a.push_back(Tcp::new(u16::arbitrary(g))).unwrap(),
+                Udp::CODE => a.push_back(Udp::new(u16::arbitrary(g))).unwrap(),
+                Quic::CODE => a.push_back(Quic::new()).unwrap(),
                 DnsAddr::CODE => a.push_back(DnsAddr::new(gen_hostname())).unwrap(),
                 Ip4::CODE => a.push_back(Ip4::new(Ipv4Addr::arbitrary(g))).unwrap(),
                 Ip6::CODE => a.push_back(Ip6::new(Ipv6Addr::arbitrary(g))).unwrap(),
@@ -201,6 +215,22 @@ impl Arbitrary for Op {
     }
 }
 
+#[test]
+fn udp_and_quic() {
+    let ma = MultiAddr::from_str("/ip4/127.0.0.1/udp/4000/quic/service/api").unwrap();
+    let codes = ma.iter().map(|p| p.code()).collect::<Vec<_>>();
+    assert_eq!(codes, [Ip4::CODE, Udp::CODE, Quic::CODE, Service::CODE]);
+    assert_eq!(*ma.iter().nth(1).unwrap().cast::<Udp>().unwrap(), 4000);
+    assert_eq!(ma.to_string(), "/ip4/127.0.0.1/udp/4000/quic/service/api");
+    assert_eq!(ma.to_socket_addr().unwrap(), "127.0.0.1:4000");
+
+    let ma = MultiAddr::from_str("/dnsaddr/localhost/udp/4000/quic").unwrap();
+    assert_eq!(ma.to_string(), "/dnsaddr/localhost/udp/4000/quic");
+    assert_eq!(ma.to_socket_addr().unwrap(), "localhost:4000");
+
+    assert!(MultiAddr::from_str("/ip4/127.0.0.1/udp/not-a-port").is_err());
+}
+
 fn gen_hostname() -> String {
     const LABEL: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz123456789_-";
     fn gen_label<R: Rng>(g: &mut R) -> String {
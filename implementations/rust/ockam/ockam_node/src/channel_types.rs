@@ -4,14 +4,338 @@ use tokio::sync;
 #[cfg(not(feature = "std"))]
 use crate::tokio::sync;
 
-/// Sender used to send payload messages
-pub type MessageSender<T> = sync::mpsc::Sender<T>;
-/// Receiver used to receive payload messages
-pub type MessageReceiver<T> = sync::mpsc::Receiver<T>;
+use crate::error::NodeError;
+#[cfg(feature = "std")]
+use crate::error::WorkerReason;
+#[cfg(feature = "std")]
+use alloc::sync::Weak;
+use core::fmt::Debug;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use ockam_core::compat::sync::Arc;
+use ockam_core::Result;
+#[cfg(feature = "std")]
+use ockam_core::{
+    errcode::{Kind, Origin},
+    Error,
+};
+#[cfg(feature = "std")]
+use sync::mpsc::error::SendError;
+
+/// Default capacity of a worker mailbox
+pub const DEFAULT_MAILBOX_CAPACITY: usize = 8;
+
+/// Capacity of the channel used to send messages to the router
+pub const ROUTER_CHANNEL_CAPACITY: usize = 64;
+
+/// What to do with a message sent to a worker whose mailbox is full
+///
+/// Note: the policy is only enforced on `std` targets. The `no_std` executor
+/// channels don't support backpressure
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MailboxOverflowPolicy {
+    /// Wait until the worker makes room in its mailbox
+    #[default]
+    Block,
+    /// Drop the oldest message of the mailbox to make room for the new one
+    DropOldest,
+    /// Drop the new message
+    DropNewest,
+    /// Return an error to the sender
+    Error,
+}
+
+/// Capacity and overflow policy of a worker mailbox
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MailboxConfig {
+    capacity: usize,
+    overflow_policy: MailboxOverflowPolicy,
+}
+
+impl Default for MailboxConfig {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAILBOX_CAPACITY, MailboxOverflowPolicy::default())
+    }
+}
+
+impl MailboxConfig {
+    /// Create a new mailbox configuration. The capacity is at least 1
+    pub fn new(capacity: usize, overflow_policy: MailboxOverflowPolicy) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            overflow_policy,
+        }
+    }
+
+    /// Set the capacity of the mailbox. The capacity is at least 1
+    pub fn with_capacity(self, capacity: usize) -> Self {
+        Self::new(capacity, self.overflow_policy)
+    }
+
+    /// Set the overflow policy of the mailbox
+    pub fn with_overflow_policy(self, overflow_policy: MailboxOverflowPolicy) -> Self {
+        Self::new(self.capacity, overflow_policy)
+    }
+
+    /// Maximum number of messages waiting in the mailbox
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// What to do when the mailbox is full
+    pub fn overflow_policy(&self) -> MailboxOverflowPolicy {
+        self.overflow_policy
+    }
+}
+
+/// Statistics of a worker mailbox
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MailboxStats {
+    /// Maximum number of messages waiting in the mailbox
+    pub capacity: usize,
+    /// What to do when the mailbox is full
+    pub overflow_policy: MailboxOverflowPolicy,
+    /// Number of messages waiting in the mailbox, including the messages of blocked senders
+    pub queue_depth: usize,
+    /// Highest queue depth since the mailbox was created
+    pub max_queue_depth: usize,
+    /// Number of messages dropped because the mailbox was full
+    pub dropped_messages: usize,
+    /// Number of messages rejected with an error because the mailbox was full
+    pub rejected_messages: usize,
+}
+
+/// State shared by the senders and the receiver of a mailbox
+struct Mailbox<T> {
+    config: MailboxConfig,
+    queue_depth: AtomicUsize,
+    max_queue_depth: AtomicUsize,
+    dropped_messages: AtomicUsize,
+    rejected_messages: AtomicUsize,
+    /// Receiver used by the senders to evict the oldest message with the
+    /// `DropOldest` policy
+    #[cfg(feature = "std")]
+    receiver: Option<Weak<sync::Mutex<sync::mpsc::Receiver<T>>>>,
+    #[cfg(not(feature = "std"))]
+    _marker: core::marker::PhantomData<T>,
+}
+
+impl<T> Mailbox<T> {
+    fn stats(&self) -> MailboxStats {
+        MailboxStats {
+            capacity: self.config.capacity,
+            overflow_policy: self.config.overflow_policy,
+            queue_depth: self.queue_depth.load(Ordering::Relaxed),
+            max_queue_depth: self.max_queue_depth.load(Ordering::Relaxed),
+            dropped_messages: self.dropped_messages.load(Ordering::Relaxed),
+            rejected_messages: self.rejected_messages.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Count a message entering the mailbox. The message is counted before
+    /// it is queued so that the receiver never sees a negative depth
+    fn increment(&self) {
+        let depth = self.queue_depth.fetch_add(1, Ordering::Relaxed) + 1;
+        self.max_queue_depth.fetch_max(depth, Ordering::Relaxed);
+    }
+
+    /// Count a message leaving the mailbox, or failing to enter it
+    fn decrement(&self) {
+        self.queue_depth.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Sender used to send payload messages to a worker mailbox
+pub struct MessageSender<T> {
+    sender: sync::mpsc::Sender<T>,
+    mailbox: Arc<Mailbox<T>>,
+}
+
+impl<T> Clone for MessageSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            mailbox: self.mailbox.clone(),
+        }
+    }
+}
+
+impl<T> Debug for MessageSender<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MessageSender")
+            .field("config", &self.mailbox.config)
+            .finish()
+    }
+}
+
+impl<T: Debug> MessageSender<T> {
+    /// Send a message to the mailbox, applying its overflow policy if it is full
+    pub async fn send(&self, msg: T) -> Result<()> {
+        self.mailbox.increment();
+
+        #[cfg(feature = "std")]
+        let res = match self.mailbox.config.overflow_policy {
+            MailboxOverflowPolicy::Block => self.sender.send(msg).await,
+            _ => return self.send_without_blocking(msg).await,
+        };
+        #[cfg(not(feature = "std"))]
+        let res = self.sender.send(msg).await;
+
+        res.map_err(|e| {
+            self.mailbox.decrement();
+            NodeError::from_send_err(e)
+        })
+    }
 
-/// Create message channel
+    #[cfg(feature = "std")]
+    async fn send_without_blocking(&self, mut msg: T) -> Result<()> {
+        use sync::mpsc::error::TrySendError;
+
+        loop {
+            match self.sender.try_send(msg) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Closed(m)) => {
+                    self.mailbox.decrement();
+                    return Err(NodeError::from_send_err(SendError(m)));
+                }
+                Err(TrySendError::Full(m)) => match self.mailbox.config.overflow_policy {
+                    MailboxOverflowPolicy::DropOldest => {
+                        self.drop_oldest().await;
+                        msg = m;
+                    }
+                    MailboxOverflowPolicy::Error => {
+                        self.mailbox.decrement();
+                        self.mailbox
+                            .rejected_messages
+                            .fetch_add(1, Ordering::Relaxed);
+                        return Err(Error::new(
+                            Origin::Node,
+                            Kind::ResourceExhausted,
+                            NodeError::WorkerState(WorkerReason::MailboxFull),
+                        ));
+                    }
+                    MailboxOverflowPolicy::DropNewest | MailboxOverflowPolicy::Block => {
+                        self.mailbox.decrement();
+                        self.mailbox
+                            .dropped_messages
+                            .fetch_add(1, Ordering::Relaxed);
+                        debug!("Mailbox full, dropped a new message");
+                        return Ok(());
+                    }
+                },
+            }
+        }
+    }
+
+    /// Remove the oldest message from the mailbox. If the receiver is busy taking
+    /// a message, just yield since there will soon be room in the mailbox
+    #[cfg(feature = "std")]
+    async fn drop_oldest(&self) {
+        let receiver = self.mailbox.receiver.as_ref().and_then(|r| r.upgrade());
+        if let Some(receiver) = receiver {
+            if let Ok(mut receiver) = receiver.try_lock() {
+                if receiver.try_recv().is_ok() {
+                    self.mailbox.decrement();
+                    self.mailbox
+                        .dropped_messages
+                        .fetch_add(1, Ordering::Relaxed);
+                    debug!("Mailbox full, dropped the oldest message");
+                    return;
+                }
+            }
+        }
+        tokio::task::yield_now().await;
+    }
+}
+
+impl<T> MessageSender<T> {
+    /// Return the statistics of the mailbox
+    pub fn stats(&self) -> MailboxStats {
+        self.mailbox.stats()
+    }
+}
+
+enum ReceiverKind<T> {
+    Owned(sync::mpsc::Receiver<T>),
+    /// Receiver shared with the senders to support the `DropOldest` policy
+    #[cfg(feature = "std")]
+    Shared(Arc<sync::Mutex<sync::mpsc::Receiver<T>>>),
+}
+
+/// Receiver used to receive payload messages from a worker mailbox
+pub struct MessageReceiver<T> {
+    receiver: ReceiverKind<T>,
+    mailbox: Arc<Mailbox<T>>,
+}
+
+impl<T> Debug for MessageReceiver<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MessageReceiver")
+            .field("config", &self.mailbox.config)
+            .finish()
+    }
+}
+
+impl<T> MessageReceiver<T> {
+    /// Receive the next message of the mailbox, or `None` if all the senders were dropped
+    pub async fn recv(&mut self) -> Option<T> {
+        let msg = match &mut self.receiver {
+            ReceiverKind::Owned(receiver) => receiver.recv().await,
+            #[cfg(feature = "std")]
+            ReceiverKind::Shared(receiver) => receiver.lock().await.recv().await,
+        };
+        if msg.is_some() {
+            self.mailbox.decrement();
+        }
+        msg
+    }
+
+    /// Return the statistics of the mailbox
+    pub fn stats(&self) -> MailboxStats {
+        self.mailbox.stats()
+    }
+}
+
+/// Create message channel with the default mailbox configuration
 pub fn message_channel<T>() -> (MessageSender<T>, MessageReceiver<T>) {
-    sync::mpsc::channel(8)
+    message_channel_with_config(MailboxConfig::default())
+}
+
+/// Create message channel for a mailbox with a given capacity and overflow policy
+pub fn message_channel_with_config<T>(
+    config: MailboxConfig,
+) -> (MessageSender<T>, MessageReceiver<T>) {
+    let (sender, receiver) = sync::mpsc::channel(config.capacity);
+
+    #[cfg(feature = "std")]
+    let (receiver, shared) = if config.overflow_policy == MailboxOverflowPolicy::DropOldest {
+        let receiver = Arc::new(sync::Mutex::new(receiver));
+        let shared = Some(Arc::downgrade(&receiver));
+        (ReceiverKind::Shared(receiver), shared)
+    } else {
+        (ReceiverKind::Owned(receiver), None)
+    };
+    #[cfg(not(feature = "std"))]
+    let receiver = ReceiverKind::Owned(receiver);
+
+    let mailbox = Arc::new(Mailbox {
+        config,
+        queue_depth: AtomicUsize::new(0),
+        max_queue_depth: AtomicUsize::new(0),
+        dropped_messages: AtomicUsize::new(0),
+        rejected_messages: AtomicUsize::new(0),
+        #[cfg(feature = "std")]
+        receiver: shared,
+        #[cfg(not(feature = "std"))]
+        _marker: core::marker::PhantomData,
+    });
+
+    (
+        MessageSender {
+            sender,
+            mailbox: mailbox.clone(),
+        },
+        MessageReceiver { receiver, mailbox },
+    )
 }
 
 /// Router sender
@@ -21,7 +345,7 @@ pub type RouterReceiver<T> = sync::mpsc::Receiver<T>;
 
 /// Create router channel
 pub fn router_channel<T>() -> (RouterSender<T>, RouterReceiver<T>) {
-    sync::mpsc::channel(64)
+    sync::mpsc::channel(ROUTER_CHANNEL_CAPACITY)
 }
 
 // TODO: Consider replacing with oneshot
@@ -45,3 +369,80 @@ pub type OneshotReceiver<T> = sync::oneshot::Receiver<T>;
 pub fn oneshot_channel<T>() -> (OneshotSender<T>, OneshotReceiver<T>) {
     sync::oneshot::channel()
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn channel(policy: MailboxOverflowPolicy) -> (MessageSender<u32>, MessageReceiver<u32>) {
+        message_channel_with_config(MailboxConfig::new(2, policy))
+    }
+
+    #[tokio::test]
+    async fn test_drop_newest() -> Result<()> {
+        let (sender, mut receiver) = channel(MailboxOverflowPolicy::DropNewest);
+        for i in 1..=3 {
+            sender.send(i).await?;
+        }
+
+        assert_eq!(receiver.stats().dropped_messages, 1);
+        assert_eq!(receiver.recv().await, Some(1));
+        assert_eq!(receiver.recv().await, Some(2));
+        assert_eq!(receiver.stats().queue_depth, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest() -> Result<()> {
+        let (sender, mut receiver) = channel(MailboxOverflowPolicy::DropOldest);
+        for i in 1..=3 {
+            sender.send(i).await?;
+        }
+
+        assert_eq!(receiver.stats().dropped_messages, 1);
+        assert_eq!(receiver.recv().await, Some(2));
+        assert_eq!(receiver.recv().await, Some(3));
+        assert_eq!(receiver.stats().queue_depth, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_error_to_sender() -> Result<()> {
+        let (sender, mut receiver) = channel(MailboxOverflowPolicy::Error);
+        sender.send(1).await?;
+        sender.send(2).await?;
+
+        let error = sender.send(3).await.unwrap_err();
+        assert_eq!(error.code().kind, Kind::ResourceExhausted);
+        assert_eq!(receiver.stats().rejected_messages, 1);
+
+        assert_eq!(receiver.recv().await, Some(1));
+        sender.send(3).await?;
+        assert_eq!(receiver.recv().await, Some(2));
+        assert_eq!(receiver.recv().await, Some(3));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_block() -> Result<()> {
+        let (sender, mut receiver) = channel(MailboxOverflowPolicy::Block);
+        sender.send(1).await?;
+        sender.send(2).await?;
+
+        let blocked_sender = sender.clone();
+        let blocked = tokio::spawn(async move { blocked_sender.send(3).await });
+        tokio::task::yield_now().await;
+        assert!(!blocked.is_finished());
+
+        assert_eq!(receiver.recv().await, Some(1));
+        blocked.await.unwrap()?;
+        assert_eq!(receiver.recv().await, Some(2));
+        assert_eq!(receiver.recv().await, Some(3));
+
+        let stats = sender.stats();
+        assert_eq!(stats.queue_depth, 0);
+        assert_eq!(stats.max_queue_depth, 3);
+        assert_eq!(stats.dropped_messages, 0);
+        Ok(())
+    }
+}
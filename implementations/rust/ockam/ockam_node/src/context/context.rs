@@ -1,6 +1,6 @@
-use crate::channel_types::{SmallReceiver, SmallSender};
+use crate::channel_types::{MailboxStats, MessageReceiver, SmallSender};
 use crate::tokio::runtime::Handle;
-use crate::{error::*, AsyncDropSender, NodeMessage};
+use crate::{error::*, AsyncDropSender, NodeMessage, ShutdownDrain, WorkerStats};
 use core::sync::atomic::AtomicUsize;
 use ockam_core::compat::collections::HashMap;
 use ockam_core::compat::sync::{Arc, RwLock};
@@ -27,7 +27,7 @@ pub struct Context {
     pub(super) mailboxes: Mailboxes,
     pub(super) sender: SmallSender<NodeMessage>,
     pub(super) rt: Handle,
-    pub(super) receiver: SmallReceiver<RelayMessage>,
+    pub(super) receiver: MessageReceiver<RelayMessage>,
     pub(super) async_drop_sender: Option<AsyncDropSender>,
     pub(super) mailbox_count: Arc<AtomicUsize>,
     /// List of transports used to resolve external addresses to local workers in routes
@@ -91,6 +91,12 @@ impl Context {
         &self.mailboxes
     }
 
+    /// Return the statistics of the mailbox of the current worker: its capacity,
+    /// overflow policy, queue depth and number of dropped messages
+    pub fn mailbox_stats(&self) -> MailboxStats {
+        self.receiver.stats()
+    }
+
     /// Shared [`FlowControls`] instance
     pub fn flow_controls(&self) -> &FlowControls {
         &self.flow_controls
@@ -137,6 +143,20 @@ impl Context {
             .is_ok()
     }
 
+    /// Register a [`ShutdownDrain`] which is run when the node is gracefully stopped,
+    /// before any worker is stopped
+    pub async fn register_shutdown_drain(&self, drain: Arc<dyn ShutdownDrain>) -> Result<()> {
+        let (msg, mut rx) = NodeMessage::register_shutdown_drain(drain);
+        self.sender
+            .send(msg)
+            .await
+            .map_err(NodeError::from_send_err)?;
+        rx.recv()
+            .await
+            .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??
+            .is_ok()
+    }
+
     /// Return a list of all available worker addresses on a node
     pub async fn list_workers(&self) -> Result<Vec<Address>> {
         let (msg, mut reply_rx) = NodeMessage::list_workers();
@@ -153,6 +173,22 @@ impl Context {
             .take_workers()
     }
 
+    /// Return the statistics collected by the router for all the workers and processors of a node
+    pub async fn list_worker_stats(&self) -> Result<Vec<WorkerStats>> {
+        let (msg, mut reply_rx) = NodeMessage::list_worker_stats();
+
+        self.sender
+            .send(msg)
+            .await
+            .map_err(NodeError::from_send_err)?;
+
+        reply_rx
+            .recv()
+            .await
+            .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??
+            .take_worker_stats()
+    }
+
     /// Return true if a worker is already registered at this address
     pub async fn is_worker_registered_at(&self, address: Address) -> Result<bool> {
         let (msg, mut reply_rx) = NodeMessage::is_worker_registered_at(address.clone());
@@ -18,7 +18,9 @@ use ockam_transport_core::Transport;
 use tokio::runtime::Handle;
 
 use crate::async_drop::AsyncDrop;
-use crate::channel_types::{message_channel, small_channel, SmallReceiver, SmallSender};
+use crate::channel_types::{
+    message_channel_with_config, small_channel, MailboxConfig, SmallReceiver, SmallSender,
+};
 use crate::{debugger, Context};
 use crate::{error::*, relay::CtrlSignal, router::SenderPair, NodeMessage};
 
@@ -67,11 +69,12 @@ impl Context {
         sender: SmallSender<NodeMessage>,
         mailboxes: Mailboxes,
         async_drop_sender: Option<AsyncDropSender>,
+        mailbox_config: MailboxConfig,
         transports: Arc<RwLock<HashMap<TransportType, Arc<dyn Transport>>>>,
         flow_controls: &FlowControls,
         #[cfg(feature = "std")] tracing_context: OpenTelemetryContext,
     ) -> (Self, SenderPair, SmallReceiver<CtrlSignal>) {
-        let (mailbox_tx, receiver) = message_channel();
+        let (mailbox_tx, receiver) = message_channel_with_config(mailbox_config);
         let (ctrl_tx, ctrl_rx) = small_channel();
         (
             Self {
@@ -97,12 +100,14 @@ impl Context {
     pub(crate) fn copy_with_mailboxes(
         &self,
         mailboxes: Mailboxes,
+        mailbox_config: MailboxConfig,
     ) -> (Context, SenderPair, SmallReceiver<CtrlSignal>) {
         Context::new(
             self.runtime().clone(),
             self.sender().clone(),
             mailboxes,
             None,
+            mailbox_config,
             self.transports.clone(),
             &self.flow_controls,
             #[cfg(feature = "std")]
@@ -120,6 +125,7 @@ impl Context {
             self.sender().clone(),
             mailboxes,
             Some(drop_sender),
+            MailboxConfig::default(),
             self.transports.clone(),
             &self.flow_controls,
             #[cfg(feature = "std")]
@@ -240,6 +246,7 @@ impl Context {
             addresses,
             sender,
             true,
+            "Context",
             Arc::clone(&self.mailbox_count),
             vec![],
         );
@@ -268,7 +275,7 @@ mod tests {
 
         // after a copy with new mailboxes the list of transports should be intact
         let mailboxes = Mailboxes::new(Mailbox::deny_all("address"), vec![]);
-        let (copy, _, _) = ctx.copy_with_mailboxes(mailboxes.clone());
+        let (copy, _, _) = ctx.copy_with_mailboxes(mailboxes.clone(), MailboxConfig::default());
         assert!(copy.is_transport_registered(transport.transport_type()));
 
         // after a detached copy with new mailboxes the list of transports should be intact
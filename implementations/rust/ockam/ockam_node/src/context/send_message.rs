@@ -242,12 +242,15 @@ impl Context {
             }
         };
 
+        #[cfg(feature = "metrics")]
+        let started_at = std::time::Instant::now();
         let req = NodeMessage::SenderReq(addr, reply_tx);
         self.sender
             .send(req)
             .await
             .map_err(NodeError::from_send_err)?;
-        let (addr, sender) = reply_rx
+        #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+        let (addr, sender, worker_type) = reply_rx
             .recv()
             .await
             .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??
@@ -290,10 +293,9 @@ impl Context {
         }
 
         // Send the packed user message with associated route
-        sender
-            .send(relay_msg)
-            .await
-            .map_err(NodeError::from_send_err)?;
+        sender.send(relay_msg).await?;
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_message_delivery(worker_type, started_at.elapsed());
 
         Ok(())
     }
@@ -349,12 +351,15 @@ impl Context {
                 return Err(err);
             }
         };
+        #[cfg(feature = "metrics")]
+        let started_at = std::time::Instant::now();
         let req = NodeMessage::SenderReq(addr, reply_tx);
         self.sender
             .send(req)
             .await
             .map_err(NodeError::from_send_err)?;
-        let (addr, sender) = reply_rx
+        #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+        let (addr, sender, worker_type) = reply_rx
             .recv()
             .await
             .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??
@@ -375,10 +380,9 @@ impl Context {
         }
 
         // Forward the message
-        sender
-            .send(relay_msg)
-            .await
-            .map_err(NodeError::from_send_err)?;
+        sender.send(relay_msg).await?;
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_message_delivery(worker_type, started_at.elapsed());
 
         Ok(())
     }
@@ -37,7 +37,20 @@ impl Context {
     /// This call will hang until a safe shutdown has been completed
     /// or the desired timeout has been reached.
     pub async fn stop_timeout(&self, seconds: u8) -> Result<()> {
-        let (req, mut rx) = NodeMessage::stop_node(ShutdownType::Graceful(seconds));
+        self.stop_with(ShutdownType::Graceful(seconds)).await
+    }
+
+    /// Signal to the local runtime to shut down, after having run the registered
+    /// [`ShutdownDrain`](crate::ShutdownDrain)s
+    ///
+    /// This call will hang until the drain is done, or the timeout is reached, and
+    /// a safe shutdown has been completed, with the same timeout.
+    pub async fn stop_with_drain(&self, seconds: u8) -> Result<()> {
+        self.stop_with(ShutdownType::Drain(seconds)).await
+    }
+
+    async fn stop_with(&self, shutdown_type: ShutdownType) -> Result<()> {
+        let (req, mut rx) = NodeMessage::stop_node(shutdown_type);
         self.sender
             .send(req)
             .await
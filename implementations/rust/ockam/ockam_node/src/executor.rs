@@ -49,7 +49,7 @@ impl Executor {
     pub fn new(rt: Arc<Runtime>, flow_controls: &FlowControls) -> Self {
         let router = Router::new(flow_controls);
         #[cfg(feature = "metrics")]
-        let metrics = Metrics::new(&rt, router.get_metrics_readout());
+        let metrics = Metrics::new(router.sender(), router.get_metrics_readout());
         Self {
             rt,
             router,
@@ -109,7 +109,7 @@ impl Executor {
 
         // Shut down metrics collector
         #[cfg(feature = "metrics")]
-        alive.fetch_or(true, Ordering::Acquire);
+        alive.store(false, Ordering::Release);
 
         // Last join user code
         let res = self
@@ -154,7 +154,7 @@ impl Executor {
 
         // Shut down metrics collector
         #[cfg(feature = "metrics")]
-        alive.fetch_or(true, Ordering::Acquire);
+        alive.store(false, Ordering::Release);
 
         // Last join user code
         let res = self
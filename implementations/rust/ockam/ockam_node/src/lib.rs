@@ -62,6 +62,7 @@ mod node;
 mod processor_builder;
 mod relay;
 mod router;
+mod shutdown_drain;
 
 /// Support for storing persistent values
 pub mod storage;
@@ -81,6 +82,7 @@ pub use error::*;
 pub use executor::*;
 pub use messages::*;
 pub use processor_builder::ProcessorBuilder;
+pub use shutdown_drain::ShutdownDrain;
 #[cfg(feature = "std")]
 pub use storage::database;
 pub use worker_builder::WorkerBuilder;
@@ -2,6 +2,7 @@ use crate::channel_types::{small_channel, MessageSender, SmallReceiver, SmallSen
 use crate::{
     error::{NodeError, NodeReason, RouterReason, WorkerReason},
     router::SenderPair,
+    ShutdownDrain,
 };
 use core::{fmt, sync::atomic::AtomicUsize};
 use ockam_core::compat::{string::String, sync::Arc, vec::Vec};
@@ -20,6 +21,8 @@ pub enum NodeMessage {
         senders: SenderPair,
         /// A detached context/ "worker" runs no relay state
         detached: bool,
+        /// Name of the type of the worker, used to label its metrics
+        worker_type: &'static str,
         /// A mechanism to read channel fill-state for a worker
         mailbox_count: Arc<AtomicUsize>,
         /// Reply channel for command confirmation
@@ -29,6 +32,8 @@ pub enum NodeMessage {
     },
     /// Return a list of all worker addresses
     ListWorkers(SmallSender<NodeReplyResult>),
+    /// Return the statistics of all workers
+    ListWorkerStats(SmallSender<NodeReplyResult>),
     /// Return a list of all worker addresses
     IsWorkerRegisteredAt(SmallSender<NodeReplyResult>, Address),
     /// Add an existing address to a cluster
@@ -41,6 +46,8 @@ pub enum NodeMessage {
         addrs: Vec<Address>,
         /// Pair of senders to the worker relay (msgs and ctrl)
         senders: SenderPair,
+        /// Name of the type of the processor, used to label its metrics
+        worker_type: &'static str,
         /// Reply channel for command confirmation
         reply: SmallSender<NodeReplyResult>,
         /// List of metadata for each address
@@ -50,6 +57,11 @@ pub enum NodeMessage {
     StopProcessor(Address, SmallSender<NodeReplyResult>),
     /// Stop the node (and all workers)
     StopNode(ShutdownType, SmallSender<NodeReplyResult>),
+    /// Register a step run before the workers are stopped, during a shutdown with a drain
+    RegisterShutdownDrain(Arc<dyn ShutdownDrain>, SmallSender<NodeReplyResult>),
+    /// Let the router know that the drains are done, to resume the shutdown with the given
+    /// timeout
+    Drained(u8),
     /// Immediately stop the node runtime
     AbortNode,
     /// Let the router know a particular address has stopped
@@ -73,12 +85,15 @@ impl fmt::Display for NodeMessage {
         match self {
             NodeMessage::StartWorker { .. } => write!(f, "StartWorker"),
             NodeMessage::ListWorkers(_) => write!(f, "ListWorkers"),
+            NodeMessage::ListWorkerStats(_) => write!(f, "ListWorkerStats"),
             NodeMessage::IsWorkerRegisteredAt(_, _) => write!(f, "IsWorkerRegisteredAt"),
             NodeMessage::SetCluster(_, _, _) => write!(f, "SetCluster"),
             NodeMessage::StopWorker(_, _, _) => write!(f, "StopWorker"),
             NodeMessage::StartProcessor { .. } => write!(f, "StartProcessor"),
             NodeMessage::StopProcessor(_, _) => write!(f, "StopProcessor"),
             NodeMessage::StopNode(_, _) => write!(f, "StopNode"),
+            NodeMessage::RegisterShutdownDrain(_, _) => write!(f, "RegisterShutdownDrain"),
+            NodeMessage::Drained(_) => write!(f, "Drained"),
             NodeMessage::AbortNode => write!(f, "AbortNode"),
             NodeMessage::StopAck(_) => write!(f, "StopAck"),
             NodeMessage::SenderReq(_, _) => write!(f, "SenderReq"),
@@ -100,10 +115,13 @@ impl NodeMessage {
     ///               relay behind it that can respond to shutdown
     ///               commands.  Setting this to `true` will disable
     ///               stop ACK support in the router
+    ///
+    /// * `worker_type`: name of the type of the worker, see [`worker_type_name`]
     pub fn start_worker(
         addrs: Vec<Address>,
         senders: SenderPair,
         detached: bool,
+        worker_type: &'static str,
         mailbox_count: Arc<AtomicUsize>,
         metadata: Vec<AddressAndMetadata>,
     ) -> (Self, SmallReceiver<NodeReplyResult>) {
@@ -113,6 +131,7 @@ impl NodeMessage {
                 addrs,
                 senders,
                 detached,
+                worker_type,
                 mailbox_count,
                 reply,
                 addresses_metadata: metadata,
@@ -125,6 +144,7 @@ impl NodeMessage {
     pub fn start_processor(
         addrs: Vec<Address>,
         senders: SenderPair,
+        worker_type: &'static str,
         metadata: Vec<AddressAndMetadata>,
     ) -> (Self, SmallReceiver<NodeReplyResult>) {
         let (tx, rx) = small_channel();
@@ -132,6 +152,7 @@ impl NodeMessage {
             Self::StartProcessor {
                 addrs,
                 senders,
+                worker_type,
                 reply: tx,
                 addresses_metadata: metadata,
             },
@@ -151,6 +172,12 @@ impl NodeMessage {
         (Self::ListWorkers(tx), rx)
     }
 
+    /// Create a list worker statistics message and reply receiver
+    pub fn list_worker_stats() -> (Self, SmallReceiver<NodeReplyResult>) {
+        let (tx, rx) = small_channel();
+        (Self::ListWorkerStats(tx), rx)
+    }
+
     /// Check if a worker is already registered at a given address
     pub fn is_worker_registered_at(address: Address) -> (Self, SmallReceiver<NodeReplyResult>) {
         let (tx, rx) = small_channel();
@@ -175,6 +202,14 @@ impl NodeMessage {
         (Self::StopNode(tt, tx), rx)
     }
 
+    /// Create a register shutdown drain message and reply receiver
+    pub fn register_shutdown_drain(
+        drain: Arc<dyn ShutdownDrain>,
+    ) -> (Self, SmallReceiver<NodeReplyResult>) {
+        let (tx, rx) = small_channel();
+        (Self::RegisterShutdownDrain(drain, tx), rx)
+    }
+
     /// Create a sender request message and reply receiver
     pub fn sender_request(route: Address) -> (Self, SmallReceiver<NodeReplyResult>) {
         let (tx, rx) = small_channel();
@@ -215,6 +250,8 @@ pub enum RouterReply {
     Ok,
     /// A list of worker addresses
     Workers(Vec<Address>),
+    /// A list of worker statistics
+    WorkerStats(Vec<WorkerStats>),
     /// Return true if there is a worker already registered at a given Address
     WorkerIsRegisteredAtAddress(bool),
     /// Message sender to a specific worker
@@ -223,6 +260,8 @@ pub enum RouterReply {
         addr: Address,
         /// The relay sender
         sender: MessageSender<RelayMessage>,
+        /// Name of the type of the worker receiving the message
+        worker_type: &'static str,
     },
     /// Indicate the 'ready' state of an address
     State(bool),
@@ -232,6 +271,39 @@ pub enum RouterReply {
     Metadata(Option<AddressMetadata>),
 }
 
+/// Statistics collected by the router for a worker or a processor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerStats {
+    /// Primary address of the worker
+    pub address: Address,
+    /// True if this is a processor
+    pub processor: bool,
+    /// Name of the type of the worker or processor, see [`worker_type_name`]
+    pub worker_type: &'static str,
+    /// Number of messages routed to the worker since it started
+    pub routed_messages: u64,
+    /// Number of messages waiting in the worker mailbox
+    pub mailbox_size: u64,
+    /// Maximum number of messages waiting in the worker mailbox
+    pub mailbox_capacity: u64,
+    /// Highest number of messages waiting in the worker mailbox since it started
+    pub max_mailbox_size: u64,
+    /// Number of messages dropped because the worker mailbox was full
+    pub dropped_messages: u64,
+    /// Number of messages rejected with an error because the worker mailbox was full
+    pub rejected_messages: u64,
+}
+
+/// Name of a worker or processor type, without its module path nor its type parameters.
+///
+/// Contrary to addresses, which are often random, this name is stable across restarts and
+/// bounded by the number of worker types, so it can be used to label metrics
+pub fn worker_type_name<T: ?Sized>() -> &'static str {
+    let name = core::any::type_name::<T>();
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
 /// Specify the type of node shutdown
 ///
 /// For most users `ShutdownType::Graceful()` is recommended.  The
@@ -256,6 +328,14 @@ pub enum ShutdownType {
     /// when reaching the timeout (failover into `Immediate`
     /// strategy).  **A given timeout of `0` will wait forever!**
     Graceful(u8),
+    /// Run the registered [`ShutdownDrain`]s, while the node keeps routing
+    /// messages, then execute a `Graceful` shutdown
+    ///
+    /// The drain step is bounded by the given timeout, then the graceful
+    /// shutdown uses the same timeout. A timeout of `0` skips the drain step.
+    /// Stopping the node again while it is draining doesn't interrupt the
+    /// drain: the node is stopped once the drain is done.
+    Drain(u8),
     /// Immediately shutdown workers and run shutdown hooks
     ///
     /// This strategy can lead to data loss:
@@ -321,6 +401,11 @@ impl RouterReply {
         Ok(Self::Workers(v))
     }
 
+    /// Return [RouterReply::WorkerStats] for the given statistics
+    pub fn worker_stats(v: Vec<WorkerStats>) -> NodeReplyResult {
+        Ok(Self::WorkerStats(v))
+    }
+
     /// Return [RouterReply::WorkerIsRegistered] for a given address
     pub fn worker_is_registered_at_address(registered: bool) -> NodeReplyResult {
         Ok(Self::WorkerIsRegisteredAtAddress(registered))
@@ -337,14 +422,26 @@ impl RouterReply {
     }
 
     /// Return [RouterReply::Sender] for the given information
-    pub fn sender(addr: Address, sender: MessageSender<RelayMessage>) -> NodeReplyResult {
-        Ok(RouterReply::Sender { addr, sender })
+    pub fn sender(
+        addr: Address,
+        sender: MessageSender<RelayMessage>,
+        worker_type: &'static str,
+    ) -> NodeReplyResult {
+        Ok(RouterReply::Sender {
+            addr,
+            sender,
+            worker_type,
+        })
     }
 
     /// Consume the wrapper and return [RouterReply::Sender]
-    pub fn take_sender(self) -> Result<(Address, MessageSender<RelayMessage>)> {
+    pub fn take_sender(self) -> Result<(Address, MessageSender<RelayMessage>, &'static str)> {
         match self {
-            Self::Sender { addr, sender } => Ok((addr, sender)),
+            Self::Sender {
+                addr,
+                sender,
+                worker_type,
+            } => Ok((addr, sender, worker_type)),
             _ => Err(NodeError::NodeState(NodeReason::Unknown).internal()),
         }
     }
@@ -357,6 +454,14 @@ impl RouterReply {
         }
     }
 
+    /// Consume the wrapper and return [RouterReply::WorkerStats]
+    pub fn take_worker_stats(self) -> Result<Vec<WorkerStats>> {
+        match self {
+            Self::WorkerStats(s) => Ok(s),
+            _ => Err(NodeError::NodeState(NodeReason::Unknown).internal()),
+        }
+    }
+
     /// Consume the wrapper and return a bool
     pub fn take_worker_is_registered(self) -> Result<bool> {
         match self {
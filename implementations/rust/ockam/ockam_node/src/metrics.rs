@@ -1,113 +1,309 @@
-use crate::tokio::{runtime::Runtime, time};
+use crate::channel_types::SmallSender;
+use crate::error::{NodeError, NodeReason};
+use crate::router::WorkerMeta;
+use crate::tokio::time;
+use crate::{NodeMessage, WorkerStats};
 use core::{
     sync::atomic::{AtomicBool, AtomicUsize, Ordering},
     time::Duration,
 };
-use ockam_core::compat::{collections::BTreeMap, sync::Arc};
-use ockam_core::env::get_env;
-use std::{fs::OpenOptions, io::Write};
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::Result;
+use once_cell::sync::Lazy;
+use opentelemetry::metrics::{Histogram, Meter, ObservableCounter, ObservableGauge};
+use opentelemetry::{global, KeyValue};
 
-pub struct Metrics {
-    rt: Arc<Runtime>,
-    router: (Arc<AtomicUsize>, Arc<AtomicUsize>),
+/// Name of the OpenTelemetry meter used for the node runtime metrics
+pub(crate) const METER_NAME: &str = "ockam_node";
+
+/// How often the statistics of each worker are collected from the router
+const WORKER_STATS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Return the meter used for all the node runtime metrics.
+///
+/// The instruments are exported by the meter provider installed globally, if any,
+/// otherwise recording them is a no-op.
+pub(crate) fn meter() -> Meter {
+    global::meter(METER_NAME)
+}
+
+/// Time taken to resolve the destination of a message with the router and to deliver it
+/// to the destination mailbox.
+///
+/// Measurements are labelled by the type of the destination worker rather than by its
+/// address, since addresses are often random and would make the number of series unbounded
+static MESSAGE_DELIVERY_DURATION: Lazy<Histogram<f64>> = Lazy::new(|| {
+    meter()
+        .f64_histogram("ockam.router.message_delivery_duration")
+        .with_description("Time taken to route a message to the mailbox of its destination")
+        .with_unit("s")
+        .init()
+});
+
+/// Record the time taken to deliver a message to a worker of a given type
+pub(crate) fn record_message_delivery(worker_type: &'static str, duration: Duration) {
+    MESSAGE_DELIVERY_DURATION.record(
+        duration.as_secs_f64(),
+        &[KeyValue::new("worker_type", worker_type)],
+    );
+}
+
+/// Number of workers and processors currently registered in the router, by type
+#[derive(Debug, Default)]
+pub(crate) struct RouterCounts {
+    workers: AtomicUsize,
+    detached_workers: AtomicUsize,
+    processors: AtomicUsize,
+    clusters: AtomicUsize,
+}
+
+impl RouterCounts {
+    /// Count a newly registered worker or processor
+    pub(crate) fn started(&self, meta: &WorkerMeta) {
+        self.counter(meta).fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Stop counting a worker or processor which has been removed
+    pub(crate) fn stopped(&self, meta: &WorkerMeta) {
+        // The counters are only modified by the router so they can't underflow
+        self.counter(meta).fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Reset all the counts, when the router removes all its records at once
+    pub(crate) fn reset(&self) {
+        self.workers.store(0, Ordering::Relaxed);
+        self.detached_workers.store(0, Ordering::Relaxed);
+        self.processors.store(0, Ordering::Relaxed);
+    }
+
+    pub(crate) fn set_clusters(&self, clusters: usize) {
+        self.clusters.store(clusters, Ordering::Relaxed);
+    }
+
+    /// Total number of registered workers and processors
+    pub(crate) fn total(&self) -> usize {
+        self.workers.load(Ordering::Relaxed)
+            + self.detached_workers.load(Ordering::Relaxed)
+            + self.processors.load(Ordering::Relaxed)
+    }
+
+    fn counter(&self, meta: &WorkerMeta) -> &AtomicUsize {
+        if meta.processor {
+            &self.processors
+        } else if meta.detached {
+            &self.detached_workers
+        } else {
+            &self.workers
+        }
+    }
+}
+
+/// Collector for the runtime metrics of a node.
+///
+/// The statistics of each worker are periodically polled from the router, summed by worker
+/// type and exposed, with the counts of workers, as OpenTelemetry observable instruments.
+/// The instruments are kept alive as long as the collector.
+pub(crate) struct Metrics {
+    router: SmallSender<NodeMessage>,
+    worker_stats: Arc<Mutex<Vec<WorkerTypeStats>>>,
+    _workers_count: ObservableGauge<u64>,
+    _clusters_count: ObservableGauge<u64>,
+    _routed_messages: ObservableCounter<u64>,
+    _mailbox_size: ObservableGauge<u64>,
+    _max_mailbox_size: ObservableGauge<u64>,
+    _lost_messages: ObservableCounter<u64>,
 }
 
 impl Metrics {
-    /// Create a new Metrics collector with access to the runtime
-    pub(crate) fn new(
-        rt: &Arc<Runtime>,
-        router: (Arc<AtomicUsize>, Arc<AtomicUsize>),
-    ) -> Arc<Self> {
+    /// Create a new Metrics collector with access to the router
+    pub(crate) fn new(router: SmallSender<NodeMessage>, counts: Arc<RouterCounts>) -> Arc<Self> {
+        let meter = meter();
+        let worker_stats: Arc<Mutex<Vec<WorkerTypeStats>>> = Default::default();
+
+        let counts_for_clusters = counts.clone();
+        let workers_count = meter
+            .u64_observable_gauge("ockam.node.workers")
+            .with_description("Number of workers and processors running on the node, by type")
+            .with_callback(move |observer| {
+                for (kind, counter) in [
+                    ("worker", &counts.workers),
+                    ("detached_worker", &counts.detached_workers),
+                    ("processor", &counts.processors),
+                ] {
+                    observer.observe(
+                        counter.load(Ordering::Relaxed) as u64,
+                        &[KeyValue::new("kind", kind)],
+                    )
+                }
+            })
+            .init();
+
+        let clusters_count = meter
+            .u64_observable_gauge("ockam.node.clusters")
+            .with_description("Number of worker clusters on the node")
+            .with_callback(move |observer| {
+                observer.observe(
+                    counts_for_clusters.clusters.load(Ordering::Relaxed) as u64,
+                    &[],
+                )
+            })
+            .init();
+
+        let stats = worker_stats.clone();
+        let routed_messages = meter
+            .u64_observable_counter("ockam.router.routed_messages")
+            .with_description(
+                "Number of messages routed to the workers or processors of a given type",
+            )
+            .with_callback(move |observer| {
+                for s in stats.lock().unwrap().iter() {
+                    observer.observe(s.routed_messages, &attributes(s))
+                }
+            })
+            .init();
+
+        let stats = worker_stats.clone();
+        let mailbox_size = meter
+            .u64_observable_gauge("ockam.worker.mailbox_size")
+            .with_description(
+                "Number of messages waiting in the mailboxes of the workers of a given type",
+            )
+            .with_callback(move |observer| {
+                for s in stats.lock().unwrap().iter() {
+                    observer.observe(s.mailbox_size, &attributes(s))
+                }
+            })
+            .init();
+
+        let stats = worker_stats.clone();
+        let max_mailbox_size = meter
+            .u64_observable_gauge("ockam.worker.max_mailbox_size")
+            .with_description(
+                "Highest number of messages waiting in the mailbox of a worker of a given type",
+            )
+            .with_callback(move |observer| {
+                for s in stats.lock().unwrap().iter() {
+                    observer.observe(s.max_mailbox_size, &attributes(s))
+                }
+            })
+            .init();
+
+        let stats = worker_stats.clone();
+        let lost_messages = meter
+            .u64_observable_counter("ockam.worker.mailbox_overflows")
+            .with_description(
+                "Number of messages dropped or rejected because the mailbox of a worker was full",
+            )
+            .with_callback(move |observer| {
+                for s in stats.lock().unwrap().iter() {
+                    let mut dropped = attributes(s);
+                    dropped.push(KeyValue::new("outcome", "dropped"));
+                    observer.observe(s.dropped_messages, &dropped);
+                    let mut rejected = attributes(s);
+                    rejected.push(KeyValue::new("outcome", "rejected"));
+                    observer.observe(s.rejected_messages, &rejected);
+                }
+            })
+            .init();
+
         Arc::new(Self {
-            rt: Arc::clone(rt),
             router,
+            worker_stats,
+            _workers_count: workers_count,
+            _clusters_count: clusters_count,
+            _routed_messages: routed_messages,
+            _mailbox_size: mailbox_size,
+            _max_mailbox_size: max_mailbox_size,
+            _lost_messages: lost_messages,
         })
     }
 
-    /// Spawned by the Executor to periodically collect metrics
+    /// Spawned by the Executor to periodically collect the statistics of each worker
     pub(crate) async fn run(self: Arc<Self>, alive: Arc<AtomicBool>) {
-        let path = match get_env::<String>("OCKAM_METRICS_PATH") {
-            Ok(Some(path)) => path,
-            _ => {
-                debug!("Metrics collection disabled, set `OCKAM_METRICS_PATH` to collect metrics");
-                return;
-            }
-        };
-
-        let mut file = OpenOptions::new()
-            .create(true)
-            .truncate(true)
-            .write(true)
-            .open(path)
-            .expect("failed to open or create metrics collection file");
-
-        file.write_all(b"Worker busy time (% since last poll)\n")
-            .expect("failed to write metrics");
-
-        let freq_ms = 100;
-        let mut acc = MetricsReport::default();
-
         loop {
             if !alive.load(Ordering::Relaxed) {
                 debug!("Metrics collector shutting down...");
                 break;
             }
 
-            let report = self.generate_report(freq_ms, &mut acc);
-
-            file.write_all(format!("{}\n", report.to_csv()).as_bytes())
-                .expect("failed to write metrics");
-            time::sleep(Duration::from_millis(freq_ms)).await;
+            // The router doesn't reply anymore once it is stopped
+            match time::timeout(WORKER_STATS_POLL_INTERVAL, self.list_worker_stats()).await {
+                Ok(Ok(stats)) => *self.worker_stats.lock().unwrap() = WorkerTypeStats::sum(stats),
+                Ok(Err(e)) => {
+                    debug!("Metrics collector can't reach the router anymore: {e}");
+                    break;
+                }
+                Err(_) => continue,
+            }
+            time::sleep(WORKER_STATS_POLL_INTERVAL).await;
         }
     }
 
-    pub(crate) fn generate_report(
-        self: &Arc<Self>,
-        freq: u64,
-        acc: &mut MetricsReport,
-    ) -> MetricsReport {
-        let m = self.rt.metrics();
+    async fn list_worker_stats(&self) -> Result<Vec<WorkerStats>> {
+        let (msg, mut reply_rx) = NodeMessage::list_worker_stats();
 
-        let tokio_workers = m.num_workers();
-        let router_addr_count = self.router.0.load(Ordering::Acquire);
-        let router_cluster_count = self.router.1.load(Ordering::Acquire);
+        self.router
+            .send(msg)
+            .await
+            .map_err(NodeError::from_send_err)?;
 
-        let mut tokio_busy_ms = BTreeMap::new();
-        for wid in 0..tokio_workers {
-            // Get the previously accumulated
-            let acc_ms = acc.tokio_busy_ms.get(&wid).unwrap_or(&0);
-            let raw_ms = m.worker_total_busy_duration(wid).as_millis();
-
-            let diff_ms = raw_ms - acc_ms;
-            let percent = diff_ms as f32 / freq as f32;
-
-            tokio_busy_ms.insert(wid, percent as u128);
-            acc.tokio_busy_ms.insert(wid, raw_ms);
-        }
-
-        MetricsReport {
-            tokio_busy_ms,
-            router_addr_count,
-            router_cluster_count,
-        }
+        reply_rx
+            .recv()
+            .await
+            .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??
+            .take_worker_stats()
     }
 }
 
-#[derive(Default)]
-#[allow(unused)]
-pub struct MetricsReport {
-    tokio_busy_ms: BTreeMap<usize, u128>,
-    router_addr_count: usize,
-    router_cluster_count: usize,
+/// Statistics of all the workers, or processors, of a given type.
+///
+/// Metrics are not labelled by worker address since addresses are often random and
+/// would make the number of series unbounded
+#[derive(Debug, Default)]
+struct WorkerTypeStats {
+    worker_type: &'static str,
+    processor: bool,
+    routed_messages: u64,
+    mailbox_size: u64,
+    max_mailbox_size: u64,
+    dropped_messages: u64,
+    rejected_messages: u64,
 }
 
-impl MetricsReport {
-    /// Generate a line of CSV for this report
-    pub fn to_csv(&self) -> String {
-        self.tokio_busy_ms
-            .iter()
-            .map(|(wid, depth)| format!("({}:{}%)", wid, depth))
-            .collect::<Vec<String>>()
-            .join(",")
+impl WorkerTypeStats {
+    /// Sum the statistics of the workers having the same type
+    fn sum(stats: Vec<WorkerStats>) -> Vec<WorkerTypeStats> {
+        let mut by_type: BTreeMap<(&'static str, bool), WorkerTypeStats> = BTreeMap::new();
+        for s in stats {
+            let sum = by_type
+                .entry((s.worker_type, s.processor))
+                .or_insert_with(|| WorkerTypeStats {
+                    worker_type: s.worker_type,
+                    processor: s.processor,
+                    ..Default::default()
+                });
+            sum.routed_messages += s.routed_messages;
+            sum.mailbox_size += s.mailbox_size;
+            sum.max_mailbox_size = sum.max_mailbox_size.max(s.max_mailbox_size);
+            sum.dropped_messages += s.dropped_messages;
+            sum.rejected_messages += s.rejected_messages;
+        }
+        by_type.into_values().collect()
     }
 }
+
+/// Attributes identifying a type of worker or processor
+fn attributes(stats: &WorkerTypeStats) -> Vec<KeyValue> {
+    vec![
+        KeyValue::new("worker_type", stats.worker_type),
+        KeyValue::new(
+            "kind",
+            if stats.processor {
+                "processor"
+            } else {
+                "worker"
+            },
+        ),
+    ]
+}
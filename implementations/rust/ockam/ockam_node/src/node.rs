@@ -1,3 +1,4 @@
+use crate::channel_types::MailboxConfig;
 use crate::tokio::runtime::Runtime;
 use crate::{debugger, Context, Executor};
 use ockam_core::compat::sync::Arc;
@@ -135,6 +136,7 @@ impl NodeBuilder {
                 vec![],
             ),
             None,
+            MailboxConfig::default(),
             Default::default(),
             &flow_controls,
             #[cfg(feature = "std")]
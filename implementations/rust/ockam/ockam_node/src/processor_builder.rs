@@ -1,6 +1,7 @@
+use crate::channel_types::MailboxConfig;
 use crate::debugger;
 use crate::error::{NodeError, NodeReason};
-use crate::{relay::ProcessorRelay, Context, NodeMessage};
+use crate::{relay::ProcessorRelay, worker_type_name, Context, NodeMessage};
 use alloc::string::String;
 use ockam_core::compat::{sync::Arc, vec::Vec};
 use ockam_core::{
@@ -264,12 +265,13 @@ where
     let addresses = mailboxes.addresses();
 
     // Pass it to the context
-    let (ctx, sender, ctrl_rx) = context.copy_with_mailboxes(mailboxes);
+    let (ctx, sender, ctrl_rx) = context.copy_with_mailboxes(mailboxes, MailboxConfig::default());
 
     debugger::log_inherit_context("PROCESSOR", context, &ctx);
 
     // Send start request to router
-    let (msg, mut rx) = NodeMessage::start_processor(addresses, sender, metadata);
+    let (msg, mut rx) =
+        NodeMessage::start_processor(addresses, sender, worker_type_name::<P>(), metadata);
     context
         .sender()
         .send(msg)
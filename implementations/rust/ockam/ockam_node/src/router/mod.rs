@@ -8,18 +8,19 @@ mod stop_worker;
 mod utils;
 
 #[cfg(feature = "metrics")]
-use std::sync::atomic::AtomicUsize;
+use crate::metrics::RouterCounts;
 
-use record::{AddressRecord, InternalMap, WorkerMeta};
+pub(crate) use record::WorkerMeta;
+use record::{AddressRecord, InternalMap};
 use state::{NodeState, RouterState};
 
 use crate::channel_types::{router_channel, MessageSender, RouterReceiver, SmallSender};
 use crate::{
     error::{NodeError, NodeReason},
     relay::CtrlSignal,
-    NodeMessage, NodeReplyResult, RouterReply, ShutdownType,
+    NodeMessage, NodeReplyResult, RouterReply, ShutdownDrain, ShutdownType,
 };
-use ockam_core::compat::{collections::BTreeMap, sync::Arc};
+use ockam_core::compat::{collections::BTreeMap, sync::Arc, vec::Vec};
 use ockam_core::flow_control::FlowControls;
 use ockam_core::{Address, RelayMessage, Result, TransportType};
 
@@ -48,6 +49,9 @@ pub struct Router {
     external: BTreeMap<TransportType, Address>,
     /// Receiver for messages from node
     receiver: Option<RouterReceiver<NodeMessage>>,
+    /// Steps run before stopping the workers, during a graceful shutdown
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    drains: Vec<Arc<dyn ShutdownDrain>>,
 }
 
 enum RouteType {
@@ -71,11 +75,12 @@ impl Router {
             map: InternalMap::new(flow_controls),
             external: BTreeMap::new(),
             receiver: Some(receiver),
+            drains: Vec::new(),
         }
     }
 
     #[cfg(feature = "metrics")]
-    pub(crate) fn get_metrics_readout(&self) -> (Arc<AtomicUsize>, Arc<AtomicUsize>) {
+    pub(crate) fn get_metrics_readout(&self) -> Arc<RouterCounts> {
         self.map.get_metrics()
     }
 
@@ -97,6 +102,7 @@ impl Router {
                 WorkerMeta {
                     processor: false,
                     detached: true,
+                    worker_type: "Context",
                 },
             ),
         );
@@ -186,6 +192,7 @@ impl Router {
                 addrs,
                 senders,
                 detached,
+                worker_type,
                 mailbox_count,
                 ref reply,
                 addresses_metadata,
@@ -194,7 +201,11 @@ impl Router {
                     self,
                     addrs,
                     senders,
-                    detached,
+                    WorkerMeta {
+                        processor: false,
+                        detached,
+                        worker_type,
+                    },
                     addresses_metadata,
                     mailbox_count,
                     reply,
@@ -209,23 +220,39 @@ impl Router {
             StartProcessor {
                 addrs,
                 senders,
+                worker_type,
                 ref reply,
                 addresses_metadata,
-            } => start_processor::exec(self, addrs, senders, addresses_metadata, reply).await?,
+            } => {
+                start_processor::exec(self, addrs, senders, worker_type, addresses_metadata, reply)
+                    .await?
+            }
             StopProcessor(ref addr, ref reply) => stop_processor::exec(self, addr, reply).await?,
 
             //// ==! Core node controls
+            StopNode(ShutdownType::Graceful(_) | ShutdownType::Drain(_), reply)
+                if self.state.draining() =>
+            {
+                // The shutdown is resumed once the drain is done
+                self.state.wait_for_drain(reply);
+            }
+            StopNode(ShutdownType::Drain(timeout), reply) => {
+                // This sets state to draining, and then sends the Drained message
+                if !shutdown::drain(self, timeout, &reply)
+                    && self.stop_gracefully(timeout, vec![reply]).await?
+                {
+                    return Ok(true);
+                }
+            }
+            Drained(timeout) => {
+                let replies = self.state.drained();
+                if self.stop_gracefully(timeout, replies).await? {
+                    return Ok(true);
+                }
+            }
             StopNode(ShutdownType::Graceful(timeout), reply) => {
-                // This sets state to stopping, and the sends the AbortNode message
-                if shutdown::graceful(self, timeout, reply).await? {
-                    info!("No more workers left.  Goodbye!");
-                    if let Some(sender) = self.state.stop_reply() {
-                        sender
-                            .send(RouterReply::ok())
-                            .await
-                            .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?;
-                        return Ok(true);
-                    };
+                if self.stop_gracefully(timeout, vec![reply]).await? {
+                    return Ok(true);
                 }
             }
             StopNode(ShutdownType::Immediate, reply) => {
@@ -233,12 +260,16 @@ impl Router {
                 return Ok(true);
             }
 
+            RegisterShutdownDrain(drain, reply) => {
+                self.drains.push(drain);
+                reply
+                    .send(RouterReply::ok())
+                    .await
+                    .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?;
+            }
+
             AbortNode => {
-                if let Some(sender) = self.state.stop_reply() {
-                    sender
-                        .send(RouterReply::ok())
-                        .await
-                        .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?;
+                if self.reply_stopped().await? {
                     self.map.clear_address_records_map();
                     return Ok(true);
                 }
@@ -252,11 +283,7 @@ impl Router {
             StopAck(addr) => {
                 if shutdown::ack(self, addr).await? {
                     info!("No more workers left.  Goodbye!");
-                    if let Some(sender) = self.state.stop_reply() {
-                        sender
-                            .send(RouterReply::ok())
-                            .await
-                            .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?;
+                    if self.reply_stopped().await? {
                         return Ok(true);
                     }
                 }
@@ -269,6 +296,17 @@ impl Router {
                 .await
                 .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?,
 
+            ListWorkerStats(sender) => sender
+                .send(RouterReply::worker_stats(
+                    self.map
+                        .address_records_map()
+                        .iter()
+                        .map(|(address, record)| record.stats(address))
+                        .collect(),
+                ))
+                .await
+                .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?,
+
             IsWorkerRegisteredAt(sender, address) => sender
                 .send(RouterReply::worker_is_registered_at_address(
                     self.map.address_records_map().contains_key(&address),
@@ -337,6 +375,35 @@ impl Router {
         Ok(false)
     }
 
+    /// Start a graceful shutdown. Return true if the node is already stopped
+    async fn stop_gracefully(
+        &mut self,
+        timeout: u8,
+        replies: Vec<SmallSender<NodeReplyResult>>,
+    ) -> Result<bool> {
+        // This sets state to stopping, and the sends the AbortNode message
+        if shutdown::graceful(self, timeout, replies).await? {
+            info!("No more workers left.  Goodbye!");
+            return self.reply_stopped().await;
+        }
+        Ok(false)
+    }
+
+    /// Let all the callers which requested the node to stop know that it is stopped.
+    /// Return false if the node is not stopping
+    async fn reply_stopped(&self) -> Result<bool> {
+        let Some(senders) = self.state.stop_replies() else {
+            return Ok(false);
+        };
+        let mut result = Ok(true);
+        for sender in senders {
+            if sender.send(RouterReply::ok()).await.is_err() {
+                result = Err(NodeError::NodeState(NodeReason::Unknown).internal());
+            }
+        }
+        result
+    }
+
     /// Block current task running this router.  Return fatal errors
     async fn run_inner(&mut self) -> Result<()> {
         while let Some(msg) = self.get_recv()?.recv().await {
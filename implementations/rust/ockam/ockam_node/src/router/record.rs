@@ -1,8 +1,10 @@
 use crate::channel_types::{MessageSender, SmallSender};
+#[cfg(feature = "metrics")]
+use crate::metrics::RouterCounts;
 use crate::relay::CtrlSignal;
 use crate::{
     error::{NodeError, NodeReason},
-    NodeReplyResult, RouterReply,
+    NodeReplyResult, RouterReply, WorkerStats,
 };
 use core::sync::atomic::{AtomicUsize, Ordering};
 use ockam_core::{
@@ -32,9 +34,9 @@ pub struct InternalMap {
     stopping: BTreeSet<Address>,
     /// Access to [`FlowControls`] to clean resources
     flow_controls: FlowControls,
-    /// Metrics collection and sharing
+    /// Counts of workers and processors, shared with the metrics collector
     #[cfg(feature = "metrics")]
-    metrics: (Arc<AtomicUsize>, Arc<AtomicUsize>),
+    metrics: Arc<RouterCounts>,
 }
 
 impl InternalMap {
@@ -55,6 +57,8 @@ impl InternalMap {
 
 impl InternalMap {
     pub(super) fn clear_address_records_map(&mut self) {
+        #[cfg(feature = "metrics")]
+        self.metrics.reset();
         self.address_records_map.clear()
     }
 
@@ -78,7 +82,12 @@ impl InternalMap {
         primary_address: &Address,
     ) -> Option<AddressRecord> {
         self.flow_controls.cleanup_address(primary_address);
-        self.address_records_map.remove(primary_address)
+        let record = self.address_records_map.remove(primary_address);
+        #[cfg(feature = "metrics")]
+        if let Some(record) = &record {
+            self.metrics.stopped(&record.meta);
+        }
+        record
     }
 
     pub(super) fn insert_address_record(
@@ -86,7 +95,14 @@ impl InternalMap {
         primary_address: Address,
         record: AddressRecord,
     ) -> Option<AddressRecord> {
-        self.address_records_map.insert(primary_address, record)
+        #[cfg(feature = "metrics")]
+        self.metrics.started(&record.meta);
+        let previous = self.address_records_map.insert(primary_address, record);
+        #[cfg(feature = "metrics")]
+        if let Some(previous) = &previous {
+            self.metrics.stopped(&previous.meta);
+        }
+        previous
     }
 
     pub(super) fn find_terminal_address(
@@ -131,20 +147,17 @@ impl InternalMap {
 impl InternalMap {
     #[cfg(feature = "metrics")]
     pub(super) fn update_metrics(&self) {
-        self.metrics
-            .0
-            .store(self.address_records_map.len(), Ordering::Release);
-        self.metrics.1.store(self.clusters.len(), Ordering::Release);
+        self.metrics.set_clusters(self.clusters.len());
     }
 
     #[cfg(feature = "metrics")]
-    pub(super) fn get_metrics(&self) -> (Arc<AtomicUsize>, Arc<AtomicUsize>) {
-        (Arc::clone(&self.metrics.0), Arc::clone(&self.metrics.1))
+    pub(super) fn get_metrics(&self) -> Arc<RouterCounts> {
+        Arc::clone(&self.metrics)
     }
 
     #[cfg(feature = "metrics")]
     pub(super) fn get_addr_count(&self) -> usize {
-        self.metrics.0.load(Ordering::Acquire)
+        self.metrics.total()
     }
 
     /// Add an address to a particular cluster
@@ -267,6 +280,7 @@ impl InternalMap {
 pub struct WorkerMeta {
     pub processor: bool,
     pub detached: bool,
+    pub worker_type: &'static str,
 }
 
 #[derive(Debug)]
@@ -278,6 +292,7 @@ pub struct AddressRecord {
     ready: ReadyState,
     meta: WorkerMeta,
     msg_count: Arc<AtomicUsize>,
+    routed_count: AtomicUsize,
 }
 
 impl AddressRecord {
@@ -285,6 +300,10 @@ impl AddressRecord {
         &self.address_set
     }
 
+    pub fn worker_type(&self) -> &'static str {
+        self.meta.worker_type
+    }
+
     pub fn sender(&self) -> MessageSender<RelayMessage> {
         self.sender.clone().expect("No such sender!")
     }
@@ -307,6 +326,7 @@ impl AddressRecord {
             state: AddressState::Running,
             ready: ReadyState::Initialising(vec![]),
             msg_count,
+            routed_count: AtomicUsize::new(0),
             meta,
         }
     }
@@ -314,6 +334,27 @@ impl AddressRecord {
     #[inline]
     pub fn increment_msg_count(&self) {
         self.msg_count.fetch_add(1, Ordering::Relaxed);
+        self.routed_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Return the statistics of this worker, identified by its primary address
+    pub fn stats(&self, primary_address: &Address) -> WorkerStats {
+        let mailbox = self
+            .sender
+            .as_ref()
+            .map(|sender| sender.stats())
+            .unwrap_or_default();
+        WorkerStats {
+            address: primary_address.clone(),
+            processor: self.meta.processor,
+            worker_type: self.meta.worker_type,
+            routed_messages: self.routed_count.load(Ordering::Relaxed) as u64,
+            mailbox_size: mailbox.queue_depth as u64,
+            mailbox_capacity: mailbox.capacity as u64,
+            max_mailbox_size: mailbox.max_queue_depth as u64,
+            dropped_messages: mailbox.dropped_messages as u64,
+            rejected_messages: mailbox.rejected_messages as u64,
+        }
     }
 
     /// Signal this worker to stop -- it will no longer be able to receive messages
@@ -382,7 +423,7 @@ pub enum ReadyState {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::channel_types::small_channel;
+    use crate::channel_types::{message_channel, small_channel};
     use crate::router::record::InternalMap;
 
     #[test]
@@ -416,7 +457,7 @@ mod test {
 
     /// HELPERS
     fn create_address_record(primary: &str) -> AddressRecord {
-        let (tx1, _) = small_channel();
+        let (tx1, _) = message_channel();
         let (tx2, _) = small_channel();
         AddressRecord::new(
             vec![primary.into()],
@@ -426,6 +467,7 @@ mod test {
             WorkerMeta {
                 processor: false,
                 detached: false,
+                worker_type: "Worker",
             },
         )
     }
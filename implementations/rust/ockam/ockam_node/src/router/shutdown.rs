@@ -64,12 +64,12 @@ impl Router {
 pub(super) async fn graceful(
     router: &mut Router,
     seconds: u8,
-    reply: SmallSender<NodeReplyResult>,
+    replies: Vec<SmallSender<NodeReplyResult>>,
 ) -> Result<bool> {
     // Mark the router as shutting down to prevent spawning
     info!("Initiate graceful node shutdown");
     // This changes the router state to `Stopping`
-    router.state.shutdown(reply);
+    router.state.shutdown(replies);
 
     // Start by shutting down clusterless workers
     let mut cluster = vec![];
@@ -116,6 +116,53 @@ pub(super) async fn graceful(
     Ok(false)
 }
 
+/// Run all the registered drains, while the router keeps running, then
+/// let the router know that it can resume with a graceful shutdown.
+///
+/// Return true if the drain step was started, false if there is nothing to drain
+#[cfg(feature = "std")]
+pub(super) fn drain(
+    router: &mut Router,
+    seconds: u8,
+    reply: &SmallSender<NodeReplyResult>,
+) -> bool {
+    use crate::NodeMessage;
+    use core::time::Duration;
+    use futures::future::join_all;
+    use tokio::{task, time};
+
+    if router.drains.is_empty() || seconds == 0 {
+        return false;
+    }
+
+    info!("Draining the node before shutting it down");
+    // This changes the router state to `Draining`
+    router.state.drain(reply.clone());
+
+    let drains = router.drains.clone();
+    let sender = router.sender();
+    let timeout = Duration::from_secs(seconds as u64);
+    task::spawn(async move {
+        let drained = join_all(drains.iter().map(|drain| drain.drain(Some(timeout))));
+        if time::timeout(timeout, drained).await.is_err() {
+            warn!("Drain timeout reached; stopping the workers");
+        }
+        if sender.send(NodeMessage::Drained(seconds)).await.is_err() {
+            error!("Failed to send the end of the drain to router");
+        }
+    });
+    true
+}
+
+#[cfg(not(feature = "std"))]
+pub(super) fn drain(
+    _router: &mut Router,
+    _seconds: u8,
+    _reply: &SmallSender<NodeReplyResult>,
+) -> bool {
+    false
+}
+
 /// Implement the immediate shutdown strategy
 ///
 /// When triggering an `immediate` shutdown, all worker handles are
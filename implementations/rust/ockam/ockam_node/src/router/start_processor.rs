@@ -14,11 +14,22 @@ pub(super) async fn exec(
     router: &mut Router,
     addrs: Vec<Address>,
     senders: SenderPair,
+    worker_type: &'static str,
     addresses_metadata: Vec<AddressAndMetadata>,
     reply: &SmallSender<NodeReplyResult>,
 ) -> Result<()> {
     match router.state.node_state() {
-        NodeState::Running => start(router, addrs, senders, addresses_metadata, reply).await,
+        NodeState::Running | NodeState::Draining(_) => {
+            start(
+                router,
+                addrs,
+                senders,
+                worker_type,
+                addresses_metadata,
+                reply,
+            )
+            .await
+        }
         NodeState::Stopping(_) => reject(reply).await,
         NodeState::Dead => unreachable!(),
     }?;
@@ -29,6 +40,7 @@ async fn start(
     router: &mut Router,
     addrs: Vec<Address>,
     senders: SenderPair,
+    worker_type: &'static str,
     addresses_metadata: Vec<AddressAndMetadata>,
     reply: &SmallSender<NodeReplyResult>,
 ) -> Result<()> {
@@ -55,6 +67,7 @@ async fn start(
         WorkerMeta {
             processor: true,
             detached: false,
+            worker_type,
         },
     );
 
@@ -17,18 +17,18 @@ pub(super) async fn exec(
     router: &mut Router,
     addrs: Vec<Address>,
     senders: SenderPair,
-    detached: bool,
+    meta: WorkerMeta,
     addresses_metadata: Vec<AddressAndMetadata>,
     metrics: Arc<AtomicUsize>,
     reply: &SmallSender<NodeReplyResult>,
 ) -> Result<()> {
     match router.state.node_state() {
-        NodeState::Running => {
+        NodeState::Running | NodeState::Draining(_) => {
             start(
                 router,
                 addrs,
                 senders,
-                detached,
+                meta,
                 addresses_metadata,
                 metrics,
                 reply,
@@ -45,7 +45,7 @@ async fn start(
     router: &mut Router,
     addrs: Vec<Address>,
     senders: SenderPair,
-    detached: bool,
+    meta: WorkerMeta,
     addresses_metadata: Vec<AddressAndMetadata>,
     metrics: Arc<AtomicUsize>,
     reply: &SmallSender<NodeReplyResult>,
@@ -63,16 +63,7 @@ async fn start(
     // Create an address record and insert it into the internal map
 
     // FIXME: Check for duplicates
-    let address_record = AddressRecord::new(
-        addrs.clone(),
-        msgs,
-        ctrl,
-        metrics,
-        WorkerMeta {
-            processor: false,
-            detached,
-        },
-    );
+    let address_record = AddressRecord::new(addrs.clone(), msgs, ctrl, metrics, meta);
 
     router
         .map
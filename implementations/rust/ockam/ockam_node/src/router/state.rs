@@ -2,10 +2,13 @@
 
 use crate::channel_types::SmallSender;
 use crate::messages::{NodeMessage, NodeReplyResult};
+use ockam_core::compat::vec::Vec;
 
 pub enum NodeState {
     Running,
-    Stopping(SmallSender<NodeReplyResult>),
+    /// The registered drains are running. The replies are sent once the node is stopped
+    Draining(Vec<SmallSender<NodeReplyResult>>),
+    Stopping(Vec<SmallSender<NodeReplyResult>>),
     Dead,
 }
 
@@ -22,9 +25,33 @@ impl RouterState {
         }
     }
 
+    /// Toggle this router to drain its workers before shutting down
+    pub(super) fn drain(&mut self, reply: SmallSender<NodeReplyResult>) {
+        self.node_state = NodeState::Draining(vec![reply])
+    }
+
+    pub(super) fn draining(&self) -> bool {
+        core::matches!(self.node_state, NodeState::Draining(_))
+    }
+
+    /// Reply to another stop request once the drain is done and the node is stopped
+    pub(super) fn wait_for_drain(&mut self, reply: SmallSender<NodeReplyResult>) {
+        if let NodeState::Draining(replies) = &mut self.node_state {
+            replies.push(reply)
+        }
+    }
+
+    /// Return the replies waiting for the end of the drain
+    pub(super) fn drained(&mut self) -> Vec<SmallSender<NodeReplyResult>> {
+        match &mut self.node_state {
+            NodeState::Draining(replies) => core::mem::take(replies),
+            _ => vec![],
+        }
+    }
+
     /// Toggle this router to shut down soon
-    pub(super) fn shutdown(&mut self, reply: SmallSender<NodeReplyResult>) {
-        self.node_state = NodeState::Stopping(reply)
+    pub(super) fn shutdown(&mut self, replies: Vec<SmallSender<NodeReplyResult>>) {
+        self.node_state = NodeState::Stopping(replies)
     }
 
     /// Ungracefully kill the router
@@ -32,15 +59,15 @@ impl RouterState {
         self.node_state = NodeState::Dead;
     }
 
-    pub(super) fn stop_reply(&self) -> Option<SmallSender<NodeReplyResult>> {
+    pub(super) fn stop_replies(&self) -> Option<Vec<SmallSender<NodeReplyResult>>> {
         match &self.node_state {
-            NodeState::Stopping(sender) => Some(sender.clone()),
+            NodeState::Stopping(senders) => Some(senders.clone()),
             _ => None,
         }
     }
 
     pub fn running(&self) -> bool {
-        core::matches!(self.node_state, NodeState::Running)
+        core::matches!(self.node_state, NodeState::Running | NodeState::Draining(_))
     }
 
     /// Check if this router is still `running`, meaning allows
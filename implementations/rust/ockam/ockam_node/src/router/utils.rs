@@ -34,7 +34,11 @@ pub(super) async fn resolve(
         Some(record) if record.check() => {
             trace!("{} OK", base);
             record.increment_msg_count();
-            reply.send(RouterReply::sender(addr, record.sender()))
+            reply.send(RouterReply::sender(
+                addr,
+                record.sender(),
+                record.worker_type(),
+            ))
         }
         Some(_) => {
             trace!("{} REJECTED; worker shutting down", base);
@@ -0,0 +1,26 @@
+use core::fmt::{Debug, Formatter};
+use core::time::Duration;
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+
+/// A step run by the router when the node is stopped with [`Context::stop_with_drain`](crate::Context::stop_with_drain),
+/// before any worker is stopped. A plain graceful stop doesn't run the drains.
+///
+/// While a node is draining, messages are still routed and workers can still be started,
+/// so that a component can finish its in-flight work. For example, the TCP transport waits
+/// for the open portal connections to be closed, while refusing new ones.
+///
+/// A drain is registered with [`Context::register_shutdown_drain`](crate::Context::register_shutdown_drain).
+#[async_trait]
+pub trait ShutdownDrain: Send + Sync + 'static {
+    /// Drain the component. This function must return before the `timeout`,
+    /// after which the node is stopped anyway. A timeout of `None` means that the node
+    /// waits until this function returns
+    async fn drain(&self, timeout: Option<Duration>);
+}
+
+impl Debug for dyn ShutdownDrain {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str("ShutdownDrain")
+    }
+}
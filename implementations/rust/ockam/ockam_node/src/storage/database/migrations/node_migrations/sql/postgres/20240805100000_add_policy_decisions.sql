@@ -0,0 +1,16 @@
+-- This table stores the decisions taken by the policy access controls of a node
+CREATE TABLE policy_decision
+(
+    node_name          TEXT    NOT NULL, -- Name of the node which took the decision
+    timestamp          INTEGER NOT NULL, -- UNIX timestamp in seconds
+    allowed            BOOLEAN NOT NULL, -- True if access was granted
+    subject            TEXT,             -- Identifier of the subject, if known
+    subject_attributes TEXT    NOT NULL, -- Attributes of the subject, as a JSON object
+    resource_name      TEXT,             -- Name of the protected resource, if known
+    resource_type      TEXT,             -- Type of the protected resource, if known
+    action             TEXT,             -- Action performed on the resource, if known
+    expression         TEXT,             -- Policy expression which was evaluated, if any
+    reason             TEXT              -- Explanation for a denial which was not the result of an evaluation
+);
+
+CREATE INDEX policy_decision_node_timestamp_index ON policy_decision (node_name, timestamp);
@@ -0,0 +1,4 @@
+-- When these columns are set, the signing keys of the vault are stored in a PKCS#11 token (HSM)
+-- In that case only key handles are stored in the database
+ALTER TABLE vault ADD COLUMN pkcs11_module TEXT NULL;   -- Path to the PKCS#11 module (shared library)
+ALTER TABLE vault ADD COLUMN pkcs11_slot   BIGINT NULL; -- Id of the slot holding the token
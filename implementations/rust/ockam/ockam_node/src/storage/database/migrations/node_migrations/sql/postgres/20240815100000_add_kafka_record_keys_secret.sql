@@ -0,0 +1,7 @@
+-- This table stores the secret used by the Kafka inlets of a node to derive the secrets encrypting the records keys
+-- It is persisted so that a given record key is encrypted to the same value after a restart of the node
+CREATE TABLE kafka_record_keys_secret
+(
+    node_name TEXT PRIMARY KEY, -- Name of the node running the Kafka inlets
+    secret    BYTEA NOT NULL    -- Secret used to derive the record keys secrets of each topic
+);
@@ -0,0 +1,34 @@
+-- This table stores the latest verified revocation list received by a node from an authority
+CREATE TABLE revocation_list
+(
+    authority_identifier TEXT    NOT NULL, -- Identifier of the authority which signed the revocation list
+    version              INTEGER NOT NULL, -- Version of the revocation list, it increases with each new revocation
+    revocation_list      BLOB    NOT NULL, -- Encoded revocation list: data + signature + purpose key attestation
+    node_name            TEXT    NOT NULL  -- Name of the node which received the revocation list
+);
+
+CREATE UNIQUE INDEX revocation_list_authority_node_index ON revocation_list (authority_identifier, node_name);
+
+-- This table stores the subjects revoked by the latest revocation list received by a node from an authority
+CREATE TABLE revoked_subject
+(
+    authority_identifier TEXT    NOT NULL, -- Identifier of the authority which revoked the subject
+    subject_identifier   TEXT    NOT NULL, -- Identifier of the revoked subject
+    revoked_at           INTEGER NOT NULL, -- UNIX timestamp in seconds: credentials created before that time are revoked
+    node_name            TEXT    NOT NULL  -- Name of the node which received the revocation list
+);
+
+CREATE UNIQUE INDEX revoked_subject_authority_subject_node_index ON revoked_subject (authority_identifier, subject_identifier, node_name);
+
+-- This table stores the members revoked by an authority node
+CREATE TABLE authority_revoked_member
+(
+    identifier TEXT    NOT NULL UNIQUE, -- Identifier of the revoked member
+    revoked_by TEXT    NOT NULL,        -- Identifier of the enroller who removed the member
+    revoked_at INTEGER NOT NULL,        -- UNIX timestamp in seconds
+    revision   INTEGER NOT NULL         -- Version of the revocation list which first contained this revocation
+);
+
+-- Creation time of the credential attesting the attributes of an identity, if any
+-- It is compared with the revocation time of the identity in the revocation list of the authority
+ALTER TABLE identity_attributes ADD COLUMN credential_created_at INTEGER NULL;
@@ -0,0 +1,3 @@
+-- Latest change hash of a member identity, known by the authority when it last issued a credential for that member
+-- It follows the rotations of the member identity keys
+ALTER TABLE authority_member ADD COLUMN last_change_hash TEXT NULL;
@@ -1,7 +1,9 @@
+use opentelemetry::metrics::{Counter, Meter, ObservableCounter, ObservableGauge};
+use opentelemetry::KeyValue;
 use std::collections::HashMap;
 use std::thread::ThreadId;
 use std::time::Duration;
-use tokio::runtime::Runtime;
+use tokio::runtime::{Runtime, RuntimeMetrics};
 
 struct ThreadInfo {
     poll_count: u64,
@@ -11,6 +13,8 @@ struct ThreadInfo {
 
 /// Detects if there is any thread stuck on a task and write a warning log about it.
 /// Pauses the process with SIGSTOP to allow the debugger to attach to the process.
+///
+/// The watchdog also exports the tokio runtime statistics as OpenTelemetry metrics.
 pub(crate) struct TokioRuntimeWatchdog {
     thread_map: HashMap<usize, ThreadInfo>,
 }
@@ -30,8 +34,10 @@ impl TokioRuntimeWatchdog {
         });
     }
 
-    fn watchdog_loop(mut self, tokio_metrics: tokio::runtime::RuntimeMetrics) {
+    fn watchdog_loop(mut self, tokio_metrics: RuntimeMetrics) {
         info!("Starting tokio runtime watchdog");
+        let runtime_metrics = TokioRuntimeMetrics::new(&crate::metrics::meter(), &tokio_metrics);
+
         const WATCHDOG_INTERVAL: Duration = Duration::from_millis(50);
         let watchdog_interval = std::env::var("OCKAM_WATCHDOG_INTERVAL")
             .map(|s| Duration::from_millis(s.parse().unwrap()))
@@ -67,6 +73,7 @@ impl TokioRuntimeWatchdog {
                     && !is_parked
                     && thread_info.poll_count == poll_count
                 {
+                    runtime_metrics.record_stuck_worker(*tokio_worker);
                     let ms = watchdog_interval.as_millis();
                     let tid = thread_info.thread_id;
                     let pid = std::process::id();
@@ -101,3 +108,87 @@ impl TokioRuntimeWatchdog {
         }
     }
 }
+
+/// OpenTelemetry instruments reporting the statistics of the tokio runtime.
+/// The observable instruments are kept alive as long as the watchdog is running.
+struct TokioRuntimeMetrics {
+    stuck_workers: Counter<u64>,
+    _workers_count: ObservableGauge<u64>,
+    _alive_tasks: ObservableGauge<u64>,
+    _global_queue_depth: ObservableGauge<u64>,
+    _busy_duration: ObservableCounter<f64>,
+    _poll_count: ObservableCounter<u64>,
+}
+
+impl TokioRuntimeMetrics {
+    fn new(meter: &Meter, tokio_metrics: &RuntimeMetrics) -> Self {
+        let m = tokio_metrics.clone();
+        let workers_count = meter
+            .u64_observable_gauge("ockam.tokio.workers")
+            .with_description("Number of worker threads of the tokio runtime")
+            .with_callback(move |observer| observer.observe(m.num_workers() as u64, &[]))
+            .init();
+
+        let m = tokio_metrics.clone();
+        let alive_tasks = meter
+            .u64_observable_gauge("ockam.tokio.alive_tasks")
+            .with_description("Number of tasks alive in the tokio runtime")
+            .with_callback(move |observer| observer.observe(m.num_alive_tasks() as u64, &[]))
+            .init();
+
+        let m = tokio_metrics.clone();
+        let global_queue_depth = meter
+            .u64_observable_gauge("ockam.tokio.global_queue_depth")
+            .with_description("Number of tasks waiting in the global queue of the tokio runtime")
+            .with_callback(move |observer| observer.observe(m.global_queue_depth() as u64, &[]))
+            .init();
+
+        let m = tokio_metrics.clone();
+        let busy_duration = meter
+            .f64_observable_counter("ockam.tokio.worker.busy_duration")
+            .with_description("Time spent by a tokio worker thread running tasks")
+            .with_unit("s")
+            .with_callback(move |observer| {
+                for worker in 0..m.num_workers() {
+                    observer.observe(
+                        m.worker_total_busy_duration(worker).as_secs_f64(),
+                        &[worker_attribute(worker)],
+                    )
+                }
+            })
+            .init();
+
+        let m = tokio_metrics.clone();
+        let poll_count = meter
+            .u64_observable_counter("ockam.tokio.worker.polls")
+            .with_description("Number of tasks polled by a tokio worker thread")
+            .with_callback(move |observer| {
+                for worker in 0..m.num_workers() {
+                    observer.observe(m.worker_poll_count(worker), &[worker_attribute(worker)])
+                }
+            })
+            .init();
+
+        let stuck_workers = meter
+            .u64_counter("ockam.tokio.worker.stuck")
+            .with_description("Number of times a tokio worker thread was detected as stuck")
+            .init();
+
+        Self {
+            stuck_workers,
+            _workers_count: workers_count,
+            _alive_tasks: alive_tasks,
+            _global_queue_depth: global_queue_depth,
+            _busy_duration: busy_duration,
+            _poll_count: poll_count,
+        }
+    }
+
+    fn record_stuck_worker(&self, worker: usize) {
+        self.stuck_workers.add(1, &[worker_attribute(worker)]);
+    }
+}
+
+fn worker_attribute(worker: usize) -> KeyValue {
+    KeyValue::new("worker", worker as i64)
+}
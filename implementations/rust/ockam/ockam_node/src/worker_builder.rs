@@ -1,6 +1,7 @@
+use crate::channel_types::{MailboxConfig, MailboxOverflowPolicy};
 use crate::debugger;
 use crate::error::{NodeError, NodeReason};
-use crate::{relay::WorkerRelay, Context, NodeMessage};
+use crate::{relay::WorkerRelay, worker_type_name, Context, NodeMessage};
 use alloc::string::String;
 use ockam_core::compat::{sync::Arc, vec::Vec};
 use ockam_core::{
@@ -42,6 +43,7 @@ where
             worker: self.worker,
             address: address.into(),
             metadata: None,
+            mailbox_config: MailboxConfig::default(),
         }
     }
 
@@ -51,6 +53,7 @@ where
             mailboxes,
             worker: self.worker,
             metadata_list: vec![],
+            mailbox_config: MailboxConfig::default(),
         }
     }
 }
@@ -62,6 +65,7 @@ where
     mailboxes: Mailboxes,
     worker: W,
     metadata_list: Vec<AddressAndMetadata>,
+    mailbox_config: MailboxConfig,
 }
 
 impl<W> WorkerBuilderMultipleAddresses<W>
@@ -124,9 +128,34 @@ where
         self
     }
 
+    /// Set the maximum number of messages waiting in the worker mailbox
+    pub fn with_mailbox_capacity(mut self, capacity: usize) -> Self {
+        self.mailbox_config = self.mailbox_config.with_capacity(capacity);
+        self
+    }
+
+    /// Set what to do with a message sent to the worker when its mailbox is full
+    pub fn with_mailbox_overflow_policy(mut self, overflow_policy: MailboxOverflowPolicy) -> Self {
+        self.mailbox_config = self.mailbox_config.with_overflow_policy(overflow_policy);
+        self
+    }
+
+    /// Set the [`MailboxConfig`] of the worker
+    pub fn with_mailbox_config(mut self, mailbox_config: MailboxConfig) -> Self {
+        self.mailbox_config = mailbox_config;
+        self
+    }
+
     /// Consume this builder and start a new Ockam [`Worker`] from the given context
     pub async fn start(self, context: &Context) -> Result<()> {
-        start(context, self.mailboxes, self.worker, self.metadata_list).await
+        start(
+            context,
+            self.mailboxes,
+            self.worker,
+            self.metadata_list,
+            self.mailbox_config,
+        )
+        .await
     }
 }
 
@@ -139,6 +168,7 @@ where
     address: Address,
     worker: W,
     metadata: Option<AddressAndMetadata>,
+    mailbox_config: MailboxConfig,
 }
 
 impl<W> WorkerBuilderOneAddress<W>
@@ -197,6 +227,7 @@ where
             Mailboxes::main(self.address, self.incoming_ac, self.outgoing_ac),
             self.worker,
             self.metadata.map(|m| vec![m]).unwrap_or_default(),
+            self.mailbox_config,
         )
         .await
     }
@@ -241,6 +272,24 @@ where
         self.outgoing_ac = outgoing_access_control.clone();
         self
     }
+
+    /// Set the maximum number of messages waiting in the worker mailbox
+    pub fn with_mailbox_capacity(mut self, capacity: usize) -> Self {
+        self.mailbox_config = self.mailbox_config.with_capacity(capacity);
+        self
+    }
+
+    /// Set what to do with a message sent to the worker when its mailbox is full
+    pub fn with_mailbox_overflow_policy(mut self, overflow_policy: MailboxOverflowPolicy) -> Self {
+        self.mailbox_config = self.mailbox_config.with_overflow_policy(overflow_policy);
+        self
+    }
+
+    /// Set the [`MailboxConfig`] of the worker
+    pub fn with_mailbox_config(mut self, mailbox_config: MailboxConfig) -> Self {
+        self.mailbox_config = mailbox_config;
+        self
+    }
 }
 
 /// Consume this builder and start a new Ockam [`Worker`] from the given context
@@ -249,6 +298,7 @@ async fn start<W>(
     mailboxes: Mailboxes,
     worker: W,
     metadata: Vec<AddressAndMetadata>,
+    mailbox_config: MailboxConfig,
 ) -> Result<()>
 where
     W: Worker<Context = Context>,
@@ -263,13 +313,19 @@ where
     let addresses = mailboxes.addresses();
 
     // Pass it to the context
-    let (ctx, sender, ctrl_rx) = context.copy_with_mailboxes(mailboxes);
+    let (ctx, sender, ctrl_rx) = context.copy_with_mailboxes(mailboxes, mailbox_config);
 
     debugger::log_inherit_context("WORKER", context, &ctx);
 
     // Send start request to router
-    let (msg, mut rx) =
-        NodeMessage::start_worker(addresses, sender, false, context.mailbox_count(), metadata);
+    let (msg, mut rx) = NodeMessage::start_worker(
+        addresses,
+        sender,
+        false,
+        worker_type_name::<W>(),
+        context.mailbox_count(),
+        metadata,
+    );
     context
         .sender()
         .send(msg)
@@ -8,8 +8,9 @@ use ockam_core::compat::{
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{async_trait, Address, AllowAll, Any, Decodable, DenyAll, Message, LOCAL};
 use ockam_core::{route, Processor, Result, Routed, Worker};
+use ockam_node::channel_types::MailboxOverflowPolicy;
 use ockam_node::compat::futures::FutureExt;
-use ockam_node::{Context, MessageReceiveOptions, NodeBuilder};
+use ockam_node::{Context, MessageReceiveOptions, NodeBuilder, ShutdownDrain, WorkerBuilder};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicI8, AtomicU32};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -133,6 +134,101 @@ async fn simple_worker__run_node_lifecycle__worker_lifecycle_should_be_full(
     Ok(())
 }
 
+struct CheckWorkerRunningDrain {
+    worker_shutdown_was_called: Arc<AtomicBool>,
+    worker_was_running: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl ShutdownDrain for CheckWorkerRunningDrain {
+    async fn drain(&self, _timeout: Option<Duration>) {
+        let running = !self.worker_shutdown_was_called.load(Ordering::Relaxed);
+        self.worker_was_running.store(running, Ordering::Relaxed);
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn shutdown_drain__graceful_shutdown__drain_should_run_before_stopping_workers(
+    ctx: &mut Context,
+) -> Result<()> {
+    let initialize_was_called = Arc::new(AtomicBool::new(false));
+    let shutdown_was_called = Arc::new(AtomicBool::new(false));
+    let worker_was_running = Arc::new(AtomicBool::new(false));
+
+    let worker = SimpleWorker {
+        initialize_was_called: initialize_was_called.clone(),
+        shutdown_was_called: shutdown_was_called.clone(),
+    };
+    ctx.start_worker("simple_worker", worker).await?;
+
+    ctx.register_shutdown_drain(Arc::new(CheckWorkerRunningDrain {
+        worker_shutdown_was_called: shutdown_was_called.clone(),
+        worker_was_running: worker_was_running.clone(),
+    }))
+    .await?;
+
+    ctx.stop_with_drain(1).await?;
+    sleep(Duration::new(1, 0)).await;
+
+    assert!(worker_was_running.load(Ordering::Relaxed));
+    assert!(shutdown_was_called.load(Ordering::Relaxed));
+    Ok(())
+}
+
+struct FlagDrain {
+    drain_was_called: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl ShutdownDrain for FlagDrain {
+    async fn drain(&self, _timeout: Option<Duration>) {
+        self.drain_was_called.store(true, Ordering::Relaxed);
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn shutdown_drain__graceful_shutdown__drain_should_not_run(ctx: &mut Context) -> Result<()> {
+    let drain_was_called = Arc::new(AtomicBool::new(false));
+    ctx.register_shutdown_drain(Arc::new(FlagDrain {
+        drain_was_called: drain_was_called.clone(),
+    }))
+    .await?;
+
+    ctx.stop().await?;
+
+    assert!(!drain_was_called.load(Ordering::Relaxed));
+    Ok(())
+}
+
+struct SlowDrain;
+
+#[async_trait]
+impl ShutdownDrain for SlowDrain {
+    async fn drain(&self, _timeout: Option<Duration>) {
+        sleep(Duration::from_millis(500)).await;
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn shutdown_drain__stop_while_draining__both_stops_should_complete(
+    ctx: &mut Context,
+) -> Result<()> {
+    ctx.register_shutdown_drain(Arc::new(SlowDrain)).await?;
+
+    let child_ctx = ctx.new_detached("second_stop", DenyAll, DenyAll).await?;
+    let second_stop = tokio::spawn(async move {
+        sleep(Duration::from_millis(100)).await;
+        child_ctx.stop().await
+    });
+
+    ctx.stop_with_drain(2).await?;
+    second_stop.await.unwrap()?;
+    Ok(())
+}
+
 struct FailingWorkerProcessor {
     shutdown_was_called: Arc<AtomicBool>,
 }
@@ -691,3 +787,80 @@ async fn message_handle__error_during_handling__keep_worker_running(
 
     Ok(())
 }
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn worker_stats__messages_sent__should_be_counted(ctx: &mut Context) -> Result<()> {
+    let mut child_ctx = ctx.new_detached("stats", AllowAll, AllowAll).await?;
+
+    for _ in 0..3 {
+        ctx.send(route!["stats"], "Hello".to_string()).await?;
+        child_ctx.receive::<String>().await?;
+    }
+
+    let stats = ctx
+        .list_worker_stats()
+        .await?
+        .into_iter()
+        .find(|s| s.address == "stats".into())
+        .expect("the detached context should be listed");
+    assert_eq!(stats.worker_type, "Context");
+    assert_eq!(stats.routed_messages, 3);
+    assert_eq!(stats.mailbox_size, 0);
+    Ok(())
+}
+
+struct BlockedWorker {
+    release: Arc<tokio::sync::Semaphore>,
+}
+
+#[async_trait]
+impl Worker for BlockedWorker {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(
+        &mut self,
+        _ctx: &mut Self::Context,
+        _msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        let _ = self.release.acquire().await;
+        Ok(())
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn bounded_mailbox__drop_newest__messages_should_be_dropped(ctx: &mut Context) -> Result<()> {
+    let release = Arc::new(tokio::sync::Semaphore::new(0));
+    WorkerBuilder::new(BlockedWorker {
+        release: release.clone(),
+    })
+    .with_address("blocked")
+    .with_mailbox_capacity(1)
+    .with_mailbox_overflow_policy(MailboxOverflowPolicy::DropNewest)
+    .start(ctx)
+    .await?;
+
+    // The first message is being handled, the second one is queued
+    // and the last two ones are dropped
+    ctx.send(route!["blocked"], "1".to_string()).await?;
+    ctx.sleep(Duration::from_millis(100)).await;
+    for i in 2..=4 {
+        ctx.send(route!["blocked"], i.to_string()).await?;
+    }
+
+    let stats = ctx
+        .list_worker_stats()
+        .await?
+        .into_iter()
+        .find(|s| s.address == "blocked".into())
+        .expect("the worker should be listed");
+    assert_eq!(stats.worker_type, "BlockedWorker");
+    assert_eq!(stats.mailbox_capacity, 1);
+    assert_eq!(stats.mailbox_size, 1);
+    assert_eq!(stats.dropped_messages, 2);
+
+    release.add_permits(1);
+    Ok(())
+}
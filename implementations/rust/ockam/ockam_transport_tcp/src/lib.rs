@@ -29,9 +29,10 @@ pub(crate) use workers::*;
 
 pub use options::{TcpConnectionOptions, TcpListenerOptions};
 pub use portal::{
-    new_certificate_provider_cache, Direction, PortalInletInterceptor, PortalInterceptor,
-    PortalInterceptorFactory, PortalInterceptorWorker, PortalInternalMessage, PortalMessage,
-    PortalOutletInterceptor, TlsCertificate, TlsCertificateProvider, MAX_PAYLOAD_SIZE,
+    new_certificate_provider_cache, Direction, InletLoadBalancing, PortalInletInterceptor,
+    PortalInterceptor, PortalInterceptorFactory, PortalInterceptorRejection,
+    PortalInterceptorWorker, PortalInternalMessage, PortalLimits, PortalMessage,
+    PortalOutletInterceptor, RateLimit, TlsCertificate, TlsCertificateProvider, MAX_PAYLOAD_SIZE,
 };
 pub use protocol_version::*;
 pub use registry::*;
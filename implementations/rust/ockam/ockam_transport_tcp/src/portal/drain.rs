@@ -0,0 +1,65 @@
+use crate::TcpRegistry;
+use core::time::Duration;
+use ockam_core::{async_trait, Address};
+use ockam_node::ShutdownDrain;
+use tokio::time::{sleep, Instant};
+use tracing::{debug, warn};
+
+/// Interval between two checks of the open connections of a draining portal
+const DRAIN_POLLING_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Wait until the inlets or outlets at `portal_addresses` don't have any open connection,
+/// or until the timeout is reached. Return true if all the connections were closed
+pub(crate) async fn wait_for_closed_connections(
+    registry: &TcpRegistry,
+    portal_addresses: &[Address],
+    timeout: Option<Duration>,
+) -> bool {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    loop {
+        let active_connections: u64 = registry
+            .get_all_portal_stats()
+            .iter()
+            .filter(|stats| portal_addresses.contains(stats.address()))
+            .map(|stats| stats.active_connections())
+            .sum();
+        if active_connections == 0 {
+            return true;
+        }
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            warn!("{active_connections} portal connections are still open after the drain timeout");
+            return false;
+        }
+        debug!("Waiting for {active_connections} portal connections to be closed");
+        sleep(DRAIN_POLLING_INTERVAL).await;
+    }
+}
+
+/// Drain all the inlets and outlets of a TCP transport when the node is stopped:
+/// new connections are refused, and the open connections can keep running until the
+/// shutdown timeout is reached
+pub(crate) struct TcpShutdownDrain {
+    registry: TcpRegistry,
+}
+
+impl TcpShutdownDrain {
+    pub(crate) fn new(registry: TcpRegistry) -> Self {
+        Self { registry }
+    }
+}
+
+#[async_trait]
+impl ShutdownDrain for TcpShutdownDrain {
+    async fn drain(&self, timeout: Option<Duration>) {
+        let portal_addresses: Vec<Address> = self
+            .registry
+            .get_all_portal_stats()
+            .iter()
+            .map(|stats| stats.address().clone())
+            .collect();
+        for address in portal_addresses.iter() {
+            self.registry.add_draining_portal(address);
+        }
+        wait_for_closed_connections(&self.registry, &portal_addresses, timeout).await;
+    }
+}
@@ -1,7 +1,7 @@
 use crate::portal::addresses::{Addresses, PortalType};
 use crate::portal::tls_certificate::TlsCertificateProvider;
-use crate::portal::{ReadHalfMaybeTls, WriteHalfMaybeTls};
-use crate::{portal::TcpPortalWorker, TcpInlet, TcpInletOptions, TcpRegistry};
+use crate::portal::{InletRoutes, PortalLimiter, ReadHalfMaybeTls, WriteHalfMaybeTls};
+use crate::{portal::TcpPortalWorker, TcpInlet, TcpInletOptions, TcpPortalCounters, TcpRegistry};
 use log::warn;
 use ockam_core::compat::net::SocketAddr;
 use ockam_core::compat::sync::{Arc, RwLock};
@@ -30,38 +30,46 @@ pub struct InletSharedState {
 ///
 /// TCP Portal Inlet listen processors are created by `TcpTransport`
 /// after a call is made to
-/// [`TcpTransport::create_inlet`](crate::TcpTransport::create_inlet).
+/// [`TcpTransport::create_inlet`](crate::TcpTransport::create_inlet)
+/// or [`TcpTransport::create_balanced_inlet`](crate::TcpTransport::create_balanced_inlet).
 pub(crate) struct TcpInletListenProcessor {
     registry: TcpRegistry,
     inner: TcpListener,
-    inlet_shared_state: Arc<RwLock<InletSharedState>>,
+    routes: InletRoutes,
     options: TcpInletOptions,
+    counters: Arc<TcpPortalCounters>,
+    limiter: PortalLimiter,
 }
 
 impl TcpInletListenProcessor {
     pub fn new(
         registry: TcpRegistry,
         inner: TcpListener,
-        inlet_shared_state: Arc<RwLock<InletSharedState>>,
+        routes: InletRoutes,
         options: TcpInletOptions,
+        counters: Arc<TcpPortalCounters>,
     ) -> Self {
+        let limiter = PortalLimiter::new(&options.limits, None);
         Self {
             registry,
             inner,
-            inlet_shared_state,
+            routes,
             options,
+            counters,
+            limiter,
         }
     }
 
-    /// Start a new `TcpInletListenProcessor`
+    /// Start a new `TcpInletListenProcessor`, spreading the connections between the
+    /// `outlet_listener_routes`. Return one [`TcpInlet`] per route, to update or pause that route
     #[instrument(skip_all, name = "TcpInletListenProcessor::start")]
     pub(crate) async fn start(
         ctx: &Context,
         registry: TcpRegistry,
-        outlet_listener_route: Route,
+        outlet_listener_routes: Vec<Route>,
         addr: SocketAddr,
         options: TcpInletOptions,
-    ) -> Result<TcpInlet> {
+    ) -> Result<Vec<TcpInlet>> {
         let processor_address = Address::random_tagged("TcpInletListenProcessor");
 
         debug!("Binding TcpPortalListenerWorker to {}", addr);
@@ -73,21 +81,28 @@ impl TcpInletListenProcessor {
             }
         };
         let socket_addr = inner.local_addr().map_err(TransportError::from)?;
-        let inlet_shared_state = InletSharedState {
-            route: outlet_listener_route,
-            is_paused: options.is_paused,
-        };
-        let inlet_shared_state = Arc::new(RwLock::new(inlet_shared_state));
-        let processor = Self::new(registry, inner, inlet_shared_state.clone(), options);
+        let inlet_shared_states: Vec<_> = outlet_listener_routes
+            .into_iter()
+            .map(|route| {
+                Arc::new(RwLock::new(InletSharedState {
+                    route,
+                    is_paused: options.is_paused,
+                }))
+            })
+            .collect();
+        let routes = InletRoutes::new(inlet_shared_states.clone(), options.load_balancing);
+        let counters = Arc::new(TcpPortalCounters::new(processor_address.clone(), true));
+        let processor = Self::new(registry, inner, routes, options, counters);
 
         ctx.start_processor(processor_address.clone(), processor)
             .await?;
 
-        Ok(TcpInlet::new_regular(
-            socket_addr,
-            processor_address,
-            inlet_shared_state,
-        ))
+        Ok(inlet_shared_states
+            .into_iter()
+            .map(|inlet_shared_state| {
+                TcpInlet::new_regular(socket_addr, processor_address.clone(), inlet_shared_state)
+            })
+            .collect())
     }
 
     /// Returns a TLS acceptor, in case of failure it retries until the timeout is hit.
@@ -172,6 +187,7 @@ impl Processor for TcpInletListenProcessor {
     #[instrument(skip_all, name = "TcpInletListenProcessor::initialize")]
     async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
         self.registry.add_inlet_listener_processor(&ctx.address());
+        self.registry.add_portal_counters(self.counters.clone());
 
         Ok(())
     }
@@ -180,6 +196,8 @@ impl Processor for TcpInletListenProcessor {
     async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
         self.registry
             .remove_inlet_listener_processor(&ctx.address());
+        self.registry.remove_portal_counters(&ctx.address());
+        self.registry.remove_draining_portal(&ctx.address());
 
         Ok(())
     }
@@ -190,18 +208,36 @@ impl Processor for TcpInletListenProcessor {
 
         let addresses = Addresses::generate(PortalType::Inlet);
 
-        let inlet_shared_state = self.inlet_shared_state.read().unwrap().clone();
+        if self.registry.is_portal_draining(&ctx.address()) {
+            debug!("The inlet is draining, closing the connection from {socket_addr}");
+            return Ok(true);
+        }
 
-        if inlet_shared_state.is_paused {
-            // Just drop the stream
+        // The first route is used for the connection, the other ones are tried in turn
+        // if an outlet refuses the connection, for example because it is draining
+        let mut inlet_shared_states = self.routes.select(socket_addr.ip());
+        if inlet_shared_states.is_empty() {
+            // All the routes are paused, just drop the stream
             return Ok(true);
         }
 
-        TcpInletOptions::setup_flow_control(
-            ctx.flow_controls(),
-            &addresses,
-            inlet_shared_state.route.next()?,
-        );
+        let Some(connection_limiter) = self.limiter.open_connection(&[]) else {
+            warn!("The connection limits of the inlet are reached, closing the connection from {socket_addr}");
+            return Ok(true);
+        };
+
+        for inlet_shared_state in inlet_shared_states.iter() {
+            TcpInletOptions::setup_flow_control(
+                ctx.flow_controls(),
+                &addresses,
+                inlet_shared_state.route.next()?,
+            );
+        }
+        let ping_route = inlet_shared_states.remove(0).route;
+        let fallback_ping_routes = inlet_shared_states
+            .into_iter()
+            .map(|inlet_shared_state| inlet_shared_state.route)
+            .collect();
 
         let streams = if let Some(certificate_provider) = &self.options.tls_certificate_provider {
             let (rx, tx) = tokio::io::split(TlsStream::from(
@@ -231,10 +267,13 @@ impl Processor for TcpInletListenProcessor {
             self.registry.clone(),
             streams,
             HostnamePort::from(socket_addr),
-            inlet_shared_state.route,
+            ping_route,
+            fallback_ping_routes,
             addresses,
             self.options.incoming_access_control.clone(),
             self.options.outgoing_access_control.clone(),
+            self.counters.clone(),
+            connection_limiter,
         )
         .await?;
 
@@ -0,0 +1,217 @@
+use crate::portal::InletSharedState;
+use core::fmt::{Display, Formatter};
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use minicbor::{CborLen, Decode, Encode};
+use ockam_core::compat::net::IpAddr;
+use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use std::collections::hash_map::DefaultHasher;
+
+/// Strategy used by an inlet to choose the outlet of a new TCP connection,
+/// when it balances the connections between several outlets
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub enum InletLoadBalancing {
+    /// Use each outlet in turn
+    #[n(0)] #[default] RoundRobin,
+    /// Use a random outlet
+    #[n(1)] Random,
+    /// Always use the same outlet for the connections coming from the same IP address,
+    /// as long as this outlet is available
+    #[n(2)] StickyClientIp,
+}
+
+impl InletLoadBalancing {
+    /// Name of the strategy
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            InletLoadBalancing::RoundRobin => "round-robin",
+            InletLoadBalancing::Random => "random",
+            InletLoadBalancing::StickyClientIp => "sticky-client-ip",
+        }
+    }
+}
+
+impl Display for InletLoadBalancing {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for InletLoadBalancing {
+    type Err = ockam_core::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "round-robin" => Ok(InletLoadBalancing::RoundRobin),
+            "random" => Ok(InletLoadBalancing::Random),
+            "sticky-client-ip" | "sticky" => Ok(InletLoadBalancing::StickyClientIp),
+            _ => Err(ockam_core::Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                format!(
+                    "invalid load balancing strategy '{s}', expected one of: round-robin, random, sticky-client-ip"
+                ),
+            )),
+        }
+    }
+}
+
+/// Routes to the outlets of an inlet. Each route can be paused independently,
+/// to take its outlet out of the rotation
+#[derive(Debug)]
+pub(crate) struct InletRoutes {
+    routes: Vec<Arc<RwLock<InletSharedState>>>,
+    load_balancing: InletLoadBalancing,
+    next: AtomicUsize,
+}
+
+impl InletRoutes {
+    pub(crate) fn new(
+        routes: Vec<Arc<RwLock<InletSharedState>>>,
+        load_balancing: InletLoadBalancing,
+    ) -> Self {
+        Self {
+            routes,
+            load_balancing,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Return the states of the routes to use for a new connection coming from `client_ip`,
+    /// in order of preference. The first route is the one chosen by the load balancing strategy,
+    /// the next ones are used if the previous outlets refuse the connection.
+    /// Paused routes are skipped, so the list is empty if all the routes are paused
+    pub(crate) fn select(&self, client_ip: IpAddr) -> Vec<InletSharedState> {
+        let states: Vec<InletSharedState> = self
+            .routes
+            .iter()
+            .map(|state| state.read().unwrap().clone())
+            .collect();
+        if states.is_empty() {
+            return vec![];
+        }
+
+        let start = match self.load_balancing {
+            InletLoadBalancing::RoundRobin => self.next.fetch_add(1, Ordering::Relaxed),
+            InletLoadBalancing::Random => rand::random::<usize>(),
+            InletLoadBalancing::StickyClientIp => {
+                let mut hasher = DefaultHasher::new();
+                client_ip.hash(&mut hasher);
+                hasher.finish() as usize
+            }
+        };
+
+        (0..states.len())
+            .map(|i| &states[(start + i) % states.len()])
+            .filter(|state| !state.is_paused)
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::{route, Route};
+
+    fn routes(paused: &[bool]) -> Vec<Arc<RwLock<InletSharedState>>> {
+        paused
+            .iter()
+            .enumerate()
+            .map(|(i, is_paused)| {
+                Arc::new(RwLock::new(InletSharedState {
+                    route: route![format!("outlet{i}")],
+                    is_paused: *is_paused,
+                }))
+            })
+            .collect()
+    }
+
+    fn select(routes: &InletRoutes, client_ip: &str) -> Option<Route> {
+        routes
+            .select(client_ip.parse().unwrap())
+            .into_iter()
+            .next()
+            .map(|state| state.route)
+    }
+
+    #[test]
+    fn round_robin_skips_the_paused_routes() {
+        let routes = InletRoutes::new(
+            routes(&[false, true, false]),
+            InletLoadBalancing::RoundRobin,
+        );
+        assert_eq!(select(&routes, "127.0.0.1"), Some(route!["outlet0"]));
+        assert_eq!(select(&routes, "127.0.0.1"), Some(route!["outlet2"]));
+        assert_eq!(select(&routes, "127.0.0.1"), Some(route!["outlet2"]));
+        assert_eq!(select(&routes, "127.0.0.1"), Some(route!["outlet0"]));
+    }
+
+    #[test]
+    fn sticky_routes_use_the_same_outlet_for_a_client() {
+        let states = routes(&[false, false, false]);
+        let routes = InletRoutes::new(states.clone(), InletLoadBalancing::StickyClientIp);
+        let first = select(&routes, "10.0.0.1").unwrap();
+        for _ in 0..5 {
+            assert_eq!(select(&routes, "10.0.0.1"), Some(first.clone()));
+        }
+
+        // the client uses another outlet while its outlet is paused
+        let index = states
+            .iter()
+            .position(|s| s.read().unwrap().route == first)
+            .unwrap();
+        states[index].write().unwrap().is_paused = true;
+        assert_ne!(select(&routes, "10.0.0.1"), Some(first.clone()));
+        states[index].write().unwrap().is_paused = false;
+        assert_eq!(select(&routes, "10.0.0.1"), Some(first));
+    }
+
+    #[test]
+    fn the_other_routes_are_selected_as_fallbacks() {
+        let routes = InletRoutes::new(
+            routes(&[false, true, false, false]),
+            InletLoadBalancing::RoundRobin,
+        );
+        let fallbacks = |routes: &InletRoutes| -> Vec<Route> {
+            routes
+                .select("127.0.0.1".parse().unwrap())
+                .into_iter()
+                .map(|state| state.route)
+                .collect()
+        };
+        assert_eq!(
+            fallbacks(&routes),
+            vec![route!["outlet0"], route!["outlet2"], route!["outlet3"]]
+        );
+        assert_eq!(
+            fallbacks(&routes),
+            vec![route!["outlet2"], route!["outlet3"], route!["outlet0"]]
+        );
+    }
+
+    #[test]
+    fn no_route_is_selected_when_all_are_paused() {
+        let routes = InletRoutes::new(routes(&[true, true]), InletLoadBalancing::Random);
+        assert_eq!(select(&routes, "127.0.0.1"), None);
+    }
+
+    #[test]
+    fn parse_load_balancing() {
+        for load_balancing in [
+            InletLoadBalancing::RoundRobin,
+            InletLoadBalancing::Random,
+            InletLoadBalancing::StickyClientIp,
+        ] {
+            assert_eq!(
+                InletLoadBalancing::from_str(load_balancing.as_str()).unwrap(),
+                load_balancing
+            );
+        }
+        assert!(InletLoadBalancing::from_str("fastest").is_err());
+    }
+}
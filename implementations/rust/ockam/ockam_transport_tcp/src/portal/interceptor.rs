@@ -1,4 +1,5 @@
 use crate::{PortalMessage, MAX_PAYLOAD_SIZE};
+use ockam_core::errcode::{Kind, Origin};
 use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
 use ockam_core::{
     async_trait, route, Address, AllowOnwardAddress, AllowSourceAddress, Any,
@@ -6,9 +7,11 @@ use ockam_core::{
     LocalInfo, LocalMessage, NeutralMessage, OutgoingAccessControl, Route, Routed, Worker,
 };
 use ockam_node::{Context, WorkerBuilder};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
 
 /// Direction of the data being intercepted
 #[derive(Clone, Copy, Debug)]
@@ -26,6 +29,8 @@ pub trait PortalInterceptor: 'static + Send + Sync {
     /// The returned buffer can be of any size and will be sent to the original destination.
     /// The buffer will always be discarded after the call, and it's up to the interceptor
     /// to properly preserve it when needed.
+    /// When an error is returned, the buffer is dropped. If that error is a
+    /// [`PortalInterceptorRejection`], the portal connection is closed as well.
     async fn intercept(
         &self,
         context: &mut Context,
@@ -34,10 +39,74 @@ pub trait PortalInterceptor: 'static + Send + Sync {
     ) -> ockam_core::Result<Option<Vec<u8>>>;
 }
 
+/// Error returned by a [`PortalInterceptor`] to reject the data it intercepted.
+///
+/// The portal connection is then closed on both sides, after sending the response of the
+/// rejection, if any, to the sender of the data. For example an HTTP error response.
+#[derive(Clone, Debug)]
+pub struct PortalInterceptorRejection {
+    reason: String,
+    response: Vec<u8>,
+}
+
+impl PortalInterceptorRejection {
+    /// Create a new rejection, closing the connection without a response
+    pub fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+            response: vec![],
+        }
+    }
+
+    /// Send a response to the sender of the rejected data before closing the connection
+    pub fn with_response(mut self, response: Vec<u8>) -> Self {
+        self.response = response;
+        self
+    }
+
+    /// Reason of the rejection
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    /// Response sent to the sender of the rejected data
+    pub fn response(&self) -> &[u8] {
+        &self.response
+    }
+
+    /// Return the rejection of an interception error, if it is one
+    pub fn find(error: &ockam_core::Error) -> Option<&Self> {
+        error.source()?.downcast_ref()
+    }
+}
+
+impl Display for PortalInterceptorRejection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.reason)
+    }
+}
+
+impl Error for PortalInterceptorRejection {}
+
+impl From<PortalInterceptorRejection> for ockam_core::Error {
+    #[track_caller]
+    fn from(rejection: PortalInterceptorRejection) -> Self {
+        ockam_core::Error::new(Origin::Transport, Kind::Invalid, rejection)
+    }
+}
+
 /// Portal Interceptor Factory
 pub trait PortalInterceptorFactory: 'static + Send + Sync {
     /// Create a new instance of a portal interceptor
     fn create(&self) -> Arc<dyn PortalInterceptor>;
+
+    /// Create a new instance of a portal interceptor for a connection, given the local
+    /// information of its first message, for example the identity of the secure channel
+    /// it was received from
+    fn create_for_connection(&self, local_info: &[LocalInfo]) -> Arc<dyn PortalInterceptor> {
+        let _ = local_info;
+        self.create()
+    }
 }
 
 /// Portal interceptor for the outlet side
@@ -46,6 +115,7 @@ pub struct PortalOutletInterceptor {
     outgoing_access_control: Arc<dyn OutgoingAccessControl>,
     incoming_access_control: Arc<dyn IncomingAccessControl>,
     spawner_flow_control_id: Option<FlowControlId>,
+    outlet_address: Option<Address>,
 }
 
 impl PortalOutletInterceptor {
@@ -72,8 +142,41 @@ impl PortalOutletInterceptor {
             interceptor_factory,
             outgoing_access_control,
             incoming_access_control: incoming_access_control.clone(),
+            outlet_address: None,
         };
 
+        Self::start(context, listener_address, worker, incoming_access_control).await
+    }
+
+    /// Starts a listener intercepting the data of a given outlet, see [`Self::create`].
+    /// The messages whose onward route ends with the listener are forwarded to the outlet,
+    /// so that the listener address can be used in place of the outlet address.
+    pub async fn create_for_outlet(
+        context: &Context,
+        listener_address: Address,
+        outlet_address: Address,
+        spawner_flow_control_id: Option<FlowControlId>,
+        interceptor_factory: Arc<dyn PortalInterceptorFactory>,
+        outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        incoming_access_control: Arc<dyn IncomingAccessControl>,
+    ) -> ockam_core::Result<()> {
+        let worker = Self {
+            spawner_flow_control_id,
+            interceptor_factory,
+            outgoing_access_control,
+            incoming_access_control: incoming_access_control.clone(),
+            outlet_address: Some(outlet_address),
+        };
+
+        Self::start(context, listener_address, worker, incoming_access_control).await
+    }
+
+    async fn start(
+        context: &Context,
+        listener_address: Address,
+        worker: Self,
+        incoming_access_control: Arc<dyn IncomingAccessControl>,
+    ) -> ockam_core::Result<()> {
         WorkerBuilder::new(worker)
             .with_address(listener_address)
             .with_incoming_access_control_arc(incoming_access_control)
@@ -98,6 +201,11 @@ impl Worker for PortalOutletInterceptor {
 
         // Remove our address
         message = message.pop_front_onward_route()?;
+        if message.onward_route_ref().is_empty() {
+            if let Some(outlet_address) = &self.outlet_address {
+                message = message.push_front_onward_route(outlet_address);
+            }
+        }
 
         // unique flow control id for each interceptor instance
         let flow_control_id = FlowControls::generate_flow_control_id();
@@ -109,7 +217,8 @@ impl Worker for PortalOutletInterceptor {
             self.spawner_flow_control_id.clone(),
             self.incoming_access_control.clone(),
             self.outgoing_access_control.clone(),
-            self.interceptor_factory.create(),
+            self.interceptor_factory
+                .create_for_connection(message.local_info_ref()),
         )
         .await?;
 
@@ -212,7 +321,8 @@ impl Worker for PortalInletInterceptor {
             route![inlet_responder_address],
             self.request_outgoing_access_control.clone(),
             self.response_incoming_access_control.clone(),
-            self.interceptor_factory.create(),
+            self.interceptor_factory
+                .create_for_connection(message.local_info_ref()),
         )
         .await?;
 
@@ -238,6 +348,7 @@ pub struct PortalInterceptorWorker {
     disconnect_received: Arc<AtomicBool>,
     interceptor: Arc<dyn PortalInterceptor>,
     direction: Direction,
+    rejected: bool,
 }
 
 #[async_trait]
@@ -262,10 +373,40 @@ impl Worker for PortalInterceptorWorker {
 
         match portal_message {
             PortalMessage::Payload(message, _) => {
-                let buffer: Option<Vec<u8>> = self
+                // the response of a rejection, sent back by the other worker,
+                // is not intercepted
+                if routed_message.src_addr() == self.other_worker_address {
+                    return self.forward(context, routed_message).await;
+                }
+                // the stream can't be resumed after a rejection
+                if self.rejected {
+                    trace!("dropping a payload received after a rejection");
+                    return Ok(());
+                }
+
+                let buffer = match self
                     .interceptor
                     .intercept(context, self.direction, message)
-                    .await?;
+                    .await
+                {
+                    Ok(buffer) => buffer,
+                    Err(error) => {
+                        if let Some(rejection) = PortalInterceptorRejection::find(&error) {
+                            warn!("closing the portal connection after a rejection: {rejection}");
+                            self.rejected = true;
+                            self.reject(
+                                context,
+                                onward_route,
+                                return_route,
+                                rejection.response(),
+                                &local_info,
+                            )
+                            .await?;
+                            return Ok(());
+                        }
+                        return Err(error);
+                    }
+                };
                 match buffer {
                     Some(buffer) => {
                         trace!(
@@ -384,25 +525,34 @@ impl PortalInterceptorWorker {
             disconnect_received: disconnect_received.clone(),
             fixed_onward_route: Some(inlet_instance),
             interceptor: interceptor.clone(),
+            rejected: false,
         };
 
+        // allow the other worker to send back the response of a rejection
         WorkerBuilder::new(from_outlet_worker)
             .with_address(from_outlet_worker_address.clone())
-            .with_incoming_access_control_arc(incoming_access_control)
+            .with_incoming_access_control_arc(Arc::new(AnyIncomingAccessControl::new(vec![
+                Arc::new(AllowSourceAddress(from_inlet_worker_address.clone())),
+                incoming_access_control,
+            ])))
             .start(context)
             .await?;
 
         let from_inlet_worker = Self {
-            other_worker_address: from_outlet_worker_address,
+            other_worker_address: from_outlet_worker_address.clone(),
             direction: Direction::FromInletToOutlet,
             disconnect_received: disconnect_received.clone(),
             fixed_onward_route: None,
             interceptor: interceptor.clone(),
+            rejected: false,
         };
 
         WorkerBuilder::new(from_inlet_worker)
             .with_address(from_inlet_worker_address.clone())
-            .with_outgoing_access_control_arc(outgoing_access_control)
+            .with_outgoing_access_control(AnyOutgoingAccessControl::new(vec![
+                Arc::new(AllowOnwardAddress::new(from_outlet_worker_address.clone())),
+                outgoing_access_control,
+            ]))
             .start(context)
             .await?;
 
@@ -449,6 +599,7 @@ impl PortalInterceptorWorker {
             disconnect_received: disconnect_received.clone(),
             fixed_onward_route: Some(outlet_route),
             interceptor: interceptor.clone(),
+            rejected: false,
         };
         let from_outlet_worker = Self {
             other_worker_address: from_inlet_worker_address.clone(),
@@ -456,6 +607,7 @@ impl PortalInterceptorWorker {
             disconnect_received: disconnect_received.clone(),
             fixed_onward_route: None,
             interceptor: interceptor.clone(),
+            rejected: false,
         };
 
         let flow_controls = context.flow_controls();
@@ -467,18 +619,22 @@ impl PortalInterceptorWorker {
             vec![],
         );
 
-        // allow the other worker to forward the `pong` message
+        // allow the other worker to forward the `pong` message,
+        // and to send back the response of a rejection
         WorkerBuilder::new(from_inlet_worker)
             .with_address(from_inlet_worker_address.clone())
             .with_incoming_access_control_arc(Arc::new(AnyIncomingAccessControl::new(vec![
                 Arc::new(AllowSourceAddress(from_outlet_worker_address.clone())),
                 incoming_access_control,
             ])))
-            .with_outgoing_access_control_arc(Arc::new(FlowControlOutgoingAccessControl::new(
-                flow_controls,
-                flow_control_id.clone(),
-                spawner_flow_control_id.clone(),
-            )))
+            .with_outgoing_access_control(AnyOutgoingAccessControl::new(vec![
+                Arc::new(AllowOnwardAddress::new(from_outlet_worker_address.clone())),
+                Arc::new(FlowControlOutgoingAccessControl::new(
+                    flow_controls,
+                    flow_control_id.clone(),
+                    spawner_flow_control_id.clone(),
+                )),
+            ]))
             .start(context)
             .await?;
 
@@ -547,6 +703,76 @@ impl PortalInterceptorWorker {
         provided_return_route: Route,
         buffer: &[u8],
         local_info: &[LocalInfo],
+    ) -> ockam_core::Result<()> {
+        let payloads = buffer
+            .chunks(MAX_PAYLOAD_SIZE)
+            .map(|chunk| PortalMessage::Payload(chunk, None).encode())
+            .collect::<ockam_core::Result<Vec<_>>>()?;
+        self.send(
+            context,
+            provided_onward_route,
+            provided_return_route,
+            payloads,
+            local_info,
+        )
+        .await
+    }
+
+    /// Close the connection on both sides after a rejection: the receiver of the rejected
+    /// data is disconnected, and the other worker sends the response of the rejection,
+    /// then a disconnection, back to the sender of the data
+    async fn reject(
+        &self,
+        context: &mut Context,
+        provided_onward_route: Route,
+        provided_return_route: Route,
+        response: &[u8],
+        local_info: &[LocalInfo],
+    ) -> ockam_core::Result<()> {
+        self.send(
+            context,
+            provided_onward_route,
+            provided_return_route.clone(),
+            vec![PortalMessage::Disconnect.encode()?],
+            local_info,
+        )
+        .await?;
+
+        let mut payloads = response
+            .chunks(MAX_PAYLOAD_SIZE)
+            .map(|chunk| PortalMessage::Payload(chunk, None).encode())
+            .collect::<ockam_core::Result<Vec<_>>>()?;
+        payloads.push(PortalMessage::Disconnect.encode()?);
+
+        // the other worker only has a fixed onward route if this worker doesn't
+        let onward_route: Route = if self.fixed_onward_route.is_some() {
+            provided_return_route
+                .clone()
+                .modify()
+                .prepend(self.other_worker_address.clone())
+                .into()
+        } else {
+            route![self.other_worker_address.clone()]
+        };
+        for payload in payloads {
+            let message = LocalMessage::new()
+                .with_onward_route(onward_route.clone())
+                .with_return_route(route![context.address()])
+                .with_payload(payload)
+                .with_local_info(local_info.to_vec());
+
+            context.forward(message).await?;
+        }
+        Ok(())
+    }
+
+    async fn send(
+        &self,
+        context: &mut Context,
+        provided_onward_route: Route,
+        provided_return_route: Route,
+        payloads: Vec<Vec<u8>>,
+        local_info: &[LocalInfo],
     ) -> ockam_core::Result<()> {
         let return_route: Route;
         let onward_route;
@@ -567,11 +793,11 @@ impl PortalInterceptorWorker {
             onward_route = provided_onward_route.clone().modify().pop_front().into();
         };
 
-        for chunk in buffer.chunks(MAX_PAYLOAD_SIZE) {
+        for payload in payloads {
             let message = LocalMessage::new()
                 .with_onward_route(onward_route.clone())
                 .with_return_route(return_route.clone())
-                .with_payload(PortalMessage::Payload(chunk, None).encode()?)
+                .with_payload(payload)
                 .with_local_info(local_info.to_vec());
 
             context.forward(message).await?;
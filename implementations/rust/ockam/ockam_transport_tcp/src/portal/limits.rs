@@ -0,0 +1,371 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+use ockam_core::compat::collections::HashMap;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::compat::vec::Vec;
+use ockam_core::LocalInfo;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Type of the [`LocalInfo`] added by identity secure channels, containing the identifier of
+/// the other side of the channel. It is defined in `ockam_identity`, which this crate doesn't
+/// depend on. Its data is used as the key of the per-identity limits
+const IDENTITY_SECURE_CHANNEL_LOCAL_INFO: &str = "IDENTITY_SECURE_CHANNEL_IDENTIFIER";
+
+/// Rate of a token bucket: tokens are added at `per_second`, up to `burst` tokens
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    per_second: u64,
+    burst: u64,
+}
+
+impl RateLimit {
+    /// Create a rate limit allowing bursts of one second worth of tokens
+    pub fn new(per_second: u64) -> Self {
+        Self {
+            per_second,
+            burst: per_second,
+        }
+    }
+
+    /// Set the maximum number of tokens which can be used at once
+    pub fn with_burst(mut self, burst: u64) -> Self {
+        self.burst = burst;
+        self
+    }
+
+    /// Number of tokens added per second
+    pub fn per_second(&self) -> u64 {
+        self.per_second
+    }
+
+    /// Maximum number of tokens which can be used at once
+    pub fn burst(&self) -> u64 {
+        self.burst
+    }
+}
+
+/// Limits applied to the connections of a portal
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PortalLimits {
+    max_connections: Option<usize>,
+    connection_rate: Option<RateLimit>,
+    bandwidth: Option<RateLimit>,
+}
+
+impl PortalLimits {
+    /// No limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum number of concurrent connections.
+    /// New connections are closed when the limit is reached
+    pub fn with_max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = Some(max_connections);
+        self
+    }
+
+    /// Set the maximum number of new connections per second.
+    /// New connections are closed when the limit is reached
+    pub fn with_connection_rate(mut self, connection_rate: RateLimit) -> Self {
+        self.connection_rate = Some(connection_rate);
+        self
+    }
+
+    /// Set the maximum number of bytes per second, sent in both directions, shared by all
+    /// the connections. The connections are slowed down when the limit is reached
+    pub fn with_bandwidth(mut self, bandwidth: RateLimit) -> Self {
+        self.bandwidth = Some(bandwidth);
+        self
+    }
+
+    /// Maximum number of concurrent connections
+    pub fn max_connections(&self) -> Option<usize> {
+        self.max_connections
+    }
+
+    /// Maximum number of new connections per second
+    pub fn connection_rate(&self) -> Option<RateLimit> {
+        self.connection_rate
+    }
+
+    /// Maximum number of bytes per second
+    pub fn bandwidth(&self) -> Option<RateLimit> {
+        self.bandwidth
+    }
+
+    /// Return true if no limit is set
+    pub fn is_unlimited(&self) -> bool {
+        self.max_connections.is_none() && self.connection_rate.is_none() && self.bandwidth.is_none()
+    }
+}
+
+/// Token bucket which can be shared between connections
+#[derive(Debug)]
+struct TokenBucket {
+    rate: RateLimit,
+    state: Mutex<TokenBucketState>,
+}
+
+#[derive(Debug)]
+struct TokenBucketState {
+    /// Available tokens, negative when tokens were reserved in advance
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: RateLimit) -> Self {
+        Self {
+            rate,
+            state: Mutex::new(TokenBucketState {
+                tokens: rate.burst as f64,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    fn refill(&self, state: &mut TokenBucketState) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens =
+            (state.tokens + elapsed * self.rate.per_second as f64).min(self.rate.burst as f64);
+        state.last_refill = now;
+    }
+
+    /// Take tokens if they are available
+    fn try_acquire(&self, tokens: u64) -> bool {
+        let mut state = self.state.lock().unwrap();
+        self.refill(&mut state);
+        if state.tokens >= tokens as f64 {
+            state.tokens -= tokens as f64;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Reserve tokens, and return how long to wait until they are available
+    fn reserve(&self, tokens: u64) -> Duration {
+        let mut state = self.state.lock().unwrap();
+        self.refill(&mut state);
+        state.tokens -= tokens as f64;
+        if state.tokens >= 0.0 || self.rate.per_second == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / self.rate.per_second as f64)
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        self.refill(&mut state);
+        state.tokens >= self.rate.burst as f64
+    }
+}
+
+/// State of the limits of a portal, or of one identity using a portal
+#[derive(Debug)]
+struct LimitsState {
+    max_connections: Option<usize>,
+    active_connections: AtomicUsize,
+    connection_rate: Option<TokenBucket>,
+    bandwidth: Option<TokenBucket>,
+}
+
+impl LimitsState {
+    fn new(limits: &PortalLimits) -> Self {
+        Self {
+            max_connections: limits.max_connections,
+            active_connections: AtomicUsize::new(0),
+            connection_rate: limits.connection_rate.map(TokenBucket::new),
+            bandwidth: limits.bandwidth.map(TokenBucket::new),
+        }
+    }
+
+    /// Count a new connection if the maximum number of connections is not reached
+    fn try_open_connection(&self) -> bool {
+        let Some(max_connections) = self.max_connections else {
+            self.active_connections.fetch_add(1, Ordering::Relaxed);
+            return true;
+        };
+        self.active_connections
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |active| {
+                (active < max_connections).then_some(active + 1)
+            })
+            .is_ok()
+    }
+
+    fn close_connection(&self) {
+        self.active_connections.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Return true if dropping this state doesn't lose anything
+    fn is_idle(&self) -> bool {
+        self.active_connections.load(Ordering::Relaxed) == 0
+            && self.connection_rate.as_ref().map_or(true, |b| b.is_full())
+            && self.bandwidth.as_ref().map_or(true, |b| b.is_full())
+    }
+}
+
+/// Limits of the connections of a portal, shared by its listener and its connections
+#[derive(Debug)]
+pub(crate) struct PortalLimiter {
+    portal: Option<Arc<LimitsState>>,
+    identity_limits: Option<PortalLimits>,
+    identities: Mutex<HashMap<Vec<u8>, Arc<LimitsState>>>,
+}
+
+impl PortalLimiter {
+    pub(crate) fn new(limits: &PortalLimits, identity_limits: Option<&PortalLimits>) -> Self {
+        Self {
+            portal: (!limits.is_unlimited()).then(|| Arc::new(LimitsState::new(limits))),
+            identity_limits: identity_limits.filter(|l| !l.is_unlimited()).cloned(),
+            identities: Default::default(),
+        }
+    }
+
+    /// Accept a new connection, for the identity found in the local info of the message
+    /// which opened it, if any. Return `None` if a limit is reached
+    pub(crate) fn open_connection(
+        &self,
+        local_info: &[LocalInfo],
+    ) -> Option<Arc<ConnectionLimiter>> {
+        let mut states = vec![];
+        if let Some(portal) = &self.portal {
+            states.push(portal.clone());
+        }
+        if let Some(identity_limits) = &self.identity_limits {
+            // connections without identity share the same limits
+            let key = local_info
+                .iter()
+                .find(|i| i.type_identifier() == IDENTITY_SECURE_CHANNEL_LOCAL_INFO)
+                .map(|i| i.data().to_vec())
+                .unwrap_or_default();
+            let mut identities = self.identities.lock().unwrap();
+            identities.retain(|_, state| !state.is_idle());
+            let state = identities
+                .entry(key)
+                .or_insert_with(|| Arc::new(LimitsState::new(identity_limits)));
+            states.push(state.clone());
+        }
+
+        // check all the limits before counting the connection
+        let rate_allowed = states.iter().all(|state| {
+            state
+                .connection_rate
+                .as_ref()
+                .map_or(true, |bucket| bucket.try_acquire(1))
+        });
+        if !rate_allowed {
+            return None;
+        }
+        let mut opened: Vec<Arc<LimitsState>> = vec![];
+        for state in states {
+            if state.try_open_connection() {
+                opened.push(state);
+            } else {
+                for state in opened {
+                    state.close_connection();
+                }
+                return None;
+            }
+        }
+        Some(Arc::new(ConnectionLimiter { states: opened }))
+    }
+}
+
+/// Limits applied to one portal connection. The connection stops being counted when dropped
+#[derive(Debug)]
+pub(crate) struct ConnectionLimiter {
+    states: Vec<Arc<LimitsState>>,
+}
+
+impl ConnectionLimiter {
+    /// Wait until `bytes` can be transferred without exceeding the bandwidth limits
+    pub(crate) async fn transfer(&self, bytes: usize) {
+        let wait = self
+            .states
+            .iter()
+            .filter_map(|state| state.bandwidth.as_ref())
+            .map(|bucket| bucket.reserve(bytes as u64))
+            .max()
+            .unwrap_or_default();
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+impl Drop for ConnectionLimiter {
+    fn drop(&mut self) {
+        for state in &self.states {
+            state.close_connection();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity(name: &str) -> Vec<LocalInfo> {
+        vec![LocalInfo::new(
+            IDENTITY_SECURE_CHANNEL_LOCAL_INFO.into(),
+            name.as_bytes().to_vec(),
+        )]
+    }
+
+    #[test]
+    fn limit_the_number_of_connections() {
+        let limiter = PortalLimiter::new(&PortalLimits::new().with_max_connections(2), None);
+        let first = limiter.open_connection(&[]).unwrap();
+        let _second = limiter.open_connection(&[]).unwrap();
+        assert!(limiter.open_connection(&[]).is_none());
+
+        drop(first);
+        assert!(limiter.open_connection(&[]).is_some());
+    }
+
+    #[test]
+    fn limit_the_number_of_connections_per_identity() {
+        let limiter = PortalLimiter::new(
+            &PortalLimits::new().with_max_connections(3),
+            Some(&PortalLimits::new().with_max_connections(1)),
+        );
+        let _alice = limiter.open_connection(&identity("alice")).unwrap();
+        assert!(limiter.open_connection(&identity("alice")).is_none());
+        let _bob = limiter.open_connection(&identity("bob")).unwrap();
+        let _anonymous = limiter.open_connection(&[]).unwrap();
+
+        // the portal limit is reached
+        assert!(limiter.open_connection(&identity("carol")).is_none());
+    }
+
+    #[test]
+    fn limit_the_connection_rate() {
+        let limiter = PortalLimiter::new(
+            &PortalLimits::new().with_connection_rate(RateLimit::new(1).with_burst(2)),
+            None,
+        );
+        assert!(limiter.open_connection(&[]).is_some());
+        assert!(limiter.open_connection(&[]).is_some());
+        assert!(limiter.open_connection(&[]).is_none());
+    }
+
+    #[tokio::test]
+    async fn limit_the_bandwidth() {
+        let limiter = PortalLimiter::new(
+            &PortalLimits::new().with_bandwidth(RateLimit::new(1000)),
+            None,
+        );
+        let connection = limiter.open_connection(&[]).unwrap();
+
+        let start = Instant::now();
+        connection.transfer(1000).await;
+        assert!(start.elapsed() < Duration::from_millis(100));
+
+        connection.transfer(200).await;
+        assert!(start.elapsed() >= Duration::from_millis(150));
+    }
+}
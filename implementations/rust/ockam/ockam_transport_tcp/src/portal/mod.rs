@@ -1,6 +1,9 @@
 pub mod addresses;
+mod drain;
 mod inlet_listener;
+mod inlet_routes;
 mod interceptor;
+mod limits;
 pub mod options;
 mod outlet_listener;
 mod portal_message;
@@ -8,11 +11,16 @@ mod portal_receiver;
 mod portal_worker;
 mod tls_certificate;
 
+pub(crate) use drain::{wait_for_closed_connections, TcpShutdownDrain};
 pub(crate) use inlet_listener::*;
+pub use inlet_routes::InletLoadBalancing;
+pub(crate) use inlet_routes::InletRoutes;
 pub use interceptor::{
     Direction, PortalInletInterceptor, PortalInterceptor, PortalInterceptorFactory,
-    PortalInterceptorWorker, PortalOutletInterceptor,
+    PortalInterceptorRejection, PortalInterceptorWorker, PortalOutletInterceptor,
 };
+pub(crate) use limits::{ConnectionLimiter, PortalLimiter};
+pub use limits::{PortalLimits, RateLimit};
 pub(crate) use outlet_listener::*;
 pub use portal_message::*;
 pub(crate) use portal_receiver::*;
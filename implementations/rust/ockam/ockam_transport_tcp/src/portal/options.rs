@@ -1,5 +1,5 @@
 use crate::portal::addresses::Addresses;
-use crate::TlsCertificateProvider;
+use crate::{InletLoadBalancing, PortalLimits, TlsCertificateProvider};
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::{FlowControlId, FlowControls};
 use ockam_core::{Address, AllowAll, IncomingAccessControl, OutgoingAccessControl};
@@ -11,6 +11,8 @@ pub struct TcpInletOptions {
     pub(crate) outgoing_access_control: Arc<dyn OutgoingAccessControl>,
     pub(crate) is_paused: bool,
     pub(crate) tls_certificate_provider: Option<Arc<dyn TlsCertificateProvider>>,
+    pub(crate) limits: PortalLimits,
+    pub(crate) load_balancing: InletLoadBalancing,
 }
 
 impl TcpInletOptions {
@@ -21,6 +23,8 @@ impl TcpInletOptions {
             outgoing_access_control: Arc::new(AllowAll),
             is_paused: false,
             tls_certificate_provider: None,
+            limits: PortalLimits::new(),
+            load_balancing: InletLoadBalancing::default(),
         }
     }
 
@@ -40,6 +44,19 @@ impl TcpInletOptions {
         self
     }
 
+    /// Set the limits applied to the connections accepted by the inlet
+    pub fn with_limits(mut self, limits: PortalLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Set the strategy used to choose the outlet of each new connection,
+    /// when the inlet has several outlet routes
+    pub fn with_load_balancing(mut self, load_balancing: InletLoadBalancing) -> Self {
+        self.load_balancing = load_balancing;
+        self
+    }
+
     /// Set Incoming Access Control
     pub fn with_incoming_access_control_impl(
         mut self,
@@ -112,6 +129,8 @@ pub struct TcpOutletOptions {
     pub(crate) incoming_access_control: Arc<dyn IncomingAccessControl>,
     pub(crate) outgoing_access_control: Arc<dyn OutgoingAccessControl>,
     pub(crate) tls: bool,
+    pub(crate) limits: PortalLimits,
+    pub(crate) identity_limits: Option<PortalLimits>,
 }
 
 impl TcpOutletOptions {
@@ -122,6 +141,8 @@ impl TcpOutletOptions {
             incoming_access_control: Arc::new(AllowAll),
             outgoing_access_control: Arc::new(AllowAll),
             tls: false,
+            limits: PortalLimits::new(),
+            identity_limits: None,
         }
     }
 
@@ -149,6 +170,20 @@ impl TcpOutletOptions {
         self
     }
 
+    /// Set the limits applied to all the connections of the outlet
+    pub fn with_limits(mut self, limits: PortalLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Set the limits applied to the connections of each identity, keyed on the identifier
+    /// of the secure channel used to open the connections.
+    /// The connections opened without a secure channel share the same limits
+    pub fn with_identity_limits(mut self, limits: PortalLimits) -> Self {
+        self.identity_limits = Some(limits);
+        self
+    }
+
     /// Set Outgoing Access Control
     pub fn with_outgoing_access_control_impl(
         mut self,
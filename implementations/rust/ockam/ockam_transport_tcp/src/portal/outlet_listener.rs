@@ -1,9 +1,13 @@
 use crate::portal::addresses::{Addresses, PortalType};
-use crate::{portal::TcpPortalWorker, PortalMessage, TcpOutletOptions, TcpRegistry};
+use crate::portal::PortalLimiter;
+use crate::{
+    portal::TcpPortalWorker, PortalMessage, TcpOutletOptions, TcpPortalCounters, TcpRegistry,
+};
+use ockam_core::compat::sync::Arc;
 use ockam_core::{async_trait, Address, DenyAll, NeutralMessage, Result, Routed, Worker};
 use ockam_node::{Context, WorkerBuilder};
 use ockam_transport_core::{HostnamePort, TransportError};
-use tracing::{debug, instrument};
+use tracing::{debug, instrument, warn};
 
 /// A TCP Portal Outlet listen worker
 ///
@@ -14,15 +18,25 @@ pub(crate) struct TcpOutletListenWorker {
     registry: TcpRegistry,
     hostname_port: HostnamePort,
     options: TcpOutletOptions,
+    counters: Arc<TcpPortalCounters>,
+    limiter: PortalLimiter,
 }
 
 impl TcpOutletListenWorker {
     /// Create a new `TcpOutletListenWorker`
-    fn new(registry: TcpRegistry, hostname_port: HostnamePort, options: TcpOutletOptions) -> Self {
+    fn new(
+        registry: TcpRegistry,
+        hostname_port: HostnamePort,
+        options: TcpOutletOptions,
+        counters: Arc<TcpPortalCounters>,
+    ) -> Self {
+        let limiter = PortalLimiter::new(&options.limits, options.identity_limits.as_ref());
         Self {
             registry,
             hostname_port,
             options,
+            counters,
+            limiter,
         }
     }
 
@@ -38,7 +52,8 @@ impl TcpOutletListenWorker {
 
         options.setup_flow_control_for_outlet_listener(ctx.flow_controls(), &address);
 
-        let worker = Self::new(registry, hostname_port, options);
+        let counters = Arc::new(TcpPortalCounters::new(address.clone(), false));
+        let worker = Self::new(registry, hostname_port, options, counters);
         WorkerBuilder::new(worker)
             .with_address(address)
             .with_incoming_access_control_arc(access_control)
@@ -58,6 +73,7 @@ impl Worker for TcpOutletListenWorker {
     #[instrument(skip_all, name = "TcpOutletListenWorker::initialize")]
     async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
         self.registry.add_outlet_listener_worker(&ctx.address());
+        self.registry.add_portal_counters(self.counters.clone());
 
         Ok(())
     }
@@ -65,6 +81,8 @@ impl Worker for TcpOutletListenWorker {
     #[instrument(skip_all, name = "TcpOutletListenWorker::shutdown")]
     async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
         self.registry.remove_outlet_listener_worker(&ctx.address());
+        self.registry.remove_portal_counters(&ctx.address());
+        self.registry.remove_draining_portal(&ctx.address());
 
         Ok(())
     }
@@ -77,6 +95,26 @@ impl Worker for TcpOutletListenWorker {
     ) -> Result<()> {
         let return_route = msg.return_route();
         let src_addr = msg.src_addr();
+        // a connection over the limits, or refused while the outlet is draining, is still
+        // started, to notify the inlet that it is closed
+        let connection_limiter = if self.registry.is_portal_draining(&ctx.address()) {
+            debug!(
+                outlet = %ctx.address(),
+                "the outlet is draining, refusing the connection"
+            );
+            None
+        } else {
+            let connection_limiter = self
+                .limiter
+                .open_connection(msg.local_message().local_info_ref());
+            if connection_limiter.is_none() {
+                warn!(
+                    outlet = %ctx.address(),
+                    "the connection limits of the outlet are reached, closing the connection"
+                );
+            }
+            connection_limiter
+        };
         let body = msg.into_body()?.into_vec();
         let msg = PortalMessage::decode(&body)?;
 
@@ -99,6 +137,8 @@ impl Worker for TcpOutletListenWorker {
             addresses.clone(),
             self.options.incoming_access_control.clone(),
             self.options.outgoing_access_control.clone(),
+            self.counters.clone(),
+            connection_limiter,
         )
         .await?;
 
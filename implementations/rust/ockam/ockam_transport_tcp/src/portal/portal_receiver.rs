@@ -1,6 +1,8 @@
 use crate::portal::addresses::Addresses;
 use crate::portal::portal_message::MAX_PAYLOAD_SIZE;
-use crate::{PortalInternalMessage, PortalMessage, TcpRegistry};
+use crate::portal::ConnectionLimiter;
+use crate::{PortalInternalMessage, PortalMessage, TcpPortalCounters, TcpRegistry};
+use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::{
     async_trait, Encodable, LocalMessage, OpenTelemetryContext, Route, OCKAM_TRACER_NAME,
@@ -25,6 +27,8 @@ pub(crate) struct TcpPortalRecvProcessor<R> {
     addresses: Addresses,
     onward_route: Route,
     payload_packet_counter: u16,
+    counters: Arc<TcpPortalCounters>,
+    connection_limiter: Option<Arc<ConnectionLimiter>>,
 }
 
 impl<R: AsyncRead + Unpin + Send + Sync + 'static> TcpPortalRecvProcessor<R> {
@@ -34,6 +38,8 @@ impl<R: AsyncRead + Unpin + Send + Sync + 'static> TcpPortalRecvProcessor<R> {
         read_half: R,
         addresses: Addresses,
         onward_route: Route,
+        counters: Arc<TcpPortalCounters>,
+        connection_limiter: Option<Arc<ConnectionLimiter>>,
     ) -> Self {
         Self {
             registry,
@@ -42,6 +48,8 @@ impl<R: AsyncRead + Unpin + Send + Sync + 'static> TcpPortalRecvProcessor<R> {
             addresses,
             onward_route,
             payload_packet_counter: 0,
+            counters,
+            connection_limiter,
         }
     }
 }
@@ -69,7 +77,7 @@ impl<R: AsyncRead + Unpin + Send + Sync + 'static> Processor for TcpPortalRecvPr
     async fn process(&mut self, ctx: &mut Context) -> Result<bool> {
         self.buf.clear();
 
-        let _len = match self.read_half.read_buf(&mut self.buf).await {
+        let len = match self.read_half.read_buf(&mut self.buf).await {
             Ok(len) => len,
             Err(err) => {
                 error!("Tcp Portal connection read failed with error: {}", err);
@@ -112,6 +120,11 @@ impl<R: AsyncRead + Unpin + Send + Sync + 'static> Processor for TcpPortalRecvPr
             return Ok(false);
         }
 
+        self.counters.add_bytes_in(len);
+        if let Some(connection_limiter) = &self.connection_limiter {
+            connection_limiter.transfer(len).await;
+        }
+
         // Loop just in case buf was extended (should not happen though)
         for chunk in self.buf.chunks(MAX_PAYLOAD_SIZE) {
             let msg = LocalMessage::new()
@@ -122,7 +135,7 @@ impl<R: AsyncRead + Unpin + Send + Sync + 'static> Processor for TcpPortalRecvPr
                     PortalMessage::Payload(chunk, Some(self.payload_packet_counter)).encode()?,
                 );
 
-            self.payload_packet_counter += 1;
+            self.payload_packet_counter = self.payload_packet_counter.wrapping_add(1);
             ctx.forward_from_address(msg, self.addresses.receiver_remote.clone())
                 .await?;
         }
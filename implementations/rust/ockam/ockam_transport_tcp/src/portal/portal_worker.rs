@@ -1,8 +1,12 @@
 use crate::portal::addresses::{Addresses, PortalType};
 use crate::portal::portal_worker::ReadHalfMaybeTls::{ReadHalfNoTls, ReadHalfWithTls};
 use crate::portal::portal_worker::WriteHalfMaybeTls::{WriteHalfNoTls, WriteHalfWithTls};
+use crate::portal::ConnectionLimiter;
 use crate::transport::{connect, connect_tls};
-use crate::{portal::TcpPortalRecvProcessor, PortalInternalMessage, PortalMessage, TcpRegistry};
+use crate::{
+    portal::TcpPortalRecvProcessor, PortalInternalMessage, PortalMessage, TcpPortalCounters,
+    TcpRegistry,
+};
 use ockam_core::compat::{boxed::Box, sync::Arc};
 use ockam_core::{
     async_trait, AllowOnwardAddress, AllowSourceAddress, Decodable, DenyAll, IncomingAccessControl,
@@ -22,12 +26,14 @@ use tracing::{debug, info, instrument, trace, warn};
 ///
 /// Possible state transitions are:
 ///
-/// `Outlet`: `SendPong` -> `Initialized`
-/// `Inlet`: `SendPing` -> `ReceivePong` -> `Initialized`
+/// `Outlet`: `SendPong` -> `Initialized`, or `SendDisconnect` if the connection is over the limits
+/// `Inlet`: `SendPing` -> `ReceivePong` -> `Initialized`. In `ReceivePong`, a refused connection
+/// sends a new ping on the next fallback route, if any
 #[derive(Clone)]
 enum State {
     SendPing { ping_route: Route },
     SendPong { pong_route: Route },
+    SendDisconnect { disconnect_route: Route },
     ReceivePong,
     Initialized,
 }
@@ -51,6 +57,10 @@ pub(crate) struct TcpPortalWorker {
     last_received_packet_counter: u16,
     outgoing_access_control: Arc<dyn OutgoingAccessControl>,
     is_tls: bool,
+    counters: Arc<TcpPortalCounters>,
+    is_counted: bool,
+    connection_limiter: Option<Arc<ConnectionLimiter>>,
+    fallback_ping_routes: Vec<Route>,
 }
 
 pub(crate) enum ReadHalfMaybeTls {
@@ -73,9 +83,12 @@ impl TcpPortalWorker {
         streams: (ReadHalfMaybeTls, WriteHalfMaybeTls),
         hostname_port: HostnamePort,
         ping_route: Route,
+        fallback_ping_routes: Vec<Route>,
         addresses: Addresses,
         incoming_access_control: Arc<dyn IncomingAccessControl>,
         outgoing_access_control: Arc<dyn OutgoingAccessControl>, // To propagate to the receiver
+        counters: Arc<TcpPortalCounters>,
+        connection_limiter: Arc<ConnectionLimiter>,
     ) -> Result<()> {
         Self::start(
             ctx,
@@ -83,15 +96,20 @@ impl TcpPortalWorker {
             hostname_port,
             false,
             State::SendPing { ping_route },
+            fallback_ping_routes,
             Some(streams),
             addresses,
             incoming_access_control,
             outgoing_access_control,
+            counters,
+            Some(connection_limiter),
         )
         .await
     }
 
-    /// Start a new `TcpPortalWorker` of type [`TypeName::Outlet`]
+    /// Start a new `TcpPortalWorker` of type [`TypeName::Outlet`].
+    /// Without a connection limiter, the worker only notifies the inlet that the connection
+    /// is closed, then stops
     #[allow(clippy::too_many_arguments)]
     #[instrument(skip_all)]
     pub(super) async fn start_new_outlet(
@@ -103,17 +121,29 @@ impl TcpPortalWorker {
         addresses: Addresses,
         incoming_access_control: Arc<dyn IncomingAccessControl>,
         outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        counters: Arc<TcpPortalCounters>,
+        connection_limiter: Option<Arc<ConnectionLimiter>>,
     ) -> Result<()> {
+        let state = if connection_limiter.is_some() {
+            State::SendPong { pong_route }
+        } else {
+            State::SendDisconnect {
+                disconnect_route: pong_route,
+            }
+        };
         Self::start(
             ctx,
             registry,
             hostname_port,
             tls,
-            State::SendPong { pong_route },
+            state,
+            vec![],
             None,
             addresses,
             incoming_access_control,
             outgoing_access_control,
+            counters,
+            connection_limiter,
         )
         .await
     }
@@ -127,10 +157,13 @@ impl TcpPortalWorker {
         hostname_port: HostnamePort,
         is_tls: bool,
         state: State,
+        fallback_ping_routes: Vec<Route>,
         streams: Option<(ReadHalfMaybeTls, WriteHalfMaybeTls)>,
         addresses: Addresses,
         incoming_access_control: Arc<dyn IncomingAccessControl>,
         outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        counters: Arc<TcpPortalCounters>,
+        connection_limiter: Option<Arc<ConnectionLimiter>>,
     ) -> Result<()> {
         let portal_type = if streams.is_some() {
             PortalType::Inlet
@@ -166,6 +199,10 @@ impl TcpPortalWorker {
             last_received_packet_counter: u16::MAX,
             is_tls,
             outgoing_access_control: outgoing_access_control.clone(),
+            counters,
+            is_counted: false,
+            connection_limiter,
+            fallback_ping_routes,
         };
 
         let internal_mailbox = Mailbox::new(
@@ -227,6 +264,8 @@ impl TcpPortalWorker {
             rx,
             self.addresses.clone(),
             onward_route,
+            self.counters.clone(),
+            self.connection_limiter.clone(),
         );
 
         let remote = Mailbox::new(
@@ -404,6 +443,27 @@ impl TcpPortalWorker {
         self.remote_route = Some(pong_route);
         Ok(State::Initialized)
     }
+
+    #[instrument(skip_all)]
+    async fn handle_send_disconnect(
+        &mut self,
+        ctx: &Context,
+        disconnect_route: Route,
+    ) -> Result<()> {
+        ctx.send_from_address(
+            disconnect_route,
+            PortalMessage::Disconnect.to_neutral_message()?,
+            self.addresses.sender_remote.clone(),
+        )
+        .await?;
+        debug!(
+            "Outlet at: {} refused the connection",
+            self.addresses.sender_internal
+        );
+
+        self.is_disconnecting = true;
+        self.stop_sender(ctx).await
+    }
 }
 
 #[async_trait]
@@ -422,21 +482,44 @@ impl Worker for TcpPortalWorker {
             State::SendPong { pong_route } => {
                 self.state = self.handle_send_pong(ctx, pong_route.clone()).await?;
             }
+            State::SendDisconnect { disconnect_route } => {
+                return self.handle_send_disconnect(ctx, disconnect_route).await;
+            }
             State::ReceivePong | State::Initialized { .. } => {
                 return Err(TransportError::PortalInvalidState)?;
             }
         }
 
         self.registry
-            .add_portal_worker(&self.addresses.sender_remote);
+            .add_portal_worker(&self.addresses.sender_remote, self.counters.address());
+        self.counters.connection_opened();
+        self.is_counted = true;
 
         Ok(())
     }
 
     #[instrument(skip_all, name = "TcpPortalWorker::shutdown")]
-    async fn shutdown(&mut self, _ctx: &mut Self::Context) -> Result<()> {
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        // The worker is stopped while the connection is still open, for example when the node
+        // is stopped or when a drained outlet reaches its deadline: let the other end know
+        if matches!(self.state, State::Initialized) && !self.is_disconnecting {
+            self.is_disconnecting = true;
+            if let Err(err) = self.notify_remote_about_disconnection(ctx).await {
+                debug!(
+                    "{:?} at: {} could not notify the other side about the connection drop: {}",
+                    self.portal_type.str(),
+                    self.addresses.sender_internal,
+                    err
+                );
+            }
+            self.stop_receiver(ctx).await?;
+        }
+
         self.registry
             .remove_portal_worker(&self.addresses.sender_remote);
+        if self.is_counted {
+            self.counters.connection_closed();
+        }
 
         Ok(())
     }
@@ -466,10 +549,12 @@ impl Worker for TcpPortalWorker {
                 if !remote_packet {
                     return Err(TransportError::PortalInvalidState)?;
                 };
-                if PortalMessage::decode(&payload)? != PortalMessage::Pong {
-                    return Err(TransportError::Protocol)?;
-                };
-                self.handle_receive_pong(ctx, return_route).await
+                match PortalMessage::decode(&payload)? {
+                    PortalMessage::Pong => self.handle_receive_pong(ctx, return_route).await,
+                    // the outlet refused the connection
+                    PortalMessage::Disconnect => self.handle_refused_connection(ctx).await,
+                    _ => Err(TransportError::Protocol)?,
+                }
             }
             State::Initialized => {
                 trace!(
@@ -502,7 +587,7 @@ impl Worker for TcpPortalWorker {
                     self.handle_disconnect(ctx).await
                 }
             }
-            State::SendPing { .. } | State::SendPong { .. } => {
+            State::SendPing { .. } | State::SendPong { .. } | State::SendDisconnect { .. } => {
                 return Err(TransportError::PortalInvalidState)?;
             }
         }
@@ -519,6 +604,25 @@ impl TcpPortalWorker {
         Ok(())
     }
 
+    /// Try the next fallback route when an outlet refuses the connection,
+    /// or disconnect if there is none left
+    #[instrument(skip_all)]
+    async fn handle_refused_connection(&mut self, ctx: &Context) -> Result<()> {
+        if self.fallback_ping_routes.is_empty() {
+            return self
+                .start_disconnection(ctx, DisconnectionReason::Remote)
+                .await;
+        }
+
+        let ping_route = self.fallback_ping_routes.remove(0);
+        debug!(
+            "Inlet at: {} was refused by the outlet, trying the next route",
+            self.addresses.sender_internal
+        );
+        self.state = self.handle_send_ping(ctx, ping_route).await?;
+        Ok(())
+    }
+
     #[instrument(skip_all)]
     async fn handle_disconnect(&mut self, ctx: &Context) -> Result<()> {
         info!(
@@ -544,6 +648,9 @@ impl TcpPortalWorker {
         } else {
             return Err(TransportError::PortalInvalidState)?;
         };
+        if let Some(connection_limiter) = &self.connection_limiter {
+            connection_limiter.transfer(payload.len()).await;
+        }
 
         let result = match tx {
             WriteHalfNoTls(tx) => tx.write_all(payload).await,
@@ -556,6 +663,8 @@ impl TcpPortalWorker {
             );
             self.start_disconnection(ctx, DisconnectionReason::FailedTx)
                 .await?;
+        } else {
+            self.counters.add_bytes_out(payload.len());
         }
 
         Ok(())
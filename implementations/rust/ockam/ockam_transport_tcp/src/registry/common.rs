@@ -1,5 +1,6 @@
 use core::fmt;
 use core::fmt::Formatter;
+use core::sync::atomic::{AtomicU64, Ordering};
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::Address;
 use std::net::SocketAddr;
@@ -157,3 +158,100 @@ impl TcpListenerInfo {
         &self.flow_control_id
     }
 }
+
+/// Connection and traffic counters shared between a portal inlet or outlet and the
+/// portal workers it creates
+#[derive(Debug)]
+pub(crate) struct TcpPortalCounters {
+    address: Address,
+    is_inlet: bool,
+    active_connections: AtomicU64,
+    total_connections: AtomicU64,
+    bytes_in: AtomicU64,
+    bytes_out: AtomicU64,
+}
+
+impl TcpPortalCounters {
+    pub(crate) fn new(address: Address, is_inlet: bool) -> Self {
+        Self {
+            address,
+            is_inlet,
+            active_connections: AtomicU64::new(0),
+            total_connections: AtomicU64::new(0),
+            bytes_in: AtomicU64::new(0),
+            bytes_out: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn address(&self) -> &Address {
+        &self.address
+    }
+
+    pub(crate) fn connection_opened(&self) {
+        self.active_connections.fetch_add(1, Ordering::Relaxed);
+        self.total_connections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn connection_closed(&self) {
+        self.active_connections.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Bytes read from the TCP connection
+    pub(crate) fn add_bytes_in(&self, len: usize) {
+        self.bytes_in.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    /// Bytes written to the TCP connection
+    pub(crate) fn add_bytes_out(&self, len: usize) {
+        self.bytes_out.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn stats(&self) -> TcpPortalStats {
+        TcpPortalStats {
+            address: self.address.clone(),
+            is_inlet: self.is_inlet,
+            active_connections: self.active_connections.load(Ordering::Relaxed),
+            total_connections: self.total_connections.load(Ordering::Relaxed),
+            bytes_in: self.bytes_in.load(Ordering::Relaxed),
+            bytes_out: self.bytes_out.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Connection and traffic statistics of a Tcp portal inlet or outlet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TcpPortalStats {
+    address: Address,
+    is_inlet: bool,
+    active_connections: u64,
+    total_connections: u64,
+    bytes_in: u64,
+    bytes_out: u64,
+}
+
+impl TcpPortalStats {
+    /// Address of the inlet listener processor or of the outlet listener worker
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+    /// True for an inlet, false for an outlet
+    pub fn is_inlet(&self) -> bool {
+        self.is_inlet
+    }
+    /// Number of currently open portal connections
+    pub fn active_connections(&self) -> u64 {
+        self.active_connections
+    }
+    /// Number of portal connections opened since the portal was created
+    pub fn total_connections(&self) -> u64 {
+        self.total_connections
+    }
+    /// Number of bytes read from the Tcp connections
+    pub fn bytes_in(&self) -> u64 {
+        self.bytes_in
+    }
+    /// Number of bytes written to the Tcp connections
+    pub fn bytes_out(&self) -> u64 {
+        self.bytes_out
+    }
+}
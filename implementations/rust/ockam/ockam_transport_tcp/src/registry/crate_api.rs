@@ -1,10 +1,11 @@
-use crate::{TcpListenerInfo, TcpReceiverInfo, TcpRegistry, TcpSenderInfo};
+use crate::{TcpListenerInfo, TcpPortalCounters, TcpReceiverInfo, TcpRegistry, TcpSenderInfo};
+use ockam_core::compat::sync::Arc;
 use ockam_core::Address;
 
 impl TcpRegistry {
-    pub(crate) fn add_portal_worker(&self, addr: &Address) {
+    pub(crate) fn add_portal_worker(&self, addr: &Address, portal_addr: &Address) {
         if let Ok(mut lock) = self.registry.write() {
-            lock.add_portal_worker(addr);
+            lock.add_portal_worker(addr, portal_addr);
         }
     }
     pub(crate) fn remove_portal_worker(&self, addr: &Address) {
@@ -72,4 +73,42 @@ impl TcpRegistry {
             lock.remove_receiver_processor(addr);
         }
     }
+    pub(crate) fn add_portal_counters(&self, counters: Arc<TcpPortalCounters>) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_portal_counters(counters);
+        }
+    }
+    pub(crate) fn remove_portal_counters(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.remove_portal_counters(addr);
+        }
+    }
+    pub(crate) fn add_draining_portal(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_draining_portal(addr);
+        }
+    }
+    pub(crate) fn remove_draining_portal(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.remove_draining_portal(addr);
+        }
+    }
+    pub(crate) fn is_portal_draining(&self, addr: &Address) -> bool {
+        self.registry
+            .read()
+            .unwrap()
+            .draining_portals
+            .contains(addr)
+    }
+    /// Return the addresses of the portal workers created by an inlet or an outlet
+    pub(crate) fn get_portal_workers(&self, portal_addr: &Address) -> Vec<Address> {
+        self.registry
+            .read()
+            .unwrap()
+            .portal_workers
+            .iter()
+            .filter(|(_, x)| x == portal_addr)
+            .map(|(x, _)| x.clone())
+            .collect()
+    }
 }
@@ -1,23 +1,28 @@
-use crate::{TcpListenerInfo, TcpReceiverInfo, TcpSenderInfo};
+use crate::{TcpListenerInfo, TcpPortalCounters, TcpReceiverInfo, TcpSenderInfo};
+use ockam_core::compat::sync::Arc;
 use ockam_core::Address;
 
 #[derive(Default, Debug)]
 pub(super) struct InternalRegistry {
-    pub(super) portal_workers: Vec<Address>,
+    /// Portal workers, with the address of the inlet or outlet which created them
+    pub(super) portal_workers: Vec<(Address, Address)>,
     pub(super) portal_receiver_processors: Vec<Address>,
     pub(super) inlet_listener_processors: Vec<Address>,
     pub(super) outlet_listener_workers: Vec<Address>,
     pub(super) listener_processors: Vec<TcpListenerInfo>,
     pub(super) sender_workers: Vec<TcpSenderInfo>,
     pub(super) receiver_processors: Vec<TcpReceiverInfo>,
+    pub(super) portal_counters: Vec<Arc<TcpPortalCounters>>,
+    pub(super) draining_portals: Vec<Address>,
 }
 
 impl InternalRegistry {
-    pub(super) fn add_portal_worker(&mut self, addr: &Address) {
-        self.portal_workers.push(addr.clone())
+    pub(super) fn add_portal_worker(&mut self, addr: &Address, portal_addr: &Address) {
+        self.portal_workers
+            .push((addr.clone(), portal_addr.clone()))
     }
     pub(super) fn remove_portal_worker(&mut self, addr: &Address) {
-        self.portal_workers.retain(|x| x != addr);
+        self.portal_workers.retain(|(x, _)| x != addr);
     }
     pub(super) fn add_portal_receiver_processor(&mut self, addr: &Address) {
         self.portal_receiver_processors.push(addr.clone())
@@ -55,4 +60,18 @@ impl InternalRegistry {
     pub(super) fn remove_receiver_processor(&mut self, addr: &Address) {
         self.receiver_processors.retain(|x| x.address() != addr);
     }
+    pub(super) fn add_portal_counters(&mut self, counters: Arc<TcpPortalCounters>) {
+        self.portal_counters.push(counters)
+    }
+    pub(super) fn remove_portal_counters(&mut self, addr: &Address) {
+        self.portal_counters.retain(|x| x.address() != addr);
+    }
+    pub(super) fn add_draining_portal(&mut self, addr: &Address) {
+        if !self.draining_portals.contains(addr) {
+            self.draining_portals.push(addr.clone())
+        }
+    }
+    pub(super) fn remove_draining_portal(&mut self, addr: &Address) {
+        self.draining_portals.retain(|x| x != addr);
+    }
 }
@@ -1,5 +1,5 @@
 use crate::registry::internal::InternalRegistry;
-use crate::{TcpListenerInfo, TcpReceiverInfo, TcpSenderInfo};
+use crate::{TcpListenerInfo, TcpPortalStats, TcpReceiverInfo, TcpSenderInfo};
 use ockam_core::compat::sync::{Arc, RwLock};
 
 /// Registry of all active workers and processors in TCP Transport to ease their lifecycle management
@@ -23,4 +23,15 @@ impl TcpRegistry {
     pub fn get_all_listeners(&self) -> Vec<TcpListenerInfo> {
         self.registry.read().unwrap().listener_processors.clone()
     }
+
+    /// Return connection and traffic statistics of all active inlets and outlets
+    pub fn get_all_portal_stats(&self) -> Vec<TcpPortalStats> {
+        self.registry
+            .read()
+            .unwrap()
+            .portal_counters
+            .iter()
+            .map(|x| x.stats())
+            .collect()
+    }
 }
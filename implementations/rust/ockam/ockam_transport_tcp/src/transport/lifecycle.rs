@@ -6,6 +6,7 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 use tracing::instrument;
 
+use crate::portal::TcpShutdownDrain;
 use crate::{TcpConnectionOptions, TcpListenerInfo, TcpRegistry, TcpSenderInfo, TcpTransport, TCP};
 
 impl TcpTransport {
@@ -26,6 +27,9 @@ impl TcpTransport {
         // later address resolution when socket addresses will need to be instantiated as TCP
         // worker addresses
         ctx.register_transport(Arc::new(tcp.clone()));
+        // drain the portals before stopping the workers when the node is stopped with a drain
+        ctx.register_shutdown_drain(Arc::new(TcpShutdownDrain::new(tcp.registry.clone())))
+            .await?;
         Ok(tcp)
     }
 }
@@ -1,14 +1,16 @@
-use crate::portal::{InletSharedState, TcpInletListenProcessor};
+use crate::portal::{wait_for_closed_connections, InletSharedState, TcpInletListenProcessor};
 use crate::{portal::TcpOutletListenWorker, TcpInletOptions, TcpOutletOptions, TcpTransport};
 use core::fmt;
 use core::fmt::{Debug, Formatter};
+use core::time::Duration;
 use ockam_core::compat::net::SocketAddr;
 use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::errcode::{Kind, Origin};
 use ockam_core::flow_control::FlowControls;
-use ockam_core::{route, Address, Result, Route};
+use ockam_core::{route, Address, Error, Result, Route};
 use ockam_node::Context;
 use ockam_transport_core::{parse_socket_addr, HostnamePort};
-use tracing::instrument;
+use tracing::{debug, instrument};
 
 impl TcpTransport {
     /// Create Tcp Inlet that listens on bind_addr, transforms Tcp stream into Ockam Routable
@@ -35,11 +37,63 @@ impl TcpTransport {
         outlet_route: impl Into<Route> + Clone + Debug,
         options: TcpInletOptions,
     ) -> Result<TcpInlet> {
+        let socket_address = parse_socket_addr(&bind_addr.into())?;
+        let mut inlets = TcpInletListenProcessor::start(
+            &self.ctx,
+            self.registry.clone(),
+            vec![outlet_route.into()],
+            socket_address,
+            options,
+        )
+        .await?;
+        Ok(inlets.remove(0))
+    }
+
+    /// Create Tcp Inlet that listens on bind_addr and spreads the accepted Tcp connections
+    /// between several outlets, reachable with the outlet_routes. The outlet of each connection
+    /// is chosen with the [`InletLoadBalancing`](crate::InletLoadBalancing) strategy of the options.
+    ///
+    /// One [`TcpInlet`] is returned for each route, in the same order. Pausing one of them takes
+    /// its outlet out of the rotation, while the connections keep being accepted for the other
+    /// outlets. Stopping any of them stops the whole inlet.
+    ///
+    /// ```rust
+    /// use ockam_transport_tcp::{InletLoadBalancing, TcpInletOptions, TcpTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::{Result, route};
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let tcp = TcpTransport::create(&ctx).await?;
+    /// let inlets = tcp
+    ///     .create_balanced_inlet(
+    ///         "127.0.0.1:5000",
+    ///         vec![route!["outlet1"], route!["outlet2"]],
+    ///         TcpInletOptions::new().with_load_balancing(InletLoadBalancing::Random),
+    ///     )
+    ///     .await?;
+    /// // take the first outlet out of the rotation
+    /// inlets[0].pause();
+    /// # inlets[0].stop(&ctx).await?;
+    /// # Ok(()) }
+    /// ```
+    #[instrument(skip(self), fields(address = ? bind_addr.clone().into(), outlet_routes = ? outlet_routes))]
+    pub async fn create_balanced_inlet(
+        &self,
+        bind_addr: impl Into<String> + Clone + Debug,
+        outlet_routes: Vec<Route>,
+        options: TcpInletOptions,
+    ) -> Result<Vec<TcpInlet>> {
+        if outlet_routes.is_empty() {
+            return Err(Error::new(
+                Origin::Transport,
+                Kind::Invalid,
+                "at least one outlet route is required to create an inlet",
+            ));
+        }
         let socket_address = parse_socket_addr(&bind_addr.into())?;
         TcpInletListenProcessor::start(
             &self.ctx,
             self.registry.clone(),
-            outlet_route.into(),
+            outlet_routes,
             socket_address,
             options,
         )
@@ -124,6 +178,47 @@ impl TcpTransport {
         self.ctx.stop_worker(addr).await?;
         Ok(())
     }
+
+    /// Drain the outlet at addr, then stop it.
+    ///
+    /// While the outlet is draining, it refuses new connections: an inlet with several outlet
+    /// routes opens them via its next route, other inlets close them. The open connections keep
+    /// running until they are closed, or until the `timeout` is reached, in which case they are
+    /// closed as well.
+    /// ```rust
+    /// use ockam_transport_tcp::{TcpOutletOptions, TcpTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::{AllowAll, Result};
+    /// # use ockam_transport_core::HostnamePort;
+    /// use std::time::Duration;
+    ///
+    /// async fn test(ctx: Context) -> Result<()> {
+    ///
+    /// let tcp = TcpTransport::create(&ctx).await?;
+    /// tcp.create_outlet("outlet", HostnamePort::new("127.0.0.1", 5000), TcpOutletOptions::new()).await?;
+    /// tcp.drain_outlet("outlet", Duration::from_secs(30)).await?;
+    /// # Ok(()) }
+    /// ```
+    #[instrument(skip(self), fields(address = % addr.clone().into()))]
+    pub async fn drain_outlet(
+        &self,
+        addr: impl Into<Address> + Clone + Debug,
+        timeout: Duration,
+    ) -> Result<()> {
+        let addr = addr.into();
+        self.registry.add_draining_portal(&addr);
+
+        let portal_addresses = [addr.clone()];
+        if !wait_for_closed_connections(&self.registry, &portal_addresses, Some(timeout)).await {
+            for portal_worker in self.registry.get_portal_workers(&addr) {
+                if let Err(e) = self.ctx.stop_worker(portal_worker.clone()).await {
+                    debug!(%portal_worker, %e, "Failed to stop a portal worker of a drained outlet");
+                }
+            }
+        }
+
+        self.stop_outlet(addr).await
+    }
 }
 
 /// Result of [`TcpTransport::create_inlet`] call.
@@ -268,6 +363,23 @@ impl TcpInlet {
         Ok(())
     }
 
+    /// Unpause TCP Inlet and replace the outlet route with `full_route`, which includes
+    /// the address of the outlet.
+    pub fn unpause_with_outlet_route(
+        &self,
+        flow_controls: &FlowControls,
+        full_route: Route,
+    ) -> Result<()> {
+        let mut inlet_shared_state = self.inlet_shared_state.write().unwrap();
+
+        let next = full_route.next()?.clone();
+        inlet_shared_state.route = full_route;
+        inlet_shared_state.is_paused = false;
+        self.update_flow_controls(flow_controls, next);
+
+        Ok(())
+    }
+
     /// Stop the Inlet
     pub async fn stop(&self, ctx: &Context) -> Result<()> {
         match &self.state {
@@ -2,7 +2,7 @@ use ockam_core::{async_trait, route, AllowAll};
 use ockam_node::Context;
 use ockam_transport_tcp::{
     Direction, PortalInletInterceptor, PortalInterceptor, PortalInterceptorFactory,
-    TcpInletOptions, TcpOutletOptions, TcpTransport,
+    PortalInterceptorRejection, TcpInletOptions, TcpOutletOptions, TcpTransport,
 };
 use rand::random;
 use std::sync::{Arc, Mutex};
@@ -43,9 +43,50 @@ impl PortalInterceptorFactory for MockPortalInterceptorFactory {
     }
 }
 
+/// Interceptor rejecting the data sent by the inlet, with a response
+struct RejectingPortalInterceptor;
+
+#[async_trait]
+impl PortalInterceptor for RejectingPortalInterceptor {
+    async fn intercept(
+        &self,
+        _context: &mut Context,
+        direction: Direction,
+        buffer: &[u8],
+    ) -> ockam_core::Result<Option<Vec<u8>>> {
+        match direction {
+            Direction::FromInletToOutlet => Err(PortalInterceptorRejection::new("denied")
+                .with_response(b"denied".to_vec())
+                .into()),
+            Direction::FromOutletToInlet => Ok(Some(buffer.to_vec())),
+        }
+    }
+}
+
+impl PortalInterceptorFactory for RejectingPortalInterceptor {
+    fn create(&self) -> Arc<dyn PortalInterceptor> {
+        Arc::new(RejectingPortalInterceptor)
+    }
+}
+
 async fn setup(
     context: &mut Context,
 ) -> ockam_core::Result<(String, TcpListener, Arc<MockPortalInterceptor>)> {
+    let mock_portal_interceptor = Arc::new(MockPortalInterceptor::default());
+    let (inlet_addr, listener) = setup_with_factory(
+        context,
+        Arc::new(MockPortalInterceptorFactory {
+            interceptor: mock_portal_interceptor.clone(),
+        }),
+    )
+    .await?;
+    Ok((inlet_addr, listener, mock_portal_interceptor))
+}
+
+async fn setup_with_factory(
+    context: &mut Context,
+    interceptor_factory: Arc<dyn PortalInterceptorFactory>,
+) -> ockam_core::Result<(String, TcpListener)> {
     let tcp = TcpTransport::create(context).await?;
 
     let listener = {
@@ -60,14 +101,10 @@ async fn setup(
         listener
     };
 
-    let mock_portal_interceptor = Arc::new(MockPortalInterceptor::default());
-
     PortalInletInterceptor::create(
         context,
         "interceptor_listener".into(),
-        Arc::new(MockPortalInterceptorFactory {
-            interceptor: mock_portal_interceptor.clone(),
-        }),
+        interceptor_factory,
         Arc::new(AllowAll),
         Arc::new(AllowAll),
     )
@@ -82,11 +119,7 @@ async fn setup(
         )
         .await?;
 
-    Ok((
-        inlet.socket_address().to_string(),
-        listener,
-        mock_portal_interceptor,
-    ))
+    Ok((inlet.socket_address().to_string(), listener))
 }
 
 const LENGTH: usize = 32;
@@ -147,3 +180,33 @@ async fn interceptor__simple_payload__received(context: &mut Context) -> ockam_c
 
     Ok(())
 }
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5_000)]
+async fn interceptor__rejected_payload__both_sides_disconnected(
+    context: &mut Context,
+) -> ockam_core::Result<()> {
+    let (inlet_addr, listener) =
+        setup_with_factory(context, Arc::new(RejectingPortalInterceptor)).await?;
+
+    // the server doesn't receive the rejected payload, and is disconnected
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut received = vec![];
+        stream.read_to_end(&mut received).await.unwrap();
+        received
+    });
+
+    // Wait till the listener is up
+    tokio::time::sleep(Duration::from_millis(250)).await;
+
+    // the client receives the response of the rejection, then is disconnected
+    let mut stream = TcpStream::connect(inlet_addr).await.unwrap();
+    write_binary(&mut stream, generate_binary()).await;
+    let mut response = vec![];
+    stream.read_to_end(&mut response).await.unwrap();
+    assert_eq!(response, b"denied");
+
+    assert!(handle.await.unwrap().is_empty());
+    Ok(())
+}
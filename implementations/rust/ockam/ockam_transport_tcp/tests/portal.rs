@@ -87,6 +87,59 @@ async fn portal__standard_flow__should_succeed(ctx: &mut Context) -> Result<()>
     Ok(())
 }
 
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__standard_flow__should_update_stats(ctx: &mut Context) -> Result<()> {
+    let payload1 = generate_binary();
+    let payload2 = generate_binary();
+
+    let tcp = TcpTransport::create(ctx).await?;
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet(
+        "outlet",
+        bind_address.try_into().unwrap(),
+        TcpOutletOptions::new(),
+    )
+    .await?;
+    let inlet = tcp
+        .create_inlet("127.0.0.1:0", route!["outlet"], TcpInletOptions::new())
+        .await?;
+
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+
+        read_assert_binary(&mut stream, payload1).await;
+        write_binary(&mut stream, payload2).await;
+        stream
+    });
+
+    // Wait till the listener is up
+    tokio::time::sleep(Duration::from_millis(250)).await;
+
+    let mut stream = TcpStream::connect(inlet.socket_address()).await.unwrap();
+    write_binary(&mut stream, payload1).await;
+    read_assert_binary(&mut stream, payload2).await;
+    let _stream = handle.await.unwrap();
+
+    let stats = tcp.registry().get_all_portal_stats();
+    assert_eq!(stats.len(), 2);
+
+    let inlet_stats = stats.iter().find(|s| s.is_inlet()).unwrap();
+    assert_eq!(inlet_stats.active_connections(), 1);
+    assert_eq!(inlet_stats.total_connections(), 1);
+    assert_eq!(inlet_stats.bytes_in(), LENGTH as u64);
+    assert_eq!(inlet_stats.bytes_out(), LENGTH as u64);
+
+    let outlet_stats = stats.iter().find(|s| !s.is_inlet()).unwrap();
+    assert_eq!(outlet_stats.address(), &"outlet".into());
+    assert_eq!(outlet_stats.active_connections(), 1);
+    assert_eq!(outlet_stats.bytes_in(), LENGTH as u64);
+    assert_eq!(outlet_stats.bytes_out(), LENGTH as u64);
+
+    Ok(())
+}
+
 #[allow(non_snake_case)]
 #[ockam_macros::test(timeout = 5000)]
 async fn portal__reverse_flow__should_succeed(ctx: &mut Context) -> Result<()> {
@@ -311,3 +364,81 @@ async fn portal__update_route__should_succeed(ctx: &mut Context) -> Result<()> {
 
     Ok(())
 }
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__drain_outlet__should_keep_open_connections_and_refuse_new_ones(
+    ctx: &mut Context,
+) -> Result<()> {
+    let payload1 = generate_binary();
+    let payload2 = generate_binary();
+
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let listener1 = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let listener2 = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    tcp.create_outlet(
+        "outlet1",
+        listener1.local_addr().unwrap().to_string().try_into()?,
+        TcpOutletOptions::new(),
+    )
+    .await?;
+    tcp.create_outlet(
+        "outlet2",
+        listener2.local_addr().unwrap().to_string().try_into()?,
+        TcpOutletOptions::new(),
+    )
+    .await?;
+    let inlets = tcp
+        .create_balanced_inlet(
+            "127.0.0.1:0",
+            vec![route!["outlet1"], route!["outlet2"]],
+            TcpInletOptions::new(),
+        )
+        .await?;
+    let inlet_address = inlets[0].socket_address();
+
+    // The first connection is opened with the first outlet
+    let mut stream = TcpStream::connect(inlet_address).await.unwrap();
+    write_binary(&mut stream, payload1).await;
+    let (mut outlet_stream, _) = listener1.accept().await.unwrap();
+    read_assert_binary(&mut outlet_stream, payload1).await;
+
+    let drain = {
+        let tcp = tcp.clone();
+        tokio::spawn(async move { tcp.drain_outlet("outlet1", Duration::from_secs(10)).await })
+    };
+    tokio::time::sleep(Duration::from_millis(250)).await;
+
+    // The new connections are refused by the draining outlet and opened with the second one
+    let handle = tokio::spawn(async move {
+        let mut streams = vec![];
+        for _ in 0..2 {
+            let (mut stream, _) = listener2.accept().await.unwrap();
+            read_assert_binary(&mut stream, payload1).await;
+            write_binary(&mut stream, payload2).await;
+            streams.push(stream);
+        }
+        streams
+    });
+    for _ in 0..2 {
+        let mut new_stream = TcpStream::connect(inlet_address).await.unwrap();
+        write_binary(&mut new_stream, payload1).await;
+        read_assert_binary(&mut new_stream, payload2).await;
+    }
+    let _streams = handle.await.unwrap();
+
+    // The connection opened before the drain is still working
+    write_binary(&mut outlet_stream, payload2).await;
+    read_assert_binary(&mut stream, payload2).await;
+    assert!(!drain.is_finished());
+
+    // The outlet is stopped once its last connection is closed
+    drop(stream);
+    drain.await.unwrap()?;
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    let stats = tcp.registry().get_all_portal_stats();
+    assert!(!stats.iter().any(|s| s.address() == &"outlet1".into()));
+
+    Ok(())
+}
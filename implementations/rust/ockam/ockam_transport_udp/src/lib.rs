@@ -19,11 +19,13 @@ extern crate alloc;
 
 mod messages;
 mod options;
+mod portal;
 mod puncture;
 mod transport;
 mod workers;
 
 pub use options::UdpBindOptions;
+pub use portal::{UdpInlet, UdpInletOptions, UdpOutletOptions, DEFAULT_UDP_PORTAL_IDLE_TIMEOUT};
 pub use puncture::*;
 pub use transport::{UdpBind, UdpBindArguments, UdpTransport, UdpTransportExtension};
 
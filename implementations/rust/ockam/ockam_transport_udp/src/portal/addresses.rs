@@ -0,0 +1,19 @@
+use ockam_core::Address;
+
+/// Addresses of a UDP portal session
+#[derive(Clone, Debug)]
+pub(crate) struct PortalAddresses {
+    /// Used to receive messages from the other side of the portal
+    pub(crate) remote: Address,
+    /// Used to receive datagrams read locally from the UDP socket
+    pub(crate) internal: Address,
+}
+
+impl PortalAddresses {
+    pub(crate) fn generate(portal_type: &str) -> Self {
+        Self {
+            remote: Address::random_tagged(&format!("UdpPortalWorker.{portal_type}.remote")),
+            internal: Address::random_tagged(&format!("UdpPortalWorker.{portal_type}.internal")),
+        }
+    }
+}
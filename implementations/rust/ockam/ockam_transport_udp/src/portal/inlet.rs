@@ -0,0 +1,423 @@
+use crate::portal::{PortalAddresses, UdpInletOptions, UdpPortalMessage, MAX_DATAGRAM_SIZE};
+use core::fmt;
+use core::fmt::Formatter;
+use core::time::Duration;
+use ockam_core::compat::collections::{HashMap, VecDeque};
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::{
+    async_trait, Address, AllowAll, AllowSourceAddress, DenyAll, Mailbox, Mailboxes, Processor,
+    Result, Route, Routed, Worker,
+};
+use ockam_node::{Context, ProcessorBuilder, WorkerBuilder};
+use ockam_transport_core::TransportError;
+use std::net::SocketAddr;
+use tokio::net::UdpSocket;
+use tokio::time::Instant;
+use tracing::{debug, error, instrument, trace, warn};
+
+/// Maximum number of datagrams kept for a session while waiting for the outlet to answer
+const MAX_PENDING_DATAGRAMS: usize = 64;
+
+/// Sessions of a UDP inlet, indexed by the address of the local UDP client
+type Sessions = Arc<Mutex<HashMap<SocketAddr, InletSession>>>;
+
+struct InletSession {
+    internal_address: Address,
+    last_activity: Instant,
+}
+
+/// A UDP Portal Inlet processor
+///
+/// It reads the datagrams received on the inlet socket and dispatches them to one
+/// [`UdpInletSessionWorker`] per source address. Sessions without traffic are closed
+/// after the idle timeout of the [`UdpInletOptions`].
+pub(crate) struct UdpInletProcessor {
+    socket: Arc<UdpSocket>,
+    outlet_route: Route,
+    options: UdpInletOptions,
+    sessions: Sessions,
+    buffer: Vec<u8>,
+    last_idle_check: Instant,
+}
+
+impl UdpInletProcessor {
+    /// Start a new `UdpInletProcessor`
+    #[instrument(skip_all, name = "UdpInletProcessor::start")]
+    pub(crate) async fn start(
+        ctx: &Context,
+        outlet_route: Route,
+        addr: SocketAddr,
+        options: UdpInletOptions,
+    ) -> Result<UdpInlet> {
+        let processor_address = Address::random_tagged("UdpInletProcessor");
+
+        debug!("Binding UdpInletProcessor to {}", addr);
+        let socket = match UdpSocket::bind(addr).await {
+            Ok(socket) => socket,
+            Err(err) => {
+                error!(%addr, %err, "could not bind to address");
+                return Err(TransportError::from(err))?;
+            }
+        };
+        let socket_address = socket.local_addr().map_err(TransportError::from)?;
+
+        let processor = Self {
+            socket: Arc::new(socket),
+            outlet_route,
+            options,
+            sessions: Default::default(),
+            buffer: vec![0; MAX_DATAGRAM_SIZE],
+            last_idle_check: Instant::now(),
+        };
+
+        // The processor only sends messages to the internal address of its sessions,
+        // which only accept messages coming from this processor
+        ProcessorBuilder::new(processor)
+            .with_address(processor_address.clone())
+            .with_outgoing_access_control(AllowAll)
+            .start(ctx)
+            .await?;
+
+        Ok(UdpInlet::new(socket_address, processor_address))
+    }
+
+    /// Return the address of the session worker for a given client, starting it if necessary.
+    /// `None` is returned when the maximum number of sessions is reached.
+    async fn session(&self, ctx: &Context, peer: SocketAddr) -> Result<Option<Address>> {
+        {
+            let mut sessions = self.sessions.lock().unwrap();
+            if let Some(session) = sessions.get_mut(&peer) {
+                session.last_activity = Instant::now();
+                return Ok(Some(session.internal_address.clone()));
+            }
+            if sessions.len() >= self.options.max_sessions {
+                return Ok(None);
+            }
+        }
+
+        let addresses = PortalAddresses::generate("inlet");
+        UdpInletOptions::setup_flow_control(
+            ctx.flow_controls(),
+            &addresses.remote,
+            self.outlet_route.next()?,
+        );
+        self.sessions.lock().unwrap().insert(
+            peer,
+            InletSession {
+                internal_address: addresses.internal.clone(),
+                last_activity: Instant::now(),
+            },
+        );
+
+        UdpInletSessionWorker::start(
+            ctx,
+            self.socket.clone(),
+            peer,
+            self.outlet_route.clone(),
+            addresses.clone(),
+            ctx.address(),
+            self.sessions.clone(),
+            &self.options,
+        )
+        .await?;
+
+        debug!(%peer, "Created UDP inlet session at {}", addresses.remote);
+        Ok(Some(addresses.internal))
+    }
+
+    /// Stop the sessions which did not have any traffic for longer than the idle timeout
+    async fn stop_idle_sessions(&mut self, ctx: &Context) {
+        let idle_timeout = self.options.idle_timeout;
+        let idle: Vec<(SocketAddr, Address)> = {
+            let mut sessions = self.sessions.lock().unwrap();
+            let idle: Vec<_> = sessions
+                .iter()
+                .filter(|(_, s)| s.last_activity.elapsed() >= idle_timeout)
+                .map(|(peer, s)| (*peer, s.internal_address.clone()))
+                .collect();
+            for (peer, _) in idle.iter() {
+                sessions.remove(peer);
+            }
+            idle
+        };
+
+        for (peer, address) in idle {
+            debug!(%peer, "Closing idle UDP inlet session");
+            let _ = ctx.stop_worker(address).await;
+        }
+        self.last_idle_check = Instant::now();
+    }
+
+    /// Interval between two checks of idle sessions
+    fn idle_check_interval(&self) -> Duration {
+        (self.options.idle_timeout / 2).max(Duration::from_millis(100))
+    }
+}
+
+#[async_trait]
+impl Processor for UdpInletProcessor {
+    type Context = Context;
+
+    #[instrument(skip_all, name = "UdpInletProcessor::shutdown")]
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        let addresses: Vec<Address> = self
+            .sessions
+            .lock()
+            .unwrap()
+            .drain()
+            .map(|(_, s)| s.internal_address)
+            .collect();
+        for address in addresses {
+            let _ = ctx.stop_worker(address).await;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "UdpInletProcessor::process")]
+    async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        let interval = self.idle_check_interval();
+        if self.last_idle_check.elapsed() >= interval {
+            self.stop_idle_sessions(ctx).await;
+        }
+
+        let (len, peer) =
+            match tokio::time::timeout(interval, self.socket.recv_from(&mut self.buffer)).await {
+                Ok(Ok(received)) => received,
+                Ok(Err(err)) => {
+                    // Errors on UDP sockets are not fatal, for example, an ICMP message
+                    // received after sending a datagram to a closed port
+                    warn!(%err, "Failed to receive a datagram on a UDP inlet");
+                    return Ok(true);
+                }
+                // no datagram received, check again the idle sessions
+                Err(_) => return Ok(true),
+            };
+
+        trace!(%peer, "Received a datagram of {len} bytes on a UDP inlet");
+        let Some(session) = self.session(ctx, peer).await? else {
+            warn!(%peer, "Dropping a datagram, the UDP inlet has too many sessions");
+            return Ok(true);
+        };
+        let datagram = UdpPortalMessage::Datagram(self.buffer[..len].to_vec());
+        if let Err(err) = ctx.send(session, datagram).await {
+            warn!(%peer, %err, "Failed to forward a datagram to a UDP inlet session");
+        }
+
+        Ok(true)
+    }
+}
+
+/// A UDP Portal Inlet session worker
+///
+/// This worker carries the datagrams of a single UDP client to the outlet and writes back
+/// the datagrams sent by the outlet to that client.
+pub(crate) struct UdpInletSessionWorker {
+    socket: Arc<UdpSocket>,
+    peer: SocketAddr,
+    outlet_route: Route,
+    addresses: PortalAddresses,
+    sessions: Sessions,
+    remote_route: Option<Route>,
+    pending: VecDeque<Vec<u8>>,
+}
+
+impl UdpInletSessionWorker {
+    #[allow(clippy::too_many_arguments)]
+    async fn start(
+        ctx: &Context,
+        socket: Arc<UdpSocket>,
+        peer: SocketAddr,
+        outlet_route: Route,
+        addresses: PortalAddresses,
+        processor_address: Address,
+        sessions: Sessions,
+        options: &UdpInletOptions,
+    ) -> Result<()> {
+        let worker = Self {
+            socket,
+            peer,
+            outlet_route,
+            addresses: addresses.clone(),
+            sessions,
+            remote_route: None,
+            pending: VecDeque::new(),
+        };
+
+        let internal_mailbox = Mailbox::new(
+            addresses.internal,
+            Arc::new(AllowSourceAddress(processor_address)),
+            Arc::new(DenyAll),
+        );
+
+        let remote_mailbox = Mailbox::new(
+            addresses.remote,
+            options.incoming_access_control.clone(),
+            options.outgoing_access_control.clone(),
+        );
+
+        WorkerBuilder::new(worker)
+            .with_mailboxes(Mailboxes::new(internal_mailbox, vec![remote_mailbox]))
+            .start(ctx)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn send_to_outlet(
+        &self,
+        ctx: &Context,
+        route: Route,
+        msg: UdpPortalMessage,
+    ) -> Result<()> {
+        ctx.send_from_address(route, msg, self.addresses.remote.clone())
+            .await
+    }
+}
+
+#[async_trait]
+impl Worker for UdpInletSessionWorker {
+    type Context = Context;
+    type Message = UdpPortalMessage;
+
+    #[instrument(skip_all, name = "UdpInletSessionWorker::initialize")]
+    async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.send_to_outlet(ctx, self.outlet_route.clone(), UdpPortalMessage::Open)
+            .await
+    }
+
+    #[instrument(skip_all, name = "UdpInletSessionWorker::shutdown")]
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        {
+            let mut sessions = self.sessions.lock().unwrap();
+            if sessions
+                .get(&self.peer)
+                .map(|s| s.internal_address == self.addresses.internal)
+                .unwrap_or(false)
+            {
+                sessions.remove(&self.peer);
+            }
+        }
+
+        if let Some(remote_route) = self.remote_route.take() {
+            let _ = self
+                .send_to_outlet(ctx, remote_route, UdpPortalMessage::Close)
+                .await;
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "UdpInletSessionWorker::handle_message")]
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Self::Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        let from_remote = msg.msg_addr() == self.addresses.remote;
+        let return_route = msg.return_route();
+        let msg = msg.into_body()?;
+
+        if !from_remote {
+            // a datagram read from the local socket
+            if let UdpPortalMessage::Datagram(datagram) = msg {
+                match &self.remote_route {
+                    Some(remote_route) => {
+                        self.send_to_outlet(
+                            ctx,
+                            remote_route.clone(),
+                            UdpPortalMessage::Datagram(datagram),
+                        )
+                        .await?
+                    }
+                    None => {
+                        if self.pending.len() == MAX_PENDING_DATAGRAMS {
+                            self.pending.pop_front();
+                        }
+                        self.pending.push_back(datagram);
+                    }
+                }
+            }
+            return Ok(());
+        }
+
+        // once connected, only the outlet session which answered can use this session
+        if let Some(remote_route) = &self.remote_route {
+            if &return_route != remote_route {
+                warn!(peer = %self.peer, "Ignoring a message which was not sent by the outlet session of a UDP inlet session");
+                return Ok(());
+            }
+        }
+
+        match msg {
+            UdpPortalMessage::Opened if self.remote_route.is_some() => {
+                return Err(TransportError::Protocol)?
+            }
+            UdpPortalMessage::Opened => {
+                debug!(peer = %self.peer, "UDP inlet session is connected to {return_route}");
+                for datagram in self.pending.drain(..).collect::<Vec<_>>() {
+                    self.send_to_outlet(
+                        ctx,
+                        return_route.clone(),
+                        UdpPortalMessage::Datagram(datagram),
+                    )
+                    .await?;
+                }
+                self.remote_route = Some(return_route);
+            }
+            UdpPortalMessage::Datagram(datagram) => {
+                if let Some(session) = self.sessions.lock().unwrap().get_mut(&self.peer) {
+                    session.last_activity = Instant::now();
+                }
+                if let Err(err) = self.socket.send_to(&datagram, self.peer).await {
+                    warn!(peer = %self.peer, %err, "Failed to send a datagram to a UDP inlet client");
+                }
+            }
+            UdpPortalMessage::Close => {
+                debug!(peer = %self.peer, "UDP inlet session closed by the outlet");
+                self.remote_route = None;
+                ctx.stop_worker(self.addresses.internal.clone()).await?;
+            }
+            UdpPortalMessage::Open => return Err(TransportError::Protocol)?,
+        }
+
+        Ok(())
+    }
+}
+
+/// Result of [`UdpTransport::create_inlet`](crate::UdpTransport::create_inlet) call.
+#[derive(Clone, Debug)]
+pub struct UdpInlet {
+    socket_address: SocketAddr,
+    processor_address: Address,
+}
+
+impl fmt::Display for UdpInlet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Socket: {}. Processor address: {}",
+            self.socket_address, self.processor_address
+        )
+    }
+}
+
+impl UdpInlet {
+    /// Constructor
+    pub(crate) fn new(socket_address: SocketAddr, processor_address: Address) -> Self {
+        Self {
+            socket_address,
+            processor_address,
+        }
+    }
+
+    /// Socket Address
+    pub fn socket_address(&self) -> SocketAddr {
+        self.socket_address
+    }
+
+    /// Processor address
+    pub fn processor_address(&self) -> &Address {
+        &self.processor_address
+    }
+}
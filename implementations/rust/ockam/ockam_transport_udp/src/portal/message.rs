@@ -0,0 +1,29 @@
+use minicbor::{CborLen, Decode, Encode};
+use ockam_core::{Decodable, Encodable, Message, Result};
+
+/// Message exchanged between the two sides of a UDP portal session
+///
+/// A session is established with `Open` -> `Opened`, then datagrams are carried in both
+/// directions until one side closes the session.
+#[derive(Encode, Decode, CborLen, Debug, Clone, PartialEq, Eq)]
+#[rustfmt::skip]
+pub(crate) enum UdpPortalMessage {
+    #[n(0)] Open,
+    #[n(1)] Opened,
+    #[n(2)] Datagram(#[cbor(n(0), with = "minicbor::bytes")] Vec<u8>),
+    #[n(3)] Close,
+}
+
+impl Encodable for UdpPortalMessage {
+    fn encode(self) -> Result<Vec<u8>> {
+        ockam_core::cbor_encode_preallocate(self)
+    }
+}
+
+impl Decodable for UdpPortalMessage {
+    fn decode(data: &[u8]) -> Result<Self> {
+        Ok(minicbor::decode(data)?)
+    }
+}
+
+impl Message for UdpPortalMessage {}
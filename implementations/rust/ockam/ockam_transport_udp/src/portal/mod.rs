@@ -0,0 +1,15 @@
+mod addresses;
+mod inlet;
+mod message;
+mod options;
+mod outlet;
+
+pub(crate) use addresses::*;
+pub use inlet::UdpInlet;
+pub(crate) use inlet::UdpInletProcessor;
+pub(crate) use message::*;
+pub use options::*;
+pub(crate) use outlet::*;
+
+/// Maximum size of a datagram read from a UDP portal socket
+pub(crate) const MAX_DATAGRAM_SIZE: usize = 65_535;
@@ -0,0 +1,205 @@
+use core::time::Duration;
+use ockam_core::compat::sync::Arc;
+use ockam_core::flow_control::{FlowControlId, FlowControls};
+use ockam_core::{Address, AllowAll, IncomingAccessControl, OutgoingAccessControl};
+
+/// Default duration after which a UDP portal session without any traffic is closed
+pub const DEFAULT_UDP_PORTAL_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Default maximum number of sessions, one per client address, opened at the same time by a UDP inlet
+pub const DEFAULT_UDP_INLET_MAX_SESSIONS: usize = 1024;
+
+/// Trust Options for a UDP Inlet
+#[derive(Clone, Debug)]
+pub struct UdpInletOptions {
+    pub(crate) incoming_access_control: Arc<dyn IncomingAccessControl>,
+    pub(crate) outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    pub(crate) idle_timeout: Duration,
+    pub(crate) max_sessions: usize,
+}
+
+impl UdpInletOptions {
+    /// Default constructor without Incoming Access Control
+    pub fn new() -> Self {
+        Self {
+            incoming_access_control: Arc::new(AllowAll),
+            outgoing_access_control: Arc::new(AllowAll),
+            idle_timeout: DEFAULT_UDP_PORTAL_IDLE_TIMEOUT,
+            max_sessions: DEFAULT_UDP_INLET_MAX_SESSIONS,
+        }
+    }
+
+    /// Set Incoming Access Control
+    pub fn with_incoming_access_control_impl(
+        mut self,
+        access_control: impl IncomingAccessControl,
+    ) -> Self {
+        self.incoming_access_control = Arc::new(access_control);
+        self
+    }
+
+    /// Set Incoming Access Control
+    pub fn with_incoming_access_control(
+        mut self,
+        access_control: Arc<dyn IncomingAccessControl>,
+    ) -> Self {
+        self.incoming_access_control = access_control;
+        self
+    }
+
+    /// Set Outgoing Access Control
+    pub fn with_outgoing_access_control_impl(
+        mut self,
+        access_control: impl OutgoingAccessControl,
+    ) -> Self {
+        self.outgoing_access_control = Arc::new(access_control);
+        self
+    }
+
+    /// Set Outgoing Access Control
+    pub fn with_outgoing_access_control(
+        mut self,
+        access_control: Arc<dyn OutgoingAccessControl>,
+    ) -> Self {
+        self.outgoing_access_control = access_control;
+        self
+    }
+
+    /// Set the duration after which a session without any traffic is closed.
+    /// A session is created for each source address sending datagrams to the inlet
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Set the maximum number of sessions opened at the same time.
+    /// The datagrams of new clients are dropped while this limit is reached
+    pub fn with_max_sessions(mut self, max_sessions: usize) -> Self {
+        self.max_sessions = max_sessions;
+        self
+    }
+
+    pub(crate) fn setup_flow_control(
+        flow_controls: &FlowControls,
+        address: &Address,
+        next: &Address,
+    ) {
+        if let Some(flow_control_id) = flow_controls
+            .find_flow_control_with_producer_address(next)
+            .map(|x| x.flow_control_id().clone())
+        {
+            // Allow a sender with corresponding flow_control_id send messages to this address
+            flow_controls.add_consumer(address.clone(), &flow_control_id);
+        }
+    }
+}
+
+impl Default for UdpInletOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Trust Options for a UDP Outlet
+#[derive(Clone, Debug)]
+pub struct UdpOutletOptions {
+    pub(crate) consumer: Vec<FlowControlId>,
+    pub(crate) incoming_access_control: Arc<dyn IncomingAccessControl>,
+    pub(crate) outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    pub(crate) idle_timeout: Duration,
+}
+
+impl UdpOutletOptions {
+    /// Default constructor without Incoming Access Control
+    pub fn new() -> Self {
+        Self {
+            consumer: vec![],
+            incoming_access_control: Arc::new(AllowAll),
+            outgoing_access_control: Arc::new(AllowAll),
+            idle_timeout: DEFAULT_UDP_PORTAL_IDLE_TIMEOUT,
+        }
+    }
+
+    /// Set Incoming Access Control
+    pub fn with_incoming_access_control_impl(
+        mut self,
+        access_control: impl IncomingAccessControl,
+    ) -> Self {
+        self.incoming_access_control = Arc::new(access_control);
+        self
+    }
+
+    /// Set Incoming Access Control
+    pub fn with_incoming_access_control(
+        mut self,
+        access_control: Arc<dyn IncomingAccessControl>,
+    ) -> Self {
+        self.incoming_access_control = access_control;
+        self
+    }
+
+    /// Set Outgoing Access Control
+    pub fn with_outgoing_access_control_impl(
+        mut self,
+        access_control: impl OutgoingAccessControl,
+    ) -> Self {
+        self.outgoing_access_control = Arc::new(access_control);
+        self
+    }
+
+    /// Set Outgoing Access Control
+    pub fn with_outgoing_access_control(
+        mut self,
+        access_control: Arc<dyn OutgoingAccessControl>,
+    ) -> Self {
+        self.outgoing_access_control = access_control;
+        self
+    }
+
+    /// Set the duration after which a session without any traffic is closed
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Mark that this Outlet listener is a Consumer for to the given [`FlowControlId`]
+    /// Also, in this case spawned sessions will be marked as Consumers with [`FlowControlId`]
+    /// of the message that was used to create the session
+    pub fn as_consumer(mut self, id: &FlowControlId) -> Self {
+        self.consumer.push(id.clone());
+
+        self
+    }
+
+    pub(crate) fn setup_flow_control_for_outlet_listener(
+        &self,
+        flow_controls: &FlowControls,
+        address: &Address,
+    ) {
+        for id in &self.consumer {
+            flow_controls.add_consumer(address.clone(), id);
+        }
+    }
+
+    pub(crate) fn setup_flow_control_for_session(
+        flow_controls: &FlowControls,
+        address: &Address,
+        src_addr: &Address,
+    ) {
+        // Check if the Worker that send us this message is a Producer
+        // If yes - the session will be added to that flow control to be able to receive further
+        // messages from that Producer
+        if let Some(producer_flow_control_id) = flow_controls
+            .get_flow_control_with_producer(src_addr)
+            .map(|x| x.flow_control_id().clone())
+        {
+            flow_controls.add_consumer(address.clone(), &producer_flow_control_id);
+        }
+    }
+}
+
+impl Default for UdpOutletOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
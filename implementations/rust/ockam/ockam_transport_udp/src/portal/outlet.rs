@@ -0,0 +1,277 @@
+use crate::portal::{PortalAddresses, UdpOutletOptions, UdpPortalMessage, MAX_DATAGRAM_SIZE};
+use core::time::Duration;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::{
+    async_trait, Address, AllowOnwardAddress, AllowSourceAddress, DenyAll, Mailbox, Mailboxes,
+    Processor, Result, Route, Routed, Worker,
+};
+use ockam_node::compat::asynchronous::resolve_peer;
+use ockam_node::{Context, ProcessorBuilder, WorkerBuilder};
+use ockam_transport_core::{HostnamePort, TransportError};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use tokio::net::UdpSocket;
+use tokio::time::Instant;
+use tracing::{debug, instrument, trace, warn};
+
+/// A UDP Portal Outlet listen worker
+///
+/// UDP Portal Outlet listen workers are created by `UdpTransport`
+/// after a call is made to
+/// [`UdpTransport::create_outlet`](crate::UdpTransport::create_outlet).
+/// A session is started for each inlet session opening a connection.
+pub(crate) struct UdpOutletListenWorker {
+    peer: HostnamePort,
+    options: UdpOutletOptions,
+}
+
+impl UdpOutletListenWorker {
+    #[instrument(skip_all, name = "UdpOutletListenWorker::start")]
+    pub(crate) async fn start(
+        ctx: &Context,
+        address: Address,
+        peer: HostnamePort,
+        options: UdpOutletOptions,
+    ) -> Result<()> {
+        let access_control = options.incoming_access_control.clone();
+
+        options.setup_flow_control_for_outlet_listener(ctx.flow_controls(), &address);
+
+        WorkerBuilder::new(Self { peer, options })
+            .with_address(address)
+            .with_incoming_access_control_arc(access_control)
+            .with_outgoing_access_control(DenyAll)
+            .start(ctx)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Worker for UdpOutletListenWorker {
+    type Context = Context;
+    type Message = UdpPortalMessage;
+
+    #[instrument(skip_all, name = "UdpOutletListenWorker::handle_message")]
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Self::Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        let return_route = msg.return_route();
+        let src_addr = msg.src_addr();
+
+        if !matches!(msg.into_body()?, UdpPortalMessage::Open) {
+            return Err(TransportError::Protocol)?;
+        }
+
+        let peer = resolve_peer(self.peer.to_string()).await?;
+        let bind_address = if peer.is_ipv4() {
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0)
+        } else {
+            SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0)
+        };
+        let socket = UdpSocket::bind(bind_address)
+            .await
+            .map_err(TransportError::from)?;
+        socket.connect(peer).await.map_err(TransportError::from)?;
+
+        let addresses = PortalAddresses::generate("outlet");
+        UdpOutletOptions::setup_flow_control_for_session(
+            ctx.flow_controls(),
+            &addresses.remote,
+            &src_addr,
+        );
+
+        UdpOutletSessionWorker::start(
+            ctx,
+            Arc::new(socket),
+            return_route,
+            addresses.clone(),
+            &self.options,
+        )
+        .await?;
+
+        debug!(
+            "Created UDP outlet session to {} at {}",
+            peer, addresses.remote
+        );
+        Ok(())
+    }
+}
+
+/// A UDP Portal Outlet session worker
+///
+/// This worker writes the datagrams sent by an inlet session to the outlet peer and sends back
+/// the datagrams received from the peer, which are read by a [`UdpOutletSessionReceiver`].
+pub(crate) struct UdpOutletSessionWorker {
+    socket: Arc<UdpSocket>,
+    remote_route: Option<Route>,
+    addresses: PortalAddresses,
+    receiver_address: Address,
+    last_activity: Arc<Mutex<Instant>>,
+    idle_timeout: Duration,
+}
+
+impl UdpOutletSessionWorker {
+    async fn start(
+        ctx: &Context,
+        socket: Arc<UdpSocket>,
+        remote_route: Route,
+        addresses: PortalAddresses,
+        options: &UdpOutletOptions,
+    ) -> Result<()> {
+        let receiver_address = Address::random_tagged("UdpOutletSessionReceiver");
+        let worker = Self {
+            socket,
+            remote_route: Some(remote_route),
+            addresses: addresses.clone(),
+            receiver_address: receiver_address.clone(),
+            last_activity: Arc::new(Mutex::new(Instant::now())),
+            idle_timeout: options.idle_timeout,
+        };
+
+        let internal_mailbox = Mailbox::new(
+            addresses.internal,
+            Arc::new(AllowSourceAddress(receiver_address)),
+            Arc::new(DenyAll),
+        );
+
+        let remote_mailbox = Mailbox::new(
+            addresses.remote,
+            options.incoming_access_control.clone(),
+            options.outgoing_access_control.clone(),
+        );
+
+        WorkerBuilder::new(worker)
+            .with_mailboxes(Mailboxes::new(internal_mailbox, vec![remote_mailbox]))
+            .start(ctx)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn send_to_inlet(&self, ctx: &Context, msg: UdpPortalMessage) -> Result<()> {
+        if let Some(remote_route) = &self.remote_route {
+            ctx.send_from_address(remote_route.clone(), msg, self.addresses.remote.clone())
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Worker for UdpOutletSessionWorker {
+    type Context = Context;
+    type Message = UdpPortalMessage;
+
+    #[instrument(skip_all, name = "UdpOutletSessionWorker::initialize")]
+    async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        let receiver = UdpOutletSessionReceiver {
+            socket: self.socket.clone(),
+            sender_address: self.addresses.internal.clone(),
+            last_activity: self.last_activity.clone(),
+            idle_timeout: self.idle_timeout,
+            buffer: vec![0; MAX_DATAGRAM_SIZE],
+        };
+        ProcessorBuilder::new(receiver)
+            .with_address(self.receiver_address.clone())
+            .with_outgoing_access_control(AllowOnwardAddress(self.addresses.internal.clone()))
+            .start(ctx)
+            .await?;
+
+        self.send_to_inlet(ctx, UdpPortalMessage::Opened).await
+    }
+
+    #[instrument(skip_all, name = "UdpOutletSessionWorker::shutdown")]
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        let _ = ctx.stop_processor(self.receiver_address.clone()).await;
+        let _ = self.send_to_inlet(ctx, UdpPortalMessage::Close).await;
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "UdpOutletSessionWorker::handle_message")]
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Self::Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        let from_remote = msg.msg_addr() == self.addresses.remote;
+        if from_remote && self.remote_route.as_ref() != Some(&msg.return_route()) {
+            warn!("Ignoring a message which was not sent by the inlet session of a UDP outlet session");
+            return Ok(());
+        }
+        let msg = msg.into_body()?;
+
+        match (from_remote, msg) {
+            // a datagram sent by the inlet
+            (true, UdpPortalMessage::Datagram(datagram)) => {
+                *self.last_activity.lock().unwrap() = Instant::now();
+                if let Err(err) = self.socket.send(&datagram).await {
+                    warn!(%err, "Failed to send a datagram from a UDP outlet");
+                }
+            }
+            (true, UdpPortalMessage::Close) => {
+                debug!("UDP outlet session closed by the inlet");
+                self.remote_route = None;
+                ctx.stop_worker(self.addresses.internal.clone()).await?;
+            }
+            // a datagram read from the outlet socket
+            (false, UdpPortalMessage::Datagram(datagram)) => {
+                self.send_to_inlet(ctx, UdpPortalMessage::Datagram(datagram))
+                    .await?
+            }
+            // the receiver stopped because the session was idle
+            (false, UdpPortalMessage::Close) => {
+                debug!("Closing idle UDP outlet session");
+                ctx.stop_worker(self.addresses.internal.clone()).await?;
+            }
+            _ => return Err(TransportError::Protocol)?,
+        }
+
+        Ok(())
+    }
+}
+
+/// Processor reading the datagrams sent by the peer of a UDP outlet session
+pub(crate) struct UdpOutletSessionReceiver {
+    socket: Arc<UdpSocket>,
+    sender_address: Address,
+    last_activity: Arc<Mutex<Instant>>,
+    idle_timeout: Duration,
+    buffer: Vec<u8>,
+}
+
+#[async_trait]
+impl Processor for UdpOutletSessionReceiver {
+    type Context = Context;
+
+    #[instrument(skip_all, name = "UdpOutletSessionReceiver::process")]
+    async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        let received =
+            tokio::time::timeout(self.idle_timeout, self.socket.recv(&mut self.buffer)).await;
+
+        let message = match received {
+            Ok(Ok(len)) => {
+                trace!("Received a datagram of {len} bytes on a UDP outlet");
+                *self.last_activity.lock().unwrap() = Instant::now();
+                UdpPortalMessage::Datagram(self.buffer[..len].to_vec())
+            }
+            Ok(Err(err)) => {
+                // for example, the peer port is not open (yet)
+                warn!(%err, "Failed to receive a datagram on a UDP outlet");
+                return Ok(true);
+            }
+            Err(_) => {
+                if self.last_activity.lock().unwrap().elapsed() < self.idle_timeout {
+                    return Ok(true);
+                }
+                UdpPortalMessage::Close
+            }
+        };
+
+        let is_closing = message == UdpPortalMessage::Close;
+        ctx.send(self.sender_address.clone(), message).await?;
+        Ok(!is_closing)
+    }
+}
@@ -1,5 +1,6 @@
 mod bind;
 mod lifecycle;
+mod portals;
 mod puncture;
 
 pub use bind::*;
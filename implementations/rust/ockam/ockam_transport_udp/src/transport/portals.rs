@@ -0,0 +1,73 @@
+use crate::portal::{UdpInlet, UdpInletProcessor, UdpOutletListenWorker};
+use crate::{UdpInletOptions, UdpOutletOptions, UdpTransport};
+use core::fmt::Debug;
+use ockam_core::{Address, Result, Route};
+use ockam_transport_core::{parse_socket_addr, HostnamePort};
+use tracing::instrument;
+
+impl UdpTransport {
+    /// Create a UDP Inlet that listens on bind_addr and sends the received datagrams to an
+    /// Outlet using outlet_route. A session is created for each source address, so that the
+    /// datagrams sent back by the Outlet are returned to the client which initiated the session.
+    /// Pair of corresponding Inlet and Outlet is called Portal.
+    ///
+    /// ```rust
+    /// use ockam_transport_udp::{UdpInletOptions, UdpTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::{Result, route};
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let route_path = route!["outlet"];
+    ///
+    /// let udp = UdpTransport::create(&ctx).await?;
+    /// let inlet = udp.create_inlet("127.0.0.1:5353", route_path, UdpInletOptions::new()).await?;
+    /// # udp.stop_inlet(inlet.processor_address().clone()).await?;
+    /// # Ok(()) }
+    /// ```
+    #[instrument(skip(self), fields(address = ? bind_addr.clone().into(), outlet_route = ? outlet_route.clone()))]
+    pub async fn create_inlet(
+        &self,
+        bind_addr: impl Into<String> + Clone + Debug,
+        outlet_route: impl Into<Route> + Clone + Debug,
+        options: UdpInletOptions,
+    ) -> Result<UdpInlet> {
+        let socket_address = parse_socket_addr(&bind_addr.into())?;
+        UdpInletProcessor::start(self.ctx(), outlet_route.into(), socket_address, options).await
+    }
+
+    /// Stop the inlet with the given processor address, and all its sessions
+    #[instrument(skip(self), fields(address = ? addr.clone().into()))]
+    pub async fn stop_inlet(&self, addr: impl Into<Address> + Clone + Debug) -> Result<()> {
+        self.ctx().stop_processor(addr).await
+    }
+
+    /// Create a UDP Outlet Listener at address. For each session opened by an Inlet, the Outlet
+    /// sends the datagrams to peer from a new UDP socket, and sends back the datagrams received
+    /// on that socket to the Inlet.
+    ///
+    /// ```rust
+    /// use ockam_transport_udp::{UdpOutletOptions, UdpTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # use ockam_transport_core::HostnamePort;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let udp = UdpTransport::create(&ctx).await?;
+    /// udp.create_outlet("outlet", HostnamePort::new("localhost", 53), UdpOutletOptions::new()).await?;
+    /// # udp.stop_outlet("outlet").await?;
+    /// # Ok(()) }
+    /// ```
+    #[instrument(skip(self), fields(address = ? address.clone().into(), peer = peer.clone().to_string()))]
+    pub async fn create_outlet(
+        &self,
+        address: impl Into<Address> + Clone + Debug,
+        peer: HostnamePort,
+        options: UdpOutletOptions,
+    ) -> Result<()> {
+        UdpOutletListenWorker::start(self.ctx(), address.into(), peer, options).await
+    }
+
+    /// Stop the outlet listener at addr. Existing sessions are closed when they become idle
+    #[instrument(skip(self), fields(address = % addr.clone().into()))]
+    pub async fn stop_outlet(&self, addr: impl Into<Address> + Clone + Debug) -> Result<()> {
+        self.ctx().stop_worker(addr).await
+    }
+}
@@ -0,0 +1,173 @@
+use ockam_core::{route, Result};
+use ockam_node::Context;
+use ockam_transport_core::HostnamePort;
+use ockam_transport_udp::{UdpInletOptions, UdpOutletOptions, UdpTransport};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio::time::timeout;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Datagrams sent to an inlet are delivered to the outlet peer, and its replies are
+/// delivered to the client which sent the datagrams
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn portal__datagrams__are_forwarded_in_both_directions(ctx: &mut Context) -> Result<()> {
+    let udp = UdpTransport::create(ctx).await?;
+
+    // a server echoing the datagrams it receives
+    let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let server_address = server.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut buffer = [0; 1024];
+        while let Ok((len, peer)) = server.recv_from(&mut buffer).await {
+            let _ = server.send_to(&buffer[..len], peer).await;
+        }
+    });
+
+    udp.create_outlet(
+        "outlet",
+        HostnamePort::from(server_address),
+        UdpOutletOptions::new(),
+    )
+    .await?;
+    let inlet = udp
+        .create_inlet("127.0.0.1:0", route!["outlet"], UdpInletOptions::new())
+        .await?;
+
+    // two clients get their own answers
+    let client1 = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let client2 = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    client1.connect(inlet.socket_address()).await.unwrap();
+    client2.connect(inlet.socket_address()).await.unwrap();
+
+    let mut buffer = [0; 1024];
+    for i in 0..3 {
+        client1.send(format!("hello {i}").as_bytes()).await.unwrap();
+        let len = timeout(TIMEOUT, client1.recv(&mut buffer))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&buffer[..len], format!("hello {i}").as_bytes());
+    }
+
+    client2.send(b"bonjour").await.unwrap();
+    let len = timeout(TIMEOUT, client2.recv(&mut buffer))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(&buffer[..len], b"bonjour");
+
+    udp.stop_inlet(inlet.processor_address().clone()).await?;
+    udp.stop_outlet("outlet").await?;
+    Ok(())
+}
+
+/// Idle sessions are closed and a new session is created for the next datagrams
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn portal__idle_sessions__are_closed(ctx: &mut Context) -> Result<()> {
+    let udp = UdpTransport::create(ctx).await?;
+
+    let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let server_address = server.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut buffer = [0; 1024];
+        while let Ok((len, peer)) = server.recv_from(&mut buffer).await {
+            let _ = server.send_to(&buffer[..len], peer).await;
+        }
+    });
+
+    let idle_timeout = Duration::from_millis(300);
+    udp.create_outlet(
+        "outlet",
+        HostnamePort::from(server_address),
+        UdpOutletOptions::new().with_idle_timeout(idle_timeout),
+    )
+    .await?;
+    let inlet = udp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            UdpInletOptions::new().with_idle_timeout(idle_timeout),
+        )
+        .await?;
+
+    let initial_workers = ctx.list_workers().await?.len();
+
+    let client = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    client.connect(inlet.socket_address()).await.unwrap();
+    let mut buffer = [0; 1024];
+    client.send(b"hello").await.unwrap();
+    timeout(TIMEOUT, client.recv(&mut buffer))
+        .await
+        .unwrap()
+        .unwrap();
+
+    // one inlet session, one outlet session and its receiver
+    assert_eq!(ctx.list_workers().await?.len(), initial_workers + 3);
+
+    ctx.sleep(idle_timeout * 4).await;
+    assert_eq!(ctx.list_workers().await?.len(), initial_workers);
+
+    // the client can still use the inlet
+    client.send(b"hello again").await.unwrap();
+    let len = timeout(TIMEOUT, client.recv(&mut buffer))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(&buffer[..len], b"hello again");
+
+    Ok(())
+}
+
+/// The datagrams of new clients are dropped when the inlet has too many sessions
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn portal__max_sessions__new_clients_are_dropped(ctx: &mut Context) -> Result<()> {
+    let udp = UdpTransport::create(ctx).await?;
+
+    let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let server_address = server.local_addr().unwrap();
+    tokio::spawn(async move {
+        let mut buffer = [0; 1024];
+        while let Ok((len, peer)) = server.recv_from(&mut buffer).await {
+            let _ = server.send_to(&buffer[..len], peer).await;
+        }
+    });
+
+    udp.create_outlet(
+        "outlet",
+        HostnamePort::from(server_address),
+        UdpOutletOptions::new(),
+    )
+    .await?;
+    let inlet = udp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            UdpInletOptions::new().with_max_sessions(1),
+        )
+        .await?;
+
+    let client1 = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    let client2 = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+    client1.connect(inlet.socket_address()).await.unwrap();
+    client2.connect(inlet.socket_address()).await.unwrap();
+
+    let mut buffer = [0; 1024];
+    client1.send(b"hello").await.unwrap();
+    let len = timeout(TIMEOUT, client1.recv(&mut buffer))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(&buffer[..len], b"hello");
+
+    client2.send(b"bonjour").await.unwrap();
+    let received = timeout(Duration::from_millis(500), client2.recv(&mut buffer)).await;
+    assert!(received.is_err(), "the datagram should have been dropped");
+
+    udp.stop_inlet(inlet.processor_address().clone()).await?;
+    udp.stop_outlet("outlet").await?;
+    Ok(())
+}
@@ -27,6 +27,10 @@ pub enum VaultError {
     AeadAesGcmEncrypt,
     /// AES decryption failed
     AeadAesGcmDecrypt,
+    /// ML-KEM encapsulation failed
+    MlKemEncapsulate,
+    /// ML-KEM decapsulation failed
+    MlKemDecapsulate,
     /// HKDF key expansion failed
     HkdfExpandError,
     /// Invalid Sha256 Output length
@@ -52,6 +56,8 @@ impl core::fmt::Display for VaultError {
             Self::InvalidHkdfOutputType => write!(f, "invalid HKDF output type"),
             Self::AeadAesGcmEncrypt => write!(f, "aes encryption failed"),
             Self::AeadAesGcmDecrypt => write!(f, "aes decryption failed"),
+            Self::MlKemEncapsulate => write!(f, "ml-kem encapsulation failed"),
+            Self::MlKemDecapsulate => write!(f, "ml-kem decapsulation failed"),
             Self::HkdfExpandError => write!(f, "hkdf key expansion failed"),
             Self::KeyNotFound => write!(f, "key not found"),
             Self::InvalidSha256Len => write!(f, "invalid sha256 len"),
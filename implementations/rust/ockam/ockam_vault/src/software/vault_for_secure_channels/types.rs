@@ -16,7 +16,8 @@ impl X25519SecretKey {
         Self(key)
     }
 
-    pub(crate) fn key(&self) -> &[u8; X25519_SECRET_KEY_LENGTH] {
+    /// Return the secret key
+    pub fn key(&self) -> &[u8; X25519_SECRET_KEY_LENGTH] {
         &self.0
     }
 }
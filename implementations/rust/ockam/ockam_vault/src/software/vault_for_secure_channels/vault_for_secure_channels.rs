@@ -1,3 +1,5 @@
+use fips203::ml_kem_768;
+use fips203::traits::{Decaps, Encaps, KeyGen, SerDes};
 use sha2::{Digest, Sha256};
 use tracing::instrument;
 
@@ -15,18 +17,25 @@ use crate::storage::SecretsSqlxDatabase;
 
 use crate::{
     AeadSecret, AeadSecretKeyHandle, BufferSecret, HKDFNumberOfOutputs, HandleToSecret, HashOutput,
-    HkdfOutput, SecretBufferHandle, SoftwareVaultForVerifyingSignatures, VaultError,
-    VaultForSecureChannels, X25519PublicKey, X25519SecretKey, X25519SecretKeyHandle,
-    AEAD_SECRET_LENGTH,
+    HkdfOutput, MlKem768Ciphertext, MlKem768EncapsulationKey, MlKem768SecretKeyHandle,
+    SecretBufferHandle, SoftwareVaultForVerifyingSignatures, VaultError, VaultForSecureChannels,
+    X25519PublicKey, X25519SecretKey, X25519SecretKeyHandle, AEAD_SECRET_LENGTH,
 };
 
 use super::make_aes;
 
+/// Ephemeral ML-KEM-768 key pair. The encapsulation key is kept to avoid re-computing it
+struct MlKem768KeyPair {
+    encapsulation_key: MlKem768EncapsulationKey,
+    decapsulation_key: ml_kem_768::DecapsKey,
+}
+
 /// [`SecureChannelVault`] implementation using software
 pub struct SoftwareVaultForSecureChannels {
     ephemeral_buffer_secrets: Arc<RwLock<BTreeMap<SecretBufferHandle, BufferSecret>>>,
     ephemeral_aead_secrets: Arc<RwLock<BTreeMap<AeadSecretKeyHandle, AeadSecret>>>,
     ephemeral_x25519_secrets: Arc<RwLock<BTreeMap<X25519SecretKeyHandle, X25519SecretKey>>>,
+    ephemeral_ml_kem_768_secrets: Arc<RwLock<BTreeMap<MlKem768SecretKeyHandle, MlKem768KeyPair>>>,
     secrets_repository: Arc<dyn SecretsRepository>,
 }
 
@@ -37,6 +46,7 @@ impl SoftwareVaultForSecureChannels {
             ephemeral_buffer_secrets: Default::default(),
             ephemeral_aead_secrets: Default::default(),
             ephemeral_x25519_secrets: Default::default(),
+            ephemeral_ml_kem_768_secrets: Default::default(),
             secrets_repository,
         }
     }
@@ -102,6 +112,11 @@ impl SoftwareVaultForSecureChannels {
         self.ephemeral_x25519_secrets.read().unwrap().len()
     }
 
+    /// Return the total number of ephemeral ML-KEM-768 secrets present in the Vault
+    pub fn number_of_ephemeral_ml_kem_768_secrets(&self) -> usize {
+        self.ephemeral_ml_kem_768_secrets.read().unwrap().len()
+    }
+
     /// Return the total number of ephemeral buffer secrets present in the Vault
     pub fn number_of_ephemeral_buffer_secrets(&self) -> usize {
         self.ephemeral_buffer_secrets.read().unwrap().len()
@@ -222,6 +237,45 @@ impl VaultForSecureChannels for SoftwareVaultForSecureChannels {
         Ok(self.import_buffer_secret_impl(dh))
     }
 
+    async fn ml_kem_768_encapsulate(
+        &self,
+        peer_encapsulation_key: &MlKem768EncapsulationKey,
+    ) -> Result<(MlKem768Ciphertext, SecretBufferHandle)> {
+        let encapsulation_key = ml_kem_768::EncapsKey::try_from_bytes(peer_encapsulation_key.0)
+            .map_err(|_| VaultError::InvalidPublicKey)?;
+        let (shared_secret, ciphertext) = encapsulation_key
+            .try_encaps_with_rng(&mut thread_rng())
+            .map_err(|_| VaultError::MlKemEncapsulate)?;
+
+        let ciphertext = MlKem768Ciphertext(ciphertext.into_bytes());
+        let shared_secret = BufferSecret::new(shared_secret.into_bytes().to_vec());
+
+        Ok((ciphertext, self.import_buffer_secret_impl(shared_secret)))
+    }
+
+    async fn ml_kem_768_decapsulate(
+        &self,
+        secret_key_handle: &MlKem768SecretKeyHandle,
+        ciphertext: &MlKem768Ciphertext,
+    ) -> Result<SecretBufferHandle> {
+        let ciphertext = ml_kem_768::CipherText::try_from_bytes(ciphertext.0)
+            .map_err(|_| VaultError::MlKemDecapsulate)?;
+        let shared_secret = match self
+            .ephemeral_ml_kem_768_secrets
+            .read()
+            .unwrap()
+            .get(secret_key_handle)
+        {
+            Some(key_pair) => key_pair
+                .decapsulation_key
+                .try_decaps(&ciphertext)
+                .map_err(|_| VaultError::MlKemDecapsulate)?,
+            None => return Err(VaultError::KeyNotFound)?,
+        };
+
+        Ok(self.import_buffer_secret_impl(BufferSecret::new(shared_secret.into_bytes().to_vec())))
+    }
+
     async fn hash(&self, data: &[u8]) -> Result<HashOutput> {
         let hash = SoftwareVaultForVerifyingSignatures::compute_sha256(data)?;
 
@@ -379,6 +433,51 @@ impl VaultForSecureChannels for SoftwareVaultForSecureChannels {
         Ok(Self::compute_handle_for_public_key(public_key))
     }
 
+    async fn generate_ephemeral_ml_kem_768_secret_key(&self) -> Result<MlKem768SecretKeyHandle> {
+        let (encapsulation_key, decapsulation_key) =
+            ml_kem_768::KG::try_keygen_with_rng(&mut thread_rng())
+                .map_err(|_| VaultError::MlKemEncapsulate)?;
+        let key_pair = MlKem768KeyPair {
+            encapsulation_key: MlKem768EncapsulationKey(encapsulation_key.into_bytes()),
+            decapsulation_key,
+        };
+        let handle = MlKem768SecretKeyHandle(Self::generate_random_handle());
+
+        self.ephemeral_ml_kem_768_secrets
+            .write()
+            .unwrap()
+            .insert(handle.clone(), key_pair);
+
+        Ok(handle)
+    }
+
+    async fn delete_ephemeral_ml_kem_768_secret_key(
+        &self,
+        secret_key_handle: MlKem768SecretKeyHandle,
+    ) -> Result<bool> {
+        Ok(self
+            .ephemeral_ml_kem_768_secrets
+            .write()
+            .unwrap()
+            .remove(&secret_key_handle)
+            .is_some())
+    }
+
+    async fn get_ml_kem_768_encapsulation_key(
+        &self,
+        secret_key_handle: &MlKem768SecretKeyHandle,
+    ) -> Result<MlKem768EncapsulationKey> {
+        match self
+            .ephemeral_ml_kem_768_secrets
+            .read()
+            .unwrap()
+            .get(secret_key_handle)
+        {
+            Some(key_pair) => Ok(key_pair.encapsulation_key.clone()),
+            None => Err(VaultError::KeyNotFound)?,
+        }
+    }
+
     async fn import_secret_buffer(&self, buffer: Vec<u8>) -> Result<SecretBufferHandle> {
         Ok(self.import_buffer_secret_impl(BufferSecret::new(buffer)))
     }
@@ -1,6 +1,8 @@
+use sqlx::any::AnyArguments;
 use sqlx::encode::IsNull;
 use sqlx::error::BoxDynError;
 use sqlx::postgres::any::AnyArgumentBuffer;
+use sqlx::query::Query;
 use sqlx::*;
 use tracing::debug;
 use zeroize::{Zeroize, ZeroizeOnDrop};
@@ -36,24 +38,18 @@ impl SecretsSqlxDatabase {
     pub async fn create() -> Result<Self> {
         Ok(Self::new(SqlxDatabase::in_memory("secrets").await?))
     }
-}
-
-const ED_DSA_CURVE_25519: &str = "EdDSACurve25519";
-const EC_DSA_SHA256_CURVE_P256: &str = "ECDSASHA256CurveP256";
 
-#[async_trait]
-impl SecretsRepository for SecretsSqlxDatabase {
-    async fn store_signing_secret(
-        &self,
+    /// Return the query storing a signing secret, to execute it in a transaction
+    pub fn store_signing_secret_query(
         handle: &SigningSecretKeyHandle,
         secret: SigningSecret,
-    ) -> Result<()> {
+    ) -> Query<'_, Any, AnyArguments<'_>> {
         let secret_type: String = match handle {
             SigningSecretKeyHandle::EdDSACurve25519(_) => ED_DSA_CURVE_25519.into(),
             SigningSecretKeyHandle::ECDSASHA256CurveP256(_) => EC_DSA_SHA256_CURVE_P256.into(),
         };
 
-        let query = query(
+        query(
             r#"
             INSERT INTO signing_secret (handle, secret_type, secret)
             VALUES ($1, $2, $3)
@@ -62,8 +58,40 @@ impl SecretsRepository for SecretsSqlxDatabase {
         )
         .bind(handle)
         .bind(secret_type)
-        .bind(secret);
-        query.execute(&*self.database.pool).await.void()
+        .bind(secret)
+    }
+
+    /// Return the query storing a X25519 secret, to execute it in a transaction
+    pub fn store_x25519_secret_query(
+        handle: &X25519SecretKeyHandle,
+        secret: X25519SecretKey,
+    ) -> Query<'_, Any, AnyArguments<'_>> {
+        query(
+            r#"
+        INSERT INTO x25519_secret (handle, secret)
+        VALUES ($1, $2)
+        ON CONFLICT (handle)
+        DO UPDATE SET secret = $2"#,
+        )
+        .bind(handle)
+        .bind(secret)
+    }
+}
+
+const ED_DSA_CURVE_25519: &str = "EdDSACurve25519";
+const EC_DSA_SHA256_CURVE_P256: &str = "ECDSASHA256CurveP256";
+
+#[async_trait]
+impl SecretsRepository for SecretsSqlxDatabase {
+    async fn store_signing_secret(
+        &self,
+        handle: &SigningSecretKeyHandle,
+        secret: SigningSecret,
+    ) -> Result<()> {
+        Self::store_signing_secret_query(handle, secret)
+            .execute(&*self.database.pool)
+            .await
+            .void()
     }
 
     async fn delete_signing_secret(&self, handle: &SigningSecretKeyHandle) -> Result<bool> {
@@ -102,16 +130,10 @@ impl SecretsRepository for SecretsSqlxDatabase {
         handle: &X25519SecretKeyHandle,
         secret: X25519SecretKey,
     ) -> Result<()> {
-        let query = query(
-            r#"
-        INSERT INTO x25519_secret (handle, secret)
-        VALUES ($1, $2)
-        ON CONFLICT (handle)
-        DO UPDATE SET secret = $2"#,
-        )
-        .bind(handle)
-        .bind(secret);
-        query.execute(&*self.database.pool).await.void()
+        Self::store_x25519_secret_query(handle, secret)
+            .execute(&*self.database.pool)
+            .await
+            .void()
     }
 
     async fn delete_x25519_secret(&self, handle: &X25519SecretKeyHandle) -> Result<bool> {
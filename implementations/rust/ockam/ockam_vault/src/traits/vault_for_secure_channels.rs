@@ -1,6 +1,6 @@
 use crate::{
-    AeadSecretKeyHandle, HashOutput, HkdfOutput, SecretBufferHandle, X25519PublicKey,
-    X25519SecretKeyHandle,
+    AeadSecretKeyHandle, HashOutput, HkdfOutput, MlKem768Ciphertext, MlKem768EncapsulationKey,
+    MlKem768SecretKeyHandle, SecretBufferHandle, X25519PublicKey, X25519SecretKeyHandle,
 };
 
 use ockam_core::compat::vec::Vec;
@@ -25,6 +25,22 @@ pub trait VaultForSecureChannels: Send + Sync + 'static {
         peer_public_key: &X25519PublicKey,
     ) -> Result<SecretBufferHandle>;
 
+    /// Perform ML-KEM-768 encapsulation of a fresh shared secret for the peer Encapsulation Key.
+    /// Return the Ciphertext to send to the peer and a handle to the shared secret.
+    /// [1]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.203.pdf
+    async fn ml_kem_768_encapsulate(
+        &self,
+        peer_encapsulation_key: &MlKem768EncapsulationKey,
+    ) -> Result<(MlKem768Ciphertext, SecretBufferHandle)>;
+
+    /// Perform ML-KEM-768 decapsulation of a shared secret sent by the peer.
+    /// [1]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.203.pdf
+    async fn ml_kem_768_decapsulate(
+        &self,
+        secret_key_handle: &MlKem768SecretKeyHandle,
+        ciphertext: &MlKem768Ciphertext,
+    ) -> Result<SecretBufferHandle>;
+
     /// Compute Hash.
     /// [1]: http://www.noiseprotocol.org/noise.html#hash-functions
     async fn hash(&self, data: &[u8]) -> Result<HashOutput>;
@@ -95,6 +111,21 @@ pub trait VaultForSecureChannels: Send + Sync + 'static {
         public_key: &X25519PublicKey,
     ) -> Result<X25519SecretKeyHandle>;
 
+    /// Generate a fresh ephemeral (not persisted) ML-KEM-768 Key.
+    async fn generate_ephemeral_ml_kem_768_secret_key(&self) -> Result<MlKem768SecretKeyHandle>;
+
+    /// Delete ephemeral ML-KEM-768 Key.
+    async fn delete_ephemeral_ml_kem_768_secret_key(
+        &self,
+        secret_key_handle: MlKem768SecretKeyHandle,
+    ) -> Result<bool>;
+
+    /// Get [`MlKem768EncapsulationKey`] of the corresponding ML-KEM-768 Secret Key given its Handle.
+    async fn get_ml_kem_768_encapsulation_key(
+        &self,
+        secret_key_handle: &MlKem768SecretKeyHandle,
+    ) -> Result<MlKem768EncapsulationKey>;
+
     /// Import a Secret Buffer.
     async fn import_secret_buffer(&self, buffer: Vec<u8>) -> Result<SecretBufferHandle>;
 
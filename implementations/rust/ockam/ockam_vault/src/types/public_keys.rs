@@ -9,6 +9,12 @@ pub const EDDSA_CURVE25519_PUBLIC_KEY_LENGTH: usize = 32;
 /// NIST P256 public key length.
 pub const ECDSA_SHA256_CURVEP256_PUBLIC_KEY_LENGTH: usize = 65;
 
+/// ML-KEM-768 encapsulation key length.
+pub const ML_KEM_768_ENCAPSULATION_KEY_LENGTH: usize = 1184;
+
+/// ML-KEM-768 ciphertext length.
+pub const ML_KEM_768_CIPHERTEXT_LENGTH: usize = 1088;
+
 /// A public key for verifying signatures.
 #[derive(Encode, Decode, CborLen, Debug, Clone, PartialEq, Eq)]
 #[rustfmt::skip]
@@ -65,3 +71,21 @@ pub struct ECDSASHA256CurveP256PublicKey(
 pub struct X25519PublicKey(
     #[cbor(n(0), with = "minicbor::bytes")] pub [u8; X25519_PUBLIC_KEY_LENGTH],
 );
+
+/// ML-KEM-768 Encapsulation (Public) Key is used to encapsulate a shared secret.
+///
+/// - ML-KEM as defined [here][1].
+///
+/// [1]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.203.pdf
+#[derive(Encode, Decode, CborLen, Clone, Debug, PartialEq, Eq)]
+#[cbor(transparent)]
+pub struct MlKem768EncapsulationKey(
+    #[cbor(n(0), with = "minicbor::bytes")] pub [u8; ML_KEM_768_ENCAPSULATION_KEY_LENGTH],
+);
+
+/// ML-KEM-768 Ciphertext, containing a shared secret encapsulated for an [`MlKem768EncapsulationKey`].
+#[derive(Encode, Decode, CborLen, Clone, Debug, PartialEq, Eq)]
+#[cbor(transparent)]
+pub struct MlKem768Ciphertext(
+    #[cbor(n(0), with = "minicbor::bytes")] pub [u8; ML_KEM_768_CIPHERTEXT_LENGTH],
+);
@@ -0,0 +1,44 @@
+use ockam_core::errcode::{Kind, Origin};
+use thiserror::Error;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("pkcs11 error loading the module {module}: {error}")]
+    LoadModule { module: String, error: String },
+    #[error("pkcs11 slot {0} was not found or has no token")]
+    SlotNotFound(u64),
+    #[error("pkcs11 error opening a session on slot {slot}: {error}")]
+    OpenSession { slot: u64, error: String },
+    #[error("pkcs11 error logging into slot {slot}: {error}")]
+    Login { slot: u64, error: String },
+    #[error("pkcs11 error creating new key: {0}")]
+    Create(String),
+    #[error("pkcs11 error signing message with key {keyid}: {error}")]
+    Sign { keyid: String, error: String },
+    #[error("pkcs11 error exporting public key {keyid}: {error}")]
+    Export { keyid: String, error: String },
+    #[error("pkcs11 error deleting key {keyid}: {error}")]
+    Delete { keyid: String, error: String },
+    #[error("pkcs11 error listing the existing keys: {0}")]
+    MissingKeys(String),
+    #[error("key type is not supported")]
+    UnsupportedKeyType,
+    #[error("public key ec point is incorrect")]
+    InvalidPublicKey,
+    #[error("signature is incorrect")]
+    InvalidSignature,
+    #[error("key was not found")]
+    KeyNotFound,
+    #[error("invalid handle")]
+    InvalidHandle,
+    #[error("pkcs11 operation was interrupted: {0}")]
+    Interrupted(String),
+}
+
+impl From<Error> for ockam_core::Error {
+    #[track_caller]
+    fn from(e: Error) -> Self {
+        ockam_core::Error::new(Origin::Vault, Kind::Io, e)
+    }
+}
@@ -0,0 +1,372 @@
+use crate::error::Error;
+use cryptoki::context::{CInitializeArgs, Pkcs11};
+use cryptoki::error::RvError;
+use cryptoki::mechanism::Mechanism;
+use cryptoki::object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::types::AuthPin;
+use ockam_core::compat::sync::Mutex;
+use ockam_core::Result;
+use ockam_vault::{
+    ECDSASHA256CurveP256PublicKey, ECDSASHA256CurveP256Signature, HandleToSecret, Signature,
+    SigningSecretKeyHandle, SoftwareVaultForVerifyingSignatures, VerifyingPublicKey,
+};
+use std::fmt::{Debug, Formatter, Write};
+use std::path::PathBuf;
+use tracing as log;
+
+/// DER encoding of the OID of the NIST P-256 curve (prime256v1)
+const P256_EC_PARAMS: [u8; 10] = [0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+
+/// Length of an uncompressed SEC1 P-256 point
+const P256_POINT_LENGTH: usize = 65;
+
+/// Length of the CKA_ID generated for new keys
+const KEY_ID_LENGTH: u32 = 16;
+
+/// Label set on all the keys created by Ockam in the token
+const DEFAULT_KEY_LABEL: &str = "ockam";
+
+/// PKCS#11 configuration.
+#[derive(Clone)]
+pub struct Pkcs11Config {
+    module: PathBuf,
+    slot: u64,
+    pin: Option<String>,
+    key_label: String,
+}
+
+impl Debug for Pkcs11Config {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pkcs11Config")
+            .field("module", &self.module)
+            .field("slot", &self.slot)
+            .field("key_label", &self.key_label)
+            .finish()
+    }
+}
+
+impl Pkcs11Config {
+    /// Create a new configuration for a given PKCS#11 module (shared library) and slot
+    pub fn new(module: impl Into<PathBuf>, slot: u64) -> Pkcs11Config {
+        Pkcs11Config {
+            module: module.into(),
+            slot,
+            pin: None,
+            key_label: DEFAULT_KEY_LABEL.to_string(),
+        }
+    }
+
+    /// Set the user PIN used to log into the token
+    pub fn with_pin(self, pin: impl Into<String>) -> Self {
+        Self {
+            pin: Some(pin.into()),
+            ..self
+        }
+    }
+
+    /// Set the label used to create and discover keys in the token
+    pub fn with_key_label(self, key_label: impl Into<String>) -> Self {
+        Self {
+            key_label: key_label.into(),
+            ..self
+        }
+    }
+
+    /// Path to the PKCS#11 module
+    pub fn module(&self) -> PathBuf {
+        self.module.clone()
+    }
+
+    /// Slot id
+    pub fn slot(&self) -> u64 {
+        self.slot
+    }
+}
+
+/// PKCS#11 client.
+///
+/// All the operations are executed on a single authenticated read-write session.
+pub struct Pkcs11Client {
+    session: Mutex<Session>,
+    config: Pkcs11Config,
+}
+
+impl Pkcs11Client {
+    /// Load the PKCS#11 module, open a session on the configured slot and log in
+    pub fn new(config: Pkcs11Config) -> Result<Pkcs11Client> {
+        let module = config.module.to_string_lossy().to_string();
+        log::trace!(%module, slot = config.slot, "load pkcs11 module");
+        let pkcs11 = Pkcs11::new(&config.module).map_err(|err| Error::LoadModule {
+            module: module.clone(),
+            error: err.to_string(),
+        })?;
+        match pkcs11.initialize(CInitializeArgs::OsThreads) {
+            // the module might have already been initialized by another client in this process
+            Ok(()) | Err(cryptoki::error::Error::AlreadyInitialized) => (),
+            Err(cryptoki::error::Error::Pkcs11(RvError::CryptokiAlreadyInitialized, ..)) => (),
+            Err(err) => {
+                return Err(Error::LoadModule {
+                    module,
+                    error: err.to_string(),
+                })?
+            }
+        }
+
+        let slot = pkcs11
+            .get_slots_with_token()
+            .map_err(|_| Error::SlotNotFound(config.slot))?
+            .into_iter()
+            .find(|s| s.id() == config.slot)
+            .ok_or(Error::SlotNotFound(config.slot))?;
+
+        let session = pkcs11
+            .open_rw_session(slot)
+            .map_err(|err| Error::OpenSession {
+                slot: config.slot,
+                error: err.to_string(),
+            })?;
+        if let Some(pin) = &config.pin {
+            session
+                .login(UserType::User, Some(&AuthPin::new(pin.clone())))
+                .map_err(|err| Error::Login {
+                    slot: config.slot,
+                    error: err.to_string(),
+                })?;
+        }
+        log::debug!(%module, slot = config.slot, "opened pkcs11 session");
+
+        Ok(Self {
+            session: Mutex::new(session),
+            config,
+        })
+    }
+
+    fn cast_handle_to_key_id(handle: &SigningSecretKeyHandle) -> Result<Vec<u8>> {
+        match handle {
+            SigningSecretKeyHandle::EdDSACurve25519(_) => Err(Error::InvalidHandle)?,
+            SigningSecretKeyHandle::ECDSASHA256CurveP256(handle) => Ok(handle.value().clone()),
+        }
+    }
+
+    fn key_id_to_handle(key_id: Vec<u8>) -> SigningSecretKeyHandle {
+        SigningSecretKeyHandle::ECDSASHA256CurveP256(HandleToSecret::new(key_id))
+    }
+
+    /// Find an object of a given class with the given CKA_ID
+    fn find_key(session: &Session, class: ObjectClass, key_id: &[u8]) -> Option<ObjectHandle> {
+        session
+            .find_objects(&[
+                Attribute::Class(class),
+                Attribute::KeyType(KeyType::EC),
+                Attribute::Id(key_id.to_vec()),
+            ])
+            .ok()
+            .and_then(|objects| objects.into_iter().next())
+    }
+
+    /// Create a new NIST P-256 key-pair in the token and return its handle.
+    /// The private key is marked as sensitive and non-extractable.
+    pub fn create_key(&self) -> Result<SigningSecretKeyHandle> {
+        log::trace!("create new key");
+        let session = self.session.lock().unwrap();
+        let key_id = session
+            .generate_random_vec(KEY_ID_LENGTH)
+            .map_err(|err| Error::Create(err.to_string()))?;
+        let label = self.config.key_label.as_bytes().to_vec();
+
+        let public_key_template = [
+            Attribute::Token(true),
+            Attribute::Private(false),
+            Attribute::Verify(true),
+            Attribute::EcParams(P256_EC_PARAMS.to_vec()),
+            Attribute::Id(key_id.clone()),
+            Attribute::Label(label.clone()),
+        ];
+        let private_key_template = [
+            Attribute::Token(true),
+            Attribute::Private(true),
+            Attribute::Sensitive(true),
+            Attribute::Extractable(false),
+            Attribute::Sign(true),
+            Attribute::Id(key_id.clone()),
+            Attribute::Label(label),
+        ];
+
+        session
+            .generate_key_pair(
+                &Mechanism::EccKeyPairGen,
+                &public_key_template,
+                &private_key_template,
+            )
+            .map_err(|err| {
+                log::error!(%err, "failed to create new key");
+                Error::Create(err.to_string())
+            })?;
+
+        log::debug!(keyid = hex(&key_id), "created new key");
+        Ok(Self::key_id_to_handle(key_id))
+    }
+
+    /// Destroy both the private and the public key of a key-pair
+    pub fn delete_key(&self, key: &SigningSecretKeyHandle) -> Result<bool> {
+        let key_id = Self::cast_handle_to_key_id(key)?;
+        log::trace!(keyid = hex(&key_id), "delete key");
+        let session = self.session.lock().unwrap();
+
+        let Some(private_key) = Self::find_key(&session, ObjectClass::PRIVATE_KEY, &key_id) else {
+            log::debug!(keyid = hex(&key_id), "key does not exist");
+            return Ok(false);
+        };
+        let public_key = Self::find_key(&session, ObjectClass::PUBLIC_KEY, &key_id);
+
+        for object in [Some(private_key), public_key].into_iter().flatten() {
+            session.destroy_object(object).map_err(|err| {
+                log::error!(keyid = hex(&key_id), %err, "failed to delete key");
+                Error::Delete {
+                    keyid: hex(&key_id),
+                    error: err.to_string(),
+                }
+            })?;
+        }
+        log::debug!(keyid = hex(&key_id), "key deleted");
+        Ok(true)
+    }
+
+    /// Return the handles of all the private keys created with the configured label
+    pub fn list_keys(&self) -> Result<Vec<SigningSecretKeyHandle>> {
+        let session = self.session.lock().unwrap();
+        let objects = session
+            .find_objects(&[
+                Attribute::Class(ObjectClass::PRIVATE_KEY),
+                Attribute::KeyType(KeyType::EC),
+                Attribute::Label(self.config.key_label.as_bytes().to_vec()),
+            ])
+            .map_err(|err| {
+                log::error!(%err, "failed to list all keys");
+                Error::MissingKeys(err.to_string())
+            })?;
+
+        let mut result = vec![];
+        for object in objects {
+            let attributes = session
+                .get_attributes(object, &[AttributeType::Id])
+                .map_err(|err| Error::MissingKeys(err.to_string()))?;
+            for attribute in attributes {
+                if let Attribute::Id(key_id) = attribute {
+                    result.push(Self::key_id_to_handle(key_id))
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Return the public key of a key-pair, read from its public key object
+    pub fn public_key(&self, key: &SigningSecretKeyHandle) -> Result<VerifyingPublicKey> {
+        let key_id = Self::cast_handle_to_key_id(key)?;
+        log::trace!(keyid = hex(&key_id), "get public key");
+        let session = self.session.lock().unwrap();
+
+        let public_key =
+            Self::find_key(&session, ObjectClass::PUBLIC_KEY, &key_id).ok_or(Error::KeyNotFound)?;
+        let attributes = session
+            .get_attributes(
+                public_key,
+                &[AttributeType::EcParams, AttributeType::EcPoint],
+            )
+            .map_err(|err| {
+                log::error!(keyid = hex(&key_id), %err, "failed to get public key");
+                Error::Export {
+                    keyid: hex(&key_id),
+                    error: err.to_string(),
+                }
+            })?;
+
+        let mut point = None;
+        for attribute in attributes {
+            match attribute {
+                Attribute::EcParams(params) if params != P256_EC_PARAMS => {
+                    log::error!(
+                        keyid = hex(&key_id),
+                        "curve not supported to get a public key"
+                    );
+                    return Err(Error::UnsupportedKeyType)?;
+                }
+                Attribute::EcPoint(p) => point = Some(p),
+                _ => (),
+            }
+        }
+        let point = point.ok_or(Error::InvalidPublicKey)?;
+        let public_key = ECDSASHA256CurveP256PublicKey(
+            decode_ec_point(&point)?
+                .try_into()
+                .map_err(|_| Error::InvalidPublicKey)?,
+        );
+        log::debug!(keyid = hex(&key_id), "received public key");
+        Ok(VerifyingPublicKey::ECDSASHA256CurveP256(public_key))
+    }
+
+    /// Sign a message with the private key of a key-pair.
+    /// The message is hashed locally and only its digest is sent to the token.
+    pub fn sign(&self, key: &SigningSecretKeyHandle, message: &[u8]) -> Result<Signature> {
+        let key_id = Self::cast_handle_to_key_id(key)?;
+        log::trace!(keyid = hex(&key_id), "sign message");
+        let digest = SoftwareVaultForVerifyingSignatures::compute_sha256(message)?;
+        let session = self.session.lock().unwrap();
+
+        let private_key = Self::find_key(&session, ObjectClass::PRIVATE_KEY, &key_id)
+            .ok_or(Error::KeyNotFound)?;
+        let signature = session
+            .sign(&Mechanism::Ecdsa, private_key, &digest.0)
+            .map_err(|err| {
+                log::error!(keyid = hex(&key_id), %err, "failed to sign message");
+                Error::Sign {
+                    keyid: hex(&key_id),
+                    error: err.to_string(),
+                }
+            })?;
+
+        // CKM_ECDSA returns the raw r || s concatenation
+        let signature = ECDSASHA256CurveP256Signature(
+            signature.try_into().map_err(|_| Error::InvalidSignature)?,
+        );
+        log::debug!(keyid = hex(&key_id), "signed message");
+        Ok(Signature::ECDSASHA256CurveP256(signature))
+    }
+}
+
+/// CKA_EC_POINT is specified as a DER-encoded OCTET STRING but some modules
+/// return the raw SEC1 point instead
+fn decode_ec_point(point: &[u8]) -> Result<Vec<u8>> {
+    match point {
+        [0x04, 0x41, rest @ ..] if rest.len() == P256_POINT_LENGTH => Ok(rest.to_vec()),
+        [0x04, ..] if point.len() == P256_POINT_LENGTH => Ok(point.to_vec()),
+        _ => Err(Error::InvalidPublicKey)?,
+    }
+}
+
+fn hex(key_id: &[u8]) -> String {
+    key_id.iter().fold(String::new(), |mut result, b| {
+        let _ = write!(result, "{b:02x}");
+        result
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ec_point_encoding() {
+        let mut point = [7u8; P256_POINT_LENGTH];
+        point[0] = 0x04;
+
+        let mut encoded = vec![0x04, P256_POINT_LENGTH as u8];
+        encoded.extend_from_slice(&point);
+        assert_eq!(decode_ec_point(&encoded).unwrap(), point.to_vec());
+
+        // raw points are accepted as well
+        assert_eq!(decode_ec_point(&point).unwrap(), point.to_vec());
+        assert!(decode_ec_point(&point[1..]).is_err());
+    }
+}
@@ -0,0 +1,94 @@
+use ockam_core::Result;
+use ockam_vault::{
+    SigningKeyType, SoftwareVaultForVerifyingSignatures, VaultForSigning,
+    VaultForVerifyingSignatures,
+};
+use ockam_vault_pkcs11::{Pkcs11Config, Pkcs11SigningVault};
+
+/// These tests need to be executed with the following environment variables
+/// OCKAM_PKCS11_MODULE: path to the PKCS#11 module, for example /usr/lib/softhsm/libsofthsm2.so
+/// OCKAM_PKCS11_SLOT: id of a slot with an initialized token
+/// OCKAM_PKCS11_PIN: user PIN of that token
+async fn create_vault() -> Result<Pkcs11SigningVault> {
+    let module = std::env::var("OCKAM_PKCS11_MODULE").expect("OCKAM_PKCS11_MODULE is not set");
+    let slot = std::env::var("OCKAM_PKCS11_SLOT")
+        .expect("OCKAM_PKCS11_SLOT is not set")
+        .parse()
+        .expect("OCKAM_PKCS11_SLOT must be a number");
+    let pin = std::env::var("OCKAM_PKCS11_PIN").expect("OCKAM_PKCS11_PIN is not set");
+
+    Pkcs11SigningVault::create_with_config(Pkcs11Config::new(module, slot).with_pin(pin)).await
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_sign_verify() -> Result<()> {
+    let signing_vault = create_vault().await?;
+    let handle = signing_vault
+        .generate_signing_secret_key(SigningKeyType::ECDSASHA256CurveP256)
+        .await?;
+    let message = b"hello world";
+    let signature = signing_vault.sign(&handle, message.as_slice()).await?;
+    let public_key = signing_vault.get_verifying_public_key(&handle).await?;
+
+    // the signature is verified in software
+    let verifier = SoftwareVaultForVerifyingSignatures::new();
+    assert!(
+        verifier
+            .verify_signature(&public_key, message, &signature)
+            .await?
+    );
+    assert!(
+        !verifier
+            .verify_signature(&public_key, b"another message", &signature)
+            .await?
+    );
+
+    signing_vault.delete_signing_secret_key(handle).await?;
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_keys_management() -> Result<()> {
+    let signing_vault = create_vault().await?;
+
+    let number_of_keys1 = signing_vault.number_of_keys().await?;
+
+    let handle = signing_vault
+        .generate_signing_secret_key(SigningKeyType::ECDSASHA256CurveP256)
+        .await?;
+
+    let number_of_keys2 = signing_vault.number_of_keys().await?;
+    assert_eq!(number_of_keys1 + 1, number_of_keys2);
+
+    let public_key = signing_vault.get_verifying_public_key(&handle).await?;
+
+    let handle2 = signing_vault.get_secret_key_handle(&public_key).await?;
+    assert_eq!(handle, handle2);
+
+    // keys are persisted in the token and discovered by a new vault
+    let signing_vault2 = create_vault().await?;
+    assert_eq!(
+        signing_vault2.get_secret_key_handle(&public_key).await?,
+        handle
+    );
+
+    assert!(signing_vault.delete_signing_secret_key(handle).await?);
+    let number_of_keys3 = signing_vault.number_of_keys().await?;
+    assert_eq!(number_of_keys2, number_of_keys3 + 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_only_p256_keys_are_supported() -> Result<()> {
+    let signing_vault = create_vault().await?;
+    assert!(signing_vault
+        .generate_signing_secret_key(SigningKeyType::EdDSACurve25519)
+        .await
+        .is_err());
+    Ok(())
+}